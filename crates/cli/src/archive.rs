@@ -0,0 +1,123 @@
+use crate::{parse_download, ParseOptions};
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use zip::ZipArchive;
+
+/// Largest entry that is decompressed, far above the size of any tachograph download. The size an
+/// entry declares is not trusted, so that a crafted archive cannot exhaust memory.
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Entries of an archive processed by [`run`].
+pub struct ArchiveSummary {
+    pub parsed: usize,
+    pub failed: usize,
+}
+
+/// Parses every file of the `.zip` archive `input` without extracting it to disk, and writes the
+/// output of each to `out_dir` under the path of its entry with the extension of the output
+/// format appended, e.g. `exports/driver.ddd.json`. The next entry is decompressed on another
+/// thread while the current one is parsed. Entries that fail to decompress or to parse are
+/// reported on stderr and do not stop the others.
+pub fn run(input: &Path, out_dir: &Path, options: &ParseOptions) -> Result<ArchiveSummary> {
+    let file = File::open(input).context("Failed to open archive")?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).context("Failed to read archive")?;
+
+    thread::scope(|scope| {
+        // Holds one decompressed entry ahead of the one being parsed
+        let (sender, receiver) = mpsc::sync_channel::<Result<(PathBuf, Vec<u8>)>>(1);
+        scope.spawn(move || {
+            for index in 0..archive.len() {
+                let Some(entry) = read_entry(&mut archive, index).transpose() else {
+                    continue;
+                };
+                if sender.send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut summary = ArchiveSummary {
+            parsed: 0,
+            failed: 0,
+        };
+        for entry in receiver {
+            let (name, bytes) = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    summary.failed += 1;
+                    eprintln!("{:#}", err);
+                    continue;
+                }
+            };
+            match process_entry(&name, &bytes, out_dir, options) {
+                Ok(output) => {
+                    summary.parsed += 1;
+                    println!("{} -> {}", name.display(), output.display());
+                }
+                Err(err) => {
+                    summary.failed += 1;
+                    eprintln!("Failed to process {}: {:#}", name.display(), err);
+                }
+            }
+        }
+        Ok(summary)
+    })
+}
+
+/// The path and the decompressed bytes of a file entry, `None` for directories and for entries
+/// whose path would leave the output directory.
+fn read_entry(
+    archive: &mut ZipArchive<BufReader<File>>,
+    index: usize,
+) -> Result<Option<(PathBuf, Vec<u8>)>> {
+    let mut entry = archive
+        .by_index(index)
+        .with_context(|| format!("Failed to read archive entry {}", index))?;
+    if entry.is_dir() {
+        return Ok(None);
+    }
+    let Some(name) = entry.enclosed_name() else {
+        eprintln!("Skipping archive entry with unsafe path: {}", entry.name());
+        return Ok(None);
+    };
+    let mut bytes = Vec::with_capacity(entry.size().min(MAX_ENTRY_BYTES) as usize);
+    entry
+        .by_ref()
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to decompress {}", name.display()))?;
+    if bytes.len() as u64 > MAX_ENTRY_BYTES {
+        anyhow::bail!(
+            "Failed to decompress {}: larger than {} bytes",
+            name.display(),
+            MAX_ENTRY_BYTES
+        );
+    }
+    Ok(Some((name, bytes)))
+}
+
+fn process_entry(
+    name: &Path,
+    bytes: &[u8],
+    out_dir: &Path,
+    options: &ParseOptions,
+) -> Result<PathBuf> {
+    let is_sensor_dump = name
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("esm"));
+    let (output, _) = parse_download(bytes, is_sensor_dump, options)?;
+
+    let mut file_name = name.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(options.extension());
+    let output_path = out_dir.join(name).with_file_name(file_name);
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).context("Failed to create output directory")?;
+    }
+    fs::write(&output_path, output).context("Failed to write output file")?;
+    Ok(output_path)
+}
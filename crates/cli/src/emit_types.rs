@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tachograph_parser::{
+    analysis::{ActivityDay, DriverEvent},
+    capabilities::Capabilities,
+    card_parser::CardData,
+    detector::TachoFileDetection,
+    sensor_parser::SensorData,
+    view::VuEventView,
+    vu_parser::VuData,
+};
+use ts_rs::TS;
+
+/// Writes the TypeScript declarations of the JSON output of this build to `out_dir`, one file
+/// per type as in the node bindings, plus a `version.ts` with the version of the parser they
+/// were generated from. No JSON Schema is written, the types are only described by ts-rs.
+/// Returns the version of the parser.
+pub fn run(out_dir: &Path) -> Result<String> {
+    fs::create_dir_all(out_dir).context("Failed to create output directory")?;
+    CardData::export_all_to(out_dir).context("Failed to export CardData")?;
+    VuData::export_all_to(out_dir).context("Failed to export VuData")?;
+    SensorData::export_all_to(out_dir).context("Failed to export SensorData")?;
+    TachoFileDetection::export_all_to(out_dir).context("Failed to export TachoFileDetection")?;
+    Capabilities::export_all_to(out_dir).context("Failed to export Capabilities")?;
+    ActivityDay::export_all_to(out_dir).context("Failed to export ActivityDay")?;
+    DriverEvent::export_all_to(out_dir).context("Failed to export DriverEvent")?;
+    VuEventView::export_all_to(out_dir).context("Failed to export VuEventView")?;
+
+    let version = tachograph_parser::capabilities().version;
+    fs::write(
+        out_dir.join("version.ts"),
+        format!("export const PARSER_VERSION = \"{}\";\n", version),
+    )
+    .context("Failed to write version.ts")?;
+    Ok(version)
+}
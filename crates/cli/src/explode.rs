@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tachograph_parser::{
+    card_parser::CardParser,
+    detector::{self, TachoFileType},
+    vu_parser::VuParser,
+};
+
+/// Writes every EF of a card file, or every block of a VU file, to its own file in `out_dir`.
+/// Each file keeps the EF header or the SID and TREP, so it can be read on its own by other
+/// parsers and vendor tools. Files are named by their position in the download and their tag,
+/// e.g. `003_0520_00.bin` for a card Identification EF or `001_7602.bin` for a VU activities block.
+/// Returns the number of files written.
+pub fn run(input: &Path, out_dir: &Path) -> Result<usize> {
+    let bytes = fs::read(input).context("Failed to read input file")?;
+    let file_type = detector::detect_from_bytes(&bytes).context("Failed to detect file type")?;
+    let blocks: Vec<(String, &[u8])> = match file_type {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => VuParser::new_from_bytes(&bytes)?
+            .block_coverage()
+            .context("Failed to split VU file into blocks")?
+            .into_iter()
+            .map(|block| {
+                let tag = match block.trep {
+                    Some(trep) => format!("{:02X}{:02X}", block.sid, trep),
+                    None => format!("{:02X}", block.sid),
+                };
+                (tag, &bytes[block.offset..block.offset + block.length])
+            })
+            .collect(),
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => CardParser::new_from_bytes(&bytes)?
+            .ef_coverage()
+            .context("Failed to split card file into EFs")?
+            .into_iter()
+            .map(|ef| {
+                let tag = format!("{:04X}_{:02X}", ef.sfid, ef.file_id);
+                // the last EF of a cut download is written with the bytes that are there
+                let end = (ef.offset + 5 + ef.size as usize).min(bytes.len());
+                (tag, &bytes[ef.offset..end])
+            })
+            .collect(),
+    };
+
+    fs::create_dir_all(out_dir).context("Failed to create output directory")?;
+    for (index, (tag, block)) in blocks.iter().enumerate() {
+        let path = out_dir.join(format!("{:03}_{}.bin", index, tag));
+        fs::write(&path, block).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(blocks.len())
+}
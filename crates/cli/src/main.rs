@@ -2,11 +2,23 @@ use anyhow::{Context, Result};
 use clap::{value_parser, Arg, Command};
 use flexi_logger::Logger;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tachograph_parser::{
+    binary::BinaryFormat,
+    card_parser::CardParser,
     detector::{self, TachoFileType},
-    parse_card_from_file_to_json_pretty, parse_vu_from_file_to_json_pretty,
+    json::{JsonOptions, TimestampFormat},
+    locale::Locale,
+    sensor_parser::SensorParser,
+    trace,
+    vu_parser::VuParser,
 };
+use watch::{OutputFormat, WatchOptions};
+
+mod archive;
+mod emit_types;
+mod explode;
+mod watch;
 
 fn main() -> Result<()> {
     let matches = Command::new("Tachograph Parser")
@@ -20,7 +32,7 @@ fn main() -> Result<()> {
                 .long("input")
                 .value_parser(value_parser!(PathBuf))
                 .required(true)
-                .help("Input file path"),
+                .help("Input file path, .esm files are read as motion sensor dumps. A .zip archive is parsed entry by entry, --output is then a directory"),
         )
         .arg(
             Arg::new("output")
@@ -35,10 +47,181 @@ fn main() -> Result<()> {
                 .short('v')
                 .long("verbose")
                 .action(clap::ArgAction::Count)
+                .global(true)
                 .help("Enable verbose logging"),
         )
+        .arg(
+            Arg::new("locale")
+                .short('l')
+                .long("locale")
+                .value_parser(value_parser!(Locale))
+                .global(true)
+                .help(
+                    "Add country names in this locale (en, de, fr, es, ro) next to nation fields",
+                ),
+        )
+        .arg(
+            Arg::new("timestamps")
+                .long("timestamps")
+                .value_parser(value_parser!(TimestampFormat))
+                .global(true)
+                .help("Timestamp format: rfc3339 (default), unix-seconds or unix-millis"),
+        )
+        .arg(
+            Arg::new("provenance")
+                .long("provenance")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Wrap the output with the SHA-256 of the input file, the parser version and the time of parsing"),
+        )
+        .arg(
+            Arg::new("canonical-order")
+                .long("canonical-order")
+                .action(clap::ArgAction::SetTrue)
+                .global(true)
+                .help("Order the records of every array by their timestamp, so that the output of two downloads can be diffed"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["json", "cbor", "msgpack"])
+                .default_value("json")
+                .help("Output format: json, or cbor and msgpack for smaller self-describing binary output"),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_parser(value_parser!(PathBuf))
+                .help("Write an annotated hex dump of every value read by the parser to this path"),
+        )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .action(clap::ArgAction::SetTrue)
+                .help("Keep the data of files whose last block is cut short, e.g. by an aborted download"),
+        )
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            Command::new("watch")
+                .about("Parse files as they are dropped into a directory")
+                .arg(
+                    Arg::new("dir")
+                        .long("dir")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("Directory to watch for new files"),
+                )
+                .arg(
+                    Arg::new("out-dir")
+                        .long("out-dir")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("Directory the parsed output is written to"),
+                )
+                .arg(
+                    Arg::new("quarantine")
+                        .long("quarantine")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Directory files that fail to parse are moved to, with an error report (default: <out-dir>/quarantine)"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(value_parser!(OutputFormat))
+                        .default_value("json")
+                        .help("Output format: json or ndjson"),
+                ),
+        )
+        .subcommand(
+            Command::new("explode")
+                .about("Write every EF or VU block of a file to its own binary file")
+                .arg(
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("Input file path"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("Directory the blocks are written to"),
+                ),
+        )
+        .subcommand(
+            Command::new("emit-types")
+                .about("Write the TypeScript declarations of the JSON output of this build")
+                .arg(
+                    Arg::new("out")
+                        .long("out")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("Directory the declarations are written to"),
+                ),
+        )
         .get_matches();
 
+    // Set up logging if verbose flag is used
+    if matches.get_count("verbose") > 0 {
+        std::env::set_var("RUST_LOG", "trace");
+        Logger::try_with_env()?
+            .start()
+            .context("Failed to start logger")?;
+    }
+
+    let json_options = JsonOptions {
+        pretty: true,
+        nation_locale: matches.get_one::<Locale>("locale").copied(),
+        timestamp_format: matches
+            .get_one::<TimestampFormat>("timestamps")
+            .copied()
+            .unwrap_or_default(),
+        provenance: matches.get_flag("provenance"),
+        canonical_order: matches.get_flag("canonical-order"),
+    };
+
+    if let Some(("watch", watch_matches)) = matches.subcommand() {
+        let out_dir = watch_matches.get_one::<PathBuf>("out-dir").unwrap().clone();
+        return watch::run(&WatchOptions {
+            dir: watch_matches.get_one::<PathBuf>("dir").unwrap().clone(),
+            quarantine_dir: watch_matches
+                .get_one::<PathBuf>("quarantine")
+                .cloned()
+                .unwrap_or_else(|| out_dir.join("quarantine")),
+            out_dir,
+            format: *watch_matches.get_one::<OutputFormat>("format").unwrap(),
+            json_options,
+        });
+    }
+
+    if let Some(("explode", explode_matches)) = matches.subcommand() {
+        let input = explode_matches.get_one::<PathBuf>("input").unwrap();
+        let out_dir = explode_matches.get_one::<PathBuf>("output").unwrap();
+        let count = explode::run(input, out_dir)?;
+        println!(
+            "Wrote {} blocks of {} to {}",
+            count,
+            input.display(),
+            out_dir.display()
+        );
+        return Ok(());
+    }
+
+    if let Some(("emit-types", emit_types_matches)) = matches.subcommand() {
+        let out_dir = emit_types_matches.get_one::<PathBuf>("out").unwrap();
+        let version = emit_types::run(out_dir)?;
+        println!(
+            "Wrote the types of tachograph_parser {} to {}",
+            version,
+            out_dir.display()
+        );
+        return Ok(());
+    }
+
     let input = matches
         .get_one::<PathBuf>("input")
         .unwrap()
@@ -46,37 +229,151 @@ fn main() -> Result<()> {
         .unwrap();
     let output = matches.get_one::<PathBuf>("output").unwrap();
 
-    let detected_file_type = detector::detect_from_file(input)?;
+    let has_extension = |extension: &str| {
+        PathBuf::from(input)
+            .extension()
+            .is_some_and(|value| value.eq_ignore_ascii_case(extension))
+    };
+    let options = ParseOptions {
+        // json is written by the JSON serializers, the binary formats share their options
+        binary_format: match matches.get_one::<String>("format").unwrap().as_str() {
+            "json" => None,
+            format => Some(format.parse::<BinaryFormat>()?),
+        },
+        json_options,
+        lenient: matches.get_flag("lenient"),
+    };
 
-    // Set up logging if verbose flag is used
-    if matches.get_count("verbose") > 0 {
-        std::env::set_var("RUST_LOG", "trace");
-        Logger::try_with_env()?
-            .start()
-            .context("Failed to start logger")?;
+    if has_extension("zip") {
+        let summary = archive::run(Path::new(input), output, &options)?;
+        println!(
+            "Processing of {} complete: {} entries parsed, {} failed. Output written to: {}",
+            input,
+            summary.parsed,
+            summary.failed,
+            output.to_str().unwrap()
+        );
+        if summary.failed > 0 {
+            anyhow::bail!(
+                "{} of {} entries could not be parsed",
+                summary.failed,
+                summary.parsed + summary.failed
+            );
+        }
+        return Ok(());
     }
 
-    let json_output = match detected_file_type {
-        TachoFileType::VehicleUnitGen1
-        | TachoFileType::VehicleUnitGen2
-        | TachoFileType::VehicleUnitGen2V2 => {
-            parse_vu_from_file_to_json_pretty(input).context("Failed to process input file")?
-        }
-        TachoFileType::DriverCardGen1
-        | TachoFileType::DriverCardGen2
-        | TachoFileType::DriverCardGen2V2 => {
-            parse_card_from_file_to_json_pretty(input).context("Failed to process input file")?
+    // Motion sensor dumps have no header to detect them by, only their extension
+    let is_sensor_dump = has_extension("esm");
+    let bytes = fs::read(input).context("Failed to read input file")?;
+
+    // Written before the regular parse so that it is available even when parsing fails
+    if let Some(trace_path) = matches.get_one::<PathBuf>("trace") {
+        if !is_sensor_dump {
+            write_trace(&bytes, trace_path)?;
         }
-    };
+    }
+
+    let (parsed_output, file_type) =
+        parse_download(&bytes, is_sensor_dump, &options).context("Failed to process input file")?;
 
-    fs::write(output, json_output).context("Failed to write output file")?;
+    fs::write(output, parsed_output).context("Failed to write output file")?;
 
     println!(
-        "Processing of {} complete with file type: {:?}. Output written to: {}",
+        "Processing of {} complete with file type: {}. Output written to: {}",
         input,
-        detected_file_type,
+        file_type,
         output.to_str().unwrap()
     );
 
     Ok(())
 }
+
+/// Output settings shared by single files and the entries of an archive.
+pub struct ParseOptions {
+    /// `None` for JSON output.
+    pub binary_format: Option<BinaryFormat>,
+    pub json_options: JsonOptions,
+    pub lenient: bool,
+}
+impl ParseOptions {
+    /// Extension of the output files, as given to `--format`.
+    pub fn extension(&self) -> &'static str {
+        match self.binary_format {
+            None => "json",
+            Some(BinaryFormat::Cbor) => "cbor",
+            Some(BinaryFormat::MessagePack) => "msgpack",
+        }
+    }
+}
+
+/// Parses a download into the output format, returning the output and the detected file type.
+pub fn parse_download(
+    bytes: &[u8],
+    is_sensor_dump: bool,
+    options: &ParseOptions,
+) -> Result<(Vec<u8>, String)> {
+    let json_options = &options.json_options;
+    if is_sensor_dump {
+        let parser =
+            SensorParser::new_from_bytes(bytes).context("Failed to create SensorParser")?;
+        let output = match options.binary_format {
+            Some(format) => parser.parse_to_binary_with_options(format, json_options),
+            None => parser
+                .parse_to_json_with_options(json_options)
+                .map(String::into_bytes),
+        }?;
+        return Ok((output, "motion sensor".to_string()));
+    }
+
+    let detected_file_type = detector::detect_from_bytes(bytes)?;
+    let output = match detected_file_type {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => {
+            let parser = VuParser::new_from_bytes(bytes)
+                .context("Failed to create VuParser")?
+                .lenient(options.lenient);
+            match options.binary_format {
+                Some(format) => parser.parse_to_binary_with_options(format, json_options),
+                None => parser
+                    .parse_to_json_with_options(json_options)
+                    .map(String::into_bytes),
+            }
+        }
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => {
+            let parser = CardParser::new_from_bytes(bytes)
+                .context("Failed to create CardParser")?
+                .lenient(options.lenient);
+            match options.binary_format {
+                Some(format) => parser.parse_to_binary_with_options(format, json_options),
+                None => parser
+                    .parse_to_json_with_options(json_options)
+                    .map(String::into_bytes),
+            }
+        }
+    }?;
+    Ok((output, detected_file_type.to_string()))
+}
+
+/// Writes the annotated hex dump of `--trace` for a card or VU download.
+fn write_trace(bytes: &[u8], trace_path: &Path) -> Result<()> {
+    let (_, entries) = match detector::detect_from_bytes(bytes)? {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => {
+            let (result, entries) = VuParser::new_from_bytes(bytes)?.parse_with_trace();
+            (result.map(|_| ()), entries)
+        }
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => {
+            let (result, entries) = CardParser::new_from_bytes(bytes)?.parse_with_trace();
+            (result.map(|_| ()), entries)
+        }
+    };
+    fs::write(trace_path, trace::render_annotated_hex(bytes, &entries))
+        .context("Failed to write trace file")
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, bail, Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use tachograph_parser::{
+    detector::{self, TachoFileType},
+    json::{self, JsonOptions},
+    sink::{self, NdjsonSink},
+    TachoData,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Ndjson,
+}
+
+pub struct WatchOptions {
+    pub dir: PathBuf,
+    pub out_dir: PathBuf,
+    pub quarantine_dir: PathBuf,
+    pub format: OutputFormat,
+    pub json_options: JsonOptions,
+}
+
+/// A file is only parsed once no change to it was seen for this long,
+/// so downloads still being copied into the folder are not read half-written.
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Parses every file dropped into `options.dir` until the process is stopped.
+/// Files already in the folder are parsed on start unless their output exists.
+pub fn run(options: &WatchOptions) -> Result<()> {
+    fs::create_dir_all(&options.out_dir).context("Failed to create output directory")?;
+    fs::create_dir_all(&options.quarantine_dir).context("Failed to create quarantine directory")?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("Failed to create watcher")?;
+    watcher
+        .watch(&options.dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch {}", options.dir.display()))?;
+    println!("Watching {} for new files", options.dir.display());
+
+    for entry in fs::read_dir(&options.dir).context("Failed to read watched directory")? {
+        let path = entry.context("Failed to read watched directory")?.path();
+        if is_candidate(&path) && !output_path(options, &path).exists() {
+            process(options, &path);
+        }
+    }
+
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    loop {
+        match receiver.recv_timeout(SETTLE_TIME / 4) {
+            Ok(Ok(event)) => {
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    for path in event.paths {
+                        pending.insert(path, Instant::now());
+                    }
+                }
+            }
+            Ok(Err(err)) => eprintln!("Watcher error: {}", err),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => bail!("Watcher stopped unexpectedly"),
+        }
+
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, last_change)| last_change.elapsed() >= SETTLE_TIME)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in settled {
+            pending.remove(&path);
+            // Files moved away in the meantime are reported too
+            if is_candidate(&path) {
+                process(options, &path);
+            }
+        }
+    }
+}
+
+/// Skips directories and hidden files, which upload tools commonly use while a transfer is in progress.
+fn is_candidate(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_none_or(|name| name.starts_with('.'));
+    path.is_file() && !hidden
+}
+
+fn output_path(options: &WatchOptions, path: &Path) -> PathBuf {
+    let extension = match options.format {
+        OutputFormat::Json => "json",
+        OutputFormat::Ndjson => "ndjson",
+    };
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    options.out_dir.join(file_name)
+}
+
+fn process(options: &WatchOptions, path: &Path) {
+    // A panicking parser must not take the whole daemon down
+    let result = panic::catch_unwind(AssertUnwindSafe(|| convert(options, path))).unwrap_or_else(
+        |payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(anyhow!("Parser panicked: {}", message))
+        },
+    );
+    match result {
+        Ok((file_type, output)) => {
+            println!("{} ({}) -> {}", path.display(), file_type, output.display())
+        }
+        Err(err) => {
+            eprintln!("Failed to process {}: {:#}", path.display(), err);
+            if let Err(quarantine_err) = quarantine(options, path, &err) {
+                eprintln!(
+                    "Failed to quarantine {}: {:#}",
+                    path.display(),
+                    quarantine_err
+                );
+            }
+        }
+    }
+}
+
+fn convert(options: &WatchOptions, path: &Path) -> Result<(TachoFileType, PathBuf)> {
+    let bytes = fs::read(path).context("Failed to read input file")?;
+    let file_type = detector::detect_from_bytes(&bytes).context("Failed to detect file type")?;
+    let data = tachograph_parser::parse_from_bytes(&bytes).context("Failed to parse file")?;
+
+    // Serialized in memory first so a failure does not leave a truncated output behind
+    let output = match options.format {
+        OutputFormat::Json => {
+            let mut buffer = Vec::new();
+            match &data {
+                TachoData::Card { card_data } => json::write_json_with_source(
+                    card_data,
+                    &bytes,
+                    &options.json_options,
+                    &mut buffer,
+                ),
+                TachoData::Vu { vu_data } => json::write_json_with_source(
+                    vu_data,
+                    &bytes,
+                    &options.json_options,
+                    &mut buffer,
+                ),
+            }?;
+            buffer
+        }
+        OutputFormat::Ndjson => {
+            let mut ndjson_sink = NdjsonSink::new(Vec::new());
+            match &data {
+                TachoData::Card { card_data } => sink::write_card_data(card_data, &mut ndjson_sink),
+                TachoData::Vu { vu_data } => sink::write_vu_data(vu_data, &mut ndjson_sink),
+            }?;
+            ndjson_sink.into_inner()
+        }
+    };
+
+    let output_path = output_path(options, path);
+    fs::write(&output_path, output).context("Failed to write output file")?;
+    Ok((file_type, output_path))
+}
+
+/// Moves a file that failed to parse into the quarantine folder, next to a
+/// `<file name>.error.txt` report with the full error chain.
+fn quarantine(options: &WatchOptions, path: &Path, err: &anyhow::Error) -> Result<()> {
+    let file_name = path.file_name().context("Input path has no file name")?;
+    let target = options.quarantine_dir.join(file_name);
+    // Renaming fails across file systems, fall back to copying
+    if fs::rename(path, &target).is_err() {
+        fs::copy(path, &target).context("Failed to copy file to quarantine")?;
+        fs::remove_file(path).context("Failed to remove quarantined file")?;
+    }
+    let mut report = target.into_os_string();
+    report.push(".error.txt");
+    let causes: String = err.chain().map(|cause| format!("{}\n", cause)).collect();
+    fs::write(report, causes).context("Failed to write error report")
+}
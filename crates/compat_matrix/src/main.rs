@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use clap::{value_parser, Arg, Command};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tachograph_parser::{
+    capabilities,
+    card_parser::{CardParser, EfAppendix, EfParseStatus},
+    detector,
+    vu_parser::{BlockParseStatus, VuParser},
+};
+
+/// Vendor of the files placed directly in the corpus directory.
+const UNLABELED: &str = "unlabeled";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Pass,
+    /// Parsed, but with data skipped or kept raw, in the given category.
+    Partial(&'static str),
+    Fail(&'static str),
+}
+
+/// Outcome of every block or EF of a file, by name.
+type Outcomes = Vec<(String, Outcome)>;
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Cell {
+    pass: usize,
+    partial: usize,
+    fail: usize,
+    /// Partial and failed blocks by category.
+    categories: BTreeMap<&'static str, usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Row {
+    vendor: String,
+    generation: String,
+    block: String,
+    #[serde(flatten)]
+    cell: Cell,
+}
+
+/// Groups parser errors by their likely cause, from the formatted error chain.
+fn categorize(error: &str) -> &'static str {
+    let error = error.to_lowercase();
+    if error.contains("panicked") {
+        "panic"
+    } else if error.contains("failed to fill whole buffer")
+        || error.contains("unexpected end")
+        || error.contains("remain in the file")
+        || error.contains("ends in the middle")
+    {
+        "truncated"
+    } else if error.contains("does not start with") || error.contains("unknown file") {
+        "unrecognized_file"
+    } else if error.contains("invalid") || error.contains("unknown") {
+        "invalid_value"
+    } else {
+        "other"
+    }
+}
+
+/// Generation label and the outcome of each block of a single file.
+fn file_outcomes(bytes: &[u8]) -> (String, Outcomes) {
+    let file_type = match detector::detect_from_bytes(bytes) {
+        Ok(file_type) => file_type,
+        Err(_) => {
+            let outcome = Outcome::Fail("unrecognized_file");
+            return ("Unknown".to_string(), vec![("file".to_string(), outcome)]);
+        }
+    };
+    let capabilities = capabilities();
+    let outcomes = if file_type.is_card() {
+        card_outcomes(bytes, &capabilities)
+    } else {
+        vu_outcomes(bytes, &capabilities)
+    };
+    let outcomes = outcomes.unwrap_or_else(|e| {
+        vec![(
+            "file".to_string(),
+            Outcome::Fail(categorize(&format!("{:#}", e))),
+        )]
+    });
+    (file_type.to_string(), outcomes)
+}
+
+fn card_outcomes(bytes: &[u8], capabilities: &capabilities::Capabilities) -> Result<Outcomes> {
+    let coverage = CardParser::new_from_bytes(bytes)?.ef_coverage()?;
+    Ok(coverage
+        .into_iter()
+        .map(|ef| {
+            let name = capabilities
+                .card_efs
+                .iter()
+                .find(|capability| capability.sfid == ef.sfid)
+                .map_or_else(
+                    || format!("EF {:#06x}", ef.sfid),
+                    |capability| capability.name.clone(),
+                );
+            let is_signature =
+                EfAppendix::from_byte(ef.file_id).is_some_and(EfAppendix::is_signature);
+            let block = if is_signature {
+                format!("{} signature", name)
+            } else {
+                name
+            };
+            let outcome = match ef.status {
+                EfParseStatus::Parsed => Outcome::Pass,
+                EfParseStatus::PartiallyParsed { .. } => Outcome::Partial("unused_bytes"),
+                EfParseStatus::PreservedRaw => Outcome::Partial("preserved_raw"),
+                EfParseStatus::Skipped => Outcome::Fail("skipped"),
+                EfParseStatus::Failed { error } => Outcome::Fail(categorize(&error)),
+            };
+            (block, outcome)
+        })
+        .collect())
+}
+
+fn vu_outcomes(bytes: &[u8], capabilities: &capabilities::Capabilities) -> Result<Outcomes> {
+    let coverage = VuParser::new_from_bytes(bytes)?.block_coverage()?;
+    Ok(coverage
+        .into_iter()
+        .map(|block| {
+            let name = match (block.trep, &block.status) {
+                (_, BlockParseStatus::Unknown) => "unknown block".to_string(),
+                (None, _) => "block header".to_string(),
+                (Some(trep), _) => capabilities
+                    .vu_blocks
+                    .iter()
+                    .find(|capability| capability.trep == trep)
+                    .map_or_else(
+                        || format!("TREP {:#04x}", trep),
+                        |capability| capability.name.clone(),
+                    ),
+            };
+            let outcome = match block.status {
+                BlockParseStatus::Parsed => Outcome::Pass,
+                BlockParseStatus::Unknown => Outcome::Partial("unknown_block"),
+                BlockParseStatus::Failed { error } => Outcome::Fail(categorize(&error)),
+            };
+            (name, outcome)
+        })
+        .collect())
+}
+
+/// Every file below `dir`, labeled with the first directory below `corpus`.
+fn collect_files(corpus: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(corpus, &path, files)?;
+            continue;
+        }
+        let vendor = path
+            .strip_prefix(corpus)
+            .ok()
+            .and_then(|relative| {
+                let mut components = relative.components();
+                let first = components.next()?;
+                // files directly in the corpus directory have no vendor directory
+                components.next()?;
+                Some(first.as_os_str().to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| UNLABELED.to_string());
+        files.push((vendor, path));
+    }
+    Ok(())
+}
+
+/// Adds the outcomes of one file to the matrix. A block found several times in a file, such as
+/// the activities of each day, counts once with its worst outcome.
+fn record(
+    matrix: &mut BTreeMap<(String, String, String), Cell>,
+    vendor: &str,
+    generation: &str,
+    outcomes: Outcomes,
+) {
+    let mut worst: BTreeMap<String, Outcome> = BTreeMap::new();
+    for (block, outcome) in outcomes {
+        let rank = |outcome: &Outcome| match outcome {
+            Outcome::Pass => 0,
+            Outcome::Partial(_) => 1,
+            Outcome::Fail(_) => 2,
+        };
+        match worst.get(&block) {
+            Some(existing) if rank(existing) >= rank(&outcome) => {}
+            _ => {
+                worst.insert(block, outcome);
+            }
+        }
+    }
+    for (block, outcome) in worst {
+        let cell = matrix
+            .entry((vendor.to_string(), generation.to_string(), block))
+            .or_default();
+        match outcome {
+            Outcome::Pass => cell.pass += 1,
+            Outcome::Partial(category) => {
+                cell.partial += 1;
+                *cell.categories.entry(category).or_default() += 1;
+            }
+            Outcome::Fail(category) => {
+                cell.fail += 1;
+                *cell.categories.entry(category).or_default() += 1;
+            }
+        }
+    }
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut csv = String::from("vendor,generation,block,pass,partial,fail,categories\n");
+    for row in rows {
+        let categories: Vec<String> = row
+            .cell
+            .categories
+            .iter()
+            .map(|(category, count)| format!("{}={}", category, count))
+            .collect();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            row.vendor,
+            row.generation,
+            row.block,
+            row.cell.pass,
+            row.cell.partial,
+            row.cell.fail,
+            categories.join(";")
+        ));
+    }
+    csv
+}
+
+fn main() -> Result<()> {
+    let matches = Command::new("compat_matrix")
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .arg(
+            Arg::new("corpus")
+                .long("corpus")
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .help("Corpus directory, with one subdirectory of files per vendor"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_parser(value_parser!(PathBuf))
+                .help("Output file path (default: stdout)"),
+        )
+        .arg(
+            Arg::new("csv")
+                .long("csv")
+                .action(clap::ArgAction::SetTrue)
+                .help("Write CSV instead of JSON"),
+        )
+        .get_matches();
+
+    let corpus = matches.get_one::<PathBuf>("corpus").unwrap();
+    let mut files = Vec::new();
+    collect_files(corpus, corpus, &mut files)?;
+
+    let results: Vec<(String, String, Outcomes)> = files
+        .par_iter()
+        .map(|(vendor, path)| {
+            let (generation, outcomes) = match fs::read(path) {
+                Ok(bytes) => file_outcomes(&bytes),
+                Err(_) => (
+                    "Unknown".to_string(),
+                    vec![("file".to_string(), Outcome::Fail("unreadable"))],
+                ),
+            };
+            (vendor.clone(), generation, outcomes)
+        })
+        .collect();
+
+    let mut matrix = BTreeMap::new();
+    for (vendor, generation, outcomes) in results {
+        record(&mut matrix, &vendor, &generation, outcomes);
+    }
+    let rows: Vec<Row> = matrix
+        .into_iter()
+        .map(|((vendor, generation, block), cell)| Row {
+            vendor,
+            generation,
+            block,
+            cell,
+        })
+        .collect();
+
+    let output = if matches.get_flag("csv") {
+        to_csv(&rows)
+    } else {
+        serde_json::to_string_pretty(&rows).context("Failed to serialize matrix")?
+    };
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => fs::write(path, output).context("Failed to write output file")?,
+        None => println!("{}", output),
+    }
+    eprintln!("Processed {} files", files.len());
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! Detects the type of a digital tachograph download from its first bytes, without parsing it.
+//!
+//! The parser depends on this crate and re-exports it as `tachograph_parser::detector`. Upload
+//! gateways that only route files by type can depend on it alone, it builds without chrono,
+//! serde or the parser. Enable the `serde` feature to serialize the detection.
+use anyhow::{Context, Result};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::io::Read;
+use std::str::FromStr;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Kind of downloaded file. Displayed, parsed and serialized as the same kebab-case name,
+/// e.g. `vehicle-unit-gen2-v2`, so every output refers to a file type the same way.
+pub enum TachoFileType {
+    VehicleUnitGen1,
+    VehicleUnitGen2,
+    VehicleUnitGen2V2,
+    DriverCardGen1,
+    DriverCardGen2,
+    DriverCardGen2V2,
+}
+impl Display for TachoFileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl FromStr for TachoFileType {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        TachoFileType::ALL
+            .into_iter()
+            .find(|file_type| file_type.as_str() == value)
+            .with_context(|| format!("Unsupported file type: {}", value))
+    }
+}
+
+impl TachoFileType {
+    pub const ALL: [TachoFileType; 6] = [
+        TachoFileType::VehicleUnitGen1,
+        TachoFileType::VehicleUnitGen2,
+        TachoFileType::VehicleUnitGen2V2,
+        TachoFileType::DriverCardGen1,
+        TachoFileType::DriverCardGen2,
+        TachoFileType::DriverCardGen2V2,
+    ];
+
+    /// Canonical name of the file type, the one used by `Display`, `FromStr` and serde.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TachoFileType::VehicleUnitGen1 => "vehicle-unit-gen1",
+            TachoFileType::VehicleUnitGen2 => "vehicle-unit-gen2",
+            TachoFileType::VehicleUnitGen2V2 => "vehicle-unit-gen2-v2",
+            TachoFileType::DriverCardGen1 => "driver-card-gen1",
+            TachoFileType::DriverCardGen2 => "driver-card-gen2",
+            TachoFileType::DriverCardGen2V2 => "driver-card-gen2-v2",
+        }
+    }
+
+    /// Whether the file was downloaded from a card. Card files of every kind are reported as
+    /// `DriverCard*`, see [`CardKind`] for telling them apart.
+    pub fn is_card(self) -> bool {
+        matches!(
+            self,
+            TachoFileType::DriverCardGen1
+                | TachoFileType::DriverCardGen2
+                | TachoFileType::DriverCardGen2V2
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Kind of card a card file was downloaded from, read from the card type in
+/// EF_Application_Identification.
+pub enum CardKind {
+    Driver,
+    Workshop,
+    Control,
+    Company,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct TachoFileDetection {
+    pub file_type: TachoFileType,
+    /// `None` for VU files, and for card files whose card type could not be read.
+    pub card_kind: Option<CardKind>,
+}
+
+/// Reads the card type from the EF_Application_Identification of the Tachograph DF, or of the
+/// Tachograph_G2 DF when the former is missing.
+fn detect_card_kind(buffer: &[u8]) -> Option<CardKind> {
+    let mut reader = std::io::Cursor::new(buffer);
+    let header_position = [[0x05, 0x01, 0x00], [0x05, 0x01, 0x02]]
+        .iter()
+        .find_map(|header| {
+            reader.set_position(2);
+            find_header(&mut reader, header)
+        })?;
+    // The card type is the first byte of the EF, after the 3 bytes of header and 2 of length
+    let card_type = *buffer.get(header_position as usize + 5)?;
+    match card_type {
+        1 => Some(CardKind::Driver),
+        2 => Some(CardKind::Workshop),
+        3 => Some(CardKind::Control),
+        4 => Some(CardKind::Company),
+        _ => {
+            log::warn!(
+                "Unknown card type in application identification: {}",
+                card_type
+            );
+            None
+        }
+    }
+}
+
+fn detect(fb: u8, sb: u8, buffer: &[u8]) -> Result<TachoFileType> {
+    match [fb, sb] {
+        // Vehicle Unit
+        // Vehicle unit files always start with TREP 0x76, second byte usually refers to the block SID
+        [0x76, _] => {
+            // The order of these checks is NOT important for VU, as they are mutually exclusive
+
+            // Check for Gen2V2 blocks
+            if (0x31..=0x35).contains(&sb) {
+                log::info!("File detected as Vehicle Unit Gen2V2");
+                return Ok(TachoFileType::VehicleUnitGen2V2);
+            }
+
+            // Check for Gen2 blocks
+            if (0x21..=0x25).contains(&sb) {
+                log::info!("File detected as Vehicle Unit Gen2");
+                return Ok(TachoFileType::VehicleUnitGen2);
+            }
+
+            // Check for Gen1 blocks
+            if (0x01..=0x05).contains(&sb) {
+                log::info!("File detected as Vehicle Unit Gen1");
+                return Ok(TachoFileType::VehicleUnitGen1);
+            }
+
+            Err(anyhow::anyhow!("Unsupported Vehicle Unit tacho file type"))
+        }
+        // Vehicle Unit with the response message header kept in front of the first block
+        [0x80, 0xF0] if response_header_length(buffer) > 0 => {
+            detect(buffer[4], buffer[5], &buffer[4..])
+        }
+        // Driver Card
+        // These bytes should always be the same and should refer to the CardIccIdentification Gen1 (which driver files start with)
+        [0x00, 0x02] => {
+            // The order of these checks is important for Driver Card
+
+            let mut reader = std::io::Cursor::new(buffer);
+            reader.set_position(2); // Skip the first two bytes we've already read
+
+            // Check for Gen2 V2 first
+            if find_header(&mut reader, &[0x05, 0x25, 0x02]).is_some() {
+                log::info!("File detected as Driver Card Gen2V2");
+                return Ok(TachoFileType::DriverCardGen2V2);
+            }
+
+            // Reset reader position and check for Gen2
+            reader.set_position(2);
+            if find_header(&mut reader, &[0x05, 0x01, 0x02]).is_some() {
+                log::info!("File detected as Driver Card Gen2");
+                return Ok(TachoFileType::DriverCardGen2);
+            }
+
+            // Reset reader position and check for Gen1
+            reader.set_position(2);
+            if find_header(&mut reader, &[0x05, 0x01, 0x00]).is_some() {
+                log::info!("File detected as Driver Card Gen1");
+                return Ok(TachoFileType::DriverCardGen1);
+            }
+
+            Err(anyhow::anyhow!("Unsupported Driver Card tacho file type"))
+        }
+        _ => anyhow::bail!(
+            "Unsupported tacho file type, first byte: 0x{:02X}, second byte: 0x{:02X}",
+            fb,
+            sb
+        ),
+    }
+}
+/// TREPs of the Gen1 VU blocks, the second byte of each block.
+pub const GEN1_TREPS: [u8; 5] = [0x01, 0x02, 0x03, 0x04, 0x05];
+/// TREPs of the Gen2 VU blocks.
+pub const GEN2_TREPS: [u8; 5] = [0x21, 0x22, 0x23, 0x24, 0x25];
+/// TREPs of the Gen2 version 2 VU blocks.
+pub const GEN2V2_TREPS: [u8; 4] = [0x31, 0x32, 0x33, 0x35];
+
+/// Length of the download response message header at the start of `data`, the `Fmt Tgt Src
+/// Len` bytes of appendix 7 that some download tools keep in front of each block. 0 when
+/// `data` does not start with one.
+pub fn response_header_length(data: &[u8]) -> usize {
+    match data {
+        [0x80, 0xF0, 0xEE, _, 0x76, trep, ..]
+            if GEN1_TREPS.contains(trep)
+                || GEN2_TREPS.contains(trep)
+                || GEN2V2_TREPS.contains(trep) =>
+        {
+            4
+        }
+        _ => 0,
+    }
+}
+
+/// Attempts to find a header in the buffer by reading 3 bytes at a time and comparing with the given header
+fn find_header(reader: &mut std::io::Cursor<&[u8]>, header: &[u8]) -> Option<u64> {
+    let mut buffer = [0u8; 3];
+    while reader.read_exact(&mut buffer).is_ok() {
+        if buffer == header {
+            return Some(reader.position() - 3);
+        }
+        reader.set_position(reader.position() - 2);
+    }
+    None
+}
+
+pub fn detect_from_bytes(bytes: &[u8]) -> Result<TachoFileType> {
+    let fb = *bytes.first().context("Failed to read first byte")?;
+    let sb = *bytes.get(1).context("Failed to read second byte")?;
+    detect(fb, sb, bytes)
+}
+
+pub fn detect_from_file(file_path: &str) -> Result<TachoFileType> {
+    let bytes = std::fs::read(file_path).context("Failed to read file")?;
+    detect_from_bytes(&bytes)
+}
+
+/// Like [`detect_from_bytes`], also reporting the [`CardKind`] of card files.
+pub fn detect_detailed_from_bytes(bytes: &[u8]) -> Result<TachoFileDetection> {
+    let file_type = detect_from_bytes(bytes)?;
+    let card_kind = if file_type.is_card() {
+        detect_card_kind(bytes)
+    } else {
+        None
+    };
+    Ok(TachoFileDetection {
+        file_type,
+        card_kind,
+    })
+}
+
+pub fn detect_detailed_from_file(file_path: &str) -> Result<TachoFileDetection> {
+    let bytes = std::fs::read(file_path).context("Failed to read file")?;
+    detect_detailed_from_bytes(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CardIccIdentification header followed by a Gen1 EF_Application_Identification
+    fn card_file(card_type: u8) -> Vec<u8> {
+        let mut bytes = vec![0x00, 0x02, 0x00, 0x00, 0x01, 0xAA];
+        bytes.extend_from_slice(&[0x05, 0x01, 0x00, 0x00, 0x0A, card_type]);
+        bytes.extend_from_slice(&[0x00; 9]);
+        bytes
+    }
+
+    #[test]
+    fn test_detect_card_kind() {
+        let detection = detect_detailed_from_bytes(&card_file(2)).unwrap();
+        assert_eq!(
+            detection,
+            TachoFileDetection {
+                file_type: TachoFileType::DriverCardGen1,
+                card_kind: Some(CardKind::Workshop),
+            }
+        );
+        assert_eq!(
+            detect_detailed_from_bytes(&card_file(4)).unwrap().card_kind,
+            Some(CardKind::Company)
+        );
+        assert_eq!(
+            detect_detailed_from_bytes(&card_file(7)).unwrap().card_kind,
+            None
+        );
+
+        // TREP of a Gen1 overview block
+        let detection = detect_detailed_from_bytes(&[0x76, 0x01, 0x00, 0x00]).unwrap();
+        assert_eq!(detection.file_type, TachoFileType::VehicleUnitGen1);
+        assert_eq!(detection.card_kind, None);
+    }
+
+    #[test]
+    fn test_file_type_names_round_trip() {
+        for file_type in TachoFileType::ALL {
+            let name = file_type.to_string();
+            assert_eq!(name.parse::<TachoFileType>().unwrap(), file_type);
+        }
+        assert_eq!(
+            TachoFileType::VehicleUnitGen2V2.to_string(),
+            "vehicle-unit-gen2-v2"
+        );
+        assert!("VehicleUnitGen1".parse::<TachoFileType>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_file_type_serde_names() {
+        for file_type in TachoFileType::ALL {
+            let name = format!("\"{}\"", file_type);
+            assert_eq!(serde_json::to_string(&file_type).unwrap(), name);
+            assert_eq!(
+                serde_json::from_str::<TachoFileType>(&name).unwrap(),
+                file_type
+            );
+        }
+    }
+}
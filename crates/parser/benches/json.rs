@@ -0,0 +1,45 @@
+//! JSON serialization of a VU download with a day of detailed speed data. Compare runs with and
+//! without `--features simd-json`:
+//!
+//! ```sh
+//! cargo bench -p tachograph_parser --bench json
+//! cargo bench -p tachograph_parser --bench json --features simd-json
+//! ```
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tachograph_parser::json::{self, JsonOptions};
+use tachograph_parser::vu_parser::VuParser;
+
+fn detailed_speed_download() -> Vec<u8> {
+    let mut input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd").to_vec();
+    // a detailed speed block with one record per minute of a day, then its signature
+    input.extend([0x76, 0x04]);
+    input.extend(1440u16.to_be_bytes());
+    for minute in 0..1440u32 {
+        input.extend((0x65E1_0000 + minute * 60).to_be_bytes());
+        input.extend((0..60).map(|second| ((minute + second) % 120) as u8));
+    }
+    input.extend([0x00; 128]);
+    input
+}
+
+fn serialize(c: &mut Criterion) {
+    let vu_data = VuParser::new_from_bytes(&detailed_speed_download())
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let mut group = c.benchmark_group("serialize");
+    for (name, pretty) in [("compact", false), ("pretty", true)] {
+        let options = JsonOptions {
+            pretty,
+            ..JsonOptions::default()
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| json::to_json(black_box(&vu_data), &options).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, serialize);
+criterion_main!(benches);
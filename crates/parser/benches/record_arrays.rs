@@ -0,0 +1,65 @@
+//! Parsing of Gen2 record arrays, the bulk of a Gen2 VU download. Compare runs with and without
+//! `--features smallvec` for the arrays that usually hold a single record:
+//!
+//! ```sh
+//! cargo bench -p tachograph_parser --bench record_arrays
+//! cargo bench -p tachograph_parser --bench record_arrays --features smallvec
+//! ```
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::io::Cursor;
+use tachograph_parser::dt::gen2::RecordArray;
+use tachograph_parser::dt::{TimeReal, VehicleIdentificationNumber};
+
+/// Header of a record array: record type, record size and number of records.
+fn header(record_type: u8, record_size: u16, no_of_records: u16) -> Vec<u8> {
+    let mut input = vec![record_type];
+    input.extend(record_size.to_be_bytes());
+    input.extend(no_of_records.to_be_bytes());
+    input
+}
+
+fn single_record(c: &mut Criterion) {
+    let mut input = header(0x0A, 17, 1);
+    input.extend(b"WDB9634031L123456");
+
+    let mut group = c.benchmark_group("single_record");
+    group.bench_function("vec", |b| {
+        b.iter(|| {
+            RecordArray::parse(
+                &mut Cursor::new(black_box(&input[..])),
+                VehicleIdentificationNumber::parse,
+            )
+            .unwrap()
+            .into_inner()
+        })
+    });
+    group.bench_function("small_records", |b| {
+        b.iter(|| {
+            RecordArray::parse_small(
+                &mut Cursor::new(black_box(&input[..])),
+                VehicleIdentificationNumber::parse,
+            )
+            .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn many_records(c: &mut Criterion) {
+    // a day of date of day downloaded records, one per minute
+    let mut input = header(0x06, 4, 1440);
+    for minute in 0..1440u32 {
+        input.extend((0x65E1_0000 + minute * 60).to_be_bytes());
+    }
+
+    c.bench_function("many_records", |b| {
+        b.iter(|| {
+            RecordArray::parse(&mut Cursor::new(black_box(&input[..])), TimeReal::parse)
+                .unwrap()
+                .into_inner()
+        })
+    });
+}
+
+criterion_group!(benches, single_record, many_records);
+criterion_main!(benches);
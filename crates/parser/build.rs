@@ -0,0 +1,114 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Modules of `src/dt` whose doc comments link the appendix 1 section of each type.
+const DT_MODULES: [(&str, &str); 5] = [
+    ("dt", "src/dt/mod.rs"),
+    ("dt::external", "src/dt/external.rs"),
+    ("dt::gen1", "src/dt/gen1.rs"),
+    ("dt::gen2", "src/dt/gen2.rs"),
+    ("dt::gen2v2", "src/dt/gen2v2.rs"),
+];
+
+struct Entry {
+    rust_type: String,
+    data_type: String,
+    section: String,
+    url: String,
+    /// Whether the type can implement `SpecDocumented`, false for aliases and generic types.
+    implementable: bool,
+}
+
+/// Parses a `/// [DataType: appendix 2.73.](url)` doc line.
+fn parse_reference(line: &str) -> Option<(String, String, String)> {
+    let rest = line.trim().strip_prefix("/// [")?;
+    let (data_type, rest) = rest.split_once(": appendix ")?;
+    let (section, rest) = rest.split_once("](")?;
+    let url = rest.strip_suffix(')')?;
+    Some((
+        data_type.to_string(),
+        section.trim_end_matches('.').to_string(),
+        url.to_string(),
+    ))
+}
+
+/// The name of the type declared or implemented on the line, and whether it is a plain struct or
+/// enum. Private aliases such as the `NoOf...` counters are not part of the API and are skipped.
+fn parse_declaration(line: &str) -> Option<(String, bool)> {
+    let line = line.trim();
+    for (prefix, implementable) in [
+        ("pub struct ", true),
+        ("pub enum ", true),
+        ("pub type ", false),
+        ("impl ", true),
+    ] {
+        if let Some(rest) = line.strip_prefix(prefix) {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let generic = rest[end..].starts_with('<');
+            return Some((rest[..end].to_string(), implementable && !generic));
+        }
+    }
+    None
+}
+
+fn main() {
+    let mut entries: Vec<Entry> = Vec::new();
+    for (module, path) in DT_MODULES {
+        println!("cargo:rerun-if-changed={}", path);
+        let source = fs::read_to_string(path).expect("readable dt module");
+        let lines: Vec<&str> = source.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            let Some((data_type, section, url)) = parse_reference(line) else {
+                continue;
+            };
+            // the declaration follows the rest of the doc comment and any attributes
+            let declaration = lines[index + 1..]
+                .iter()
+                .map(|line| line.trim())
+                .find(|line| {
+                    !line.is_empty() && !line.starts_with("///") && !line.starts_with("#[")
+                })
+                .and_then(parse_declaration);
+            let Some((name, implementable)) = declaration else {
+                continue;
+            };
+            let rust_type = format!("{}::{}", module, name);
+            let implementable =
+                implementable && !entries.iter().any(|entry| entry.rust_type == rust_type);
+            entries.push(Entry {
+                rust_type,
+                data_type,
+                section,
+                url,
+                implementable,
+            });
+        }
+    }
+
+    let mut generated = String::from("pub(crate) static SPEC_REFS: &[SpecRef] = &[\n");
+    for entry in &entries {
+        writeln!(
+            generated,
+            "    SpecRef {{ rust_type: {:?}, data_type: {:?}, section: {:?}, url: {:?} }},",
+            entry.rust_type, entry.data_type, entry.section, entry.url
+        )
+        .unwrap();
+    }
+    generated.push_str("];\n");
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.implementable {
+            writeln!(
+                generated,
+                "impl SpecDocumented for crate::{} {{ fn spec_ref() -> &'static SpecRef {{ &SPEC_REFS[{}] }} }}",
+                entry.rust_type, index
+            )
+            .unwrap();
+        }
+    }
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR set by cargo");
+    fs::write(Path::new(&out_dir).join("spec_refs.rs"), generated).expect("writable OUT_DIR");
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use std::env;
+use std::io::{self, BufWriter};
+use tachograph_parser::sink::{self, LengthPrefixedNdjsonSink};
+use tachograph_parser::TachoData;
+
+/// Streams the records of a card or VU file to stdout as length-prefixed NDJSON, one frame per
+/// record, e.g. `cargo run --example kafka_sink -- file.ddd | your-producer`.
+/// The producer reads a big-endian u32 length, then that many bytes, and sends them as one message.
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        eprintln!("Usage: {} <path_to_tachograph_file>", args[0]);
+        std::process::exit(1);
+    }
+
+    let bytes = std::fs::read(&args[1])?;
+    let mut sink = LengthPrefixedNdjsonSink::new(BufWriter::new(io::stdout().lock()));
+    match tachograph_parser::parse_from_bytes(&bytes)? {
+        TachoData::Card { card_data } => sink::write_card_data(&card_data, &mut sink)?,
+        TachoData::Vu { vu_data } => sink::write_vu_data(&vu_data, &mut sink)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,388 @@
+//! Calibration history of vehicles, from VU downloads and workshop cards.
+use crate::capabilities::Generation;
+use crate::card_parser::CardData;
+use crate::dt::{
+    gen1, gen2, Coded, VehicleIdentificationNumber, VehicleRegistrationIdentification,
+};
+use crate::units::Kilometers;
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Months, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Where a calibration record was read from.
+pub enum CalibrationSource {
+    /// The technical data of a VU download.
+    VehicleUnit,
+    /// The `Calibration` EF of a workshop card.
+    WorkshopCard,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A calibration of a vehicle, see [`calibrations`].
+pub struct CalibrationEntry {
+    pub source: CalibrationSource,
+    pub generation: Generation,
+    /// Serialized calibration purpose, e.g. `PeriodicInspection`.
+    pub purpose: String,
+    /// Whether the purpose restarts the two-year inspection interval: activation, (first)
+    /// installation or periodic inspection.
+    pub inspection: bool,
+    /// Date and time set by the calibration.
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub time: DateTime<Utc>,
+    /// Odometer set by the calibration.
+    pub odometer: Kilometers,
+    pub vehicle_registration_number: Option<String>,
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub next_calibration_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Two inspections further apart than the two-year periodic inspection interval.
+pub struct InspectionGap {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub previous_inspection: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub next_inspection: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Calibrations of one vehicle, oldest first, see [`calibrations`].
+pub struct CalibrationHistory {
+    pub vehicle_identification_number: String,
+    pub calibrations: Vec<CalibrationEntry>,
+    pub inspection_gaps: Vec<InspectionGap>,
+}
+
+fn purpose_label<T: Serialize>(purpose: &T) -> String {
+    match serde_json::to_value(purpose) {
+        Ok(serde_json::Value::String(label)) => label,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+fn is_gen1_inspection(purpose: &Coded<gen1::CalibrationPurpose>) -> bool {
+    matches!(
+        purpose.known(),
+        Some(
+            gen1::CalibrationPurpose::Activation
+                | gen1::CalibrationPurpose::FirstInstallation
+                | gen1::CalibrationPurpose::Installation
+                | gen1::CalibrationPurpose::PeriodicInspection
+        )
+    )
+}
+
+fn is_gen2_inspection(purpose: &gen2::CalibrationPurposeGen2) -> bool {
+    matches!(
+        purpose,
+        gen2::CalibrationPurposeGen2::Activation
+            | gen2::CalibrationPurposeGen2::FirstInstallation
+            | gen2::CalibrationPurposeGen2::Installation
+            | gen2::CalibrationPurposeGen2::PeriodicInspection
+    )
+}
+
+/// Merges the calibration records of VU downloads and workshop cards into one chronological
+/// history per VIN. The same calibration found in several downloads, matched by time and
+/// odometer, is kept once, preferring the VU record. Records without a calibration time are
+/// skipped. Consecutive inspections more than two years apart are reported as gaps; the period
+/// after the last inspection is not checked.
+pub fn calibrations<'a>(
+    vus: impl IntoIterator<Item = &'a VuData>,
+    cards: impl IntoIterator<Item = &'a CardData>,
+) -> Vec<CalibrationHistory> {
+    let mut entries: Vec<(String, CalibrationEntry)> = Vec::new();
+    let vin_of = |vin: &Option<VehicleIdentificationNumber>, fallback: &str| {
+        vin.as_ref()
+            .map_or_else(|| fallback.to_string(), |vin| vin.0 .0.clone())
+    };
+    let vrn_of = |vrn: &Option<VehicleRegistrationIdentification>| {
+        vrn.as_ref()
+            .map(|vrn| vrn.vehicle_registration_number.vehicle_reg_number.0.clone())
+    };
+    for vu_data in vus {
+        match vu_data {
+            VuData::Gen1(blocks) => {
+                let vu_vin = &blocks.vu_overview.vehicle_identification_number.0 .0;
+                let records = blocks
+                    .vu_company_locks
+                    .iter()
+                    .flat_map(|block| &block.vu_calibration_data.vu_calibration_records);
+                for record in records {
+                    let Some(time) = &record.new_time_value else {
+                        continue;
+                    };
+                    entries.push((
+                        vin_of(&record.vehicle_identification_number, vu_vin),
+                        CalibrationEntry {
+                            source: CalibrationSource::VehicleUnit,
+                            generation: Generation::Gen1,
+                            purpose: purpose_label(&record.calibration_purpose),
+                            inspection: is_gen1_inspection(&record.calibration_purpose),
+                            time: time.0,
+                            odometer: record.new_odometer_value.0,
+                            vehicle_registration_number: vrn_of(
+                                &record.vehicle_registration_identification,
+                            ),
+                            next_calibration_date: record
+                                .next_calibration_date
+                                .as_ref()
+                                .map(|date| date.0),
+                        },
+                    ));
+                }
+            }
+            VuData::Gen2(blocks) => {
+                let vu_vin = blocks
+                    .vu_overview
+                    .vehicle_identification_number_record_array
+                    .first()
+                    .map(|vin| vin.0 .0.clone())
+                    .unwrap_or_default();
+                let records = blocks
+                    .vu_company_locks
+                    .iter()
+                    .flat_map(|block| &block.vu_calibration_record_array);
+                for record in records {
+                    let Some(time) = &record.new_time_value else {
+                        continue;
+                    };
+                    entries.push((
+                        vin_of(&record.vehicle_identification_number, &vu_vin),
+                        CalibrationEntry {
+                            source: CalibrationSource::VehicleUnit,
+                            generation: Generation::Gen2,
+                            purpose: purpose_label(&record.calibration_purpose),
+                            inspection: is_gen2_inspection(&record.calibration_purpose),
+                            time: time.0,
+                            odometer: record.new_odometer_value.0,
+                            vehicle_registration_number: vrn_of(
+                                &record.vehicle_registration_identification,
+                            ),
+                            next_calibration_date: record
+                                .next_calibration_date
+                                .as_ref()
+                                .map(|date| date.0),
+                        },
+                    ));
+                }
+            }
+            VuData::Gen2V2(blocks) => {
+                let vu_vin = blocks
+                    .vu_overview
+                    .vehicle_identification_number_record_array
+                    .first()
+                    .map(|vin| vin.0 .0.clone())
+                    .unwrap_or_default();
+                let records = blocks
+                    .vu_company_locks
+                    .iter()
+                    .flat_map(|block| &block.vu_calibration_record_array);
+                for record in records {
+                    let Some(time) = &record.new_time_value else {
+                        continue;
+                    };
+                    entries.push((
+                        vin_of(&record.vehicle_identification_number, &vu_vin),
+                        CalibrationEntry {
+                            source: CalibrationSource::VehicleUnit,
+                            generation: Generation::Gen2V2,
+                            purpose: purpose_label(&record.calibration_purpose),
+                            inspection: is_gen2_inspection(&record.calibration_purpose),
+                            time: time.0,
+                            odometer: record.new_odometer_value.0,
+                            vehicle_registration_number: vrn_of(
+                                &record.vehicle_registration_identification,
+                            ),
+                            next_calibration_date: record
+                                .next_calibration_date
+                                .as_ref()
+                                .map(|date| date.0),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+    for card_data in cards {
+        let gen1_blocks = match card_data {
+            CardData::Gen1 { gen1_blocks, .. }
+            | CardData::Gen2 { gen1_blocks, .. }
+            | CardData::Gen2V2 { gen1_blocks, .. } => gen1_blocks,
+        };
+        let records = gen1_blocks
+            .calibration
+            .iter()
+            .flat_map(|calibration| &calibration.calibration_records);
+        for record in records {
+            entries.push((
+                record.vehicle_identification_number.0 .0.clone(),
+                CalibrationEntry {
+                    source: CalibrationSource::WorkshopCard,
+                    generation: Generation::Gen1,
+                    purpose: purpose_label(&record.calibration_purpose),
+                    inspection: is_gen1_inspection(&record.calibration_purpose),
+                    time: record.new_time_value.0,
+                    odometer: record.new_odometer_value.0,
+                    vehicle_registration_number: Some(
+                        record
+                            .vehicle_registration
+                            .vehicle_registration_number
+                            .vehicle_reg_number
+                            .0
+                            .clone(),
+                    ),
+                    next_calibration_date: Some(record.next_calibration_date.0),
+                },
+            ));
+        }
+    }
+    group_calibrations(entries)
+}
+
+/// Groups calibrations by VIN, see [`calibrations`].
+fn group_calibrations(entries: Vec<(String, CalibrationEntry)>) -> Vec<CalibrationHistory> {
+    let mut histories: Vec<CalibrationHistory> = Vec::new();
+    for (vin, entry) in entries {
+        let index = match histories
+            .iter()
+            .position(|history| history.vehicle_identification_number == vin)
+        {
+            Some(index) => index,
+            None => {
+                histories.push(CalibrationHistory {
+                    vehicle_identification_number: vin,
+                    calibrations: Vec::new(),
+                    inspection_gaps: Vec::new(),
+                });
+                histories.len() - 1
+            }
+        };
+        let calibrations = &mut histories[index].calibrations;
+        let duplicate = calibrations
+            .iter()
+            .any(|existing| existing.time == entry.time && existing.odometer == entry.odometer);
+        if !duplicate {
+            calibrations.push(entry);
+        }
+    }
+    for history in &mut histories {
+        history.calibrations.sort_by_key(|entry| entry.time);
+        let inspections: Vec<DateTime<Utc>> = history
+            .calibrations
+            .iter()
+            .filter(|entry| entry.inspection)
+            .map(|entry| entry.time)
+            .collect();
+        history.inspection_gaps = inspections
+            .windows(2)
+            .filter(|pair| {
+                pair[0]
+                    .checked_add_months(Months::new(24))
+                    .is_some_and(|due| pair[1] > due)
+            })
+            .map(|pair| InspectionGap {
+                previous_inspection: pair[0],
+                next_inspection: pair[1],
+            })
+            .collect();
+    }
+    histories.sort_by(|a, b| {
+        a.vehicle_identification_number
+            .cmp(&b.vehicle_identification_number)
+    });
+    histories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::Generation;
+    use crate::units::Kilometers;
+
+    fn calibration(time: &str, purpose: &str, inspection: bool) -> CalibrationEntry {
+        CalibrationEntry {
+            source: CalibrationSource::VehicleUnit,
+            generation: Generation::Gen2,
+            purpose: purpose.to_string(),
+            inspection,
+            time: time.parse().unwrap(),
+            odometer: Kilometers(1000),
+            vehicle_registration_number: None,
+            next_calibration_date: None,
+        }
+    }
+
+    #[test]
+    fn test_group_calibrations() {
+        let vin = "WDB9634031L123456".to_string();
+        let card_copy = CalibrationEntry {
+            source: CalibrationSource::WorkshopCard,
+            ..calibration("2020-05-01T10:00:00Z", "Activation", true)
+        };
+        let histories = group_calibrations(vec![
+            (
+                vin.clone(),
+                calibration("2022-04-30T10:00:00Z", "PeriodicInspection", true),
+            ),
+            (
+                vin.clone(),
+                calibration("2020-05-01T10:00:00Z", "Activation", true),
+            ),
+            // same calibration downloaded from a workshop card
+            (vin.clone(), card_copy),
+            (
+                vin.clone(),
+                calibration(
+                    "2023-01-01T10:00:00Z",
+                    "TimeAdjustmentWithoutCalibration",
+                    false,
+                ),
+            ),
+            (
+                vin.clone(),
+                calibration("2024-06-01T10:00:00Z", "PeriodicInspection", true),
+            ),
+            (
+                "OTHERVIN".to_string(),
+                calibration("2019-01-01T10:00:00Z", "Activation", true),
+            ),
+        ]);
+        assert_eq!(histories.len(), 2);
+        assert_eq!(histories[1].vehicle_identification_number, vin);
+        let history = &histories[1];
+        assert_eq!(history.calibrations.len(), 4);
+        assert_eq!(
+            history.calibrations[0].source,
+            CalibrationSource::VehicleUnit
+        );
+        assert!(history
+            .calibrations
+            .windows(2)
+            .all(|pair| pair[0].time <= pair[1].time));
+        // within two years, then a gap skipping over the time adjustment
+        assert_eq!(
+            history.inspection_gaps,
+            vec![InspectionGap {
+                previous_inspection: "2022-04-30T10:00:00Z".parse().unwrap(),
+                next_inspection: "2024-06-01T10:00:00Z".parse().unwrap(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,478 @@
+//! VU time adjustments and their effect on the times recorded around them.
+use super::timeline::ActivityPeriod;
+use crate::capabilities::Generation;
+use crate::dt::{gen2, VuDownloadablePeriod};
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Who set the VU clock.
+pub enum TimeAdjustmentSource {
+    /// A workshop, identified by its card.
+    Workshop,
+    /// The VU itself from the GNSS time. Gen2 VUs record these automatic adjustments without a
+    /// workshop card.
+    Gnss,
+}
+
+#[derive(Debug, Clone)]
+/// Thresholds of [`time_adjustment_audit`].
+pub struct TimeAdjustmentConfig {
+    /// Largest accepted change of the clock by a single adjustment.
+    pub max_adjustment_seconds: i64,
+    /// Largest accepted sum of all adjustments within the period of the file.
+    pub max_cumulative_drift_seconds: i64,
+}
+impl Default for TimeAdjustmentConfig {
+    fn default() -> Self {
+        // Regulation (EU) 165/2014 requires an inspection once UTC time deviates by more than
+        // 20 minutes
+        TimeAdjustmentConfig {
+            max_adjustment_seconds: 20 * 60,
+            max_cumulative_drift_seconds: 20 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A time adjustment recorded by a VU, see [`time_adjustment_audit`].
+pub struct TimeAdjustment {
+    pub source: TimeAdjustmentSource,
+    pub generation: Generation,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub old_time_value: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub new_time_value: DateTime<Utc>,
+    /// New minus old time, positive when the clock was put forward.
+    pub delta_seconds: i64,
+    /// Sum of the deltas of the adjustments within the period up to and including this one.
+    pub cumulative_drift_seconds: i64,
+    pub workshop_name: Option<String>,
+    pub exceeds_threshold: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Time adjustments of a VU download, oldest first.
+pub struct TimeAdjustmentAudit {
+    pub adjustments: Vec<TimeAdjustment>,
+    /// Start of the period the drift is computed over, the downloadable period of the VU.
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub period_start: Option<DateTime<Utc>>,
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub period_end: Option<DateTime<Utc>>,
+    /// Sum of the deltas of the adjustments within the period.
+    pub cumulative_drift_seconds: i64,
+    pub exceeds_cumulative_threshold: bool,
+}
+
+/// Sorts time adjustments by their new time and flags the ones changing the clock by more than
+/// the configured threshold. The cumulative drift only counts adjustments made within `period`,
+/// or all of them when the period is unknown.
+pub fn time_adjustment_audit(
+    mut adjustments: Vec<TimeAdjustment>,
+    period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    config: &TimeAdjustmentConfig,
+) -> TimeAdjustmentAudit {
+    adjustments.sort_by_key(|adjustment| (adjustment.new_time_value, adjustment.old_time_value));
+    let mut cumulative_drift_seconds = 0;
+    for adjustment in adjustments.iter_mut() {
+        adjustment.delta_seconds =
+            (adjustment.new_time_value - adjustment.old_time_value).num_seconds();
+        adjustment.exceeds_threshold =
+            adjustment.delta_seconds.abs() > config.max_adjustment_seconds;
+        let within_period = period.is_none_or(|(start, end)| {
+            adjustment.new_time_value >= start && adjustment.new_time_value <= end
+        });
+        if within_period {
+            cumulative_drift_seconds += adjustment.delta_seconds;
+        }
+        adjustment.cumulative_drift_seconds = cumulative_drift_seconds;
+    }
+    TimeAdjustmentAudit {
+        adjustments,
+        period_start: period.map(|(start, _)| start),
+        period_end: period.map(|(_, end)| end),
+        cumulative_drift_seconds,
+        exceeds_cumulative_threshold: cumulative_drift_seconds.abs()
+            > config.max_cumulative_drift_seconds,
+    }
+}
+
+fn time_adjustment(
+    source: TimeAdjustmentSource,
+    generation: Generation,
+    old_time_value: DateTime<Utc>,
+    new_time_value: DateTime<Utc>,
+    workshop_name: Option<String>,
+) -> TimeAdjustment {
+    TimeAdjustment {
+        source,
+        generation,
+        old_time_value,
+        new_time_value,
+        delta_seconds: 0,
+        cumulative_drift_seconds: 0,
+        workshop_name,
+        exceeds_threshold: false,
+    }
+}
+
+fn gen2_time_adjustments<'a>(
+    blocks: &'a [gen2::VuEventsAndFaultsBlockGen2],
+    generation: Generation,
+) -> impl Iterator<Item = TimeAdjustment> + 'a {
+    blocks
+        .iter()
+        .flat_map(|block| &block.vu_time_adjustment_record_array)
+        .map(move |record| {
+            let source = match record.workshop_card_number_and_generation {
+                Some(_) => TimeAdjustmentSource::Workshop,
+                None => TimeAdjustmentSource::Gnss,
+            };
+            let workshop_name = (source == TimeAdjustmentSource::Workshop)
+                .then(|| record.workshop_name.name.0.clone());
+            time_adjustment(
+                source,
+                generation,
+                record.old_time_value,
+                record.new_time_value,
+                workshop_name,
+            )
+        })
+}
+
+impl VuData {
+    /// Time adjustments of the VU, with the drift over its downloadable period, see
+    /// [`time_adjustment_audit`].
+    pub fn time_adjustment_audit(&self, config: &TimeAdjustmentConfig) -> TimeAdjustmentAudit {
+        let (adjustments, period): (Vec<TimeAdjustment>, Option<&VuDownloadablePeriod>) = match self
+        {
+            VuData::Gen1(blocks) => (
+                blocks
+                    .vu_events_and_faults
+                    .iter()
+                    .flat_map(|block| &block.vu_time_adjustment_data.vu_time_adjustment_records)
+                    .map(|record| {
+                        time_adjustment(
+                            TimeAdjustmentSource::Workshop,
+                            Generation::Gen1,
+                            record.old_time_value,
+                            record.new_time_value,
+                            Some(record.workshop_name.name.0.clone()),
+                        )
+                    })
+                    .collect(),
+                Some(&blocks.vu_overview.vu_downloadable_period),
+            ),
+            VuData::Gen2(blocks) => (
+                gen2_time_adjustments(&blocks.vu_events_and_faults, Generation::Gen2).collect(),
+                blocks
+                    .vu_overview
+                    .vu_downloadable_period_record_array
+                    .first(),
+            ),
+            VuData::Gen2V2(blocks) => (
+                gen2_time_adjustments(&blocks.vu_events_and_faults, Generation::Gen2V2).collect(),
+                blocks
+                    .vu_overview
+                    .vu_downloadable_period_record_array
+                    .first(),
+            ),
+        };
+        let period = period.map(|period| {
+            (
+                period.min_downloadable_time.0,
+                period.max_downloadable_time.0,
+            )
+        });
+        time_adjustment_audit(adjustments, period, config)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// What [`clock_corrected_timeline`] does with the times recorded before a time adjustment.
+pub enum ClockCorrection {
+    /// Keeps the recorded times and only reports their offsets.
+    Annotate,
+    /// Moves the recorded times by their offsets.
+    Adjust,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An activity period with the offsets of the clock it was recorded with, see
+/// [`clock_corrected_timeline`].
+pub struct ClockCorrectedPeriod {
+    /// The period as recorded, or moved by the offsets with [`ClockCorrection::Adjust`].
+    pub period: ActivityPeriod,
+    /// Correction of the recorded start, the sum of the deltas of the adjustments that followed it.
+    pub start_offset_seconds: i64,
+    pub end_offset_seconds: i64,
+    /// The start or the end falls in a window the clock was set back over. Times in it were
+    /// recorded twice, once under each clock, and are not corrected.
+    pub ambiguous: bool,
+}
+
+/// Corrects a timeline for the clock adjustments of the VU that recorded it, e.g. the
+/// adjustments of [`VuData::time_adjustment_audit`], oldest first.
+/// A time recorded before an adjustment was read from the clock it corrected, so it is off by the
+/// deltas of that adjustment and of every later one; times after the last adjustment are kept. On a naive timeline, a
+/// clock put forward leaves a gap and a clock set back books the window between the new and the
+/// old time twice. With [`ClockCorrection::Adjust`] the periods are sorted by their corrected start.
+pub fn clock_corrected_timeline(
+    timeline: &[ActivityPeriod],
+    adjustments: &[TimeAdjustment],
+    correction: ClockCorrection,
+) -> Vec<ClockCorrectedPeriod> {
+    let mut corrected: Vec<ClockCorrectedPeriod> = timeline
+        .iter()
+        .map(|period| {
+            let start_offset = clock_offset(period.start, adjustments);
+            let end_offset = clock_offset(period.end, adjustments);
+            let start_offset_seconds = start_offset.unwrap_or(0);
+            let end_offset_seconds = end_offset.unwrap_or(0);
+            let mut period = period.clone();
+            if correction == ClockCorrection::Adjust {
+                period.start += Duration::seconds(start_offset_seconds);
+                period.end = (period.end + Duration::seconds(end_offset_seconds)).max(period.start);
+            }
+            ClockCorrectedPeriod {
+                period,
+                start_offset_seconds,
+                end_offset_seconds,
+                ambiguous: start_offset.is_none() || end_offset.is_none(),
+            }
+        })
+        .collect();
+    if correction == ClockCorrection::Adjust {
+        corrected.sort_by_key(|corrected| corrected.period.start);
+    }
+    corrected
+}
+
+/// Seconds to add to a recorded time, the sum of the deltas of every adjustment after it, `None`
+/// when the time falls in a window the clock was set back over.
+fn clock_offset(time: DateTime<Utc>, adjustments: &[TimeAdjustment]) -> Option<i64> {
+    for (index, adjustment) in adjustments.iter().enumerate() {
+        if time < adjustment.old_time_value.min(adjustment.new_time_value) {
+            return Some(
+                adjustments[index..]
+                    .iter()
+                    .map(|later| (later.new_time_value - later.old_time_value).num_seconds())
+                    .sum(),
+            );
+        }
+        if time < adjustment.old_time_value {
+            return None;
+        }
+    }
+    Some(0)
+}
+
+/// Begin and end of an event or fault, made safe for duration computations. A clock set back
+/// while the incident lasted can leave its end before its begin, the end is then moved to the
+/// begin, giving a duration of zero rather than a negative one. An incident that had not ended yet
+/// keeps `None` as its end.
+pub fn normalize_event_times(
+    begin_time: DateTime<Utc>,
+    end_time: Option<DateTime<Utc>>,
+) -> (DateTime<Utc>, Option<DateTime<Utc>>) {
+    (
+        begin_time,
+        end_time.map(|end_time| end_time.max(begin_time)),
+    )
+}
+
+impl VuData {
+    /// Activity timeline of both slots corrected for the time adjustments of the VU, see
+    /// [`clock_corrected_timeline`].
+    pub fn clock_corrected_timeline(
+        &self,
+        correction: ClockCorrection,
+    ) -> Vec<ClockCorrectedPeriod> {
+        let audit = self.time_adjustment_audit(&TimeAdjustmentConfig::default());
+        clock_corrected_timeline(&self.activity_timeline(), &audit.adjustments, correction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{ActivityOrigin, ActivityPeriod};
+    use crate::capabilities::Generation;
+    use crate::dt::{gen1, ActivityChangeInfoCardActivity, ActivityChangeInfoSlot, TimeReal};
+    use chrono::{DateTime, Duration, Utc};
+
+    #[test]
+    fn test_normalize_event_times() {
+        let begin: DateTime<Utc> = "2024-03-01T10:00:00Z".parse().unwrap();
+        let later = begin + Duration::minutes(5);
+        assert_eq!(
+            normalize_event_times(begin, Some(later)),
+            (begin, Some(later))
+        );
+        // the clock was set back an hour while the event lasted
+        assert_eq!(
+            normalize_event_times(begin, Some(begin - Duration::hours(1))),
+            (begin, Some(begin))
+        );
+        assert_eq!(normalize_event_times(begin, None), (begin, None));
+
+        // an event still going on when the card was downloaded
+        let mut bytes = vec![0x07];
+        bytes.extend_from_slice(&(begin.timestamp() as u32).to_be_bytes());
+        bytes.extend_from_slice(&[0xFF; 4]);
+        bytes.extend_from_slice(&[0x00; 15]);
+        let record =
+            gen1::CardEventRecord::parse(&mut std::io::Cursor::new(bytes.as_slice())).unwrap();
+        assert_eq!(record.event_begin_time, TimeReal(begin));
+        assert_eq!(record.event_end_time, None);
+    }
+
+    #[test]
+    fn test_time_adjustment_audit() {
+        let adjustment = |source, old: &str, new: &str| {
+            time_adjustment(
+                source,
+                Generation::Gen2,
+                old.parse().unwrap(),
+                new.parse().unwrap(),
+                None,
+            )
+        };
+        let period = Some((
+            "2024-01-01T00:00:00Z".parse().unwrap(),
+            "2024-12-31T00:00:00Z".parse().unwrap(),
+        ));
+        let audit = time_adjustment_audit(
+            vec![
+                adjustment(
+                    TimeAdjustmentSource::Workshop,
+                    "2024-06-01T10:30:00Z",
+                    "2024-06-01T10:00:00Z",
+                ),
+                adjustment(
+                    TimeAdjustmentSource::Gnss,
+                    "2024-03-01T09:59:30Z",
+                    "2024-03-01T10:00:00Z",
+                ),
+                // before the downloadable period
+                adjustment(
+                    TimeAdjustmentSource::Workshop,
+                    "2023-03-01T09:00:00Z",
+                    "2023-03-01T10:00:00Z",
+                ),
+            ],
+            period,
+            &TimeAdjustmentConfig::default(),
+        );
+        let deltas: Vec<(i64, i64, bool)> = audit
+            .adjustments
+            .iter()
+            .map(|adjustment| {
+                (
+                    adjustment.delta_seconds,
+                    adjustment.cumulative_drift_seconds,
+                    adjustment.exceeds_threshold,
+                )
+            })
+            .collect();
+        assert_eq!(
+            deltas,
+            vec![(3600, 0, true), (30, 30, false), (-1800, -1770, true)]
+        );
+        assert_eq!(audit.cumulative_drift_seconds, -1770);
+        assert!(audit.exceeds_cumulative_threshold);
+
+        let lenient = TimeAdjustmentConfig {
+            max_adjustment_seconds: 3600,
+            max_cumulative_drift_seconds: 3600,
+        };
+        let audit = time_adjustment_audit(audit.adjustments, None, &lenient);
+        assert!(audit.adjustments.iter().all(|a| !a.exceeds_threshold));
+        assert_eq!(audit.cumulative_drift_seconds, 1830);
+        assert!(!audit.exceeds_cumulative_threshold);
+    }
+
+    #[test]
+    fn test_clock_corrected_timeline() {
+        let period = |start: &str, end: &str| ActivityPeriod {
+            start: start.parse().unwrap(),
+            end: end.parse().unwrap(),
+            slot: ActivityChangeInfoSlot::Driver,
+            activity: ActivityChangeInfoCardActivity::Driving,
+            origin: ActivityOrigin::Recorded,
+        };
+        let adjustment = |old: &str, new: &str| {
+            time_adjustment(
+                TimeAdjustmentSource::Workshop,
+                Generation::Gen1,
+                old.parse().unwrap(),
+                new.parse().unwrap(),
+                None,
+            )
+        };
+        let adjustments = [
+            // put forward by 20 minutes
+            adjustment("2024-03-01T08:00:00Z", "2024-03-01T08:20:00Z"),
+            // set back by 30 minutes
+            adjustment("2024-03-02T12:30:00Z", "2024-03-02T12:00:00Z"),
+        ];
+        let timeline = [
+            period("2024-03-01T06:00:00Z", "2024-03-01T07:00:00Z"),
+            period("2024-03-02T10:00:00Z", "2024-03-02T11:00:00Z"),
+            period("2024-03-02T12:10:00Z", "2024-03-02T12:20:00Z"),
+            period("2024-03-02T13:00:00Z", "2024-03-02T14:00:00Z"),
+        ];
+
+        let annotated =
+            clock_corrected_timeline(&timeline, &adjustments, ClockCorrection::Annotate);
+        let offsets: Vec<(i64, i64, bool)> = annotated
+            .iter()
+            .map(|corrected| {
+                (
+                    corrected.start_offset_seconds,
+                    corrected.end_offset_seconds,
+                    corrected.ambiguous,
+                )
+            })
+            .collect();
+        assert_eq!(
+            offsets,
+            vec![
+                // both adjustments came after it
+                (-600, -600, false),
+                (-1800, -1800, false),
+                (0, 0, true),
+                (0, 0, false)
+            ]
+        );
+        assert_eq!(annotated[0].period, timeline[0]);
+
+        let adjusted = clock_corrected_timeline(&timeline, &adjustments, ClockCorrection::Adjust);
+        assert_eq!(
+            adjusted[0].period,
+            period("2024-03-01T05:50:00Z", "2024-03-01T06:50:00Z")
+        );
+        assert_eq!(
+            adjusted[1].period,
+            period("2024-03-02T09:30:00Z", "2024-03-02T10:30:00Z")
+        );
+        assert_eq!(adjusted[2].period, timeline[2]);
+        assert_eq!(adjusted[3].period, timeline[3]);
+    }
+}
@@ -0,0 +1,507 @@
+//! Cross-checks between the EFs of a card or the blocks of a VU download, and quality scores.
+use super::clock::TimeAdjustmentConfig;
+use crate::card_parser::{CardChipIdentity, CardData};
+use crate::diagnostics::{QualityIssue, QualityPenalty, QualityScore};
+use crate::dt::{
+    ActivityChangeInfoCardStatus, CardCurrentUse, CardDriverActivity,
+    VehicleRegistrationIdentification,
+};
+use crate::vu_parser::{VuData, VuGen1Blocks};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A finding that does not prevent parsing, but means the data should be read with care.
+pub enum ConsistencyWarning {
+    /// The card was downloaded while inserted in a VU, so the activities of the open session
+    /// after the download are missing and the last recorded activity may still change.
+    #[serde(rename_all = "camelCase")]
+    SessionOpenAtDownload {
+        #[serde(with = "crate::json::timestamp")]
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        session_open_time: DateTime<Utc>,
+        session_open_vehicle: String,
+        #[serde(with = "crate::json::optional_timestamp")]
+        #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+        last_activity: Option<DateTime<Utc>>,
+        #[serde(with = "crate::json::optional_timestamp")]
+        #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+        last_download: Option<DateTime<Utc>>,
+    },
+    /// The vehicle or VU identification differs between the blocks of a VU download, a known
+    /// sign of a VU board swapped into another unit.
+    #[serde(rename_all = "camelCase")]
+    IdentificationConflict {
+        field: IdentificationField,
+        /// Value in the overview block, or for the VU serial number in the first technical data
+        /// block.
+        expected: String,
+        /// Value in a technical data block, for the vehicle from its latest calibration.
+        found: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Identification repeated across the blocks of a VU download, see
+/// [`ConsistencyWarning::IdentificationConflict`].
+pub enum IdentificationField {
+    VehicleIdentificationNumber,
+    /// Registering nation and number.
+    VehicleRegistration,
+    VuSerialNumber,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Cross-checks between the EFs of a card or the blocks of a VU download, see
+/// [`CardData::consistency_report`] and [`VuData::consistency_report`].
+pub struct ConsistencyReport {
+    pub warnings: Vec<ConsistencyWarning>,
+}
+impl ConsistencyReport {
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Detects a card downloaded while a session was still open.
+///
+/// The session opened at `session_open_time` is considered closed when a later activity change
+/// records the card as withdrawn. Otherwise it was open at download time if the card was
+/// downloaded, or when the download time is unknown had its last activity, after it opened.
+pub fn detect_open_session(
+    current_use: &CardCurrentUse,
+    card_driver_activity: &CardDriverActivity,
+    last_download: Option<DateTime<Utc>>,
+) -> Option<ConsistencyWarning> {
+    let session_open_time = current_use.session_open_time.0;
+    let changes = card_driver_activity
+        .activity_daily_records
+        .iter()
+        .flat_map(|record| {
+            let day = record.activity_record_date.0;
+            record
+                .activity_change_info
+                .iter()
+                .map(move |change| (day + change.minutes.duration(), change))
+        });
+    let mut last_activity: Option<DateTime<Utc>> = None;
+    let mut withdrawn = false;
+    for (time, change) in changes {
+        last_activity = last_activity.max(Some(time));
+        if time > session_open_time
+            && change.card_status == ActivityChangeInfoCardStatus::NotInserted
+        {
+            withdrawn = true;
+        }
+    }
+    let reference = last_download.or(last_activity)?;
+    if withdrawn || reference < session_open_time {
+        return None;
+    }
+    Some(ConsistencyWarning::SessionOpenAtDownload {
+        session_open_time,
+        session_open_vehicle: current_use
+            .session_open_vehicle
+            .vehicle_registration_number
+            .vehicle_reg_number
+            .0
+            .clone(),
+        last_activity,
+        last_download,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A card extended serial number seen with more than one chip identification or card number.
+pub struct DuplicateCardSerial {
+    pub extended_serial_number: String,
+    /// Positions of the conflicting identities in the input of [`find_duplicate_card_serials`].
+    pub indices: Vec<usize>,
+    pub chip_identifications: Vec<String>,
+    pub card_numbers: Vec<String>,
+}
+
+/// Finds card serials that appear on different chips or with different card numbers across a
+/// set of downloads, which points to a cloned or tampered card. Repeated downloads of the same
+/// card share all three identifiers and are not reported.
+pub fn find_duplicate_card_serials(identities: &[CardChipIdentity]) -> Vec<DuplicateCardSerial> {
+    let mut duplicates: Vec<DuplicateCardSerial> = Vec::new();
+    for (index, identity) in identities.iter().enumerate() {
+        let existing = duplicates
+            .iter_mut()
+            .find(|duplicate| duplicate.extended_serial_number == identity.extended_serial_number);
+        let duplicate = match existing {
+            Some(duplicate) => duplicate,
+            None => {
+                duplicates.push(DuplicateCardSerial {
+                    extended_serial_number: identity.extended_serial_number.clone(),
+                    indices: Vec::new(),
+                    chip_identifications: Vec::new(),
+                    card_numbers: Vec::new(),
+                });
+                duplicates.last_mut().expect("just pushed")
+            }
+        };
+        duplicate.indices.push(index);
+        if !duplicate
+            .chip_identifications
+            .contains(&identity.chip_identification)
+        {
+            duplicate
+                .chip_identifications
+                .push(identity.chip_identification.clone());
+        }
+        if let Some(card_number) = &identity.card_number {
+            if !duplicate.card_numbers.contains(card_number) {
+                duplicate.card_numbers.push(card_number.clone());
+            }
+        }
+    }
+    duplicates.retain(|duplicate| {
+        duplicate.chip_identifications.len() > 1 || duplicate.card_numbers.len() > 1
+    });
+    duplicates
+}
+
+/// Registering nation and number of a vehicle, e.g. `Germany B TA 1234`.
+fn vehicle_registration_label(registration: &VehicleRegistrationIdentification) -> String {
+    format!(
+        "{} {}",
+        registration.vehicle_registration_nation.0,
+        registration
+            .vehicle_registration_number
+            .vehicle_reg_number
+            .0
+            .trim()
+    )
+}
+
+/// Identification conflicts of a Gen1 download, see [`VuData::consistency_report`].
+fn gen1_identification_conflicts(blocks: &VuGen1Blocks) -> Vec<ConsistencyWarning> {
+    let overview = &blocks.vu_overview;
+    let vin = overview.vehicle_identification_number.0 .0.trim();
+    let registration = vehicle_registration_label(&overview.vehicle_registration_identification);
+    let vu_serial = blocks
+        .vu_company_locks
+        .first()
+        .map(|block| block.vu_identification.vu_serial_number.to_serial_string());
+    let mut warnings = Vec::new();
+    let mut conflict = |field, expected: &str, found: &str| {
+        if expected.is_empty() || found.is_empty() || expected == found {
+            return;
+        }
+        let warning = ConsistencyWarning::IdentificationConflict {
+            field,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        };
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    };
+    for block in &blocks.vu_company_locks {
+        if let Some(vu_serial) = &vu_serial {
+            conflict(
+                IdentificationField::VuSerialNumber,
+                vu_serial,
+                &block.vu_identification.vu_serial_number.to_serial_string(),
+            );
+        }
+        // Earlier calibrations may name a vehicle the VU was installed in before
+        let latest = block
+            .vu_calibration_data
+            .vu_calibration_records
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, record)| (record.new_time_value.as_ref().map(|t| t.0), *index))
+            .map(|(_, record)| record);
+        let Some(latest) = latest else {
+            continue;
+        };
+        if let Some(found) = &latest.vehicle_identification_number {
+            conflict(
+                IdentificationField::VehicleIdentificationNumber,
+                vin,
+                found.0 .0.trim(),
+            );
+        }
+        if let Some(found) = &latest.vehicle_registration_identification {
+            conflict(
+                IdentificationField::VehicleRegistration,
+                &registration,
+                &vehicle_registration_label(found),
+            );
+        }
+    }
+    warnings
+}
+
+impl VuData {
+    /// Cross-checks of the identification a Gen1 download repeats across its blocks: the VIN and
+    /// VRN of the overview against the latest calibration of each technical data block, and the
+    /// VU serial number between technical data blocks. Gen2 downloads are not checked yet.
+    pub fn consistency_report(&self) -> ConsistencyReport {
+        let mut report = ConsistencyReport::default();
+        if let VuData::Gen1(blocks) = self {
+            report.warnings = gen1_identification_conflicts(blocks);
+        }
+        report
+    }
+
+    /// Data quality of the file from its diagnostics, its time adjustments beyond the default
+    /// [`TimeAdjustmentConfig`] and the [`Self::consistency_report`], see [`QualityScore`].
+    pub fn quality_score(&self) -> QualityScore {
+        let mut penalties = self.diagnostics().quality_penalties();
+        let audit = self.time_adjustment_audit(&TimeAdjustmentConfig::default());
+        let time_anomalies = audit
+            .adjustments
+            .iter()
+            .filter(|adjustment| adjustment.exceeds_threshold)
+            .count()
+            + audit.exceeds_cumulative_threshold as usize;
+        penalties.push(QualityPenalty::per_item(
+            QualityIssue::TimeAnomalies,
+            time_anomalies,
+            10,
+            30,
+        ));
+        penalties.push(QualityPenalty::per_item(
+            QualityIssue::ConsistencyWarnings,
+            self.consistency_report().warnings.len(),
+            10,
+            20,
+        ));
+        QualityScore::from_penalties(penalties)
+    }
+}
+
+impl CardData {
+    /// Data quality of the file from a truncated EF and the [`Self::consistency_report`], see
+    /// [`QualityScore`]. Unknown EFs are kept raw and cost no points.
+    pub fn quality_score(&self) -> QualityScore {
+        QualityScore::from_penalties(vec![
+            QualityPenalty::per_item(
+                QualityIssue::Truncation,
+                self.diagnostics().truncated_ef.iter().count(),
+                25,
+                25,
+            ),
+            QualityPenalty::per_item(
+                QualityIssue::ConsistencyWarnings,
+                self.consistency_report().warnings.len(),
+                10,
+                20,
+            ),
+        ])
+    }
+}
+
+impl CardData {
+    /// Consistency checks over the latest generation's EFs, see [`ConsistencyWarning`].
+    pub fn consistency_report(&self) -> ConsistencyReport {
+        let (current_usage, card_download) = match self {
+            CardData::Gen1 { gen1_blocks, .. } => {
+                (&gen1_blocks.current_usage, &gen1_blocks.card_download)
+            }
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                (&gen2_blocks.current_usage, &gen2_blocks.card_download)
+            }
+        };
+        let last_download = card_download
+            .as_ref()
+            .and_then(|download| download.last_card_download.as_ref())
+            .map(|time| time.0);
+        let mut report = ConsistencyReport::default();
+        if let Some(current_usage) = current_usage {
+            report.warnings.extend(detect_open_session(
+                &current_usage.card_current_use,
+                self.card_driver_activity(),
+                last_download,
+            ));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::{change, day, registration};
+    use crate::card_parser::CardChipIdentity;
+    use crate::diagnostics::QualityIssue;
+    use crate::dt::{
+        ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, CardActivityChangeInfo,
+        CardCurrentUse, CardDriverActivity, TimeReal,
+    };
+    use crate::vu_parser::VuParser;
+
+    /// A Gen1 technical data block holding a single activation in the vehicle `vin`/`vrn`.
+    fn technical_block(vu_serial: u32, vin: &[u8; 17], vrn: &[u8; 13]) -> Vec<u8> {
+        let text = || {
+            let mut bytes = vec![0x01];
+            bytes.extend_from_slice(&[b' '; 35]);
+            bytes
+        };
+        let time = 0x5C00_0000u32.to_be_bytes();
+        let mut block = vec![0x76, 0x05];
+        // VuIdentification
+        block.extend(text());
+        block.extend(text());
+        block.extend_from_slice(&[b'0'; 16]);
+        block.extend_from_slice(&vu_serial.to_be_bytes());
+        block.extend_from_slice(&[0x03, 0x19, 0x01, 0x10]);
+        block.extend_from_slice(b"0100");
+        block.extend_from_slice(&time);
+        block.extend_from_slice(&time);
+        block.extend_from_slice(&[b'0'; 8]);
+        // SensorPaired
+        block.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0x03, 0x19, 0x01, 0x10]);
+        block.extend_from_slice(&[b'0'; 8]);
+        block.extend_from_slice(&time);
+        // VuCalibrationData
+        block.extend_from_slice(&[0x01, 0x01]);
+        block.extend(text());
+        block.extend(text());
+        block.extend_from_slice(&[0x02, 0x0D]);
+        block.extend_from_slice(b"DF00000123456700");
+        block.extend_from_slice(&time);
+        block.extend_from_slice(vin);
+        block.extend_from_slice(&[0x0D, 0x01]);
+        block.extend_from_slice(vrn);
+        block.extend_from_slice(&[0x0F, 0xA0, 0x0F, 0xA0, 0x0C, 0x80]);
+        block.extend_from_slice(b"315/80 R22.5   ");
+        block.push(90);
+        block.extend_from_slice(&[0x00; 6]);
+        for _ in 0..3 {
+            block.extend_from_slice(&time);
+        }
+        block.extend_from_slice(&[0x00; 128]);
+        block
+    }
+
+    #[test]
+    fn test_vu_identification_conflicts() {
+        let overview: &[u8] = include_bytes!("../../tests/fixtures/vu_gen1_overview.ddd");
+        let mut input = overview.to_vec();
+        input.extend(technical_block(1, b"WDB9634031L123456", b"B TA 1234    "));
+        let vu_data = VuParser::new_from_bytes(&input).unwrap().parse().unwrap();
+        assert!(vu_data.consistency_report().is_empty());
+
+        // the same VU reporting another board and vehicle in a later download
+        input.extend(technical_block(2, b"WDB9634031L654321", b"M XY 9876    "));
+        let vu_data = VuParser::new_from_bytes(&input).unwrap().parse().unwrap();
+        let conflicts: Vec<(IdentificationField, String, String)> = vu_data
+            .consistency_report()
+            .warnings
+            .into_iter()
+            .map(|warning| match warning {
+                ConsistencyWarning::IdentificationConflict {
+                    field,
+                    expected,
+                    found,
+                } => (field, expected, found),
+                warning => panic!("Unexpected warning {:?}", warning),
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            vec![
+                (
+                    IdentificationField::VuSerialNumber,
+                    "1 03/19 Actia S.A.".to_string(),
+                    "2 03/19 Actia S.A.".to_string()
+                ),
+                (
+                    IdentificationField::VehicleIdentificationNumber,
+                    "WDB9634031L123456".to_string(),
+                    "WDB9634031L654321".to_string()
+                ),
+                (
+                    IdentificationField::VehicleRegistration,
+                    "Germany B TA 1234".to_string(),
+                    "Germany M XY 9876".to_string()
+                ),
+            ]
+        );
+        assert!(vu_data
+            .quality_score()
+            .penalties
+            .iter()
+            .any(|penalty| penalty.issue == QualityIssue::ConsistencyWarnings
+                && penalty.points == 20));
+    }
+
+    #[test]
+    fn test_detect_open_session() {
+        use ActivityChangeInfoCardActivity::*;
+        let current_use = CardCurrentUse {
+            session_open_time: TimeReal("2024-03-01T08:00:00Z".parse().unwrap()),
+            session_open_vehicle: registration("B123ABC"),
+        };
+        let activity = |changes: Vec<CardActivityChangeInfo>| CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: vec![day("2024-03-01", 120, changes)],
+        };
+        let open_changes = vec![
+            change(0, BreakRest),
+            change(480, Driving),
+            change(600, Work),
+        ];
+        let open = activity(open_changes.clone());
+        let downloaded = Some("2024-03-01T11:00:00Z".parse().unwrap());
+        let warning = detect_open_session(&current_use, &open, downloaded).unwrap();
+        let ConsistencyWarning::SessionOpenAtDownload { last_activity, .. } = &warning else {
+            unreachable!()
+        };
+        assert_eq!(
+            *last_activity,
+            Some("2024-03-01T10:00:00Z".parse().unwrap())
+        );
+        // download time unknown, the last activity is within the session
+        assert!(detect_open_session(&current_use, &open, None).is_some());
+        // downloaded before the session opened
+        let earlier = Some("2024-03-01T07:00:00Z".parse().unwrap());
+        assert!(detect_open_session(&current_use, &open, earlier).is_none());
+
+        let mut closed_changes = open_changes;
+        closed_changes.push(CardActivityChangeInfo {
+            card_status: ActivityChangeInfoCardStatus::NotInserted,
+            ..change(660, BreakRest)
+        });
+        let closed = activity(closed_changes);
+        assert!(detect_open_session(&current_use, &closed, downloaded).is_none());
+    }
+
+    #[test]
+    fn test_find_duplicate_card_serials() {
+        let identity = |serial: &str, chip: &str, card_number: &str| CardChipIdentity {
+            extended_serial_number: serial.to_string(),
+            chip_identification: chip.to_string(),
+            card_number: Some(card_number.to_string()),
+        };
+        let identities = [
+            identity("1 03/19 Gemalto", "0011223344556677", "D0000000000001"),
+            // the same card downloaded again
+            identity("1 03/19 Gemalto", "0011223344556677", "D0000000000001"),
+            identity("2 03/19 Gemalto", "8899AABBCCDDEEFF", "D0000000000002"),
+            // same serial on another chip
+            identity("1 03/19 Gemalto", "FFFFFFFFFFFFFFFF", "D0000000000001"),
+        ];
+        let duplicates = find_duplicate_card_serials(&identities);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].extended_serial_number, "1 03/19 Gemalto");
+        assert_eq!(duplicates[0].indices, vec![0, 1, 3]);
+        assert_eq!(duplicates[0].chip_identifications.len(), 2);
+        assert_eq!(duplicates[0].card_numbers.len(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+//! Crew operation, paired from the periods of both slots of a VU.
+use super::timeline::{ActivityOrigin, ActivityPeriod};
+use crate::dt::{ActivityChangeInfoCardActivity, ActivityChangeInfoSlot};
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A stretch of crew operation in which neither slot changed activity.
+pub struct CrewSegment {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub end: DateTime<Utc>,
+    pub driver_activity: ActivityChangeInfoCardActivity,
+    pub co_driver_activity: ActivityChangeInfoCardActivity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An uninterrupted period in which cards were inserted in both slots of the VU.
+pub struct CrewSession {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub end: DateTime<Utc>,
+    /// What each slot was doing, in chronological order.
+    pub segments: Vec<CrewSegment>,
+}
+
+/// Pairs the driver and co-driver periods of a VU timeline into crew sessions.
+/// Only recorded periods count, so a session lasts while both cards are inserted.
+pub fn pair_crew_periods(timeline: &[ActivityPeriod]) -> Vec<CrewSession> {
+    let recorded = |slot: ActivityChangeInfoSlot| -> Vec<&ActivityPeriod> {
+        timeline
+            .iter()
+            .filter(|period| period.slot == slot && period.origin == ActivityOrigin::Recorded)
+            .collect()
+    };
+    let driver = recorded(ActivityChangeInfoSlot::Driver);
+    let co_driver = recorded(ActivityChangeInfoSlot::CoDriver);
+
+    let mut sessions: Vec<CrewSession> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < driver.len() && j < co_driver.len() {
+        let (driver_period, co_driver_period) = (driver[i], co_driver[j]);
+        let start = driver_period.start.max(co_driver_period.start);
+        let end = driver_period.end.min(co_driver_period.end);
+        if driver_period.end <= co_driver_period.end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+        if start >= end {
+            continue;
+        }
+        let segment = CrewSegment {
+            start,
+            end,
+            driver_activity: driver_period.activity.clone(),
+            co_driver_activity: co_driver_period.activity.clone(),
+        };
+        match sessions.last_mut() {
+            Some(session) if session.end == start => {
+                session.end = end;
+                match session.segments.last_mut() {
+                    Some(last)
+                        if last.driver_activity == segment.driver_activity
+                            && last.co_driver_activity == segment.co_driver_activity =>
+                    {
+                        last.end = end;
+                    }
+                    _ => session.segments.push(segment),
+                }
+            }
+            _ => sessions.push(CrewSession {
+                start,
+                end,
+                segments: vec![segment],
+            }),
+        }
+    }
+    sessions
+}
+
+impl VuData {
+    /// Periods of crew operation recorded by the VU, see [`pair_crew_periods`].
+    pub fn crew_sessions(&self) -> Vec<CrewSession> {
+        pair_crew_periods(&self.activity_timeline())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::change;
+    use crate::analysis::vu_activity_timeline;
+    use crate::dt::{
+        ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, ActivityChangeInfoSlot,
+        ActivityChangeInfoStatus, CardActivityChangeInfo,
+    };
+    use chrono::{DateTime, Duration, Utc};
+
+    #[test]
+    fn test_crew_sessions() {
+        use ActivityChangeInfoCardActivity::*;
+        let co_driver = |minutes, activity| CardActivityChangeInfo {
+            slot: ActivityChangeInfoSlot::CoDriver,
+            ..change(minutes, activity)
+        };
+        let withdrawn = |change: CardActivityChangeInfo| CardActivityChangeInfo {
+            card_status: ActivityChangeInfoCardStatus::NotInserted,
+            driving_or_following_activity_status: ActivityChangeInfoStatus::Unknown,
+            ..change
+        };
+        // the co-driver joins at 08:00, they swap at 12:00 and the driver leaves at 14:00
+        let changes = vec![
+            change(0, Work),
+            withdrawn(co_driver(0, BreakRest)),
+            change(8 * 60, Driving),
+            co_driver(8 * 60, BreakRest),
+            co_driver(10 * 60, BreakRest),
+            change(12 * 60, Availability),
+            co_driver(12 * 60, Driving),
+            withdrawn(change(14 * 60, BreakRest)),
+        ];
+        let day_start: DateTime<Utc> = "2024-03-01T00:00:00Z".parse().unwrap();
+        let timeline = vu_activity_timeline([(day_start, changes.as_slice())]);
+        let sessions = pair_crew_periods(&timeline);
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.start, day_start + Duration::hours(8));
+        assert_eq!(session.end, day_start + Duration::hours(14));
+        let segments: Vec<(
+            i64,
+            ActivityChangeInfoCardActivity,
+            ActivityChangeInfoCardActivity,
+        )> = session
+            .segments
+            .iter()
+            .map(|segment| {
+                (
+                    (segment.end - segment.start).num_hours(),
+                    segment.driver_activity.clone(),
+                    segment.co_driver_activity.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            segments,
+            vec![(4, Driving, BreakRest), (2, Availability, Driving)]
+        );
+    }
+}
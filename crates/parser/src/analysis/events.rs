@@ -0,0 +1,253 @@
+//! Events and faults of a driver across card and VU downloads.
+use super::clock::normalize_event_times;
+use super::merge::RecordOrigin;
+use crate::card_parser::CardData;
+use crate::dt::TimeReal;
+use crate::sink::{card_number_to_string, label, EventKind};
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An incident of a driver, recorded on their card, by the VUs they drove, or both, see
+/// [`driver_events`].
+pub struct DriverEvent {
+    pub kind: EventKind,
+    /// Serialized event or fault type, e.g. `PowerSupplyInterruption`.
+    pub event_type: String,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub begin_time: DateTime<Utc>,
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub end_time: Option<DateTime<Utc>>,
+    /// Whether the driver's card recorded the incident.
+    pub on_card: bool,
+    /// VINs of the VU downloads that recorded the incident.
+    pub vehicle_identification_numbers: Vec<String>,
+    /// Kinds and generations of the downloads that recorded the incident, each listed once.
+    pub origins: Vec<RecordOrigin>,
+}
+
+/// Events and faults of the driver holding `card_number`, from their card downloads and from the
+/// VU downloads that name the card in one of the slots, in one chronological list. The same
+/// incident recorded by several sources, matched by kind, type and begin time, is listed once.
+/// Only the latest generation of a card's data is read, and cards with another number are
+/// skipped. With a `period`, only incidents overlapping it are kept.
+pub fn driver_events<'a>(
+    card_number: &str,
+    period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    cards: impl IntoIterator<Item = &'a CardData>,
+    vus: impl IntoIterator<Item = &'a VuData>,
+) -> Vec<DriverEvent> {
+    let card_event = |origin, kind, event_type, begin_time, end_time: Option<&TimeReal>| {
+        let (begin_time, end_time) = normalize_event_times(begin_time, end_time.map(|t| t.0));
+        DriverEvent {
+            kind,
+            event_type,
+            begin_time,
+            end_time,
+            on_card: true,
+            vehicle_identification_numbers: Vec::new(),
+            origins: vec![origin],
+        }
+    };
+    let mut entries = Vec::new();
+    for card in cards {
+        let identification = match card {
+            CardData::Gen1 { gen1_blocks, .. } => &gen1_blocks.identification,
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                &gen2_blocks.identification
+            }
+        };
+        let number = card_number_to_string(&identification.card_identification.card_number);
+        if number.as_deref() != Some(card_number) {
+            continue;
+        }
+        let origin = RecordOrigin::of_card(card);
+        match card {
+            CardData::Gen1 { gen1_blocks, .. } => {
+                for record in gen1_blocks.events_data.records.iter().flatten() {
+                    entries.push(card_event(
+                        origin,
+                        EventKind::Event,
+                        label(&record.event_type),
+                        record.event_begin_time.0,
+                        record.event_end_time.as_ref(),
+                    ));
+                }
+                for record in gen1_blocks.faults_data.records.iter().flatten() {
+                    entries.push(card_event(
+                        origin,
+                        EventKind::Fault,
+                        label(&record.fault_type),
+                        record.fault_begin_time.0,
+                        record.fault_end_time.as_ref(),
+                    ));
+                }
+            }
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                for record in gen2_blocks.events_data.records() {
+                    entries.push(card_event(
+                        origin,
+                        EventKind::Event,
+                        label(&record.event_type),
+                        record.event_begin_time.0,
+                        record.event_end_time.as_ref(),
+                    ));
+                }
+                for record in gen2_blocks.faults_data.records.iter().flatten() {
+                    entries.push(card_event(
+                        origin,
+                        EventKind::Fault,
+                        label(&record.fault_type),
+                        record.fault_begin_time.0,
+                        record.fault_end_time.as_ref(),
+                    ));
+                }
+            }
+        }
+    }
+    for vu in vus {
+        let vin = vu
+            .vehicle_identification_number()
+            .map(|vin| vin.0 .0.clone())
+            .unwrap_or_default();
+        for event in vu.events() {
+            let slots = [
+                event.card_driver_slot_begin,
+                event.card_codriver_slot_begin,
+                event.card_driver_slot_end,
+                event.card_codriver_slot_end,
+            ];
+            let names_driver = slots.iter().flatten().any(|card| {
+                card_number_to_string(card.card_number).as_deref() == Some(card_number)
+            });
+            if names_driver {
+                let (begin_time, end_time) =
+                    normalize_event_times(event.begin_time, event.end_time);
+                entries.push(DriverEvent {
+                    kind: event.kind,
+                    event_type: event.event_type,
+                    begin_time,
+                    end_time,
+                    on_card: false,
+                    vehicle_identification_numbers: vec![vin.clone()],
+                    origins: vec![RecordOrigin::of_vu(vu)],
+                });
+            }
+        }
+    }
+    merge_driver_events(entries, period)
+}
+
+/// Deduplicates and orders the events of [`driver_events`].
+fn merge_driver_events(
+    entries: Vec<DriverEvent>,
+    period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+) -> Vec<DriverEvent> {
+    let mut events: Vec<DriverEvent> = Vec::new();
+    for entry in entries {
+        let overlaps = period.is_none_or(|(start, end)| {
+            entry.begin_time <= end && entry.end_time.unwrap_or(entry.begin_time) >= start
+        });
+        if !overlaps {
+            continue;
+        }
+        let existing = events.iter_mut().find(|event| {
+            event.kind == entry.kind
+                && event.event_type == entry.event_type
+                && event.begin_time == entry.begin_time
+        });
+        match existing {
+            Some(event) => {
+                event.on_card |= entry.on_card;
+                event.end_time = event.end_time.or(entry.end_time);
+                for vin in entry.vehicle_identification_numbers {
+                    if !event.vehicle_identification_numbers.contains(&vin) {
+                        event.vehicle_identification_numbers.push(vin);
+                    }
+                }
+                for origin in entry.origins {
+                    if !event.origins.contains(&origin) {
+                        event.origins.push(origin);
+                    }
+                }
+            }
+            None => events.push(entry),
+        }
+    }
+    events.sort_by_key(|event| event.begin_time);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{DataSource, RecordOrigin};
+    use crate::capabilities::Generation;
+    use crate::sink::EventKind;
+    use chrono::{DateTime, Duration, Utc};
+
+    #[test]
+    fn test_merge_driver_events() {
+        let card = RecordOrigin {
+            source: DataSource::CardFile,
+            generation: Generation::Gen1,
+        };
+        let vu = RecordOrigin {
+            source: DataSource::VuFile,
+            generation: Generation::Gen2,
+        };
+        let event = |begin: &str, on_card: bool, vins: &[&str]| DriverEvent {
+            kind: EventKind::Event,
+            event_type: "PowerSupplyInterruption".to_string(),
+            begin_time: begin.parse().unwrap(),
+            end_time: on_card.then(|| begin.parse::<DateTime<Utc>>().unwrap() + Duration::hours(1)),
+            on_card,
+            vehicle_identification_numbers: vins.iter().map(|vin| vin.to_string()).collect(),
+            origins: vec![if on_card { card } else { vu }],
+        };
+        let fault = DriverEvent {
+            kind: EventKind::Fault,
+            ..event("2024-03-02T08:00:00Z", true, &[])
+        };
+        let events = merge_driver_events(
+            vec![
+                event("2024-03-02T08:00:00Z", false, &["VIN1"]),
+                event("2024-03-01T08:00:00Z", true, &[]),
+                // the same incident from the card and a second download of the same VU
+                event("2024-03-02T08:00:00Z", true, &[]),
+                event("2024-03-02T08:00:00Z", false, &["VIN1"]),
+                fault,
+                event("2024-02-01T08:00:00Z", true, &[]),
+            ],
+            Some((
+                "2024-03-01T00:00:00Z".parse().unwrap(),
+                "2024-03-31T00:00:00Z".parse().unwrap(),
+            )),
+        );
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0].begin_time,
+            "2024-03-01T08:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        let merged = events
+            .iter()
+            .find(|event| {
+                event.kind == EventKind::Event && !event.vehicle_identification_numbers.is_empty()
+            })
+            .unwrap();
+        assert!(merged.on_card);
+        assert_eq!(
+            merged.vehicle_identification_numbers,
+            vec!["VIN1".to_string()]
+        );
+        assert!(merged.end_time.is_some());
+        assert_eq!(merged.origins, vec![vu, card]);
+    }
+}
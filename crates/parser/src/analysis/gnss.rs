@@ -0,0 +1,244 @@
+//! GNSS positions and implausible moves between them.
+use crate::card_parser::CardData;
+use crate::dt::{gen2, OdometerShort, TimeReal};
+use crate::units::Kilometers;
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+/// Speed above which the move between two GNSS positions is taken as impossible, see
+/// [`detect_teleports`].
+pub const MAX_PLAUSIBLE_SPEED_KMH: f64 = 150.0;
+
+/// Moves shorter than this are left to the GNSS accuracy and never flagged, however close in
+/// time the positions are.
+const MIN_TELEPORT_DISTANCE_KM: f64 = 1.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A GNSS position with the odometer recorded next to it, from a place or accumulated driving
+/// record.
+pub struct GnssFix {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub time: DateTime<Utc>,
+    /// Decimal degrees.
+    pub latitude: f64,
+    pub longitude: f64,
+    pub odometer: Kilometers,
+}
+impl GnssFix {
+    /// `None` when the position is unknown.
+    fn new(
+        time: &TimeReal,
+        coordinates: &gen2::GeoCoordinatesGen2,
+        odometer: &OdometerShort,
+    ) -> Option<Self> {
+        Some(GnssFix {
+            time: time.0,
+            latitude: coordinates.latitude?,
+            longitude: coordinates.longitude?,
+            odometer: odometer.0,
+        })
+    }
+
+    /// Great-circle distance in km.
+    pub fn distance_km(&self, other: &GnssFix) -> f64 {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+        let (latitude, other_latitude) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let half_latitude = (other_latitude - latitude) / 2.0;
+        let half_longitude = (other.longitude - self.longitude).to_radians() / 2.0;
+        let a = half_latitude.sin().powi(2)
+            + latitude.cos() * other_latitude.cos() * half_longitude.sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Two consecutive GNSS positions too far apart for the time between them, which points to a
+/// manipulated position or a wrong clock.
+pub struct Teleport {
+    pub from: GnssFix,
+    pub to: GnssFix,
+    pub distance_km: f64,
+    /// Speed needed to cover `distance_km` in the time between the positions.
+    pub implied_speed_kmh: f64,
+    /// Distance on the odometer between the positions, a straight line longer than this is
+    /// impossible too.
+    pub odometer_km: i64,
+}
+
+/// Flags consecutive GNSS positions, in chronological order, whose implied speed exceeds
+/// `max_speed_kmh`, e.g. [`MAX_PLAUSIBLE_SPEED_KMH`]. Positions recorded in the same second
+/// count as one second apart.
+pub fn detect_teleports(fixes: &[GnssFix], max_speed_kmh: f64) -> Vec<Teleport> {
+    let mut fixes = fixes.to_vec();
+    fixes.sort_by_key(|fix| fix.time);
+    fixes
+        .windows(2)
+        .filter_map(|pair| {
+            let (from, to) = (pair[0], pair[1]);
+            let distance_km = from.distance_km(&to);
+            let hours = (to.time - from.time).num_seconds().max(1) as f64 / 3600.0;
+            let implied_speed_kmh = distance_km / hours;
+            (distance_km > MIN_TELEPORT_DISTANCE_KM && implied_speed_kmh > max_speed_kmh).then(
+                || Teleport {
+                    from,
+                    to,
+                    distance_km,
+                    implied_speed_kmh,
+                    odometer_km: to.odometer.0 as i64 - from.odometer.0 as i64,
+                },
+            )
+        })
+        .collect()
+}
+
+impl CardData {
+    /// Known GNSS positions of the place and accumulated driving records, in record order.
+    /// Gen1 cards record no positions.
+    pub fn gnss_fixes(&self) -> Vec<GnssFix> {
+        match self {
+            CardData::Gen1 { .. } => Vec::new(),
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                let places = gen2_blocks
+                    .places
+                    .place_records
+                    .iter()
+                    .filter_map(|record| {
+                        let place = &record.entry_gnss_place_record;
+                        GnssFix::new(
+                            &place.time_stamp,
+                            &place.geo_coordinates,
+                            &record.vehicle_odometer_value,
+                        )
+                    });
+                let accumulated_driving = gen2_blocks
+                    .gnss_accumulated_driving
+                    .gnss_accumulated_driving_records
+                    .iter()
+                    .filter_map(|record| {
+                        let place = &record.gnss_place_record;
+                        GnssFix::new(
+                            &place.time_stamp,
+                            &place.geo_coordinates,
+                            &record.vehicle_odometer_value,
+                        )
+                    });
+                places.chain(accumulated_driving).collect()
+            }
+        }
+    }
+
+    /// Impossible moves between the card's GNSS positions, see [`detect_teleports`].
+    pub fn teleports(&self, max_speed_kmh: f64) -> Vec<Teleport> {
+        detect_teleports(&self.gnss_fixes(), max_speed_kmh)
+    }
+}
+
+impl VuData {
+    /// Known GNSS positions of the place and accumulated driving records, in record order.
+    /// Gen1 VUs record no positions.
+    pub fn gnss_fixes(&self) -> Vec<GnssFix> {
+        match self {
+            VuData::Gen1(_) => Vec::new(),
+            VuData::Gen2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .flat_map(|block| {
+                    let places = block
+                        .vu_place_daily_work_period_record_array
+                        .iter()
+                        .filter_map(|record| {
+                            let record = &record.place_record;
+                            let place = &record.entry_gnss_place_record;
+                            GnssFix::new(
+                                &place.time_stamp,
+                                &place.geo_coordinates,
+                                &record.vehicle_odometer_value,
+                            )
+                        });
+                    let accumulated_driving =
+                        block.vu_gnss_ad_record_array.iter().filter_map(|record| {
+                            let place = &record.gnss_place_record;
+                            GnssFix::new(
+                                &place.time_stamp,
+                                &place.geo_coordinates,
+                                &record.vehicle_odometer_value,
+                            )
+                        });
+                    places.chain(accumulated_driving)
+                })
+                .collect(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .flat_map(|block| {
+                    let places = block
+                        .vu_place_daily_work_period_record_array
+                        .iter()
+                        .filter_map(|record| {
+                            let record = &record.place_record;
+                            let place = &record.entry_gnss_place_auth_record;
+                            GnssFix::new(
+                                &place.time_stamp,
+                                &place.gnss_coordinates,
+                                &record.vehicle_odometer_value,
+                            )
+                        });
+                    let accumulated_driving =
+                        block.vu_gnss_ad_record_array.iter().filter_map(|record| {
+                            let place = &record.gnss_place_auth_record;
+                            GnssFix::new(
+                                &place.time_stamp,
+                                &place.gnss_coordinates,
+                                &record.vehicle_odometer_value,
+                            )
+                        });
+                    places.chain(accumulated_driving)
+                })
+                .collect(),
+        }
+    }
+
+    /// Impossible moves between the VU's GNSS positions, see [`detect_teleports`].
+    pub fn teleports(&self, max_speed_kmh: f64) -> Vec<Teleport> {
+        detect_teleports(&self.gnss_fixes(), max_speed_kmh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Kilometers;
+
+    #[test]
+    fn test_detect_teleports() {
+        let fix = |time: &str, latitude: f64, longitude: f64, odometer: u32| GnssFix {
+            time: time.parse().unwrap(),
+            latitude,
+            longitude,
+            odometer: Kilometers(odometer),
+        };
+        let fixes = [
+            // Antwerp to Paris, about 300 km in half an hour
+            fix("2024-03-01T09:30:00Z", 48.8566, 2.3522, 1050),
+            fix("2024-03-01T08:00:00Z", 50.8503, 4.3517, 1000),
+            fix("2024-03-01T09:00:00Z", 51.2194, 4.4025, 1045),
+            // within the GNSS accuracy of the previous position
+            fix("2024-03-01T09:30:00Z", 48.8600, 2.3522, 1050),
+        ];
+        assert!((fixes[1].distance_km(&fixes[2]) - 41.2).abs() < 0.5);
+        let teleports = detect_teleports(&fixes, MAX_PLAUSIBLE_SPEED_KMH);
+        assert_eq!(teleports.len(), 1);
+        assert_eq!(teleports[0].from, fixes[2]);
+        assert_eq!(teleports[0].odometer_km, 5);
+        assert!(teleports[0].implied_speed_kmh > 500.0);
+        assert!(detect_teleports(&fixes, 1000.0).is_empty());
+    }
+}
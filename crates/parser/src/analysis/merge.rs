@@ -0,0 +1,207 @@
+//! Merging of the daily records of several downloads of the same card.
+use crate::capabilities::Generation;
+use crate::card_parser::CardData;
+use crate::dt::CardActivityDailyRecord;
+use crate::sink::card_number_to_string;
+use crate::vu_parser::VuData;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Kind of download a record was read from.
+pub enum DataSource {
+    CardFile,
+    VuFile,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// The download a record of a merge was read from, to tell apart what each generation of cards
+/// and VUs recorded in mixed fleets.
+pub struct RecordOrigin {
+    pub source: DataSource,
+    pub generation: Generation,
+}
+impl RecordOrigin {
+    pub fn of_card(card: &CardData) -> Self {
+        let generation = match card {
+            CardData::Gen1 { .. } => Generation::Gen1,
+            CardData::Gen2 { .. } => Generation::Gen2,
+            CardData::Gen2V2 { .. } => Generation::Gen2V2,
+        };
+        RecordOrigin {
+            source: DataSource::CardFile,
+            generation,
+        }
+    }
+
+    pub fn of_vu(vu: &VuData) -> Self {
+        let generation = match vu {
+            VuData::Gen1(_) => Generation::Gen1,
+            VuData::Gen2(_) => Generation::Gen2,
+            VuData::Gen2V2(_) => Generation::Gen2V2,
+        };
+        RecordOrigin {
+            source: DataSource::VuFile,
+            generation,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// The version of a day kept by [`merge_card_days`], with the downloads holding that day.
+pub struct MergedCardDay {
+    pub record: CardActivityDailyRecord,
+    /// Position in the input of the download the record was taken from.
+    pub source: usize,
+    /// Card generation of the download the record was taken from.
+    pub origin: RecordOrigin,
+    /// Positions of the other downloads holding the same day.
+    pub other_sources: Vec<usize>,
+    /// Whether the downloads hold different versions of the day, e.g. because one of them was
+    /// made before the day ended.
+    pub conflicting: bool,
+}
+
+/// Daily activity records of several downloads of the same card, one per day in chronological
+/// order, each from the latest generation's activity data. When downloads hold different
+/// versions of a day, the most complete one is kept: the highest daily presence counter, then
+/// the longest record, then the most activity changes, then the latest download in the input.
+/// Fails if the downloads are not of the same card.
+pub fn merge_card_days<'a>(
+    downloads: impl IntoIterator<Item = &'a CardData>,
+) -> Result<Vec<MergedCardDay>> {
+    let mut card_number: Option<Option<String>> = None;
+    let mut days = Vec::new();
+    for card in downloads {
+        let identification = match card {
+            CardData::Gen1 { gen1_blocks, .. } => &gen1_blocks.identification,
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                &gen2_blocks.identification
+            }
+        };
+        let number = card_number_to_string(&identification.card_identification.card_number);
+        match &card_number {
+            Some(first) if *first != number => anyhow::bail!(
+                "Cannot merge downloads of different cards: {:?} and {:?}",
+                first,
+                number
+            ),
+            _ => card_number = Some(number),
+        }
+        days.push((
+            RecordOrigin::of_card(card),
+            card.card_driver_activity()
+                .activity_daily_records
+                .as_slice(),
+        ));
+    }
+    Ok(merge_daily_records(&days))
+}
+
+/// Resolves the days of [`merge_card_days`], given the origin and daily records of each
+/// download.
+fn merge_daily_records(
+    downloads: &[(RecordOrigin, &[CardActivityDailyRecord])],
+) -> Vec<MergedCardDay> {
+    let completeness = |record: &CardActivityDailyRecord| {
+        (
+            record.activity_daily_presence_counter.0,
+            record.activity_record_length,
+            record.activity_change_info.len(),
+        )
+    };
+    let mut days: BTreeMap<DateTime<Utc>, MergedCardDay> = BTreeMap::new();
+    for (source, (origin, records)) in downloads.iter().enumerate() {
+        for record in records.iter() {
+            let Some(day) = days.get_mut(&record.activity_record_date.0) else {
+                days.insert(
+                    record.activity_record_date.0,
+                    MergedCardDay {
+                        record: record.clone(),
+                        source,
+                        origin: *origin,
+                        other_sources: Vec::new(),
+                        conflicting: false,
+                    },
+                );
+                continue;
+            };
+            day.conflicting |= day.record != *record;
+            if completeness(record) >= completeness(&day.record) {
+                day.other_sources.push(day.source);
+                day.record = record.clone();
+                day.source = source;
+                day.origin = *origin;
+            } else {
+                day.other_sources.push(source);
+            }
+        }
+    }
+    days.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::{change, day};
+    use crate::capabilities::Generation;
+    use crate::dt::{
+        ActivityChangeInfoCardActivity, CardActivityDailyRecord, DailyPresenceCounter,
+    };
+
+    #[test]
+    fn test_merge_daily_records() {
+        use ActivityChangeInfoCardActivity::*;
+        let with_length = |mut record: CardActivityDailyRecord, presence_counter: u16| {
+            record.activity_record_length = 12 + 2 * record.activity_change_info.len() as u16;
+            record.activity_daily_presence_counter = DailyPresenceCounter(presence_counter);
+            record
+        };
+        // the second download was made during the second day, before the later changes
+        let first = [
+            with_length(day("2024-03-01", 0, vec![change(0, BreakRest)]), 7),
+            with_length(
+                day(
+                    "2024-03-02",
+                    0,
+                    vec![change(0, BreakRest), change(480, Driving)],
+                ),
+                8,
+            ),
+        ];
+        let second = [
+            with_length(day("2024-03-02", 0, vec![change(0, BreakRest)]), 8),
+            with_length(day("2024-03-03", 0, vec![change(0, Work)]), 9),
+        ];
+        let card = |generation| RecordOrigin {
+            source: DataSource::CardFile,
+            generation,
+        };
+        let (gen2, gen1) = (card(Generation::Gen2), card(Generation::Gen1));
+        let days = merge_daily_records(&[(gen2, &second), (gen1, &first)]);
+        assert_eq!(
+            days.iter()
+                .map(|day| (day.source, day.other_sources.clone(), day.conflicting))
+                .collect::<Vec<_>>(),
+            vec![(1, vec![], false), (1, vec![0], true), (0, vec![], false)]
+        );
+        assert_eq!(days[1].record, first[1]);
+        assert_eq!(
+            days.iter().map(|day| day.origin).collect::<Vec<_>>(),
+            vec![gen1, gen1, gen2]
+        );
+
+        // the same version in both downloads, the later one is named as source
+        let days = merge_daily_records(&[(gen1, &first), (gen1, &first)]);
+        assert!(days.iter().all(|day| day.source == 1 && !day.conflicting));
+    }
+}
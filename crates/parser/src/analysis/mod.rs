@@ -0,0 +1,75 @@
+//! Analyses over parsed card and VU files, one submodule per topic, all re-exported here.
+mod calibration;
+mod clock;
+mod consistency;
+mod crew;
+mod events;
+mod gnss;
+mod merge;
+mod reconciliation;
+mod speed;
+mod timeline;
+mod working_time;
+
+pub use calibration::*;
+pub use clock::*;
+pub use consistency::*;
+pub use crew::*;
+pub use events::*;
+pub use gnss::*;
+pub use merge::*;
+pub use reconciliation::*;
+pub use speed::*;
+pub use timeline::*;
+pub use working_time::*;
+
+/// Records shared by the tests of the submodules.
+#[cfg(test)]
+mod test_helpers {
+    use crate::dt::external::NationNumeric;
+    use crate::dt::{
+        ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, ActivityChangeInfoSlot,
+        ActivityChangeInfoStatus, CardActivityChangeInfo, CardActivityDailyRecord,
+        DailyPresenceCounter, Distance, IA5String, TimeReal, VehicleRegistrationIdentification,
+        VehicleRegistrationNumber,
+    };
+    use crate::units::{Kilometers, Minutes};
+
+    pub(super) fn change(
+        minutes: u16,
+        activity: ActivityChangeInfoCardActivity,
+    ) -> CardActivityChangeInfo {
+        CardActivityChangeInfo {
+            slot: ActivityChangeInfoSlot::Driver,
+            driving_or_following_activity_status: ActivityChangeInfoStatus::Single,
+            card_status: ActivityChangeInfoCardStatus::Inserted,
+            activity,
+            minutes: Minutes(minutes),
+        }
+    }
+
+    pub(super) fn day(
+        date: &str,
+        distance: u16,
+        changes: Vec<CardActivityChangeInfo>,
+    ) -> CardActivityDailyRecord {
+        CardActivityDailyRecord {
+            activity_previous_record_length: 0,
+            activity_record_length: 0,
+            activity_record_date: TimeReal(format!("{}T00:00:00Z", date).parse().unwrap()),
+            activity_daily_presence_counter: DailyPresenceCounter(0),
+            activity_day_distance: Distance(Kilometers(distance as u32)),
+            activity_change_info: changes,
+        }
+    }
+
+    pub(super) fn registration(vrn: &str) -> VehicleRegistrationIdentification {
+        VehicleRegistrationIdentification {
+            vehicle_registration_nation: NationNumeric("Romania".to_string()),
+            vehicle_registration_number: VehicleRegistrationNumber {
+                code_page: 1,
+                vehicle_reg_number: IA5String(vrn.to_string()),
+            },
+        }
+    }
+}
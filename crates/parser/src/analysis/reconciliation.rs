@@ -0,0 +1,465 @@
+//! Distance cross-checks between odometers, detailed speed and the vehicles a card was used in.
+use super::speed::SpeedTrace;
+use crate::capabilities::Generation;
+use crate::card_parser::CardData;
+use crate::dt::{gen1, gen2};
+use crate::units::Kilometers;
+use crate::vu_parser::VuData;
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A vehicle use from a card, with the DF and EF it was read from, see [`CardData::vehicles_used`].
+pub struct VehicleUsed {
+    pub generation: Generation,
+    pub source_ef: String,
+    pub vehicle_registration_nation: String,
+    pub vehicle_registration_number: String,
+    /// Only recorded in the Gen2 DF.
+    pub vehicle_identification_number: Option<String>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub first_use: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub last_use: DateTime<Utc>,
+    pub odometer_begin: Kilometers,
+    pub odometer_end: Kilometers,
+}
+
+/// Merges the vehicle records of the Tachograph and Tachograph_G2 DFs, oldest first.
+/// A vehicle use recorded in both DFs, matched by VRN, first and last use, is kept once, from
+/// the Gen2 DF since it carries the VIN.
+pub fn merge_vehicles_used(
+    gen1_records: &[gen1::CardVehicleRecord],
+    gen2_records: &[gen2::CardVehicleRecordGen2],
+) -> Vec<VehicleUsed> {
+    let mut vehicles: Vec<VehicleUsed> = Vec::new();
+    for record in gen2_records {
+        vehicles.push(VehicleUsed {
+            generation: Generation::Gen2,
+            source_ef: "Tachograph_G2/VehiclesUsed".to_string(),
+            vehicle_registration_nation: record
+                .vehicle_registration
+                .vehicle_registration_nation
+                .0
+                .clone(),
+            vehicle_registration_number: record
+                .vehicle_registration
+                .vehicle_registration_number
+                .vehicle_reg_number
+                .0
+                .clone(),
+            vehicle_identification_number: Some(record.vehicle_identification_number.0 .0.clone()),
+            first_use: record.vehicle_first_use.0,
+            last_use: record.vehicle_last_use.0,
+            odometer_begin: record.vehicle_odometer_begin.0,
+            odometer_end: record.vehicle_odometer_end.0,
+        });
+    }
+    for record in gen1_records {
+        let vehicle = VehicleUsed {
+            generation: Generation::Gen1,
+            source_ef: "Tachograph/VehiclesUsed".to_string(),
+            vehicle_registration_nation: record
+                .vehicle_registration
+                .vehicle_registration_nation
+                .0
+                .clone(),
+            vehicle_registration_number: record
+                .vehicle_registration
+                .vehicle_registration_number
+                .vehicle_reg_number
+                .0
+                .clone(),
+            vehicle_identification_number: None,
+            first_use: record.vehicle_first_use.0,
+            last_use: record.vehicle_last_use.0,
+            odometer_begin: record.vehicle_odometer_begin.0,
+            odometer_end: record.vehicle_odometer_end.0,
+        };
+        let duplicate = vehicles.iter().any(|existing| {
+            existing.vehicle_registration_number == vehicle.vehicle_registration_number
+                && existing.first_use == vehicle.first_use
+                && existing.last_use == vehicle.last_use
+        });
+        if !duplicate {
+            vehicles.push(vehicle);
+        }
+    }
+    vehicles.sort_by_key(|vehicle| vehicle.first_use);
+    vehicles
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Distance between two odometer readings next to the distance integrated from detailed speed
+/// over the same period, see [`cross_check_distance`].
+pub struct DistanceCheck {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub end: DateTime<Utc>,
+    /// Negative when the odometer went backwards.
+    pub odometer_km: i64,
+    pub speed_km: f64,
+    /// Whether the two distances differ by more than the tolerance.
+    pub discrepancy: bool,
+}
+
+/// Compares the odometer delta between consecutive readings with the distance driven according
+/// to the speed traces, a common tamper indicator.
+///
+/// The VU records detailed speed only while the vehicle moves, so seconds without speed data
+/// count as stationary. Periods starting before the first recorded second are skipped, since the
+/// speed data for them is no longer held. A period is flagged when the distances differ by more
+/// than `tolerance` (a fraction of the odometer distance, e.g. 0.05) plus 1 km for the odometer
+/// resolution.
+pub fn cross_check_distance(
+    traces: &[SpeedTrace],
+    odometer_readings: &[(DateTime<Utc>, Kilometers)],
+    tolerance: f64,
+) -> Vec<DistanceCheck> {
+    let Some(first_recorded) = traces.iter().map(|trace| trace.start).min() else {
+        return Vec::new();
+    };
+    let mut readings = odometer_readings.to_vec();
+    readings.sort_by_key(|(time, _)| *time);
+    readings
+        .windows(2)
+        .filter(|pair| pair[0].0 >= first_recorded)
+        .map(|pair| {
+            let ((start, odometer_start), (end, odometer_end)) = (pair[0], pair[1]);
+            let odometer_km = odometer_end.0 as i64 - odometer_start.0 as i64;
+            let speed_km: f64 = traces
+                .iter()
+                .map(|trace| trace.distance_km(start, end))
+                .sum();
+            let allowed = odometer_km.abs() as f64 * tolerance + 1.0;
+            DistanceCheck {
+                start,
+                end,
+                odometer_km,
+                speed_km,
+                discrepancy: (odometer_km as f64 - speed_km).abs() > allowed,
+            }
+        })
+        .collect()
+}
+
+/// Odometer readings of a vehicle, see [`VuData::odometer_readings`].
+pub type OdometerReadings = Vec<(DateTime<Utc>, Kilometers)>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Distance of one day of a driver's card according to each source, see
+/// [`reconcile_distances`].
+pub struct DistanceReconciliation {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub date: NaiveDate,
+    /// Distance of the card's daily activity record.
+    pub card_activity_km: i64,
+    /// Odometer deltas of the card's vehicle uses of the day. `None` when a vehicle use spans
+    /// midnight, since its distance cannot be split between the days.
+    pub vehicles_used_km: Option<i64>,
+    /// Odometer deltas between the midnight readings of the VUs of the vehicles used that day.
+    /// `None` when there was no vehicle use, or a vehicle has no download covering the day.
+    pub vu_odometer_km: Option<i64>,
+    /// Whether the card and vehicle use distances differ by more than the tolerance, or the card
+    /// records more distance than the vehicles covered.
+    pub discrepancy: bool,
+}
+
+/// Compares, day by day, the distance of a card's daily activity records with the odometer
+/// deltas of its vehicle uses and with the midnight odometer readings of the VUs of those
+/// vehicles, a common fraud and consistency check.
+///
+/// `vu_readings` holds the readings of [`VuData::odometer_readings`] by vehicle registration
+/// number. A VU also records the distance driven with other cards or none, so it only flags the
+/// card recording more than the vehicles covered. Distances are compared with `tolerance` (a
+/// fraction of the distance, e.g. 0.05) plus 1 km for the odometer resolution, as in
+/// [`cross_check_distance`].
+pub fn reconcile_distances(
+    card_days: &[(NaiveDate, Kilometers)],
+    vehicles_used: &[VehicleUsed],
+    vu_readings: &[(String, OdometerReadings)],
+    tolerance: f64,
+) -> Vec<DistanceReconciliation> {
+    let allowed = |km: i64| km.abs() as f64 * tolerance + 1.0;
+    let mut days: Vec<DistanceReconciliation> = card_days
+        .iter()
+        .map(|&(date, distance)| {
+            let day_start = date.and_time(NaiveTime::MIN).and_utc();
+            let day_end = day_start + Duration::days(1);
+            let uses: Vec<&VehicleUsed> = vehicles_used
+                .iter()
+                .filter(|vehicle| vehicle.first_use < day_end && vehicle.last_use >= day_start)
+                .collect();
+            let vehicles_used_km = uses
+                .iter()
+                .map(|vehicle| {
+                    let within_day = vehicle.first_use >= day_start && vehicle.last_use < day_end;
+                    within_day
+                        .then(|| vehicle.odometer_end.0 as i64 - vehicle.odometer_begin.0 as i64)
+                })
+                .sum::<Option<i64>>();
+            let mut registrations: Vec<&str> = uses
+                .iter()
+                .map(|vehicle| vehicle.vehicle_registration_number.trim())
+                .collect();
+            registrations.sort();
+            registrations.dedup();
+            let vu_odometer_km = (!registrations.is_empty())
+                .then(|| {
+                    registrations
+                        .iter()
+                        .map(|registration| {
+                            // the vehicle may have been downloaded several times
+                            let reading = |time| {
+                                vu_readings
+                                    .iter()
+                                    .filter(|(number, _)| number.trim() == *registration)
+                                    .flat_map(|(_, readings)| readings)
+                                    .find(|(reading_time, _)| *reading_time == time)
+                                    .map(|(_, odometer)| odometer.0 as i64)
+                            };
+                            Some(reading(day_end)? - reading(day_start)?)
+                        })
+                        .sum::<Option<i64>>()
+                })
+                .flatten();
+            let card_activity_km = distance.0 as i64;
+            let discrepancy = vehicles_used_km
+                .is_some_and(|km| (card_activity_km - km).abs() as f64 > allowed(card_activity_km))
+                || vu_odometer_km.is_some_and(|km| (card_activity_km - km) as f64 > allowed(km));
+            DistanceReconciliation {
+                date,
+                card_activity_km,
+                vehicles_used_km,
+                vu_odometer_km,
+                discrepancy,
+            }
+        })
+        .collect();
+    days.sort_by_key(|day| day.date);
+    days
+}
+
+impl CardData {
+    /// Vehicles recorded in the VehiclesUsed EF of each generation's DF, see [`merge_vehicles_used`].
+    pub fn vehicles_used(&self) -> Vec<VehicleUsed> {
+        match self {
+            CardData::Gen1 { gen1_blocks, .. } => {
+                merge_vehicles_used(&gen1_blocks.vehicles_used.card_vehicle_records, &[])
+            }
+            CardData::Gen2 {
+                gen1_blocks,
+                gen2_blocks,
+                ..
+            }
+            | CardData::Gen2V2 {
+                gen1_blocks,
+                gen2_blocks,
+                ..
+            } => merge_vehicles_used(
+                &gen1_blocks.vehicles_used.card_vehicle_records,
+                &gen2_blocks.vehicles_used.card_vehicle_records,
+            ),
+        }
+    }
+
+    /// Distance of each day of the card against its vehicle uses and the downloads of those
+    /// vehicles' VUs, see [`reconcile_distances`].
+    pub fn distance_reconciliation(
+        &self,
+        vus: &[VuData],
+        tolerance: f64,
+    ) -> Vec<DistanceReconciliation> {
+        let card_days: Vec<(NaiveDate, Kilometers)> = self
+            .card_driver_activity()
+            .activity_daily_records
+            .iter()
+            .map(|record| {
+                (
+                    record.activity_record_date.0.date_naive(),
+                    record.activity_day_distance.0,
+                )
+            })
+            .collect();
+        let vu_readings: Vec<(String, OdometerReadings)> = vus
+            .iter()
+            .filter_map(|vu| {
+                let registration = vu.vehicle_registration_number()?;
+                Some((registration.0.clone(), vu.odometer_readings()))
+            })
+            .collect();
+        reconcile_distances(&card_days, &self.vehicles_used(), &vu_readings, tolerance)
+    }
+}
+
+impl VuData {
+    /// Odometer at the end of every downloaded day, in chronological order.
+    pub fn odometer_readings(&self) -> Vec<(DateTime<Utc>, Kilometers)> {
+        let mut readings: Vec<(DateTime<Utc>, Kilometers)> = match self {
+            VuData::Gen1(blocks) => blocks
+                .vu_activities
+                .iter()
+                .map(|block| (block.time_real.0, block.odometer_value_midnight.0))
+                .collect(),
+            VuData::Gen2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .filter_map(|block| {
+                    let day = block.date_of_day_downloaded_record_array.first()?;
+                    let odometer = block.odometer_value_midnight_record_array.first()?;
+                    Some((day.0 .0, odometer.0))
+                })
+                .collect(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .filter_map(|block| {
+                    let day = block.date_of_day_downloaded_record_array.first()?;
+                    let odometer = block.odometer_value_midnight_record_array.first()?;
+                    Some((day.0 .0, odometer.0))
+                })
+                .collect(),
+        };
+        for (time, _) in &mut readings {
+            *time += Duration::days(1);
+        }
+        readings.sort_by_key(|(time, _)| *time);
+        readings
+    }
+
+    /// Odometer against detailed speed for each day covered by both, see [`cross_check_distance`].
+    pub fn distance_checks(&self, tolerance: f64) -> Result<Vec<DistanceCheck>> {
+        Ok(cross_check_distance(
+            &self.speed_traces()?,
+            &self.odometer_readings(),
+            tolerance,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::registration;
+    use crate::capabilities::Generation;
+    use crate::dt::{
+        gen1, gen2, IA5String, OdometerShort, TimeReal, VehicleIdentificationNumber,
+        VuDataBlockCounter,
+    };
+    use crate::units::Kilometers;
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    fn gen1_vehicle(vrn: &str, first_use: &str, last_use: &str) -> gen1::CardVehicleRecord {
+        gen1::CardVehicleRecord {
+            vehicle_odometer_begin: OdometerShort(Kilometers(1000)),
+            vehicle_odometer_end: OdometerShort(Kilometers(1200)),
+            vehicle_first_use: TimeReal(first_use.parse().unwrap()),
+            vehicle_last_use: TimeReal(last_use.parse().unwrap()),
+            vehicle_registration: registration(vrn),
+            vu_data_block_counter: VuDataBlockCounter(0),
+        }
+    }
+
+    #[test]
+    fn test_merge_vehicles_used() {
+        let gen1_records = vec![
+            gen1_vehicle("B123ABC", "2024-03-02T06:00:00Z", "2024-03-02T18:00:00Z"),
+            gen1_vehicle("B999XYZ", "2024-03-01T06:00:00Z", "2024-03-01T18:00:00Z"),
+        ];
+        let shared = &gen1_records[0];
+        let gen2_records = vec![gen2::CardVehicleRecordGen2 {
+            vehicle_odometer_begin: OdometerShort(Kilometers(1000)),
+            vehicle_odometer_end: OdometerShort(Kilometers(1200)),
+            vehicle_first_use: TimeReal(shared.vehicle_first_use.0),
+            vehicle_last_use: TimeReal(shared.vehicle_last_use.0),
+            vehicle_registration: registration("B123ABC"),
+            vu_data_block_counter: VuDataBlockCounter(0),
+            vehicle_identification_number: VehicleIdentificationNumber(IA5String(
+                "WDB9634031L123456".to_string(),
+            )),
+        }];
+
+        let vehicles = merge_vehicles_used(&gen1_records, &gen2_records);
+        let summary: Vec<(&str, Generation, &str)> = vehicles
+            .iter()
+            .map(|vehicle| {
+                (
+                    vehicle.vehicle_registration_number.as_str(),
+                    vehicle.generation,
+                    vehicle.source_ef.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("B999XYZ", Generation::Gen1, "Tachograph/VehiclesUsed"),
+                ("B123ABC", Generation::Gen2, "Tachograph_G2/VehiclesUsed"),
+            ]
+        );
+        assert_eq!(
+            vehicles[1].vehicle_identification_number.as_deref(),
+            Some("WDB9634031L123456")
+        );
+    }
+
+    #[test]
+    fn test_reconcile_distances() {
+        let date = |day: &str| day.parse::<NaiveDate>().unwrap();
+        let time = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        // 200 km on each vehicle use
+        let vehicles = merge_vehicles_used(
+            &[
+                gen1_vehicle("B123ABC", "2024-03-01T06:00:00Z", "2024-03-01T18:00:00Z"),
+                gen1_vehicle("B123ABC", "2024-03-02T06:00:00Z", "2024-03-02T18:00:00Z"),
+                gen1_vehicle("B999XYZ", "2024-03-03T20:00:00Z", "2024-03-04T02:00:00Z"),
+            ],
+            &[],
+        );
+        let card_days = [
+            (date("2024-03-01"), Kilometers(200)),
+            (date("2024-03-02"), Kilometers(150)),
+            (date("2024-03-03"), Kilometers(120)),
+            (date("2024-03-05"), Kilometers(0)),
+        ];
+        let vu_readings = vec![(
+            "B123ABC".to_string(),
+            vec![
+                (time("2024-03-01T00:00:00Z"), Kilometers(1000)),
+                (time("2024-03-02T00:00:00Z"), Kilometers(1250)),
+                (time("2024-03-03T00:00:00Z"), Kilometers(1400)),
+            ],
+        )];
+
+        let days = reconcile_distances(&card_days, &vehicles, &vu_readings, 0.05);
+        let summary: Vec<(Option<i64>, Option<i64>, bool)> = days
+            .iter()
+            .map(|day| (day.vehicles_used_km, day.vu_odometer_km, day.discrepancy))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                // the vehicle covered more than the card, another driver may have driven it
+                (Some(200), Some(250), false),
+                (Some(200), Some(150), true),
+                // the use spans midnight and the vehicle was not downloaded
+                (None, None, false),
+                (Some(0), None, false),
+            ]
+        );
+    }
+}
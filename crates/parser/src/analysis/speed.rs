@@ -0,0 +1,427 @@
+//! Detailed speed traces.
+use crate::dt::{CardNumber, CardSlotNumber, Coded, Speed, TimeReal, VuDetailedSpeedBlock};
+use crate::sink::card_number_to_string;
+use crate::vu_parser::VuData;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Speeds recorded once per second without interruption, starting at `start`.
+pub struct SpeedTrace {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    /// Speed in km/h for each second, `None` where the VU recorded no data.
+    pub samples: Vec<Option<u8>>,
+}
+impl SpeedTrace {
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::seconds(self.samples.len() as i64)
+    }
+}
+
+/// Speeds in km/h of detailed speed samples, with the 0xFF "no data" samples as `None`.
+pub fn normalize_speeds(speeds: &[Speed]) -> Vec<Option<u8>> {
+    speeds.iter().map(Speed::value).collect()
+}
+
+/// Joins detailed speed blocks into continuous traces, in chronological order.
+/// A block continues the current trace when it begins right where the trace ends, any other
+/// block starts a new trace, so the gaps between traces are the periods without speed data.
+/// Blocks covering a minute that was already added, as found in overlapping downloads, are
+/// skipped. Fails if a block does not begin on a full minute.
+pub fn stitch_speed_blocks<'a>(
+    blocks: impl IntoIterator<Item = &'a VuDetailedSpeedBlock>,
+) -> Result<Vec<SpeedTrace>> {
+    let mut blocks: Vec<&VuDetailedSpeedBlock> = blocks.into_iter().collect();
+    for block in &blocks {
+        let begin = block.speed_block_begin_date.0;
+        if begin.second() != 0 {
+            anyhow::bail!(
+                "Speed block begin date {} is not aligned to a minute",
+                begin
+            );
+        }
+    }
+    blocks.sort_by_key(|block| block.speed_block_begin_date.0);
+
+    let mut traces: Vec<SpeedTrace> = Vec::new();
+    for block in blocks {
+        let begin = block.speed_block_begin_date.0;
+        let samples = normalize_speeds(&block.speeds_per_second);
+        match traces.last_mut() {
+            Some(last) if begin < last.end() => {
+                log::warn!("Skipping speed block at {}, already covered", begin);
+            }
+            Some(last) if begin == last.end() => last.samples.extend(samples),
+            _ => traces.push(SpeedTrace {
+                start: begin,
+                samples,
+            }),
+        }
+    }
+    Ok(traces)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Speed over one interval of a [`SpeedTrace`], see [`SpeedTrace::downsample`].
+pub struct SpeedAggregate {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    /// Seconds covered, less than the interval for the last aggregate of a trace.
+    pub seconds: u32,
+    /// Seconds with speed data, the only ones the speeds are computed from.
+    pub seconds_with_data: u32,
+    /// Speeds in km/h, `None` if no second of the interval has speed data.
+    pub max_speed: Option<u8>,
+    pub mean_speed: Option<f64>,
+}
+
+impl SpeedTrace {
+    /// Max and mean speed per interval of `interval_seconds`, typically 5 or 60, counted from
+    /// `start`. Seconds without speed data are left out. Panics if `interval_seconds` is 0.
+    pub fn downsample(&self, interval_seconds: usize) -> Vec<SpeedAggregate> {
+        self.samples
+            .chunks(interval_seconds)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let speeds: Vec<u8> = chunk.iter().flatten().copied().collect();
+                SpeedAggregate {
+                    start: self.start + Duration::seconds((index * interval_seconds) as i64),
+                    seconds: chunk.len() as u32,
+                    seconds_with_data: speeds.len() as u32,
+                    max_speed: speeds.iter().copied().max(),
+                    mean_speed: (!speeds.is_empty()).then(|| {
+                        speeds.iter().map(|&speed| speed as f64).sum::<f64>() / speeds.len() as f64
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Distance in km driven between `from` and `to`, integrating the speed of every second
+    /// with speed data.
+    pub fn distance_km(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+        let offset = |time: DateTime<Utc>| {
+            (time - self.start)
+                .num_seconds()
+                .clamp(0, self.samples.len() as i64) as usize
+        };
+        self.samples[offset(from)..offset(to).max(offset(from))]
+            .iter()
+            .flatten()
+            .map(|&speed| speed as f64 / 3600.0)
+            .sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Detailed speed recorded while a driver's card was inserted, see
+/// [`VuData::driver_speed_segments`].
+pub struct DriverSpeedSegment {
+    pub card_slot_number: Coded<CardSlotNumber>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub card_insertion_time: DateTime<Utc>,
+    /// `None` when the card was still inserted at download time.
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub card_withdrawal_time: Option<DateTime<Utc>>,
+    /// The part of a speed trace within the insertion.
+    pub speed: SpeedTrace,
+}
+
+impl SpeedTrace {
+    /// The samples between `from` and `to`, `None` if the trace does not overlap them.
+    pub fn slice(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<SpeedTrace> {
+        let start = from.max(self.start);
+        let end = to.min(self.end());
+        if start >= end {
+            return None;
+        }
+        let offset = |time: DateTime<Utc>| (time - self.start).num_seconds() as usize;
+        Some(SpeedTrace {
+            start,
+            samples: self.samples[offset(start)..offset(end)].to_vec(),
+        })
+    }
+}
+
+impl VuData {
+    /// Card insertion and withdrawal records of the card with `card_number`, oldest first, as
+    /// segments without speed. Records of insertions that span midnight are stored with both days
+    /// and listed once.
+    fn card_insertions(&self, card_number: &str) -> Vec<DriverSpeedSegment> {
+        let mut insertions: Vec<DriverSpeedSegment> = Vec::new();
+        let mut push = |slot: &Coded<CardSlotNumber>,
+                        number: &CardNumber,
+                        insertion: &TimeReal,
+                        withdrawal: &Option<TimeReal>| {
+            if card_number_to_string(number).as_deref() != Some(card_number) {
+                return;
+            }
+            let insertion = DriverSpeedSegment {
+                card_slot_number: slot.clone(),
+                card_insertion_time: insertion.0,
+                card_withdrawal_time: withdrawal.as_ref().map(|time| time.0),
+                speed: SpeedTrace {
+                    start: insertion.0,
+                    samples: Vec::new(),
+                },
+            };
+            if !insertions.contains(&insertion) {
+                insertions.push(insertion);
+            }
+        };
+        match self {
+            VuData::Gen1(blocks) => {
+                for block in &blocks.vu_activities {
+                    for record in &block.vu_card_iw_data.vu_card_iw_records {
+                        push(
+                            &record.card_slot_number,
+                            &record.full_card_number.card_number,
+                            &record.card_insertion_time,
+                            &record.card_withdrawal_time,
+                        );
+                    }
+                }
+            }
+            VuData::Gen2(blocks) => {
+                for block in &blocks.vu_activities {
+                    for record in &block.vu_card_iw_record_array {
+                        push(
+                            &record.card_slot_number,
+                            &record
+                                .full_card_number_and_generation
+                                .full_card_number
+                                .card_number,
+                            &record.card_insertion_date,
+                            &record.card_withdrawl_time,
+                        );
+                    }
+                }
+            }
+            VuData::Gen2V2(blocks) => {
+                for block in &blocks.vu_activities {
+                    for record in &block.vu_card_iw_record_array {
+                        push(
+                            &record.card_slot_number,
+                            &record
+                                .full_card_number_and_generation
+                                .full_card_number
+                                .card_number,
+                            &record.card_insertion_date,
+                            &record.card_withdrawl_time,
+                        );
+                    }
+                }
+            }
+        }
+        insertions.sort_by_key(|insertion| insertion.card_insertion_time);
+        insertions
+    }
+
+    /// Detailed speed recorded while the card with `card_number` was inserted in either slot,
+    /// joining the card insertion and withdrawal records with [`VuData::speed_traces`]. A card
+    /// that was not withdrawn covers the speed data up to the download. Insertions overlapping
+    /// several traces yield a segment per trace.
+    pub fn driver_speed_segments(&self, card_number: &str) -> Result<Vec<DriverSpeedSegment>> {
+        Ok(speed_during_insertions(
+            self.card_insertions(card_number),
+            &self.speed_traces()?,
+        ))
+    }
+}
+
+/// Fills the speed of the insertions of [`VuData::driver_speed_segments`].
+fn speed_during_insertions(
+    insertions: Vec<DriverSpeedSegment>,
+    traces: &[SpeedTrace],
+) -> Vec<DriverSpeedSegment> {
+    let mut segments = Vec::new();
+    for insertion in insertions {
+        let withdrawal = insertion
+            .card_withdrawal_time
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        for trace in traces {
+            if let Some(speed) = trace.slice(insertion.card_insertion_time, withdrawal) {
+                segments.push(DriverSpeedSegment {
+                    speed,
+                    ..insertion.clone()
+                });
+            }
+        }
+    }
+    segments
+}
+
+impl VuData {
+    /// Detailed speed of every speed block in the file, see [`stitch_speed_blocks`].
+    /// Gen2v2 files are not parsed for detailed speed yet and yield no traces.
+    pub fn speed_traces(&self) -> Result<Vec<SpeedTrace>> {
+        match self {
+            VuData::Gen1(blocks) => stitch_speed_blocks(
+                blocks
+                    .vu_detailed_speed
+                    .iter()
+                    .flat_map(|block| &block.vu_detailed_speed_data.vu_detailed_speed_records),
+            ),
+            VuData::Gen2(blocks) => stitch_speed_blocks(
+                blocks
+                    .vu_detailed_speed
+                    .iter()
+                    .flat_map(|block| &block.vu_detailed_speed_block_record_array),
+            ),
+            VuData::Gen2V2(_) => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::cross_check_distance;
+    use crate::dt::{CardSlotNumber, Coded, Speed, TimeReal, VuDetailedSpeedBlock};
+    use crate::units::{Kilometers, Kmh};
+    use chrono::Duration;
+
+    fn speed_block(begin: &str, speed: u8) -> VuDetailedSpeedBlock {
+        VuDetailedSpeedBlock {
+            speed_block_begin_date: TimeReal(begin.parse().unwrap()),
+            speeds_per_second: (0..60).map(|_| Speed(Kmh(speed))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_stitch_speed_blocks() {
+        let blocks = [
+            speed_block("2024-03-01T08:01:00Z", 2),
+            speed_block("2024-03-01T08:00:00Z", 1),
+            // overlaps the first block
+            speed_block("2024-03-01T08:01:00Z", 9),
+            speed_block("2024-03-01T08:05:00Z", 3),
+        ];
+        let traces = stitch_speed_blocks(&blocks).unwrap();
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].start, blocks[1].speed_block_begin_date.0);
+        assert_eq!(traces[0].samples.len(), 120);
+        assert_eq!(
+            (traces[0].samples[59], traces[0].samples[60]),
+            (Some(1), Some(2))
+        );
+        assert_eq!(
+            traces[0].end(),
+            blocks[0].speed_block_begin_date.0 + Duration::minutes(1)
+        );
+        assert_eq!(traces[1].start, blocks[3].speed_block_begin_date.0);
+
+        let misaligned = [speed_block("2024-03-01T08:00:30Z", 1)];
+        assert!(stitch_speed_blocks(&misaligned).is_err());
+    }
+
+    #[test]
+    fn test_downsample_and_cross_check_distance() {
+        let mut trace = SpeedTrace {
+            start: "2024-03-01T08:00:00Z".parse().unwrap(),
+            samples: vec![Some(60); 3600],
+        };
+        trace.samples[3] = Some(90);
+        let aggregates = trace.downsample(5);
+        assert_eq!(aggregates.len(), 720);
+        assert_eq!(aggregates[0].max_speed, Some(90));
+        assert_eq!(aggregates[0].mean_speed, Some(66.0));
+        assert_eq!(aggregates[1].start, trace.start + Duration::seconds(5));
+        let per_minute = SpeedTrace {
+            samples: vec![Some(10); 90],
+            ..trace.clone()
+        }
+        .downsample(60);
+        assert_eq!(
+            per_minute.iter().map(|a| a.seconds).collect::<Vec<_>>(),
+            vec![60, 30]
+        );
+        // 0xFF samples carry no speed, they are not averaged as 255 km/h
+        let mut gaps = SpeedTrace {
+            samples: normalize_speeds(&[
+                Speed(Kmh(20)),
+                Speed(Kmh(Speed::NO_DATA)),
+                Speed(Kmh(40)),
+            ]),
+            ..trace.clone()
+        };
+        assert_eq!(gaps.samples[1], None);
+        let aggregate = &gaps.downsample(5)[0];
+        assert_eq!(aggregate.seconds_with_data, 2);
+        assert_eq!(aggregate.max_speed, Some(40));
+        assert_eq!(aggregate.mean_speed, Some(30.0));
+        gaps.samples = vec![None; 5];
+        assert_eq!(gaps.downsample(5)[0].mean_speed, None);
+
+        let time = |hour: u32| trace.start + Duration::hours(hour as i64);
+        let readings = [
+            // before the speed data starts, skipped
+            (time(0) - Duration::hours(1), Kilometers(900)),
+            (time(0), Kilometers(1000)),
+            (time(2), Kilometers(1060)),
+            (time(3), Kilometers(1100)),
+        ];
+        let checks = cross_check_distance(&[trace], &readings, 0.05);
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].odometer_km, 60);
+        assert!((checks[0].speed_km - 60.0083).abs() < 0.001);
+        assert!(!checks[0].discrepancy);
+        // 40 km on the odometer without any recorded speed
+        assert_eq!(checks[1].speed_km, 0.0);
+        assert!(checks[1].discrepancy);
+    }
+
+    #[test]
+    fn test_speed_during_insertions() {
+        let trace = |start: &str, seconds: usize| SpeedTrace {
+            start: start.parse().unwrap(),
+            samples: (0..seconds)
+                .map(|second| Some((second % 100) as u8))
+                .collect(),
+        };
+        let traces = [
+            trace("2024-03-01T08:00:00Z", 3600),
+            trace("2024-03-01T10:00:00Z", 600),
+        ];
+        let insertion = |insertion: &str, withdrawal: Option<&str>| DriverSpeedSegment {
+            card_slot_number: Coded::Known(CardSlotNumber::DriverSlot),
+            card_insertion_time: insertion.parse().unwrap(),
+            card_withdrawal_time: withdrawal.map(|time| time.parse().unwrap()),
+            speed: SpeedTrace {
+                start: insertion.parse().unwrap(),
+                samples: Vec::new(),
+            },
+        };
+        let segments = speed_during_insertions(
+            vec![
+                insertion("2024-03-01T08:30:00Z", Some("2024-03-01T08:30:10Z")),
+                // still inserted, covers the rest of the first trace and all of the second
+                insertion("2024-03-01T08:59:00Z", None),
+                // between the traces
+                insertion("2024-03-01T09:10:00Z", Some("2024-03-01T09:20:00Z")),
+            ],
+            &traces,
+        );
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].speed.start, segments[0].card_insertion_time);
+        assert_eq!(segments[0].speed.samples, traces[0].samples[1800..1810]);
+        assert_eq!(segments[1].speed.samples.len(), 60);
+        assert_eq!(segments[1].speed.end(), traces[0].end());
+        assert_eq!(segments[2].speed, traces[1]);
+        assert_eq!(traces[0].slice(traces[0].end(), traces[1].start), None);
+    }
+}
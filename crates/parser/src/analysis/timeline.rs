@@ -0,0 +1,422 @@
+//! Activity timelines built from the daily activity records of cards and VUs.
+use crate::card_parser::CardData;
+use crate::dt::{
+    ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, ActivityChangeInfoSlot,
+    ActivityChangeInfoStatus, CardActivityChangeInfo, CardDriverActivity,
+};
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Whether an activity was recorded by the VU or entered manually by the driver.
+pub enum ActivityOrigin {
+    /// Recorded while the card was inserted (p=0).
+    Recorded,
+    /// Entered manually for a period in which the card was withdrawn (p=1, c=1).
+    ManualEntry,
+}
+impl ActivityOrigin {
+    /// Origin of the activity following a change, `None` when the card was withdrawn and the
+    /// following activity is unknown (p=1, c=0).
+    pub fn of(change: &CardActivityChangeInfo) -> Option<Self> {
+        match (
+            &change.card_status,
+            &change.driving_or_following_activity_status,
+        ) {
+            (ActivityChangeInfoCardStatus::Inserted, _) => Some(ActivityOrigin::Recorded),
+            (ActivityChangeInfoCardStatus::NotInserted, ActivityChangeInfoStatus::Known) => {
+                Some(ActivityOrigin::ManualEntry)
+            }
+            (ActivityChangeInfoCardStatus::NotInserted, _) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A continuous stretch of a single activity.
+pub struct ActivityPeriod {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub end: DateTime<Utc>,
+    pub slot: ActivityChangeInfoSlot,
+    pub activity: ActivityChangeInfoCardActivity,
+    pub origin: ActivityOrigin,
+}
+impl ActivityPeriod {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Builds an activity timeline from a card's daily records.
+/// Each change lasts until the next one or until midnight. Periods in which the card was withdrawn
+/// and no activity was entered manually are left out, since their activity is not relevant.
+/// Manually entered periods are kept apart from recorded ones, see [`ActivityOrigin`].
+/// Consecutive periods of the same activity and origin are merged, including across midnight.
+pub fn card_activity_timeline(card_driver_activity: &CardDriverActivity) -> Vec<ActivityPeriod> {
+    let mut timeline: Vec<ActivityPeriod> = Vec::new();
+    for record in &card_driver_activity.activity_daily_records {
+        let changes: Vec<&CardActivityChangeInfo> = record.activity_change_info.iter().collect();
+        push_day_periods(&mut timeline, record.activity_record_date.0, &changes);
+    }
+    timeline
+}
+
+/// Builds an activity timeline from a VU's daily activity changes, given per downloaded day.
+/// The VU records the changes of both slots in a single list, each slot's activity lasts until
+/// the next change in the same slot. Otherwise follows [`card_activity_timeline`].
+/// Periods are sorted by start, driver slot first.
+pub fn vu_activity_timeline<'a>(
+    days: impl IntoIterator<Item = (DateTime<Utc>, &'a [CardActivityChangeInfo])>,
+) -> Vec<ActivityPeriod> {
+    let mut days: Vec<(DateTime<Utc>, &[CardActivityChangeInfo])> = days.into_iter().collect();
+    days.sort_by_key(|(day_start, _)| *day_start);
+    let mut driver: Vec<ActivityPeriod> = Vec::new();
+    let mut co_driver: Vec<ActivityPeriod> = Vec::new();
+    for (day_start, changes) in days {
+        let (driver_changes, co_driver_changes): (Vec<_>, Vec<_>) = changes
+            .iter()
+            .partition(|change| change.slot == ActivityChangeInfoSlot::Driver);
+        push_day_periods(&mut driver, day_start, &driver_changes);
+        push_day_periods(&mut co_driver, day_start, &co_driver_changes);
+    }
+    driver.append(&mut co_driver);
+    driver.sort_by_key(|period| period.start);
+    driver
+}
+
+/// Appends the periods of one day's changes to a timeline, merging with its last period.
+fn push_day_periods(
+    timeline: &mut Vec<ActivityPeriod>,
+    day_start: DateTime<Utc>,
+    changes: &[&CardActivityChangeInfo],
+) {
+    for (index, change) in changes.iter().enumerate() {
+        let end_minutes = changes
+            .get(index + 1)
+            .map(|next| next.minutes.0)
+            .unwrap_or(MINUTES_PER_DAY)
+            .min(MINUTES_PER_DAY);
+        if end_minutes <= change.minutes.0 {
+            continue;
+        }
+        let Some(origin) = ActivityOrigin::of(change) else {
+            continue;
+        };
+        let period = ActivityPeriod {
+            start: day_start + change.minutes.duration(),
+            end: day_start + Duration::minutes(end_minutes as i64),
+            slot: change.slot.clone(),
+            activity: change.activity.clone(),
+            origin,
+        };
+        match timeline.last_mut() {
+            Some(last)
+                if last.end == period.start
+                    && last.activity == period.activity
+                    && last.slot == period.slot
+                    && last.origin == period.origin =>
+            {
+                last.end = period.end;
+            }
+            _ => timeline.push(period),
+        }
+    }
+}
+
+/// Splits the periods of a timeline that cross 00:00 UTC into one period per UTC day, undoing the
+/// merge across midnight of [`card_activity_timeline`] and [`vu_activity_timeline`]. Daily figures must be taken from this split view: a period counted
+/// whole on the day it starts inflates that day and leaves the next one short.
+pub fn split_at_utc_midnight(timeline: &[ActivityPeriod]) -> Vec<ActivityPeriod> {
+    split_at(timeline, |start| {
+        (start.date_naive() + Duration::days(1))
+            .and_time(NaiveTime::MIN)
+            .and_utc()
+    })
+}
+
+/// Splits the periods of a timeline at every boundary, `next_boundary` giving the first boundary
+/// after a time.
+pub(super) fn split_at(
+    timeline: &[ActivityPeriod],
+    next_boundary: impl Fn(DateTime<Utc>) -> DateTime<Utc>,
+) -> Vec<ActivityPeriod> {
+    let mut split = Vec::with_capacity(timeline.len());
+    for period in timeline {
+        let mut start = period.start;
+        while start < period.end {
+            let end = period.end.min(next_boundary(start));
+            split.push(ActivityPeriod {
+                start,
+                end,
+                ..period.clone()
+            });
+            start = end;
+        }
+    }
+    split
+}
+
+/// Monday 00:00 UTC of the week containing `time`.
+pub(super) fn tacho_week_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    let date = time.date_naive();
+    (date - Duration::days(date.weekday().num_days_from_monday() as i64))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Minutes per activity on one UTC day, see [`daily_activity_totals`].
+pub struct DailyActivityTotals {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub date: NaiveDate,
+    pub driving_minutes: i64,
+    pub work_minutes: i64,
+    pub availability_minutes: i64,
+    pub break_rest_minutes: i64,
+}
+
+/// Minutes per activity for every UTC day of the timeline, oldest first, with periods crossing
+/// midnight counted on each side, see [`split_at_utc_midnight`]. Both slots are counted together,
+/// VU timelines should be filtered by slot first.
+pub fn daily_activity_totals(timeline: &[ActivityPeriod]) -> Vec<DailyActivityTotals> {
+    let mut totals: Vec<DailyActivityTotals> = Vec::new();
+    let mut periods = split_at_utc_midnight(timeline);
+    periods.sort_by_key(|period| period.start);
+    for period in periods {
+        let date = period.start.date_naive();
+        let day = match totals.last_mut() {
+            Some(day) if day.date == date => day,
+            _ => {
+                totals.push(DailyActivityTotals {
+                    date,
+                    driving_minutes: 0,
+                    work_minutes: 0,
+                    availability_minutes: 0,
+                    break_rest_minutes: 0,
+                });
+                totals.last_mut().expect("just pushed")
+            }
+        };
+        let minutes = period.duration().num_minutes();
+        match period.activity {
+            ActivityChangeInfoCardActivity::Driving => day.driving_minutes += minutes,
+            ActivityChangeInfoCardActivity::Work => day.work_minutes += minutes,
+            ActivityChangeInfoCardActivity::Availability => day.availability_minutes += minutes,
+            ActivityChangeInfoCardActivity::BreakRest => day.break_rest_minutes += minutes,
+        }
+    }
+    totals
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// The periods of one UTC day, see [`activities_by_day`].
+pub struct ActivityDay {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub date: NaiveDate,
+    pub periods: Vec<ActivityPeriod>,
+}
+
+/// Periods of the timeline grouped by UTC day, oldest first, with periods crossing midnight split
+/// between both days, see [`split_at_utc_midnight`]. Days without a period are left out.
+pub fn activities_by_day(timeline: &[ActivityPeriod]) -> Vec<ActivityDay> {
+    let mut days: Vec<ActivityDay> = Vec::new();
+    let mut periods = split_at_utc_midnight(timeline);
+    periods.sort_by_key(|period| period.start);
+    for period in periods {
+        let date = period.start.date_naive();
+        match days.last_mut() {
+            Some(day) if day.date == date => day.periods.push(period),
+            _ => days.push(ActivityDay {
+                date,
+                periods: vec![period],
+            }),
+        }
+    }
+    days
+}
+
+impl CardData {
+    /// Activity timeline of the card, from the latest generation's activity data.
+    pub fn activity_timeline(&self) -> Vec<ActivityPeriod> {
+        card_activity_timeline(self.card_driver_activity())
+    }
+
+    /// Activity timeline of the card with periods split at midnight, see [`split_at_utc_midnight`].
+    pub fn activity_timeline_by_day(&self) -> Vec<ActivityPeriod> {
+        split_at_utc_midnight(&self.activity_timeline())
+    }
+
+    pub(crate) fn card_driver_activity(&self) -> &CardDriverActivity {
+        match self {
+            CardData::Gen1 { gen1_blocks, .. } => {
+                &gen1_blocks.driver_activity_data.card_driver_activity
+            }
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                &gen2_blocks.driver_activity_data.card_driver_activity
+            }
+        }
+    }
+}
+
+impl VuData {
+    /// Activity timeline of both slots, see [`vu_activity_timeline`].
+    pub fn activity_timeline(&self) -> Vec<ActivityPeriod> {
+        match self {
+            VuData::Gen1(blocks) => {
+                vu_activity_timeline(blocks.vu_activities.iter().map(|block| {
+                    (
+                        block.time_real.0,
+                        block
+                            .vu_activity_daily_data
+                            .activity_change_infos
+                            .as_slice(),
+                    )
+                }))
+            }
+            VuData::Gen2(blocks) => {
+                vu_activity_timeline(blocks.vu_activities.iter().filter_map(|block| {
+                    let day = block.date_of_day_downloaded_record_array.first()?;
+                    Some((day.0 .0, block.vu_activity_daily_record_array.as_slice()))
+                }))
+            }
+            VuData::Gen2V2(blocks) => {
+                vu_activity_timeline(blocks.vu_activities.iter().filter_map(|block| {
+                    let day = block.date_of_day_downloaded_record_array.first()?;
+                    Some((day.0 .0, block.vu_activity_daily_record_array.as_slice()))
+                }))
+            }
+        }
+    }
+
+    /// Activity timeline of both slots with periods split at midnight, see [`split_at_utc_midnight`].
+    pub fn activity_timeline_by_day(&self) -> Vec<ActivityPeriod> {
+        split_at_utc_midnight(&self.activity_timeline())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::{change, day};
+    use crate::dt::{
+        ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, ActivityChangeInfoStatus,
+        CardActivityChangeInfo, CardDriverActivity,
+    };
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn test_split_at_utc_midnight() {
+        use ActivityChangeInfoCardActivity::*;
+        let activity = CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: vec![
+                day(
+                    "2024-03-01",
+                    0,
+                    vec![change(0, BreakRest), change(22 * 60, Driving)],
+                ),
+                day(
+                    "2024-03-02",
+                    0,
+                    vec![change(0, Driving), change(2 * 60, BreakRest)],
+                ),
+            ],
+        };
+        let timeline = card_activity_timeline(&activity);
+        let split = split_at_utc_midnight(&timeline);
+        assert_eq!(split.len(), 4);
+        assert_eq!(split[1].end, split[2].start);
+        assert_eq!(
+            split[2].start,
+            "2024-03-02T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            (split[1].activity.clone(), split[2].activity.clone()),
+            (Driving, Driving)
+        );
+
+        let days = activities_by_day(&timeline);
+        assert_eq!(
+            days.iter()
+                .map(|day| (day.date.to_string(), day.periods.len()))
+                .collect::<Vec<_>>(),
+            vec![("2024-03-01".to_string(), 2), ("2024-03-02".to_string(), 2)]
+        );
+        assert_eq!(days[1].periods[0], split[2]);
+
+        let totals = daily_activity_totals(&timeline);
+        let summary: Vec<(String, i64, i64)> = totals
+            .iter()
+            .map(|day| {
+                (
+                    day.date.to_string(),
+                    day.driving_minutes,
+                    day.break_rest_minutes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2024-03-01".to_string(), 2 * 60, 22 * 60),
+                ("2024-03-02".to_string(), 2 * 60, 22 * 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timeline_manual_entries() {
+        use ActivityChangeInfoCardActivity::*;
+        let withdrawn = |minutes, activity, status| CardActivityChangeInfo {
+            card_status: ActivityChangeInfoCardStatus::NotInserted,
+            driving_or_following_activity_status: status,
+            ..change(minutes, activity)
+        };
+        // work entered manually until 06:00, unknown until 08:00, then work recorded until 12:00
+        let activity = CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: vec![day(
+                "2024-03-01",
+                0,
+                vec![
+                    withdrawn(0, Work, ActivityChangeInfoStatus::Known),
+                    withdrawn(6 * 60, BreakRest, ActivityChangeInfoStatus::Unknown),
+                    change(8 * 60, Work),
+                    withdrawn(12 * 60, Work, ActivityChangeInfoStatus::Known),
+                ],
+            )],
+        };
+        let timeline = card_activity_timeline(&activity);
+        let summary: Vec<(i64, ActivityOrigin)> = timeline
+            .iter()
+            .map(|period| (period.duration().num_hours(), period.origin))
+            .collect();
+        // adjacent work periods are not merged when their origin differs
+        assert_eq!(
+            summary,
+            vec![
+                (6, ActivityOrigin::ManualEntry),
+                (4, ActivityOrigin::Recorded),
+                (12, ActivityOrigin::ManualEntry),
+            ]
+        );
+    }
+}
@@ -0,0 +1,674 @@
+//! Fleet KPIs and working time: 561/2006 weeks, daily periods and weekly reports.
+use super::timeline::{card_activity_timeline, split_at, tacho_week_start, ActivityPeriod};
+use crate::card_parser::CardData;
+use crate::dt::ActivityChangeInfoCardActivity;
+use crate::units::Kilometers;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Clone)]
+/// Settings for [`compute_kpis`].
+pub struct KpiConfig {
+    /// Start of the night time window, in local time.
+    /// Directive 2002/15/EC leaves the 4 hour window between 00:00 and 07:00 to national law.
+    pub night_start: NaiveTime,
+    /// End of the night time window, in local time. May be earlier than `night_start` to wrap midnight.
+    pub night_end: NaiveTime,
+    /// Offset used to turn the UTC timestamps of the tachograph into local time.
+    pub utc_offset: FixedOffset,
+}
+impl Default for KpiConfig {
+    fn default() -> Self {
+        KpiConfig {
+            night_start: NaiveTime::from_hms_opt(0, 0, 0).expect("valid time"),
+            night_end: NaiveTime::from_hms_opt(4, 0, 0).expect("valid time"),
+            utc_offset: FixedOffset::east_opt(0).expect("valid offset"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct FleetKpis {
+    pub driving_minutes: i64,
+    pub work_minutes: i64,
+    pub availability_minutes: i64,
+    pub break_rest_minutes: i64,
+    /// Driving minutes per minute of break/rest, `None` without any break/rest.
+    pub driving_to_rest_ratio: Option<f64>,
+    /// Driving and other work inside the night time window.
+    pub night_work_minutes: i64,
+    /// Local days on which any night work was performed.
+    pub night_work_days: usize,
+    /// Driving on local Saturdays and Sundays.
+    pub weekend_driving_minutes: i64,
+    /// Average distance over the days with a recorded distance, in km.
+    pub average_daily_distance_km: Option<f64>,
+}
+
+/// Computes KPIs over an activity timeline and the distances recorded per day.
+pub fn compute_kpis(
+    timeline: &[ActivityPeriod],
+    daily_distances_km: &[(NaiveDate, Kilometers)],
+    config: &KpiConfig,
+) -> FleetKpis {
+    let minutes_of = |activity: ActivityChangeInfoCardActivity| -> i64 {
+        timeline
+            .iter()
+            .filter(|period| period.activity == activity)
+            .map(|period| period.duration().num_minutes())
+            .sum()
+    };
+    let driving_minutes = minutes_of(ActivityChangeInfoCardActivity::Driving);
+    let work_minutes = minutes_of(ActivityChangeInfoCardActivity::Work);
+    let availability_minutes = minutes_of(ActivityChangeInfoCardActivity::Availability);
+    let break_rest_minutes = minutes_of(ActivityChangeInfoCardActivity::BreakRest);
+
+    let mut night_work_minutes = 0;
+    let mut night_work_dates: Vec<NaiveDate> = Vec::new();
+    let mut weekend_driving_minutes = 0;
+    for period in timeline {
+        let is_work = matches!(
+            period.activity,
+            ActivityChangeInfoCardActivity::Driving | ActivityChangeInfoCardActivity::Work
+        );
+        let is_driving = period.activity == ActivityChangeInfoCardActivity::Driving;
+        if !is_work {
+            continue;
+        }
+        for date in local_dates(period, config.utc_offset) {
+            let day_start = local_to_utc(date, NaiveTime::MIN, config.utc_offset);
+            if is_driving && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                weekend_driving_minutes +=
+                    overlap_minutes(period, day_start, day_start + Duration::days(1));
+            }
+            let night_start = local_to_utc(date, config.night_start, config.utc_offset);
+            let mut night_end = local_to_utc(date, config.night_end, config.utc_offset);
+            if night_end <= night_start {
+                night_end += Duration::days(1);
+            }
+            let night_minutes = overlap_minutes(period, night_start, night_end);
+            if night_minutes > 0 {
+                night_work_minutes += night_minutes;
+                if !night_work_dates.contains(&date) {
+                    night_work_dates.push(date);
+                }
+            }
+        }
+    }
+
+    let distances: Vec<u32> = daily_distances_km
+        .iter()
+        .map(|(_, distance)| distance.0)
+        .filter(|distance| *distance > 0)
+        .collect();
+    let average_daily_distance_km = (!distances.is_empty())
+        .then(|| distances.iter().map(|d| *d as f64).sum::<f64>() / distances.len() as f64);
+
+    FleetKpis {
+        driving_minutes,
+        work_minutes,
+        availability_minutes,
+        break_rest_minutes,
+        driving_to_rest_ratio: (break_rest_minutes > 0)
+            .then(|| driving_minutes as f64 / break_rest_minutes as f64),
+        night_work_minutes,
+        night_work_days: night_work_dates.len(),
+        weekend_driving_minutes,
+        average_daily_distance_km,
+    }
+}
+
+/// Local dates touched by the period, starting one day early so that a night window which
+/// began the evening before is also considered.
+fn local_dates(period: &ActivityPeriod, utc_offset: FixedOffset) -> Vec<NaiveDate> {
+    let first = period.start.with_timezone(&utc_offset).date_naive() - Duration::days(1);
+    let last = period.end.with_timezone(&utc_offset).date_naive();
+    first.iter_days().take_while(|date| *date <= last).collect()
+}
+
+fn local_to_utc(date: NaiveDate, time: NaiveTime, utc_offset: FixedOffset) -> DateTime<Utc> {
+    (date.and_time(time) - Duration::seconds(utc_offset.local_minus_utc() as i64)).and_utc()
+}
+
+fn overlap_minutes(period: &ActivityPeriod, start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+    let overlap = period.end.min(end) - period.start.max(start);
+    overlap.num_minutes().max(0)
+}
+
+#[derive(Debug, Clone)]
+/// Settings for [`working_time_weekly_report`].
+pub struct WorkingTimeConfig {
+    /// Most working time allowed in a single week, 60 hours under Directive 2002/15/EC.
+    pub weekly_limit_minutes: i64,
+    /// Most average weekly working time allowed over the reference period, 48 hours under
+    /// Directive 2002/15/EC.
+    pub average_limit_minutes: i64,
+    /// Number of weeks the average is taken over. The directive's 4 month reference period is
+    /// about 17 weeks, national law may extend it to 6 months.
+    pub reference_weeks: usize,
+    /// Offset used to place the UTC timestamps of the tachograph in local weeks.
+    pub utc_offset: FixedOffset,
+}
+impl Default for WorkingTimeConfig {
+    fn default() -> Self {
+        WorkingTimeConfig {
+            weekly_limit_minutes: 60 * 60,
+            average_limit_minutes: 48 * 60,
+            reference_weeks: 17,
+            utc_offset: FixedOffset::east_opt(0).expect("valid offset"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A week in the sense of Regulation (EC) No 561/2006 article 4(i): Monday 00:00 to Sunday 24:00
+/// UTC, see [`tacho_weeks`].
+pub struct TachoWeek {
+    pub iso_year: i32,
+    pub iso_week: u32,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    pub driving_minutes: i64,
+    pub work_minutes: i64,
+    pub availability_minutes: i64,
+    pub break_rest_minutes: i64,
+    /// Periods of the week, those crossing into the next or previous week cut at Monday 00:00.
+    pub periods: Vec<ActivityPeriod>,
+}
+
+/// Buckets a timeline into 561/2006 weeks, the unit of the weekly and fortnightly driving limits.
+/// Every week between the first and the last period is returned, oldest first, including weeks
+/// without activity so that consecutive weeks can be paired. Weeks always start on Monday 00:00
+/// UTC, whatever the driver's local time. Both slots are counted together, VU timelines should be
+/// filtered by slot first.
+pub fn tacho_weeks(timeline: &[ActivityPeriod]) -> Vec<TachoWeek> {
+    let mut periods = split_at(timeline, |start| {
+        tacho_week_start(start) + Duration::weeks(1)
+    });
+    periods.sort_by_key(|period| period.start);
+    let (Some(first), Some(last)) = (periods.first(), periods.last()) else {
+        return Vec::new();
+    };
+    let mut weeks: Vec<TachoWeek> = Vec::new();
+    let mut week_start = tacho_week_start(first.start);
+    let last_week_start = tacho_week_start(last.start);
+    while week_start <= last_week_start {
+        let iso_week = week_start.iso_week();
+        weeks.push(TachoWeek {
+            iso_year: iso_week.year(),
+            iso_week: iso_week.week(),
+            start: week_start,
+            driving_minutes: 0,
+            work_minutes: 0,
+            availability_minutes: 0,
+            break_rest_minutes: 0,
+            periods: Vec::new(),
+        });
+        week_start += Duration::weeks(1);
+    }
+    for period in periods {
+        let index = ((tacho_week_start(period.start) - weeks[0].start).num_weeks()) as usize;
+        let week = &mut weeks[index];
+        let minutes = period.duration().num_minutes();
+        match period.activity {
+            ActivityChangeInfoCardActivity::Driving => week.driving_minutes += minutes,
+            ActivityChangeInfoCardActivity::Work => week.work_minutes += minutes,
+            ActivityChangeInfoCardActivity::Availability => week.availability_minutes += minutes,
+            ActivityChangeInfoCardActivity::BreakRest => week.break_rest_minutes += minutes,
+        }
+        week.periods.push(period);
+    }
+    weeks
+}
+
+#[derive(Debug, Clone)]
+/// Settings for [`daily_periods`].
+pub struct DailyPeriodConfig {
+    /// Shortest rest that ends a daily period, the 9 hour reduced daily rest of 561/2006
+    /// article 4(g). The 3 hour first part of a split rest does not end a period.
+    pub min_daily_rest_minutes: i64,
+    /// Counts the time in which no activity is known, typically with the card withdrawn, as
+    /// rest. Without it an overnight withdrawal never ends a daily period.
+    pub unknown_as_rest: bool,
+}
+impl Default for DailyPeriodConfig {
+    fn default() -> Self {
+        DailyPeriodConfig {
+            min_daily_rest_minutes: 9 * 60,
+            unknown_as_rest: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// The time between two daily or weekly rests, which is what 561/2006 daily driving limits apply
+/// to, unlike the calendar day, see [`daily_periods`].
+pub struct DailyPeriod {
+    /// End of the rest before the period, or the start of the timeline.
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub start: DateTime<Utc>,
+    /// Start of the rest after the period, or the end of the timeline.
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub end: DateTime<Utc>,
+    /// Length of the rest ending the period, `None` when the timeline ends first.
+    pub following_rest_minutes: Option<i64>,
+    pub driving_minutes: i64,
+    pub work_minutes: i64,
+    pub availability_minutes: i64,
+    /// Breaks and rests too short to end the period.
+    pub break_rest_minutes: i64,
+    pub periods: Vec<ActivityPeriod>,
+}
+
+/// Buckets a timeline into daily periods, delimited by rests of at least
+/// [`DailyPeriodConfig::min_daily_rest_minutes`]. A daily period can span midnight, e.g. a shift
+/// from 18:00 to 04:00, and a calendar day can hold two of them. Consecutive rests, including
+/// unknown time when configured, are added up before being compared to the minimum. The
+/// timeline should hold a single driver's periods, VU timelines should be filtered by slot first.
+pub fn daily_periods(timeline: &[ActivityPeriod], config: &DailyPeriodConfig) -> Vec<DailyPeriod> {
+    let mut periods: Vec<&ActivityPeriod> = timeline.iter().collect();
+    periods.sort_by_key(|period| (period.start, period.end));
+    let (Some(first), Some(timeline_end)) = (
+        periods.first().map(|period| period.start),
+        periods.iter().map(|period| period.end).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    // maximal stretches of rest, each as (start, end)
+    let mut rests: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    let mut push_rest = |start: DateTime<Utc>, end: DateTime<Utc>| match rests.last_mut() {
+        Some(last) if last.1 >= start => last.1 = last.1.max(end),
+        _ => rests.push((start, end)),
+    };
+    let mut covered_until = first;
+    for period in &periods {
+        if config.unknown_as_rest && period.start > covered_until {
+            push_rest(covered_until, period.start);
+        }
+        if period.activity == ActivityChangeInfoCardActivity::BreakRest {
+            push_rest(period.start, period.end);
+        }
+        covered_until = covered_until.max(period.end);
+    }
+
+    let mut bounds: Vec<(DateTime<Utc>, DateTime<Utc>, Option<i64>)> = Vec::new();
+    let mut start = first;
+    for (rest_start, rest_end) in rests {
+        let minutes = (rest_end - rest_start).num_minutes();
+        if minutes < config.min_daily_rest_minutes {
+            continue;
+        }
+        if rest_start > start {
+            bounds.push((start, rest_start, Some(minutes)));
+        }
+        start = rest_end;
+    }
+    if timeline_end > start {
+        bounds.push((start, timeline_end, None));
+    }
+
+    bounds
+        .into_iter()
+        .map(|(start, end, following_rest_minutes)| {
+            let mut daily_period = DailyPeriod {
+                start,
+                end,
+                following_rest_minutes,
+                driving_minutes: 0,
+                work_minutes: 0,
+                availability_minutes: 0,
+                break_rest_minutes: 0,
+                periods: Vec::new(),
+            };
+            for period in &periods {
+                let minutes = overlap_minutes(period, start, end);
+                if minutes == 0 {
+                    continue;
+                }
+                match period.activity {
+                    ActivityChangeInfoCardActivity::Driving => {
+                        daily_period.driving_minutes += minutes
+                    }
+                    ActivityChangeInfoCardActivity::Work => daily_period.work_minutes += minutes,
+                    ActivityChangeInfoCardActivity::Availability => {
+                        daily_period.availability_minutes += minutes
+                    }
+                    ActivityChangeInfoCardActivity::BreakRest => {
+                        daily_period.break_rest_minutes += minutes
+                    }
+                }
+                daily_period.periods.push(ActivityPeriod {
+                    start: period.start.max(start),
+                    end: period.end.min(end),
+                    ..(*period).clone()
+                });
+            }
+            daily_period
+        })
+        .filter(|daily_period| !daily_period.periods.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Working time of one ISO week, a row of [`working_time_weekly_report`].
+pub struct WorkingTimeWeek {
+    pub iso_year: i32,
+    pub iso_week: u32,
+    /// Local Monday the week starts on.
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub week_start: NaiveDate,
+    pub driving_minutes: i64,
+    pub work_minutes: i64,
+    /// Driving and other work. Availability and breaks are not working time.
+    pub working_time_minutes: i64,
+    /// Average weekly working time over the reference period ending with this week, taken over
+    /// fewer weeks at the start of the timeline.
+    pub average_working_time_minutes: f64,
+    pub exceeds_weekly_limit: bool,
+    pub exceeds_average_limit: bool,
+}
+
+/// Aggregates a driver's working time per local ISO week, as needed for working time directive
+/// reporting. The timeline may be built from several downloads of the same card: periods are
+/// sorted and time already covered by an earlier period is only counted once.
+/// Every week between the first and the last period is reported, including weeks without work.
+pub fn working_time_weekly_report(
+    timeline: &[ActivityPeriod],
+    config: &WorkingTimeConfig,
+) -> Vec<WorkingTimeWeek> {
+    let mut periods: Vec<&ActivityPeriod> = timeline
+        .iter()
+        .filter(|period| {
+            matches!(
+                period.activity,
+                ActivityChangeInfoCardActivity::Driving | ActivityChangeInfoCardActivity::Work
+            )
+        })
+        .collect();
+    periods.sort_by_key(|period| (period.start, period.end));
+    let (Some(first), Some(last)) = (
+        timeline.iter().map(|period| period.start).min(),
+        timeline.iter().map(|period| period.end).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let week_of = |time: DateTime<Utc>| {
+        let date = time.with_timezone(&config.utc_offset).date_naive();
+        date - Duration::days(date.weekday().num_days_from_monday() as i64)
+    };
+    let first_week = week_of(first);
+    let last_week = week_of(last - Duration::minutes(1)).max(first_week);
+    let mut weeks: Vec<WorkingTimeWeek> = first_week
+        .iter_weeks()
+        .take_while(|week_start| *week_start <= last_week)
+        .map(|week_start| {
+            let iso_week = week_start.iso_week();
+            WorkingTimeWeek {
+                iso_year: iso_week.year(),
+                iso_week: iso_week.week(),
+                week_start,
+                driving_minutes: 0,
+                work_minutes: 0,
+                working_time_minutes: 0,
+                average_working_time_minutes: 0.0,
+                exceeds_weekly_limit: false,
+                exceeds_average_limit: false,
+            }
+        })
+        .collect();
+
+    let mut covered_until = first;
+    for period in periods {
+        let unseen = ActivityPeriod {
+            start: period.start.max(covered_until),
+            ..period.clone()
+        };
+        if unseen.end <= unseen.start {
+            continue;
+        }
+        covered_until = unseen.end;
+        for week in weeks.iter_mut() {
+            let week_start = local_to_utc(week.week_start, NaiveTime::MIN, config.utc_offset);
+            let minutes = overlap_minutes(&unseen, week_start, week_start + Duration::weeks(1));
+            match unseen.activity {
+                ActivityChangeInfoCardActivity::Driving => week.driving_minutes += minutes,
+                _ => week.work_minutes += minutes,
+            }
+        }
+    }
+
+    for week in weeks.iter_mut() {
+        week.working_time_minutes = week.driving_minutes + week.work_minutes;
+        week.exceeds_weekly_limit = week.working_time_minutes > config.weekly_limit_minutes;
+    }
+    let reference_weeks = config.reference_weeks.max(1);
+    for index in 0..weeks.len() {
+        let reference = &weeks[(index + 1).saturating_sub(reference_weeks)..=index];
+        let average = reference
+            .iter()
+            .map(|week| week.working_time_minutes as f64)
+            .sum::<f64>()
+            / reference.len() as f64;
+        weeks[index].average_working_time_minutes = average;
+        weeks[index].exceeds_average_limit = average > config.average_limit_minutes as f64;
+    }
+    weeks
+}
+
+impl CardData {
+    /// KPIs over the card's whole activity timeline, see [`compute_kpis`].
+    pub fn fleet_kpis(&self, config: &KpiConfig) -> FleetKpis {
+        let card_driver_activity = self.card_driver_activity();
+        let daily_distances_km: Vec<(NaiveDate, Kilometers)> = card_driver_activity
+            .activity_daily_records
+            .iter()
+            .map(|record| {
+                (
+                    record.activity_record_date.0.date_naive(),
+                    record.activity_day_distance.0,
+                )
+            })
+            .collect();
+        compute_kpis(
+            &card_activity_timeline(card_driver_activity),
+            &daily_distances_km,
+            config,
+        )
+    }
+
+    /// Weekly working time over the card's activity timeline, see [`working_time_weekly_report`].
+    pub fn working_time_report(&self, config: &WorkingTimeConfig) -> Vec<WorkingTimeWeek> {
+        working_time_weekly_report(&self.activity_timeline(), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::test_helpers::{change, day};
+    use crate::analysis::{card_activity_timeline, ActivityOrigin, ActivityPeriod};
+    use crate::dt::{ActivityChangeInfoCardActivity, ActivityChangeInfoSlot, CardDriverActivity};
+    use crate::units::Kilometers;
+    use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Utc};
+
+    #[test]
+    fn test_timeline_and_kpis() {
+        use ActivityChangeInfoCardActivity::*;
+        // Friday: rest, driving 22:00 until Saturday 02:00, then rest
+        let activity = CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: vec![
+                day(
+                    "2024-03-01",
+                    300,
+                    vec![change(0, BreakRest), change(22 * 60, Driving)],
+                ),
+                day(
+                    "2024-03-02",
+                    100,
+                    vec![change(0, Driving), change(2 * 60, BreakRest)],
+                ),
+            ],
+        };
+        let timeline = card_activity_timeline(&activity);
+        // the driving period is merged across midnight
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline[1].activity, Driving);
+        assert_eq!(timeline[1].duration(), Duration::hours(4));
+
+        let distances = [
+            ("2024-03-01".parse().unwrap(), Kilometers(300)),
+            ("2024-03-02".parse().unwrap(), Kilometers(100)),
+        ];
+        let kpis = compute_kpis(&timeline, &distances, &KpiConfig::default());
+        assert_eq!(kpis.driving_minutes, 4 * 60);
+        assert_eq!(kpis.break_rest_minutes, 44 * 60);
+        assert_eq!(kpis.night_work_minutes, 2 * 60);
+        assert_eq!(kpis.night_work_days, 1);
+        assert_eq!(kpis.weekend_driving_minutes, 2 * 60);
+        assert_eq!(kpis.average_daily_distance_km, Some(200.0));
+
+        // a night window wrapping midnight, in UTC+1
+        let config = KpiConfig {
+            night_start: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            night_end: NaiveTime::from_hms_opt(5, 0, 0).unwrap(),
+            utc_offset: FixedOffset::east_opt(3600).unwrap(),
+        };
+        let kpis = compute_kpis(&timeline, &distances, &config);
+        // 22:00-02:00 UTC is 23:00-03:00 local, entirely inside the window
+        assert_eq!(kpis.night_work_minutes, 4 * 60);
+        assert_eq!(kpis.night_work_days, 1);
+        // Saturday local time starts at 23:00 UTC on Friday
+        assert_eq!(kpis.weekend_driving_minutes, 3 * 60);
+    }
+
+    #[test]
+    fn test_tacho_weeks_and_daily_periods() {
+        use ActivityChangeInfoCardActivity::*;
+        // a night shift from Sunday 18:00 to Monday 04:00, then two hours of work on Monday
+        let activity = CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: vec![
+                day(
+                    "2024-03-03",
+                    0,
+                    vec![change(0, BreakRest), change(18 * 60, Driving)],
+                ),
+                day(
+                    "2024-03-04",
+                    0,
+                    vec![
+                        change(0, Driving),
+                        change(4 * 60, BreakRest),
+                        change(14 * 60, Work),
+                        change(16 * 60, BreakRest),
+                    ],
+                ),
+            ],
+        };
+        let timeline = card_activity_timeline(&activity);
+
+        let weeks = tacho_weeks(&timeline);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!((weeks[0].iso_week, weeks[1].iso_week), (9, 10));
+        assert_eq!(
+            weeks[1].start,
+            "2024-03-04T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(weeks[0].driving_minutes, 6 * 60);
+        assert_eq!(weeks[1].driving_minutes, 4 * 60);
+        assert_eq!(weeks[1].work_minutes, 2 * 60);
+
+        let daily = daily_periods(&timeline, &DailyPeriodConfig::default());
+        assert_eq!(daily.len(), 2);
+        assert_eq!(
+            (daily[0].start, daily[0].end),
+            (
+                "2024-03-03T18:00:00Z".parse().unwrap(),
+                "2024-03-04T04:00:00Z".parse().unwrap()
+            )
+        );
+        assert_eq!(daily[0].driving_minutes, 10 * 60);
+        assert_eq!(daily[0].following_rest_minutes, Some(10 * 60));
+        // the final 8 hour rest is too short to end the period
+        assert_eq!(daily[1].work_minutes, 2 * 60);
+        assert_eq!(daily[1].break_rest_minutes, 8 * 60);
+        assert_eq!(daily[1].following_rest_minutes, None);
+
+        // without the Monday rest qualifying, the shift and the work share a daily period
+        let config = DailyPeriodConfig {
+            min_daily_rest_minutes: 11 * 60,
+            ..Default::default()
+        };
+        let daily = daily_periods(&timeline, &config);
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].break_rest_minutes, 18 * 60);
+    }
+
+    #[test]
+    fn test_working_time_weekly_report() {
+        use ActivityChangeInfoCardActivity::*;
+        let period = |start: &str, end: &str, activity| ActivityPeriod {
+            start: start.parse().unwrap(),
+            end: end.parse().unwrap(),
+            slot: ActivityChangeInfoSlot::Driver,
+            activity,
+            origin: ActivityOrigin::Recorded,
+        };
+        let driving = period("2024-03-04T06:00:00Z", "2024-03-05T20:00:00Z", Driving);
+        let timeline = vec![
+            driving.clone(),
+            period("2024-03-06T00:00:00Z", "2024-03-07T00:00:00Z", Work),
+            // the same period again from a later download
+            driving,
+            // work across the night from Sunday to Monday
+            period("2024-03-17T22:00:00Z", "2024-03-18T02:00:00Z", Work),
+            period("2024-03-18T02:00:00Z", "2024-03-19T00:00:00Z", BreakRest),
+        ];
+        let config = WorkingTimeConfig {
+            reference_weeks: 2,
+            ..WorkingTimeConfig::default()
+        };
+        let report = working_time_weekly_report(&timeline, &config);
+        let summary: Vec<(u32, i64, i64, f64, bool, bool)> = report
+            .iter()
+            .map(|week| {
+                (
+                    week.iso_week,
+                    week.driving_minutes,
+                    week.working_time_minutes,
+                    week.average_working_time_minutes,
+                    week.exceeds_weekly_limit,
+                    week.exceeds_average_limit,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (10, 38 * 60, 62 * 60, 3720.0, true, true),
+                (11, 0, 2 * 60, 1920.0, false, false),
+                (12, 0, 2 * 60, 120.0, false, false),
+            ]
+        );
+        assert_eq!(report[0].week_start, "2024-03-04".parse().unwrap());
+        assert!(working_time_weekly_report(&[], &config).is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+//! CBOR and MessagePack output, self-describing like the JSON output but smaller, e.g. for
+//! message buses with a payload limit.
+//!
+//! The document is the one [`crate::json`] writes: same field names, and the [`JsonOptions`]
+//! apply except `pretty`. Each format needs its cargo feature, `cbor` or `msgpack`.
+use crate::json::{self, JsonOptions, Provenance, ProvenanceEnvelope, SourceDigest};
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryFormat {
+    Cbor,
+    /// Maps with the field names, not the positional arrays MessagePack encoders default to.
+    MessagePack,
+}
+impl FromStr for BinaryFormat {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "cbor" => Ok(BinaryFormat::Cbor),
+            "msgpack" | "messagepack" => Ok(BinaryFormat::MessagePack),
+            _ => anyhow::bail!("Unsupported binary format: {}", value),
+        }
+    }
+}
+
+pub fn write_binary<T: Serialize, W: Write>(
+    data: &T,
+    format: BinaryFormat,
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    let (document, _guard) = json::prepare(data, options)?;
+    match format {
+        BinaryFormat::Cbor => write_cbor(&document, writer),
+        BinaryFormat::MessagePack => write_msgpack(&document, writer),
+    }
+}
+
+pub fn to_binary<T: Serialize>(
+    data: &T,
+    format: BinaryFormat,
+    options: &JsonOptions,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    write_binary(data, format, options, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Same as [`write_binary`], with `data` wrapped in a [`ProvenanceEnvelope`] for `input` when
+/// [`JsonOptions::provenance`] is set.
+pub fn write_binary_with_source<T: Serialize, W: Write>(
+    data: &T,
+    input: &[u8],
+    format: BinaryFormat,
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    if !options.provenance {
+        return write_binary(data, format, options, writer);
+    }
+    write_binary_with_digest(data, &SourceDigest::new(input), format, options, writer)
+}
+
+/// Same as [`write_binary_with_source`], for a source file only `source` is kept of.
+pub(crate) fn write_binary_with_digest<T: Serialize, W: Write>(
+    data: &T,
+    source: &SourceDigest,
+    format: BinaryFormat,
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    if !options.provenance {
+        return write_binary(data, format, options, writer);
+    }
+    let envelope = ProvenanceEnvelope {
+        provenance: Provenance::from_digest(source),
+        data,
+    };
+    write_binary(&envelope, format, options, writer)
+}
+
+pub fn to_binary_with_source<T: Serialize>(
+    data: &T,
+    input: &[u8],
+    format: BinaryFormat,
+    options: &JsonOptions,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    write_binary_with_source(data, input, format, options, &mut buffer)?;
+    Ok(buffer)
+}
+
+pub(crate) fn to_binary_with_digest<T: Serialize>(
+    data: &T,
+    source: &SourceDigest,
+    format: BinaryFormat,
+    options: &JsonOptions,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    write_binary_with_digest(data, source, format, options, &mut buffer)?;
+    Ok(buffer)
+}
+
+#[cfg(feature = "cbor")]
+fn write_cbor<T: Serialize, W: Write>(data: &T, writer: W) -> Result<()> {
+    use anyhow::Context;
+    ciborium::into_writer(data, writer).context("Failed to serialize to CBOR")
+}
+
+#[cfg(not(feature = "cbor"))]
+fn write_cbor<T: Serialize, W: Write>(_data: &T, _writer: W) -> Result<()> {
+    anyhow::bail!("CBOR output needs the `cbor` feature of tachograph_parser")
+}
+
+#[cfg(feature = "msgpack")]
+fn write_msgpack<T: Serialize, W: Write>(data: &T, mut writer: W) -> Result<()> {
+    use anyhow::Context;
+    rmp_serde::encode::write_named(&mut writer, data).context("Failed to serialize to MessagePack")
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn write_msgpack<T: Serialize, W: Write>(_data: &T, _writer: W) -> Result<()> {
+    anyhow::bail!("MessagePack output needs the `msgpack` feature of tachograph_parser")
+}
+
+#[cfg(all(test, feature = "cbor", feature = "msgpack"))]
+mod tests {
+    use super::*;
+    use crate::json::TimestampFormat;
+    use crate::vu_parser::VuParser;
+    use serde_json::Value;
+
+    #[test]
+    fn test_binary_matches_json() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let vu_data = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+        let options = JsonOptions {
+            timestamp_format: TimestampFormat::UnixSeconds,
+            ..Default::default()
+        };
+        let json: Value =
+            serde_json::from_str(&json::to_json(&vu_data, &options).unwrap()).unwrap();
+
+        let cbor = to_binary(&vu_data, BinaryFormat::Cbor, &options).unwrap();
+        let from_cbor: Value = ciborium::from_reader(&cbor[..]).unwrap();
+        assert_eq!(from_cbor, json);
+
+        let msgpack = to_binary(&vu_data, BinaryFormat::MessagePack, &options).unwrap();
+        let from_msgpack: Value = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(from_msgpack, json);
+        assert!(msgpack.len() < json.to_string().len());
+    }
+}
@@ -1,4 +1,6 @@
-use std::io::Cursor;
+use crate::trace;
+use byteorder::ByteOrder;
+use std::io::{self, Cursor};
 
 pub fn extract_u8_bits_into_tup(byte: u8) -> (u8, u8, u8, u8, u8, u8, u8, u8) {
     (
@@ -53,22 +55,75 @@ pub fn extract_u16_bits_into_tup(
 }
 
 pub trait TakeExact {
-    fn take_exact(&mut self, size: usize) -> Cursor<&[u8]>;
+    /// A cursor over the next `size` bytes, which this cursor moves past. Fails with
+    /// [`io::ErrorKind::UnexpectedEof`] when fewer bytes remain, like the reads do.
+    fn take_exact(&mut self, size: usize) -> io::Result<Cursor<&[u8]>>;
 }
 
 impl TakeExact for Cursor<&[u8]> {
-    fn take_exact(&mut self, size: usize) -> Cursor<&[u8]> {
-        let position = self.position() as usize;
+    fn take_exact(&mut self, size: usize) -> io::Result<Cursor<&[u8]>> {
+        let position = (self.position() as usize).min(self.get_ref().len());
         let remaining = self.get_ref().len() - position;
         if size > remaining {
-            panic!(
-                "Attempted to take {} bytes, but only {} bytes remain",
-                size, remaining
-            );
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Attempted to take {} bytes, but only {} bytes remain",
+                    size, remaining
+                ),
+            ));
         }
         let end = position + size;
         let slice = &self.get_ref()[position..end];
         self.set_position(end as u64);
-        Cursor::new(slice)
+        Ok(Cursor::new(slice))
+    }
+}
+
+/// Primitive reads used by the `dt` parsers. Mirrors the `byteorder::ReadBytesExt` and
+/// `std::io::Read` methods of the same name, and reports every successful read to [`trace`].
+pub trait TracedRead {
+    fn read_u8(&mut self) -> io::Result<u8>;
+    fn read_u16<B: ByteOrder>(&mut self) -> io::Result<u16>;
+    fn read_u32<B: ByteOrder>(&mut self) -> io::Result<u32>;
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()>;
+}
+
+impl TracedRead for Cursor<&[u8]> {
+    #[track_caller]
+    fn read_u8(&mut self) -> io::Result<u8> {
+        let start = self.position();
+        let value = byteorder::ReadBytesExt::read_u8(self)?;
+        trace::record(self.get_ref(), start, 1, "u8", || {
+            format!("{:#04x} ({})", value, value)
+        });
+        Ok(value)
+    }
+    #[track_caller]
+    fn read_u16<B: ByteOrder>(&mut self) -> io::Result<u16> {
+        let start = self.position();
+        let value = byteorder::ReadBytesExt::read_u16::<B>(self)?;
+        trace::record(self.get_ref(), start, 2, "u16", || {
+            format!("{:#06x} ({})", value, value)
+        });
+        Ok(value)
+    }
+    #[track_caller]
+    fn read_u32<B: ByteOrder>(&mut self) -> io::Result<u32> {
+        let start = self.position();
+        let value = byteorder::ReadBytesExt::read_u32::<B>(self)?;
+        trace::record(self.get_ref(), start, 4, "u32", || {
+            format!("{:#010x} ({})", value, value)
+        });
+        Ok(value)
+    }
+    #[track_caller]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let start = self.position();
+        io::Read::read_exact(self, buf)?;
+        trace::record(self.get_ref(), start, buf.len(), "bytes", || {
+            trace::summarize_bytes(buf)
+        });
+        Ok(())
     }
 }
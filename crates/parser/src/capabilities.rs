@@ -0,0 +1,214 @@
+use crate::card_parser::EfTag;
+use crate::detector::TachoFileType;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum Generation {
+    Gen1,
+    Gen2,
+    Gen2V2,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A card EF the parser decodes. Its signature EF, when the EF has one, is decoded as well.
+pub struct CardEfCapability {
+    pub sfid: u16,
+    pub name: String,
+    /// First generation whose DF contains the EF: Gen1 EFs are read from both the Tachograph
+    /// and the Tachograph_G2 DF, Gen2 and Gen2V2 EFs from the Tachograph_G2 DF only.
+    pub since: Generation,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A VU download block the parser decodes.
+pub struct VuBlockCapability {
+    pub trep: u8,
+    pub name: String,
+    pub generation: Generation,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// What this build of the parser can do, see [`capabilities`].
+pub struct Capabilities {
+    /// Version of the crate this description belongs to.
+    pub version: String,
+    pub file_types: Vec<TachoFileType>,
+    pub card_efs: Vec<CardEfCapability>,
+    pub vu_blocks: Vec<VuBlockCapability>,
+    /// Helpers built on top of the parsed data, by the name of their function.
+    pub analysis: Vec<String>,
+    /// Optional cargo features this build was compiled with.
+    pub features: Vec<String>,
+}
+
+const VU_BLOCKS: [(u8, &str, Generation); 14] = [
+    (0x01, "VuOverview", Generation::Gen1),
+    (0x02, "VuActivities", Generation::Gen1),
+    (0x03, "VuEventsAndFaults", Generation::Gen1),
+    (0x04, "VuDetailedSpeed", Generation::Gen1),
+    (0x05, "VuCompanyLocks", Generation::Gen1),
+    (0x21, "VuOverview", Generation::Gen2),
+    (0x22, "VuActivities", Generation::Gen2),
+    (0x23, "VuEventsAndFaults", Generation::Gen2),
+    (0x24, "VuDetailedSpeed", Generation::Gen2),
+    (0x25, "VuCompanyLocks", Generation::Gen2),
+    (0x31, "VuOverview", Generation::Gen2V2),
+    (0x32, "VuActivities", Generation::Gen2V2),
+    (0x33, "VuEventsAndFaults", Generation::Gen2V2),
+    (0x35, "VuCompanyLocks", Generation::Gen2V2),
+];
+
+const ANALYSIS: [&str; 52] = [
+    "CardData::activity_timeline",
+    "CardData::activity_timeline_by_day",
+    "CardData::fleet_kpis",
+    "CardData::activity_memory_estimate",
+    "CardData::chip_identity",
+    "CardData::vehicles_used",
+    "CardData::consistency_report",
+    "CardData::distance_reconciliation",
+    "CardData::working_time_report",
+    "CardData::teleports",
+    "CardData::quality_score",
+    "VuData::activity_timeline",
+    "VuData::activity_timeline_by_day",
+    "VuData::crew_sessions",
+    "VuData::speed_traces",
+    "VuData::distance_checks",
+    "VuData::driver_speed_segments",
+    "VuData::teleports",
+    "VuData::quality_score",
+    "VuData::consistency_report",
+    "VuData::daily_printout",
+    "SpeedTrace::downsample",
+    "VuData::bytes_unaccounted",
+    "VuData::merge",
+    "VuData::events",
+    "VuData::events_sorted",
+    "VuData::calibrations",
+    "VuData::activity_days",
+    "VuData::time_adjustment_audit",
+    "VuData::clock_corrected_timeline",
+    "analysis::daily_activity_totals",
+    "analysis::activities_by_day",
+    "analysis::tacho_weeks",
+    "analysis::daily_periods",
+    "analysis::find_duplicate_card_serials",
+    "analysis::calibrations",
+    "analysis::driver_events",
+    "analysis::reconcile_distances",
+    "analysis::merge_card_days",
+    "analysis::normalize_speeds",
+    "analysis::normalize_event_times",
+    "analysis::clock_corrected_timeline",
+    "transform::minimize",
+    "validation::validate_bytes",
+    "CardParser::ef_coverage",
+    "CardParser::ef_order_deviations",
+    "VuParser::block_coverage",
+    "VuParser::new_from_reader",
+    "peek_card_identification",
+    "peek_vu_identification",
+    "fingerprint::fingerprint",
+    "record_id::record_id",
+];
+
+/// Describes the file types, EFs, VU blocks and analysis helpers supported by this build.
+/// Anything not listed is either skipped or preserved raw, see [`crate::diagnostics`] and
+/// [`crate::card_parser::RawCardEf`].
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        file_types: TachoFileType::ALL.to_vec(),
+        // grouped by generation, in download order within each
+        card_efs: [Generation::Gen1, Generation::Gen2, Generation::Gen2V2]
+            .into_iter()
+            .flat_map(|generation| EfTag::all().filter(move |tag| tag.since() == Some(generation)))
+            .map(|tag| CardEfCapability {
+                sfid: tag.fid(),
+                name: tag.name().unwrap_or_default().to_string(),
+                since: tag.since().unwrap_or(Generation::Gen1),
+            })
+            .collect(),
+        vu_blocks: VU_BLOCKS
+            .iter()
+            .map(|(trep, name, generation)| VuBlockCapability {
+                trep: *trep,
+                name: name.to_string(),
+                generation: *generation,
+            })
+            .collect(),
+        analysis: ANALYSIS.iter().map(|name| name.to_string()).collect(),
+        features: features(),
+    }
+}
+
+/// Optional cargo features this build was compiled with.
+pub(crate) fn features() -> Vec<String> {
+    let mut features = Vec::new();
+    if cfg!(feature = "ts") {
+        features.push("ts".to_string());
+    }
+    if cfg!(feature = "simd-json") {
+        features.push("simd-json".to_string());
+    }
+    if cfg!(feature = "smallvec") {
+        features.push("smallvec".to_string());
+    }
+    if cfg!(feature = "cbor") {
+        features.push("cbor".to_string());
+    }
+    if cfg!(feature = "msgpack") {
+        features.push("msgpack".to_string());
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_parser;
+    use crate::vu_parser::VuParser;
+
+    #[test]
+    fn test_capabilities_match_parsers() {
+        let capabilities = capabilities();
+        for ef in &capabilities.card_efs {
+            let file_id = match ef.since {
+                Generation::Gen1 => 0,
+                Generation::Gen2 | Generation::Gen2V2 => 2,
+            };
+            // an empty EF fails most parsers, which still means they know it
+            let [sfid_high, sfid_low] = ef.sfid.to_be_bytes();
+            let coverage =
+                card_parser::ef_coverage(&[sfid_high, sfid_low, file_id, 0x00, 0x00]).unwrap();
+            assert_ne!(
+                coverage[0].status,
+                card_parser::EfParseStatus::PreservedRaw,
+                "{} is not handled by the card parser",
+                ef.name
+            );
+        }
+        let known_treps: Vec<u8> = [
+            &VuParser::GEN1_TREPS[..],
+            &VuParser::GEN2_TREPS[..],
+            &VuParser::GEN2V2_TREPS[..],
+        ]
+        .concat();
+        let listed_treps: Vec<u8> = capabilities
+            .vu_blocks
+            .iter()
+            .map(|block| block.trep)
+            .collect();
+        assert_eq!(listed_treps, known_treps);
+    }
+}
@@ -1,15 +1,28 @@
+use crate::binary::{self, BinaryFormat};
+use crate::bytes::{TakeExact, TracedRead};
+use crate::capabilities::Generation;
+use crate::context::{with_parse_context, ParseSettings};
+use crate::detector::TachoFileType;
+use crate::diagnostics::{CardDiagnostics, TruncatedBlock};
 use crate::dt::gen1;
 use crate::dt::gen2;
 use crate::dt::gen2v2;
 use crate::dt::{self};
+use crate::extensions::{CardExtension, ExtensionRegistry};
+use crate::json::{self, JsonOptions};
+use crate::locale::Locale;
+use crate::sink::{self, TachoSink};
+use crate::source::{self, TachoRead};
+use crate::trace::{self, TraceEntry};
 use anyhow::{Context, Result};
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::BigEndian;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::io::{BufRead, Cursor, Read};
+use std::io::{BufRead, Cursor};
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardGen1Blocks {
@@ -45,15 +58,18 @@ pub struct CardGen1Blocks {
     pub control_activity_data_signature: gen1::Signature,
     pub specific_conditions: gen1::SpecificConditions,
     pub specific_conditions_signature: gen1::Signature,
+    /// EFs of the Tachograph DF the parser does not know about, kept as raw bytes.
+    /// On a card without Gen2 data, this also holds the unknown EFs with a Gen2 file_id.
+    pub unknown_efs: Vec<RawCardEf>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardGen2Blocks {
     pub card_icc_identification: gen2::CardIccIdentificationGen2,
     pub card_chip_identification: dt::CardChipIdentification,
-    pub application_identification: gen2::DriverCardApplicationIdentificationGen2,
+    pub application_identification: gen2::ApplicationIdentificationGen2,
     pub application_identification_signature: gen2::SignatureGen2,
     pub card_sign_certificate: gen2::CertificateGen2,
     pub ca_certificate: gen2::CertificateGen2,
@@ -84,12 +100,18 @@ pub struct CardGen2Blocks {
     pub vehicle_units_used_signature: gen2::SignatureGen2,
     pub gnss_accumulated_driving: gen2::GnssAccumulatedDrivingGen2,
     pub gnss_places_signature: gen2::SignatureGen2,
+    /// EFs of the Tachograph_G2 DF the parser does not know about (e.g. manufacturer or
+    /// member state specific identification data), kept as raw bytes.
+    pub unknown_efs: Vec<RawCardEf>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardGen2V2Blocks {
+    /// The Tachograph_G2 CardIccIdentification decoded with the version 2 layout, which
+    /// keeps bytes the Gen2 layout does not know about.
+    pub card_icc_identification: Option<gen2v2::CardIccIdentificationGen2V2>,
     pub application_identification: gen2v2::DriverCardApplicationIdentificationGen2V2,
     pub application_identification_signature: gen2::SignatureGen2,
     pub places_authentication: gen2v2::CardPlacesAuthDailyWorkPeriod,
@@ -106,1226 +128,1834 @@ pub struct CardGen2V2Blocks {
     pub vu_configurations_signature: Option<gen2::SignatureGen2>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "generation")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum CardData {
     #[serde(rename_all = "camelCase")]
-    Gen1 { gen1_blocks: CardGen1Blocks },
+    Gen1 {
+        gen1_blocks: CardGen1Blocks,
+        #[serde(default)]
+        extensions: Vec<CardExtension>,
+        #[serde(default)]
+        diagnostics: CardDiagnostics,
+    },
     #[serde(rename_all = "camelCase")]
     Gen2 {
         gen1_blocks: CardGen1Blocks,
         gen2_blocks: CardGen2Blocks,
+        #[serde(default)]
+        extensions: Vec<CardExtension>,
+        #[serde(default)]
+        diagnostics: CardDiagnostics,
     },
     #[serde(rename_all = "camelCase")]
     Gen2V2 {
         gen1_blocks: CardGen1Blocks,
         gen2_blocks: CardGen2Blocks,
         gen2v2_blocks: CardGen2V2Blocks,
+        #[serde(default)]
+        extensions: Vec<CardExtension>,
+        #[serde(default)]
+        diagnostics: CardDiagnostics,
     },
 }
 
-fn panic_on_duplicate_block_type(block_type: &str) {
-    panic!("{}: duplicate block type detected. This suggests an unexpected structure in the Card files, where multiple instances of the same block type are present within a single generation. This indicates a bug in the parser.", block_type);
+impl CardData {
+    /// Card files of every kind are reported as `DriverCard*`, see [`crate::detector::CardKind`].
+    pub fn file_type(&self) -> TachoFileType {
+        match self {
+            CardData::Gen1 { .. } => TachoFileType::DriverCardGen1,
+            CardData::Gen2 { .. } => TachoFileType::DriverCardGen2,
+            CardData::Gen2V2 { .. } => TachoFileType::DriverCardGen2V2,
+        }
+    }
+
+    pub fn diagnostics(&self) -> &CardDiagnostics {
+        match self {
+            CardData::Gen1 { diagnostics, .. }
+            | CardData::Gen2 { diagnostics, .. }
+            | CardData::Gen2V2 { diagnostics, .. } => diagnostics,
+        }
+    }
+
+    /// Unknown EFs of all generations decoded by the parsers registered with
+    /// [`CardParser::extensions`].
+    pub fn extensions(&self) -> &[CardExtension] {
+        match self {
+            CardData::Gen1 { extensions, .. }
+            | CardData::Gen2 { extensions, .. }
+            | CardData::Gen2V2 { extensions, .. } => extensions,
+        }
+    }
+
+    /// Preferred language of the card holder, taken from the Tachograph_G2 DF when present
+    /// since that is the copy Gen2 VUs read. VU downloads do not record a language of their own.
+    pub fn card_holder_preferred_language(&self) -> &dt::Language {
+        let identification = match self {
+            CardData::Gen1 { gen1_blocks, .. } => &gen1_blocks.identification,
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                &gen2_blocks.identification
+            }
+        };
+        &identification
+            .driver_card_holder_identification
+            .card_holder_preferred_language
+    }
+
+    /// [`Locale`] of the card holder's preferred language, so that reports can be shown in the
+    /// language the VU displays to this driver. `None` for languages without translations.
+    pub fn card_holder_locale(&self) -> Option<Locale> {
+        Locale::from_language(self.card_holder_preferred_language())
+    }
+
+    /// Estimates how long until the cyclic activity buffer starts overwriting the oldest day.
+    /// Uses the latest generation's activity data. Returns `None` for cards without driver activity.
+    pub fn activity_memory_estimate(&self) -> Option<ActivityMemoryEstimate> {
+        let (driver_activity_data, activity_structure_length) = match self {
+            CardData::Gen1 { gen1_blocks, .. } => {
+                let activity_structure_length = match &gen1_blocks.application_identification {
+                    gen1::ApplicationIdentification::DriverCard(app) => {
+                        app.activity_structure_length
+                    }
+                    gen1::ApplicationIdentification::WorkshopCard(app) => {
+                        app.activity_structure_length
+                    }
+                    _ => return None,
+                };
+                (&gen1_blocks.driver_activity_data, activity_structure_length)
+            }
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+                let activity_structure_length = match &gen2_blocks.application_identification {
+                    gen2::ApplicationIdentificationGen2::DriverCard(app) => {
+                        app.activity_structure_length
+                    }
+                    gen2::ApplicationIdentificationGen2::WorkshopCard(app) => {
+                        app.activity_structure_length
+                    }
+                    _ => return None,
+                };
+                (&gen2_blocks.driver_activity_data, activity_structure_length)
+            }
+        };
+        Some(estimate_activity_memory(
+            &driver_activity_data.card_driver_activity,
+            activity_structure_length,
+        ))
+    }
+
+    /// Identifiers of the card's chip, for correlating downloads of the same physical card.
+    /// EF_ICC and EF_IC live in the MF, so they are the same in both DFs; the Gen1 copy is used.
+    pub fn chip_identity(&self) -> CardChipIdentity {
+        let gen1_blocks = match self {
+            CardData::Gen1 { gen1_blocks, .. }
+            | CardData::Gen2 { gen1_blocks, .. }
+            | CardData::Gen2V2 { gen1_blocks, .. } => gen1_blocks,
+        };
+        CardChipIdentity {
+            extended_serial_number: gen1_blocks
+                .card_icc_identification
+                .card_extended_serial_number
+                .to_serial_string(),
+            chip_identification: gen1_blocks.card_chip_identification.to_hex(),
+            card_number: sink::card_number_to_string(
+                &gen1_blocks.identification.card_identification.card_number,
+            ),
+        }
+    }
 }
 
-pub struct CardParser {
-    input: Vec<u8>,
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// See [`CardData::chip_identity`].
+pub struct CardChipIdentity {
+    /// `card_extended_serial_number` of EF_ICC, see [`gen1::ExtendedSerialNumber::to_serial_string`].
+    pub extended_serial_number: String,
+    /// EF_IC as hex, see [`dt::CardChipIdentification::to_hex`].
+    pub chip_identification: String,
+    pub card_number: Option<String>,
 }
-impl CardParser {
-    pub fn new_from_file(file_path: &str) -> Result<Self> {
-        let input = std::fs::read(file_path).expect("Failed to read file");
-        Ok(CardParser { input })
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Fill level of the card's cyclic activity buffer, see [`CardData::activity_memory_estimate`].
+pub struct ActivityMemoryEstimate {
+    /// `activity_structure_length` from the application identification.
+    pub capacity_bytes: usize,
+    pub used_bytes: usize,
+    pub free_bytes: usize,
+    pub daily_records: usize,
+    pub average_daily_record_bytes: Option<usize>,
+    /// Daily records of average size that still fit before the oldest one is overwritten.
+    pub daily_records_until_overwrite: Option<usize>,
+    /// Day on which the oldest record is expected to be overwritten, assuming the card keeps
+    /// recording days at the same rate as it did over the stored period.
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub projected_overwrite_date: Option<DateTime<Utc>>,
+}
+
+/// See [`CardData::activity_memory_estimate`].
+pub fn estimate_activity_memory(
+    card_driver_activity: &dt::CardDriverActivity,
+    activity_structure_length: u16,
+) -> ActivityMemoryEstimate {
+    let records = &card_driver_activity.activity_daily_records;
+    let capacity_bytes = activity_structure_length as usize;
+    let used_bytes = records
+        .iter()
+        .map(|record| record.activity_record_length as usize)
+        .sum::<usize>()
+        .min(capacity_bytes);
+    let free_bytes = capacity_bytes - used_bytes;
+    let average_daily_record_bytes = (!records.is_empty())
+        .then(|| used_bytes / records.len())
+        .filter(|average| *average > 0);
+    let daily_records_until_overwrite =
+        average_daily_record_bytes.map(|average| free_bytes / average);
+
+    // Days without activity produce no record, so project with the observed records per calendar day
+    let projected_overwrite_date = match (
+        records.first(),
+        records.last(),
+        daily_records_until_overwrite,
+    ) {
+        (Some(oldest), Some(newest), Some(remaining)) => {
+            let newest_date = newest.activity_record_date.0;
+            let stored_days = (newest_date - oldest.activity_record_date.0).num_days() + 1;
+            let records_per_day = records.len() as f64 / stored_days.max(1) as f64;
+            // The first record that does not fit is the one that overwrites the oldest day
+            let days_until_overwrite = ((remaining + 1) as f64 / records_per_day).ceil() as i64;
+            Some(newest_date + Duration::days(days_until_overwrite))
+        }
+        _ => None,
+    };
+
+    ActivityMemoryEstimate {
+        capacity_bytes,
+        used_bytes,
+        free_bytes,
+        daily_records: records.len(),
+        average_daily_record_bytes,
+        daily_records_until_overwrite,
+        projected_overwrite_date,
     }
-    pub fn new_from_bytes(bytes: &[u8]) -> Result<Self> {
-        Ok(CardParser {
-            input: bytes.to_vec(),
-        })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An EF present in the file that the parser has no structure for, such as the proprietary EFs
+/// some manufacturers add after the standard set.
+/// It deserializes back from the JSON output unchanged, and [`RawCardEf::to_bytes`] restores it
+/// as it was in the downloaded file, so re-export tools can keep vendor data.
+pub struct RawCardEf {
+    /// File identifier of the EF.
+    pub sfid: u16,
+    /// Appendix byte following the file identifier: 0 for Gen1 data, 1 for its signature,
+    /// 2 for Gen2 data and 3 for its signature.
+    pub file_id: u8,
+    pub data: Vec<u8>,
+}
+impl RawCardEf {
+    /// Encodes the EF with its header (file identifier, appendix byte and length), as in a card download.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(5 + self.data.len());
+        bytes.extend_from_slice(&self.sfid.to_be_bytes());
+        bytes.push(self.file_id);
+        bytes.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
     }
+}
 
-    pub fn parse(&self) -> Result<CardData> {
-        let mut cursor = Cursor::new(&self.input[..]);
-        let mut card_icc_identification: Option<gen1::CardIccIdentification> = None;
-        let mut card_chip_identification: Option<dt::CardChipIdentification> = None;
-        let mut application_identification: Option<gen1::ApplicationIdentification> = None;
-        let mut application_identification_signature: Option<gen1::Signature> = None;
-        let mut card_certificate: Option<gen1::Certificate> = None;
-        let mut member_state_certificate: Option<gen1::Certificate> = None;
-        let mut identification: Option<dt::Identification> = None;
-        let mut identification_signature: Option<gen1::Signature> = None;
-        let mut card_download: Option<dt::CardDownload> = None;
-        let mut card_download_signature: Option<gen1::Signature> = None;
-        let mut calibration: Option<gen1::WorkshopCardCalibrationData> = None;
-        let mut calibration_signature: Option<gen1::Signature> = None;
-        let mut sensor_installation_data: Option<gen1::SensorInstallation> = None;
-        let mut sensor_installation_data_signature: Option<gen1::Signature> = None;
-        let mut driver_licence_info: Option<dt::CardDrivingLicenceInformation> = None;
-        let mut driver_licence_info_signature: Option<gen1::Signature> = None;
-        let mut events_data: Option<gen1::CardEventData> = None;
-        let mut events_data_signature: Option<gen1::Signature> = None;
-        let mut faults_data: Option<gen1::CardFaultData> = None;
-        let mut faults_data_signature: Option<gen1::Signature> = None;
-        let mut driver_activity_data: Option<dt::DriverActivityData> = None;
-        let mut driver_activity_data_signature: Option<gen1::Signature> = None;
-        let mut vehicles_used: Option<gen1::CardVehiclesUsed> = None;
-        let mut vehicles_used_signature: Option<gen1::Signature> = None;
-        let mut places: Option<gen1::CardPlaceDailyWorkPeriod> = None;
-        let mut places_signature: Option<gen1::Signature> = None;
-        let mut current_usage: Option<dt::CurrentUsage> = None;
-        let mut current_usage_signature: Option<gen1::Signature> = None;
-        let mut control_activity_data: Option<gen1::CardControlActivityDataRecord> = None;
-        let mut control_activity_data_signature: Option<gen1::Signature> = None;
-        let mut specific_conditions: Option<gen1::SpecificConditions> = None;
-        let mut specific_conditions_signature: Option<gen1::Signature> = None;
-
-        // GEN2
-        let mut card_icc_identification_gen2: Option<gen2::CardIccIdentificationGen2> = None;
-        let mut card_chip_identification_gen2: Option<dt::CardChipIdentification> = None;
-        let mut application_identification_gen2: Option<
-            gen2::DriverCardApplicationIdentificationGen2,
-        > = None;
-        let mut application_identification_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut card_sign_certificate_gen2: Option<gen2::CertificateGen2> = None;
-        let mut ca_certificate_gen2: Option<gen2::CertificateGen2> = None;
-        let mut link_certificate_gen2: Option<gen2::CertificateGen2> = None;
-        let mut identification_gen2: Option<dt::Identification> = None;
-        let mut identification_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut card_download_gen2: Option<dt::CardDownload> = None;
-        let mut card_download_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut driver_licence_info_gen2: Option<dt::CardDrivingLicenceInformation> = None;
-        let mut driver_licence_info_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut events_data_gen2: Option<gen2::CardEventDataGen2> = None;
-        let mut events_data_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut faults_data_gen2: Option<gen2::CardFaultDataGen2> = None;
-        let mut faults_data_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut driver_activity_data_gen2: Option<dt::DriverActivityData> = None;
-        let mut driver_activity_data_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut vehicles_used_gen2: Option<gen2::CardVehiclesUsedGen2> = None;
-        let mut vehicles_used_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut places_gen2: Option<gen2::CardPlaceDailyWorkPeriodGen2> = None;
-        let mut places_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut current_usage_gen2: Option<dt::CurrentUsage> = None;
-        let mut current_usage_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut control_activity_data_gen2: Option<gen2::CardControlActivityDataRecordGen2> = None;
-        let mut control_activity_data_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut specific_conditions_gen2: Option<gen2::SpecificConditionsGen2> = None;
-        let mut specific_conditions_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut vehicle_units_used_gen2: Option<gen2::CardVehicleUnitsUsedGen2> = None;
-        let mut vehicle_units_used_signature_gen2: Option<gen2::SignatureGen2> = None;
-        let mut gnss_places_gen2: Option<gen2::GnssAccumulatedDrivingGen2> = None;
-        let mut gnss_places_signature_gen2: Option<gen2::SignatureGen2> = None;
-
-        // GEN2V2
-        let mut application_identification_gen2v2: Option<
-            gen2v2::DriverCardApplicationIdentificationGen2V2,
-        > = None;
-        let mut application_identification_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut places_authentication_gen2v2: Option<gen2v2::CardPlacesAuthDailyWorkPeriod> = None;
-        let mut places_authentication_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut gnss_places_authentication_gen2v2: Option<gen2v2::GNSSAuthAccumulatedDriving> =
-            None;
-        let mut gnss_places_authentication_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut border_crossings_gen2v2: Option<gen2v2::CardBorderCrossings> = None;
-        let mut border_crossings_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut load_unload_operations_gen2v2: Option<gen2v2::CardLoadUnloadOperations> = None;
-        let mut load_unload_operations_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut load_type_entries_gen2v2: Option<gen2v2::CardLoadTypeEntries> = None;
-        let mut load_type_entries_signature_gen2v2: Option<gen2::SignatureGen2> = None;
-        let mut vu_configurations_gen2v2: Option<gen2v2::VuConfigurations> = None;
-        let mut vu_configurations_signature_gen2v2: Option<gen2::SignatureGen2> = None;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A card EF, by the file identifier in front of it in a card download. The byte after the
+/// identifier is the [`EfAppendix`].
+pub enum EfTag {
+    CardIccIdentification,
+    CardChipIdentification,
+    ApplicationIdentification,
+    CardCertificate,
+    CardSignCertificate,
+    CaCertificate,
+    LinkCertificate,
+    Identification,
+    CardDownload,
+    WorkshopCardDownload,
+    Calibration,
+    SensorInstallationData,
+    DrivingLicenceInfo,
+    EventsData,
+    FaultsData,
+    DriverActivityData,
+    VehiclesUsed,
+    Places,
+    CurrentUsage,
+    ControlActivityData,
+    SpecificConditions,
+    VehicleUnitsUsed,
+    GnssPlaces,
+    ApplicationIdentificationV2,
+    PlacesAuthentication,
+    GnssPlacesAuthentication,
+    BorderCrossings,
+    LoadUnloadOperations,
+    LoadTypeEntries,
+    VuConfigurations,
+    /// An EF the parser has no structure for, kept as a [`RawCardEf`].
+    Unknown(u16),
+}
+
+/// Every known EF in the order of the card file structures of Appendix 2 (TCS_150 to TCS_155),
+/// with its file identifier, which [`EfTag::from_fid`] and [`EfTag::fid`] look up here, its name
+/// and the first generation whose DF contains it. Workshop cards hold their download date in
+/// 0x0509 instead of 0x050E.
+#[rustfmt::skip]
+const EF_TAGS: [(EfTag, u16, &str, Generation); 30] = [
+    (EfTag::CardIccIdentification, 0x0002, "CardIccIdentification", Generation::Gen1),
+    (EfTag::CardChipIdentification, 0x0005, "CardChipIdentification", Generation::Gen1),
+    (EfTag::ApplicationIdentification, 0x0501, "ApplicationIdentification", Generation::Gen1),
+    (EfTag::CardCertificate, 0xC100, "CardCertificate", Generation::Gen1),
+    (EfTag::CardSignCertificate, 0xC101, "CardSignCertificate", Generation::Gen2),
+    (EfTag::CaCertificate, 0xC108, "CaCertificate", Generation::Gen1),
+    (EfTag::LinkCertificate, 0xC109, "LinkCertificate", Generation::Gen2),
+    (EfTag::Identification, 0x0520, "Identification", Generation::Gen1),
+    (EfTag::CardDownload, 0x050E, "CardDownload", Generation::Gen1),
+    (EfTag::WorkshopCardDownload, 0x0509, "CardDownload (workshop card)", Generation::Gen1),
+    (EfTag::Calibration, 0x050A, "Calibration", Generation::Gen1),
+    (EfTag::SensorInstallationData, 0x050B, "SensorInstallationData", Generation::Gen1),
+    (EfTag::DrivingLicenceInfo, 0x0521, "DrivingLicenceInfo", Generation::Gen1),
+    (EfTag::EventsData, 0x0502, "EventsData", Generation::Gen1),
+    (EfTag::FaultsData, 0x0503, "FaultsData", Generation::Gen1),
+    (EfTag::DriverActivityData, 0x0504, "DriverActivityData", Generation::Gen1),
+    (EfTag::VehiclesUsed, 0x0505, "VehiclesUsed", Generation::Gen1),
+    (EfTag::Places, 0x0506, "Places", Generation::Gen1),
+    (EfTag::CurrentUsage, 0x0507, "CurrentUsage", Generation::Gen1),
+    (EfTag::ControlActivityData, 0x0508, "ControlActivityData", Generation::Gen1),
+    (EfTag::SpecificConditions, 0x0522, "SpecificConditions", Generation::Gen1),
+    (EfTag::VehicleUnitsUsed, 0x0523, "VehicleUnitsUsed", Generation::Gen2),
+    (EfTag::GnssPlaces, 0x0524, "GnssPlaces", Generation::Gen2),
+    (EfTag::ApplicationIdentificationV2, 0x0525, "ApplicationIdentificationV2", Generation::Gen2V2),
+    (EfTag::PlacesAuthentication, 0x0526, "PlacesAuthentication", Generation::Gen2V2),
+    (EfTag::GnssPlacesAuthentication, 0x0527, "GnssPlacesAuthentication", Generation::Gen2V2),
+    (EfTag::BorderCrossings, 0x0528, "BorderCrossings", Generation::Gen2V2),
+    (EfTag::LoadUnloadOperations, 0x0529, "LoadUnloadOperations", Generation::Gen2V2),
+    (EfTag::LoadTypeEntries, 0x0530, "LoadTypeEntries", Generation::Gen2V2),
+    (EfTag::VuConfigurations, 0x0531, "VuConfigurations", Generation::Gen2V2),
+];
+
+impl EfTag {
+    pub fn from_fid(fid: u16) -> Self {
+        EF_TAGS
+            .iter()
+            .find(|(_, known, ..)| *known == fid)
+            .map_or(EfTag::Unknown(fid), |(tag, ..)| *tag)
+    }
+
+    pub fn fid(self) -> u16 {
+        match self {
+            EfTag::Unknown(fid) => fid,
+            tag => tag.entry().map_or(0, |(_, fid, ..)| *fid),
+        }
+    }
+
+    /// Every known EF, in the order they appear in a download.
+    pub fn all() -> impl Iterator<Item = EfTag> {
+        EF_TAGS.iter().map(|(tag, ..)| *tag)
+    }
+
+    /// Name of the EF without its `EF_` prefix, `None` for unknown EFs.
+    pub fn name(self) -> Option<&'static str> {
+        self.entry().map(|(_, _, name, _)| *name)
+    }
+
+    /// First generation whose DF contains the EF: Gen1 EFs are read from both the Tachograph
+    /// and the Tachograph_G2 DF, Gen2 and Gen2V2 EFs from the Tachograph_G2 DF only.
+    pub fn since(self) -> Option<Generation> {
+        self.entry().map(|(.., since)| *since)
+    }
+
+    /// Whether a signature EF follows the data EF in a download.
+    pub fn is_signed(self) -> bool {
+        // Only the 0x05xx EFs of the Tachograph DFs are signed, not the ICC, chip and
+        // certificate EFs.
+        self.entry().is_some_and(|(_, fid, ..)| fid >> 8 == 0x05)
+    }
+
+    /// Position of the EF in a download, see [`CardParser::ef_order_deviations`].
+    fn position(self) -> Option<usize> {
+        EF_TAGS.iter().position(|(tag, ..)| *tag == self)
+    }
+
+    fn entry(self) -> Option<&'static (EfTag, u16, &'static str, Generation)> {
+        self.position().map(|position| &EF_TAGS[position])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The byte after the file identifier of an EF in a card download, telling the DF the EF was
+/// read from and whether it holds the data or its signature.
+pub enum EfAppendix {
+    /// 0x00, data of the Tachograph DF.
+    Gen1Data,
+    /// 0x01, signature of the Tachograph DF EF before it.
+    Gen1Signature,
+    /// 0x02, data of the Tachograph_G2 DF.
+    Gen2Data,
+    /// 0x03, signature of the Tachograph_G2 DF EF before it.
+    Gen2Signature,
+}
+impl EfAppendix {
+    /// `None` for any other byte, which the parser stops at.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(EfAppendix::Gen1Data),
+            1 => Some(EfAppendix::Gen1Signature),
+            2 => Some(EfAppendix::Gen2Data),
+            3 => Some(EfAppendix::Gen2Signature),
+            _ => None,
+        }
+    }
+
+    pub fn byte(self) -> u8 {
+        match self {
+            EfAppendix::Gen1Data => 0,
+            EfAppendix::Gen1Signature => 1,
+            EfAppendix::Gen2Data => 2,
+            EfAppendix::Gen2Signature => 3,
+        }
+    }
+
+    pub fn is_signature(self) -> bool {
+        matches!(self, EfAppendix::Gen1Signature | EfAppendix::Gen2Signature)
+    }
+
+    /// Whether the EF was read from the Tachograph_G2 DF.
+    pub fn is_gen2(self) -> bool {
+        matches!(self, EfAppendix::Gen2Data | EfAppendix::Gen2Signature)
+    }
+
+    /// Appendix of the signature of a data EF of the same DF.
+    pub fn signature(self) -> Self {
+        if self.is_gen2() {
+            EfAppendix::Gen2Signature
+        } else {
+            EfAppendix::Gen1Signature
+        }
+    }
+}
+
+fn duplicate_block_type(block_type: &str) -> anyhow::Error {
+    anyhow::anyhow!("{}: duplicate block type detected, the card file holds multiple instances of the same block type within a single generation", block_type)
+}
+
+/// The EFs of a card file parsed so far, filled in by [`CardEfs::parse_ef`].
+#[derive(Default)]
+struct CardEfs {
+    card_icc_identification: Option<gen1::CardIccIdentification>,
+    card_chip_identification: Option<dt::CardChipIdentification>,
+    application_identification: Option<gen1::ApplicationIdentification>,
+    application_identification_signature: Option<gen1::Signature>,
+    card_certificate: Option<gen1::Certificate>,
+    member_state_certificate: Option<gen1::Certificate>,
+    identification: Option<dt::Identification>,
+    identification_signature: Option<gen1::Signature>,
+    card_download: Option<dt::CardDownload>,
+    card_download_signature: Option<gen1::Signature>,
+    calibration: Option<gen1::WorkshopCardCalibrationData>,
+    calibration_signature: Option<gen1::Signature>,
+    sensor_installation_data: Option<gen1::SensorInstallation>,
+    sensor_installation_data_signature: Option<gen1::Signature>,
+    driver_licence_info: Option<dt::CardDrivingLicenceInformation>,
+    driver_licence_info_signature: Option<gen1::Signature>,
+    events_data: Option<gen1::CardEventData>,
+    events_data_signature: Option<gen1::Signature>,
+    faults_data: Option<gen1::CardFaultData>,
+    faults_data_signature: Option<gen1::Signature>,
+    driver_activity_data: Option<dt::DriverActivityData>,
+    driver_activity_data_signature: Option<gen1::Signature>,
+    vehicles_used: Option<gen1::CardVehiclesUsed>,
+    vehicles_used_signature: Option<gen1::Signature>,
+    places: Option<gen1::CardPlaceDailyWorkPeriod>,
+    places_signature: Option<gen1::Signature>,
+    current_usage: Option<dt::CurrentUsage>,
+    current_usage_signature: Option<gen1::Signature>,
+    control_activity_data: Option<gen1::CardControlActivityDataRecord>,
+    control_activity_data_signature: Option<gen1::Signature>,
+    specific_conditions: Option<gen1::SpecificConditions>,
+    specific_conditions_signature: Option<gen1::Signature>,
+
+    // GEN2
+    card_icc_identification_gen2: Option<gen2::CardIccIdentificationGen2>,
+    card_icc_identification_gen2v2: Option<gen2v2::CardIccIdentificationGen2V2>,
+    card_chip_identification_gen2: Option<dt::CardChipIdentification>,
+    application_identification_gen2: Option<gen2::ApplicationIdentificationGen2>,
+    application_identification_signature_gen2: Option<gen2::SignatureGen2>,
+    card_sign_certificate_gen2: Option<gen2::CertificateGen2>,
+    ca_certificate_gen2: Option<gen2::CertificateGen2>,
+    link_certificate_gen2: Option<gen2::CertificateGen2>,
+    identification_gen2: Option<dt::Identification>,
+    identification_signature_gen2: Option<gen2::SignatureGen2>,
+    card_download_gen2: Option<dt::CardDownload>,
+    card_download_signature_gen2: Option<gen2::SignatureGen2>,
+    driver_licence_info_gen2: Option<dt::CardDrivingLicenceInformation>,
+    driver_licence_info_signature_gen2: Option<gen2::SignatureGen2>,
+    events_data_gen2: Option<gen2::CardEventDataGen2>,
+    events_data_signature_gen2: Option<gen2::SignatureGen2>,
+    faults_data_gen2: Option<gen2::CardFaultDataGen2>,
+    faults_data_signature_gen2: Option<gen2::SignatureGen2>,
+    driver_activity_data_gen2: Option<dt::DriverActivityData>,
+    driver_activity_data_signature_gen2: Option<gen2::SignatureGen2>,
+    vehicles_used_gen2: Option<gen2::CardVehiclesUsedGen2>,
+    vehicles_used_signature_gen2: Option<gen2::SignatureGen2>,
+    places_gen2: Option<gen2::CardPlaceDailyWorkPeriodGen2>,
+    places_signature_gen2: Option<gen2::SignatureGen2>,
+    current_usage_gen2: Option<dt::CurrentUsage>,
+    current_usage_signature_gen2: Option<gen2::SignatureGen2>,
+    control_activity_data_gen2: Option<gen2::CardControlActivityDataRecordGen2>,
+    control_activity_data_signature_gen2: Option<gen2::SignatureGen2>,
+    specific_conditions_gen2: Option<gen2::SpecificConditionsGen2>,
+    specific_conditions_signature_gen2: Option<gen2::SignatureGen2>,
+    vehicle_units_used_gen2: Option<gen2::CardVehicleUnitsUsedGen2>,
+    vehicle_units_used_signature_gen2: Option<gen2::SignatureGen2>,
+    gnss_places_gen2: Option<gen2::GnssAccumulatedDrivingGen2>,
+    gnss_places_signature_gen2: Option<gen2::SignatureGen2>,
+
+    // GEN2V2
+    application_identification_gen2v2: Option<gen2v2::DriverCardApplicationIdentificationGen2V2>,
+    application_identification_signature_gen2v2: Option<gen2::SignatureGen2>,
+    places_authentication_gen2v2: Option<gen2v2::CardPlacesAuthDailyWorkPeriod>,
+    places_authentication_signature_gen2v2: Option<gen2::SignatureGen2>,
+    gnss_places_authentication_gen2v2: Option<gen2v2::GNSSAuthAccumulatedDriving>,
+    gnss_places_authentication_signature_gen2v2: Option<gen2::SignatureGen2>,
+    border_crossings_gen2v2: Option<gen2v2::CardBorderCrossings>,
+    border_crossings_signature_gen2v2: Option<gen2::SignatureGen2>,
+    load_unload_operations_gen2v2: Option<gen2v2::CardLoadUnloadOperations>,
+    load_unload_operations_signature_gen2v2: Option<gen2::SignatureGen2>,
+    load_type_entries_gen2v2: Option<gen2v2::CardLoadTypeEntries>,
+    load_type_entries_signature_gen2v2: Option<gen2::SignatureGen2>,
+    vu_configurations_gen2v2: Option<gen2v2::VuConfigurations>,
+    vu_configurations_signature_gen2v2: Option<gen2::SignatureGen2>,
+    unknown_efs: Vec<RawCardEf>,
+    unknown_efs_gen2: Vec<RawCardEf>,
+}
+
+impl CardEfs {
+    /// Parses the EFs of a card file in order. With `coverage`, an EF that fails to parse is
+    /// recorded there instead of failing the parse, see [`CardParser::ef_coverage`].
+    fn parse(
+        input: &[u8],
+        lenient: bool,
+        mut coverage: Option<&mut Vec<EfCoverage>>,
+    ) -> Result<(Self, Option<TruncatedBlock>)> {
+        let mut cursor = Cursor::new(input);
+        let mut efs = CardEfs::default();
+        let mut truncated_ef: Option<TruncatedBlock> = None;
 
         // all data blocks for card files follow the structure
         // file_id (2 bytes), sfid (1 byte), size (2 bytes)
         while !cursor.fill_buf()?.is_empty() {
+            let offset = cursor.position() as usize;
+            if lenient {
+                if let Some(truncated) = CardParser::truncated_ef(input, offset) {
+                    log::warn!(
+                        "File ends inside the EF at offset {}, dropping it",
+                        truncated.offset
+                    );
+                    truncated_ef = Some(truncated);
+                    break;
+                }
+            }
             let sfid = cursor
                 .read_u16::<BigEndian>()
-                .expect("Failed to read file_id");
-            let file_id = cursor.read_u8().expect("Failed to read sfid");
+                .context("Failed to read sfid")?;
+            let file_id = cursor.read_u8().context("Failed to read file_id")?;
+            let size = cursor
+                .read_u16::<BigEndian>()
+                .context("Failed to read EF size")?;
 
             log::debug!(
                 "Parsing card data with sfid: {:04X} and file_id: {:02X}",
                 sfid,
                 file_id
             );
-            // Page 283
-            match (sfid, file_id) {
-                // CardIccIdentification Gen1
-                (0x0002, 0) => {
-                    if card_icc_identification.is_some() {
-                        panic_on_duplicate_block_type("card_icc_identification_gen1");
-                    }
-                    card_icc_identification = Some(
-                        CardBlock::parse(&mut cursor, gen1::CardIccIdentification::parse)?
-                            .into_inner(),
-                    );
-                }
-                // CardChipIdentification Gen1
-                (0x0005, 0) => {
-                    if card_chip_identification.is_some() {
-                        panic_on_duplicate_block_type("card_chip_identification_gen1");
-                    }
-                    card_chip_identification = Some(
-                        CardBlock::parse(&mut cursor, dt::CardChipIdentification::parse)?
-                            .into_inner(),
-                    );
-                }
-                // ApplicationIdentification Gen1
-                (0x0501, 0) => {
-                    if application_identification.is_some() {
-                        panic_on_duplicate_block_type("application_identification_gen1");
-                    }
-                    application_identification = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::ApplicationIdentification::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // ApplicationIdentification Signature Gen1
-                (0x0501, 1) => {
-                    if application_identification_signature.is_some() {
-                        panic_on_duplicate_block_type("application_identification_signature_gen1");
-                    }
-                    application_identification_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // CardCertificate Gen1
-                (0xC100, 0) => {
-                    if card_certificate.is_some() {
-                        panic_on_duplicate_block_type("card_certificate_gen1");
-                    }
-                    card_certificate =
-                        Some(CardBlock::parse(&mut cursor, gen1::Certificate::parse)?.into_inner());
-                }
-                // MemberStateCertificate Gen1
-                (0xC108, 0) => {
-                    if member_state_certificate.is_some() {
-                        panic_on_duplicate_block_type("member_state_certificate_gen1");
-                    }
-                    member_state_certificate =
-                        Some(CardBlock::parse(&mut cursor, gen1::Certificate::parse)?.into_inner());
-                }
-                // Identification Gen1
-                (0x0520, 0) => {
-                    if identification.is_some() {
-                        panic_on_duplicate_block_type("identification_gen1");
-                    }
-                    identification = Some(
-                        CardBlock::parse(&mut cursor, dt::Identification::parse)?.into_inner(),
-                    );
-                }
-                // Identification Signature Gen1
-                (0x0520, 1) => {
-                    if identification_signature.is_some() {
-                        panic_on_duplicate_block_type("identification_signature_gen1");
-                    }
-                    identification_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // CardDownload Gen1
-                // 0x050E is CardDownload for driver card
-                // 0x0509 is CardDownload for workshop card
-                (0x050E, 0) | (0x0509, 0) => {
-                    if card_download.is_some() {
-                        panic_on_duplicate_block_type("card_download_gen1");
-                    }
-                    card_download =
-                        Some(CardBlock::parse(&mut cursor, dt::CardDownload::parse)?.into_inner());
-                }
-                // CardDownload Signature Gen1
-                (0x050E, 1) | (0x0509, 1) => {
-                    if card_download_signature.is_some() {
-                        panic_on_duplicate_block_type("card_download_signature_gen1");
-                    }
-                    card_download_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // Calibration Gen1
-                (0x050A, 0) => {
-                    if calibration.is_some() {
-                        panic_on_duplicate_block_type("calibration_gen1");
-                    }
-                    calibration = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::WorkshopCardCalibrationData::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // Calibration Signature Gen1
-                (0x050A, 1) => {
-                    if calibration_signature.is_some() {
-                        panic_on_duplicate_block_type("calibration_signature_gen1");
-                    }
-                    calibration_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // SensorInstallationData Gen1
-                (0x050B, 0) => {
-                    if sensor_installation_data.is_some() {
-                        panic_on_duplicate_block_type("sensor_installation_data_gen1");
-                    }
-                    sensor_installation_data = Some(
-                        CardBlock::parse(&mut cursor, gen1::SensorInstallation::parse)?
-                            .into_inner(),
-                    );
-                }
-                // SensorInstallationData Signature Gen1
-                (0x050B, 1) => {
-                    if sensor_installation_data_signature.is_some() {
-                        panic_on_duplicate_block_type("sensor_installation_data_signature_gen1");
-                    }
-                    sensor_installation_data_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // DrivingLicenseInfo Gen1
-                (0x0521, 0) => {
-                    if driver_licence_info.is_some() {
-                        panic_on_duplicate_block_type("driver_licence_info_gen1");
-                    }
-                    driver_licence_info = Some(
-                        CardBlock::parse(&mut cursor, dt::CardDrivingLicenceInformation::parse)?
-                            .into_inner(),
-                    );
-                }
-                // DrivingLicenseInfo Signature Gen1
-                (0x0521, 1) => {
-                    driver_licence_info_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // EventsData Gen1
-                (0x0502, 0) => {
-                    if events_data.is_some() {
-                        panic_on_duplicate_block_type("events_data_gen1");
-                    }
-                    events_data = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::CardEventData::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // EventsData Signature Gen1
-                (0x0502, 1) => {
-                    events_data_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // FaultsData Gen1
-                (0x0503, 0) => {
-                    if faults_data.is_some() {
-                        panic_on_duplicate_block_type("faults_data_gen1");
-                    }
-                    faults_data = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::CardFaultData::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // FaultsData Signature Gen1
-                (0x0503, 1) => {
-                    faults_data_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // DriverActivityData Gen1
-                (0x0504, 0) => {
-                    if driver_activity_data.is_some() {
-                        panic_on_duplicate_block_type("driver_activity_data_gen1");
-                    }
-                    driver_activity_data = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            dt::DriverActivityData::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // DriverActivityData Signature Gen1
-                (0x0504, 1) => {
-                    driver_activity_data_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // VehiclesUsed Gen1
-                (0x0505, 0) => {
-                    if vehicles_used.is_some() {
-                        panic_on_duplicate_block_type("vehicles_used_gen1");
-                    }
-                    vehicles_used = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::CardVehiclesUsed::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // VehiclesUsed Signature Gen1
-                (0x0505, 1) => {
-                    vehicles_used_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // Places Gen1
-                (0x0506, 0) => {
-                    if places.is_some() {
-                        panic_on_duplicate_block_type("places_gen1");
-                    }
-                    places = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::CardPlaceDailyWorkPeriod::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // Places Signature Gen1
-                (0x0506, 1) => {
-                    if places_signature.is_some() {
-                        panic_on_duplicate_block_type("places_signature_gen1");
-                    }
-                    places_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // CurrentUsage Gen1
-                (0x0507, 0) => {
-                    if current_usage.is_some() {
-                        panic_on_duplicate_block_type("current_usage_gen1");
-                    }
-                    current_usage =
-                        Some(CardBlock::parse(&mut cursor, dt::CurrentUsage::parse)?.into_inner());
-                }
-                // CurrentUsage Signature Gen1
-                (0x0507, 1) => {
-                    if current_usage_signature.is_some() {
-                        panic_on_duplicate_block_type("current_usage_signature_gen1");
-                    }
-                    current_usage_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // ControlActivityData Gen1
-                (0x0508, 0) => {
-                    if control_activity_data.is_some() {
-                        panic_on_duplicate_block_type("control_activity_data_gen1");
-                    }
-                    control_activity_data = Some(
-                        CardBlock::parse(&mut cursor, gen1::CardControlActivityDataRecord::parse)?
-                            .into_inner(),
-                    );
-                }
-                // ControlActivityData Signature Gen1
-                (0x0508, 1) => {
-                    if control_activity_data_signature.is_some() {
-                        panic_on_duplicate_block_type("control_activity_data_signature_gen1");
-                    }
-                    control_activity_data_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // SpecificConditions Gen1
-                (0x0522, 0) => {
-                    if specific_conditions.is_some() {
-                        panic_on_duplicate_block_type("specific_conditions_gen1");
-                    }
-                    specific_conditions = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen1::SpecificConditions::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // SpecificConditions Signature Gen1
-                (0x0522, 1) => {
-                    if specific_conditions_signature.is_some() {
-                        panic_on_duplicate_block_type("specific_conditions_signature_gen1");
-                    }
-                    specific_conditions_signature =
-                        Some(CardBlock::parse(&mut cursor, gen1::Signature::parse)?.into_inner());
-                }
-                // CardIccIdentification Gen2
-                (0x0002, 2) => {
-                    if card_icc_identification_gen2.is_some() {
-                        panic_on_duplicate_block_type("card_icc_identification_gen2");
-                    }
-                    card_icc_identification_gen2 = Some(
-                        CardBlock::parse(&mut cursor, gen2::CardIccIdentificationGen2::parse)?
-                            .into_inner(),
-                    );
-                }
-                // CardChipIdentification Gen2
-                (0x0005, 2) => {
-                    if card_chip_identification_gen2.is_some() {
-                        panic_on_duplicate_block_type("card_chip_identification_gen2");
-                    }
-                    card_chip_identification_gen2 = Some(
-                        CardBlock::parse(&mut cursor, dt::CardChipIdentification::parse)?
-                            .into_inner(),
-                    );
-                }
-                // ApplicationIdentification Gen2
-                (0x0501, 2) => {
-                    if application_identification_gen2.is_some() {
-                        panic_on_duplicate_block_type("application_identification_gen2");
-                    }
-                    application_identification_gen2 = Some(
-                        CardBlock::parse(
-                            &mut cursor,
-                            gen2::DriverCardApplicationIdentificationGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // ApplicationIdentification Signature Gen2
-                (0x0501, 3) => {
-                    if application_identification_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("application_identification_signature_gen2");
-                    }
-                    application_identification_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // CardSignCertificate Gen2
-                (0xC101, 2) => {
-                    if card_sign_certificate_gen2.is_some() {
-                        panic_on_duplicate_block_type("card_sign_certificate_gen2");
-                    }
-                    card_sign_certificate_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CertificateGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // MemberStateCertificate Gen2
-                (0xC108, 2) => {
-                    if ca_certificate_gen2.is_some() {
-                        panic_on_duplicate_block_type("ca_certificate_gen2");
-                    }
-                    ca_certificate_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CertificateGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // LinkCertificate Gen2
-                (0xC109, 2) => {
-                    if link_certificate_gen2.is_some() {
-                        panic_on_duplicate_block_type("link_certificate_gen2");
-                    }
-                    link_certificate_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CertificateGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // Identification Gen2
-                (0x0520, 2) => {
-                    if identification_gen2.is_some() {
-                        panic_on_duplicate_block_type("identification_gen2");
-                    }
-                    identification_gen2 = Some(
-                        CardBlock::parse(&mut cursor, dt::Identification::parse)?.into_inner(),
-                    );
-                }
-                // Identification Signature Gen2
-                (0x0520, 3) => {
-                    if identification_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("identification_signature_gen2");
-                    }
-                    identification_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // CardDownload Gen2
-                (0x050E, 2) => {
-                    if card_download_gen2.is_some() {
-                        panic_on_duplicate_block_type("card_download_gen2");
-                    }
-                    card_download_gen2 =
-                        Some(CardBlock::parse(&mut cursor, dt::CardDownload::parse)?.into_inner());
-                }
-                // CardDownload Signature Gen2
-                (0x050E, 3) => {
-                    if card_download_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("card_download_signature_gen2");
-                    }
-                    card_download_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // DrivingLicenseInfo Gen2
-                (0x0521, 2) => {
-                    if driver_licence_info_gen2.is_some() {
-                        panic_on_duplicate_block_type("driver_licence_info_gen2");
-                    }
-                    driver_licence_info_gen2 = Some(
-                        CardBlock::parse(&mut cursor, dt::CardDrivingLicenceInformation::parse)?
-                            .into_inner(),
-                    );
-                }
-                // DrivingLicenseInfo Signature Gen2
-                (0x0521, 3) => {
-                    if driver_licence_info_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("driver_licence_info_signature_gen2");
-                    }
-                    driver_licence_info_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // EventsData Gen2
-                (0x0502, 2) => {
-                    if events_data_gen2.is_some() {
-                        panic_on_duplicate_block_type("events_data_gen2");
-                    }
-                    events_data_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CardEventDataGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // EventsData Signature Gen2
-                (0x0502, 3) => {
-                    if events_data_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("events_data_signature_gen2");
-                    }
-                    events_data_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // FaultsData Gen2
-                (0x0503, 2) => {
-                    if faults_data_gen2.is_some() {
-                        panic_on_duplicate_block_type("faults_data_gen2");
-                    }
-                    faults_data_gen2 = Some(
-                        CardBlock::parse(&mut cursor, gen2::CardFaultDataGen2::parse)?.into_inner(),
-                    );
-                }
-                // FaultsData Signature Gen2
-                (0x0503, 3) => {
-                    if faults_data_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("faults_data_signature_gen2");
-                    }
-                    faults_data_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // DriverActivityData Gen2
-                (0x0504, 2) => {
-                    if driver_activity_data_gen2.is_some() {
-                        panic_on_duplicate_block_type("driver_activity_data_gen2");
-                    }
-                    driver_activity_data_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            dt::DriverActivityData::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // DriverActivityData Signature Gen2
-                (0x0504, 3) => {
-                    if driver_activity_data_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("driver_activity_data_signature_gen2");
-                    }
-                    driver_activity_data_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // VehiclesUsed Gen2
-                (0x0505, 2) => {
-                    if vehicles_used_gen2.is_some() {
-                        panic_on_duplicate_block_type("vehicles_used_gen2");
-                    }
-                    vehicles_used_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CardVehiclesUsedGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // VehiclesUsed Signature Gen2
-                (0x0505, 3) => {
-                    if vehicles_used_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("vehicles_used_signature_gen2");
-                    }
-                    vehicles_used_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // Places Gen2
-                (0x0506, 2) => {
-                    if places_gen2.is_some() {
-                        panic_on_duplicate_block_type("places_gen2");
-                    }
-                    places_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CardPlaceDailyWorkPeriodGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // Places Signature Gen2
-                (0x0506, 3) => {
-                    if places_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("places_signature_gen2");
-                    }
-                    places_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // CurrentUsage Gen2
-                (0x0507, 2) => {
-                    if current_usage_gen2.is_some() {
-                        panic_on_duplicate_block_type("current_usage_gen2");
-                    }
-                    current_usage_gen2 =
-                        Some(CardBlock::parse(&mut cursor, dt::CurrentUsage::parse)?.into_inner());
-                }
-                // CurrentUsage Signature Gen2
-                (0x0507, 3) => {
-                    if current_usage_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("current_usage_signature_gen2");
-                    }
-                    current_usage_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
-                }
-                // ControlActivityData Gen2
-                (0x0508, 2) => {
-                    if control_activity_data_gen2.is_some() {
-                        panic_on_duplicate_block_type("control_activity_data_gen2");
-                    }
-                    control_activity_data_gen2 = Some(
-                        CardBlock::parse(
-                            &mut cursor,
-                            gen2::CardControlActivityDataRecordGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
+            let Some(appendix) = EfAppendix::from_byte(file_id) else {
+                log::debug!(
+                    "Found unknown block with sfid: {:#04x}, file_id: {:#04x}",
+                    sfid,
+                    file_id
+                );
+                if let Some(coverage) = coverage {
+                    skip_efs(input, offset, coverage);
+                }
+                break;
+            };
+            let remaining = input.len() - cursor.position() as usize;
+            let Ok(mut data) = cursor.take_exact(size as usize) else {
+                let error = format!(
+                    "EF declares {} bytes but only {} remain in the file",
+                    size, remaining
+                );
+                if let Some(coverage) = coverage {
+                    coverage.push(EfCoverage {
+                        offset,
+                        sfid,
+                        file_id,
+                        size,
+                        status: EfParseStatus::Failed { error },
+                    });
+                } else if !matches!(EfTag::from_fid(sfid), EfTag::Unknown(_)) {
+                    anyhow::bail!("Failed to read EF {:#06x}: {}", sfid, error);
                 }
-                // ControlActivityData Signature Gen2
-                (0x0508, 3) => {
-                    if control_activity_data_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("control_activity_data_signature_gen2");
-                    }
-                    control_activity_data_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                break;
+            };
+
+            let parsed = efs.parse_ef(sfid, file_id, appendix, &mut data);
+            let unused_bytes = (size as u64).saturating_sub(data.position()) as u16;
+            if matches!(parsed, Ok(true)) && unused_bytes > 0 {
+                log::warn!(
+                    "EF {:#06x} with file_id {:#04x} has {} bytes its parser did not consume",
+                    sfid,
+                    file_id,
+                    unused_bytes
+                );
+            }
+            let Some(coverage) = coverage.as_deref_mut() else {
+                parsed?;
+                continue;
+            };
+            let status = match parsed {
+                Ok(false) => EfParseStatus::PreservedRaw,
+                Ok(true) if unused_bytes > 0 => EfParseStatus::PartiallyParsed { unused_bytes },
+                Ok(true) => EfParseStatus::Parsed,
+                Err(e) => EfParseStatus::Failed {
+                    error: format!("{:#}", e),
+                },
+            };
+            coverage.push(EfCoverage {
+                offset,
+                sfid,
+                file_id,
+                size,
+                status,
+            });
+        }
+        Ok((efs, truncated_ef))
+    }
+
+    /// Parses the `data` of the EF `sfid` into its slot, the single dispatch of the EFs shared by
+    /// [`CardParser::parse`] and [`CardParser::ef_coverage`]. Returns `false` for an EF the parser
+    /// does not know, which is kept raw.
+    fn parse_ef(
+        &mut self,
+        sfid: u16,
+        file_id: u8,
+        appendix: EfAppendix,
+        data: &mut Cursor<&[u8]>,
+    ) -> Result<bool> {
+        // Page 283
+        match (EfTag::from_fid(sfid), appendix) {
+            // CardIccIdentification Gen1
+            (EfTag::CardIccIdentification, EfAppendix::Gen1Data) => {
+                if self.card_icc_identification.is_some() {
+                    return Err(duplicate_block_type("card_icc_identification_gen1"));
+                }
+                self.card_icc_identification =
+                    Some(parse_ef(data, gen1::CardIccIdentification::parse)?);
+            }
+            // CardChipIdentification Gen1
+            (EfTag::CardChipIdentification, EfAppendix::Gen1Data) => {
+                if self.card_chip_identification.is_some() {
+                    return Err(duplicate_block_type("card_chip_identification_gen1"));
                 }
-                // SpecificConditions Gen2
-                (0x0522, 2) => {
-                    if specific_conditions_gen2.is_some() {
-                        panic_on_duplicate_block_type("specific_conditions_gen2");
-                    }
-                    specific_conditions_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SpecificConditionsGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
+                self.card_chip_identification =
+                    Some(parse_ef(data, dt::CardChipIdentification::parse)?);
+            }
+            // ApplicationIdentification Gen1
+            (EfTag::ApplicationIdentification, EfAppendix::Gen1Data) => {
+                if self.application_identification.is_some() {
+                    return Err(duplicate_block_type("application_identification_gen1"));
+                }
+                self.application_identification = Some(parse_ef_dyn_size(
+                    data,
+                    gen1::ApplicationIdentification::parse_dyn_size,
+                )?);
+            }
+            // ApplicationIdentification Signature Gen1
+            (EfTag::ApplicationIdentification, EfAppendix::Gen1Signature) => {
+                if self.application_identification_signature.is_some() {
+                    return Err(duplicate_block_type(
+                        "application_identification_signature_gen1",
+                    ));
+                }
+                self.application_identification_signature =
+                    Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // CardCertificate Gen1
+            (EfTag::CardCertificate, EfAppendix::Gen1Data) => {
+                if self.card_certificate.is_some() {
+                    return Err(duplicate_block_type("card_certificate_gen1"));
                 }
-                // SpecificConditions Signature Gen2
-                (0x0522, 3) => {
-                    if specific_conditions_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("specific_conditions_signature_gen2");
-                    }
-                    specific_conditions_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.card_certificate = Some(parse_ef(data, gen1::Certificate::parse)?);
+            }
+            // MemberStateCertificate Gen1
+            (EfTag::CaCertificate, EfAppendix::Gen1Data) => {
+                if self.member_state_certificate.is_some() {
+                    return Err(duplicate_block_type("member_state_certificate_gen1"));
                 }
-                // VehicleUnitsUsed Gen2
-                (0x0523, 2) => {
-                    if vehicle_units_used_gen2.is_some() {
-                        panic_on_duplicate_block_type("vehicle_units_used_gen2");
-                    }
-                    vehicle_units_used_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::CardVehicleUnitsUsedGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
+                self.member_state_certificate = Some(parse_ef(data, gen1::Certificate::parse)?);
+            }
+            // Identification Gen1
+            (EfTag::Identification, EfAppendix::Gen1Data) => {
+                if self.identification.is_some() {
+                    return Err(duplicate_block_type("identification_gen1"));
                 }
-                // VehicleUnitsUsed Signature Gen2
-                (0x0523, 3) => {
-                    if vehicle_units_used_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("vehicle_units_used_signature_gen2");
-                    }
-                    vehicle_units_used_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.identification = Some(parse_ef(data, dt::Identification::parse)?);
+            }
+            // Identification Signature Gen1
+            (EfTag::Identification, EfAppendix::Gen1Signature) => {
+                if self.identification_signature.is_some() {
+                    return Err(duplicate_block_type("identification_signature_gen1"));
                 }
-                // GnssAccumulatedDriving Gen2
-                (0x0524, 2) => {
-                    if gnss_places_gen2.is_some() {
-                        panic_on_duplicate_block_type("gnss_places_gen2");
-                    }
-                    gnss_places_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::GnssAccumulatedDrivingGen2::parse,
-                        )?
-                        .into_inner(),
-                    );
+                self.identification_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // CardDownload Gen1
+            // 0x050E is CardDownload for driver card
+            // 0x0509 is CardDownload for workshop card
+            (EfTag::CardDownload, EfAppendix::Gen1Data)
+            | (EfTag::WorkshopCardDownload, EfAppendix::Gen1Data) => {
+                if self.card_download.is_some() {
+                    return Err(duplicate_block_type("card_download_gen1"));
+                }
+                self.card_download = Some(parse_ef(data, dt::CardDownload::parse)?);
+            }
+            // CardDownload Signature Gen1
+            (EfTag::CardDownload, EfAppendix::Gen1Signature)
+            | (EfTag::WorkshopCardDownload, EfAppendix::Gen1Signature) => {
+                if self.card_download_signature.is_some() {
+                    return Err(duplicate_block_type("card_download_signature_gen1"));
                 }
-                // GnssAccumulatedDriving Signature Gen2
-                (0x0524, 3) => {
-                    if gnss_places_signature_gen2.is_some() {
-                        panic_on_duplicate_block_type("gnss_places_signature_gen2");
-                    }
-                    gnss_places_signature_gen2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.card_download_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // Calibration Gen1
+            (EfTag::Calibration, EfAppendix::Gen1Data) => {
+                if self.calibration.is_some() {
+                    return Err(duplicate_block_type("calibration_gen1"));
+                }
+                self.calibration = Some(parse_ef_dyn_size(
+                    data,
+                    gen1::WorkshopCardCalibrationData::parse_dyn_size,
+                )?);
+            }
+            // Calibration Signature Gen1
+            (EfTag::Calibration, EfAppendix::Gen1Signature) => {
+                if self.calibration_signature.is_some() {
+                    return Err(duplicate_block_type("calibration_signature_gen1"));
                 }
-                // ApplicationIdentification Gen2v2
-                (0x0525, 2) => {
-                    if application_identification_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("application_identification_gen2v2");
-                    }
-                    application_identification_gen2v2 = Some(
-                        CardBlock::parse(
-                            &mut cursor,
-                            gen2v2::DriverCardApplicationIdentificationGen2V2::parse,
-                        )?
-                        .into_inner(),
-                    );
+                self.calibration_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // SensorInstallationData Gen1
+            (EfTag::SensorInstallationData, EfAppendix::Gen1Data) => {
+                if self.sensor_installation_data.is_some() {
+                    return Err(duplicate_block_type("sensor_installation_data_gen1"));
                 }
-                // ApplicationIdentification Signature Gen2v2
-                (0x0525, 3) => {
-                    if application_identification_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type(
-                            "application_identification_signature_gen2v2",
-                        );
-                    }
-                    application_identification_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.sensor_installation_data =
+                    Some(parse_ef(data, gen1::SensorInstallation::parse)?);
+            }
+            // SensorInstallationData Signature Gen1
+            (EfTag::SensorInstallationData, EfAppendix::Gen1Signature) => {
+                if self.sensor_installation_data_signature.is_some() {
+                    return Err(duplicate_block_type(
+                        "sensor_installation_data_signature_gen1",
+                    ));
+                }
+                self.sensor_installation_data_signature =
+                    Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // DrivingLicenseInfo Gen1
+            (EfTag::DrivingLicenceInfo, EfAppendix::Gen1Data) => {
+                if self.driver_licence_info.is_some() {
+                    return Err(duplicate_block_type("driver_licence_info_gen1"));
                 }
-                // PlacesAuthentication Gen2v2
-                (0x0526, 2) => {
-                    if places_authentication_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("places_authentication_gen2v2");
-                    }
-                    places_authentication_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2v2::CardPlacesAuthDailyWorkPeriod::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.driver_licence_info =
+                    Some(parse_ef(data, dt::CardDrivingLicenceInformation::parse)?);
+            }
+            // DrivingLicenseInfo Signature Gen1
+            (EfTag::DrivingLicenceInfo, EfAppendix::Gen1Signature) => {
+                self.driver_licence_info_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // EventsData Gen1
+            (EfTag::EventsData, EfAppendix::Gen1Data) => {
+                if self.events_data.is_some() {
+                    return Err(duplicate_block_type("events_data_gen1"));
+                }
+                self.events_data = Some(parse_ef_dyn_size(
+                    data,
+                    gen1::CardEventData::parse_dyn_size,
+                )?);
+            }
+            // EventsData Signature Gen1
+            (EfTag::EventsData, EfAppendix::Gen1Signature) => {
+                self.events_data_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // FaultsData Gen1
+            (EfTag::FaultsData, EfAppendix::Gen1Data) => {
+                if self.faults_data.is_some() {
+                    return Err(duplicate_block_type("faults_data_gen1"));
+                }
+                self.faults_data = Some(parse_ef_dyn_size(
+                    data,
+                    gen1::CardFaultData::parse_dyn_size,
+                )?);
+            }
+            // FaultsData Signature Gen1
+            (EfTag::FaultsData, EfAppendix::Gen1Signature) => {
+                self.faults_data_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // DriverActivityData Gen1
+            (EfTag::DriverActivityData, EfAppendix::Gen1Data) => {
+                if self.driver_activity_data.is_some() {
+                    return Err(duplicate_block_type("driver_activity_data_gen1"));
+                }
+                self.driver_activity_data = Some(parse_ef_dyn_size(
+                    data,
+                    dt::DriverActivityData::parse_dyn_size,
+                )?);
+            }
+            // DriverActivityData Signature Gen1
+            (EfTag::DriverActivityData, EfAppendix::Gen1Signature) => {
+                self.driver_activity_data_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // VehiclesUsed Gen1
+            (EfTag::VehiclesUsed, EfAppendix::Gen1Data) => {
+                if self.vehicles_used.is_some() {
+                    return Err(duplicate_block_type("vehicles_used_gen1"));
+                }
+                let no_of_records = self
+                    .application_identification
+                    .as_ref()
+                    .and_then(gen1::ApplicationIdentification::no_of_card_vehicle_records);
+                self.vehicles_used = Some(parse_ef_dyn_size(data, |cursor, size| {
+                    gen1::CardVehiclesUsed::parse_with_no_of_records(cursor, size, no_of_records)
+                })?);
+            }
+            // VehiclesUsed Signature Gen1
+            (EfTag::VehiclesUsed, EfAppendix::Gen1Signature) => {
+                self.vehicles_used_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // Places Gen1
+            (EfTag::Places, EfAppendix::Gen1Data) => {
+                if self.places.is_some() {
+                    return Err(duplicate_block_type("places_gen1"));
+                }
+                let no_of_records = self
+                    .application_identification
+                    .as_ref()
+                    .and_then(gen1::ApplicationIdentification::no_of_card_place_records);
+                self.places = Some(parse_ef_dyn_size(data, |cursor, size| {
+                    gen1::CardPlaceDailyWorkPeriod::parse_with_no_of_records(
+                        cursor,
+                        size,
+                        no_of_records,
+                    )
+                })?);
+            }
+            // Places Signature Gen1
+            (EfTag::Places, EfAppendix::Gen1Signature) => {
+                if self.places_signature.is_some() {
+                    return Err(duplicate_block_type("places_signature_gen1"));
                 }
-                // PlacesAuthentication Signature Gen2v2
-                (0x0526, 3) => {
-                    if places_authentication_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("places_authentication_signature_gen2v2");
-                    }
-                    places_authentication_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.places_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // CurrentUsage Gen1
+            (EfTag::CurrentUsage, EfAppendix::Gen1Data) => {
+                if self.current_usage.is_some() {
+                    return Err(duplicate_block_type("current_usage_gen1"));
                 }
-                // GnssPlacesAuthentication Gen2v2
-                (0x0527, 2) => {
-                    if gnss_places_authentication_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("gnss_places_authentication_gen2v2");
-                    }
-                    gnss_places_authentication_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2v2::GNSSAuthAccumulatedDriving::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.current_usage = Some(parse_ef(data, dt::CurrentUsage::parse)?);
+            }
+            // CurrentUsage Signature Gen1
+            (EfTag::CurrentUsage, EfAppendix::Gen1Signature) => {
+                if self.current_usage_signature.is_some() {
+                    return Err(duplicate_block_type("current_usage_signature_gen1"));
                 }
-                // GnssPlacesAuthentication Signature Gen2v2
-                (0x0527, 3) => {
-                    if gnss_places_authentication_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type(
-                            "gnss_places_authentication_signature_gen2v2",
-                        );
-                    }
-                    gnss_places_authentication_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.current_usage_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // ControlActivityData Gen1
+            (EfTag::ControlActivityData, EfAppendix::Gen1Data) => {
+                if self.control_activity_data.is_some() {
+                    return Err(duplicate_block_type("control_activity_data_gen1"));
                 }
-                // BorderCrossings Gen2v2
-                (0x0528, 2) => {
-                    if border_crossings_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("border_crossings_gen2v2");
-                    }
-                    border_crossings_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2v2::CardBorderCrossings::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.control_activity_data =
+                    Some(parse_ef(data, gen1::CardControlActivityDataRecord::parse)?);
+            }
+            // ControlActivityData Signature Gen1
+            (EfTag::ControlActivityData, EfAppendix::Gen1Signature) => {
+                if self.control_activity_data_signature.is_some() {
+                    return Err(duplicate_block_type("control_activity_data_signature_gen1"));
                 }
-                // BorderCrossings Signature Gen2v2
-                (0x0528, 3) => {
-                    if border_crossings_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("border_crossings_signature_gen2v2");
-                    }
-                    border_crossings_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.control_activity_data_signature =
+                    Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // SpecificConditions Gen1
+            (EfTag::SpecificConditions, EfAppendix::Gen1Data) => {
+                if self.specific_conditions.is_some() {
+                    return Err(duplicate_block_type("specific_conditions_gen1"));
+                }
+                self.specific_conditions = Some(parse_ef_dyn_size(
+                    data,
+                    gen1::SpecificConditions::parse_dyn_size,
+                )?);
+            }
+            // SpecificConditions Signature Gen1
+            (EfTag::SpecificConditions, EfAppendix::Gen1Signature) => {
+                if self.specific_conditions_signature.is_some() {
+                    return Err(duplicate_block_type("specific_conditions_signature_gen1"));
                 }
-                // LoadUnloadOperations Gen2v2
-                (0x0529, 2) => {
-                    if load_unload_operations_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("load_unload_operations_gen2v2");
-                    }
-                    load_unload_operations_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2v2::CardLoadUnloadOperations::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.specific_conditions_signature = Some(parse_ef(data, gen1::Signature::parse)?);
+            }
+            // CardIccIdentification Gen2
+            (EfTag::CardIccIdentification, EfAppendix::Gen2Data) => {
+                if self.card_icc_identification_gen2.is_some() {
+                    return Err(duplicate_block_type("card_icc_identification_gen2"));
+                }
+                self.card_icc_identification_gen2v2 =
+                    parse_ef_dyn_size(data, gen2v2::CardIccIdentificationGen2V2::parse_dyn_size)
+                        .map_err(|err| {
+                            log::warn!("Failed to parse card_icc_identification gen2v2: {:#}", err)
+                        })
+                        .ok();
+                data.set_position(0);
+                self.card_icc_identification_gen2 =
+                    Some(parse_ef(data, gen2::CardIccIdentificationGen2::parse)?);
+            }
+            // CardChipIdentification Gen2
+            (EfTag::CardChipIdentification, EfAppendix::Gen2Data) => {
+                if self.card_chip_identification_gen2.is_some() {
+                    return Err(duplicate_block_type("card_chip_identification_gen2"));
                 }
-                // LoadUnloadOperations Signature Gen2v2
-                (0x0529, 3) => {
-                    if load_unload_operations_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("load_unload_operations_signature_gen2v2");
-                    }
-                    load_unload_operations_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.card_chip_identification_gen2 =
+                    Some(parse_ef(data, dt::CardChipIdentification::parse)?);
+            }
+            // ApplicationIdentification Gen2
+            (EfTag::ApplicationIdentification, EfAppendix::Gen2Data) => {
+                if self.application_identification_gen2.is_some() {
+                    return Err(duplicate_block_type("application_identification_gen2"));
                 }
-                (0x0530, 2) => {
-                    if load_type_entries_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("load_type_entries_gen2v2");
-                    }
-                    load_type_entries_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2v2::CardLoadTypeEntries::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.application_identification_gen2 =
+                    Some(parse_ef(data, gen2::ApplicationIdentificationGen2::parse)?);
+            }
+            // ApplicationIdentification Signature Gen2
+            (EfTag::ApplicationIdentification, EfAppendix::Gen2Signature) => {
+                if self.application_identification_signature_gen2.is_some() {
+                    return Err(duplicate_block_type(
+                        "application_identification_signature_gen2",
+                    ));
+                }
+                self.application_identification_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // CardSignCertificate Gen2
+            (EfTag::CardSignCertificate, EfAppendix::Gen2Data) => {
+                if self.card_sign_certificate_gen2.is_some() {
+                    return Err(duplicate_block_type("card_sign_certificate_gen2"));
+                }
+                self.card_sign_certificate_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::CertificateGen2::parse_dyn_size,
+                )?);
+            }
+            // MemberStateCertificate Gen2
+            (EfTag::CaCertificate, EfAppendix::Gen2Data) => {
+                if self.ca_certificate_gen2.is_some() {
+                    return Err(duplicate_block_type("ca_certificate_gen2"));
+                }
+                self.ca_certificate_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::CertificateGen2::parse_dyn_size,
+                )?);
+            }
+            // LinkCertificate Gen2
+            (EfTag::LinkCertificate, EfAppendix::Gen2Data) => {
+                if self.link_certificate_gen2.is_some() {
+                    return Err(duplicate_block_type("link_certificate_gen2"));
+                }
+                self.link_certificate_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::CertificateGen2::parse_dyn_size,
+                )?);
+            }
+            // Identification Gen2
+            (EfTag::Identification, EfAppendix::Gen2Data) => {
+                if self.identification_gen2.is_some() {
+                    return Err(duplicate_block_type("identification_gen2"));
                 }
-                // LoadTypeEntries Signature Gen2v2
-                (0x0530, 3) => {
-                    if load_type_entries_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("load_type_entries_signature_gen2v2");
-                    }
-                    load_type_entries_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.identification_gen2 = Some(parse_ef(data, dt::Identification::parse)?);
+            }
+            // Identification Signature Gen2
+            (EfTag::Identification, EfAppendix::Gen2Signature) => {
+                if self.identification_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("identification_signature_gen2"));
+                }
+                self.identification_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // CardDownload Gen2
+            (EfTag::CardDownload, EfAppendix::Gen2Data) => {
+                if self.card_download_gen2.is_some() {
+                    return Err(duplicate_block_type("card_download_gen2"));
                 }
-                (0x0531, 2) => {
-                    if vu_configurations_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("vu_configurations_gen2v2");
-                    }
-                    vu_configurations_gen2v2 = Some(
-                        CardBlock::parse(&mut cursor, gen2v2::VuConfigurations::parse)?
-                            .into_inner(),
-                    );
+                self.card_download_gen2 = Some(parse_ef(data, dt::CardDownload::parse)?);
+            }
+            // CardDownload Signature Gen2
+            (EfTag::CardDownload, EfAppendix::Gen2Signature) => {
+                if self.card_download_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("card_download_signature_gen2"));
+                }
+                self.card_download_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // DrivingLicenseInfo Gen2
+            (EfTag::DrivingLicenceInfo, EfAppendix::Gen2Data) => {
+                if self.driver_licence_info_gen2.is_some() {
+                    return Err(duplicate_block_type("driver_licence_info_gen2"));
                 }
-                // VuConfigurations Signature Gen2v2
-                (0x0531, 3) => {
-                    if vu_configurations_signature_gen2v2.is_some() {
-                        panic_on_duplicate_block_type("vu_configurations_signature_gen2v2");
-                    }
-                    vu_configurations_signature_gen2v2 = Some(
-                        CardBlock::parse_dyn_size(
-                            &mut cursor,
-                            gen2::SignatureGen2::parse_dyn_size,
-                        )?
-                        .into_inner(),
-                    );
+                self.driver_licence_info_gen2 =
+                    Some(parse_ef(data, dt::CardDrivingLicenceInformation::parse)?);
+            }
+            // DrivingLicenseInfo Signature Gen2
+            (EfTag::DrivingLicenceInfo, EfAppendix::Gen2Signature) => {
+                if self.driver_licence_info_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("driver_licence_info_signature_gen2"));
+                }
+                self.driver_licence_info_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // EventsData Gen2
+            (EfTag::EventsData, EfAppendix::Gen2Data) => {
+                if self.events_data_gen2.is_some() {
+                    return Err(duplicate_block_type("events_data_gen2"));
+                }
+                self.events_data_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::CardEventDataGen2::parse_dyn_size,
+                )?);
+            }
+            // EventsData Signature Gen2
+            (EfTag::EventsData, EfAppendix::Gen2Signature) => {
+                if self.events_data_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("events_data_signature_gen2"));
+                }
+                self.events_data_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // FaultsData Gen2
+            (EfTag::FaultsData, EfAppendix::Gen2Data) => {
+                if self.faults_data_gen2.is_some() {
+                    return Err(duplicate_block_type("faults_data_gen2"));
                 }
-                _ => {
-                    log::debug!(
-                        "Found unknown block with sfid: {:#04x}, file_id: {:#04x}",
-                        sfid,
-                        file_id
-                    );
-                    break;
+                self.faults_data_gen2 = Some(parse_ef(data, gen2::CardFaultDataGen2::parse)?);
+            }
+            // FaultsData Signature Gen2
+            (EfTag::FaultsData, EfAppendix::Gen2Signature) => {
+                if self.faults_data_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("faults_data_signature_gen2"));
+                }
+                self.faults_data_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // DriverActivityData Gen2
+            (EfTag::DriverActivityData, EfAppendix::Gen2Data) => {
+                if self.driver_activity_data_gen2.is_some() {
+                    return Err(duplicate_block_type("driver_activity_data_gen2"));
+                }
+                self.driver_activity_data_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    dt::DriverActivityData::parse_dyn_size,
+                )?);
+            }
+            // DriverActivityData Signature Gen2
+            (EfTag::DriverActivityData, EfAppendix::Gen2Signature) => {
+                if self.driver_activity_data_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("driver_activity_data_signature_gen2"));
+                }
+                self.driver_activity_data_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // VehiclesUsed Gen2
+            (EfTag::VehiclesUsed, EfAppendix::Gen2Data) => {
+                if self.vehicles_used_gen2.is_some() {
+                    return Err(duplicate_block_type("vehicles_used_gen2"));
+                }
+                let no_of_records = self
+                    .application_identification_gen2
+                    .as_ref()
+                    .and_then(gen2::ApplicationIdentificationGen2::no_of_card_vehicle_records);
+                self.vehicles_used_gen2 = Some(parse_ef_dyn_size(data, |cursor, size| {
+                    gen2::CardVehiclesUsedGen2::parse_with_no_of_records(
+                        cursor,
+                        size,
+                        no_of_records,
+                    )
+                })?);
+            }
+            // VehiclesUsed Signature Gen2
+            (EfTag::VehiclesUsed, EfAppendix::Gen2Signature) => {
+                if self.vehicles_used_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("vehicles_used_signature_gen2"));
+                }
+                self.vehicles_used_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // Places Gen2
+            (EfTag::Places, EfAppendix::Gen2Data) => {
+                if self.places_gen2.is_some() {
+                    return Err(duplicate_block_type("places_gen2"));
+                }
+                let no_of_records = self
+                    .application_identification_gen2
+                    .as_ref()
+                    .and_then(gen2::ApplicationIdentificationGen2::no_of_card_place_records);
+                self.places_gen2 = Some(parse_ef_dyn_size(data, |cursor, size| {
+                    gen2::CardPlaceDailyWorkPeriodGen2::parse_with_no_of_records(
+                        cursor,
+                        size,
+                        no_of_records,
+                    )
+                })?);
+            }
+            // Places Signature Gen2
+            (EfTag::Places, EfAppendix::Gen2Signature) => {
+                if self.places_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("places_signature_gen2"));
+                }
+                self.places_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // CurrentUsage Gen2
+            (EfTag::CurrentUsage, EfAppendix::Gen2Data) => {
+                if self.current_usage_gen2.is_some() {
+                    return Err(duplicate_block_type("current_usage_gen2"));
+                }
+                self.current_usage_gen2 = Some(parse_ef(data, dt::CurrentUsage::parse)?);
+            }
+            // CurrentUsage Signature Gen2
+            (EfTag::CurrentUsage, EfAppendix::Gen2Signature) => {
+                if self.current_usage_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("current_usage_signature_gen2"));
+                }
+                self.current_usage_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // ControlActivityData Gen2
+            (EfTag::ControlActivityData, EfAppendix::Gen2Data) => {
+                if self.control_activity_data_gen2.is_some() {
+                    return Err(duplicate_block_type("control_activity_data_gen2"));
+                }
+                self.control_activity_data_gen2 = Some(parse_ef(
+                    data,
+                    gen2::CardControlActivityDataRecordGen2::parse,
+                )?);
+            }
+            // ControlActivityData Signature Gen2
+            (EfTag::ControlActivityData, EfAppendix::Gen2Signature) => {
+                if self.control_activity_data_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("control_activity_data_signature_gen2"));
+                }
+                self.control_activity_data_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // SpecificConditions Gen2
+            (EfTag::SpecificConditions, EfAppendix::Gen2Data) => {
+                if self.specific_conditions_gen2.is_some() {
+                    return Err(duplicate_block_type("specific_conditions_gen2"));
+                }
+                self.specific_conditions_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SpecificConditionsGen2::parse,
+                )?);
+            }
+            // SpecificConditions Signature Gen2
+            (EfTag::SpecificConditions, EfAppendix::Gen2Signature) => {
+                if self.specific_conditions_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("specific_conditions_signature_gen2"));
+                }
+                self.specific_conditions_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // VehicleUnitsUsed Gen2
+            (EfTag::VehicleUnitsUsed, EfAppendix::Gen2Data) => {
+                if self.vehicle_units_used_gen2.is_some() {
+                    return Err(duplicate_block_type("vehicle_units_used_gen2"));
+                }
+                self.vehicle_units_used_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::CardVehicleUnitsUsedGen2::parse,
+                )?);
+            }
+            // VehicleUnitsUsed Signature Gen2
+            (EfTag::VehicleUnitsUsed, EfAppendix::Gen2Signature) => {
+                if self.vehicle_units_used_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("vehicle_units_used_signature_gen2"));
+                }
+                self.vehicle_units_used_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // GnssAccumulatedDriving Gen2
+            (EfTag::GnssPlaces, EfAppendix::Gen2Data) => {
+                if self.gnss_places_gen2.is_some() {
+                    return Err(duplicate_block_type("gnss_places_gen2"));
+                }
+                let no_of_records = self
+                    .application_identification_gen2
+                    .as_ref()
+                    .and_then(gen2::ApplicationIdentificationGen2::no_of_gnss_ad_records);
+                self.gnss_places_gen2 = Some(parse_ef_dyn_size(data, |cursor, size| {
+                    gen2::GnssAccumulatedDrivingGen2::parse_with_no_of_records(
+                        cursor,
+                        size,
+                        no_of_records,
+                    )
+                })?);
+            }
+            // GnssAccumulatedDriving Signature Gen2
+            (EfTag::GnssPlaces, EfAppendix::Gen2Signature) => {
+                if self.gnss_places_signature_gen2.is_some() {
+                    return Err(duplicate_block_type("gnss_places_signature_gen2"));
+                }
+                self.gnss_places_signature_gen2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // ApplicationIdentification Gen2v2
+            (EfTag::ApplicationIdentificationV2, EfAppendix::Gen2Data) => {
+                if self.application_identification_gen2v2.is_some() {
+                    return Err(duplicate_block_type("application_identification_gen2v2"));
+                }
+                self.application_identification_gen2v2 = Some(parse_ef(
+                    data,
+                    gen2v2::DriverCardApplicationIdentificationGen2V2::parse,
+                )?);
+            }
+            // ApplicationIdentification Signature Gen2v2
+            (EfTag::ApplicationIdentificationV2, EfAppendix::Gen2Signature) => {
+                if self.application_identification_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type(
+                        "application_identification_signature_gen2v2",
+                    ));
+                }
+                self.application_identification_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // PlacesAuthentication Gen2v2
+            (EfTag::PlacesAuthentication, EfAppendix::Gen2Data) => {
+                if self.places_authentication_gen2v2.is_some() {
+                    return Err(duplicate_block_type("places_authentication_gen2v2"));
+                }
+                self.places_authentication_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2v2::CardPlacesAuthDailyWorkPeriod::parse_dyn_size,
+                )?);
+            }
+            // PlacesAuthentication Signature Gen2v2
+            (EfTag::PlacesAuthentication, EfAppendix::Gen2Signature) => {
+                if self.places_authentication_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type(
+                        "places_authentication_signature_gen2v2",
+                    ));
+                }
+                self.places_authentication_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // GnssPlacesAuthentication Gen2v2
+            (EfTag::GnssPlacesAuthentication, EfAppendix::Gen2Data) => {
+                if self.gnss_places_authentication_gen2v2.is_some() {
+                    return Err(duplicate_block_type("gnss_places_authentication_gen2v2"));
+                }
+                self.gnss_places_authentication_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2v2::GNSSAuthAccumulatedDriving::parse_dyn_size,
+                )?);
+            }
+            // GnssPlacesAuthentication Signature Gen2v2
+            (EfTag::GnssPlacesAuthentication, EfAppendix::Gen2Signature) => {
+                if self.gnss_places_authentication_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type(
+                        "gnss_places_authentication_signature_gen2v2",
+                    ));
+                }
+                self.gnss_places_authentication_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // BorderCrossings Gen2v2
+            (EfTag::BorderCrossings, EfAppendix::Gen2Data) => {
+                if self.border_crossings_gen2v2.is_some() {
+                    return Err(duplicate_block_type("border_crossings_gen2v2"));
+                }
+                self.border_crossings_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2v2::CardBorderCrossings::parse_dyn_size,
+                )?);
+            }
+            // BorderCrossings Signature Gen2v2
+            (EfTag::BorderCrossings, EfAppendix::Gen2Signature) => {
+                if self.border_crossings_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type("border_crossings_signature_gen2v2"));
+                }
+                self.border_crossings_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // LoadUnloadOperations Gen2v2
+            (EfTag::LoadUnloadOperations, EfAppendix::Gen2Data) => {
+                if self.load_unload_operations_gen2v2.is_some() {
+                    return Err(duplicate_block_type("load_unload_operations_gen2v2"));
+                }
+                self.load_unload_operations_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2v2::CardLoadUnloadOperations::parse_dyn_size,
+                )?);
+            }
+            // LoadUnloadOperations Signature Gen2v2
+            (EfTag::LoadUnloadOperations, EfAppendix::Gen2Signature) => {
+                if self.load_unload_operations_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type(
+                        "load_unload_operations_signature_gen2v2",
+                    ));
+                }
+                self.load_unload_operations_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            (EfTag::LoadTypeEntries, EfAppendix::Gen2Data) => {
+                if self.load_type_entries_gen2v2.is_some() {
+                    return Err(duplicate_block_type("load_type_entries_gen2v2"));
+                }
+                self.load_type_entries_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2v2::CardLoadTypeEntries::parse_dyn_size,
+                )?);
+            }
+            // LoadTypeEntries Signature Gen2v2
+            (EfTag::LoadTypeEntries, EfAppendix::Gen2Signature) => {
+                if self.load_type_entries_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type("load_type_entries_signature_gen2v2"));
+                }
+                self.load_type_entries_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            (EfTag::VuConfigurations, EfAppendix::Gen2Data) => {
+                if self.vu_configurations_gen2v2.is_some() {
+                    return Err(duplicate_block_type("vu_configurations_gen2v2"));
                 }
+                self.vu_configurations_gen2v2 =
+                    Some(parse_ef(data, gen2v2::VuConfigurations::parse)?);
+            }
+            // VuConfigurations Signature Gen2v2
+            (EfTag::VuConfigurations, EfAppendix::Gen2Signature) => {
+                if self.vu_configurations_signature_gen2v2.is_some() {
+                    return Err(duplicate_block_type("vu_configurations_signature_gen2v2"));
+                }
+                self.vu_configurations_signature_gen2v2 = Some(parse_ef_dyn_size(
+                    data,
+                    gen2::SignatureGen2::parse_dyn_size,
+                )?);
+            }
+            // Unknown EF with a valid file_id, keep it raw so that nothing present in the file is lost
+            _ => {
+                log::debug!(
+                    "Preserving unknown block with sfid: {:#04x}, file_id: {:#04x}",
+                    sfid,
+                    file_id
+                );
+                let raw_ef = RawCardEf {
+                    sfid,
+                    file_id,
+                    data: data.get_ref().to_vec(),
+                };
+                data.set_position(data.get_ref().len() as u64);
+                if appendix.is_gen2() {
+                    self.unknown_efs_gen2.push(raw_ef);
+                } else {
+                    self.unknown_efs.push(raw_ef);
+                }
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+pub struct CardParser {
+    input: Vec<u8>,
+    lenient: bool,
+    strict_ef_order: bool,
+    activity_minutes_policy: dt::ActivityMinutesPolicy,
+    extensions: ExtensionRegistry,
+}
+impl CardParser {
+    pub fn new_from_file(file_path: &str) -> Result<Self> {
+        let input = std::fs::read(file_path).expect("Failed to read file");
+        Ok(CardParser {
+            input,
+            lenient: false,
+            strict_ef_order: false,
+            activity_minutes_policy: dt::ActivityMinutesPolicy::Keep,
+            extensions: ExtensionRegistry::default(),
+        })
+    }
+    pub fn new_from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(CardParser {
+            input: bytes.to_vec(),
+            lenient: false,
+            strict_ef_order: false,
+            activity_minutes_policy: dt::ActivityMinutesPolicy::Keep,
+            extensions: ExtensionRegistry::default(),
+        })
+    }
+    /// Reads the whole download from `source`, e.g. a blob that is decrypted while it is read.
+    pub fn new_from_source<S: TachoRead + ?Sized>(source: &S) -> Result<Self> {
+        let input = source::read_all(source).context("Failed to read source")?;
+        Ok(CardParser {
+            input: input.into_owned(),
+            lenient: false,
+            strict_ef_order: false,
+            activity_minutes_policy: dt::ActivityMinutesPolicy::Keep,
+            extensions: ExtensionRegistry::default(),
+        })
+    }
+
+    /// In lenient mode, an EF the file ends in the middle of, as happens with aborted downloads,
+    /// is dropped and reported in [`CardDiagnostics::truncated_ef`] instead of failing the parse.
+    /// The parse still fails if the dropped EF is a mandatory one.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Fails the parse when the EFs are not in the order of the card file structure, for
+    /// certifying download tools. By default EFs are read in any order, keyed by their tag.
+    pub fn strict_ef_order(mut self, strict_ef_order: bool) -> Self {
+        self.strict_ef_order = strict_ef_order;
+        self
+    }
+
+    /// Keeps, clamps or drops activity changes past the end of their day, see
+    /// [`dt::ActivityMinutesPolicy`].
+    pub fn activity_minutes_policy(mut self, policy: dt::ActivityMinutesPolicy) -> Self {
+        self.activity_minutes_policy = policy;
+        self
+    }
+
+    /// Decodes the manufacturer-specific EFs the parser keeps as raw bytes with the parsers of
+    /// `registry`, into [`CardData::extensions`].
+    pub fn extensions(mut self, registry: ExtensionRegistry) -> Self {
+        self.extensions = registry;
+        self
+    }
+
+    /// The EFs that come after an EF the card file structure places later, in file order.
+    /// EFs that are not part of the structure, such as vendor EFs, are not checked.
+    pub fn ef_order_deviations(&self) -> Vec<EfOrderDeviation> {
+        ef_order_deviations(&self.input)
+    }
+
+    /// The EF starting at `offset` when the file ends before its header or its declared length.
+    fn truncated_ef(input: &[u8], offset: usize) -> Option<TruncatedBlock> {
+        let ef = &input[offset..];
+        let missing_bytes = match ef {
+            [_, _, _, length_high, length_low, data @ ..] => {
+                let length = u16::from_be_bytes([*length_high, *length_low]) as usize;
+                Some(
+                    length
+                        .checked_sub(data.len())
+                        .filter(|&missing| missing > 0)?,
+                )
+            }
+            _ => None,
+        };
+        Some(TruncatedBlock {
+            offset,
+            header: ef[..ef.len().min(5)].to_vec(),
+            available_bytes: ef.len(),
+            missing_bytes,
+        })
+    }
+
+    pub fn parse(&self) -> Result<CardData> {
+        let settings = ParseSettings {
+            activity_minutes_policy: self.activity_minutes_policy,
+            ..ParseSettings::default()
+        };
+        let (card_data, invalid_fields) =
+            with_parse_context(settings, &self.input, || self.parse_card_data());
+        let mut card_data = card_data?;
+        let applied = self.apply_extensions(&card_data);
+        match &mut card_data {
+            CardData::Gen1 {
+                extensions,
+                diagnostics,
+                ..
+            }
+            | CardData::Gen2 {
+                extensions,
+                diagnostics,
+                ..
+            }
+            | CardData::Gen2V2 {
+                extensions,
+                diagnostics,
+                ..
+            } => {
+                *extensions = applied;
+                diagnostics.invalid_fields = invalid_fields;
+            }
+        }
+        Ok(card_data)
+    }
+
+    fn apply_extensions(&self, card_data: &CardData) -> Vec<CardExtension> {
+        if self.extensions.is_empty() {
+            return Vec::new();
+        }
+        let (gen1_blocks, gen2_blocks) = match card_data {
+            CardData::Gen1 { gen1_blocks, .. } => (gen1_blocks, None),
+            CardData::Gen2 {
+                gen1_blocks,
+                gen2_blocks,
+                ..
+            }
+            | CardData::Gen2V2 {
+                gen1_blocks,
+                gen2_blocks,
+                ..
+            } => (gen1_blocks, Some(gen2_blocks)),
+        };
+        let manufacturer_code = gen1_blocks
+            .card_icc_identification
+            .card_extended_serial_number
+            .manufacturer_code
+            .code;
+        let mut extensions = self
+            .extensions
+            .apply(manufacturer_code, &gen1_blocks.unknown_efs);
+        if let Some(gen2_blocks) = gen2_blocks {
+            extensions.extend(
+                self.extensions
+                    .apply(manufacturer_code, &gen2_blocks.unknown_efs),
+            );
+        }
+        extensions
+    }
+
+    fn parse_card_data(&self) -> Result<CardData> {
+        if self.strict_ef_order {
+            let deviations = self.ef_order_deviations();
+            if let Some(deviation) = deviations.first() {
+                anyhow::bail!(
+                    "{} EFs out of order, the first is EF {:#06x} (file_id {}) at offset {} after EF {:#06x} (file_id {})",
+                    deviations.len(),
+                    deviation.sfid,
+                    deviation.file_id,
+                    deviation.offset,
+                    deviation.preceded_by_sfid,
+                    deviation.preceded_by_file_id
+                );
             }
         }
+        let (mut efs, truncated_ef) = CardEfs::parse(&self.input, self.lenient, None)?;
 
-        let gen1_blocks = CardGen1Blocks {
-            card_icc_identification: card_icc_identification
+        let mut gen1_blocks = CardGen1Blocks {
+            card_icc_identification: efs
+                .card_icc_identification
                 .context("unable to find card_icc_identification gen1 after parsing file")?,
-            card_chip_identification: card_chip_identification
+            card_chip_identification: efs
+                .card_chip_identification
                 .context("unable to find card_chip_identification gen1 after parsing file")?,
-            application_identification: application_identification
+            application_identification: efs
+                .application_identification
                 .context("unable to find application_identification gen1 after parsing file")?,
-            application_identification_signature: application_identification_signature.context(
-                "unable to find application_identification_signature gen1 after parsing file",
-            )?,
-            card_certificate: card_certificate
+            application_identification_signature: efs
+                .application_identification_signature
+                .context(
+                    "unable to find application_identification_signature gen1 after parsing file",
+                )?,
+            card_certificate: efs
+                .card_certificate
                 .context("unable to find card_certificate gen1 after parsing file")?,
-            member_state_certificate: member_state_certificate
+            member_state_certificate: efs
+                .member_state_certificate
                 .context("unable to find member_state_certificate gen1 after parsing file")?,
-            identification: identification
+            identification: efs
+                .identification
                 .context("unable to find identification gen1 after parsing file")?,
-            identification_signature: identification_signature
+            identification_signature: efs
+                .identification_signature
                 .context("unable to find identification_signature gen1 after parsing file")?,
-            card_download,
-            card_download_signature,
-            calibration,
-            calibration_signature,
-            sensor_installation_data,
-            sensor_installation_data_signature,
-            driver_licence_info,
-            driver_licence_info_signature,
-            events_data: events_data
+            card_download: efs.card_download,
+            card_download_signature: efs.card_download_signature,
+            calibration: efs.calibration,
+            calibration_signature: efs.calibration_signature,
+            sensor_installation_data: efs.sensor_installation_data,
+            sensor_installation_data_signature: efs.sensor_installation_data_signature,
+            driver_licence_info: efs.driver_licence_info,
+            driver_licence_info_signature: efs.driver_licence_info_signature,
+            events_data: efs
+                .events_data
                 .context("unable to find events_data gen1 after parsing file")?,
-            events_data_signature: events_data_signature
+            events_data_signature: efs
+                .events_data_signature
                 .context("unable to find events_data_signature gen1 after parsing file")?,
-            faults_data: faults_data
+            faults_data: efs
+                .faults_data
                 .context("unable to find faults_data gen1 after parsing file")?,
-            faults_data_signature: faults_data_signature
+            faults_data_signature: efs
+                .faults_data_signature
                 .context("unable to find faults_data_signature gen1 after parsing file")?,
-            driver_activity_data: driver_activity_data
+            driver_activity_data: efs
+                .driver_activity_data
                 .context("unable to find driver_activity_data gen1 after parsing file")?,
-            driver_activity_data_signature: driver_activity_data_signature
+            driver_activity_data_signature: efs
+                .driver_activity_data_signature
                 .context("unable to find driver_activity_data_signature gen1 after parsing file")?,
-            vehicles_used: vehicles_used
+            vehicles_used: efs
+                .vehicles_used
                 .context("unable to find vehicles_used gen1 after parsing file")?,
-            vehicles_used_signature: vehicles_used_signature
+            vehicles_used_signature: efs
+                .vehicles_used_signature
                 .context("unable to find vehicles_used_signature gen1 after parsing file")?,
-            places: places.context("unable to find places gen1 after parsing file")?,
-            places_signature: places_signature
+            places: efs
+                .places
+                .context("unable to find places gen1 after parsing file")?,
+            places_signature: efs
+                .places_signature
                 .context("unable to find places_signature gen1 after parsing file")?,
-            current_usage: current_usage,
-            current_usage_signature: current_usage_signature,
-            control_activity_data: control_activity_data
+            current_usage: efs.current_usage,
+            current_usage_signature: efs.current_usage_signature,
+            control_activity_data: efs
+                .control_activity_data
                 .context("unable to find control_activity_data gen1 after parsing file")?,
-            control_activity_data_signature: control_activity_data_signature.context(
+            control_activity_data_signature: efs.control_activity_data_signature.context(
                 "unable to find control_activity_data_signature gen1 after parsing file",
             )?,
-            specific_conditions: specific_conditions
+            specific_conditions: efs
+                .specific_conditions
                 .context("unable to find specific_conditions gen1 after parsing file")?,
-            specific_conditions_signature: specific_conditions_signature
+            specific_conditions_signature: efs
+                .specific_conditions_signature
                 .context("unable to find specific_conditions_signature gen1 after parsing file")?,
+            unknown_efs: efs.unknown_efs,
         };
 
         let mut gen2_blocks: Option<CardGen2Blocks> = None;
 
-        if card_icc_identification_gen2.is_some() {
+        if efs.card_icc_identification_gen2.is_some() {
             let blocks = CardGen2Blocks {
-                card_icc_identification: card_icc_identification_gen2
+                card_icc_identification: efs.card_icc_identification_gen2
                     .context("unable to find card_icc_identification gen2 after parsing file")?,
-                card_chip_identification: card_chip_identification_gen2
+                card_chip_identification: efs.card_chip_identification_gen2
                     .context("unable to find card_chip_identification gen2 after parsing file")?,
-                application_identification: application_identification_gen2
+                application_identification: efs.application_identification_gen2
                     .context("unable to find application_identification gen2 after parsing file")?,
-                application_identification_signature: application_identification_signature_gen2
+                application_identification_signature: efs.application_identification_signature_gen2
                     .context(
                     "unable to find application_identification_signature gen2 after parsing file",
                 )?,
-                card_sign_certificate: card_sign_certificate_gen2
+                card_sign_certificate: efs.card_sign_certificate_gen2
                     .context("unable to find card_sign_certificate gen2 after parsing file")?,
-                ca_certificate: ca_certificate_gen2
+                ca_certificate: efs.ca_certificate_gen2
                     .context("unable to find ca_certificate gen2 after parsing file")?,
-                link_certificate: link_certificate_gen2
+                link_certificate: efs.link_certificate_gen2
                     .context("unable to find link_certificate gen2 after parsing file")?,
-                identification: identification_gen2
+                identification: efs.identification_gen2
                     .context("unable to find identification gen2 after parsing file")?,
-                identification_signature: identification_signature_gen2
+                identification_signature: efs.identification_signature_gen2
                     .context("unable to find identification_signature gen2 after parsing file")?,
-                card_download: card_download_gen2,
-                card_download_signature: card_download_signature_gen2,
-                driver_licence_info: driver_licence_info_gen2,
-                driver_licence_info_signature: driver_licence_info_signature_gen2,
-                events_data: events_data_gen2
+                card_download: efs.card_download_gen2,
+                card_download_signature: efs.card_download_signature_gen2,
+                driver_licence_info: efs.driver_licence_info_gen2,
+                driver_licence_info_signature: efs.driver_licence_info_signature_gen2,
+                events_data: efs.events_data_gen2
                     .context("unable to find events_data gen2 after parsing file")?,
-                events_data_signature: events_data_signature_gen2
+                events_data_signature: efs.events_data_signature_gen2
                     .context("unable to find events_data_signature gen2 after parsing file")?,
-                faults_data: faults_data_gen2
+                faults_data: efs.faults_data_gen2
                     .context("unable to find faults_data gen2 after parsing file")?,
-                faults_data_signature: faults_data_signature_gen2
+                faults_data_signature: efs.faults_data_signature_gen2
                     .context("unable to find faults_data_signature gen2 after parsing file")?,
-                driver_activity_data: driver_activity_data_gen2
+                driver_activity_data: efs.driver_activity_data_gen2
                     .context("unable to find driver_activity_data gen2 after parsing file")?,
-                driver_activity_data_signature: driver_activity_data_signature_gen2.context(
+                driver_activity_data_signature: efs.driver_activity_data_signature_gen2.context(
                     "unable to find driver_activity_data_signature gen2 after parsing file",
                 )?,
-                vehicles_used: vehicles_used_gen2
+                vehicles_used: efs.vehicles_used_gen2
                     .context("unable to find vehicles_used gen2 after parsing file")?,
-                vehicles_used_signature: vehicles_used_signature_gen2
+                vehicles_used_signature: efs.vehicles_used_signature_gen2
                     .context("unable to find vehicles_used_signature gen2 after parsing file")?,
-                places: places_gen2.context("unable to find places gen2 after parsing file")?,
-                places_signature: places_signature_gen2
+                places: efs.places_gen2.context("unable to find places gen2 after parsing file")?,
+                places_signature: efs.places_signature_gen2
                     .context("unable to find places_signature gen2 after parsing file")?,
-                current_usage: current_usage_gen2,
-                current_usage_signature: current_usage_signature_gen2,
-                control_activity_data: control_activity_data_gen2
+                current_usage: efs.current_usage_gen2,
+                current_usage_signature: efs.current_usage_signature_gen2,
+                control_activity_data: efs.control_activity_data_gen2
                     .context("unable to find control_activity_data gen2 after parsing file")?,
-                control_activity_data_signature: control_activity_data_signature_gen2.context(
+                control_activity_data_signature: efs.control_activity_data_signature_gen2.context(
                     "unable to find control_activity_data_signature gen2 after parsing file",
                 )?,
-                specific_conditions: specific_conditions_gen2
+                specific_conditions: efs.specific_conditions_gen2
                     .context("unable to find specific_conditions gen2 after parsing file")?,
-                specific_conditions_signature: specific_conditions_signature_gen2.context(
+                specific_conditions_signature: efs.specific_conditions_signature_gen2.context(
                     "unable to find specific_conditions_signature gen2 after parsing file",
                 )?,
-                vehicle_units_used: vehicle_units_used_gen2
+                vehicle_units_used: efs.vehicle_units_used_gen2
                     .context("unable to find vehicle_units_used gen2 after parsing file")?,
-                vehicle_units_used_signature: vehicle_units_used_signature_gen2.context(
+                vehicle_units_used_signature: efs.vehicle_units_used_signature_gen2.context(
                     "unable to find vehicle_units_used_signature gen2 after parsing file",
                 )?,
-                gnss_accumulated_driving: gnss_places_gen2
+                gnss_accumulated_driving: efs.gnss_places_gen2
                     .context("unable to find gnss_accumulated_driving gen2 after parsing file")?,
-                gnss_places_signature: gnss_places_signature_gen2
+                gnss_places_signature: efs.gnss_places_signature_gen2
                     .context("unable to find gnss_places_signature gen2 after parsing file")?,
+                unknown_efs: efs.unknown_efs_gen2,
             };
             gen2_blocks = Some(blocks);
+        } else if !efs.unknown_efs_gen2.is_empty() {
+            log::warn!(
+                "Found {} unknown Gen2 EFs in a card without Gen2 data, keeping them with the Gen1 data",
+                efs.unknown_efs_gen2.len()
+            );
+            gen1_blocks.unknown_efs.append(&mut efs.unknown_efs_gen2);
         }
         let mut gen2v2_blocks: Option<CardGen2V2Blocks> = None;
-        if application_identification_gen2v2.is_some() {
+        if efs.application_identification_gen2v2.is_some() {
             let blocks = CardGen2V2Blocks {
-                application_identification: application_identification_gen2v2.context(
+                card_icc_identification: efs.card_icc_identification_gen2v2,
+                application_identification: efs.application_identification_gen2v2.context(
                     "unable to find application_identification gen2v2 after parsing file",
                 )?,
-                application_identification_signature: application_identification_signature_gen2v2
+                application_identification_signature: efs.application_identification_signature_gen2v2
                     .context(
                     "unable to find application_identification_signature gen2v2 after parsing file",
                 )?,
-                places_authentication: places_authentication_gen2v2
+                places_authentication: efs.places_authentication_gen2v2
                     .context("unable to find places_authentication gen2v2 after parsing file")?,
-                places_authentication_signature: places_authentication_signature_gen2v2.context(
+                places_authentication_signature: efs.places_authentication_signature_gen2v2.context(
                     "unable to find places_authentication_signature gen2v2 after parsing file",
                 )?,
-                gnss_places_authentication: gnss_places_authentication_gen2v2.context(
+                gnss_places_authentication: efs.gnss_places_authentication_gen2v2.context(
                     "unable to find gnss_places_authentication gen2v2 after parsing file",
                 )?,
-                gnss_places_authentication_signature: gnss_places_authentication_signature_gen2v2
+                gnss_places_authentication_signature: efs.gnss_places_authentication_signature_gen2v2
                     .context(
                     "unable to find gnss_places_authentication_signature gen2v2 after parsing file",
                 )?,
-                border_crossings: border_crossings_gen2v2
+                border_crossings: efs.border_crossings_gen2v2
                     .context("unable to find border_crossings gen2v2 after parsing file")?,
-                border_crossings_signature: border_crossings_signature_gen2v2.context(
+                border_crossings_signature: efs.border_crossings_signature_gen2v2.context(
                     "unable to find border_crossings_signature gen2v2 after parsing file",
                 )?,
-                load_unload_operations: load_unload_operations_gen2v2
+                load_unload_operations: efs.load_unload_operations_gen2v2
                     .context("unable to find load_unload_operations gen2v2 after parsing file")?,
-                load_unload_operations_signature: load_unload_operations_signature_gen2v2.context(
+                load_unload_operations_signature: efs.load_unload_operations_signature_gen2v2.context(
                     "unable to find load_unload_operations_signature gen2v2 after parsing file",
                 )?,
-                load_type_entries: load_type_entries_gen2v2
+                load_type_entries: efs.load_type_entries_gen2v2
                     .context("unable to find load_type_entries gen2v2 after parsing file")?,
-                load_type_entries_signature: load_type_entries_signature_gen2v2.context(
+                load_type_entries_signature: efs.load_type_entries_signature_gen2v2.context(
                     "unable to find load_type_entries_signature gen2v2 after parsing file",
                 )?,
-                vu_configurations: vu_configurations_gen2v2,
-                vu_configurations_signature: vu_configurations_signature_gen2v2,
+                vu_configurations: efs.vu_configurations_gen2v2,
+                vu_configurations_signature: efs.vu_configurations_signature_gen2v2,
             };
             gen2v2_blocks = Some(blocks);
         }
 
+        let diagnostics = CardDiagnostics {
+            truncated_ef,
+            ..CardDiagnostics::default()
+        };
         Ok(match (gen1_blocks, gen2_blocks, gen2v2_blocks) {
-            (gen1, None, None) => CardData::Gen1 { gen1_blocks: gen1 },
+            (gen1, None, None) => CardData::Gen1 {
+                gen1_blocks: gen1,
+                extensions: Vec::new(),
+                diagnostics,
+            },
             (gen1, Some(gen2), None) => CardData::Gen2 {
                 gen1_blocks: gen1,
                 gen2_blocks: gen2,
+                extensions: Vec::new(),
+                diagnostics,
             },
             (gen1, Some(gen2), Some(gen2v2)) => CardData::Gen2V2 {
                 gen1_blocks: gen1,
                 gen2_blocks: gen2,
                 gen2v2_blocks: gen2v2,
+                extensions: Vec::new(),
+                diagnostics,
             },
             _ => anyhow::bail!("Invalid combination of card blocks"),
         })
     }
 
+    /// Parses the whole card file, then writes its identification, activity and event records
+    /// into `sink`, see [`sink::write_card_data`]. Unlike [`VuParser::parse_to_sink`], nothing is
+    /// written while the EFs are parsed: Gen2 cards carry a Gen1 copy of every EF ahead of their
+    /// Gen2 section, so which copy to write is only known once the last EF is read. A failing
+    /// parse writes no records.
+    ///
+    /// [`VuParser::parse_to_sink`]: crate::vu_parser::VuParser::parse_to_sink
+    pub fn parse_to_sink(&self, sink: &mut impl TachoSink) -> Result<()> {
+        let card_data = self.parse().context("Failed to parse card data")?;
+        sink::write_card_data(&card_data, sink)
+    }
+    /// Lists every EF found in the file with its size and how far the parser got with it, running
+    /// the EFs through the same dispatch as `parse`. Unlike `parse`, a bad EF is recorded instead
+    /// of failing, which makes it suitable for measuring coverage across an archive of files. It
+    /// only fails when the file ends inside an EF header.
+    pub fn ef_coverage(&self) -> Result<Vec<EfCoverage>> {
+        ef_coverage(&self.input)
+    }
     pub fn parse_to_json(&self) -> Result<String> {
         let card_data = self.parse().context("Failed to parse vehicle data")?;
         let json = serde_json::to_string(&card_data)
@@ -1338,9 +1968,56 @@ impl CardParser {
             .context("Failed to convert serde value to pretty JSON string")?;
         Ok(pretty_json)
     }
+    /// Parses the file while recording every primitive read, see [`trace::render_annotated_hex`].
+    /// The trace is returned even when parsing fails, up to the failing read.
+    pub fn parse_with_trace(&self) -> (Result<CardData>, Vec<TraceEntry>) {
+        trace::capture(&self.input, || self.parse())
+    }
+    pub fn parse_to_json_with_options(&self, options: &JsonOptions) -> Result<String> {
+        let card_data = self.parse().context("Failed to parse vehicle data")?;
+        json::to_json_with_source(&card_data, &self.input, options)
+    }
+    /// Same as [`Self::parse_to_json_with_options`], in CBOR or MessagePack.
+    pub fn parse_to_binary_with_options(
+        &self,
+        format: BinaryFormat,
+        options: &JsonOptions,
+    ) -> Result<Vec<u8>> {
+        let card_data = self.parse().context("Failed to parse card data")?;
+        binary::to_binary_with_source(&card_data, &self.input, format, options)
+    }
+}
+
+/// Parses the `data` of an EF, split off the file by [`CardEfs::parse`].
+fn parse_ef<T>(
+    data: &mut Cursor<&[u8]>,
+    parse_block: impl FnOnce(&mut Cursor<&[u8]>) -> Result<T>,
+) -> Result<T> {
+    parse_block(data).with_context(|| {
+        format!(
+            "Failed to parse data in EF of size {} for type {}",
+            data.get_ref().len(),
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+/// Parses the `data` of an EF whose parser takes the EF size.
+fn parse_ef_dyn_size<T>(
+    data: &mut Cursor<&[u8]>,
+    parse_block: impl FnOnce(&mut Cursor<&[u8]>, usize) -> Result<T>,
+) -> Result<T> {
+    let size = data.get_ref().len();
+    parse_block(data, size).with_context(|| {
+        format!(
+            "Failed to parse data with dyn size in EF of size {} for type {}",
+            size,
+            std::any::type_name::<T>()
+        )
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardBlock<T> {
@@ -1349,9 +2026,9 @@ pub struct CardBlock<T> {
 }
 
 impl<T> CardBlock<T> {
-    pub fn parse<F>(cursor: &mut Cursor<&[u8]>, parse_block: F) -> Result<Self>
+    pub fn parse<F>(cursor: &mut Cursor<&[u8]>, mut parse_block: F) -> Result<Self>
     where
-        F: Fn(&mut Cursor<&[u8]>) -> Result<T>,
+        F: FnMut(&mut Cursor<&[u8]>) -> Result<T>,
     {
         let size = cursor
             .read_u16::<BigEndian>()
@@ -1386,9 +2063,9 @@ impl<T> CardBlock<T> {
         Ok(CardBlock { size, data })
     }
 
-    pub fn parse_dyn_size<F>(cursor: &mut Cursor<&[u8]>, parse_block: F) -> Result<Self>
+    pub fn parse_dyn_size<F>(cursor: &mut Cursor<&[u8]>, mut parse_block: F) -> Result<Self>
     where
-        F: Fn(&mut Cursor<&[u8]>, usize) -> Result<T>,
+        F: FnMut(&mut Cursor<&[u8]>, usize) -> Result<T>,
     {
         let size = cursor
             .read_u16::<BigEndian>()
@@ -1426,15 +2103,681 @@ impl<T> CardBlock<T> {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum EfParseStatus {
+    /// Every byte of the EF was consumed by its parser.
+    Parsed,
+    /// The parser succeeded but left some bytes unread.
+    #[serde(rename_all = "camelCase")]
+    PartiallyParsed { unused_bytes: u16 },
+    /// The parser returned an error for this EF.
+    Failed { error: String },
+    /// The EF is not handled by the parser and was kept as raw bytes, see [`RawCardEf`].
+    PreservedRaw,
+    /// The EF has an invalid file_id, or comes after an EF that made the parser stop.
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// One EF found in a card file, see [`CardParser::ef_coverage`].
+pub struct EfCoverage {
+    /// Offset of the EF tag from the start of the file.
+    pub offset: usize,
+    pub sfid: u16,
+    pub file_id: u8,
+    pub size: u16,
+    pub status: EfParseStatus,
+}
+
+/// See [`CardParser::ef_coverage`].
+pub(crate) fn ef_coverage(input: &[u8]) -> Result<Vec<EfCoverage>> {
+    let mut coverage = Vec::new();
+    CardEfs::parse(input, false, Some(&mut coverage))?;
+    Ok(coverage)
+}
+
+/// Records the EFs from `offset` on as skipped, `parse` stops at the EF there and never looks at
+/// the ones after it.
+fn skip_efs(input: &[u8], mut offset: usize, coverage: &mut Vec<EfCoverage>) {
+    while let Some(&[sfid_high, sfid_low, file_id, size_high, size_low, ..]) = input.get(offset..) {
+        let sfid = u16::from_be_bytes([sfid_high, sfid_low]);
+        let size = u16::from_be_bytes([size_high, size_low]);
+        let remaining = input.len() - offset - 5;
+        let status = if remaining < size as usize {
+            EfParseStatus::Failed {
+                error: format!(
+                    "EF declares {} bytes but only {} remain in the file",
+                    size, remaining
+                ),
+            }
+        } else {
+            EfParseStatus::Skipped
+        };
+        coverage.push(EfCoverage {
+            offset,
+            sfid,
+            file_id,
+            size,
+            status,
+        });
+        offset += 5 + size as usize;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An EF found after one that the card file structure places later, see
+/// [`CardParser::ef_order_deviations`].
+pub struct EfOrderDeviation {
+    /// Offset of the EF tag from the start of the file.
+    pub offset: usize,
+    pub sfid: u16,
+    pub file_id: u8,
+    /// The EF before it that the structure places after it.
+    pub preceded_by_sfid: u16,
+    pub preceded_by_file_id: u8,
+}
+
+/// See [`CardParser::ef_order_deviations`].
+pub(crate) fn ef_order_deviations(input: &[u8]) -> Vec<EfOrderDeviation> {
+    let mut deviations = Vec::new();
+    // the furthest EF in the expected order seen so far, with its rank
+    let mut furthest: Option<((u8, usize, u8), u16, u8)> = None;
+    let mut offset = 0;
+    while let Some(&[sfid_high, sfid_low, file_id, size_high, size_low, ..]) = input.get(offset..) {
+        let sfid = u16::from_be_bytes([sfid_high, sfid_low]);
+        if let Some(position) = EfTag::from_fid(sfid).position() {
+            // the Gen1 EFs come before the Gen2 ones, and each signature right after its data
+            let rank = (file_id / 2, position, file_id % 2);
+            match furthest {
+                Some((furthest_rank, preceded_by_sfid, preceded_by_file_id))
+                    if rank < furthest_rank =>
+                {
+                    deviations.push(EfOrderDeviation {
+                        offset,
+                        sfid,
+                        file_id,
+                        preceded_by_sfid,
+                        preceded_by_file_id,
+                    })
+                }
+                _ => furthest = Some((rank, sfid, file_id)),
+            }
+        }
+        offset += 5 + u16::from_be_bytes([size_high, size_low]) as usize;
+    }
+    deviations
+}
+
+/// Reads only the card identification EF of a card file, skipping every other EF by its size
+/// header, for indexing and routing files without parsing them.
+pub fn peek_card_identification(bytes: &[u8]) -> Result<sink::IdentificationRecord> {
+    let mut cursor = Cursor::new(bytes);
+    while !cursor.fill_buf()?.is_empty() {
+        let sfid = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read sfid")?;
+        let file_id = cursor.read_u8().context("Failed to read file_id")?;
+        let size = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read EF size")?;
+        let start = cursor.position() as usize;
+        let data = bytes
+            .get(start..start + size as usize)
+            .context("EF declares more bytes than remain in the file")?;
+        let is_data =
+            EfAppendix::from_byte(file_id).is_some_and(|appendix| !appendix.is_signature());
+        if EfTag::from_fid(sfid) == EfTag::Identification && is_data {
+            let identification = dt::Identification::parse(&mut Cursor::new(data))
+                .context("Failed to parse Identification")?;
+            return Ok(sink::card_identification_record(&identification));
+        }
+        cursor.set_position((start + size as usize) as u64);
+    }
+    anyhow::bail!("No Identification EF found in the card file")
+}
+
 #[cfg(test)]
 mod tests {
     use rayon::prelude::*;
     use serde_json;
 
     use super::*;
+    use crate::units::Kilometers;
     use std::fs;
     use std::path::Path;
 
+    fn daily_record(date: &str, length: u16) -> dt::CardActivityDailyRecord {
+        dt::CardActivityDailyRecord {
+            activity_previous_record_length: 0,
+            activity_record_length: length,
+            activity_record_date: dt::TimeReal(date.parse().unwrap()),
+            activity_daily_presence_counter: dt::DailyPresenceCounter(0),
+            activity_day_distance: dt::Distance(Kilometers(0)),
+            activity_change_info: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ef_tags() {
+        assert_eq!(EfTag::all().count(), 30);
+        for tag in EfTag::all() {
+            assert_eq!(EfTag::from_fid(tag.fid()), tag);
+        }
+        assert_eq!(EfTag::from_fid(0x0509), EfTag::WorkshopCardDownload);
+        assert_eq!(EfTag::from_fid(0x0600), EfTag::Unknown(0x0600));
+        assert_eq!(EfTag::Unknown(0x0600).fid(), 0x0600);
+        assert_eq!(EfTag::Unknown(0x0600).name(), None);
+        assert!(EfTag::EventsData.is_signed());
+        assert!(!EfTag::CardCertificate.is_signed());
+        assert_eq!(EfTag::VuConfigurations.since(), Some(Generation::Gen2V2));
+
+        for byte in 0..=3 {
+            assert_eq!(EfAppendix::from_byte(byte).unwrap().byte(), byte);
+        }
+        assert_eq!(EfAppendix::from_byte(4), None);
+        assert_eq!(EfAppendix::Gen2Data.signature(), EfAppendix::Gen2Signature);
+        assert!(EfAppendix::Gen1Signature.is_signature());
+        assert!(!EfAppendix::Gen1Signature.is_gen2());
+    }
+
+    #[test]
+    fn test_estimate_activity_memory() {
+        // 3 records over 4 calendar days, 100 bytes each
+        let activity = dt::CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 200,
+            activity_daily_records: vec![
+                daily_record("2024-03-01T00:00:00Z", 100),
+                daily_record("2024-03-02T00:00:00Z", 100),
+                daily_record("2024-03-04T00:00:00Z", 100),
+            ],
+        };
+        let estimate = estimate_activity_memory(&activity, 1000);
+        assert_eq!(estimate.used_bytes, 300);
+        assert_eq!(estimate.free_bytes, 700);
+        assert_eq!(estimate.average_daily_record_bytes, Some(100));
+        assert_eq!(estimate.daily_records_until_overwrite, Some(7));
+        // the 8th new record overwrites, at 0.75 records per day that is 11 days later
+        assert_eq!(
+            estimate.projected_overwrite_date,
+            Some("2024-03-15T00:00:00Z".parse().unwrap())
+        );
+
+        let empty = dt::CardDriverActivity {
+            activity_pointer_oldest_day_record: 0,
+            activity_pointer_newest_record: 0,
+            activity_daily_records: Vec::new(),
+        };
+        let estimate = estimate_activity_memory(&empty, 1000);
+        assert_eq!(estimate.free_bytes, 1000);
+        assert_eq!(estimate.projected_overwrite_date, None);
+    }
+
+    #[test]
+    fn test_application_identification_gen2_dispatches_on_card_type() {
+        // Workshop card: type, structure version, events, faults, activity length,
+        // vehicle records, place records, calibration records, gnss, specific conditions, vu records
+        let workshop = [
+            0x02, 0x01, 0x00, 0x03, 0x06, 0x1F, 0x40, 0x00, 0x58, 0x01, 0x00, 0xFF, 0x00, 0x12,
+            0x00, 0x38, 0x00, 0x0C,
+        ];
+        let mut cursor = Cursor::new(&workshop[..]);
+        let parsed = gen2::ApplicationIdentificationGen2::parse(&mut cursor).unwrap();
+        let gen2::ApplicationIdentificationGen2::WorkshopCard(app) = parsed else {
+            panic!("Expected a workshop card application identification");
+        };
+        assert_eq!(app.activity_structure_length, 0x1F40);
+        assert_eq!(app.no_of_card_place_records, 0x0100);
+        assert_eq!(app.no_of_calibration_records, 0xFF);
+        assert_eq!(app.no_of_card_vehicle_unit_records, 0x0C);
+        assert_eq!(cursor.position(), workshop.len() as u64);
+
+        // Control card: type, structure version, control activity records
+        let control = [0x03, 0x01, 0x00, 0x00, 0xE6];
+        let parsed = gen2::ApplicationIdentificationGen2::parse(&mut Cursor::new(&control[..]));
+        assert!(matches!(
+            parsed,
+            Ok(gen2::ApplicationIdentificationGen2::ControlCard(app))
+                if app.no_of_control_activity_records == 230
+        ));
+    }
+
+    #[test]
+    fn test_card_icc_identification_gen2v2_keeps_additional_data() {
+        let mut data = vec![0x01];
+        // extended serial number: serial, month/year, equipment type, manufacturer
+        data.extend([0x00, 0x01, 0xE2, 0x40, 0x03, 0x24, 0x01, 0x10]);
+        data.extend(b"e1*0001 ");
+        data.push(0x10);
+        // embedder: country, module embedder, manufacturer information
+        data.extend([b'R', b'O', 0x00, 0x42, 0x01]);
+        data.extend([0xAB, 0xCD]);
+        data.extend([0xDE, 0xAD, 0xBE]);
+
+        let icc = gen2v2::CardIccIdentificationGen2V2::parse_dyn_size(
+            &mut Cursor::new(data.as_slice()),
+            data.len(),
+        )
+        .unwrap();
+        assert_eq!(
+            icc.card_extended_serial_number.to_serial_string(),
+            "123456 03/24 Actia S.A."
+        );
+        assert_eq!(icc.ic_identifier, vec![0xAB, 0xCD]);
+        assert_eq!(icc.additional_data, vec![0xDE, 0xAD, 0xBE]);
+
+        let gen2 =
+            gen2::CardIccIdentificationGen2::parse(&mut Cursor::new(data.as_slice())).unwrap();
+        assert_eq!(
+            gen2.card_extended_serial_number.to_serial_string(),
+            icc.card_extended_serial_number.to_serial_string()
+        );
+
+        assert!(gen2v2::CardIccIdentificationGen2V2::parse_dyn_size(
+            &mut Cursor::new(&data[..20]),
+            20
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_card_block_parse_with_record_count() {
+        // size, place pointer, then room for 2 place records
+        let mut input = vec![0x00, 0x15, 0x00];
+        for _ in 0..2 {
+            input.extend([0x65, 0xE1, 0x1A, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        }
+
+        let mut parsed_blocks = 0;
+        let places =
+            CardBlock::parse_dyn_size(&mut Cursor::new(input.as_slice()), |cursor, size| {
+                parsed_blocks += 1;
+                gen1::CardPlaceDailyWorkPeriod::parse_with_no_of_records(cursor, size, Some(1))
+            })
+            .unwrap()
+            .into_inner();
+        assert_eq!(parsed_blocks, 1);
+        assert_eq!(places.place_records.len(), 1);
+
+        let places = CardBlock::parse_dyn_size(
+            &mut Cursor::new(input.as_slice()),
+            gen1::CardPlaceDailyWorkPeriod::parse_dyn_size,
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(places.place_records.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_entry_type_keeps_raw_byte() {
+        // entry time, entry type 0x07 (not defined by the spec), country, region, odometer
+        let input = [0x65, 0xE1, 0x1A, 0x80, 0x07, 0x00, 0x00, 0x00, 0x00, 0x10];
+        let record = gen1::PlaceRecord::parse(&mut Cursor::new(&input[..])).unwrap();
+        assert!(matches!(
+            record.entry_type_daily_work_period,
+            dt::Coded::Unknown(0x07)
+        ));
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["entryTypeDailyWorkPeriod"], 7);
+
+        let mut known = input;
+        known[4] = 0x02;
+        let record = gen1::PlaceRecord::parse(&mut Cursor::new(&known[..])).unwrap();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json["entryTypeDailyWorkPeriod"],
+            "BeginRelatedTimeManuallyEntered"
+        );
+    }
+
+    #[test]
+    fn test_reserved_values_keep_raw_byte() {
+        // entry time, entry type, country, a region newer than the list, odometer
+        let input = [0x65, 0xE1, 0x1A, 0x80, 0x00, 0x0F, 0x30, 0x00, 0x00, 0x10];
+        let record = gen1::PlaceRecord::parse(&mut Cursor::new(&input[..])).unwrap();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["dailyWorkPeriodRegion"], 0x30);
+
+        let event_type = gen1::EventFaultType::parse(&mut Cursor::new(&[0x0B][..])).unwrap();
+        assert_eq!(event_type, gen1::EventFaultType::RFU(0x0B));
+        assert_eq!(serde_json::to_value(&event_type).unwrap()["RFU"], 0x0B);
+        assert_eq!(crate::sink::label(&event_type), "RFU");
+
+        // a company card in the codriver slot, a reserved code in the driver slot
+        let status = dt::CardSlotsStatus::parse(&mut Cursor::new(&[0x47][..])).unwrap();
+        assert_eq!(
+            status.codriver,
+            dt::Coded::Known(dt::CardSlotStatus::CompanyCardInserted)
+        );
+        assert_eq!(status.driver, dt::Coded::Unknown(0x07));
+        assert_eq!(status.encode(), 0x47);
+    }
+
+    #[test]
+    fn test_truncated_ef() {
+        // complete EF of 2 bytes, then an EF declaring 4 bytes of which 1 is present
+        let input = [
+            0x05, 0x01, 0x00, 0x00, 0x02, 0xAA, 0xBB, 0x05, 0x02, 0x00, 0x00, 0x04, 0xCC,
+        ];
+        assert_eq!(CardParser::truncated_ef(&input, 0), None);
+        let truncated = CardParser::truncated_ef(&input, 7).unwrap();
+        assert_eq!(truncated.header, vec![0x05, 0x02, 0x00, 0x00, 0x04]);
+        assert_eq!(truncated.available_bytes, 6);
+        assert_eq!(truncated.missing_bytes, Some(3));
+
+        let truncated = CardParser::truncated_ef(&input[..10], 7).unwrap();
+        assert_eq!(truncated.header, vec![0x05, 0x02, 0x00]);
+        assert_eq!(truncated.missing_bytes, None);
+    }
+
+    #[test]
+    fn test_invalid_card_number_is_reported() {
+        // control with a card of an RFU type, the other fields left empty
+        let mut input = vec![0x00; 46];
+        input[5] = 0xEE;
+        input[6] = 0x01;
+        let (record, fields) = with_parse_context(ParseSettings::default(), &input, || {
+            gen1::CardControlActivityDataRecord::parse(&mut Cursor::new(&input[..])).unwrap()
+        });
+        assert_eq!(record.control_card_number, None);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "control_card_number");
+        assert_eq!(fields[0].offset, Some(5));
+    }
+
+    #[test]
+    fn test_activity_minutes_past_end_of_day() {
+        // Driving at 10:00, then at minute 1500
+        let input = [0x1A, 0x58, 0x1D, 0xDC];
+        let parse = |policy| {
+            let settings = ParseSettings {
+                activity_minutes_policy: policy,
+                ..ParseSettings::default()
+            };
+            with_parse_context(settings, &input, || {
+                let mut cursor = Cursor::new(&input[..]);
+                let changes = (0..2)
+                    .map(|_| dt::CardActivityChangeInfo::parse(&mut cursor).unwrap())
+                    .collect::<Vec<_>>();
+                dt::CardActivityChangeInfo::drop_past_end_of_day(changes)
+            })
+        };
+
+        let (changes, fields) = parse(dt::ActivityMinutesPolicy::Keep);
+        assert_eq!(changes[1].minutes.0, 1500);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "minutes");
+        assert_eq!(fields[0].offset, Some(2));
+        assert_eq!(fields[0].raw, vec![0x1D, 0xDC]);
+
+        let (changes, fields) = parse(dt::ActivityMinutesPolicy::Clamp);
+        assert_eq!(changes[1].minutes.0, 1439);
+        assert_eq!(fields.len(), 1);
+
+        let (changes, fields) = parse(dt::ActivityMinutesPolicy::Drop);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].minutes.0, 600);
+        assert_eq!(fields.len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_ef_fails_with_trace() {
+        // the same ApplicationIdentification twice
+        let ef = [
+            0x05, 0x01, 0x00, 0x00, 0x0A, 0x01, 0x01, 0x00, 0x0C, 0x06, 0x1C, 0x00, 0x00, 0x54,
+            0x70,
+        ];
+        let input = [ef, ef].concat();
+        let (result, entries) = CardParser::new_from_bytes(&input)
+            .unwrap()
+            .parse_with_trace();
+        let error = format!("{:#}", result.unwrap_err());
+        assert!(error.contains("duplicate block type"), "{}", error);
+        assert!(entries.iter().any(|entry| entry.offset == Some(5)));
+    }
+
+    #[test]
+    fn test_trailing_bytes_fail_without_panicking() {
+        // an empty unknown EF, then a single byte of the next header
+        let input = [0x06, 0x00, 0x00, 0x00, 0x00, 0x05];
+        let error = CardParser::new_from_bytes(&input)
+            .unwrap()
+            .parse()
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("Failed to read sfid"));
+    }
+
+    #[test]
+    fn test_specific_conditions_keep_unknown_types() {
+        // ferry crossing begin, a Gen2 crossing end and an empty slot
+        let input = [
+            0x65, 0xE1, 0x1A, 0x80, 0x03, 0x65, 0xE1, 0x28, 0x90, 0x04, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ];
+        let conditions =
+            gen1::SpecificConditions::parse_dyn_size(&mut Cursor::new(&input[..]), input.len())
+                .unwrap();
+        let types: Vec<_> = conditions
+            .specific_condition_records
+            .iter()
+            .map(|record| record.specific_condition_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                dt::Coded::Known(gen1::SpecificConditionType::FerryTrainCrossingBegin),
+                dt::Coded::Unknown(0x04)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_card_event_data_gen2_groups() {
+        // 11 sub-arrays of 2 records, only the second record of sub-array 2 is filled
+        const RECORD_SIZE: usize = 24;
+        let mut input = vec![0u8; 11 * 2 * RECORD_SIZE];
+        let offset = 5 * RECORD_SIZE;
+        input[offset] = 0x03;
+        input[offset + 1..offset + 5].copy_from_slice(&0x65E1_1A80u32.to_be_bytes());
+        input[offset + 5..offset + 9].copy_from_slice(&0x65E1_2890u32.to_be_bytes());
+
+        let events =
+            gen2::CardEventDataGen2::parse_dyn_size(&mut Cursor::new(&input[..]), input.len())
+                .unwrap();
+        let groups: Vec<_> = events.per_type.keys().copied().collect();
+        assert_eq!(groups, vec![gen2::EventFaultTypeGroup(2)]);
+        assert_eq!(events.records().count(), 1);
+        assert!(matches!(
+            events.per_type[&gen2::EventFaultTypeGroup(2)][0].event_type,
+            gen2::EventFaultTypeGen2::TimeOverlap
+        ));
+    }
+
+    #[test]
+    fn test_place_record_id_ignores_non_key_fields() {
+        let input = [0x65, 0xE1, 0x1A, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10];
+        let record = gen1::PlaceRecord::parse(&mut Cursor::new(&input[..])).unwrap();
+
+        let mut other_odometer = input;
+        other_odometer[9] = 0x20;
+        let same_entry = gen1::PlaceRecord::parse(&mut Cursor::new(&other_odometer[..])).unwrap();
+        assert_eq!(record.record_id("owner"), same_entry.record_id("owner"));
+
+        let mut end_entry = input;
+        end_entry[4] = 0x01;
+        let end_entry = gen1::PlaceRecord::parse(&mut Cursor::new(&end_entry[..])).unwrap();
+        assert_ne!(record.record_id("owner"), end_entry.record_id("owner"));
+        assert_ne!(record.record_id("owner"), record.record_id("other"));
+    }
+
+    #[test]
+    fn test_gnss_accumulated_driving_with_record_count() {
+        // pointer, then room for 3 records, the application identification counts 2
+        let mut input = vec![0x00, 0x00];
+        for _ in 0..3 {
+            input.extend([0x65, 0xE1, 0x1A, 0x80, 0x65, 0xE1, 0x1A, 0x80, 0x0A]);
+            input.extend([0x7F, 0xFF, 0xFF, 0x7F, 0xFF, 0xFF, 0x00, 0x10, 0x00]);
+        }
+        let mut cursor = Cursor::new(input.as_slice());
+        let gnss = gen2::GnssAccumulatedDrivingGen2::parse_with_no_of_records(
+            &mut cursor,
+            input.len(),
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(gnss.gnss_accumulated_driving_records.len(), 2);
+        // the whole EF is consumed either way
+        assert_eq!(cursor.position() as usize, input.len());
+
+        let gnss = gen2::GnssAccumulatedDrivingGen2::parse(
+            &mut Cursor::new(input.as_slice()),
+            input.len(),
+        )
+        .unwrap();
+        assert_eq!(gnss.gnss_accumulated_driving_records.len(), 3);
+    }
+
+    #[test]
+    fn test_raw_card_ef_round_trips() {
+        let input = [0xC1, 0x10, 0x02, 0x00, 0x03, 0xAA, 0xBB, 0xCC];
+        let raw_ef = RawCardEf {
+            sfid: 0xC110,
+            file_id: 2,
+            data: vec![0xAA, 0xBB, 0xCC],
+        };
+        assert_eq!(raw_ef.to_bytes(), input);
+
+        let json = serde_json::to_string(&raw_ef).unwrap();
+        let deserialized: RawCardEf = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, raw_ef);
+    }
+
+    #[test]
+    fn test_ef_coverage_statuses() {
+        let mut input = Vec::new();
+        // CardChipIdentification, exact size
+        input.extend_from_slice(&[0x00, 0x05, 0x00, 0x00, 0x08]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        // CardChipIdentification gen2, two trailing bytes
+        input.extend_from_slice(&[0x00, 0x05, 0x02, 0x00, 0x0A]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        // Unknown EF, kept raw
+        input.extend_from_slice(&[0x99, 0x99, 0x00, 0x00, 0x01, 0xFF]);
+        // CardChipIdentification again, a duplicate fails like it does in `parse`
+        input.extend_from_slice(&[0x00, 0x05, 0x00, 0x00, 0x08]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        // Invalid file_id, the parser stops here
+        input.extend_from_slice(&[0x00, 0x05, 0x07, 0x00, 0x01, 0xFF]);
+        input.extend_from_slice(&[0x00, 0x05, 0x00, 0x00, 0x08]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let coverage = CardParser::new_from_bytes(&input)
+            .unwrap()
+            .ef_coverage()
+            .unwrap();
+        let mut statuses: Vec<_> = coverage.iter().map(|ef| ef.status.clone()).collect();
+        let EfParseStatus::Failed { error } = statuses.remove(3) else {
+            panic!("the duplicate EF did not fail");
+        };
+        assert!(error.contains("duplicate block type"));
+        assert_eq!(
+            statuses,
+            vec![
+                EfParseStatus::Parsed,
+                EfParseStatus::PartiallyParsed { unused_bytes: 2 },
+                EfParseStatus::PreservedRaw,
+                EfParseStatus::Skipped,
+                EfParseStatus::Skipped,
+            ]
+        );
+        assert_eq!(coverage[2].offset, 28);
+        assert_eq!(coverage[2].sfid, 0x9999);
+    }
+
+    #[test]
+    fn test_ef_order_deviations() {
+        let mut input = Vec::new();
+        for (sfid, file_id) in [
+            (0x0002u16, 0u8),
+            (0x0520, 0),
+            // ApplicationIdentification and its signature belong before Identification
+            (0x0501, 0),
+            (0x0501, 1),
+            (0x0520, 1),
+            (0x0520, 2),
+            // a vendor EF is not checked
+            (0xD000, 0),
+            (0x0501, 2),
+        ] {
+            input.extend_from_slice(&sfid.to_be_bytes());
+            input.extend_from_slice(&[file_id, 0x00, 0x02, 0xAA, 0xBB]);
+        }
+        let parser = CardParser::new_from_bytes(&input).unwrap();
+        let deviations = parser.ef_order_deviations();
+        assert_eq!(
+            deviations
+                .iter()
+                .map(|deviation| (deviation.offset, deviation.sfid, deviation.file_id))
+                .collect::<Vec<_>>(),
+            vec![(14, 0x0501, 0), (21, 0x0501, 1), (49, 0x0501, 2)]
+        );
+        assert_eq!(
+            (
+                deviations[0].preceded_by_sfid,
+                deviations[0].preceded_by_file_id
+            ),
+            (0x0520, 0)
+        );
+        assert_eq!(
+            (
+                deviations[2].preceded_by_sfid,
+                deviations[2].preceded_by_file_id
+            ),
+            (0x0520, 2)
+        );
+
+        let error = parser.strict_ef_order(true).parse().unwrap_err();
+        assert!(
+            error.to_string().starts_with("3 EFs out of order"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_peek_card_identification() {
+        let mut input = Vec::new();
+        // CardChipIdentification, skipped
+        input.extend_from_slice(&[0x00, 0x05, 0x00, 0x00, 0x08]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let missing = peek_card_identification(&input).unwrap_err();
+        assert!(missing.to_string().contains("No Identification EF"));
+
+        let mut identification = vec![0u8; 143];
+        identification[1..17].copy_from_slice(b"D123456789000000");
+        // issue, validity begin and expiry dates
+        for offset in [53, 57, 61] {
+            identification[offset..offset + 4].copy_from_slice(&0x65E1_1A80u32.to_be_bytes());
+        }
+        identification[66..70].copy_from_slice(b"DOE ");
+        input.extend_from_slice(&[0x05, 0x20, 0x00, 0x00, 143]);
+        input.extend_from_slice(&identification);
+        // truncated EF after the identification is never read
+        input.extend_from_slice(&[0x05, 0x02, 0x00, 0x10, 0x00]);
+
+        let record = peek_card_identification(&input).unwrap();
+        assert_eq!(record.card_number.as_deref(), Some("D123456789000000"));
+        assert_eq!(record.holder_surname.as_deref(), Some("DOE"));
+        assert_eq!(record.vehicle_identification_number, None);
+    }
+
     #[test]
     fn test_process_card_file() {
         let data_dir = Path::new("../../data/ddd");
@@ -0,0 +1,200 @@
+use crate::diagnostics::InvalidField;
+use crate::dt::{ActivityMinutesPolicy, StringPolicy};
+use std::cell::{Cell, RefCell};
+use std::thread::LocalKey;
+
+/// A thread-local slot [`Scoped`] can swap a value in and out of.
+pub(crate) trait Slot<T> {
+    fn swap(&self, value: T) -> T;
+}
+impl<T> Slot<T> for RefCell<T> {
+    fn swap(&self, value: T) -> T {
+        self.replace(value)
+    }
+}
+impl<T> Slot<T> for Cell<T> {
+    fn swap(&self, value: T) -> T {
+        self.replace(value)
+    }
+}
+
+/// A thread-local set to a value for a while, restored to its previous value when dropped, even
+/// if the code that ran in between panicked.
+pub(crate) struct Scoped<T: 'static, S: Slot<T> + 'static = RefCell<T>> {
+    key: &'static LocalKey<S>,
+    previous: Option<T>,
+}
+impl<T, S: Slot<T>> Scoped<T, S> {
+    pub(crate) fn set(key: &'static LocalKey<S>, value: T) -> Self {
+        let previous = key.with(|slot| slot.swap(value));
+        Scoped {
+            key,
+            previous: Some(previous),
+        }
+    }
+
+    /// Restores the previous value and returns the current one, e.g. what a collector gathered.
+    pub(crate) fn finish(mut self) -> T {
+        let previous = self.previous.take().expect("scoped value already restored");
+        self.key.with(|slot| slot.swap(previous))
+    }
+}
+impl<T, S: Slot<T>> Drop for Scoped<T, S> {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            self.key.with(|slot| slot.swap(previous));
+        }
+    }
+}
+
+/// Runs `run` with `key` set to `value`, and returns its output with the value `key` ended with.
+pub(crate) fn scoped<T: 'static, S: Slot<T>, R>(
+    key: &'static LocalKey<S>,
+    value: T,
+    run: impl FnOnce() -> R,
+) -> (R, T) {
+    let scoped = Scoped::set(key, value);
+    let output = run();
+    (output, scoped.finish())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Settings of a [`crate::vu_parser::VuParser`] or [`crate::card_parser::CardParser`] that the
+/// `dt` parsers apply, see [`with_parse_context`].
+pub(crate) struct ParseSettings {
+    pub string_policy: StringPolicy,
+    pub skip_signatures: bool,
+    pub activity_minutes_policy: ActivityMinutesPolicy,
+}
+impl ParseSettings {
+    const DEFAULT: ParseSettings = ParseSettings {
+        string_policy: StringPolicy::Clean,
+        skip_signatures: false,
+        activity_minutes_policy: ActivityMinutesPolicy::Keep,
+    };
+}
+impl Default for ParseSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// State of the parse running on this thread, see [`with_parse_context`].
+struct ParseContext {
+    settings: ParseSettings,
+    /// Address and length of the parsed input, see [`record_invalid_field`].
+    input_start: usize,
+    input_len: usize,
+    invalid_fields: Vec<InvalidField>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<ParseContext>> = const { RefCell::new(None) };
+}
+
+/// Runs `parse` on `input` with `settings` applied to the data decoded on this thread, and
+/// returns its output with the invalid optional fields of `input` it decoded.
+pub(crate) fn with_parse_context<T>(
+    settings: ParseSettings,
+    input: &[u8],
+    parse: impl FnOnce() -> T,
+) -> (T, Vec<InvalidField>) {
+    let context = ParseContext {
+        settings,
+        input_start: input.as_ptr() as usize,
+        input_len: input.len(),
+        invalid_fields: Vec::new(),
+    };
+    let (output, context) = scoped(&CONTEXT, Some(context), parse);
+    (
+        output,
+        context.expect("parse context missing").invalid_fields,
+    )
+}
+
+/// Settings of the parse running on this thread, the defaults outside of one.
+pub(crate) fn parse_settings() -> ParseSettings {
+    CONTEXT.with(|context| {
+        context
+            .borrow()
+            .as_ref()
+            .map_or(ParseSettings::DEFAULT, |context| context.settings)
+    })
+}
+
+/// Called by [`crate::dt::parse_optional`] when the `raw` bytes of `field` fail to decode.
+pub(crate) fn record_invalid_field(field: &str, raw: &[u8], error: &anyhow::Error) {
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        let Some(context) = context.as_mut() else {
+            return;
+        };
+        // Sub-cursors borrow slices of the input, so the file offset follows from the slice address
+        let offset = (raw.as_ptr() as usize)
+            .checked_sub(context.input_start)
+            .filter(|offset| offset + raw.len() <= context.input_len);
+        context.invalid_fields.push(InvalidField {
+            field: field.to_string(),
+            offset,
+            raw: raw.to_vec(),
+            error: format!("{:#}", error),
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dt::{parse_optional, TimeReal};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_scoped_restores_previous_value() {
+        thread_local! {
+            static VALUE: RefCell<u8> = const { RefCell::new(1) };
+        }
+        let (inner, last) = scoped(&VALUE, 2, || {
+            let inner = VALUE.with(|value| *value.borrow());
+            VALUE.with(|value| *value.borrow_mut() = 3);
+            inner
+        });
+        assert_eq!((inner, last), (2, 3));
+        assert_eq!(VALUE.with(|value| *value.borrow()), 1);
+
+        let panicked = std::panic::catch_unwind(|| scoped(&VALUE, 4, || panic!("parse failed")));
+        assert!(panicked.is_err());
+        assert_eq!(VALUE.with(|value| *value.borrow()), 1);
+    }
+
+    #[test]
+    fn test_parse_context_collects_invalid_fields() {
+        // empty, past 2038, valid, left with 0xFF, cut off
+        let input = [
+            0x00, 0x00, 0x00, 0x00, 0x90, 0x00, 0x00, 0x00, 0x65, 0xE1, 0x71, 0xF0, 0xFF, 0xFF,
+            0xFF, 0xFF, 0x65, 0xE1,
+        ];
+        let (times, fields) = with_parse_context(ParseSettings::default(), &input, || {
+            let mut cursor = Cursor::new(&input[..]);
+            (0..5)
+                .map(|_| parse_optional(&mut cursor, "event_end_time", TimeReal::parse))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(times.iter().filter(|time| time.is_some()).count(), 1);
+        assert!(times[2].is_some());
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "event_end_time");
+        assert_eq!(fields[0].offset, Some(4));
+        assert_eq!(fields[0].raw, vec![0x90, 0x00, 0x00, 0x00]);
+
+        // Bytes copied out of the input have no offset
+        let copy = input[4..8].to_vec();
+        let (_, fields) = with_parse_context(ParseSettings::default(), &input, || {
+            parse_optional(
+                &mut Cursor::new(&copy[..]),
+                "event_end_time",
+                TimeReal::parse,
+            )
+        });
+        assert_eq!(fields[0].offset, None);
+    }
+}
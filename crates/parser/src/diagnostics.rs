@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Non-fatal findings collected while parsing a file.
+/// Anything recorded here was tolerated by the parser, but the output may be incomplete.
+pub struct Diagnostics {
+    /// VU blocks whose SID/TREP the parser does not know, and which were skipped.
+    pub unknown_blocks: Vec<UnknownBlock>,
+    /// Size of the parsed file.
+    pub total_bytes: usize,
+    /// Bytes consumed by recognized blocks, including their SID/TREP header.
+    pub recognized_bytes: usize,
+    /// Final block cut short by an aborted download, only set in lenient mode.
+    pub truncated_block: Option<TruncatedBlock>,
+    /// Blocks were preceded by the header of their download response message, kept by some
+    /// download tools. The headers were skipped and count as recognized bytes.
+    pub response_headers: bool,
+    /// The file was captured from the download interface with its byte stuffing, every 0x10
+    /// doubled. It was unstuffed before parsing, sizes and offsets are of the unstuffed bytes.
+    pub byte_stuffing: bool,
+    /// Optional fields whose bytes were present but could not be decoded, and which are `None`
+    /// in the parsed data like the fields left empty.
+    pub invalid_fields: Vec<InvalidField>,
+    /// Signatures and certificates were moved past instead of read, see
+    /// [`crate::vu_parser::VuParserBuilder::skip_signatures`]. Their bytes are left empty.
+    pub signatures_skipped: bool,
+}
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.unknown_blocks.is_empty()
+            && self.truncated_block.is_none()
+            && self.invalid_fields.is_empty()
+    }
+
+    /// Penalties of the quality score for the unused bytes, unknown blocks and truncated block.
+    pub fn quality_penalties(&self) -> Vec<QualityPenalty> {
+        let unused_bytes = self.total_bytes.saturating_sub(self.recognized_bytes);
+        // one point per started percent of the file that is not used
+        let unused_percent = (unused_bytes * 100).div_ceil(self.total_bytes.max(1));
+        vec![
+            QualityPenalty::new(QualityIssue::UnusedBytes, unused_bytes, unused_percent, 40),
+            QualityPenalty::per_item(
+                QualityIssue::UnknownBlocks,
+                self.unknown_blocks.len(),
+                5,
+                20,
+            ),
+            QualityPenalty::per_item(
+                QualityIssue::Truncation,
+                self.truncated_block.iter().count(),
+                25,
+                25,
+            ),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Non-fatal findings collected while parsing a card file, see [`Diagnostics`] for VU files.
+pub struct CardDiagnostics {
+    /// EF the file ends in the middle of, only set in lenient mode, see
+    /// [`crate::card_parser::CardParser::lenient`].
+    pub truncated_ef: Option<TruncatedBlock>,
+    /// Optional fields of all generations whose bytes could not be decoded, see
+    /// [`Diagnostics::invalid_fields`].
+    pub invalid_fields: Vec<InvalidField>,
+}
+impl CardDiagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.truncated_ef.is_none() && self.invalid_fields.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum QualityIssue {
+    /// Bytes not part of any recognized block, counted in bytes.
+    UnusedBytes,
+    /// VU blocks with an unknown SID/TREP.
+    UnknownBlocks,
+    /// A VU block or card EF cut short by an aborted download.
+    Truncation,
+    /// Time adjustments beyond the threshold of [`crate::analysis::TimeAdjustmentConfig`],
+    /// including a cumulative drift beyond it.
+    TimeAnomalies,
+    /// Findings of [`crate::card_parser::CardData::consistency_report`] and
+    /// [`crate::vu_parser::VuData::consistency_report`].
+    ConsistencyWarnings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Points taken off the quality score for one kind of issue.
+pub struct QualityPenalty {
+    pub issue: QualityIssue,
+    /// Occurrences of the issue, bytes for [`QualityIssue::UnusedBytes`].
+    pub count: usize,
+    pub points: u8,
+}
+impl QualityPenalty {
+    /// Takes `points` off, but no more than `max_points`.
+    pub fn new(issue: QualityIssue, count: usize, points: usize, max_points: u8) -> Self {
+        QualityPenalty {
+            issue,
+            count,
+            points: points.min(max_points as usize) as u8,
+        }
+    }
+
+    /// Takes `points_each` off per occurrence, but no more than `max_points`.
+    pub fn per_item(issue: QualityIssue, count: usize, points_each: u8, max_points: u8) -> Self {
+        Self::new(
+            issue,
+            count,
+            count.saturating_mul(points_each as usize),
+            max_points,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Data quality of a parsed file from 0 to 100, so that ingestion can quarantine files below a
+/// single threshold. A file without any finding scores 100.
+pub struct QualityScore {
+    pub score: u8,
+    /// The penalties that took points off, largest first.
+    pub penalties: Vec<QualityPenalty>,
+}
+impl QualityScore {
+    /// Sums up `penalties`, leaving out the ones that take no points off.
+    pub fn from_penalties(mut penalties: Vec<QualityPenalty>) -> Self {
+        penalties.retain(|penalty| penalty.points > 0);
+        penalties.sort_by_key(|penalty| Reverse(penalty.points));
+        let lost: u32 = penalties.iter().map(|penalty| penalty.points as u32).sum();
+        QualityScore {
+            score: 100u32.saturating_sub(lost) as u8,
+            penalties,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct UnknownBlock {
+    pub sid: u8,
+    pub trep: u8,
+    /// Offset of the SID byte from the start of the file.
+    pub offset: usize,
+    /// Bytes skipped until the next known block marker, or until the end of the file.
+    pub skipped_bytes: usize,
+    /// Whether another known block was found after this one.
+    pub resynchronized: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A VU block or card EF the file ends in the middle of, typically because the download was
+/// aborted. Lenient parsing drops it and keeps everything before it.
+pub struct TruncatedBlock {
+    /// Offset of the block from the start of the file.
+    pub offset: usize,
+    /// Header bytes of the block: SID and TREP of a VU block, or file identifier, appendix byte
+    /// and length of a card EF. Shorter when the file ends inside the header.
+    pub header: Vec<u8>,
+    /// Bytes of the block present in the file, header included.
+    pub available_bytes: usize,
+    /// Bytes missing from the block. Exact for card EFs, whose header holds their length. VU
+    /// blocks do not record their length, so for Gen2 blocks this is a lower bound read from the
+    /// record array headers, and for Gen1 blocks it is unknown.
+    pub missing_bytes: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An optional field that holds data the parser could not decode, e.g. a `TimeReal` past 2038.
+/// Fields left empty, all `0x00` or all `0xFF`, are absent rather than invalid and are not
+/// reported.
+pub struct InvalidField {
+    /// Name of the field, e.g. `event_end_time`.
+    pub field: String,
+    /// Offset from the start of the file, or `None` when the bytes were read from a buffer
+    /// assembled by the parser (e.g. the unwrapped cyclic activity data of a card).
+    pub offset: Option<usize>,
+    pub raw: Vec<u8>,
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_penalties() {
+        let clean = Diagnostics {
+            total_bytes: 1000,
+            recognized_bytes: 1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            QualityScore::from_penalties(clean.quality_penalties()).score,
+            100
+        );
+
+        let unknown_block = UnknownBlock {
+            sid: 0x76,
+            trep: 0x40,
+            offset: 0,
+            skipped_bytes: 101,
+            resynchronized: true,
+        };
+        let diagnostics = Diagnostics {
+            unknown_blocks: vec![unknown_block; 5],
+            total_bytes: 1000,
+            recognized_bytes: 899,
+            truncated_block: None,
+            response_headers: false,
+            byte_stuffing: false,
+            invalid_fields: Vec::new(),
+            signatures_skipped: false,
+        };
+        let quality = QualityScore::from_penalties(diagnostics.quality_penalties());
+        // 10.1% unused rounds up to 11 points, 5 unknown blocks are capped at 20
+        assert_eq!(quality.score, 69);
+        assert_eq!(quality.penalties[0].issue, QualityIssue::UnknownBlocks);
+        assert_eq!(quality.penalties[1].points, 11);
+    }
+}
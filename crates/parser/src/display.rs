@@ -0,0 +1,548 @@
+//! `Display` impls giving one-line descriptions of the parsed data, for logs and CLI output,
+//! e.g. `OverSpeeding 2024-02-01T10:05–10:09 VRN ABC123` for a card event record.
+use crate::card_parser::CardData;
+use crate::dt::{self, external, gen1, gen2, gen2v2};
+use crate::fingerprint::to_hex;
+use crate::sink::card_number_to_string;
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+use std::fmt::{self, Display, Formatter};
+
+const MINUTES_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// `begin–end` at minute precision, with only the time of `end` when both fall on the same day
+/// and an open end when `end` is unknown.
+fn write_period(
+    f: &mut Formatter<'_>,
+    begin: &DateTime<Utc>,
+    end: Option<&DateTime<Utc>>,
+) -> fmt::Result {
+    write!(f, "{}–", begin.format(MINUTES_FORMAT))?;
+    match end {
+        Some(end) if end.date_naive() == begin.date_naive() => write!(f, "{}", end.format("%H:%M")),
+        Some(end) => write!(f, "{}", end.format(MINUTES_FORMAT)),
+        None => Ok(()),
+    }
+}
+
+/// ` card <number>` for the card inserted in the driver slot, if any.
+fn write_card(f: &mut Formatter<'_>, card: Option<&dyn Display>) -> fmt::Result {
+    match card {
+        Some(card) => write!(f, " card {}", card),
+        None => Ok(()),
+    }
+}
+
+impl Display for dt::TimeReal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%dT%H:%M:%SZ"))
+    }
+}
+
+impl Display for dt::IA5String {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.trim())
+    }
+}
+
+impl Display for dt::Name {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+impl Display for dt::HolderName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.holder_first_names, self.holder_surname)
+    }
+}
+
+impl Display for external::NationNumeric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Display for dt::VehicleRegistrationNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.vehicle_reg_number.fmt(f)
+    }
+}
+
+impl Display for dt::VehicleRegistrationIdentification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.vehicle_registration_number, self.vehicle_registration_nation
+        )
+    }
+}
+
+impl Display for dt::CardNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let dt::CardNumber::Raw { raw } = self {
+            return write!(f, "raw {}", to_hex(raw));
+        }
+        match card_number_to_string(self) {
+            Some(number) => f.write_str(&number),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl Display for gen1::FullCardNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.card_number, self.card_issuing_member_state
+        )
+    }
+}
+
+impl Display for gen2::FullCardNumberGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({})",
+            self.card_number, self.card_issuing_member_state
+        )
+    }
+}
+
+impl Display for gen2::FullCardNumberAndGenerationGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.full_card_number.fmt(f)
+    }
+}
+
+impl Display for gen1::EventFaultType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Display for gen2::EventFaultTypeGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Display for gen2v2::EventFaultTypeGen2V2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Display for gen1::CardEventRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(
+            f,
+            &self.event_begin_time.0,
+            self.event_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write!(
+            f,
+            " VRN {}",
+            self.event_vehicle_registration.vehicle_registration_number
+        )
+    }
+}
+
+impl Display for gen2::CardEventRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(
+            f,
+            &self.event_begin_time.0,
+            self.event_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write!(
+            f,
+            " VRN {}",
+            self.event_vehicle_registration.vehicle_registration_number
+        )
+    }
+}
+
+impl Display for gen1::CardFaultRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.fault_type)?;
+        write_period(
+            f,
+            &self.fault_begin_time.0,
+            self.fault_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write!(
+            f,
+            " VRN {}",
+            self.fault_vehicle_registration.vehicle_registration_number
+        )
+    }
+}
+
+impl Display for gen2::CardFaultRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.fault_type)?;
+        write_period(
+            f,
+            &self.fault_begin_time.0,
+            self.fault_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write!(
+            f,
+            " VRN {}",
+            self.fault_vehicle_registration.vehicle_registration_number
+        )
+    }
+}
+
+impl Display for gen1::VuEventRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(
+            f,
+            &self.event_begin_time.0,
+            self.event_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write_card(
+            f,
+            self.card_number_driver_slot_begin
+                .as_ref()
+                .map(|card| card as &dyn Display),
+        )
+    }
+}
+
+impl Display for gen2::VuEventRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(
+            f,
+            &self.event_begin_time.0,
+            self.event_end_time.as_ref().map(|time| &time.0),
+        )?;
+        write_card(
+            f,
+            self.card_number_and_gen_driver_slot_begin
+                .as_ref()
+                .map(|card| card as &dyn Display),
+        )
+    }
+}
+
+impl Display for gen1::VuFaultRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.fault_type)?;
+        write_period(f, &self.fault_begin_time.0, Some(&self.fault_end_time.0))?;
+        write_card(
+            f,
+            self.card_number_driver_slot_begin
+                .as_ref()
+                .map(|card| card as &dyn Display),
+        )
+    }
+}
+
+impl Display for gen2::VuFaultRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.fault_type)?;
+        write_period(f, &self.fault_begin_time.0, Some(&self.fault_end_time.0))?;
+        write_card(
+            f,
+            self.card_number_and_gen_driver_slot_begin
+                .as_ref()
+                .map(|card| card as &dyn Display),
+        )
+    }
+}
+
+impl Display for gen1::VuOverSpeedingEventRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(f, &self.event_begin_time.0, Some(&self.event_end_time.0))?;
+        write!(
+            f,
+            " max {} km/h avg {} km/h",
+            self.max_speed_value.0, self.average_speed_value.0
+        )?;
+        write_card(f, Some(&self.card_number_driver_slot))
+    }
+}
+
+impl Display for gen2::VuOverSpeedingEventRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.event_type)?;
+        write_period(f, &self.event_begin_time.0, Some(&self.event_end_time.0))?;
+        write!(
+            f,
+            " max {} km/h avg {} km/h",
+            self.max_speed_value.0, self.average_speed_value.0
+        )?;
+        write_card(
+            f,
+            self.card_number_and_gen_driver_slot_begin
+                .as_ref()
+                .map(|card| card as &dyn Display),
+        )
+    }
+}
+
+impl Display for gen1::CardVehicleRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VRN {} ",
+            self.vehicle_registration.vehicle_registration_number
+        )?;
+        write_period(f, &self.vehicle_first_use.0, Some(&self.vehicle_last_use.0))?;
+        write!(
+            f,
+            " {}–{} km",
+            self.vehicle_odometer_begin.0, self.vehicle_odometer_end.0
+        )
+    }
+}
+
+impl Display for gen2::CardVehicleRecordGen2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "VRN {} VIN {} ",
+            self.vehicle_registration.vehicle_registration_number,
+            self.vehicle_identification_number.0
+        )?;
+        write_period(f, &self.vehicle_first_use.0, Some(&self.vehicle_last_use.0))?;
+        write!(
+            f,
+            " {}–{} km",
+            self.vehicle_odometer_begin.0, self.vehicle_odometer_end.0
+        )
+    }
+}
+
+impl Display for dt::CardActivityDailyRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} activity changes, {} km",
+            self.activity_record_date.0.format("%Y-%m-%d"),
+            self.activity_change_info.len(),
+            self.activity_day_distance.0
+        )
+    }
+}
+
+impl Display for CardData {
+    /// e.g. `Driver card Gen2 DE12345678901234 (Jane Doe), 28 days of activity, 3 events, 1 fault`
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (generation, identification, events, faults) = match self {
+            CardData::Gen1 { gen1_blocks, .. } => (
+                "Gen1",
+                &gen1_blocks.identification,
+                gen1_blocks.events_data.records.iter().flatten().count(),
+                gen1_blocks.faults_data.records.iter().flatten().count(),
+            ),
+            CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => (
+                if matches!(self, CardData::Gen2 { .. }) {
+                    "Gen2"
+                } else {
+                    "Gen2V2"
+                },
+                &gen2_blocks.identification,
+                gen2_blocks.events_data.records().count(),
+                gen2_blocks.faults_data.records.iter().flatten().count(),
+            ),
+        };
+        write!(
+            f,
+            "Driver card {} {} ({}), {} days of activity, {} events, {} faults",
+            generation,
+            identification.card_identification.card_number,
+            identification
+                .driver_card_holder_identification
+                .card_holder_number,
+            self.card_driver_activity().activity_daily_records.len(),
+            events,
+            faults
+        )
+    }
+}
+
+impl Display for VuData {
+    /// e.g. `Vehicle unit Gen1 VIN WDB9634031L123456 VRN ABC123, 28 days of activity, 3 events, 1 fault`
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VuData::Gen1(blocks) => {
+                let overview = &blocks.vu_overview;
+                write!(
+                    f,
+                    "Vehicle unit Gen1 VIN {} VRN {}",
+                    overview.vehicle_identification_number.0,
+                    overview
+                        .vehicle_registration_identification
+                        .vehicle_registration_number
+                )?;
+                let events = &blocks.vu_events_and_faults;
+                write_vu_counts(
+                    f,
+                    blocks.vu_activities.len(),
+                    events
+                        .iter()
+                        .map(|block| block.vu_event_data.vu_event_records.len())
+                        .sum(),
+                    events
+                        .iter()
+                        .map(|block| block.vu_fault_data.vu_fault_records.len())
+                        .sum(),
+                )
+            }
+            VuData::Gen2(blocks) => {
+                let overview = &blocks.vu_overview;
+                write!(f, "Vehicle unit Gen2")?;
+                if let Some(vin) = overview.vehicle_identification_number_record_array.first() {
+                    write!(f, " VIN {}", vin.0)?;
+                }
+                if let Some(vrn) = overview.vehicle_registration_number_record_array.first() {
+                    write!(f, " VRN {}", vrn)?;
+                }
+                write_vu_gen2_counts(f, blocks.vu_activities.len(), &blocks.vu_events_and_faults)
+            }
+            VuData::Gen2V2(blocks) => {
+                let overview = &blocks.vu_overview;
+                write!(f, "Vehicle unit Gen2V2")?;
+                if let Some(vin) = overview.vehicle_identification_number_record_array.first() {
+                    write!(f, " VIN {}", vin.0)?;
+                }
+                if let Some(vri) = overview
+                    .vehicle_registration_identification_record_array
+                    .first()
+                {
+                    write!(f, " VRN {}", vri.vehicle_registration_number)?;
+                }
+                write_vu_gen2_counts(f, blocks.vu_activities.len(), &blocks.vu_events_and_faults)
+            }
+        }
+    }
+}
+
+fn write_vu_counts(
+    f: &mut Formatter<'_>,
+    days: usize,
+    events: usize,
+    faults: usize,
+) -> fmt::Result {
+    write!(
+        f,
+        ", {} days of activity, {} events, {} faults",
+        days, events, faults
+    )
+}
+
+fn write_vu_gen2_counts(
+    f: &mut Formatter<'_>,
+    days: usize,
+    events_and_faults: &[gen2::VuEventsAndFaultsBlockGen2],
+) -> fmt::Result {
+    write_vu_counts(
+        f,
+        days,
+        events_and_faults
+            .iter()
+            .map(|block| block.vu_event_record_array.len())
+            .sum(),
+        events_and_faults
+            .iter()
+            .map(|block| block.vu_fault_record_array.len())
+            .sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vu_parser::VuParser;
+
+    fn time(rfc3339: &str) -> dt::TimeReal {
+        dt::TimeReal(DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc())
+    }
+
+    #[test]
+    fn test_record_one_liners() {
+        let registration = dt::VehicleRegistrationIdentification {
+            vehicle_registration_nation: external::NationNumeric("Germany".to_string()),
+            vehicle_registration_number: dt::VehicleRegistrationNumber {
+                code_page: 1,
+                vehicle_reg_number: dt::IA5String("ABC123       ".to_string()),
+            },
+        };
+        let event = gen1::CardEventRecord {
+            event_type: gen1::EventFaultType::OverSpeeding,
+            event_begin_time: time("2024-02-01T10:05:12Z"),
+            event_end_time: Some(time("2024-02-01T10:09:40Z")),
+            event_vehicle_registration: registration,
+        };
+        assert_eq!(
+            event.to_string(),
+            "OverSpeeding 2024-02-01T10:05–10:09 VRN ABC123"
+        );
+        assert_eq!(
+            event.event_vehicle_registration.to_string(),
+            "ABC123 (Germany)"
+        );
+
+        let overnight = gen1::CardFaultRecord {
+            fault_type: gen1::EventFaultType::PowerSupplyInterruption,
+            fault_begin_time: time("2024-02-01T23:50:00Z"),
+            fault_end_time: Some(time("2024-02-02T00:10:00Z")),
+            fault_vehicle_registration: event.event_vehicle_registration,
+        };
+        assert_eq!(
+            overnight.to_string(),
+            "PowerSupplyInterruption 2024-02-01T23:50–2024-02-02T00:10 VRN ABC123"
+        );
+    }
+
+    #[test]
+    fn test_control_card_number_layouts() {
+        let parse = |card_type: u8, number: &[u8]| {
+            let mut input = vec![card_type, 0x0D];
+            input.extend(number);
+            gen2::FullCardNumberGen2::parse(&mut std::io::Cursor::new(&input[..])).unwrap()
+        };
+
+        let owner = parse(0x03, b"D123456789012A10");
+        assert!(matches!(owner.card_number, dt::CardNumber::Owner { .. }));
+        assert!(owner.to_string().starts_with("D123456789012A10 "));
+
+        let binary_indexes = parse(0x04, b"D123456789012\x00\x01\x00");
+        assert!(binary_indexes.to_string().starts_with("D123456789012010 "));
+
+        let garbled = parse(0x04, b"D123456789012\xFF\x01\x00");
+        assert_eq!(
+            garbled.card_number,
+            dt::CardNumber::Raw {
+                raw: b"D123456789012\xFF\x01\x00".to_vec()
+            }
+        );
+        assert!(garbled
+            .to_string()
+            .starts_with("raw 44313233343536373839303132ff0100 "));
+    }
+
+    #[test]
+    fn test_vu_data_one_liner() {
+        let vu_data = VuParser::new_from_bytes(include_bytes!(
+            "../tests/fixtures/vu_gen1_unknown_block.ddd"
+        ))
+        .unwrap()
+        .parse()
+        .unwrap();
+        let summary = vu_data.to_string();
+        assert!(summary.starts_with("Vehicle unit Gen1 VIN "), "{}", summary);
+        assert!(summary.ends_with(" faults"), "{}", summary);
+        assert!(!summary.contains('\n'));
+    }
+}
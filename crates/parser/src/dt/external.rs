@@ -1,22 +1,34 @@
+use crate::bytes::TracedRead;
 use anyhow::{Context, Result};
-use byteorder::ReadBytesExt;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-use super::IA5String;
+use super::{Coded, IA5String};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ManufacturerCode: appendix 2.94.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22253)
-pub struct ManufacturerCode(pub String);
+pub struct ManufacturerCode {
+    pub code: u8,
+    pub name: String,
+}
 impl ManufacturerCode {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let code = cursor
             .read_u8()
             .context("Failed to read ManufacturerCode")?;
+        let name =
+            Self::name(code).with_context(|| format!("Unknown ManufacturerCode: {}", code))?;
+        Ok(ManufacturerCode {
+            code,
+            name: name.to_string(),
+        })
+    }
 
+    /// Registered name of a manufacturer code, as stored in [`ManufacturerCode::name`].
+    pub fn name(code: u8) -> Option<&'static str> {
         let name = match code {
             0x10 => "Actia S.A.",
             0x11 => "Security Printing and Systems Ltd.",
@@ -81,20 +93,21 @@ impl ManufacturerCode {
             0xB1 => "UŽDAROJI AKCINĖ BENDROVĖ \"LODVILA\"",
             0xD8 => "Union of Chambers and Commodity Exchanges of Turkey - TOBB",
             0xE0 => "Turker Roll Paper Trade",
-            _ => anyhow::bail!("Unknown ManufacturerCode: {}", code),
+            _ => return None,
         };
-        Ok(ManufacturerCode(name.to_string()))
+        Some(name)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// https://dtc.jrc.ec.europa.eu/dtc_seal_manufacturer_code.php.html
+/// Holds the name of a registered manufacturer, or the two letter code as read for the others.
 pub struct SealManufacturerCode(pub String);
 impl SealManufacturerCode {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let code_value = IA5String::parse_dyn_size(cursor, 2)?;
-        let code = match code_value.0.as_str() {
+        let name = match code_value.0.as_str() {
             "RP" => "Royal Pack",
             "PR" => "Precintia Seguridad",
             "UN" => "Unisto AG",
@@ -103,22 +116,30 @@ impl SealManufacturerCode {
             "DL" => "Dilogics Europa S.L.",
             "AS" => "Acme Seals Ltd",
             "MF" => "Mega Fortris Group",
-            _ => anyhow::bail!("Unknown SealManufacturerCode: {}", code_value.0),
+            _ => {
+                log::warn!("Unknown SealManufacturerCode: {}", code_value.0);
+                return Ok(SealManufacturerCode(code_value.0));
+            }
         };
 
-        Ok(SealManufacturerCode(code.to_string()))
+        Ok(SealManufacturerCode(name.to_string()))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [NationNumeric: appendix 2.101.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22450)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct NationNumeric(pub String);
 impl NationNumeric {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let value = cursor.read_u8().context("Failed to read nation numeric")?;
+        Ok(NationNumeric(Self::name(value).to_string()))
+    }
+
+    /// English name of a nation code, as stored in [`NationNumeric`].
+    pub fn name(value: u8) -> &'static str {
         // TODO: decide if we want to keep this list up to date, or just provide the raw value
-        let parsed_country = match value {
+        match value {
             0x00 => "No information available",
             0x01 => "Austria",
             0x02 => "Albania",
@@ -179,19 +200,37 @@ impl NationNumeric {
             0xFE => "Rest of Europe",
             0xFF => "Rest of the World",
             _ => "Reserved for Future Use",
-        };
-        Ok(NationNumeric(parsed_country.to_string()))
+        }
+    }
+
+    /// Nation code this value was parsed from.
+    /// All reserved codes share one name, so they resolve to the first reserved code (0x33).
+    pub fn code(&self) -> Option<u8> {
+        (0..=u8::MAX).find(|&code| Self::name(code) == self.0)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [RegionNumeric: appendix 2.122.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23612)
-pub struct RegionNumeric(pub String);
+/// Regions newer than this list keep their raw byte, see [`Coded`].
+pub struct RegionNumeric(pub Coded<String>);
 impl RegionNumeric {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let value = cursor.read_u8().context("Failed to read region_numeric")?;
-        let region = match value {
+        let region = match Self::name(value) {
+            Some(name) => Coded::Known(name.to_string()),
+            None => {
+                log::warn!("Unknown RegionNumeric value: {}", value);
+                Coded::Unknown(value)
+            }
+        };
+        Ok(RegionNumeric(region))
+    }
+
+    /// Name of a Spanish region code, as stored in [`RegionNumeric`].
+    pub fn name(value: u8) -> Option<&'static str> {
+        let name = match value {
             // Gen1 (these are statically defined in the standard)
             0x00 => "No information available",
             0x01 => "Andalucia",
@@ -214,8 +253,8 @@ impl RegionNumeric {
             // Gen2 (regions are kept up to date here: https://dtc.jrc.ec.europa.eu/dtc_spain_region_codes.php.html)
             0x12 => "Ceuta",
             0x13 => "Melilla",
-            _ => anyhow::bail!("Invalid RegionNumeric value: {}", value),
+            _ => return None,
         };
-        Ok(RegionNumeric(region.to_string()))
+        Some(name)
     }
 }
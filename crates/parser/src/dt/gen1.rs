@@ -1,14 +1,14 @@
 #![allow(dead_code)]
 use crate::bytes::TakeExact;
+use crate::bytes::TracedRead;
 use crate::dt::*;
 use anyhow::{Context, Result};
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::BigEndian;
 use serde::{Deserialize, Serialize};
-use std::io::Read;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EquipmentType: appendix 2.67.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20100)
 pub enum EquipmentType {
@@ -20,7 +20,8 @@ pub enum EquipmentType {
     ManufacturingCard,
     VehicleUnit,
     MotionSensor,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
 }
 impl EquipmentType {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -34,12 +35,12 @@ impl EquipmentType {
             5 => Ok(EquipmentType::ManufacturingCard),
             6 => Ok(EquipmentType::VehicleUnit),
             7 => Ok(EquipmentType::MotionSensor),
-            8..=255 => Ok(EquipmentType::RFU),
+            8..=255 => Ok(EquipmentType::RFU(equipment_type)),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ExtendedSerialNumber: appendix 2.72.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21307)
@@ -65,8 +66,20 @@ impl ExtendedSerialNumber {
             manufacturer_code,
         })
     }
+
+    /// Serial number, month/year of manufacture and manufacturer, e.g. `12345678 03/19 Gemalto`.
+    /// Stable across downloads of the same equipment, so it can be used as a key.
+    pub fn to_serial_string(&self) -> String {
+        format!(
+            "{} {:02}/{:02} {}",
+            self.serial_number,
+            self.month_year.month,
+            self.month_year.year,
+            self.manufacturer_code.name
+        )
+    }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardIccIdentification: appendix 2.23.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17372)
@@ -102,7 +115,7 @@ impl CardIccIdentification {
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CalibrationPurpose: appendix 2.8.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16597)
 pub enum CalibrationPurpose {
@@ -113,24 +126,21 @@ pub enum CalibrationPurpose {
     PeriodicInspection,
 }
 
-impl CalibrationPurpose {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let value = cursor
-            .read_u8()
-            .context("Failed to read CalibrationPurpose")?;
+impl FromByte for CalibrationPurpose {
+    fn from_byte(value: u8) -> Option<Self> {
         match value {
-            0x00 => Ok(CalibrationPurpose::Reserved),
-            0x01 => Ok(CalibrationPurpose::Activation),
-            0x02 => Ok(CalibrationPurpose::FirstInstallation),
-            0x03 => Ok(CalibrationPurpose::Installation),
-            0x04 => Ok(CalibrationPurpose::PeriodicInspection),
-            _ => anyhow::bail!("Invalid CalibrationPurpose value: {}", value),
+            0x00 => Some(CalibrationPurpose::Reserved),
+            0x01 => Some(CalibrationPurpose::Activation),
+            0x02 => Some(CalibrationPurpose::FirstInstallation),
+            0x03 => Some(CalibrationPurpose::Installation),
+            0x04 => Some(CalibrationPurpose::PeriodicInspection),
+            _ => None,
         }
     }
 }
 
 pub type SensorSerialNumber = ExtendedSerialNumber;
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SensorApprovalNumber: appendix 2.131.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23887)
 pub struct SensorApprovalNumber(pub IA5String);
@@ -141,7 +151,7 @@ impl SensorApprovalNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuApprovalNumber: appendix 2.172.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25427)
 pub struct VuApprovalNumber(pub IA5String);
@@ -152,7 +162,7 @@ impl VuApprovalNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EventFaultType: appendix 2.70.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20338)
 pub enum EventFaultType {
@@ -188,7 +198,8 @@ pub enum EventFaultType {
     DisplayFault,
     DownloadingFault,
     SensorFault,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 
@@ -210,7 +221,7 @@ impl EventFaultType {
             0x08 => Ok(EventFaultType::PowerSupplyInterruption),
             0x09 => Ok(EventFaultType::MotionDataError),
             0x0A => Ok(EventFaultType::VehicleMotionConflict),
-            0x0B..=0x0F => Ok(EventFaultType::RFU),
+            0x0B..=0x0F => Ok(EventFaultType::RFU(value)),
 
             // Vehicle unit related security breach attempt events,
             0x10 => Ok(EventFaultType::VUSecurityBreachAttemptNoFurtherDetails),
@@ -222,7 +233,7 @@ impl EventFaultType {
             0x16 => Ok(EventFaultType::InternalDataTransferError),
             0x17 => Ok(EventFaultType::UnauthorizedCaseOpening),
             0x18 => Ok(EventFaultType::HardwareSabotage),
-            0x19..=0x1F => Ok(EventFaultType::RFU),
+            0x19..=0x1F => Ok(EventFaultType::RFU(value)),
 
             // Sensor related security breach attempt events,
             0x20 => Ok(EventFaultType::SensorSecurityBreachAttemptNoFurtherDetails),
@@ -231,7 +242,7 @@ impl EventFaultType {
             0x23 => Ok(EventFaultType::SensorInternalDataTransferError),
             0x24 => Ok(EventFaultType::SensorUnauthorizedCaseOpening),
             0x25 => Ok(EventFaultType::SensorHardwareSabotage),
-            0x26..=0x2F => Ok(EventFaultType::RFU),
+            0x26..=0x2F => Ok(EventFaultType::RFU(value)),
 
             // Recording equipment faults,
             0x30 => Ok(EventFaultType::ControlDeviceFaultNoFurtherDetails),
@@ -240,14 +251,14 @@ impl EventFaultType {
             0x33 => Ok(EventFaultType::DisplayFault),
             0x34 => Ok(EventFaultType::DownloadingFault),
             0x35 => Ok(EventFaultType::SensorFault),
-            0x36..=0x3F => Ok(EventFaultType::RFU),
+            0x36..=0x3F => Ok(EventFaultType::RFU(value)),
 
             // Card faults,
             0x40 => Ok(EventFaultType::NoFurtherDetails),
-            0x41..=0x4F => Ok(EventFaultType::RFU),
+            0x41..=0x4F => Ok(EventFaultType::RFU(value)),
 
             // Reserved for future use,
-            0x50..=0x7F => Ok(EventFaultType::RFU),
+            0x50..=0x7F => Ok(EventFaultType::RFU(value)),
 
             // Manufacturer specific,
             0x80..=0xFF => Ok(EventFaultType::ManufacturerSpecific),
@@ -255,51 +266,42 @@ impl EventFaultType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditionType: appendix 2.154.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24685)
+/// Gen1 only records the begin of a ferry or train crossing, there is no end marker. Other
+/// values, such as the Gen2 crossing end (0x04), are kept as [`Coded::Unknown`].
 pub enum SpecificConditionType {
-    RFU,
     OutOfScopeBegin,
     OutOfScopeEnd,
     FerryTrainCrossingBegin,
 }
-
-impl SpecificConditionType {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let value = cursor
-            .read_u8()
-            .context("Failed to read value for SpecificConditionType")?;
+impl FromByte for SpecificConditionType {
+    fn from_byte(value: u8) -> Option<Self> {
         match value {
-            0x0 => Ok(SpecificConditionType::RFU),
-            0x1 => Ok(SpecificConditionType::OutOfScopeBegin),
-            0x2 => Ok(SpecificConditionType::OutOfScopeEnd),
-            0x3 => Ok(SpecificConditionType::FerryTrainCrossingBegin),
-            0x4..=0xFF => Ok(SpecificConditionType::RFU),
+            0x1 => Some(SpecificConditionType::OutOfScopeBegin),
+            0x2 => Some(SpecificConditionType::OutOfScopeEnd),
+            0x3 => Some(SpecificConditionType::FerryTrainCrossingBegin),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditionRecord: appendix 2.152.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24614)
 pub struct SpecificConditionRecord {
     pub entry_time: TimeReal,
-    pub specific_condition_type: SpecificConditionType,
+    pub specific_condition_type: Coded<SpecificConditionType>,
 }
 impl SpecificConditionRecord {
     const SIZE: usize = 5;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let entry_time = TimeReal::parse(inner_cursor)?;
-        let specific_condition_type = SpecificConditionType::parse(inner_cursor)?;
-        if specific_condition_type == SpecificConditionType::RFU {
-            return Err(anyhow::anyhow!(
-                "RFU value found in SpecificConditionRecord"
-            ));
-        }
+        let specific_condition_type = Coded::parse(inner_cursor)?;
         Ok(SpecificConditionRecord {
             entry_time,
             specific_condition_type,
@@ -307,7 +309,7 @@ impl SpecificConditionRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditions: appendix 2.153.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24644)
@@ -324,29 +326,25 @@ impl SpecificConditions {
             }
         }
         // Sort the records by time_stamp in ascending order
-        specific_condition_records
-            .sort_by(|a, b| a.entry_time.0.timestamp().cmp(&b.entry_time.0.timestamp()));
+        sort_by_timestamp(&mut specific_condition_records);
         Ok(SpecificConditions {
             specific_condition_records: specific_condition_records,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [Certificate: appendix 2.41.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18396)
 pub struct Certificate(pub Vec<u8>);
 impl Certificate {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let mut value = vec![0u8; 194];
-        cursor
-            .read_exact(&mut value)
-            .context("Failed to read certificate")?;
+        let value = read_signature_bytes(cursor, 194).context("Failed to read certificate")?;
         Ok(Certificate(value))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [FullCardNumber: appendix 2.73.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21400)
@@ -367,7 +365,7 @@ impl FullCardNumber {
             | EquipmentType::CompanyCard => CardNumber::parse_owner(cursor)?,
             _ => CardNumber::parse_unknown(cursor)?,
         };
-        if card_type == EquipmentType::RFU {
+        if matches!(card_type, EquipmentType::RFU(_)) {
             return Err(anyhow::anyhow!("RFU value found in FullCardNumber"));
         }
 
@@ -379,7 +377,7 @@ impl FullCardNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ControlType: appendix 2.53.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19148)
@@ -402,23 +400,29 @@ impl ControlType {
             display: bits.3 == 1,
         })
     }
+
+    /// Encodes the control type into its 'cvpdxxxx'B byte, the inverse of [`Self::parse`].
+    pub fn encode(&self) -> u8 {
+        (self.card_downloading as u8) << 7
+            | (self.vu_downloading as u8) << 6
+            | (self.printing as u8) << 5
+            | (self.display as u8) << 4
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [Signature: appendix 2.149.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24501)
 pub struct Signature(pub Vec<u8>);
 impl Signature {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let mut signature_buffer = vec![0u8; 128];
-        cursor
-            .read_exact(&mut signature_buffer)
-            .context("Failed to read signature buffer")?;
+        let signature_buffer =
+            read_signature_bytes(cursor, 128).context("Failed to read signature buffer")?;
         Ok(Signature(signature_buffer))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PreviousVehicleInfo: appendix 2.118.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23250)
@@ -437,7 +441,7 @@ impl PreviousVehicleInfo {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EntryTypeDailyWorkPeriod: appendix 2.66.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20044)
 pub enum EntryTypeDailyWorkPeriod {
@@ -449,30 +453,27 @@ pub enum EntryTypeDailyWorkPeriod {
     EndRelatedTimeAssumedByVU,
 }
 
-impl EntryTypeDailyWorkPeriod {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let value = cursor
-            .read_u8()
-            .context("Failed to read EntryTypeDailyWorkPeriod")?;
+impl FromByte for EntryTypeDailyWorkPeriod {
+    fn from_byte(value: u8) -> Option<Self> {
         match value {
-            0x00 => Ok(EntryTypeDailyWorkPeriod::BeginRelatedTimeCardInsertionTimeOrTimeOfEntry),
-            0x01 => Ok(EntryTypeDailyWorkPeriod::EndRelatedTimeCardWithdrawalTimeOrTimeOfEntry),
-            0x02 => Ok(EntryTypeDailyWorkPeriod::BeginRelatedTimeManuallyEntered),
-            0x03 => Ok(EntryTypeDailyWorkPeriod::EndRelatedTimeManuallyEntered),
-            0x04 => Ok(EntryTypeDailyWorkPeriod::BeginRelatedTimeAssumedByVU),
-            0x05 => Ok(EntryTypeDailyWorkPeriod::EndRelatedTimeAssumedByVU),
-            _ => anyhow::bail!("Invalid EntryTypeDailyWorkPeriod"),
+            0x00 => Some(EntryTypeDailyWorkPeriod::BeginRelatedTimeCardInsertionTimeOrTimeOfEntry),
+            0x01 => Some(EntryTypeDailyWorkPeriod::EndRelatedTimeCardWithdrawalTimeOrTimeOfEntry),
+            0x02 => Some(EntryTypeDailyWorkPeriod::BeginRelatedTimeManuallyEntered),
+            0x03 => Some(EntryTypeDailyWorkPeriod::EndRelatedTimeManuallyEntered),
+            0x04 => Some(EntryTypeDailyWorkPeriod::BeginRelatedTimeAssumedByVU),
+            0x05 => Some(EntryTypeDailyWorkPeriod::EndRelatedTimeAssumedByVU),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PlaceRecord: appendix 2.117.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23112)
 pub struct PlaceRecord {
     pub entry_time: TimeReal,
-    pub entry_type_daily_work_period: EntryTypeDailyWorkPeriod,
+    pub entry_type_daily_work_period: Coded<EntryTypeDailyWorkPeriod>,
     pub daily_work_period_country: external::NationNumeric,
     pub daily_work_period_region: external::RegionNumeric,
     pub vehicle_odometer_value: OdometerShort,
@@ -480,10 +481,10 @@ pub struct PlaceRecord {
 impl PlaceRecord {
     const SIZE: usize = 10;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let entry_time = TimeReal::parse(inner_cursor)?;
-        let entry_type_daily_work_period = EntryTypeDailyWorkPeriod::parse(inner_cursor)?;
+        let entry_type_daily_work_period = Coded::<EntryTypeDailyWorkPeriod>::parse(inner_cursor)?;
         let daily_work_period_country = external::NationNumeric::parse(inner_cursor)?;
         let daily_work_period_region = external::RegionNumeric::parse(inner_cursor)?;
         let vehicle_odometer_value = OdometerShort::parse(inner_cursor)?;
@@ -500,7 +501,7 @@ impl PlaceRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [DriverCardApplicationIdentification: appendix 2.61.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19751)
@@ -565,7 +566,7 @@ type CardActivityLengthRange = u16;
 /// [NoOfCardVehicleRecords: appendix 2.105.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22612)
 type NoOfCardVehicleRecords = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct WorkshopCardApplicationIdentification {
@@ -629,7 +630,7 @@ impl WorkshopCardApplicationIdentification {
 /// [NoOfControlActivityRecords: appendix 2.108.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22683)
 type NoOfControlActivityRecords = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ControlCardApplicationIdentification: appendix 2.50.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18950)
@@ -660,7 +661,7 @@ impl ControlCardApplicationIdentification {
 /// [NoOfCompanyActivityRecords: appendix 2.107.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22660)
 type NoOfCompanyActivityRecords = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CompanyCardApplicationIdentification: appendix 2.48.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18846)
@@ -688,7 +689,7 @@ impl CompanyCardApplicationIdentification {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(tag = "type")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum ApplicationIdentification {
@@ -722,27 +723,54 @@ impl ApplicationIdentification {
             _ => anyhow::bail!("Invalid ApplicationIdentification type"),
         }
     }
+
+    /// Number of records in EF VehiclesUsed, for card types that have it.
+    pub fn no_of_card_vehicle_records(&self) -> Option<usize> {
+        match self {
+            ApplicationIdentification::DriverCard(app) => {
+                Some(app.no_of_card_vehicle_records as usize)
+            }
+            ApplicationIdentification::WorkshopCard(app) => {
+                Some(app.no_of_card_vehicle_records as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of records in EF Places, for card types that have it.
+    pub fn no_of_card_place_records(&self) -> Option<usize> {
+        match self {
+            ApplicationIdentification::DriverCard(app) => {
+                Some(app.no_of_card_place_records as usize)
+            }
+            ApplicationIdentification::WorkshopCard(app) => {
+                Some(app.no_of_card_place_records as usize)
+            }
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardEventRecord: appendix 2.20.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17247)
 pub struct CardEventRecord {
     pub event_type: EventFaultType,
     pub event_begin_time: TimeReal,
-    pub event_end_time: TimeReal,
+    /// `None` for a event that had not ended yet, stored with an invalid end time.
+    pub event_end_time: Option<TimeReal>,
     pub event_vehicle_registration: VehicleRegistrationIdentification,
 }
 
 impl CardEventRecord {
     const SIZE: usize = 24;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let event_type = EventFaultType::parse(inner_cursor)?;
         let event_begin_time = TimeReal::parse(inner_cursor)?;
-        let event_end_time = TimeReal::parse(inner_cursor)?;
+        let event_end_time = parse_optional(inner_cursor, "event_end_time", TimeReal::parse);
         let event_vehicle_registration = VehicleRegistrationIdentification::parse(inner_cursor)?;
 
         Ok(CardEventRecord {
@@ -754,7 +782,7 @@ impl CardEventRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardEventData: appendix 2.19.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17180)
@@ -787,25 +815,26 @@ impl CardEventData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardFaultData: appendix 2.21.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17292)
 pub struct CardFaultRecord {
     pub fault_type: EventFaultType,
     pub fault_begin_time: TimeReal,
-    pub fault_end_time: TimeReal,
+    /// `None` for a fault that had not ended yet, stored with an invalid end time.
+    pub fault_end_time: Option<TimeReal>,
     pub fault_vehicle_registration: VehicleRegistrationIdentification,
 }
 
 impl CardFaultRecord {
     const SIZE: usize = 24;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let fault_type = EventFaultType::parse(inner_cursor)?;
         let fault_begin_time = TimeReal::parse(inner_cursor)?;
-        let fault_end_time = TimeReal::parse(inner_cursor)?;
+        let fault_end_time = parse_optional(inner_cursor, "fault_end_time", TimeReal::parse);
         let fault_vehicle_registration = VehicleRegistrationIdentification::parse(inner_cursor)?;
 
         Ok(CardFaultRecord {
@@ -817,7 +846,7 @@ impl CardFaultRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardFaultData: appendix 2.22.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17340)
@@ -851,7 +880,7 @@ impl CardFaultData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehicleRecord: appendix 2.37.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18163)
@@ -866,7 +895,7 @@ pub struct CardVehicleRecord {
 impl CardVehicleRecord {
     const SIZE: usize = 31;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         Ok(CardVehicleRecord {
             vehicle_odometer_begin: OdometerShort::parse(inner_cursor)?,
@@ -879,7 +908,7 @@ impl CardVehicleRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehiclesUsed: appendix 2.38.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18302)
@@ -889,14 +918,25 @@ pub struct CardVehiclesUsed {
 }
 impl CardVehiclesUsed {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        Self::parse_with_no_of_records(cursor, size, None)
+    }
+
+    /// Parses at most `no_of_records` records, as given by the application identification,
+    /// instead of as many as fit in the EF.
+    pub fn parse_with_no_of_records(
+        cursor: &mut Cursor<&[u8]>,
+        size: usize,
+        no_of_records: Option<usize>,
+    ) -> Result<Self> {
+        let cursor = &mut cursor.take_exact(size)?;
         let vehicle_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read vehicle_pointer_newest_record")?;
 
         let mut card_vehicle_records = Vec::new();
         // 2 bytes for the pointer size
-        let no_of_records = (size - 2) / CardVehicleRecord::SIZE;
+        let fitting_records = (size - 2) / CardVehicleRecord::SIZE;
+        let no_of_records = no_of_records.map_or(fitting_records, |n| n.min(fitting_records));
         for _ in 0..no_of_records {
             if let Ok(card_vehicle_record) = CardVehicleRecord::parse(cursor) {
                 card_vehicle_records.push(card_vehicle_record);
@@ -914,7 +954,7 @@ impl CardVehiclesUsed {
 
 /// [NoOfCardPlaceRecords: appendix 2.104.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22566)
 pub type NoOfCardPlaceRecords = u8;
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardPlaceDailyWorkPeriod: appendix 2.27.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17729)
@@ -924,14 +964,25 @@ pub struct CardPlaceDailyWorkPeriod {
 }
 impl CardPlaceDailyWorkPeriod {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        Self::parse_with_no_of_records(cursor, size, None)
+    }
+
+    /// Parses at most `no_of_records` records, as given by the application identification,
+    /// instead of as many as fit in the EF.
+    pub fn parse_with_no_of_records(
+        cursor: &mut Cursor<&[u8]>,
+        size: usize,
+        no_of_records: Option<usize>,
+    ) -> Result<Self> {
+        let cursor = &mut cursor.take_exact(size)?;
 
         let place_pointer_newest_record = cursor
             .read_u8()
             .context("Failed to read place_pointer_newest_record")?;
 
         // 1 byte for the pointer size
-        let no_of_records = (size - 1) / PlaceRecord::SIZE;
+        let fitting_records = (size - 1) / PlaceRecord::SIZE;
+        let no_of_records = no_of_records.map_or(fitting_records, |n| n.min(fitting_records));
         let mut place_records = Vec::new();
 
         for _ in 0..no_of_records {
@@ -942,7 +993,7 @@ impl CardPlaceDailyWorkPeriod {
             }
         }
         // Sort the records by entry_time in ascending order
-        place_records.sort_by(|a, b| a.entry_time.0.timestamp().cmp(&b.entry_time.0.timestamp()));
+        sort_by_timestamp(&mut place_records);
         Ok(CardPlaceDailyWorkPeriod {
             place_pointer_newest_record,
             place_records,
@@ -950,7 +1001,7 @@ impl CardPlaceDailyWorkPeriod {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardControlActivityDataRecord appendix 2.15.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17002)
@@ -965,15 +1016,24 @@ pub struct CardControlActivityDataRecord {
 impl CardControlActivityDataRecord {
     const SIZE: usize = 46;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let control_type = ControlType::parse(inner_cursor)?;
-        let control_time = TimeReal::parse(inner_cursor).ok();
-        let control_card_number = FullCardNumber::parse(inner_cursor).ok();
-        let control_vehicle_registration =
-            VehicleRegistrationIdentification::parse(inner_cursor).ok();
-        let control_download_period_begin = TimeReal::parse(inner_cursor).ok();
-        let control_download_period_end = TimeReal::parse(inner_cursor).ok();
+        let control_time = parse_optional(inner_cursor, "control_time", TimeReal::parse);
+        let control_card_number =
+            parse_optional(inner_cursor, "control_card_number", FullCardNumber::parse);
+        let control_vehicle_registration = parse_optional(
+            inner_cursor,
+            "control_vehicle_registration",
+            VehicleRegistrationIdentification::parse,
+        );
+        let control_download_period_begin = parse_optional(
+            inner_cursor,
+            "control_download_period_begin",
+            TimeReal::parse,
+        );
+        let control_download_period_end =
+            parse_optional(inner_cursor, "control_download_period_end", TimeReal::parse);
 
         Ok(Self {
             control_type,
@@ -986,7 +1046,7 @@ impl CardControlActivityDataRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuDownloadActivityData: appendix 2.195.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26758)
@@ -1009,7 +1069,7 @@ impl VuDownloadActivityData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCompanyLocksData: appendix 2.183.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26258)
@@ -1036,7 +1096,7 @@ impl VuCompanyLocksData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCompanyLocksRecord: appendix 2.184.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26153)
@@ -1051,9 +1111,7 @@ impl VuCompanyLocksRecord {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(Self {
             lock_in_time: TimeReal::parse(cursor).context("Failed to parse lock_in_time")?,
-            lock_out_time: TimeReal::parse(cursor)
-                .context("Failed to parse lock_out_time")
-                .ok(),
+            lock_out_time: parse_optional(cursor, "lock_out_time", TimeReal::parse),
             company_name: Name::parse(cursor).context("Failed to parse company_name")?,
             company_address: Address::parse(cursor).context("Failed to parse company_address")?,
             company_card_number: FullCardNumber::parse(cursor)
@@ -1062,7 +1120,7 @@ impl VuCompanyLocksRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuControlActivityData: appendix 2.186.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26342)
@@ -1090,7 +1148,7 @@ impl VuControlActivityData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuControlActivityRecord: appendix 2.187.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26392)
@@ -1098,8 +1156,9 @@ pub struct VuControlActivityRecord {
     pub control_type: ControlType,
     pub control_time: TimeReal,
     pub control_card_number: FullCardNumber,
-    pub download_period_begin_time: TimeReal,
-    pub download_period_end_time: TimeReal,
+    /// Only set for VU downloads, zero otherwise.
+    pub download_period_begin_time: Option<TimeReal>,
+    pub download_period_end_time: Option<TimeReal>,
 }
 
 impl VuControlActivityRecord {
@@ -1109,15 +1168,21 @@ impl VuControlActivityRecord {
             control_time: TimeReal::parse(cursor).context("Failed to parse control_time")?,
             control_card_number: FullCardNumber::parse(cursor)
                 .context("Failed to parse control_card_number")?,
-            download_period_begin_time: TimeReal::parse(cursor)
-                .context("Failed to parse download_period_begin_time")?,
-            download_period_end_time: TimeReal::parse(cursor)
-                .context("Failed to parse download_period_end_time")?,
+            download_period_begin_time: parse_optional(
+                cursor,
+                "download_period_begin_time",
+                TimeReal::parse,
+            ),
+            download_period_end_time: parse_optional(
+                cursor,
+                "download_period_end_time",
+                TimeReal::parse,
+            ),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuOverviewBlock page 342]
@@ -1175,7 +1240,7 @@ impl VuOverviewBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuCardIWData {
@@ -1202,7 +1267,7 @@ impl VuCardIWData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCardIwRecord: appendix 2.177.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25809)
@@ -1212,11 +1277,11 @@ pub struct VuCardIwRecord {
     pub card_expiry_date: TimeReal,
     pub card_insertion_time: TimeReal,
     pub vehicle_odometer_value_at_insertion: OdometerShort,
-    pub card_slot_number: CardSlotNumber,
+    pub card_slot_number: Coded<CardSlotNumber>,
     pub card_withdrawal_time: Option<TimeReal>,
     pub vehicle_odometer_value_at_withdrawal: OdometerShort,
     pub previous_vehicle_info: Option<PreviousVehicleInfo>,
-    pub manual_entry_flag: Option<ManualInputFlag>,
+    pub manual_entry_flag: Option<Coded<ManualInputFlag>>,
 }
 
 impl VuCardIwRecord {
@@ -1232,24 +1297,22 @@ impl VuCardIwRecord {
                 .context("Failed to parse card_insertion_time")?,
             vehicle_odometer_value_at_insertion: OdometerShort::parse(cursor)
                 .context("Failed to parse vehicle_odometer_value_at_insertion")?,
-            card_slot_number: CardSlotNumber::parse(cursor)
+            card_slot_number: Coded::<CardSlotNumber>::parse(cursor)
                 .context("Failed to parse card_slot_number")?,
-            card_withdrawal_time: TimeReal::parse(cursor)
-                .context("Failed to parse card_withdrawal_time")
-                .ok(),
+            card_withdrawal_time: parse_optional(cursor, "card_withdrawal_time", TimeReal::parse),
             vehicle_odometer_value_at_withdrawal: OdometerShort::parse(cursor)
                 .context("Failed to parse vehicle_odometer_value_at_withdrawal")?,
             previous_vehicle_info: PreviousVehicleInfo::parse(cursor)
                 .context("Failed to parse previous_vehicle_info")
                 .ok(),
-            manual_entry_flag: ManualInputFlag::parse(cursor)
+            manual_entry_flag: Coded::<ManualInputFlag>::parse(cursor)
                 .context("Failed to parse manual_entry_flag")
                 .ok(),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuActivityDailyData: appendix 2.170.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25344)
@@ -1271,6 +1334,9 @@ impl VuActivityDailyData {
                     .context("Failed to parse ActivityChangeInfo")?,
             );
         }
+        // no_of_activity_changes keeps the count of the file
+        let activity_change_infos =
+            CardActivityChangeInfo::drop_past_end_of_day(activity_change_infos);
 
         Ok(Self {
             no_of_activity_changes,
@@ -1279,7 +1345,7 @@ impl VuActivityDailyData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuPlaceDailyWorkPeriodRecord: appendix 2.219.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28313)
@@ -1298,7 +1364,7 @@ impl VuPlaceDailyWorkPeriodRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuPlaceDailyWorkPeriodData: appendix 2.218.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28280)
@@ -1329,26 +1395,26 @@ impl VuPlaceDailyWorkPeriodData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuSpecificConditionRecord: appendix 2.152.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24614)
 pub struct VuSpecificConditionRecord {
     pub entry_time: TimeReal,
-    pub specific_condition_type: SpecificConditionType,
+    pub specific_condition_type: Coded<SpecificConditionType>,
 }
 
 impl VuSpecificConditionRecord {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(Self {
             entry_time: TimeReal::parse(cursor).context("Failed to parse entry_time")?,
-            specific_condition_type: SpecificConditionType::parse(cursor)
+            specific_condition_type: Coded::parse(cursor)
                 .context("Failed to parse specific_condition_type")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuSpecificConditionData: appendix 2.227.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28591)
@@ -1378,7 +1444,7 @@ impl VuSpecificConditionData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// Page 344 TREP 02
@@ -1411,7 +1477,7 @@ impl VuActivitiesBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuFaultRecord: appendix 2.201.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27156)
@@ -1434,23 +1500,31 @@ impl VuFaultRecord {
             fault_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse fault_begin_time")?,
             fault_end_time: TimeReal::parse(cursor).context("Failed to parse fault_end_time")?,
-            card_number_driver_slot_begin: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_driver_slot_begin")
-                .ok(),
-            card_number_codriver_slot_begin: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_codriver_slot_begin")
-                .ok(),
-            card_number_driver_slot_end: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_driver_slot_end")
-                .ok(),
-            card_number_codriver_slot_end: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_codriver_slot_end")
-                .ok(),
+            card_number_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_driver_slot_begin",
+                FullCardNumber::parse,
+            ),
+            card_number_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_codriver_slot_begin",
+                FullCardNumber::parse,
+            ),
+            card_number_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_driver_slot_end",
+                FullCardNumber::parse,
+            ),
+            card_number_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_codriver_slot_end",
+                FullCardNumber::parse,
+            ),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuFaultRecord: appendix 2.200.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27122)
@@ -1476,7 +1550,7 @@ impl VuFaultData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuEventRecord: appendix 2.197.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26910)
@@ -1499,26 +1573,34 @@ impl VuEventRecord {
                 .context("Failed to parse event_record_purpose")?,
             event_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse event_begin_time")?,
-            event_end_time: TimeReal::parse(cursor).ok(),
-            card_number_driver_slot_begin: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_driver_slot_begin")
-                .ok(),
-            card_number_codriver_slot_begin: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_codriver_slot_begin")
-                .ok(),
-            card_number_driver_slot_end: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_driver_slot_end")
-                .ok(),
-            card_number_codriver_slot_end: FullCardNumber::parse(cursor)
-                .context("Failed to parse card_number_codriver_slot_end")
-                .ok(),
+            event_end_time: parse_optional(cursor, "event_end_time", TimeReal::parse),
+            card_number_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_driver_slot_begin",
+                FullCardNumber::parse,
+            ),
+            card_number_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_codriver_slot_begin",
+                FullCardNumber::parse,
+            ),
+            card_number_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_driver_slot_end",
+                FullCardNumber::parse,
+            ),
+            card_number_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_codriver_slot_end",
+                FullCardNumber::parse,
+            ),
             similar_events_number: SimilarEventsNumber::parse(cursor)
                 .context("Failed to parse similar_events_number")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuEventData: appendix 2.197.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26876)
@@ -1544,7 +1626,7 @@ impl VuEventData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuOverSpeedingControlData: appendix 2.212.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27978)
@@ -1557,17 +1639,25 @@ pub struct VuOverSpeedingControlData {
 impl VuOverSpeedingControlData {
     const SIZE: usize = 9;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         Ok(Self {
-            last_overspeed_control_time: TimeReal::parse(inner_cursor).ok(),
-            first_overspeed_since: TimeReal::parse(inner_cursor).ok(),
+            last_overspeed_control_time: parse_optional(
+                inner_cursor,
+                "last_overspeed_control_time",
+                TimeReal::parse,
+            ),
+            first_overspeed_since: parse_optional(
+                inner_cursor,
+                "first_overspeed_since",
+                TimeReal::parse,
+            ),
             number_of_overspeed_since: OverspeedNumber::parse(inner_cursor)
                 .context("Failed to parse number_of_overspeed_since")?,
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuOverSpeedingEventRecord: appendix 2.215.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28097)
@@ -1603,7 +1693,7 @@ impl VuOverSpeedingEventRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuOverSpeedingEventData: appendix 2.214.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28064)
@@ -1632,12 +1722,16 @@ impl VuOverSpeedingEventData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuTimeAdjustmentRecord: appendix 2.232.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28728)
 pub struct VuTimeAdjustmentRecord {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub old_time_value: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub new_time_value: DateTime<Utc>,
     pub workshop_name: Name,
     pub workshop_address: Address,
@@ -1660,7 +1754,7 @@ impl VuTimeAdjustmentRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuTimeAdjustmentData: appendix 2.229.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28675)
@@ -1688,7 +1782,7 @@ impl VuTimeAdjustmentData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// TREP 03 page 346
@@ -1715,23 +1809,23 @@ impl VuEventsAndFaultsBlock {
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuDetailedSpeedData: appendix 2.192.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26618)
 pub struct VuDetailedSpeedData {
-    pub no_of_speed_blocks: u8,
-    pub vu_detailed_speed_records: Vec<VuDetailedSpeedBlock>,
+    pub no_of_speed_blocks: u16,
+    pub vu_detailed_speed_records: Vec<crate::dt::VuDetailedSpeedBlock>,
 }
 impl VuDetailedSpeedData {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let no_of_speed_blocks = cursor
-            .read_u8()
+            .read_u16::<BigEndian>()
             .context("Failed to read no_of_speed_blocks")?;
         let mut vu_detailed_speed_records = Vec::with_capacity(no_of_speed_blocks as usize);
         for _ in 0..no_of_speed_blocks {
             vu_detailed_speed_records.push(
-                VuDetailedSpeedBlock::parse(cursor)
+                crate::dt::VuDetailedSpeedBlock::parse(cursor)
                     .context("Failed to parse VuDetailedSpeedBlock")?,
             );
         }
@@ -1743,9 +1837,11 @@ impl VuDetailedSpeedData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
+// Keeps the TS export apart from the per-minute dt::VuDetailedSpeedBlock
+#[cfg_attr(feature = "ts", ts(rename = "VuDetailedSpeedBlockGen1"))]
 pub struct VuDetailedSpeedBlock {
     pub vu_detailed_speed_data: VuDetailedSpeedData,
     pub signature: Signature,
@@ -1760,7 +1856,7 @@ impl VuDetailedSpeedBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuIdentification {
@@ -1799,7 +1895,7 @@ impl VuIdentification {
 /// [VuSerialNumber: appendix 2.223.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28497)
 pub type VuSerialNumber = ExtendedSerialNumber;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SensorPaired: appendix 2.144.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24360)
@@ -1822,12 +1918,63 @@ impl SensorPaired {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// [SensorSCIdentifier: appendix 2.147.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+pub struct SensorSCIdentifier(pub IA5String);
+impl SensorSCIdentifier {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let value = IA5String::parse_dyn_size(cursor, 8)?;
+        Ok(SensorSCIdentifier(value))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// [SensorOSIdentifier: appendix 2.143.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+pub struct SensorOSIdentifier(pub IA5String);
+impl SensorOSIdentifier {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let value = IA5String::parse_dyn_size(cursor, 2)?;
+        Ok(SensorOSIdentifier(value))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// [SensorIdentification: appendix 2.140.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+pub struct SensorIdentification {
+    pub sensor_serial_number: SensorSerialNumber,
+    pub sensor_approval_number: SensorApprovalNumber,
+    /// Identifier of the security component of the sensor.
+    pub sensor_sc_identifier: SensorSCIdentifier,
+    /// Identifier of the operating system of the sensor.
+    pub sensor_os_identifier: SensorOSIdentifier,
+}
+impl SensorIdentification {
+    pub const SIZE: usize = 26;
+
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        Ok(Self {
+            sensor_serial_number: SensorSerialNumber::parse(cursor)
+                .context("Failed to parse sensor_serial_number")?,
+            sensor_approval_number: SensorApprovalNumber::parse(cursor)
+                .context("Failed to parse sensor_approval_number")?,
+            sensor_sc_identifier: SensorSCIdentifier::parse(cursor)
+                .context("Failed to parse sensor_sc_identifier")?,
+            sensor_os_identifier: SensorOSIdentifier::parse(cursor)
+                .context("Failed to parse sensor_os_identifier")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCalibrationRecord: appendix 2.174.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25500)
 pub struct VuCalibrationRecord {
-    pub calibration_purpose: CalibrationPurpose,
+    pub calibration_purpose: Coded<CalibrationPurpose>,
     pub workshop_name: Name,
     pub workshop_address: Address,
     pub workshop_card_number: FullCardNumber,
@@ -1849,19 +1996,27 @@ pub struct VuCalibrationRecord {
 impl VuCalibrationRecord {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(Self {
-            calibration_purpose: CalibrationPurpose::parse(cursor)
+            calibration_purpose: Coded::<CalibrationPurpose>::parse(cursor)
                 .context("Failed to parse calibration_purpose")?,
             workshop_name: Name::parse(cursor).context("Failed to parse workshop_name")?,
             workshop_address: Address::parse(cursor).context("Failed to parse workshop_address")?,
             workshop_card_number: FullCardNumber::parse(cursor)
                 .context("Failed to parse workshop_card_number")?,
-            workshop_card_expiry_date: TimeReal::parse(cursor).ok(),
-            vehicle_identification_number: VehicleIdentificationNumber::parse(cursor)
-                .context("Failed to parse vehicle_identification_number")
-                .ok(),
-            vehicle_registration_identification: VehicleRegistrationIdentification::parse(cursor)
-                .context("Failed to parse vehicle_registration_identification")
-                .ok(),
+            workshop_card_expiry_date: parse_optional(
+                cursor,
+                "workshop_card_expiry_date",
+                TimeReal::parse,
+            ),
+            vehicle_identification_number: parse_optional(
+                cursor,
+                "vehicle_identification_number",
+                VehicleIdentificationNumber::parse,
+            ),
+            vehicle_registration_identification: parse_optional(
+                cursor,
+                "vehicle_registration_identification",
+                VehicleRegistrationIdentification::parse,
+            ),
             w_vehicle_characteristic_constant: WVehicleCharacteristicConstant::parse(cursor)
                 .context("Failed to parse w_vehicle_characteristic_constant")?,
             k_constant_of_recording_equipment: KConstantOfRecordingEquipment::parse(cursor)
@@ -1875,15 +2030,15 @@ impl VuCalibrationRecord {
                 .context("Failed to parse old_odometer_value")?,
             new_odometer_value: OdometerShort::parse(cursor)
                 .context("Failed to parse new_odometer_value")?,
-            old_time_value: TimeReal::parse(cursor).ok(),
-            new_time_value: TimeReal::parse(cursor).ok(),
-            next_calibration_date: TimeReal::parse(cursor).ok(),
+            old_time_value: parse_optional(cursor, "old_time_value", TimeReal::parse),
+            new_time_value: parse_optional(cursor, "new_time_value", TimeReal::parse),
+            next_calibration_date: parse_optional(cursor, "next_calibration_date", TimeReal::parse),
         })
     }
 }
 
 /// [VuCalibrationData: appendix 2.173.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25471)
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuCalibrationData {
@@ -1909,7 +2064,7 @@ impl VuCalibrationData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCompanyLocksBlock: appendix 2.236.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28868)
@@ -1936,11 +2091,11 @@ impl VuCompanyLocksBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct WorkshopCardCalibrationRecord {
-    pub calibration_purpose: CalibrationPurpose,
+    pub calibration_purpose: Coded<CalibrationPurpose>,
     pub vehicle_identification_number: VehicleIdentificationNumber,
     pub vehicle_registration: VehicleRegistrationIdentification,
     pub w_vehicle_characteristic_constant: WVehicleCharacteristicConstant,
@@ -1960,9 +2115,9 @@ pub struct WorkshopCardCalibrationRecord {
 impl WorkshopCardCalibrationRecord {
     const SIZE: usize = 105;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(Self::SIZE);
-        let calibration_purpose =
-            CalibrationPurpose::parse(cursor).context("Failed to parse calibration_purpose")?;
+        let cursor = &mut cursor.take_exact(Self::SIZE)?;
+        let calibration_purpose = Coded::<CalibrationPurpose>::parse(cursor)
+            .context("Failed to parse calibration_purpose")?;
         let vehicle_identification_number = VehicleIdentificationNumber::parse(cursor)
             .context("Failed to parse vehicle_identification_number")?;
         let vehicle_registration = VehicleRegistrationIdentification::parse(cursor)
@@ -2015,7 +2170,7 @@ impl WorkshopCardCalibrationRecord {
 /// [NoOfCalibrationRecords: appendix 2.235.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e29088)
 pub type NoOfCalibrationRecords = u8;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [WorkshopCardCalibrationData: appendix 2.235.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e29088)
@@ -2050,7 +2205,7 @@ impl WorkshopCardCalibrationData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SensorInstallation: appendix 2.141.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24238)
@@ -2063,6 +2218,8 @@ pub struct SensorInstallation {
     pub current_vu_serial_number: VuSerialNumber,
 }
 impl SensorInstallation {
+    pub const SIZE: usize = 40;
+
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let sensor_pairing_date_first = SensorPairingDate::parse(cursor)
             .context("Failed to parse sensor_pairing_date_first")?;
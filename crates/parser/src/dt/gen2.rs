@@ -1,14 +1,16 @@
 #![allow(dead_code)]
 use super::*;
+use crate::bytes::TracedRead;
 use crate::bytes::{extract_u8_bits_into_tup, TakeExact};
 use anyhow::{Context, Result};
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::BigEndian;
 use serde::{Deserialize, Serialize};
-use std::{any::type_name, io::Read};
+use std::any::type_name;
+use std::collections::BTreeMap;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [RecordType: appendix 2.120.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23342)
 pub enum RecordTypeGen2 {
@@ -45,7 +47,8 @@ pub enum RecordTypeGen2 {
     VuPowerSupplyInterruptionRecord,
     SensorPairedRecord,
     SensorExternalGNSSCoupledRecord,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 
@@ -89,13 +92,13 @@ impl RecordTypeGen2 {
             0x1F => Ok(RecordTypeGen2::VuPowerSupplyInterruptionRecord),
             0x20 => Ok(RecordTypeGen2::SensorPairedRecord),
             0x21 => Ok(RecordTypeGen2::SensorExternalGNSSCoupledRecord),
-            0x22..=0x7F => Ok(RecordTypeGen2::RFU),
+            0x22..=0x7F => Ok(RecordTypeGen2::RFU(record_type)),
             0x80..=0xFF => Ok(RecordTypeGen2::ManufacturerSpecific),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 
 /// A generic implementation for an array of records, where the record type is parameterized
@@ -107,110 +110,130 @@ pub struct RecordArray<T> {
     pub records: Vec<T>,
 }
 
-impl<T> RecordArray<T> {
-    pub fn parse<F>(cursor: &mut Cursor<&[u8]>, parse_record: F) -> Result<Self>
-    where
-        F: Fn(&mut Cursor<&[u8]>) -> Result<T>,
-    {
-        let record_type = RecordTypeGen2::parse(cursor).context("Failed to parse record type")?;
-        let record_size = cursor
-            .read_u16::<BigEndian>()
-            .context("Failed to read record size")?;
-        let no_of_records = cursor
-            .read_u16::<BigEndian>()
-            .context("Failed to read number of records")?;
-
-        let data_size = record_size as usize * no_of_records as usize;
+/// Records of arrays which usually hold a single record, such as the VIN or the downloaded day.
+/// With the `smallvec` feature the record is stored inline instead of on the heap. The JSON and
+/// the TS bindings are the same either way, fields of this type are exported as a `Vec`.
+#[cfg(feature = "smallvec")]
+pub type SmallRecords<T> = smallvec::SmallVec<[T; 1]>;
+#[cfg(not(feature = "smallvec"))]
+pub type SmallRecords<T> = Vec<T>;
 
-        let mut raw_data = vec![0u8; data_size];
-        cursor
-            .read_exact(&mut raw_data)
-            .context("Failed to read raw data for record array")?;
-
-        let mut records = Vec::with_capacity(no_of_records as usize);
-        for (index, chunk) in raw_data.chunks(record_size as usize).enumerate() {
-            let mut inner_cursor = Cursor::new(chunk);
-            let initial_position = inner_cursor.position();
-
-            let record = parse_record(&mut inner_cursor).with_context(|| {
-                format!(
-                    "Failed to parse record of type {} at index {}",
-                    type_name::<T>(),
-                    index
-                )
-            })?;
-
-            let consumed = inner_cursor.position() - initial_position;
-            if consumed < record_size as u64 {
-                let unused_bytes = record_size as u64 - consumed;
-                log::warn!(
-                    "Record of type {} did not consume all bytes. Expected to consume {} bytes, but only consumed {}. {} bytes were unused.",
-                    type_name::<T>(),
-                    record_size,
-                    consumed,
-                    unused_bytes
-                );
-            }
+/// Storage the records of a [`RecordArray`] can be parsed into.
+pub trait RecordStorage<T> {
+    fn with_capacity(capacity: usize) -> Self;
+    fn push(&mut self, record: T);
+}
+impl<T> RecordStorage<T> for Vec<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+    fn push(&mut self, record: T) {
+        Vec::push(self, record)
+    }
+}
+#[cfg(feature = "smallvec")]
+impl<T> RecordStorage<T> for smallvec::SmallVec<[T; 1]> {
+    fn with_capacity(capacity: usize) -> Self {
+        smallvec::SmallVec::with_capacity(capacity)
+    }
+    fn push(&mut self, record: T) {
+        smallvec::SmallVec::push(self, record)
+    }
+}
 
-            records.push(record);
+/// Lower bound of the bytes missing from a sequence of record arrays cut short at the end of
+/// `data`: the rest of the array the data ends in, or a header if it ends between two arrays.
+pub(crate) fn record_arrays_missing_bytes(data: &[u8]) -> usize {
+    const HEADER_SIZE: usize = 5;
+    let mut position = 0;
+    loop {
+        let Some(header) = data.get(position..position + HEADER_SIZE) else {
+            return position + HEADER_SIZE - data.len();
+        };
+        let record_size = u16::from_be_bytes([header[1], header[2]]) as usize;
+        let no_of_records = u16::from_be_bytes([header[3], header[4]]) as usize;
+        let end = position + HEADER_SIZE + record_size * no_of_records;
+        if end > data.len() {
+            return end - data.len();
+        }
+        position = end;
+    }
+}
+
+/// Reads the header of a record array and parses its records into `C`.
+fn parse_records<T, C, F>(
+    cursor: &mut Cursor<&[u8]>,
+    mut parse_record: F,
+) -> Result<(RecordTypeGen2, u16, u16, C)>
+where
+    C: RecordStorage<T>,
+    F: FnMut(&mut Cursor<&[u8]>, usize) -> Result<T>,
+{
+    let record_type = RecordTypeGen2::parse(cursor).context("Failed to parse record type")?;
+    let record_size = cursor
+        .read_u16::<BigEndian>()
+        .context("Failed to read record size")?;
+    let no_of_records = cursor
+        .read_u16::<BigEndian>()
+        .context("Failed to read number of records")?;
+
+    if record_size == 0 && no_of_records > 0 {
+        anyhow::bail!(
+            "Record array of {} records with a record size of 0",
+            no_of_records
+        );
+    }
+    let data_size = record_size as usize * no_of_records as usize;
+
+    let data = cursor
+        .take_exact(data_size)
+        .context("Failed to read raw data for record array")?
+        .into_inner();
+
+    let mut records = C::with_capacity(no_of_records as usize);
+    for (index, chunk) in data.chunks(record_size.max(1) as usize).enumerate() {
+        let mut inner_cursor = Cursor::new(chunk);
+        let initial_position = inner_cursor.position();
+
+        let record = parse_record(&mut inner_cursor, record_size as usize).with_context(|| {
+            format!(
+                "Failed to parse record of type {} at index {}",
+                type_name::<T>(),
+                index
+            )
+        })?;
+
+        let consumed = inner_cursor.position() - initial_position;
+        if consumed < record_size as u64 {
+            let unused_bytes = record_size as u64 - consumed;
+            log::warn!(
+                "Record of type {} did not consume all bytes. Expected to consume {} bytes, but only consumed {}. {} bytes were unused.",
+                type_name::<T>(),
+                record_size,
+                consumed,
+                unused_bytes
+            );
         }
 
-        Ok(RecordArray {
-            record_type,
-            record_size,
-            no_of_records,
-            records,
-        })
+        records.push(record);
     }
+    Ok((record_type, record_size, no_of_records, records))
+}
 
-    pub fn parse_dyn_size<F>(cursor: &mut Cursor<&[u8]>, parse_record: F) -> Result<Self>
+impl<T> RecordArray<T> {
+    pub fn parse<F>(cursor: &mut Cursor<&[u8]>, mut parse_record: F) -> Result<Self>
     where
-        F: Fn(&mut Cursor<&[u8]>, usize) -> Result<T>,
+        F: FnMut(&mut Cursor<&[u8]>) -> Result<T>,
     {
-        let record_type = RecordTypeGen2::parse(cursor).context("Failed to parse record type")?;
-        let record_size = cursor
-            .read_u16::<BigEndian>()
-            .context("Failed to read record size")?;
-        let no_of_records = cursor
-            .read_u16::<BigEndian>()
-            .context("Failed to read number of records")?;
-
-        let data_size = record_size as usize * no_of_records as usize;
-
-        let mut raw_data = vec![0u8; data_size];
-        cursor
-            .read_exact(&mut raw_data)
-            .context("Failed to read raw data for record array")?;
-
-        let mut records = Vec::with_capacity(no_of_records as usize);
-        for (index, chunk) in raw_data.chunks(record_size as usize).enumerate() {
-            let mut inner_cursor = Cursor::new(chunk);
-            let initial_position = inner_cursor.position();
-
-            let record =
-                parse_record(&mut inner_cursor, record_size as usize).with_context(|| {
-                    format!(
-                        "Failed to parse record of type {} at index {}",
-                        type_name::<T>(),
-                        index
-                    )
-                })?;
-
-            let consumed = inner_cursor.position() - initial_position;
-            if consumed < record_size as u64 {
-                let unused_bytes = record_size as u64 - consumed;
-                log::warn!(
-                    "Record of type {} did not consume all bytes. Expected to consume {} bytes, but only consumed {}. {} bytes were unused.",
-                    type_name::<T>(),
-                    record_size,
-                    consumed,
-                    unused_bytes
-                );
-            }
-
-            records.push(record);
-        }
+        Self::parse_dyn_size(cursor, |cursor, _| parse_record(cursor))
+    }
 
+    pub fn parse_dyn_size<F>(cursor: &mut Cursor<&[u8]>, parse_record: F) -> Result<Self>
+    where
+        F: FnMut(&mut Cursor<&[u8]>, usize) -> Result<T>,
+    {
+        let (record_type, record_size, no_of_records, records) =
+            parse_records(cursor, parse_record)?;
         Ok(RecordArray {
             record_type,
             record_size,
@@ -218,21 +241,41 @@ impl<T> RecordArray<T> {
             records,
         })
     }
+
+    /// Parses the records of an array that usually holds a single record, see [`SmallRecords`].
+    pub fn parse_small<F>(
+        cursor: &mut Cursor<&[u8]>,
+        mut parse_record: F,
+    ) -> Result<SmallRecords<T>>
+    where
+        F: FnMut(&mut Cursor<&[u8]>) -> Result<T>,
+    {
+        Self::parse_small_dyn_size(cursor, |cursor, _| parse_record(cursor))
+    }
+
+    pub fn parse_small_dyn_size<F>(
+        cursor: &mut Cursor<&[u8]>,
+        parse_record: F,
+    ) -> Result<SmallRecords<T>>
+    where
+        F: FnMut(&mut Cursor<&[u8]>, usize) -> Result<T>,
+    {
+        let (_, _, _, records) = parse_records(cursor, parse_record)?;
+        Ok(records)
+    }
+
     pub fn into_inner(self) -> Vec<T> {
         self.records
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [Certificate: appendix 2.41.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18396)
 pub struct CertificateGen2(pub Vec<u8>);
 impl CertificateGen2 {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let mut value = vec![0u8; size];
-        cursor
-            .read_exact(&mut value)
-            .context("Failed to read value")?;
+        let value = read_signature_bytes(cursor, size).context("Failed to read value")?;
         Ok(CertificateGen2(value))
     }
 }
@@ -243,7 +286,7 @@ pub type MemberStateCertificateGen2 = CertificateGen2;
 /// [VuCertificate: appendix 2.181.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26086)
 pub type VuCertificateGen2 = CertificateGen2;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum EquipmentTypeGen2 {
     Reserved,
@@ -266,7 +309,8 @@ pub enum EquipmentTypeGen2 {
     DriverCardSign,
     WorkshopCardSign,
     VehicleUnitSign,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
 }
 impl EquipmentTypeGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -292,12 +336,12 @@ impl EquipmentTypeGen2 {
             17 => Ok(EquipmentTypeGen2::DriverCardSign),
             18 => Ok(EquipmentTypeGen2::WorkshopCardSign),
             19 => Ok(EquipmentTypeGen2::VehicleUnitSign),
-            20..=255 => Ok(EquipmentTypeGen2::RFU),
+            20..=255 => Ok(EquipmentTypeGen2::RFU(equipment_type)),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [FullCardNumber: appendix 2.73.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21400)
@@ -313,9 +357,10 @@ impl FullCardNumberGen2 {
 
         let card_number = match card_type {
             EquipmentTypeGen2::DriverCard => CardNumber::parse_driver(cursor)?,
-            EquipmentTypeGen2::WorkshopCard
-            | EquipmentTypeGen2::ControlCard
-            | EquipmentTypeGen2::CompanyCard => CardNumber::parse_owner(cursor)?,
+            EquipmentTypeGen2::WorkshopCard => CardNumber::parse_owner(cursor)?,
+            EquipmentTypeGen2::ControlCard | EquipmentTypeGen2::CompanyCard => {
+                CardNumber::parse_owner_tolerant(cursor)?
+            }
             _ => CardNumber::parse_unknown(cursor)?,
         };
 
@@ -327,7 +372,7 @@ impl FullCardNumberGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [Generation: appendix 2.75.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23342)
 pub enum GenerationGen2 {
@@ -349,7 +394,7 @@ impl GenerationGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [FullCardNumberAndGeneration: appendix 2.74.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21438)
@@ -358,27 +403,20 @@ pub struct FullCardNumberAndGenerationGen2 {
     pub generation: GenerationGen2,
 }
 impl FullCardNumberAndGenerationGen2 {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Option<Self> {
-        let full_card_number = match FullCardNumberGen2::parse(cursor) {
-            Ok(number) => number,
-            Err(_) => return None,
-        };
-        let generation = match GenerationGen2::parse(cursor) {
-            Ok(gen) => gen,
-            Err(_) => return None,
-        };
-        let value = match generation {
-            GenerationGen2::RFU => None,
-            _ => Some(FullCardNumberAndGenerationGen2 {
-                full_card_number,
-                generation,
-            }),
-        };
-        value
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let full_card_number = FullCardNumberGen2::parse(cursor)?;
+        let generation = GenerationGen2::parse(cursor)?;
+        if generation == GenerationGen2::RFU {
+            anyhow::bail!("RFU value found in FullCardNumberAndGeneration");
+        }
+        Ok(FullCardNumberAndGenerationGen2 {
+            full_card_number,
+            generation,
+        })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ControlType: appendix 2.53.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19148)
@@ -403,9 +441,18 @@ impl ControlTypeGen2 {
             roadside_calibration_checking: bits.4 == 1,
         })
     }
+
+    /// Encodes the control type into its 'cvpdrxxx'B byte, the inverse of [`Self::parse`].
+    pub fn encode(&self) -> u8 {
+        (self.card_downloading as u8) << 7
+            | (self.vu_downloading as u8) << 6
+            | (self.printing as u8) << 5
+            | (self.display as u8) << 4
+            | (self.roadside_calibration_checking as u8) << 3
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [Signature: appendix 2.149.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24501)
 pub struct SignatureGen2(pub Vec<u8>);
@@ -414,15 +461,13 @@ impl SignatureGen2 {
         if size < 64 || size > 132 {
             anyhow::bail!("expected signature size to be 64..132 bytes, got {}", size);
         }
-        let mut signature_buffer = vec![0u8; size];
-        cursor
-            .read_exact(&mut signature_buffer)
-            .context("Failed to read signature buffer")?;
+        let signature_buffer =
+            read_signature_bytes(cursor, size).context("Failed to read signature buffer")?;
         Ok(SignatureGen2(signature_buffer))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PreviousVehicleInfo: appendix 2.118.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23250)
@@ -434,7 +479,7 @@ pub struct PreviousVehicleInfoGen2 {
 impl PreviousVehicleInfoGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let vehicle_registration_identification = VehicleRegistrationIdentification::parse(cursor)?;
-        let card_withdrawal_time = TimeReal::parse(cursor).ok();
+        let card_withdrawal_time = parse_optional(cursor, "card_withdrawal_time", TimeReal::parse);
         let vu_generation = GenerationGen2::parse(cursor)?;
         Ok(PreviousVehicleInfoGen2 {
             vehicle_registration_identification,
@@ -444,7 +489,7 @@ impl PreviousVehicleInfoGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSPlaceRecord: appendix 2.80.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21772)
@@ -468,29 +513,63 @@ impl GNSSPlaceRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSAccuracy: appendix 2.77.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21573)
-pub struct GnssAccuracyGen2(pub u8);
+/// The stored value is the HDOP of the fix multiplied by 10, see appendix 12.
+pub struct GnssAccuracyGen2 {
+    /// Raw value as stored, in the range 0 to 100.
+    pub value: u8,
+    /// Horizontal dilution of precision, `value / 10`.
+    pub hdop: f64,
+    /// Rough horizontal error in meters, the HDOP multiplied by a nominal range error of
+    /// [`GnssAccuracyGen2::NOMINAL_RANGE_ERROR_METERS`].
+    pub estimated_error_meters: f64,
+}
 impl GnssAccuracyGen2 {
+    /// User equivalent range error assumed for a typical single frequency receiver.
+    pub const NOMINAL_RANGE_ERROR_METERS: f64 = 5.0;
+
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let value = cursor.read_u8().context("Failed to read GNSSAccuracy")?;
         if value > 100 {
             anyhow::bail!("Invalid GNSSAccuracy");
         }
-        Ok(GnssAccuracyGen2(value))
+        Ok(GnssAccuracyGen2::from_raw(value))
+    }
+
+    pub fn from_raw(value: u8) -> Self {
+        let hdop = value as f64 / 10.0;
+        GnssAccuracyGen2 {
+            value,
+            hdop,
+            estimated_error_meters: hdop * Self::NOMINAL_RANGE_ERROR_METERS,
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GeoCoordinates: appendix 2.76.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21534)
+/// Both values are stored as ±DDDMM.M multiplied by 10, so they are precise to a tenth of an arc minute.
 pub struct GeoCoordinatesGen2 {
-    pub latitude: f64,
-    pub longitude: f64,
+    /// Decimal degrees, `None` when the position is unknown or the raw value is invalid.
+    pub latitude: Option<f64>,
+    /// Decimal degrees, `None` when the position is unknown or the raw value is invalid.
+    pub longitude: Option<f64>,
+    /// Decoded degrees are exact to ± this value.
+    pub precision_degrees: f64,
+    pub raw_latitude: i32,
+    pub raw_longitude: i32,
 }
 impl GeoCoordinatesGen2 {
+    /// Value stored in both coordinates when the position is unknown.
+    pub const UNKNOWN_POSITION: i32 = 0x7FFFFF;
+    /// Half of the resolution of a tenth of an arc minute.
+    pub const PRECISION_DEGREES: f64 = 1.0 / 1200.0;
+
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let mut lat_buffer = [0u8; 3];
         cursor
@@ -502,37 +581,95 @@ impl GeoCoordinatesGen2 {
             .read_exact(&mut lon_buffer)
             .context("Failed to read longitude for GeoCoordinates")?;
 
-        let latitude = Self::decode_coordinate(&lat_buffer);
-        let longitude = Self::decode_coordinate(&lon_buffer);
-
-        Ok(GeoCoordinatesGen2 {
+        Ok(GeoCoordinatesGen2::from_raw(
+            Self::raw_coordinate(&lat_buffer),
+            Self::raw_coordinate(&lon_buffer),
+        ))
+    }
+
+    pub fn from_raw(raw_latitude: i32, raw_longitude: i32) -> Self {
+        let unknown =
+            raw_latitude == Self::UNKNOWN_POSITION || raw_longitude == Self::UNKNOWN_POSITION;
+        let (latitude, longitude) = if unknown {
+            (None, None)
+        } else {
+            (
+                Self::decode_coordinate(raw_latitude, 90),
+                Self::decode_coordinate(raw_longitude, 180),
+            )
+        };
+        GeoCoordinatesGen2 {
             latitude,
             longitude,
-        })
+            precision_degrees: Self::PRECISION_DEGREES,
+            raw_latitude,
+            raw_longitude,
+        }
     }
 
-    fn decode_coordinate(buffer: &[u8; 3]) -> f64 {
-        let fill_byte = if (buffer[0] & 0x80) > 0 { 0xff } else { 0x00 };
-        let value = i32::from_be_bytes([fill_byte, buffer[0], buffer[1], buffer[2]]);
-
-        // Convert from DDDMM.M * 10 format to decimal degrees
-        let str_value = format!("{:+07}", value);
-        if str_value.len() == 7 {
-            if let (Ok(deg), Ok(min_ten)) = (
-                str_value[0..4].parse::<i32>(),
-                str_value[4..7].parse::<i32>(),
-            ) {
-                let result = f64::from(deg) + (f64::from(min_ten) / 10.0) / 60.0;
-                return (result * 10000.0).trunc() / 10000.0;
-            }
+    /// Encodes the raw latitude and longitude into their 6 bytes, the inverse of [`Self::parse`].
+    pub fn encode(&self) -> [u8; 6] {
+        let latitude = Self::encode_raw_coordinate(self.raw_latitude);
+        let longitude = Self::encode_raw_coordinate(self.raw_longitude);
+        [
+            latitude[0],
+            latitude[1],
+            latitude[2],
+            longitude[0],
+            longitude[1],
+            longitude[2],
+        ]
+    }
+
+    /// Sign extends a 24 bit big endian two's complement value.
+    pub fn raw_coordinate(buffer: &[u8; 3]) -> i32 {
+        i32::from_be_bytes([buffer[0], buffer[1], buffer[2], 0]) >> 8
+    }
+
+    /// Truncates a raw value to its 24 bit big endian two's complement form, the inverse of
+    /// [`Self::raw_coordinate`].
+    pub fn encode_raw_coordinate(raw: i32) -> [u8; 3] {
+        debug_assert!(
+            (-(1 << 23)..1 << 23).contains(&raw),
+            "raw coordinate {} does not fit in 24 bits",
+            raw
+        );
+        let bytes = raw.to_be_bytes();
+        [bytes[1], bytes[2], bytes[3]]
+    }
+
+    /// Converts decimal degrees into a raw ±DDDMM.M * 10 value, rounded to the nearest tenth of
+    /// an arc minute, the inverse of [`Self::decode_coordinate`].
+    pub fn encode_coordinate(value: f64) -> i32 {
+        let total_tenths = (value.abs() * 600.0).round() as i32;
+        let raw = total_tenths / 600 * 1000 + total_tenths % 600;
+        if value < 0.0 {
+            -raw
+        } else {
+            raw
         }
+    }
 
-        // Return 0.0 if parsing fails
-        0.0
+    /// Converts a raw ±DDDMM.M * 10 value into decimal degrees.
+    /// Returns `None` for minutes of 60 or more and for values beyond `max_degrees`.
+    pub fn decode_coordinate(raw: i32, max_degrees: i32) -> Option<f64> {
+        let magnitude = raw.unsigned_abs();
+        let degrees = magnitude / 1000;
+        let tenths_of_minutes = magnitude % 1000;
+        if tenths_of_minutes >= 600 {
+            return None;
+        }
+        // tenths of minutes since 0 degrees, 600 per degree
+        let total_tenths = degrees * 600 + tenths_of_minutes;
+        if total_tenths > max_degrees.unsigned_abs() * 600 {
+            return None;
+        }
+        let value = total_tenths as f64 / 600.0;
+        Some(if raw < 0 { -value } else { value })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuGNSSADRecord: appendix 2.203.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27345)
@@ -546,12 +683,16 @@ pub struct VuGNSSADRecordGen2 {
 impl VuGNSSADRecordGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let time_stamp = TimeReal::parse(cursor)?;
-        let card_number_and_gen_driver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_driver_slot")
-            .ok();
-        let card_number_and_gen_codriver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_codriver_slot")
-            .ok();
+        let card_number_and_gen_driver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_driver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
+        let card_number_and_gen_codriver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_codriver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let gnss_place_record = GNSSPlaceRecordGen2::parse(cursor)?;
         let vehicle_odometer_value = OdometerShort::parse(cursor)?;
 
@@ -564,7 +705,7 @@ impl VuGNSSADRecordGen2 {
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EntryTypeDailyWorkPeriod: appendix 2.66.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20044)
 pub enum EntryTypeDailyWorkPeriodGen2 {
@@ -574,28 +715,25 @@ pub enum EntryTypeDailyWorkPeriodGen2 {
     EndRelatedTimeManuallyEntered,
 }
 
-impl EntryTypeDailyWorkPeriodGen2 {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let value = cursor
-            .read_u8()
-            .context("Failed to read EntryTypeDailyWorkPeriod")?;
+impl FromByte for EntryTypeDailyWorkPeriodGen2 {
+    fn from_byte(value: u8) -> Option<Self> {
         match value {
-            0x00 => Ok(Self::BeginRelatedTimeCardInsertionTimeOrTimeOfEntry),
-            0x01 => Ok(Self::EndRelatedTimeCardWithdrawalTimeOrTimeOfEntry),
-            0x02 => Ok(Self::BeginRelatedTimeManuallyEntered),
-            0x03 => Ok(Self::EndRelatedTimeManuallyEntered),
-            _ => anyhow::bail!("Invalid EntryTypeDailyWorkPeriod"),
+            0x00 => Some(Self::BeginRelatedTimeCardInsertionTimeOrTimeOfEntry),
+            0x01 => Some(Self::EndRelatedTimeCardWithdrawalTimeOrTimeOfEntry),
+            0x02 => Some(Self::BeginRelatedTimeManuallyEntered),
+            0x03 => Some(Self::EndRelatedTimeManuallyEntered),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PlaceRecord: appendix 2.117.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23112)
 pub struct PlaceRecordGen2 {
     pub entry_time: TimeReal,
-    pub entry_type_daily_work_period: EntryTypeDailyWorkPeriodGen2,
+    pub entry_type_daily_work_period: Coded<EntryTypeDailyWorkPeriodGen2>,
     pub daily_work_period_country: external::NationNumeric,
     pub daily_work_period_region: external::RegionNumeric,
     pub vehicle_odometer_value: OdometerShort,
@@ -604,10 +742,11 @@ pub struct PlaceRecordGen2 {
 impl PlaceRecordGen2 {
     const SIZE: usize = 21;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let entry_time = TimeReal::parse(inner_cursor)?;
-        let entry_type_daily_work_period = EntryTypeDailyWorkPeriodGen2::parse(inner_cursor)?;
+        let entry_type_daily_work_period =
+            Coded::<EntryTypeDailyWorkPeriodGen2>::parse(inner_cursor)?;
         let daily_work_period_country = external::NationNumeric::parse(inner_cursor)?;
         let daily_work_period_region = external::RegionNumeric::parse(inner_cursor)?;
         let vehicle_odometer_value = OdometerShort::parse(inner_cursor)?;
@@ -626,11 +765,12 @@ impl PlaceRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditionType: appendix 2.154.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24685)
 pub enum SpecificConditionTypeGen2 {
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     OutOfScopeBegin,
     OutOfScopeEnd,
     FerryTrainCrossingBegin,
@@ -643,17 +783,17 @@ impl SpecificConditionTypeGen2 {
             .read_u8()
             .context("Failed to read value for SpecificConditionType")?;
         match value {
-            0x0 => Ok(Self::RFU),
+            0x0 => Ok(Self::RFU(value)),
             0x1 => Ok(Self::OutOfScopeBegin),
             0x2 => Ok(Self::OutOfScopeEnd),
             0x3 => Ok(Self::FerryTrainCrossingBegin),
             0x4 => Ok(Self::FerryTrainCrossingEnd),
-            0x5..=0xFF => Ok(Self::RFU),
+            0x5..=0xFF => Ok(Self::RFU(value)),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditionRecord: appendix 2.152.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24614)
@@ -664,7 +804,7 @@ pub struct SpecificConditionRecordGen2 {
 impl SpecificConditionRecordGen2 {
     const SIZE: usize = 5;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let entry_time = TimeReal::parse(inner_cursor)?;
         let specific_condition_type = SpecificConditionTypeGen2::parse(inner_cursor)?;
@@ -675,7 +815,7 @@ impl SpecificConditionRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EventFaultType: appendix 2.70.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20338)
 pub enum EventFaultTypeGen2 {
@@ -723,7 +863,8 @@ pub enum EventFaultTypeGen2 {
     RemoteCommunicationFacility,
     ITSInterface,
     CardFaultNoFurtherDetails,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 
@@ -749,7 +890,7 @@ impl EventFaultTypeGen2 {
             0x0C => Ok(Self::CommunicationErrorWithRemoteCommunicationFacility),
             0x0D => Ok(Self::AbsenceOfPositionInfoFromGNSSReceiver),
             0x0E => Ok(Self::CommunicationErrorWithExternalGNSSFacility),
-            0x0F => Ok(Self::RFU),
+            0x0F => Ok(Self::RFU(value)),
 
             // Vehicle unit related security breach attempt events,
             0x10 => Ok(Self::VUSecurityBreachAttemptNoFurtherDetails),
@@ -764,7 +905,7 @@ impl EventFaultTypeGen2 {
             0x19 => Ok(Self::TamperDetectionOfGNSS),
             0x1A => Ok(Self::ExternalGNSSFacilityAuthenticationFailure),
             0x1B => Ok(Self::ExternalGNSSFacilityCertificateExpired),
-            0x1C..=0x1F => Ok(Self::RFU),
+            0x1C..=0x1F => Ok(Self::RFU(value)),
 
             // Sensor related security breach attempt events,
             0x20 => Ok(Self::SensorSecurityBreachAttemptNoFurtherDetails),
@@ -773,7 +914,7 @@ impl EventFaultTypeGen2 {
             0x23 => Ok(Self::SensorInternalDataTransferError),
             0x24 => Ok(Self::SensorUnauthorizedCaseOpening),
             0x25 => Ok(Self::SensorHardwareSabotage),
-            0x26..=0x2F => Ok(Self::RFU),
+            0x26..=0x2F => Ok(Self::RFU(value)),
 
             // Recording equipment faults,
             0x30 => Ok(Self::ControlDeviceFaultNoFurtherDetails),
@@ -786,14 +927,14 @@ impl EventFaultTypeGen2 {
             0x37 => Ok(Self::ExternalGNSSFacility),
             0x38 => Ok(Self::RemoteCommunicationFacility),
             0x39 => Ok(Self::ITSInterface),
-            0x3A..=0x3F => Ok(Self::RFU),
+            0x3A..=0x3F => Ok(Self::RFU(value)),
 
             // Card faults,
             0x40 => Ok(Self::CardFaultNoFurtherDetails),
-            0x41..=0x4F => Ok(Self::RFU),
+            0x41..=0x4F => Ok(Self::RFU(value)),
 
             // Reserved for future use,
-            0x50..=0x7F => Ok(Self::RFU),
+            0x50..=0x7F => Ok(Self::RFU(value)),
 
             // Manufacturer specific,
             0x80..=0xFF => Ok(Self::ManufacturerSpecific),
@@ -801,7 +942,7 @@ impl EventFaultTypeGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ManufacturerSpecificEventFaultData: appendix 2.95.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22276)
@@ -829,7 +970,7 @@ impl ManufacturerSpecificEventFaultDataGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ExtendedSerialNumber: appendix 2.72.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21307)
@@ -856,12 +997,23 @@ impl ExtendedSerialNumberGen2 {
             manufacturer_code,
         })
     }
+
+    /// Same format as [`gen1::ExtendedSerialNumber::to_serial_string`], so serials of both generations compare equal.
+    pub fn to_serial_string(&self) -> String {
+        format!(
+            "{} {:02}/{:02} {}",
+            self.serial_number,
+            self.month_year.month,
+            self.month_year.year,
+            self.manufacturer_code.name
+        )
+    }
 }
 
 /// [VuSerialNumber: appendix 2.223.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28497)
 pub type VuSerialNumberGen2 = ExtendedSerialNumberGen2;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuApprovalNumber: appendix 2.172.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25427)
 pub struct VuApprovalNumberGen2(pub IA5String);
@@ -873,7 +1025,7 @@ impl VuApprovalNumberGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuAbility: appendix 2.169.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25277)
 pub enum VuAbilityGen2 {
@@ -897,7 +1049,7 @@ impl VuAbilityGen2 {
 /// [SensorSerialNumber: appendix 2.148.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24483)
 pub type SensorSerialNumberGen2 = ExtendedSerialNumberGen2;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SensorApprovalNumber: appendix 2.131.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23887)
 pub struct SensorApprovalNumberGen2(pub IA5String);
@@ -912,7 +1064,7 @@ impl SensorApprovalNumberGen2 {
 /// [SensorGNSSSerialNumber: appendix 2.139.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24175)
 pub type SensorGnssSerialNumberGen2 = ExtendedSerialNumberGen2;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SensorExternalGNSSApprovalNumber: appendix 2.132.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23931)
 pub struct SensorExternalGnssApprovalNumberGen2(pub IA5String);
@@ -925,7 +1077,7 @@ impl SensorExternalGnssApprovalNumberGen2 {
 }
 
 pub type SensorGnssCouplingDateGen2 = TimeReal;
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CalibrationPurpose: appendix 2.8.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16597)
 pub enum CalibrationPurposeGen2 {
@@ -936,7 +1088,8 @@ pub enum CalibrationPurposeGen2 {
     PeriodicInspection,
     EntryOfVRNByCompany,
     TimeAdjustmentWithoutCalibration,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 
@@ -953,14 +1106,14 @@ impl CalibrationPurposeGen2 {
             0x04 => CalibrationPurposeGen2::PeriodicInspection,
             0x05 => CalibrationPurposeGen2::EntryOfVRNByCompany,
             0x06 => CalibrationPurposeGen2::TimeAdjustmentWithoutCalibration,
-            0x07..=0x7F => CalibrationPurposeGen2::RFU,
+            0x07..=0x7F => CalibrationPurposeGen2::RFU(value),
             0x80..=0xFF => CalibrationPurposeGen2::ManufacturerSpecific,
         };
         Ok(purpose)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ExtendedSealIdentifier: appendix 2.71.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21276)
@@ -983,7 +1136,7 @@ impl ExtendedSealIdentifierGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SealRecord: appendix 2.130.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23854)
@@ -1002,7 +1155,7 @@ impl SealRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SealDataVu: appendix 2.129.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23827)
@@ -1013,14 +1166,14 @@ pub struct SealDataVuGen2 {
 impl SealDataVuGen2 {
     const NO_OF_RECORDS: usize = 5;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::NO_OF_RECORDS * SealRecordGen2::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::NO_OF_RECORDS * SealRecordGen2::SIZE)?;
 
         let mut seal_records = Vec::with_capacity(Self::NO_OF_RECORDS);
         for _ in 0..Self::NO_OF_RECORDS {
             let seal = SealRecordGen2::parse(inner_cursor)?;
             // do not include unused or RFU seals
             match seal.equipment_type {
-                EquipmentTypeGen2::Unused | EquipmentTypeGen2::RFU => continue,
+                EquipmentTypeGen2::Unused | EquipmentTypeGen2::RFU(_) => continue,
                 _ => seal_records.push(seal),
             }
         }
@@ -1043,7 +1196,7 @@ pub type NoOfSpecificConditionRecordsGen2 = u16;
 /// [NoOfCardVehicleUnitRecords: appendix 2.106.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22635)
 pub type NoOfCardVehicleUnitRecordsGen2 = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [DriverCardApplicationIdentification: appendix 2.61.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19751)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1122,23 +1275,205 @@ impl DriverCardApplicationIdentificationGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// [NoOfCalibrationRecords: appendix 2.103.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22543)
+pub type NoOfCalibrationRecordsGen2 = u8;
+/// [NoOfControlActivityRecords: appendix 2.108.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22683)
+pub type NoOfControlActivityRecordsGen2 = u16;
+/// [NoOfCompanyActivityRecords: appendix 2.107.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22660)
+pub type NoOfCompanyActivityRecordsGen2 = u16;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
+/// [WorkshopCardApplicationIdentification: appendix 2.234.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+pub struct WorkshopCardApplicationIdentificationGen2 {
+    pub type_of_tachograph_card_id: EquipmentTypeGen2,
+    pub card_structure_version: CardStructureVersion,
+    pub no_of_events_per_type: NoOfEventsPerTypeGen2,
+    pub no_of_faults_per_type: NoOfFaultsPerTypeGen2,
+    pub activity_structure_length: CardActivityLengthRange,
+    pub no_of_card_vehicle_records: NoOfCardVehicleRecordsGen2,
+    pub no_of_card_place_records: NoOfCardPlaceRecordsGen2,
+    pub no_of_calibration_records: NoOfCalibrationRecordsGen2,
+    pub no_of_gnss_ad_records: NoOfGnssAdRecordsGen2,
+    pub no_of_specific_condition_records: NoOfSpecificConditionRecordsGen2,
+    pub no_of_card_vehicle_unit_records: NoOfCardVehicleUnitRecordsGen2,
+}
+
+impl WorkshopCardApplicationIdentificationGen2 {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let type_of_tachograph_card_id = EquipmentTypeGen2::parse(cursor)?;
+        let card_structure_version = CardStructureVersion::parse(cursor)?;
+        let no_of_events_per_type = cursor
+            .read_u8()
+            .context("Failed to read no_of_events_per_type")?;
+        let no_of_faults_per_type = cursor
+            .read_u8()
+            .context("Failed to read no_of_faults_per_type")?;
+        let activity_structure_length = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read activity_structure_length")?;
+        let no_of_card_vehicle_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_card_vehicle_records")?;
+        let no_of_card_place_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_card_place_records")?;
+        let no_of_calibration_records = cursor
+            .read_u8()
+            .context("Failed to read no_of_calibration_records")?;
+        let no_of_gnss_ad_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_gnss_ad_records")?;
+        let no_of_specific_condition_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_specific_condition_records")?;
+        let no_of_card_vehicle_unit_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_card_vehicle_unit_records")?;
+
+        Ok(WorkshopCardApplicationIdentificationGen2 {
+            type_of_tachograph_card_id,
+            card_structure_version,
+            no_of_events_per_type,
+            no_of_faults_per_type,
+            activity_structure_length,
+            no_of_card_vehicle_records,
+            no_of_card_place_records,
+            no_of_calibration_records,
+            no_of_gnss_ad_records,
+            no_of_specific_condition_records,
+            no_of_card_vehicle_unit_records,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
-pub struct ApplicationIdentificationGen2 {
-    pub driver_card_application_identification: DriverCardApplicationIdentificationGen2,
+/// [ControlCardApplicationIdentification: appendix 2.50.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18950)
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct ControlCardApplicationIdentificationGen2 {
+    pub type_of_tachograph_card_id: EquipmentTypeGen2,
+    pub card_structure_version: CardStructureVersion,
+    pub no_of_control_activity_records: NoOfControlActivityRecordsGen2,
 }
-impl ApplicationIdentificationGen2 {
+
+impl ControlCardApplicationIdentificationGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let driver_card_application_identification =
-            DriverCardApplicationIdentificationGen2::parse(cursor)?;
-        Ok(ApplicationIdentificationGen2 {
-            driver_card_application_identification,
+        let type_of_tachograph_card_id = EquipmentTypeGen2::parse(cursor)?;
+        let card_structure_version = CardStructureVersion::parse(cursor)?;
+        let no_of_control_activity_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_control_activity_records")?;
+        Ok(ControlCardApplicationIdentificationGen2 {
+            type_of_tachograph_card_id,
+            card_structure_version,
+            no_of_control_activity_records,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+/// [CompanyCardApplicationIdentification: appendix 2.48.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18846)
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct CompanyCardApplicationIdentificationGen2 {
+    pub type_of_tachograph_card_id: EquipmentTypeGen2,
+    pub card_structure_version: CardStructureVersion,
+    pub no_of_company_activity_records: NoOfCompanyActivityRecordsGen2,
+}
+
+impl CompanyCardApplicationIdentificationGen2 {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let type_of_tachograph_card_id = EquipmentTypeGen2::parse(cursor)?;
+        let card_structure_version = CardStructureVersion::parse(cursor)?;
+        let no_of_company_activity_records = cursor
+            .read_u16::<BigEndian>()
+            .context("Failed to read no_of_company_activity_records")?;
+        Ok(CompanyCardApplicationIdentificationGen2 {
+            type_of_tachograph_card_id,
+            card_structure_version,
+            no_of_company_activity_records,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "type")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum ApplicationIdentificationGen2 {
+    DriverCard(DriverCardApplicationIdentificationGen2),
+    WorkshopCard(WorkshopCardApplicationIdentificationGen2),
+    ControlCard(ControlCardApplicationIdentificationGen2),
+    CompanyCard(CompanyCardApplicationIdentificationGen2),
+}
+impl ApplicationIdentificationGen2 {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let type_of_tachograph_card_id = EquipmentTypeGen2::parse(cursor).context(
+            "Failed to read type_of_tachograph_card_id in ApplicationIdentificationGen2",
+        )?;
+
+        // return back to the previous position (before parsing the type)
+        // so that we can fully parse the inner struct below
+        cursor.set_position(cursor.position() - 1);
+
+        match type_of_tachograph_card_id {
+            EquipmentTypeGen2::DriverCard => Ok(ApplicationIdentificationGen2::DriverCard(
+                DriverCardApplicationIdentificationGen2::parse(cursor)?,
+            )),
+            EquipmentTypeGen2::WorkshopCard => Ok(ApplicationIdentificationGen2::WorkshopCard(
+                WorkshopCardApplicationIdentificationGen2::parse(cursor)?,
+            )),
+            EquipmentTypeGen2::ControlCard => Ok(ApplicationIdentificationGen2::ControlCard(
+                ControlCardApplicationIdentificationGen2::parse(cursor)?,
+            )),
+            EquipmentTypeGen2::CompanyCard => Ok(ApplicationIdentificationGen2::CompanyCard(
+                CompanyCardApplicationIdentificationGen2::parse(cursor)?,
+            )),
+            _ => anyhow::bail!("Invalid ApplicationIdentificationGen2 type"),
+        }
+    }
+    /// Number of records in EF VehiclesUsed, for card types that have it.
+    pub fn no_of_card_vehicle_records(&self) -> Option<usize> {
+        match self {
+            ApplicationIdentificationGen2::DriverCard(app) => {
+                Some(app.no_of_card_vehicle_records as usize)
+            }
+            ApplicationIdentificationGen2::WorkshopCard(app) => {
+                Some(app.no_of_card_vehicle_records as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of records in EF Places, for card types that have it.
+    pub fn no_of_card_place_records(&self) -> Option<usize> {
+        match self {
+            ApplicationIdentificationGen2::DriverCard(app) => {
+                Some(app.no_of_card_place_records as usize)
+            }
+            ApplicationIdentificationGen2::WorkshopCard(app) => {
+                Some(app.no_of_card_place_records as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of records in EF GNSS_Places, for card types that have it.
+    pub fn no_of_gnss_ad_records(&self) -> Option<usize> {
+        match self {
+            ApplicationIdentificationGen2::DriverCard(app) => {
+                Some(app.no_of_gnss_ad_records as usize)
+            }
+            ApplicationIdentificationGen2::WorkshopCard(app) => {
+                Some(app.no_of_gnss_ad_records as usize)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardIccIdentification: appendix 2.23.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17372)
@@ -1175,25 +1510,26 @@ impl CardIccIdentificationGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardEventRecord: appendix 2.20.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17247)
 pub struct CardEventRecordGen2 {
     pub event_type: EventFaultTypeGen2,
     pub event_begin_time: TimeReal,
-    pub event_end_time: TimeReal,
+    /// `None` for a event that had not ended yet, stored with an invalid end time.
+    pub event_end_time: Option<TimeReal>,
     pub event_vehicle_registration: VehicleRegistrationIdentification,
 }
 
 impl CardEventRecordGen2 {
     const SIZE: usize = 24;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let event_type = EventFaultTypeGen2::parse(inner_cursor)?;
         let event_begin_time = TimeReal::parse(inner_cursor)?;
-        let event_end_time = TimeReal::parse(inner_cursor)?;
+        let event_end_time = parse_optional(inner_cursor, "event_end_time", TimeReal::parse);
         let event_vehicle_registration = VehicleRegistrationIdentification::parse(inner_cursor)?;
 
         Ok(CardEventRecordGen2 {
@@ -1205,22 +1541,29 @@ impl CardEventRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Position of a sub-array in [`CardEventDataGen2`]. The card keeps a fixed sub-array per event
+/// type, ordered by ascending event type, with the security breach attempts grouped in the last.
+pub struct EventFaultTypeGroup(pub u8);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardEventData: appendix 2.19.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17180)
 pub struct CardEventDataGen2 {
-    pub records: Vec<Vec<CardEventRecordGen2>>,
+    /// Records of every sub-array that holds any, keyed by the sub-array they were read from.
+    pub per_type: BTreeMap<EventFaultTypeGroup, Vec<CardEventRecordGen2>>,
 }
 impl CardEventDataGen2 {
     const OUTER_RECORDS_AMOUNT: usize = 11;
     const INNER_RECORDS_AMOUNT: usize = 1;
 
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let mut card_event_records = Vec::new();
+        let mut per_type = BTreeMap::new();
         let inner_record_amounts = size / Self::OUTER_RECORDS_AMOUNT / CardEventRecordGen2::SIZE;
 
-        for _ in 0..Self::OUTER_RECORDS_AMOUNT {
+        for group in 0..Self::OUTER_RECORDS_AMOUNT {
             let mut inner_card_event_records = Vec::new();
             for _ in 0..inner_record_amounts {
                 if let Ok(card_event_record) = CardEventRecordGen2::parse(cursor) {
@@ -1228,35 +1571,39 @@ impl CardEventDataGen2 {
                 }
             }
             // Only include the records if there are any
-            if inner_card_event_records.len() > 0 {
-                card_event_records.push(inner_card_event_records);
+            if !inner_card_event_records.is_empty() {
+                per_type.insert(EventFaultTypeGroup(group as u8), inner_card_event_records);
             }
         }
-        Ok(CardEventDataGen2 {
-            records: card_event_records,
-        })
+        Ok(CardEventDataGen2 { per_type })
+    }
+
+    /// Records of all sub-arrays, in storage order.
+    pub fn records(&self) -> impl Iterator<Item = &CardEventRecordGen2> {
+        self.per_type.values().flatten()
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardFaultData: appendix 2.21.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17292)
 pub struct CardFaultRecordGen2 {
     pub fault_type: EventFaultTypeGen2,
     pub fault_begin_time: TimeReal,
-    pub fault_end_time: TimeReal,
+    /// `None` for a fault that had not ended yet, stored with an invalid end time.
+    pub fault_end_time: Option<TimeReal>,
     pub fault_vehicle_registration: VehicleRegistrationIdentification,
 }
 
 impl CardFaultRecordGen2 {
     pub const SIZE: usize = 24;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let fault_type = EventFaultTypeGen2::parse(inner_cursor)?;
         let fault_begin_time = TimeReal::parse(inner_cursor)?;
-        let fault_end_time = TimeReal::parse(inner_cursor)?;
+        let fault_end_time = parse_optional(inner_cursor, "fault_end_time", TimeReal::parse);
         let fault_vehicle_registration = VehicleRegistrationIdentification::parse(inner_cursor)?;
 
         Ok(CardFaultRecordGen2 {
@@ -1268,7 +1615,7 @@ impl CardFaultRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardFaultData: appendix 2.22.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17340)
@@ -1313,7 +1660,7 @@ impl CardFaultDataGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehicleRecord: appendix 2.37.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18163)
@@ -1329,7 +1676,7 @@ pub struct CardVehicleRecordGen2 {
 impl CardVehicleRecordGen2 {
     const SIZE: usize = 48;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         Ok(CardVehicleRecordGen2 {
             vehicle_odometer_begin: OdometerShort::parse(inner_cursor)?,
@@ -1343,7 +1690,7 @@ impl CardVehicleRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehiclesUsed: appendix 2.38.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18302)
@@ -1353,14 +1700,26 @@ pub struct CardVehiclesUsedGen2 {
 }
 impl CardVehiclesUsedGen2 {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        Self::parse_with_no_of_records(cursor, size, None)
+    }
+
+    /// Parses at most `no_of_records` records, as given by the application identification,
+    /// instead of as many as fit in the EF.
+    pub fn parse_with_no_of_records(
+        cursor: &mut Cursor<&[u8]>,
+        size: usize,
+        no_of_records: Option<usize>,
+    ) -> Result<Self> {
+        let cursor = &mut cursor.take_exact(size)?;
         let vehicle_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read vehicle_pointer_newest_record")?;
         let mut card_vehicle_records = Vec::new();
 
-        let amount_of_records = (size - 2) / CardVehicleRecordGen2::SIZE;
-        for _ in 0..amount_of_records {
+        // 2 bytes for the pointer size
+        let fitting_records = (size - 2) / CardVehicleRecordGen2::SIZE;
+        let no_of_records = no_of_records.map_or(fitting_records, |n| n.min(fitting_records));
+        for _ in 0..no_of_records {
             if let Ok(card_vehicle_record) = CardVehicleRecordGen2::parse(cursor) {
                 card_vehicle_records.push(card_vehicle_record);
             } else {
@@ -1375,7 +1734,7 @@ impl CardVehiclesUsedGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardPlaceDailyWorkPeriod: appendix 2.27.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17729)
@@ -1385,15 +1744,27 @@ pub struct CardPlaceDailyWorkPeriodGen2 {
 }
 impl CardPlaceDailyWorkPeriodGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        Self::parse_with_no_of_records(cursor, size, None)
+    }
+
+    /// Parses at most `no_of_records` records, as given by the application identification,
+    /// instead of as many as fit in the EF.
+    pub fn parse_with_no_of_records(
+        cursor: &mut Cursor<&[u8]>,
+        size: usize,
+        no_of_records: Option<usize>,
+    ) -> Result<Self> {
+        let cursor = &mut cursor.take_exact(size)?;
         let place_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read place_pointer_newest_record")?;
 
         let mut place_records = Vec::new();
-        let amount_of_records = (size - 2) / PlaceRecordGen2::SIZE;
+        // 2 bytes for the pointer size
+        let fitting_records = (size - 2) / PlaceRecordGen2::SIZE;
+        let no_of_records = no_of_records.map_or(fitting_records, |n| n.min(fitting_records));
 
-        for _ in 0..amount_of_records {
+        for _ in 0..no_of_records {
             if let Ok(place_record) = PlaceRecordGen2::parse(cursor) {
                 place_records.push(place_record);
             } else {
@@ -1401,7 +1772,7 @@ impl CardPlaceDailyWorkPeriodGen2 {
             }
         }
         // Sort the records by entry_time in ascending order
-        place_records.sort_by(|a, b| a.entry_time.0.timestamp().cmp(&b.entry_time.0.timestamp()));
+        sort_by_timestamp(&mut place_records);
         Ok(CardPlaceDailyWorkPeriodGen2 {
             place_pointer_newest_record,
             place_records,
@@ -1409,7 +1780,7 @@ impl CardPlaceDailyWorkPeriodGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardControlActivityDataRecord appendix 2.15.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17002)
@@ -1424,21 +1795,36 @@ pub struct CardControlActivityDataRecordGen2 {
 impl CardControlActivityDataRecordGen2 {
     const SIZE: usize = 46;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         Ok(Self {
             control_type: ControlTypeGen2::parse(inner_cursor)?,
-            control_time: TimeReal::parse(inner_cursor).ok(),
-            control_card_number: FullCardNumberGen2::parse(inner_cursor).ok(),
-            control_vehicle_registration: VehicleRegistrationIdentification::parse(inner_cursor)
-                .ok(),
-            control_download_period_begin: TimeReal::parse(inner_cursor).ok(),
-            control_download_period_end: TimeReal::parse(inner_cursor).ok(),
+            control_time: parse_optional(inner_cursor, "control_time", TimeReal::parse),
+            control_card_number: parse_optional(
+                inner_cursor,
+                "control_card_number",
+                FullCardNumberGen2::parse,
+            ),
+            control_vehicle_registration: parse_optional(
+                inner_cursor,
+                "control_vehicle_registration",
+                VehicleRegistrationIdentification::parse,
+            ),
+            control_download_period_begin: parse_optional(
+                inner_cursor,
+                "control_download_period_begin",
+                TimeReal::parse,
+            ),
+            control_download_period_end: parse_optional(
+                inner_cursor,
+                "control_download_period_end",
+                TimeReal::parse,
+            ),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [SpecificConditions: appendix 2.153.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24644)
@@ -1448,7 +1834,7 @@ pub struct SpecificConditionsGen2 {
 }
 impl SpecificConditionsGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
         let condition_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read condition_pointer_newest_record")?;
@@ -1463,8 +1849,7 @@ impl SpecificConditionsGen2 {
             }
         }
         // Sort the records by time_stamp in desc order
-        specific_condition_records
-            .sort_by(|a, b| b.entry_time.0.timestamp().cmp(&a.entry_time.0.timestamp()));
+        specific_condition_records.sort_by(|a, b| b.entry_time.cmp(&a.entry_time));
         Ok(SpecificConditionsGen2 {
             condition_pointer_newest_record,
             specific_condition_records,
@@ -1472,7 +1857,7 @@ impl SpecificConditionsGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehicleUnitRecord: appendix 2.39.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18302)
@@ -1485,7 +1870,7 @@ pub struct CardVehicleUnitRecordGen2 {
 impl CardVehicleUnitRecordGen2 {
     const SIZE: usize = 10;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let time_stamp = TimeReal::parse(inner_cursor)?;
         let manufacturer_code = external::ManufacturerCode::parse(inner_cursor)?;
@@ -1507,7 +1892,7 @@ impl CardVehicleUnitRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardVehicleUnitsUsed: appendix 2.40.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18350)
@@ -1529,7 +1914,7 @@ impl CardVehicleUnitsUsedGen2 {
             }
         }
         // Sort the records by time_stamp in desc order
-        vehicle_units.sort_by(|a, b| b.time_stamp.0.timestamp().cmp(&a.time_stamp.0.timestamp()));
+        vehicle_units.sort_by(|a, b| b.time_stamp.cmp(&a.time_stamp));
         Ok(CardVehicleUnitsUsedGen2 {
             no_of_card_vehicle_unit_records,
             card_vehicle_unit_records: vehicle_units,
@@ -1537,7 +1922,7 @@ impl CardVehicleUnitsUsedGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSAccumulatedDrivingRecord: appendix 2.79.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21640)
@@ -1567,7 +1952,7 @@ impl GNSSAccumulatedDrivingRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSAccumulatedDriving: appendix 2.79.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21595)
@@ -1577,13 +1962,25 @@ pub struct GnssAccumulatedDrivingGen2 {
 }
 impl GnssAccumulatedDrivingGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(size);
+        Self::parse_with_no_of_records(cursor, size, None)
+    }
+
+    /// Parses at most `no_of_records` records, as given by the application identification,
+    /// instead of as many as fit in the EF.
+    pub fn parse_with_no_of_records(
+        cursor: &mut Cursor<&[u8]>,
+        size: usize,
+        no_of_records: Option<usize>,
+    ) -> Result<Self> {
+        let inner_cursor = &mut cursor.take_exact(size)?;
         let gnss_ad_pointer_newest_record = inner_cursor
             .read_u16::<BigEndian>()
             .context("Failed to read gnss_ad_pointer_newest_record")?;
 
         let mut gnss_accumulated_driving_records = Vec::new();
-        let no_of_records = (size - 2) / GNSSAccumulatedDrivingRecordGen2::SIZE;
+        // 2 bytes for the pointer size
+        let fitting_records = (size - 2) / GNSSAccumulatedDrivingRecordGen2::SIZE;
+        let no_of_records = no_of_records.map_or(fitting_records, |n| n.min(fitting_records));
         for _ in 0..no_of_records {
             if let Ok(gnss_accumulated_driving_record) =
                 GNSSAccumulatedDrivingRecordGen2::parse(inner_cursor)
@@ -1594,8 +1991,7 @@ impl GnssAccumulatedDrivingGen2 {
             }
         }
         // Sort the records by time_stamp in ascending order
-        gnss_accumulated_driving_records
-            .sort_by(|a, b| a.time_stamp.0.timestamp().cmp(&b.time_stamp.0.timestamp()));
+        sort_by_timestamp(&mut gnss_accumulated_driving_records);
         Ok(GnssAccumulatedDrivingGen2 {
             gnss_ad_pointer_newest_record,
             gnss_accumulated_driving_records,
@@ -1603,7 +1999,7 @@ impl GnssAccumulatedDrivingGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct DateOfDayDownloadedGen2(pub TimeReal);
 
@@ -1615,7 +2011,7 @@ impl DateOfDayDownloadedGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuCardIwRecordGen2 {
@@ -1624,11 +2020,11 @@ pub struct VuCardIwRecordGen2 {
     pub card_expiry_date: TimeReal,
     pub card_insertion_date: TimeReal,
     pub vehicle_odometer_value_at_insertion: OdometerShort,
-    pub card_slot_number: CardSlotNumber,
+    pub card_slot_number: Coded<CardSlotNumber>,
     pub card_withdrawl_time: Option<TimeReal>,
     pub vehicle_odometer_value_at_withdrawal: OdometerShort,
     pub previous_vehicle_info: Option<PreviousVehicleInfoGen2>,
-    pub manual_input_flag: Option<ManualInputFlag>,
+    pub manual_input_flag: Option<Coded<ManualInputFlag>>,
 }
 
 impl VuCardIwRecordGen2 {
@@ -1644,22 +2040,22 @@ impl VuCardIwRecordGen2 {
                 .context("Failed to parse card_insertion_date")?,
             vehicle_odometer_value_at_insertion: OdometerShort::parse(cursor)
                 .context("Failed to parse vehicle_odometer_value_at_insertion")?,
-            card_slot_number: CardSlotNumber::parse(cursor)
+            card_slot_number: Coded::<CardSlotNumber>::parse(cursor)
                 .context("Failed to parse card_slot_number")?,
-            card_withdrawl_time: TimeReal::parse(cursor).ok(),
+            card_withdrawl_time: parse_optional(cursor, "card_withdrawl_time", TimeReal::parse),
             vehicle_odometer_value_at_withdrawal: OdometerShort::parse(cursor)
                 .context("Failed to parse vehicle_odometer_value_at_withdrawal")?,
             previous_vehicle_info: PreviousVehicleInfoGen2::parse(cursor)
                 .context("Failed to parse previous_vehicle_info")
                 .ok(),
-            manual_input_flag: ManualInputFlag::parse(cursor)
+            manual_input_flag: Coded::<ManualInputFlag>::parse(cursor)
                 .context("Failed to parse manual_input_flag")
                 .ok(),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuPlaceDailyWorkPeriodRecordGen2 {
@@ -1670,54 +2066,58 @@ pub struct VuPlaceDailyWorkPeriodRecordGen2 {
 impl VuPlaceDailyWorkPeriodRecordGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuPlaceDailyWorkPeriodRecordGen2 {
-            full_card_number_and_generation: FullCardNumberAndGenerationGen2::parse(cursor),
+            full_card_number_and_generation: parse_optional(
+                cursor,
+                "full_card_number_and_generation",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             place_record: PlaceRecordGen2::parse(cursor).context("Failed to parse place_record")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuActivitiesBlockGen2 {
-    pub date_of_day_downloaded_record_array: Vec<DateOfDayDownloadedGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<DateOfDayDownloadedGen2>"))]
+    pub date_of_day_downloaded_record_array: SmallRecords<DateOfDayDownloadedGen2>,
 
-    pub odometer_value_midnight_record_array: Vec<OdometerValueMidnight>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<OdometerValueMidnight>"))]
+    pub odometer_value_midnight_record_array: SmallRecords<OdometerValueMidnight>,
     pub vu_card_iw_record_array: Vec<VuCardIwRecordGen2>,
     pub vu_activity_daily_record_array: Vec<CardActivityChangeInfo>,
     pub vu_place_daily_work_period_record_array: Vec<VuPlaceDailyWorkPeriodRecordGen2>,
     pub vu_gnss_ad_record_array: Vec<VuGNSSADRecordGen2>,
     pub vu_specific_condition_record_array: Vec<SpecificConditionRecordGen2>,
-    pub signature_record_array: Vec<SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<SignatureGen2>"))]
+    pub signature_record_array: SmallRecords<SignatureGen2>,
 }
 
 impl VuActivitiesBlockGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuActivitiesBlockGen2 {
-            date_of_day_downloaded_record_array: RecordArray::parse(
+            date_of_day_downloaded_record_array: RecordArray::parse_small(
                 cursor,
                 DateOfDayDownloadedGen2::parse,
             )
-            .context("Failed to parse date_of_day_downloaded_record_array")?
-            .into_inner(),
+            .context("Failed to parse date_of_day_downloaded_record_array")?,
 
-            odometer_value_midnight_record_array: RecordArray::parse(
+            odometer_value_midnight_record_array: RecordArray::parse_small(
                 cursor,
                 OdometerValueMidnight::parse,
             )
-            .context("Failed to parse odometer_value_midnight_record_array")?
-            .into_inner(),
+            .context("Failed to parse odometer_value_midnight_record_array")?,
 
             vu_card_iw_record_array: RecordArray::parse(cursor, VuCardIwRecordGen2::parse)
                 .context("Failed to parse vu_card_iw_record_array")?
                 .into_inner(),
 
-            vu_activity_daily_record_array: RecordArray::parse(
-                cursor,
-                CardActivityChangeInfo::parse,
-            )
-            .context("Failed to parse vu_activity_daily_record_array")?
-            .into_inner(),
+            vu_activity_daily_record_array: CardActivityChangeInfo::drop_past_end_of_day(
+                RecordArray::parse(cursor, CardActivityChangeInfo::parse)
+                    .context("Failed to parse vu_activity_daily_record_array")?
+                    .into_inner(),
+            ),
 
             vu_place_daily_work_period_record_array: RecordArray::parse(
                 cursor,
@@ -1737,17 +2137,16 @@ impl VuActivitiesBlockGen2 {
             .context("Failed to parse vu_specific_condition_record_array")?
             .into_inner(),
 
-            signature_record_array: RecordArray::parse_dyn_size(
+            signature_record_array: RecordArray::parse_small_dyn_size(
                 cursor,
                 SignatureGen2::parse_dyn_size,
             )
-            .context("Failed to parse signature_record_array")?
-            .into_inner(),
+            .context("Failed to parse signature_record_array")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1788,7 +2187,7 @@ impl VuIdentificationGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct SensorPairedRecordGen2 {
@@ -1810,7 +2209,7 @@ impl SensorPairedRecordGen2 {
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1834,7 +2233,7 @@ impl SensorExternalGNSSCoupledRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCalibrationRecord: appendix 2.174.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25506)
@@ -1869,10 +2268,21 @@ impl VuCalibrationRecordGen2V2 {
             workshop_address: Address::parse(cursor).context("Failed to parse workshop_address")?,
             workshop_card_number: FullCardNumberGen2::parse(cursor)
                 .context("Failed to parse workshop_card_number")?,
-            workshop_card_expiry_date: TimeReal::parse(cursor).ok(),
-            vehicle_identification_number: VehicleIdentificationNumber::parse(cursor).ok(),
-            vehicle_registration_identification: VehicleRegistrationIdentification::parse(cursor)
-                .ok(),
+            workshop_card_expiry_date: parse_optional(
+                cursor,
+                "workshop_card_expiry_date",
+                TimeReal::parse,
+            ),
+            vehicle_identification_number: parse_optional(
+                cursor,
+                "vehicle_identification_number",
+                VehicleIdentificationNumber::parse,
+            ),
+            vehicle_registration_identification: parse_optional(
+                cursor,
+                "vehicle_registration_identification",
+                VehicleRegistrationIdentification::parse,
+            ),
             w_vehicle_characteristic_constant: WVehicleCharacteristicConstant::parse(cursor)
                 .context("Failed to parse w_vehicle_characteristic_constant")?,
             k_constant_of_recording_equipment: KConstantOfRecordingEquipment::parse(cursor)
@@ -1886,15 +2296,15 @@ impl VuCalibrationRecordGen2V2 {
                 .context("Failed to parse old_odometer_value")?,
             new_odometer_value: OdometerShort::parse(cursor)
                 .context("Failed to parse new_odometer_value")?,
-            old_time_value: TimeReal::parse(cursor).ok(),
-            new_time_value: TimeReal::parse(cursor).ok(),
-            next_calibration_date: TimeReal::parse(cursor).ok(),
+            old_time_value: parse_optional(cursor, "old_time_value", TimeReal::parse),
+            new_time_value: parse_optional(cursor, "new_time_value", TimeReal::parse),
+            next_calibration_date: parse_optional(cursor, "next_calibration_date", TimeReal::parse),
             seal_data_vu: SealDataVuGen2::parse(cursor).context("Failed to parse seal_data_vu")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1908,9 +2318,11 @@ pub struct VuCardRecordGen2 {
 impl VuCardRecordGen2 {
     const SIZE: usize = 28;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let card_number_and_generation_information = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_generation_information")
-            .ok();
+        let card_number_and_generation_information = parse_optional(
+            cursor,
+            "card_number_and_generation_information",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let card_extended_serial_number = ExtendedSerialNumberGen2::parse(cursor)
             .context("Failed to parse card_extended_serial_number")?;
         let card_structure_version = CardStructureVersion::parse(cursor)
@@ -1943,7 +2355,7 @@ impl VuCardRecordGen2 {
         })
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1955,9 +2367,11 @@ pub struct VuITSConsentRecordGen2 {
 impl VuITSConsentRecordGen2 {
     const SIZE: usize = 20;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let card_number_and_gen = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen")
-            .ok();
+        let card_number_and_gen = parse_optional(
+            cursor,
+            "card_number_and_gen",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let consent = cursor.read_u8().context("Failed to parse consent")? != 0;
         Ok(VuITSConsentRecordGen2 {
             card_number_and_gen,
@@ -1966,7 +2380,7 @@ impl VuITSConsentRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1992,25 +2406,33 @@ impl VuPowerSupplyInterruptionRecordGen2 {
             event_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse event_begin_time")?,
             event_end_time: TimeReal::parse(cursor).context("Failed to parse event_end_time")?,
-            card_number_and_gen_driver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_begin")
-                .ok(),
-            card_number_and_gen_driver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_end")
-                .ok(),
-            card_number_and_gen_codriver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_begin")
-                .ok(),
-            card_number_and_gen_codriver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_end")
-                .ok(),
+            card_number_and_gen_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             similar_events_number: SimilarEventsNumber::parse(cursor)
                 .context("Failed to parse similar_events_number")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// TREP 0x25 page 348
@@ -2022,7 +2444,8 @@ pub struct VuCompanyLocksGen2 {
     pub vu_card_record_array: Vec<VuCardRecordGen2>,
     pub vu_its_consent_record_array: Vec<VuITSConsentRecordGen2>,
     pub vu_power_supply_interruption_record_array: Vec<VuPowerSupplyInterruptionRecordGen2>,
-    pub signature_record_array: Vec<SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<SignatureGen2>"))]
+    pub signature_record_array: SmallRecords<SignatureGen2>,
 }
 impl VuCompanyLocksGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -2067,22 +2490,22 @@ impl VuCompanyLocksGen2 {
             .context("Failed to parse vu_power_supply_interruption_record_array")?
             .into_inner(),
 
-            signature_record_array: RecordArray::parse_dyn_size(
+            signature_record_array: RecordArray::parse_small_dyn_size(
                 cursor,
                 SignatureGen2::parse_dyn_size,
             )
-            .context("Failed to parse signature_record_array")?
-            .into_inner(),
+            .context("Failed to parse signature_record_array")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuDetailedSpeedBlockGen2 {
     pub vu_detailed_speed_block_record_array: Vec<VuDetailedSpeedBlock>,
-    pub signature_record_array: Vec<SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<SignatureGen2>"))]
+    pub signature_record_array: SmallRecords<SignatureGen2>,
 }
 
 impl VuDetailedSpeedBlockGen2 {
@@ -2095,17 +2518,16 @@ impl VuDetailedSpeedBlockGen2 {
             .context("Failed to parse vu_detailed_speed_block_record_array")?
             .into_inner(),
 
-            signature_record_array: RecordArray::parse_dyn_size(
+            signature_record_array: RecordArray::parse_small_dyn_size(
                 cursor,
                 SignatureGen2::parse_dyn_size,
             )
-            .context("Failed to parse signature_record_array")?
-            .into_inner(),
+            .context("Failed to parse signature_record_array")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuFaultRecordGen2 {
@@ -2129,18 +2551,26 @@ impl VuFaultRecordGen2 {
             fault_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse fault_begin_time")?,
             fault_end_time: TimeReal::parse(cursor).context("Failed to parse fault_end_time")?,
-            card_number_and_gen_driver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_begin")
-                .ok(),
-            card_number_and_gen_codriver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_begin")
-                .ok(),
-            card_number_and_gen_driver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_end")
-                .ok(),
-            card_number_and_gen_codriver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_end")
-                .ok(),
+            card_number_and_gen_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             manufacturer_specific_event_fault_data: ManufacturerSpecificEventFaultDataGen2::parse(
                 cursor,
             )
@@ -2150,7 +2580,7 @@ impl VuFaultRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuEventRecordGen2 {
@@ -2174,19 +2604,27 @@ impl VuEventRecordGen2 {
                 .context("Failed to parse event_record_purpose")?,
             event_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse event_begin_time")?,
-            event_end_time: TimeReal::parse(cursor).ok(),
-            card_number_and_gen_driver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_begin")
-                .ok(),
-            card_number_and_gen_codriver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_begin")
-                .ok(),
-            card_number_and_gen_driver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_end")
-                .ok(),
-            card_number_and_gen_codriver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_end")
-                .ok(),
+            event_end_time: parse_optional(cursor, "event_end_time", TimeReal::parse),
+            card_number_and_gen_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             similar_events_number: SimilarEventsNumber::parse(cursor)
                 .context("Failed to parse similar_events_number")?,
             manufacturer_specific_event_fault_data: ManufacturerSpecificEventFaultDataGen2::parse(
@@ -2198,7 +2636,7 @@ impl VuEventRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuOverSpeedingControlDataGen2 {
@@ -2211,8 +2649,12 @@ pub struct VuOverSpeedingControlDataGen2 {
 impl VuOverSpeedingControlDataGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuOverSpeedingControlDataGen2 {
-            last_overspeed_control_time: TimeReal::parse(cursor).ok(),
-            first_overspeed_since: TimeReal::parse(cursor).ok(),
+            last_overspeed_control_time: parse_optional(
+                cursor,
+                "last_overspeed_control_time",
+                TimeReal::parse,
+            ),
+            first_overspeed_since: parse_optional(cursor, "first_overspeed_since", TimeReal::parse),
             number_of_overspeed_since: OverspeedNumber::parse(cursor)
                 .context("Failed to parse number_of_overspeed_since")
                 .ok(),
@@ -2220,7 +2662,7 @@ impl VuOverSpeedingControlDataGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuOverSpeedingEventRecordGen2 {
@@ -2248,21 +2690,27 @@ impl VuOverSpeedingEventRecordGen2 {
             max_speed_value: SpeedMax::parse(cursor).context("Failed to parse max_speed_value")?,
             average_speed_value: SpeedAverage::parse(cursor)
                 .context("Failed to parse average_speed_value")?,
-            card_number_and_gen_driver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_begin")
-                .ok(),
+            card_number_and_gen_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             similar_events_number: SimilarEventsNumber::parse(cursor)
                 .context("Failed to parse similar_events_number")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /** [VuTimeAdjustmentRecord: appendix 2.232.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28728) */
 pub struct VuTimeAdjustmentRecordGen2 {
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub old_time_value: DateTime<Utc>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub new_time_value: DateTime<Utc>,
     pub workshop_name: Name,
     pub workshop_address: Address,
@@ -2280,14 +2728,16 @@ impl VuTimeAdjustmentRecordGen2 {
                 .0,
             workshop_name: Name::parse(cursor).context("Failed to parse workshop_name")?,
             workshop_address: Address::parse(cursor).context("Failed to parse workshop_address")?,
-            workshop_card_number_and_generation: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse workshop_card_number_and_generation")
-                .ok(),
+            workshop_card_number_and_generation: parse_optional(
+                cursor,
+                "workshop_card_number_and_generation",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuEventsAndFaultsBlockGen2 {
@@ -2296,7 +2746,8 @@ pub struct VuEventsAndFaultsBlockGen2 {
     pub vu_over_speeding_control_data_record_array: Vec<VuOverSpeedingControlDataGen2>,
     pub vu_over_speeding_event_record_array: Vec<VuOverSpeedingEventRecordGen2>,
     pub vu_time_adjustment_record_array: Vec<VuTimeAdjustmentRecordGen2>,
-    pub signature_record_array: Vec<SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<SignatureGen2>"))]
+    pub signature_record_array: SmallRecords<SignatureGen2>,
 }
 
 impl VuEventsAndFaultsBlockGen2 {
@@ -2326,17 +2777,16 @@ impl VuEventsAndFaultsBlockGen2 {
             )
             .context("Failed to parse vu_time_adjustment_record_array")?
             .into_inner(),
-            signature_record_array: RecordArray::parse_dyn_size(
+            signature_record_array: RecordArray::parse_small_dyn_size(
                 cursor,
                 SignatureGen2::parse_dyn_size,
             )
-            .context("Failed to parse signature_record_array")?
-            .into_inner(),
+            .context("Failed to parse signature_record_array")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuDownloadActivityData: appendix 2.195.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26758)
@@ -2348,14 +2798,18 @@ pub struct VuDownloadActivityDataGen2 {
 impl VuDownloadActivityDataGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuDownloadActivityDataGen2 {
-            downloading_time: TimeReal::parse(cursor).ok(),
-            full_card_number_and_generation: FullCardNumberAndGenerationGen2::parse(cursor),
+            downloading_time: parse_optional(cursor, "downloading_time", TimeReal::parse),
+            full_card_number_and_generation: parse_optional(
+                cursor,
+                "full_card_number_and_generation",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             company_or_workshop_name: Name::parse(cursor).ok(),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuCompanyLocksRecord: appendix 2.184.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26187)
@@ -2370,20 +2824,20 @@ impl VuCompanyLocksRecordGen2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuCompanyLocksRecordGen2 {
             lock_in_time: TimeReal::parse(cursor).context("Failed to parse lock_in_time")?,
-            lock_out_time: TimeReal::parse(cursor)
-                .context("Failed to parse lock_out_time")
-                .ok(),
+            lock_out_time: parse_optional(cursor, "lock_out_time", TimeReal::parse),
             company_name: Name::parse(cursor).context("Failed to parse company_name")?,
             company_address: Address::parse(cursor).context("Failed to parse company_address")?,
-            company_card_number_and_generation: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse company_card_number_and_generation")
-                .ok()
-                .context("FullCardNumberAndGeneration is None")?,
+            company_card_number_and_generation: parse_optional(
+                cursor,
+                "company_card_number_and_generation",
+                FullCardNumberAndGenerationGen2::parse,
+            )
+            .context("FullCardNumberAndGeneration is None")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuControlActivityRecord: appendix 2.187.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26376)
@@ -2400,10 +2854,12 @@ impl VuControlActivityRecordGen2 {
         Ok(VuControlActivityRecordGen2 {
             control_type: ControlTypeGen2::parse(cursor).context("Failed to parse control_type")?,
             control_time: TimeReal::parse(cursor).context("Failed to parse control_time")?,
-            control_card_number_and_generation: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse control_card_number_and_generation")
-                .ok()
-                .context("FullCardNumberAndGeneration is None")?,
+            control_card_number_and_generation: parse_optional(
+                cursor,
+                "control_card_number_and_generation",
+                FullCardNumberAndGenerationGen2::parse,
+            )
+            .context("FullCardNumberAndGeneration is None")?,
             download_period_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse download_period_begin_time")?,
             download_period_end_time: TimeReal::parse(cursor)
@@ -2412,7 +2868,7 @@ impl VuControlActivityRecordGen2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 
@@ -2423,15 +2879,20 @@ pub struct VuOverviewBlockGen2 {
     /// VU certificate
     pub vu_certificate_record_array: Vec<VuCertificateGen2>,
     /// Vehicle identification
-    pub vehicle_identification_number_record_array: Vec<VehicleIdentificationNumber>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VehicleIdentificationNumber>"))]
+    pub vehicle_identification_number_record_array: SmallRecords<VehicleIdentificationNumber>,
     /// Vehicle registration number
-    pub vehicle_registration_number_record_array: Vec<VehicleRegistrationNumber>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VehicleRegistrationNumber>"))]
+    pub vehicle_registration_number_record_array: SmallRecords<VehicleRegistrationNumber>,
     /// VU current date and time
-    pub current_date_time_record_array: Vec<CurrentDateTime>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<CurrentDateTime>"))]
+    pub current_date_time_record_array: SmallRecords<CurrentDateTime>,
     /// Downloadable period
-    pub vu_downloadable_period_record_array: Vec<VuDownloadablePeriod>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VuDownloadablePeriod>"))]
+    pub vu_downloadable_period_record_array: SmallRecords<VuDownloadablePeriod>,
     /// Type of cards inserted in the VU
-    pub card_slots_status_record_array: Vec<CardSlotsStatus>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<CardSlotsStatus>"))]
+    pub card_slots_status_record_array: SmallRecords<CardSlotsStatus>,
     /// Previous VU download
     pub vu_download_activity_data_record_array: Vec<VuDownloadActivityDataGen2>,
     /// All company locks stored.
@@ -2439,7 +2900,8 @@ pub struct VuOverviewBlockGen2 {
     /// All control records stored in the VU.
     pub vu_control_activity_record_array: Vec<VuControlActivityRecordGen2>,
     /// ECC signature of all preceding data except the certificates
-    pub signature_record_array: Vec<SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<SignatureGen2>"))]
+    pub signature_record_array: SmallRecords<SignatureGen2>,
 }
 
 impl VuOverviewBlockGen2 {
@@ -2457,30 +2919,31 @@ impl VuOverviewBlockGen2 {
             )
             .context("Failed to parse vu_certificate_record_array")?
             .into_inner(),
-            vehicle_identification_number_record_array: RecordArray::parse(
+            vehicle_identification_number_record_array: RecordArray::parse_small(
                 cursor,
                 VehicleIdentificationNumber::parse,
             )
-            .context("Failed to parse vehicle_identification_number_record_array")?
-            .into_inner(),
-            vehicle_registration_number_record_array: RecordArray::parse(
+            .context("Failed to parse vehicle_identification_number_record_array")?,
+            vehicle_registration_number_record_array: RecordArray::parse_small(
                 cursor,
                 VehicleRegistrationNumber::parse,
             )
-            .context("Failed to parse vehicle_registration_number_record_array")?
-            .into_inner(),
-            current_date_time_record_array: RecordArray::parse(cursor, CurrentDateTime::parse)
-                .context("Failed to parse current_date_time_record_array")?
-                .into_inner(),
-            vu_downloadable_period_record_array: RecordArray::parse(
+            .context("Failed to parse vehicle_registration_number_record_array")?,
+            current_date_time_record_array: RecordArray::parse_small(
+                cursor,
+                CurrentDateTime::parse,
+            )
+            .context("Failed to parse current_date_time_record_array")?,
+            vu_downloadable_period_record_array: RecordArray::parse_small(
                 cursor,
                 VuDownloadablePeriod::parse,
             )
-            .context("Failed to parse vu_downloadable_period_record_array")?
-            .into_inner(),
-            card_slots_status_record_array: RecordArray::parse(cursor, CardSlotsStatus::parse)
-                .context("Failed to parse card_slots_status_record_array")?
-                .into_inner(),
+            .context("Failed to parse vu_downloadable_period_record_array")?,
+            card_slots_status_record_array: RecordArray::parse_small(
+                cursor,
+                CardSlotsStatus::parse,
+            )
+            .context("Failed to parse card_slots_status_record_array")?,
             vu_download_activity_data_record_array: RecordArray::parse(
                 cursor,
                 VuDownloadActivityDataGen2::parse,
@@ -2499,12 +2962,11 @@ impl VuOverviewBlockGen2 {
             )
             .context("Failed to parse vu_control_activity_record_array")?
             .into_inner(),
-            signature_record_array: RecordArray::parse_dyn_size(
+            signature_record_array: RecordArray::parse_small_dyn_size(
                 cursor,
                 SignatureGen2::parse_dyn_size,
             )
-            .context("Failed to parse signature_record_array")?
-            .into_inner(),
+            .context("Failed to parse signature_record_array")?,
         })
     }
 }
@@ -8,7 +8,7 @@ use ts_rs::TS;
 
 use super::gen2::FullCardNumberAndGenerationGen2;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuOverviewBlock: appendix 2.183.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28866)
@@ -18,15 +18,21 @@ pub struct VuOverviewBlockGen2V2 {
     /// VU certificate
     pub vu_certificate_record_array: Vec<gen2::VuCertificateGen2>,
     /// Vehicle identification
-    pub vehicle_identification_number_record_array: Vec<VehicleIdentificationNumber>,
-    /// Vehicle registration number
-    pub vehicle_registration_number_record_array: Vec<VehicleRegistrationNumberGen2V2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VehicleIdentificationNumber>"))]
+    pub vehicle_identification_number_record_array: gen2::SmallRecords<VehicleIdentificationNumber>,
+    /// Vehicle registration nation and number, replacing the Gen2 registration number array
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VehicleRegistrationIdentification>"))]
+    pub vehicle_registration_identification_record_array:
+        gen2::SmallRecords<VehicleRegistrationIdentification>,
     /// VU current date and time
-    pub current_date_time_record_array: Vec<TimeReal>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<TimeReal>"))]
+    pub current_date_time_record_array: gen2::SmallRecords<TimeReal>,
     /// Downloadable period
-    pub vu_downloadable_period_record_array: Vec<VuDownloadablePeriod>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<VuDownloadablePeriod>"))]
+    pub vu_downloadable_period_record_array: gen2::SmallRecords<VuDownloadablePeriod>,
     /// Type of cards inserted in the VU
-    pub card_slots_status_record_array: Vec<CardSlotsStatus>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<CardSlotsStatus>"))]
+    pub card_slots_status_record_array: gen2::SmallRecords<CardSlotsStatus>,
     /// Previous VU download
     pub vu_download_activity_data_record_array: Vec<gen2::VuDownloadActivityDataGen2>,
     /// All company locks stored.
@@ -34,7 +40,8 @@ pub struct VuOverviewBlockGen2V2 {
     /// All control records stored in the VU.
     pub vu_control_activity_record_array: Vec<gen2::VuControlActivityRecordGen2>,
     /// ECC signature of all preceding data except the certificates
-    pub signature_record_array: Vec<gen2::SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<gen2::SignatureGen2>"))]
+    pub signature_record_array: gen2::SmallRecords<gen2::SignatureGen2>,
 }
 
 impl VuOverviewBlockGen2V2 {
@@ -50,27 +57,23 @@ impl VuOverviewBlockGen2V2 {
                 .into_inner();
 
         let vehicle_identification_number_record_array =
-            RecordArray::parse(cursor, VehicleIdentificationNumber::parse)
-                .context("Failed to parse vehicle_identification_number_record_array")?
-                .into_inner();
+            RecordArray::parse_small(cursor, VehicleIdentificationNumber::parse)
+                .context("Failed to parse vehicle_identification_number_record_array")?;
 
-        let vehicle_registration_number_record_array =
-            RecordArray::parse(cursor, VehicleRegistrationNumberGen2V2::parse)
-                .context("Failed to parse vehicle_registration_number_record_array")?
-                .into_inner();
+        let vehicle_registration_identification_record_array =
+            RecordArray::parse_small(cursor, VehicleRegistrationIdentification::parse)
+                .context("Failed to parse vehicle_registration_identification_record_array")?;
 
-        let current_date_time_record_array = RecordArray::parse(cursor, TimeReal::parse)
-            .context("Failed to parse current_date_time_record_array")?
-            .into_inner();
+        let current_date_time_record_array = RecordArray::parse_small(cursor, TimeReal::parse)
+            .context("Failed to parse current_date_time_record_array")?;
 
         let vu_downloadable_period_record_array =
-            RecordArray::parse(cursor, VuDownloadablePeriod::parse)
-                .context("Failed to parse vu_downloadable_period_record_array")?
-                .into_inner();
+            RecordArray::parse_small(cursor, VuDownloadablePeriod::parse)
+                .context("Failed to parse vu_downloadable_period_record_array")?;
 
-        let card_slots_status_record_array = RecordArray::parse(cursor, CardSlotsStatus::parse)
-            .context("Failed to parse card_slots_status_record_array")?
-            .into_inner();
+        let card_slots_status_record_array =
+            RecordArray::parse_small(cursor, CardSlotsStatus::parse)
+                .context("Failed to parse card_slots_status_record_array")?;
 
         let vu_download_activity_data_record_array =
             RecordArray::parse(cursor, gen2::VuDownloadActivityDataGen2::parse)
@@ -88,15 +91,14 @@ impl VuOverviewBlockGen2V2 {
                 .into_inner();
 
         let signature_record_array =
-            RecordArray::parse_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
-                .context("Failed to parse signature_record_array")?
-                .into_inner();
+            RecordArray::parse_small_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
+                .context("Failed to parse signature_record_array")?;
 
         Ok(VuOverviewBlockGen2V2 {
             member_state_certificate_record_array,
             vu_certificate_record_array,
             vehicle_identification_number_record_array,
-            vehicle_registration_number_record_array,
+            vehicle_registration_identification_record_array,
             current_date_time_record_array,
             vu_downloadable_period_record_array,
             card_slots_status_record_array,
@@ -123,7 +125,7 @@ type NoOfLoadTypeEntryRecords = u16;
 /// [VuConfigurationLengthRange: appendix 2.185a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26321)
 type VuConfigurationLengthRange = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [DriverCardApplicationIdentificationGen2V2: appendix 2.61a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19892)
@@ -162,13 +164,14 @@ impl DriverCardApplicationIdentificationGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PositionAuthenticationStatus: appendix 2.117a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23200)
 pub enum PositionAuthenticationStatus {
     NotAuthenticated,
     Authenticated,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
 }
 impl PositionAuthenticationStatus {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -178,12 +181,26 @@ impl PositionAuthenticationStatus {
         Ok(match value {
             0x00 => PositionAuthenticationStatus::NotAuthenticated,
             0x01 => PositionAuthenticationStatus::Authenticated,
-            0x02..=0xFF => PositionAuthenticationStatus::RFU,
+            0x02..=0xFF => PositionAuthenticationStatus::RFU(value),
         })
     }
+
+    /// Raw value as stored.
+    pub fn raw(&self) -> u8 {
+        match self {
+            PositionAuthenticationStatus::NotAuthenticated => 0x00,
+            PositionAuthenticationStatus::Authenticated => 0x01,
+            PositionAuthenticationStatus::RFU(value) => *value,
+        }
+    }
+
+    /// Only an explicit `Authenticated` counts, reserved values are treated as not authenticated.
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self, PositionAuthenticationStatus::Authenticated)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [PlaceAuthStatusRecord: appendix 2.116b.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23087)
@@ -194,7 +211,7 @@ pub struct PlaceAuthStatusRecord {
 impl PlaceAuthStatusRecord {
     pub const SIZE: usize = 5;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let entry_time = TimeReal::parse(inner_cursor).context("Failed to parse entry_time")?;
         let authentication_status = PositionAuthenticationStatus::parse(inner_cursor)
@@ -206,7 +223,7 @@ impl PlaceAuthStatusRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardPlacesAuthDailyWorkPeriod: appendix 2.26a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17697)
@@ -217,7 +234,7 @@ pub struct CardPlacesAuthDailyWorkPeriod {
 
 impl CardPlacesAuthDailyWorkPeriod {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
 
         let place_auth_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
@@ -243,7 +260,7 @@ impl CardPlacesAuthDailyWorkPeriod {
 /// [NoOfGNSSAdRecords: appendix 2.111.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22756)
 pub type NoOfGNSSAdRecords = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSAuthStatusADRecord: appendix 2.79a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21683)
@@ -254,7 +271,7 @@ pub struct GNSSAuthStatusADRecord {
 impl GNSSAuthStatusADRecord {
     pub const SIZE: usize = 5;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let time_stamp = TimeReal::parse(inner_cursor).context("Failed to parse time_stamp")?;
         let authentication_status = PositionAuthenticationStatus::parse(inner_cursor)
@@ -267,7 +284,7 @@ impl GNSSAuthStatusADRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSAuthAccumulatedDriving: appendix 2.79a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21683)
@@ -278,7 +295,7 @@ pub struct GNSSAuthAccumulatedDriving {
 
 impl GNSSAuthAccumulatedDriving {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
 
         let gnss_auth_ad_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
@@ -303,7 +320,7 @@ impl GNSSAuthAccumulatedDriving {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [GNSSPlaceAuthRecord: appendix 2.79c.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21739)
@@ -333,7 +350,7 @@ impl GNSSPlaceAuthRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardBorderCrossingRecord: appendix 2.11b.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16857)
@@ -352,7 +369,7 @@ pub struct CardBorderCrossingRecord {
 impl CardBorderCrossingRecord {
     pub const SIZE: usize = 17;
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(Self::SIZE);
+        let cursor = &mut cursor.take_exact(Self::SIZE)?;
 
         let country_left =
             external::NationNumeric::parse(cursor).context("Failed to parse country_left")?;
@@ -372,7 +389,7 @@ impl CardBorderCrossingRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardBorderCrossings: appendix 2.11a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16826)
@@ -382,7 +399,7 @@ pub struct CardBorderCrossings {
 }
 impl CardBorderCrossings {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
         let border_crossing_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
             .context("Failed to parse border_crossing_pointer_newest_record")?;
@@ -404,11 +421,12 @@ impl CardBorderCrossings {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [OperationType: appendix 2.114a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22905)
 pub enum OperationType {
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     LoadOperation,
     UnloadOperation,
     SimultaneousLoadUnloadOperation,
@@ -417,16 +435,16 @@ impl OperationType {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let value = cursor.read_u8().context("Failed to parse operation_type")?;
         Ok(match value {
-            0x00 => OperationType::RFU,
+            0x00 => OperationType::RFU(value),
             0x01 => OperationType::LoadOperation,
             0x02 => OperationType::UnloadOperation,
             0x03 => OperationType::SimultaneousLoadUnloadOperation,
-            0x04..=0xFF => OperationType::RFU,
+            0x04..=0xFF => OperationType::RFU(value),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardLoadUnloadRecord: appendix 2.24d.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17576)
@@ -456,7 +474,7 @@ impl CardLoadUnloadRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardLoadUnloadOperations: appendix 2.24c.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17544)
@@ -466,7 +484,7 @@ pub struct CardLoadUnloadOperations {
 }
 impl CardLoadUnloadOperations {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
 
         let load_unload_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
@@ -489,14 +507,15 @@ impl CardLoadUnloadOperations {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [LoadType: appendix 2.90a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22110)
 pub enum LoadType {
     UndefinedLoadType,
     Goods,
     Passengers,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
 }
 impl LoadType {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -505,12 +524,12 @@ impl LoadType {
             0x00 => LoadType::UndefinedLoadType,
             0x01 => LoadType::Goods,
             0x02 => LoadType::Passengers,
-            0x03..=0xFF => LoadType::RFU,
+            0x03..=0xFF => LoadType::RFU(value),
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardLoadTypeEntryRecord: appendix 2.24b.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17521)
@@ -532,7 +551,7 @@ impl CardLoadTypeEntryRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardLoadTypeEntries: appendix 2.24a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17490)
@@ -542,7 +561,7 @@ pub struct CardLoadTypeEntries {
 }
 impl CardLoadTypeEntries {
     pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
-        let cursor = &mut cursor.take_exact(size);
+        let cursor = &mut cursor.take_exact(size)?;
 
         let load_type_pointer_newest_record = cursor
             .read_u16::<BigEndian>()
@@ -565,7 +584,7 @@ impl CardLoadTypeEntries {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// VuConfigurations: there is no documentation for this block in the spec, none at all, so we'll just process the bytes
 pub struct VuConfigurations(pub Vec<u8>);
@@ -580,35 +599,16 @@ impl VuConfigurations {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-/// [VehicleRegistrationNumber: appendix 2.168.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25188)
-#[cfg_attr(feature = "ts", derive(TS))]
-pub struct VehicleRegistrationNumberGen2V2 {
-    pub code_page: u8,
-    pub vehicle_reg_number: IA5String,
-}
-impl VehicleRegistrationNumberGen2V2 {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let code_page = cursor.read_u8().context("Failed to read code page")?;
-        // Vu Gen2v2 uses 14 bytes for vehicle registration number, even though the spec says 13 ¯\_(ツ)_/¯
-        let vehicle_reg_number = IA5String::parse_with_code_page(cursor, 14, code_page)
-            .context("Failed to parse VehicleRegistrationNumberGen2V2")?;
-        Ok(VehicleRegistrationNumberGen2V2 {
-            code_page,
-            vehicle_reg_number,
-        })
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VuActivitiesBlockGen2V2 {
     /// Date of day downloaded
-    pub date_of_day_downloaded_record_array: Vec<gen2::DateOfDayDownloadedGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<gen2::DateOfDayDownloadedGen2>"))]
+    pub date_of_day_downloaded_record_array: gen2::SmallRecords<gen2::DateOfDayDownloadedGen2>,
     /// Odometer at end of downloaded day
-    pub odometer_value_midnight_record_array: Vec<OdometerValueMidnight>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<OdometerValueMidnight>"))]
+    pub odometer_value_midnight_record_array: gen2::SmallRecords<OdometerValueMidnight>,
     /// Cards insertion withdrawal cycles data. If no data available, array has noOfRecords = 0.
     /// When a record crosses 00:00 (insertion on previous day) or 24:00 (withdrawal next day),
     /// it appears in full within both days involved.
@@ -626,29 +626,29 @@ pub struct VuActivitiesBlockGen2V2 {
     /// Load/unload operations for the day downloaded.
     pub vu_load_unload_record_array: Vec<VuLoadUnloadRecord>,
     /// ECC signature of all preceding data
-    pub signature_record_array: Vec<gen2::SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<gen2::SignatureGen2>"))]
+    pub signature_record_array: gen2::SmallRecords<gen2::SignatureGen2>,
 }
 
 impl VuActivitiesBlockGen2V2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let date_of_day_downloaded_record_array =
-            RecordArray::parse(cursor, gen2::DateOfDayDownloadedGen2::parse)
-                .context("Failed to parse date_of_day_downloaded_record_array")?
-                .into_inner();
+            RecordArray::parse_small(cursor, gen2::DateOfDayDownloadedGen2::parse)
+                .context("Failed to parse date_of_day_downloaded_record_array")?;
 
         let odometer_value_midnight_record_array =
-            RecordArray::parse(cursor, OdometerValueMidnight::parse)
-                .context("Failed to parse odometer_value_midnight_record_array")?
-                .into_inner();
+            RecordArray::parse_small(cursor, OdometerValueMidnight::parse)
+                .context("Failed to parse odometer_value_midnight_record_array")?;
 
         let vu_card_iw_record_array = RecordArray::parse(cursor, gen2::VuCardIwRecordGen2::parse)
             .context("Failed to parse vu_card_iw_record_array")?
             .into_inner();
 
-        let vu_activity_daily_record_array =
+        let vu_activity_daily_record_array = CardActivityChangeInfo::drop_past_end_of_day(
             RecordArray::parse(cursor, CardActivityChangeInfo::parse)
                 .context("Failed to parse vu_activity_daily_record_array")?
-                .into_inner();
+                .into_inner(),
+        );
 
         let vu_place_daily_work_period_record_array =
             RecordArray::parse(cursor, VuPlaceDailyWorkPeriodRecordGen2V2::parse)
@@ -675,9 +675,8 @@ impl VuActivitiesBlockGen2V2 {
             .into_inner();
 
         let signature_record_array =
-            RecordArray::parse_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
-                .context("Failed to parse signature_record_array")?
-                .into_inner();
+            RecordArray::parse_small_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
+                .context("Failed to parse signature_record_array")?;
 
         Ok(VuActivitiesBlockGen2V2 {
             date_of_day_downloaded_record_array,
@@ -694,13 +693,13 @@ impl VuActivitiesBlockGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [PlaceAuthRecord: appendix 2.216a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23047)
 pub struct PlaceAuthRecord {
     pub entry_time: TimeReal,
-    pub entry_type_daily_work_period: gen2::EntryTypeDailyWorkPeriodGen2,
+    pub entry_type_daily_work_period: Coded<gen2::EntryTypeDailyWorkPeriodGen2>,
     pub daily_work_period_country: external::NationNumeric,
     pub daily_work_period_region: external::RegionNumeric,
     pub vehicle_odometer_value: OdometerShort,
@@ -710,8 +709,9 @@ pub struct PlaceAuthRecord {
 impl PlaceAuthRecord {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let entry_time = TimeReal::parse(cursor).context("Failed to parse entry_time")?;
-        let entry_type_daily_work_period = gen2::EntryTypeDailyWorkPeriodGen2::parse(cursor)
-            .context("Failed to parse entry_type_daily_work_period")?;
+        let entry_type_daily_work_period =
+            Coded::<gen2::EntryTypeDailyWorkPeriodGen2>::parse(cursor)
+                .context("Failed to parse entry_type_daily_work_period")?;
         let daily_work_period_country = external::NationNumeric::parse(cursor)
             .context("Failed to parse daily_work_period_country")?;
         let daily_work_period_region = external::RegionNumeric::parse(cursor)
@@ -732,7 +732,7 @@ impl PlaceAuthRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuPlaceDailyWorkPeriod: appendix 2.219.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28313)
@@ -744,13 +744,17 @@ pub struct VuPlaceDailyWorkPeriodRecordGen2V2 {
 impl VuPlaceDailyWorkPeriodRecordGen2V2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         Ok(VuPlaceDailyWorkPeriodRecordGen2V2 {
-            full_card_number_and_generation: gen2::FullCardNumberAndGenerationGen2::parse(cursor),
+            full_card_number_and_generation: parse_optional(
+                cursor,
+                "full_card_number_and_generation",
+                gen2::FullCardNumberAndGenerationGen2::parse,
+            ),
             place_record: PlaceAuthRecord::parse(cursor).context("Failed to parse place_record")?,
         })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuGNSSADRecord: appendix 2.203.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27345)
@@ -764,12 +768,16 @@ pub struct VuGNSSADRecordGen2V2 {
 impl VuGNSSADRecordGen2V2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let timestamp = TimeReal::parse(cursor).context("Failed to parse timestamp")?;
-        let card_number_and_gen_driver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_driver_slot")
-            .ok();
-        let card_number_and_gen_codriver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_codriver_slot")
-            .ok();
+        let card_number_and_gen_driver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_driver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
+        let card_number_and_gen_codriver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_codriver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let gnss_place_auth_record =
             GNSSPlaceAuthRecord::parse(cursor).context("Failed to parse gnss_place_auth_record")?;
         let vehicle_odometer_value =
@@ -785,7 +793,7 @@ impl VuGNSSADRecordGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuBorderCrossingRecord: appendix 2.203a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27413)
@@ -800,12 +808,16 @@ pub struct VuBorderCrossingRecord {
 }
 impl VuBorderCrossingRecord {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let card_number_and_gen_driver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_driver_slot")
-            .ok();
-        let card_number_and_gen_codriver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_codriver_slot")
-            .ok();
+        let card_number_and_gen_driver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_driver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
+        let card_number_and_gen_codriver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_codriver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let country_left =
             external::NationNumeric::parse(cursor).context("Failed to parse country_left")?;
         let country_entered =
@@ -826,7 +838,7 @@ impl VuBorderCrossingRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 /// [VuLoadUnloadRecord: appendix 2.208a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27834)
@@ -843,12 +855,16 @@ impl VuLoadUnloadRecord {
         let time_stamp = TimeReal::parse(cursor).context("Failed to parse time_stamp")?;
         let operation_type =
             OperationType::parse(cursor).context("Failed to parse operation_type")?;
-        let card_number_and_gen_driver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_driver_slot")
-            .ok();
-        let card_number_and_gen_codriver_slot = FullCardNumberAndGenerationGen2::parse(cursor)
-            .context("Failed to parse card_number_and_gen_codriver_slot")
-            .ok();
+        let card_number_and_gen_driver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_driver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
+        let card_number_and_gen_codriver_slot = parse_optional(
+            cursor,
+            "card_number_and_gen_codriver_slot",
+            FullCardNumberAndGenerationGen2::parse,
+        );
         let gnss_place_auth_record =
             GNSSPlaceAuthRecord::parse(cursor).context("Failed to parse gnss_place_auth_record")?;
         let vehicle_odometer_value =
@@ -865,7 +881,7 @@ impl VuLoadUnloadRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuDigitalMapVersion: appendix 2.192a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26652)
 pub struct VuDigitalMapVersion(pub IA5String);
@@ -878,7 +894,7 @@ impl VuDigitalMapVersion {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -933,7 +949,7 @@ impl VuIdentificationGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// TREP 0x35 page 349
@@ -955,7 +971,8 @@ pub struct VuCompanyLocksGen2V2 {
     pub vu_power_supply_interruption_record_array:
         Vec<gen2v2::VuPowerSupplyInterruptionRecordGen2V2>,
     // /// ECC signature of all preceding data
-    pub signature_record_array: Vec<gen2::SignatureGen2>,
+    #[cfg_attr(feature = "ts", ts(as = "Vec<gen2::SignatureGen2>"))]
+    pub signature_record_array: gen2::SmallRecords<gen2::SignatureGen2>,
 }
 impl VuCompanyLocksGen2V2 {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -994,9 +1011,8 @@ impl VuCompanyLocksGen2V2 {
                 .into_inner();
 
         let signature_record_array =
-            RecordArray::parse_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
-                .context("Failed to parse signature_record_array")?
-                .into_inner();
+            RecordArray::parse_small_dyn_size(cursor, gen2::SignatureGen2::parse_dyn_size)
+                .context("Failed to parse signature_record_array")?;
 
         Ok(VuCompanyLocksGen2V2 {
             vu_identification_record_array,
@@ -1011,7 +1027,7 @@ impl VuCompanyLocksGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [VuCalibrationRecord: appendix 2.174.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25506)
@@ -1048,10 +1064,18 @@ impl VuCalibrationRecordGen2V2 {
             Address::parse(cursor).context("Failed to parse workshop_address")?;
         let workshop_card_number = gen2::FullCardNumberGen2::parse(cursor)
             .context("Failed to parse workshop_card_number")?;
-        let workshop_card_expiry_date = TimeReal::parse(cursor).ok();
-        let vehicle_identification_number = VehicleIdentificationNumber::parse(cursor).ok();
-        let vehicle_registration_identification =
-            VehicleRegistrationIdentification::parse(cursor).ok();
+        let workshop_card_expiry_date =
+            parse_optional(cursor, "workshop_card_expiry_date", TimeReal::parse);
+        let vehicle_identification_number = parse_optional(
+            cursor,
+            "vehicle_identification_number",
+            VehicleIdentificationNumber::parse,
+        );
+        let vehicle_registration_identification = parse_optional(
+            cursor,
+            "vehicle_registration_identification",
+            VehicleRegistrationIdentification::parse,
+        );
         let w_vehicle_characteristic_constant = WVehicleCharacteristicConstant::parse(cursor)
             .context("Failed to parse w_vehicle_characteristic_constant")?;
         let k_constant_of_recording_equipment = KConstantOfRecordingEquipment::parse(cursor)
@@ -1065,9 +1089,10 @@ impl VuCalibrationRecordGen2V2 {
             OdometerShort::parse(cursor).context("Failed to parse old_odometer_value")?;
         let new_odometer_value =
             OdometerShort::parse(cursor).context("Failed to parse new_odometer_value")?;
-        let old_time_value = TimeReal::parse(cursor).ok();
-        let new_time_value = TimeReal::parse(cursor).ok();
-        let next_calibration_date = TimeReal::parse(cursor).ok();
+        let old_time_value = parse_optional(cursor, "old_time_value", TimeReal::parse);
+        let new_time_value = parse_optional(cursor, "new_time_value", TimeReal::parse);
+        let next_calibration_date =
+            parse_optional(cursor, "next_calibration_date", TimeReal::parse);
         let seal_data_vu =
             gen2::SealDataVuGen2::parse(cursor).context("Failed to parse seal_data_vu")?;
         cursor.consume(24);
@@ -1104,7 +1129,7 @@ impl VuCalibrationRecordGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EventFaultType: appendix 2.70.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20338)
 pub enum EventFaultTypeGen2V2 {
@@ -1155,7 +1180,8 @@ pub enum EventFaultTypeGen2V2 {
     ITSInterface,
     InternalSensorFault,
     CardFaultNoFurtherDetails,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 
@@ -1197,7 +1223,7 @@ impl EventFaultTypeGen2V2 {
             0x1A => Ok(Self::ExternalGNSSFacilityAuthenticationFailure),
             0x1B => Ok(Self::ExternalGNSSFacilityCertificateExpired),
             0x1C => Ok(Self::InconsistencyBetweenMotionDataAndStoredDriverActivityData),
-            0x1D..=0x1F => Ok(Self::RFU),
+            0x1D..=0x1F => Ok(Self::RFU(value)),
 
             // Sensor related security breach attempt events,
             0x20 => Ok(Self::SensorSecurityBreachAttemptNoFurtherDetails),
@@ -1206,7 +1232,7 @@ impl EventFaultTypeGen2V2 {
             0x23 => Ok(Self::SensorInternalDataTransferError),
             0x24 => Ok(Self::SensorUnauthorizedCaseOpening),
             0x25 => Ok(Self::SensorHardwareSabotage),
-            0x26..=0x2F => Ok(Self::RFU),
+            0x26..=0x2F => Ok(Self::RFU(value)),
 
             // Recording equipment faults,
             0x30 => Ok(Self::ControlDeviceFaultNoFurtherDetails),
@@ -1220,14 +1246,14 @@ impl EventFaultTypeGen2V2 {
             0x38 => Ok(Self::RemoteCommunicationFacility),
             0x39 => Ok(Self::ITSInterface),
             0x3A => Ok(Self::InternalSensorFault),
-            0x3B..=0x3F => Ok(Self::RFU),
+            0x3B..=0x3F => Ok(Self::RFU(value)),
 
             // Card faults,
             0x40 => Ok(Self::CardFaultNoFurtherDetails),
-            0x41..=0x4F => Ok(Self::RFU),
+            0x41..=0x4F => Ok(Self::RFU(value)),
 
             // Reserved for future use,
-            0x50..=0x7F => Ok(Self::RFU),
+            0x50..=0x7F => Ok(Self::RFU(value)),
 
             // Manufacturer specific,
             0x80..=0xFF => Ok(Self::ManufacturerSpecific),
@@ -1235,7 +1261,7 @@ impl EventFaultTypeGen2V2 {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 
@@ -1262,20 +1288,131 @@ impl VuPowerSupplyInterruptionRecordGen2V2 {
             event_begin_time: TimeReal::parse(cursor)
                 .context("Failed to parse event_begin_time")?,
             event_end_time: TimeReal::parse(cursor).context("Failed to parse event_end_time")?,
-            card_number_and_gen_driver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_begin")
-                .ok(),
-            card_number_and_gen_driver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_driver_slot_end")
-                .ok(),
-            card_number_and_gen_codriver_slot_begin: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_begin")
-                .ok(),
-            card_number_and_gen_codriver_slot_end: FullCardNumberAndGenerationGen2::parse(cursor)
-                .context("Failed to parse card_number_and_gen_codriver_slot_end")
-                .ok(),
+            card_number_and_gen_driver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_driver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_driver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_begin: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_begin",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
+            card_number_and_gen_codriver_slot_end: parse_optional(
+                cursor,
+                "card_number_and_gen_codriver_slot_end",
+                FullCardNumberAndGenerationGen2::parse,
+            ),
             similar_events_number: SimilarEventsNumber::parse(cursor)
                 .context("Failed to parse similar_events_number")?,
         })
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// [ExtendedSerialNumber: appendix 2.72.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20372)
+pub struct ExtendedSerialNumberGen2V2 {
+    pub serial_number: u32,
+    pub month_year: MonthYear,
+    pub equipment_type: gen2::EquipmentTypeGen2,
+    pub manufacturer_code: external::ManufacturerCode,
+}
+impl ExtendedSerialNumberGen2V2 {
+    pub const SIZE: usize = 8;
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
+
+        let serial_number = inner_cursor
+            .read_u32::<BigEndian>()
+            .context("Failed to read serial_number")?;
+        let month_year = MonthYear::parse(inner_cursor).context("Failed to parse month_year")?;
+        let equipment_type = gen2::EquipmentTypeGen2::parse(inner_cursor)
+            .context("Failed to parse equipment_type")?;
+        let manufacturer_code = external::ManufacturerCode::parse(inner_cursor)
+            .context("Failed to parse manufacturer_code")?;
+
+        Ok(ExtendedSerialNumberGen2V2 {
+            serial_number,
+            month_year,
+            equipment_type,
+            manufacturer_code,
+        })
+    }
+
+    /// Same format as [`gen2::ExtendedSerialNumberGen2::to_serial_string`].
+    pub fn to_serial_string(&self) -> String {
+        format!(
+            "{} {:02}/{:02} {}",
+            self.serial_number,
+            self.month_year.month,
+            self.month_year.year,
+            self.manufacturer_code.name
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// [CardIccIdentification: appendix 2.23.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17407)
+pub struct CardIccIdentificationGen2V2 {
+    pub clock_stop: u8,
+    pub card_extended_serial_number: ExtendedSerialNumberGen2V2,
+    pub card_approval_number: CardApprovalNumber,
+    pub card_personaliser_id: external::ManufacturerCode,
+    pub embedder_ic_assembler_id: EmbedderIcAssemblerId,
+    pub ic_identifier: Vec<u8>,
+    /// Bytes following the Gen2 layout, which version 2 cards may append.
+    pub additional_data: Vec<u8>,
+}
+impl CardIccIdentificationGen2V2 {
+    /// Size of the fields shared with [`gen2::CardIccIdentificationGen2`].
+    pub const MIN_SIZE: usize = 25;
+    pub fn parse_dyn_size(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Self> {
+        if size < Self::MIN_SIZE {
+            anyhow::bail!(
+                "CardIccIdentificationGen2V2 needs at least {} bytes, got {}",
+                Self::MIN_SIZE,
+                size
+            );
+        }
+        let inner_cursor = &mut cursor.take_exact(size)?;
+
+        let clock_stop = inner_cursor
+            .read_u8()
+            .context("Failed to read clock_stop")?;
+        let card_extended_serial_number = ExtendedSerialNumberGen2V2::parse(inner_cursor)
+            .context("Failed to parse card_extended_serial_number")?;
+        let card_approval_number = CardApprovalNumber::parse(inner_cursor)
+            .context("Failed to parse card_approval_number")?;
+        let card_personaliser_id = external::ManufacturerCode::parse(inner_cursor)
+            .context("Failed to parse card_personaliser_id")?;
+        let embedder_ic_assembler_id = EmbedderIcAssemblerId::parse(inner_cursor)
+            .context("Failed to parse embedder_ic_assembler_id")?;
+        let mut ic_identifier = vec![0u8; 2];
+        inner_cursor
+            .read_exact(&mut ic_identifier)
+            .context("Failed to read ic_identifier")?;
+        let mut additional_data = vec![0u8; size - Self::MIN_SIZE];
+        inner_cursor
+            .read_exact(&mut additional_data)
+            .context("Failed to read additional_data")?;
+
+        Ok(CardIccIdentificationGen2V2 {
+            clock_stop,
+            card_extended_serial_number,
+            card_approval_number,
+            card_personaliser_id,
+            embedder_ic_assembler_id,
+            ic_identifier,
+            additional_data,
+        })
+    }
+}
@@ -4,17 +4,62 @@ pub mod gen1;
 pub mod gen2;
 pub mod gen2v2;
 use crate::bytes::TakeExact;
+use crate::bytes::TracedRead;
 use crate::bytes::{extract_u16_bits_into_tup, extract_u8_bits_into_tup};
+use crate::units::{Kilometers, Kmh, Minutes};
 use anyhow::{Context, Result};
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::BigEndian;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::io::{Cursor, Read};
+use std::io::Cursor;
 use textcode;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Enums decoded from a single byte, see [`Coded`].
+pub trait FromByte: Sized {
+    /// Returns `None` for values the implemented spec revision does not define.
+    fn from_byte(value: u8) -> Option<Self>;
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A byte-mapped value which keeps the raw byte when it is newer than the implemented spec
+/// revision, so that such values do not fail parsing. Serializes as `T` when known and as the
+/// raw byte otherwise.
+pub enum Coded<T> {
+    Known(T),
+    Unknown(u8),
+}
+impl<T: FromByte> Coded<T> {
+    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let value = cursor
+            .read_u8()
+            .with_context(|| format!("Failed to read {}", std::any::type_name::<T>()))?;
+        Ok(Self::from_byte(value))
+    }
+
+    pub fn from_byte(value: u8) -> Self {
+        match T::from_byte(value) {
+            Some(known) => Coded::Known(known),
+            None => {
+                log::warn!("Unknown {} value: {}", std::any::type_name::<T>(), value);
+                Coded::Unknown(value)
+            }
+        }
+    }
+}
+impl<T> Coded<T> {
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            Coded::Known(known) => Some(known),
+            Coded::Unknown(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct BCDString(pub String);
 /// [BCDString: appendix 2.7.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16562)
@@ -29,12 +74,103 @@ impl BCDString {
             .iter()
             .map(|&byte| format!("{:02X}", byte))
             .collect::<String>();
+        debug_assert_eq!(bcd_string.len(), size * 2);
 
         Ok(BCDString(bcd_string))
     }
+
+    /// Packs the digits back into bytes, two per byte, the inverse of [`Self::parse_dyn_size`].
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        if !self.0.len().is_multiple_of(2) {
+            anyhow::bail!("BCDString has an odd number of digits: {}", self.0);
+        }
+        (0..self.0.len())
+            .step_by(2)
+            .map(|index| {
+                u8::from_str_radix(&self.0[index..index + 2], 16)
+                    .with_context(|| format!("Invalid BCDString digits: {}", self.0))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Eq, Hash)]
+/// How decoded text fields are post-processed, see
+/// [`crate::vu_parser::VuParserBuilder::string_policy`].
+pub enum StringPolicy {
+    /// Trims the value and removes padding, control and replacement characters.
+    #[default]
+    Clean,
+    /// Keeps the decoded value as is, e.g. to inspect the padding a manufacturer uses.
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Eq, Hash)]
+/// What happens to activity changes at minute 1440 or later, past the end of their day, see
+/// [`crate::card_parser::CardParser::activity_minutes_policy`]. Invalid minutes corrupt daily
+/// aggregations, e.g. [`crate::analysis::daily_activity_totals`]. They are reported in
+/// [`crate::diagnostics::CardDiagnostics::invalid_fields`], or
+/// [`crate::diagnostics::Diagnostics::invalid_fields`] for VU files, whatever the policy.
+pub enum ActivityMinutesPolicy {
+    #[default]
+    Keep,
+    /// Moves the change to the last minute of the day.
+    Clamp,
+    /// Leaves the change out of its daily record.
+    Drop,
+}
+
+/// Reads `size` bytes of a signature or certificate, or only moves past them, see
+/// [`crate::vu_parser::VuParserBuilder::skip_signatures`].
+pub(crate) fn read_signature_bytes(cursor: &mut Cursor<&[u8]>, size: usize) -> Result<Vec<u8>> {
+    if crate::context::parse_settings().skip_signatures {
+        let remaining = (cursor.get_ref().len() as u64).saturating_sub(cursor.position());
+        if remaining < size as u64 {
+            let eof = std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+            return Err(anyhow::Error::new(eof).context(format!(
+                "Unexpected end of data: {} bytes needed, {} remain",
+                size, remaining
+            )));
+        }
+        cursor.set_position(cursor.position() + size as u64);
+        return Ok(Vec::new());
+    }
+    let mut buffer = vec![0u8; size];
+    cursor.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decodes an optional field with `parse`, mapping a failure to `None`.
+///
+/// A failure on bytes that are present, as opposed to left empty with `0x00` or `0xFF` or cut
+/// off by the end of the data, is reported in [`crate::diagnostics::Diagnostics::invalid_fields`],
+/// or in [`crate::diagnostics::CardDiagnostics::invalid_fields`] for card files.
+pub(crate) fn parse_optional<T>(
+    cursor: &mut Cursor<&[u8]>,
+    field: &str,
+    parse: impl FnOnce(&mut Cursor<&[u8]>) -> Result<T>,
+) -> Option<T> {
+    let start = cursor.position() as usize;
+    let err = match parse(cursor) {
+        Ok(value) => return Some(value),
+        Err(err) => err,
+    };
+    let data = *cursor.get_ref();
+    let end = (cursor.position() as usize).min(data.len());
+    let raw = data.get(start..end).unwrap_or_default();
+    let absent = raw.iter().all(|b| *b == 0x00) || raw.iter().all(|b| *b == 0xFF);
+    let ran_out_of_data = err.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof)
+    });
+    if !absent && !ran_out_of_data {
+        crate::context::record_invalid_field(field, raw, &err);
+    }
+    None
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct IA5String(pub String);
 impl IA5String {
@@ -93,6 +229,9 @@ impl IA5String {
         "\u{200F}",      // Right-to-Left Mark
     ];
     fn clean_string(value: String) -> String {
+        if crate::context::parse_settings().string_policy == StringPolicy::Raw {
+            return value;
+        }
         let mut value = value.trim().to_string();
         for invalid in Self::INVALID_EMPTY_CHARS.iter() {
             value = value.replace(invalid, "");
@@ -147,7 +286,7 @@ impl IA5String {
         Ok(IA5String(value))
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [EmbedderIcAssemblerId: appendix 2.65.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20005)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -178,7 +317,7 @@ impl EmbedderIcAssemblerId {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [CardReplacementIndex: appendix 2.31.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17853)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardReplacementIndex(pub IA5String);
@@ -190,7 +329,7 @@ impl CardReplacementIndex {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [CardConsecutiveIndex: appendix 2.14.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16973)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardConsecutiveIndex(pub IA5String);
@@ -202,7 +341,7 @@ impl CardConsecutiveIndex {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [CardRenewalIndex: appendix 2.30.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17812)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardRenewalIndex(pub IA5String);
@@ -214,7 +353,7 @@ impl CardRenewalIndex {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
@@ -234,9 +373,16 @@ pub enum CardNumber {
         card_replacement_index: CardReplacementIndex,
         card_renewal_index: CardRenewalIndex,
     },
+    /// A card number matching neither layout, kept as read, see [`Self::parse_owner_tolerant`].
+    #[serde(rename_all = "camelCase")]
+    Raw {
+        raw: Vec<u8>,
+    },
     None,
 }
 impl CardNumber {
+    const SIZE: usize = 16;
+
     // This method is only used to consume the null bytes
     pub fn parse_unknown(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let _ = cursor
@@ -269,12 +415,47 @@ impl CardNumber {
             card_renewal_index,
         })
     }
+
+    /// Parses a control or company card number recorded by a VU. These have the owner layout,
+    /// but some VUs write the three trailing index characters as binary values (`0x01` rather
+    /// than `'1'`), which are read as the digits they stand for. When the indexes are neither and
+    /// the number is not empty, the 16 bytes are kept as [`CardNumber::Raw`] and reported as an invalid field rather than
+    /// decoded into a garbled number.
+    pub fn parse_owner_tolerant(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
+        let position = cursor.position() as usize;
+        let raw = cursor
+            .get_ref()
+            .get(position..position + Self::SIZE)
+            .context("Failed to read CardNumber")?;
+        cursor.set_position((position + Self::SIZE) as u64);
+
+        // CardConsecutiveIndex is a digit or a letter, the replacement and renewal indexes digits
+        let indexes = &raw[13..];
+        let empty = raw.iter().all(|&byte| byte == 0x00) || raw.iter().all(|&byte| byte == 0xFF);
+        if empty
+            || indexes[0].is_ascii_alphanumeric() && indexes[1..].iter().all(u8::is_ascii_digit)
+        {
+            return Self::parse_owner(&mut Cursor::new(raw));
+        }
+        if indexes.iter().all(|&index| index <= 9) {
+            let mut ascii = raw.to_vec();
+            ascii[13..].iter_mut().for_each(|index| *index += b'0');
+            return Self::parse_owner(&mut Cursor::new(&ascii[..]));
+        }
+        let err = anyhow::anyhow!("Card number indexes {:02X?} are not valid", indexes);
+        crate::context::record_invalid_field("card_number", raw, &err);
+        Ok(CardNumber::Raw { raw: raw.to_vec() })
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 /// [TimeReal: appendix 2.162.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24993)
 #[cfg_attr(feature = "ts", derive(TS))]
-pub struct TimeReal(pub DateTime<Utc>);
+pub struct TimeReal(
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub DateTime<Utc>,
+);
 // TODO: Determine what timezone is used in the DDD files
 // According to @mpi-wl, the timezone is UTC, see https://github.com/jugglingcats/tachograph-cursor/issues/54#issuecomment-603089791
 impl TimeReal {
@@ -301,7 +482,45 @@ impl TimeReal {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Records that carry the time they were recorded at, the order to sort and merge them by.
+///
+/// Not an `Ord` on the records themselves, different records can share a timestamp and that
+/// order would disagree with their `Eq`.
+pub trait Timestamped {
+    fn timestamp(&self) -> &TimeReal;
+}
+
+/// Sorts records oldest first, keeping the download order of records with the same timestamp.
+pub fn sort_by_timestamp<T: Timestamped>(records: &mut [T]) {
+    records.sort_by(|a, b| a.timestamp().cmp(b.timestamp()));
+}
+
+macro_rules! impl_timestamped {
+    ($($record:ty => $field:ident),* $(,)?) => {
+        $(impl Timestamped for $record {
+            fn timestamp(&self) -> &TimeReal {
+                &self.$field
+            }
+        })*
+    };
+}
+impl_timestamped!(
+    gen1::SpecificConditionRecord => entry_time,
+    gen1::PlaceRecord => entry_time,
+    gen1::CardEventRecord => event_begin_time,
+    gen1::CardFaultRecord => fault_begin_time,
+    gen1::CardVehicleRecord => vehicle_first_use,
+    gen2::PlaceRecordGen2 => entry_time,
+    gen2::SpecificConditionRecordGen2 => entry_time,
+    gen2::CardEventRecordGen2 => event_begin_time,
+    gen2::CardFaultRecordGen2 => fault_begin_time,
+    gen2::CardVehicleRecordGen2 => vehicle_first_use,
+    gen2::CardVehicleUnitRecordGen2 => time_stamp,
+    gen2::GNSSAccumulatedDrivingRecordGen2 => time_stamp,
+    gen2::GNSSPlaceRecordGen2 => time_stamp,
+);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [CurrentDateTime: appendix 2.54.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19437)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CurrentDateTime(pub TimeReal);
@@ -311,7 +530,7 @@ impl CurrentDateTime {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [CardApprovalNumber: appendix 2.11.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16800)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardApprovalNumber(pub IA5String);
@@ -322,7 +541,7 @@ impl CardApprovalNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [WVehicleCharacteristicConstant: appendix 2.239.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e29395)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct WVehicleCharacteristicConstant(pub u16);
@@ -335,7 +554,7 @@ impl WVehicleCharacteristicConstant {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [KConstantOfRecordingEquipment: appendix 2.85.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21927)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct KConstantOfRecordingEquipment(pub u16);
@@ -348,7 +567,7 @@ impl KConstantOfRecordingEquipment {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardStructureVersion: appendix 2.36.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18081)
 pub enum CardStructureVersion {
@@ -383,7 +602,7 @@ impl CardStructureVersion {
         }
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [LTyreCircumference: appendix 2.91.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22169)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct LTyreCircumference(pub u16);
@@ -396,7 +615,7 @@ impl LTyreCircumference {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [TyreSize: appendix 2.163.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25026)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct TyreSize(pub IA5String);
@@ -406,14 +625,22 @@ impl TyreSize {
         Ok(TyreSize(value))
     }
 }
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [Speed: appendix 2.155.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24822)
 #[cfg_attr(feature = "ts", derive(TS))]
-pub struct Speed(pub u8);
+pub struct Speed(pub Kmh);
 impl Speed {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let value = cursor.read_u8().context("Failed to read value for Speed")?;
-        Ok(Speed(value))
+        Ok(Speed(Kmh(value)))
+    }
+
+    /// Value used for seconds without speed data, e.g. in detailed speed blocks.
+    pub const NO_DATA: u8 = 0xFF;
+
+    /// Speed in km/h, `None` for [`Speed::NO_DATA`].
+    pub fn value(&self) -> Option<u8> {
+        (self.0 .0 != Self::NO_DATA).then_some(self.0 .0)
     }
 }
 
@@ -429,7 +656,7 @@ pub type SpeedMax = Speed;
 /// [OverspeedNumber: appendix 2.116.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23023)
 pub type OverspeedNumber = Speed;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [Name: appendix 2.299.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22398)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -446,7 +673,7 @@ impl Name {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [Address: appendix 2.2.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16375)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -469,7 +696,7 @@ pub type VuManufacturerName = Name;
 /// [VuManufacturerAddress: appendix 2.209.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27911)
 pub type VuManufacturerAddress = Address;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [VuSoftwareVersion: appendix 2.226.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28569)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuSoftwareVersion(pub IA5String);
@@ -483,7 +710,7 @@ impl VuSoftwareVersion {
 /// [VuSoftInstallationDate: appendix 2.224.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28515)
 pub type VuSoftInstallationDate = TimeReal;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [VuSoftwareIdentification: appendix 2.225.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28538)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -506,7 +733,7 @@ impl VuSoftwareIdentification {
 /// [VuManufacturingDate: appendix 2.211.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e27955)
 pub type VuManufacturingDate = TimeReal;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [SimilarEventsNumber: appendix 2.151.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24591)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct SimilarEventsNumber(pub u8);
@@ -519,7 +746,7 @@ impl SimilarEventsNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [EventFaultRecordPurpose: appendix 2.69.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20262)
 pub enum EventFaultRecordPurpose {
@@ -531,7 +758,8 @@ pub enum EventFaultRecordPurpose {
     OneOfFiveMostSeriousEventsLastYear,
     FirstEventAfterLastCalibration,
     ActiveOrOngoing,
-    RFU,
+    /// Reserved value, kept as read.
+    RFU(u8),
     ManufacturerSpecific,
 }
 impl EventFaultRecordPurpose {
@@ -548,14 +776,14 @@ impl EventFaultRecordPurpose {
             0x05 => Self::OneOfFiveMostSeriousEventsLastYear,
             0x06 => Self::FirstEventAfterLastCalibration,
             0x07 => Self::ActiveOrOngoing,
-            0x08..=0x7F => Self::RFU,
+            0x08..=0x7F => Self::RFU(value),
             0x80..=0xFF => Self::ManufacturerSpecific,
         };
         Ok(parsed_value)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [VehicleIdentificationNumber: appendix 2.165.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25052)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VehicleIdentificationNumber(pub IA5String);
@@ -567,7 +795,7 @@ impl VehicleIdentificationNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [VehicleRegistrationNumber: appendix 2.168.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25188)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -587,7 +815,7 @@ impl VehicleRegistrationNumber {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum CardSlotStatus {
     NoCardInserted,
@@ -596,13 +824,38 @@ pub enum CardSlotStatus {
     ControlCardInserted,
     CompanyCardInserted,
 }
-#[derive(Debug, Serialize, Deserialize)]
+impl CardSlotStatus {
+    /// The 4 bit code of the slot in [`CardSlotsStatus`].
+    pub fn encode(&self) -> u8 {
+        match self {
+            CardSlotStatus::NoCardInserted => 0b0000,
+            CardSlotStatus::DriverCardInserted => 0b0001,
+            CardSlotStatus::WorkshopCardInserted => 0b0010,
+            CardSlotStatus::ControlCardInserted => 0b0011,
+            CardSlotStatus::CompanyCardInserted => 0b0100,
+        }
+    }
+}
+impl FromByte for CardSlotStatus {
+    fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0b0000 => Some(CardSlotStatus::NoCardInserted),
+            0b0001 => Some(CardSlotStatus::DriverCardInserted),
+            0b0010 => Some(CardSlotStatus::WorkshopCardInserted),
+            0b0011 => Some(CardSlotStatus::ControlCardInserted),
+            0b0100 => Some(CardSlotStatus::CompanyCardInserted),
+            _ => None,
+        }
+    }
+}
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardSlotsStatus: appendix 2.34.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17939)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct CardSlotsStatus {
-    pub codriver: CardSlotStatus,
-    pub driver: CardSlotStatus,
+    /// Unknown nibbles are kept as read, in the low 4 bits.
+    pub codriver: Coded<CardSlotStatus>,
+    pub driver: Coded<CardSlotStatus>,
 }
 impl CardSlotsStatus {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
@@ -610,32 +863,25 @@ impl CardSlotsStatus {
             .read_u8()
             .context("Failed to read card slots status")?;
 
-        let bits = extract_u8_bits_into_tup(status);
-        let codriver_status = match bits {
-            (0, 0, 0, 0, _, _, _, _) => CardSlotStatus::NoCardInserted,
-            (0, 0, 0, 1, _, _, _, _) => CardSlotStatus::DriverCardInserted,
-            (0, 0, 1, 0, _, _, _, _) => CardSlotStatus::WorkshopCardInserted,
-            (0, 0, 1, 1, _, _, _, _) => CardSlotStatus::ControlCardInserted,
-            (0, 1, 0, 0, _, _, _, _) => CardSlotStatus::CompanyCardInserted,
-            _ => anyhow::bail!("Invalid codriver slot status"),
+        let card_slots_status = CardSlotsStatus {
+            codriver: Coded::from_byte(status >> 4),
+            driver: Coded::from_byte(status & 0x0F),
         };
+        debug_assert_eq!(card_slots_status.encode(), status);
+        Ok(card_slots_status)
+    }
 
-        let driver_status = match bits {
-            (_, _, _, _, 0, 0, 0, 0) => CardSlotStatus::NoCardInserted,
-            (_, _, _, _, 0, 0, 0, 1) => CardSlotStatus::DriverCardInserted,
-            (_, _, _, _, 0, 0, 1, 0) => CardSlotStatus::WorkshopCardInserted,
-            (_, _, _, _, 0, 0, 1, 1) => CardSlotStatus::ControlCardInserted,
-            (_, _, _, _, 0, 1, 0, 0) => CardSlotStatus::CompanyCardInserted,
-            _ => anyhow::bail!("Invalid driver slot status"),
+    /// Encodes the status into its 'ccccdddd'B byte, the inverse of [`Self::parse`].
+    pub fn encode(&self) -> u8 {
+        let encode = |slot: &Coded<CardSlotStatus>| match slot {
+            Coded::Known(status) => status.encode(),
+            Coded::Unknown(value) => *value,
         };
-        Ok(CardSlotsStatus {
-            codriver: codriver_status,
-            driver: driver_status,
-        })
+        encode(&self.codriver) << 4 | encode(&self.driver)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [HolderName: appendix 2.83.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21860)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -652,32 +898,28 @@ impl HolderName {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [CardSlotNumber: appendix 2.33.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17911)
 pub enum CardSlotNumber {
     DriverSlot,
     CoDriverSlot,
 }
-impl CardSlotNumber {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let value = cursor
-            .read_u8()
-            .context("Failed to read card_slot_number value")?;
-        let card_slot = match value {
-            0 => CardSlotNumber::DriverSlot,
-            1 => CardSlotNumber::CoDriverSlot,
-            _ => anyhow::bail!("Invalid card slot number"),
-        };
-        Ok(card_slot)
+impl FromByte for CardSlotNumber {
+    fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(CardSlotNumber::DriverSlot),
+            1 => Some(CardSlotNumber::CoDriverSlot),
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [OdometerShort: appendix 2.113.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22854)
 /// Value in km in the operating range 0 to 9 999 999 km.
 #[cfg_attr(feature = "ts", derive(TS))]
-pub struct OdometerShort(pub u32);
+pub struct OdometerShort(pub Kilometers);
 impl OdometerShort {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let mut km_buffer = [0u8; 3];
@@ -686,13 +928,13 @@ impl OdometerShort {
             .context("Failed to read odometer short km value")?;
         // odometer short is 3 bytes, so we must pad the buffer with 1 byte to use a u32
         let km = u32::from_be_bytes([0, km_buffer[0], km_buffer[1], km_buffer[2]]);
-        Ok(OdometerShort(km))
+        Ok(OdometerShort(Kilometers(km)))
     }
 }
 /// [OdometerValueMidnight: appendix 2.114.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22880)
 pub type OdometerValueMidnight = OdometerShort;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [VehicleRegistrationIdentification: appendix 2.116.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25120)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -711,35 +953,31 @@ impl VehicleRegistrationIdentification {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 /// [ManualInputFlag: appendix 2.93.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22225)
 pub enum ManualInputFlag {
     NoEntry,
     ManualEntries,
 }
-impl ManualInputFlag {
-    pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let manual_input_flag = cursor
-            .read_u8()
-            .context("Failed to read manual input flag")?;
-        let manual_input_flag = match manual_input_flag {
-            0 => ManualInputFlag::NoEntry,
-            1 => ManualInputFlag::ManualEntries,
-            _ => anyhow::bail!("Invalid manual input flag"),
-        };
-        Ok(manual_input_flag)
+impl FromByte for ManualInputFlag {
+    fn from_byte(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(ManualInputFlag::NoEntry),
+            1 => Some(ManualInputFlag::ManualEntries),
+            _ => None,
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum ActivityChangeInfoSlot {
     Driver,
     CoDriver,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum ActivityChangeInfoStatus {
     Single,  // When p=0, c=0
@@ -748,14 +986,14 @@ pub enum ActivityChangeInfoStatus {
     Known,   // When p=1, c=1
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum ActivityChangeInfoCardStatus {
     Inserted,    // p=0
     NotInserted, // p=1
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum ActivityChangeInfoCardActivity {
     BreakRest,    // aa=00
@@ -764,7 +1002,7 @@ pub enum ActivityChangeInfoCardActivity {
     Driving,      // aa=11
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [ActivityChangeInfo: appendix 2.1.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16027)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -773,14 +1011,16 @@ pub struct CardActivityChangeInfo {
     pub driving_or_following_activity_status: ActivityChangeInfoStatus,
     pub card_status: ActivityChangeInfoCardStatus,
     pub activity: ActivityChangeInfoCardActivity,
-    pub minutes: u16,
+    pub minutes: Minutes,
 }
 
 impl CardActivityChangeInfo {
     pub const SIZE: usize = 2;
+    const MINUTES_PER_DAY: u16 = 24 * 60;
 
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(Self::SIZE);
+        let inner_cursor = &mut cursor.take_exact(Self::SIZE)?;
+        let raw = *inner_cursor.get_ref();
         let value_buffer = inner_cursor
             .read_u16::<BigEndian>()
             .context("Failed to read activity change info")?;
@@ -859,17 +1099,69 @@ impl CardActivityChangeInfo {
         // As a result of a manual entry, the bits 'c' and 'aa' of the word (stored in
         // a card) may be overwritten later to reflect the entry.
 
-        Ok(CardActivityChangeInfo {
+        let mut change_info = CardActivityChangeInfo {
             slot,
             driving_or_following_activity_status,
             card_status,
             activity,
-            minutes,
-        })
+            minutes: Minutes(minutes),
+        };
+        // every bit of the word is decoded, so encoding must give it back unchanged
+        debug_assert_eq!(change_info.encode(), value_buffer);
+
+        if minutes >= Self::MINUTES_PER_DAY {
+            let err = anyhow::anyhow!(
+                "Activity change at minute {} is past the end of the day",
+                minutes
+            );
+            crate::context::record_invalid_field("minutes", raw, &err);
+            let policy = crate::context::parse_settings().activity_minutes_policy;
+            if policy == ActivityMinutesPolicy::Clamp {
+                change_info.minutes = Minutes(Self::MINUTES_PER_DAY - 1);
+            }
+        }
+        Ok(change_info)
+    }
+
+    /// Removes the changes past the end of their day when the [`ActivityMinutesPolicy`] of the
+    /// parse drops them.
+    pub(crate) fn drop_past_end_of_day(
+        mut changes: Vec<CardActivityChangeInfo>,
+    ) -> Vec<CardActivityChangeInfo> {
+        if crate::context::parse_settings().activity_minutes_policy == ActivityMinutesPolicy::Drop {
+            changes.retain(|change| change.minutes.0 < Self::MINUTES_PER_DAY);
+        }
+        changes
+    }
+
+    /// Encodes the change into its 'scpaattttttttttt'B word, the inverse of [`Self::parse`].
+    /// 'c' is taken from `driving_or_following_activity_status` and 'p' from `card_status`, so a
+    /// status that does not match the card status (e.g. Crew while not inserted) is not preserved.
+    /// Minutes past the 11 bits of the time field are cut off.
+    pub fn encode(&self) -> u16 {
+        let s = match self.slot {
+            ActivityChangeInfoSlot::Driver => 0,
+            ActivityChangeInfoSlot::CoDriver => 1,
+        };
+        let c = match self.driving_or_following_activity_status {
+            ActivityChangeInfoStatus::Single | ActivityChangeInfoStatus::Unknown => 0,
+            ActivityChangeInfoStatus::Crew | ActivityChangeInfoStatus::Known => 1,
+        };
+        let p = match self.card_status {
+            ActivityChangeInfoCardStatus::Inserted => 0,
+            ActivityChangeInfoCardStatus::NotInserted => 1,
+        };
+        let a = match self.activity {
+            ActivityChangeInfoCardActivity::BreakRest => 0b00,
+            ActivityChangeInfoCardActivity::Availability => 0b01,
+            ActivityChangeInfoCardActivity::Work => 0b10,
+            ActivityChangeInfoCardActivity::Driving => 0b11,
+        };
+        s << 15 | c << 14 | p << 13 | a << 11 | (self.minutes.0 & 0x07FF)
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardChipIdentification: appendix 2.1.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16027)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -893,9 +1185,18 @@ impl CardChipIdentification {
             card_chip_identification_signature: card_chip_identification_signature.to_vec(),
         })
     }
+
+    /// IC serial number followed by the IC manufacturing references, as uppercase hex.
+    pub fn to_hex(&self) -> String {
+        self.card_chip_identification_number
+            .iter()
+            .chain(&self.card_chip_identification_signature)
+            .map(|byte| format!("{:02X}", byte))
+            .collect()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [Datef: appendix 2.63.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20100)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -922,7 +1223,7 @@ impl Datef {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct Language(pub IA5String);
 impl Language {
@@ -930,9 +1231,14 @@ impl Language {
         let value = IA5String::parse_dyn_size(cursor, 2).context("Failed to parse Language")?;
         Ok(Language(value))
     }
+
+    /// The ISO 639-1 code, `None` when the card holds anything else, e.g. padding.
+    pub fn code(&self) -> Option<crate::locale::LanguageCode> {
+        self.0 .0.trim().parse().ok()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardIdentification: appendix 2.24.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17430)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -965,7 +1271,7 @@ impl CardIdentification {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [DriverCardHolderIdentification: appendix 2.62.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19928)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -987,7 +1293,7 @@ impl DriverCardHolderIdentification {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [Identification: appendix 4.2.2.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e41651)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1009,7 +1315,7 @@ impl Identification {
 /// [LastCardDownload: appendix 2.89.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22044)
 pub type LastCardDownload = TimeReal;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardDownload: appendix 4.2.2.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e41651)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1018,14 +1324,14 @@ pub struct CardDownload {
 }
 impl CardDownload {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
-        let inner_cursor = &mut cursor.take_exact(LastCardDownload::SIZE);
+        let inner_cursor = &mut cursor.take_exact(LastCardDownload::SIZE)?;
 
         let last_card_download = LastCardDownload::parse(inner_cursor).ok();
         Ok(CardDownload { last_card_download })
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardDrivingLicenceInformation: appendix 2.18.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17139)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1047,7 +1353,7 @@ impl CardDrivingLicenceInformation {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// EF Block page 281
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1063,7 +1369,7 @@ impl CardDrivingLicenceInfo {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [DailyPresenceCounter: appendix 2.56.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19510)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct DailyPresenceCounter(pub u16);
@@ -1078,24 +1384,24 @@ impl DailyPresenceCounter {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [Distance: appendix 2.60.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19665)
 /// Value in km in the operational range 0 to 9 999 km.
 #[cfg_attr(feature = "ts", derive(TS))]
-pub struct Distance(pub u16);
+pub struct Distance(pub Kilometers);
 impl Distance {
     pub fn parse(cursor: &mut Cursor<&[u8]>) -> Result<Self> {
         let km = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read distance")?;
-        Ok(Distance(km))
+        Ok(Distance(Kilometers(km as u32)))
     }
 }
 
 /// [CardActivityLengthRange: appendix 2.10.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16777)
 pub type CardActivityLengthRange = u16;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardDriverActivity: appendix 2.9.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16718)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1122,13 +1428,26 @@ impl CardActivityDailyRecord {
         let activity_record_length: CardActivityLengthRange = cursor
             .read_u16::<BigEndian>()
             .context("Failed to read activity_record_length")?;
+        if (activity_record_length as usize) < Self::SIZE_OF_METADATA {
+            anyhow::bail!(
+                "Invalid activity_record_length {}, expected at least {} bytes of metadata",
+                activity_record_length,
+                Self::SIZE_OF_METADATA
+            );
+        }
 
         let activity_record_date = TimeReal::parse(cursor)?;
         let activity_daily_presence_counter = DailyPresenceCounter::parse(cursor)?;
         let activity_day_distance = Distance::parse(cursor)?;
 
-        let records_amount = (activity_record_length as usize - Self::SIZE_OF_METADATA)
-            / CardActivityChangeInfo::SIZE;
+        // Never trust the declared length more than the bytes we actually have left
+        let remaining_bytes = cursor
+            .get_ref()
+            .len()
+            .saturating_sub(cursor.position() as usize);
+        let records_amount = ((activity_record_length as usize - Self::SIZE_OF_METADATA)
+            / CardActivityChangeInfo::SIZE)
+            .min(remaining_bytes / CardActivityChangeInfo::SIZE);
 
         let mut activity_change_info = Vec::with_capacity(records_amount);
         for _ in 0..records_amount {
@@ -1136,6 +1455,8 @@ impl CardActivityDailyRecord {
                 activity_change_info.push(record);
             }
         }
+        let activity_change_info =
+            CardActivityChangeInfo::drop_past_end_of_day(activity_change_info);
 
         Ok(CardActivityDailyRecord {
             activity_previous_record_length,
@@ -1148,7 +1469,7 @@ impl CardActivityDailyRecord {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardDriverActivity: appendix 2.17.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17092)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1166,7 +1487,9 @@ impl CardDriverActivity {
             .read_u16::<BigEndian>()
             .context("Failed to read activity_pointer_newest_record")?;
 
-        let size = size - cursor.position() as usize;
+        let size = size
+            .checked_sub(cursor.position() as usize)
+            .context("CardDriverActivity is shorter than its pointers")?;
 
         // Read the entire cyclic data block
         let mut cyclic_data = vec![0u8; size];
@@ -1197,10 +1520,21 @@ impl CardDriverActivity {
         size: usize,
     ) -> Result<Vec<u8>> {
         // Get the length of the newest record
-        let newest_record_length = u16::from_be_bytes([
-            cyclic_data[newest_record as usize + 2],
-            cyclic_data[newest_record as usize + 3],
-        ]) as usize;
+        let newest_record_length = match cyclic_data.get(newest_record + 2..newest_record + 4) {
+            Some(&[high, low]) => u16::from_be_bytes([high, low]) as usize,
+            _ => anyhow::bail!(
+                "activity_pointer_newest_record {} is outside of the {} bytes of cyclic data",
+                newest_record,
+                size
+            ),
+        };
+        if oldest_record > size {
+            anyhow::bail!(
+                "activity_pointer_oldest_day_record {} is outside of the {} bytes of cyclic data",
+                oldest_record,
+                size
+            );
+        }
 
         // Calculate the end position of the newest record
         let end_of_newest_record = (newest_record + newest_record_length) % size;
@@ -1229,12 +1563,29 @@ impl CardDriverActivity {
         let mut records = Vec::new();
 
         while cursor.position() < data.len() as u64 {
+            let record_start = cursor.position() as usize;
+            // activity_record_length follows activity_previous_record_length
+            let record_length = data
+                .get(record_start + 2..record_start + 4)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize);
+
             match CardActivityDailyRecord::parse(&mut cursor) {
                 Ok(record) => records.push(record),
-                Err(e) => {
-                    log::warn!("Failed to parse daily record: {:?}", e);
-                    break;
-                }
+                Err(e) => match record_length {
+                    // The record boundary is still known, so skip the malformed record and keep going
+                    Some(length) if length >= CardActivityDailyRecord::SIZE_OF_METADATA => {
+                        log::warn!(
+                            "Skipping malformed daily record at offset {}: {:?}",
+                            record_start,
+                            e
+                        );
+                        cursor.set_position((record_start + length) as u64);
+                    }
+                    _ => {
+                        log::warn!("Failed to parse daily record: {:?}", e);
+                        break;
+                    }
+                },
             }
         }
 
@@ -1242,7 +1593,7 @@ impl CardDriverActivity {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// EF Block page 281
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1258,7 +1609,7 @@ impl DriverActivityData {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [VuDataBlockCounter: appendix 2.189.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26512)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuDataBlockCounter(pub u16);
@@ -1278,7 +1629,7 @@ impl VuDataBlockCounter {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [CardCurrentUse appendix 2.16.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17059)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1297,7 +1648,7 @@ impl CardCurrentUse {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// EF Block page 281
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1311,7 +1662,7 @@ impl CurrentUsage {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct MonthYear {
@@ -1332,7 +1683,7 @@ impl MonthYear {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [VuDownloadablePeriod: appendix 2.193](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26674)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1354,7 +1705,7 @@ impl VuDownloadablePeriod {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 /// [VuDetailedSpeedBlock: appendix 2.224.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26534)
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -1380,7 +1731,7 @@ impl VuDetailedSpeedBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 /// [VuPartNumber: appendix 2.217.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e28257)
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuPartNumber(pub IA5String);
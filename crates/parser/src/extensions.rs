@@ -0,0 +1,149 @@
+//! Decoding of manufacturer-specific card EFs by the integrator.
+//!
+//! Some card manufacturers add proprietary EFs after the standard set. The parser keeps them as
+//! [`RawCardEf`]s, and parsers registered in an [`ExtensionRegistry`] for the manufacturer code of
+//! the card and the file identifier of the EF decode them into JSON values, which end up in
+//! [`crate::card_parser::CardData::extensions`]:
+//!
+//! ```no_run
+//! use tachograph_parser::card_parser::CardParser;
+//! use tachograph_parser::extensions::ExtensionRegistry;
+//!
+//! let mut registry = ExtensionRegistry::default();
+//! registry.register(0x21, 0x0601, |ef| Ok(serde_json::json!({ "length": ef.data.len() })));
+//! let card_data = CardParser::new_from_bytes(&std::fs::read("driver.ddd")?)?
+//!     .extensions(registry)
+//!     .parse()?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::card_parser::RawCardEf;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::RefUnwindSafe;
+use std::sync::Arc;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+type ExtensionParser = dyn Fn(&RawCardEf) -> Result<Value> + Send + Sync + RefUnwindSafe;
+
+/// Parsers for manufacturer-specific EFs, keyed by the manufacturer code of the card (the
+/// `manufacturer_code` of EF_ICC's `card_extended_serial_number`) and the EF's file identifier.
+/// Cloning is cheap, the parsers are shared.
+#[derive(Clone, Default)]
+pub struct ExtensionRegistry {
+    parsers: HashMap<(u8, u16), Arc<ExtensionParser>>,
+}
+impl ExtensionRegistry {
+    /// Registers `parser` for the EF `sfid` of cards by `manufacturer_code`, replacing the
+    /// parser registered before for the same key. It is called for the EF of each DF the
+    /// file holds, [`RawCardEf::file_id`] tells them apart.
+    pub fn register<F>(&mut self, manufacturer_code: u8, sfid: u16, parser: F) -> &mut Self
+    where
+        F: Fn(&RawCardEf) -> Result<Value> + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.parsers
+            .insert((manufacturer_code, sfid), Arc::new(parser));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    /// Runs the registered parsers over the EFs the card parser kept as raw bytes, in file order.
+    /// EFs without a parser are left out.
+    pub fn apply(&self, manufacturer_code: u8, raw_efs: &[RawCardEf]) -> Vec<CardExtension> {
+        raw_efs
+            .iter()
+            .filter_map(|ef| {
+                let parser = self.parsers.get(&(manufacturer_code, ef.sfid))?;
+                let (value, error) = match parser(ef) {
+                    Ok(value) => (Some(value), None),
+                    Err(err) => {
+                        log::warn!(
+                            "Extension parser for EF {:#06x} (file_id {}) failed: {:#}",
+                            ef.sfid,
+                            ef.file_id,
+                            err
+                        );
+                        (None, Some(format!("{:#}", err)))
+                    }
+                };
+                Some(CardExtension {
+                    manufacturer_code,
+                    sfid: ef.sfid,
+                    file_id: ef.file_id,
+                    value,
+                    error,
+                })
+            })
+            .collect()
+    }
+}
+impl fmt::Debug for ExtensionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys: Vec<_> = self.parsers.keys().collect();
+        keys.sort();
+        f.debug_struct("ExtensionRegistry")
+            .field("parsers", &keys)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A manufacturer-specific EF decoded by a parser of the [`ExtensionRegistry`]. The EF itself
+/// is still in `unknown_efs`.
+pub struct CardExtension {
+    pub manufacturer_code: u8,
+    pub sfid: u16,
+    /// Appendix byte of the EF, see [`RawCardEf::file_id`].
+    pub file_id: u8,
+    /// What the parser returned, `None` when it failed.
+    pub value: Option<Value>,
+    /// Error of the parser, which does not fail the parse of the card.
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn raw_ef(sfid: u16, file_id: u8, data: &[u8]) -> RawCardEf {
+        RawCardEf {
+            sfid,
+            file_id,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_apply_by_manufacturer_and_sfid() {
+        let mut registry = ExtensionRegistry::default();
+        registry
+            .register(0x21, 0x0601, |ef| Ok(json!({ "firstByte": ef.data[0] })))
+            .register(0x21, 0x0602, |_| anyhow::bail!("Unsupported layout"));
+        let raw_efs = [
+            raw_ef(0x0601, 0, &[0x2A]),
+            raw_ef(0x0603, 0, &[0x00]),
+            raw_ef(0x0601, 2, &[0x2B]),
+            raw_ef(0x0602, 0, &[]),
+        ];
+
+        let extensions = registry.apply(0x21, &raw_efs);
+        assert_eq!(extensions.len(), 3);
+        assert_eq!((extensions[0].sfid, extensions[0].file_id), (0x0601, 0));
+        assert_eq!(extensions[0].value, Some(json!({ "firstByte": 0x2A })));
+        assert_eq!(extensions[1].value, Some(json!({ "firstByte": 0x2B })));
+        assert_eq!(extensions[2].value, None);
+        assert_eq!(extensions[2].error.as_deref(), Some("Unsupported layout"));
+
+        // the same EF of another manufacturer is not decoded
+        assert!(registry.apply(0x22, &raw_efs).is_empty());
+    }
+}
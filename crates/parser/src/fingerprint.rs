@@ -0,0 +1,153 @@
+use crate::card_parser::{self, EfParseStatus};
+use crate::detector::{self, TachoFileType};
+use crate::sink::{self, ActivityRecord, EventRecord, IdentificationRecord, TachoSink};
+use crate::vu_parser::VuParser;
+use crate::TachoData;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Identifies a download independently of how it was transported, see [`fingerprint`].
+pub struct FileFingerprint {
+    pub file_type: TachoFileType,
+    /// SHA-256 over the EFs (card) or blocks (VU) the parser recognizes, as lowercase hex.
+    /// EF order, empty EFs and data the parser skips do not change it.
+    pub content_hash: String,
+    /// Card number of the card holder, card files only.
+    pub card_number: Option<String>,
+    /// VIN of the vehicle, VU files only.
+    pub vehicle_identification_number: Option<String>,
+    /// Time of the first and last activity change in the file.
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub period_start: Option<DateTime<Utc>>,
+    #[serde(with = "crate::json::optional_timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub period_end: Option<DateTime<Utc>>,
+}
+
+/// Collects the identification and activity period of a file.
+#[derive(Default)]
+struct FingerprintSink {
+    identification: IdentificationRecord,
+    period: Option<(DateTime<Utc>, DateTime<Utc>)>,
+}
+impl TachoSink for FingerprintSink {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()> {
+        self.identification = record.clone();
+        Ok(())
+    }
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()> {
+        self.period = Some(match self.period {
+            Some((start, end)) => (start.min(record.time), end.max(record.time)),
+            None => (record.time, record.time),
+        });
+        Ok(())
+    }
+    fn write_event(&mut self, _record: &EventRecord) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Computes a fingerprint of a card or VU file, to recognize the same download when it is
+/// uploaded again, possibly re-wrapped by another download tool.
+/// The file must parse, since the identifiers are taken from the parsed data.
+pub fn fingerprint(bytes: &[u8]) -> Result<FileFingerprint> {
+    let file_type = detector::detect_from_bytes(bytes).context("Failed to detect file type")?;
+    let mut collector = FingerprintSink::default();
+    let content_hash = match crate::parse_from_bytes(bytes)? {
+        TachoData::Card { card_data } => {
+            sink::write_card_data(&card_data, &mut collector)?;
+            card_content_hash(bytes)?
+        }
+        TachoData::Vu { vu_data } => {
+            sink::write_vu_data(&vu_data, &mut collector)?;
+            vu_content_hash(bytes)?
+        }
+    };
+    Ok(FileFingerprint {
+        file_type,
+        content_hash,
+        card_number: collector.identification.card_number,
+        vehicle_identification_number: collector.identification.vehicle_identification_number,
+        period_start: collector.period.map(|(start, _)| start),
+        period_end: collector.period.map(|(_, end)| end),
+    })
+}
+
+/// Hashes the EFs sorted by SFID and file_id, each with its header.
+fn card_content_hash(bytes: &[u8]) -> Result<String> {
+    let mut efs: Vec<(u16, u8, &[u8])> = card_parser::ef_coverage(bytes)?
+        .into_iter()
+        .filter(|ef| ef.size > 0 && ef.status != EfParseStatus::Skipped)
+        // a truncated last EF is reported too, but has no data to hash
+        .filter_map(|ef| {
+            let start = ef.offset + 5;
+            let data = bytes.get(start..start + ef.size as usize)?;
+            Some((ef.sfid, ef.file_id, data))
+        })
+        .collect();
+    efs.sort();
+    let mut hasher = Sha256::new();
+    for (sfid, file_id, data) in efs {
+        hasher.update(sfid.to_be_bytes());
+        hasher.update([file_id]);
+        hasher.update((data.len() as u16).to_be_bytes());
+        hasher.update(data);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Hashes the recognized blocks in sorted order, each prefixed with its length.
+fn vu_content_hash(bytes: &[u8]) -> Result<String> {
+    let mut blocks: Vec<&[u8]> = VuParser::block_ranges(bytes)?
+        .into_iter()
+        .map(|range| &bytes[range])
+        .collect();
+    blocks.sort();
+    let mut hasher = Sha256::new();
+    for block in blocks {
+        hasher.update((block.len() as u32).to_be_bytes());
+        hasher.update(block);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_unknown_blocks() {
+        let with_unknown = include_bytes!("../tests/fixtures/vu_gen1_unknown_block.ddd");
+        let vu_data = VuParser::new_from_bytes(with_unknown)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let unknown = &vu_data.diagnostics().unknown_blocks[0];
+        let mut without_unknown = with_unknown.to_vec();
+        without_unknown.drain(unknown.offset..unknown.offset + unknown.skipped_bytes);
+
+        let expected = fingerprint(with_unknown).unwrap();
+        let actual = fingerprint(&without_unknown).unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(expected.file_type, TachoFileType::VehicleUnitGen1);
+        assert!(expected.vehicle_identification_number.is_some());
+
+        let other = include_bytes!("../tests/fixtures/vu_gen1_locks_and_controls.ddd");
+        assert_ne!(
+            fingerprint(other).unwrap().content_hash,
+            expected.content_hash
+        );
+    }
+}
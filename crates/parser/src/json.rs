@@ -0,0 +1,448 @@
+use crate::capabilities;
+use crate::context::Scoped;
+use crate::fingerprint::to_hex;
+use crate::locale::{self, Locale};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::io::Write;
+use std::str::FromStr;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// How timestamps are written to JSON.
+pub enum TimestampFormat {
+    /// RFC 3339 string, e.g. `2024-03-01T08:30:00Z`.
+    #[default]
+    Rfc3339,
+    /// Integer seconds since the Unix epoch.
+    UnixSeconds,
+    /// Integer milliseconds since the Unix epoch.
+    UnixMillis,
+}
+impl FromStr for TimestampFormat {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "rfc3339" => Ok(TimestampFormat::Rfc3339),
+            "unix" | "unix-seconds" => Ok(TimestampFormat::UnixSeconds),
+            "unix-millis" => Ok(TimestampFormat::UnixMillis),
+            _ => anyhow::bail!("Unsupported timestamp format: {}", value),
+        }
+    }
+}
+
+thread_local! {
+    static TIMESTAMP_FORMAT: RefCell<TimestampFormat> =
+        const { RefCell::new(TimestampFormat::Rfc3339) };
+}
+
+fn timestamp_format() -> TimestampFormat {
+    TIMESTAMP_FORMAT.with(|format| *format.borrow())
+}
+
+/// `#[serde(with)]` module writing a timestamp in the format of the [`JsonOptions`] being
+/// written on this thread, RFC 3339 outside of [`write_json`].
+pub(crate) mod timestamp {
+    use super::*;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp_format() {
+            TimestampFormat::Rfc3339 => timestamp.serialize(serializer),
+            TimestampFormat::UnixSeconds => serializer.serialize_i64(timestamp.timestamp()),
+            TimestampFormat::UnixMillis => serializer.serialize_i64(timestamp.timestamp_millis()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        DateTime::deserialize(deserializer)
+    }
+}
+
+/// Same as [`timestamp`], for optional timestamps.
+pub(crate) mod optional_timestamp {
+    use super::*;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn serialize<S: Serializer>(
+        timestamp: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => timestamp::serialize(timestamp, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::deserialize(deserializer)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Options applied when serializing parsed card or VU data to JSON.
+pub struct JsonOptions {
+    pub pretty: bool,
+    /// When set, every nation field gets a `<field>Localized` sibling with the country name in this locale.
+    pub nation_locale: Option<Locale>,
+    /// Epoch formats are only understood by consumers; deserializing them back into the parser
+    /// types, and the TypeScript bindings, expect RFC 3339 strings.
+    pub timestamp_format: TimestampFormat,
+    /// Wraps the document in a [`ProvenanceEnvelope`]. Only applies to [`write_json_with_source`]
+    /// and [`to_json_with_source`], which the parsers' `parse_to_json_with_options` use, since
+    /// the hash needs the source file.
+    pub provenance: bool,
+    /// Orders the records of every array by their timestamp, see [`order_records`], so that the
+    /// output of two downloads of the same period can be diffed. The order of the records in the
+    /// file, e.g. of the cyclic card EFs, is lost.
+    pub canonical_order: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Which file and which build of the parser a JSON document was produced from, so that an
+/// archive can later prove where an artifact came from.
+pub struct Provenance {
+    /// SHA-256 of the whole source file, as lowercase hex.
+    pub source_sha256: String,
+    pub source_bytes: usize,
+    pub parser_version: String,
+    /// Optional cargo features the parser was compiled with.
+    pub features: Vec<String>,
+    #[serde(with = "crate::json::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub parsed_at: DateTime<Utc>,
+}
+impl Provenance {
+    /// Provenance of data parsed from `input` now.
+    pub fn new(input: &[u8]) -> Self {
+        Self::from_digest(&SourceDigest::new(input))
+    }
+
+    /// Provenance of data parsed now from the source file `source` was taken of.
+    pub(crate) fn from_digest(source: &SourceDigest) -> Self {
+        Provenance {
+            source_sha256: source.sha256.clone(),
+            source_bytes: source.bytes,
+            parser_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: capabilities::features(),
+            parsed_at: Utc::now(),
+        }
+    }
+}
+
+/// What [`Provenance`] needs of a source file, for parsers that no longer hold it as it was read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceDigest {
+    sha256: String,
+    bytes: usize,
+}
+impl SourceDigest {
+    pub(crate) fn new(input: &[u8]) -> Self {
+        SourceDigest {
+            sha256: to_hex(&Sha256::digest(input)),
+            bytes: input.len(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// A serialized card or VU with its [`Provenance`], see [`JsonOptions::provenance`].
+pub struct ProvenanceEnvelope<T> {
+    pub provenance: Provenance,
+    pub data: T,
+}
+
+pub fn to_json<T: Serialize>(data: &T, options: &JsonOptions) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_json(data, options, &mut buffer)?;
+    String::from_utf8(buffer).context("Failed to convert JSON bytes to string")
+}
+
+/// Serializes straight into `writer`, which avoids holding the whole document in memory.
+/// Localizing nations and ordering records still need an intermediate `serde_json::Value`.
+pub fn write_json<T: Serialize, W: Write>(
+    data: &T,
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    let (document, _guard) = prepare(data, options)?;
+    serialize(&document, options.pretty, writer)
+}
+
+/// `data` as it is written with `options`, see [`prepare`].
+pub(crate) enum Document<'a, T> {
+    Data(&'a T),
+    Value(Value),
+}
+impl<T: Serialize> Serialize for Document<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Document::Data(data) => data.serialize(serializer),
+            Document::Value(value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// Applies `options` to `data`, all but `pretty`. The document has to be serialized while the
+/// returned guard, which sets the timestamp format of this thread, is alive.
+pub(crate) fn prepare<'a, T: Serialize>(
+    data: &'a T,
+    options: &JsonOptions,
+) -> Result<(Document<'a, T>, Scoped<TimestampFormat>)> {
+    if options.nation_locale.is_none() && !options.canonical_order {
+        let guard = Scoped::set(&TIMESTAMP_FORMAT, options.timestamp_format);
+        return Ok((Document::Data(data), guard));
+    }
+    // timestamps are compared as RFC 3339 strings, whatever the format they are written in
+    let reference = if options.canonical_order {
+        Some(serde_json::to_value(data).context("Failed to convert data to serde value")?)
+    } else {
+        None
+    };
+    let guard = Scoped::set(&TIMESTAMP_FORMAT, options.timestamp_format);
+    let mut value = serde_json::to_value(data).context("Failed to convert data to serde value")?;
+    if let Some(reference) = &reference {
+        order_records(&mut value, reference);
+    }
+    if let Some(nation_locale) = options.nation_locale {
+        locale::localize_nations(&mut value, nation_locale);
+    }
+    Ok((Document::Value(value), guard))
+}
+
+/// Sorts every array of `value` whose items all have a timestamp by that timestamp, earliest
+/// first, with items of the same time kept in their order. The timestamp of an item is the first
+/// of its fields, in the alphabetical order of the serialized fields, holding one, e.g.
+/// `eventBeginTime` before `eventEndTime`. It is read from `reference`, the same document
+/// serialized with RFC 3339 timestamps, as epoch timestamps cannot be told apart from other
+/// numbers.
+fn order_records(value: &mut Value, reference: &Value) {
+    match (value, reference) {
+        (Value::Object(map), Value::Object(reference)) => {
+            for (key, child) in map.iter_mut() {
+                if let Some(reference) = reference.get(key) {
+                    order_records(child, reference);
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(reference)) if items.len() == reference.len() => {
+            let mut order: Vec<usize> = (0..items.len()).collect();
+            if let Some(times) = reference
+                .iter()
+                .map(record_time)
+                .collect::<Option<Vec<_>>>()
+            {
+                // stable, ties keep their index order
+                order.sort_by_key(|&index| times[index]);
+            }
+            let mut unordered: Vec<Option<Value>> = items.drain(..).map(Some).collect();
+            for &index in &order {
+                let mut item = unordered[index].take().unwrap_or_default();
+                order_records(&mut item, &reference[index]);
+                items.push(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// First RFC 3339 timestamp among the fields of a record.
+fn record_time(record: &Value) -> Option<DateTime<Utc>> {
+    record.as_object()?.values().find_map(|field| {
+        let time = DateTime::parse_from_rfc3339(field.as_str()?).ok()?;
+        Some(time.with_timezone(&Utc))
+    })
+}
+
+/// Same as [`write_json`], with `data` wrapped in a [`ProvenanceEnvelope`] for `input` when
+/// [`JsonOptions::provenance`] is set.
+pub fn write_json_with_source<T: Serialize, W: Write>(
+    data: &T,
+    input: &[u8],
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    if !options.provenance {
+        return write_json(data, options, writer);
+    }
+    write_json_with_digest(data, &SourceDigest::new(input), options, writer)
+}
+
+/// Same as [`write_json_with_source`], for a source file only `source` is kept of.
+pub(crate) fn write_json_with_digest<T: Serialize, W: Write>(
+    data: &T,
+    source: &SourceDigest,
+    options: &JsonOptions,
+    writer: W,
+) -> Result<()> {
+    if !options.provenance {
+        return write_json(data, options, writer);
+    }
+    let envelope = ProvenanceEnvelope {
+        provenance: Provenance::from_digest(source),
+        data,
+    };
+    write_json(&envelope, options, writer)
+}
+
+pub fn to_json_with_source<T: Serialize>(
+    data: &T,
+    input: &[u8],
+    options: &JsonOptions,
+) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_json_with_source(data, input, options, &mut buffer)?;
+    String::from_utf8(buffer).context("Failed to convert JSON bytes to string")
+}
+
+pub(crate) fn to_json_with_digest<T: Serialize>(
+    data: &T,
+    source: &SourceDigest,
+    options: &JsonOptions,
+) -> Result<String> {
+    let mut buffer = Vec::new();
+    write_json_with_digest(data, source, options, &mut buffer)?;
+    String::from_utf8(buffer).context("Failed to convert JSON bytes to string")
+}
+
+#[cfg(not(feature = "simd-json"))]
+fn serialize<T: Serialize, W: Write>(data: &T, pretty: bool, writer: W) -> Result<()> {
+    if pretty {
+        serde_json::to_writer_pretty(writer, data)
+    } else {
+        serde_json::to_writer(writer, data)
+    }
+    .context("Failed to convert serde value to JSON string")
+}
+
+#[cfg(feature = "simd-json")]
+fn serialize<T: Serialize, W: Write>(data: &T, pretty: bool, writer: W) -> Result<()> {
+    if pretty {
+        simd_json::to_writer_pretty(writer, data)
+    } else {
+        simd_json::to_writer(writer, data)
+    }
+    .context("Failed to convert serde value to JSON string")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dt::TimeReal;
+    use serde_json::json;
+
+    #[test]
+    fn test_timestamp_format() {
+        let time = TimeReal("2024-03-01T08:30:00Z".parse().unwrap());
+        let to_json_with = |timestamp_format| {
+            let options = JsonOptions {
+                timestamp_format,
+                ..Default::default()
+            };
+            to_json(&time, &options).unwrap()
+        };
+        assert_eq!(
+            to_json_with(TimestampFormat::Rfc3339),
+            "\"2024-03-01T08:30:00Z\""
+        );
+        assert_eq!(to_json_with(TimestampFormat::UnixSeconds), "1709281800");
+        assert_eq!(to_json_with(TimestampFormat::UnixMillis), "1709281800000");
+        // the format only applies while writing
+        assert_eq!(
+            serde_json::to_string(&time).unwrap(),
+            "\"2024-03-01T08:30:00Z\""
+        );
+    }
+
+    #[test]
+    fn test_provenance_envelope() {
+        let data = json!({ "generation": "gen1" });
+        let input = b"abc";
+        let options = JsonOptions {
+            provenance: true,
+            ..Default::default()
+        };
+        let output = to_json_with_source(&data, input, &options).unwrap();
+        let envelope: ProvenanceEnvelope<serde_json::Value> =
+            serde_json::from_str(&output).unwrap();
+        assert_eq!(envelope.data, data);
+        assert_eq!(
+            envelope.provenance.source_sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(envelope.provenance.source_bytes, 3);
+        assert_eq!(
+            envelope.provenance.parser_version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let plain = to_json_with_source(&data, input, &JsonOptions::default()).unwrap();
+        assert_eq!(plain, to_json(&data, &JsonOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn test_canonical_order() {
+        let value = json!({
+            "records": [
+                { "eventType": "B", "eventEndTime": "2024-03-01T07:00:00Z", "eventBeginTime": "2024-03-02T08:00:00Z" },
+                { "eventType": "A", "eventBeginTime": "2024-03-01T08:00:00Z" },
+                { "eventType": "C", "eventBeginTime": "2024-03-02T08:00:00Z" },
+            ],
+            "speed": [3, 1, 2],
+            "mixed": [{ "time": "2024-03-02T08:00:00Z" }, { "name": "untimed" }],
+        });
+        let options = JsonOptions {
+            canonical_order: true,
+            timestamp_format: TimestampFormat::UnixSeconds,
+            ..Default::default()
+        };
+        let output: Value = serde_json::from_str(&to_json(&value, &options).unwrap()).unwrap();
+        let event_types: Vec<&str> = output["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["eventType"].as_str().unwrap())
+            .collect();
+        // B and C begin at the same time, eventBeginTime sorts before eventEndTime
+        assert_eq!(event_types, ["A", "B", "C"]);
+        assert_eq!(output["speed"], json!([3, 1, 2]));
+        assert_eq!(output["mixed"], value["mixed"]);
+    }
+
+    #[test]
+    fn test_to_json_round_trips() {
+        let value = json!({
+            "activityChangeInfo": [{ "minutes": 0, "activity": "Driving" }, { "minutes": 1439, "activity": "BreakRest" }],
+            "name": "Zoë \"quoted\"\n",
+            "speed": [0, 255],
+            "latitude": -48.508333333333,
+            "missing": null,
+        });
+        for pretty in [false, true] {
+            let options = JsonOptions {
+                pretty,
+                ..Default::default()
+            };
+            let output = to_json(&value, &options).unwrap();
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&output).unwrap(),
+                value
+            );
+        }
+    }
+}
@@ -1,13 +1,42 @@
+pub mod analysis;
+pub mod binary;
 mod bytes;
+pub mod capabilities;
 pub mod card_parser;
+mod context;
 pub mod detector;
+pub mod diagnostics;
+mod display;
+/// Types of the data dictionary of the spec, as laid out in the files. Not covered by semver
+/// yet, prefer [`prelude`] unless a raw record is needed.
+#[cfg_attr(not(feature = "unstable"), doc(hidden))]
 pub mod dt;
+pub mod extensions;
+pub mod fingerprint;
+pub mod json;
+pub mod locale;
+pub mod prelude;
+pub mod printout;
+pub mod record_id;
+pub mod sensor_parser;
+pub mod sink;
+pub mod source;
+pub mod spec;
+pub mod trace;
+pub mod transform;
+pub mod units;
+pub mod validation;
+pub mod view;
 pub mod vu_parser;
 use anyhow::{Context, Result};
+pub use capabilities::capabilities;
+pub use card_parser::peek_card_identification;
 use card_parser::CardParser;
 use detector::TachoFileType;
+use sensor_parser::SensorParser;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
+pub use vu_parser::peek_vu_identification;
 use vu_parser::VuParser;
 
 // Vehicle Unit
@@ -90,6 +119,18 @@ pub fn parse_card_from_bytes_to_json_pretty(bytes: &[u8]) -> Result<String> {
     Ok(card_data_json)
 }
 
+// Motion sensor
+pub fn parse_sensor_from_file(file_path: &str) -> Result<sensor_parser::SensorData> {
+    SensorParser::new_from_file(file_path)
+        .context("Failed to create SensorParser")?
+        .parse()
+}
+pub fn parse_sensor_from_bytes(bytes: &[u8]) -> Result<sensor_parser::SensorData> {
+    SensorParser::new_from_bytes(bytes)
+        .context("Failed to create SensorParser")?
+        .parse()
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub enum TachoData {
@@ -120,3 +161,9 @@ pub fn parse_from_bytes(bytes: &[u8]) -> Result<TachoData> {
     };
     Ok(output)
 }
+
+/// Like [`parse_from_bytes`], for downloads that are read through a [`source::TachoRead`].
+pub fn parse_from_source<S: source::TachoRead + ?Sized>(source: &S) -> Result<TachoData> {
+    let bytes = source::read_all(source).context("Failed to read source")?;
+    parse_from_bytes(&bytes)
+}
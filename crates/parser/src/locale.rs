@@ -0,0 +1,618 @@
+use crate::dt::external::NationNumeric;
+use crate::dt::{ActivityChangeInfoCardActivity, Language};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Language used for human-readable names added on top of the parsed output.
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+    Ro,
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+    /// Accepts a language code or a language tag, e.g. `de` or `de-AT`.
+    fn from_str(tag: &str) -> Result<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            "fr" => Ok(Locale::Fr),
+            "es" => Ok(Locale::Es),
+            "ro" => Ok(Locale::Ro),
+            _ => anyhow::bail!("Unsupported locale: {}", tag),
+        }
+    }
+}
+
+impl Locale {
+    /// Locale of a card holder's preferred language, `None` when the language is not valid or
+    /// has no translations.
+    pub fn from_language(language: &Language) -> Option<Self> {
+        language.code()?.locale()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// ISO 639-1 code of an official language of the member states, the values a card holder's
+/// [`Language`] is meant to hold.
+pub enum LanguageCode {
+    Bg,
+    Cs,
+    Da,
+    De,
+    El,
+    En,
+    Es,
+    Et,
+    Fi,
+    Fr,
+    Ga,
+    Hr,
+    Hu,
+    It,
+    Lt,
+    Lv,
+    Mt,
+    Nl,
+    Pl,
+    Pt,
+    Ro,
+    Sk,
+    Sl,
+    Sv,
+}
+
+impl LanguageCode {
+    pub const ALL: [LanguageCode; 24] = [
+        LanguageCode::Bg,
+        LanguageCode::Cs,
+        LanguageCode::Da,
+        LanguageCode::De,
+        LanguageCode::El,
+        LanguageCode::En,
+        LanguageCode::Es,
+        LanguageCode::Et,
+        LanguageCode::Fi,
+        LanguageCode::Fr,
+        LanguageCode::Ga,
+        LanguageCode::Hr,
+        LanguageCode::Hu,
+        LanguageCode::It,
+        LanguageCode::Lt,
+        LanguageCode::Lv,
+        LanguageCode::Mt,
+        LanguageCode::Nl,
+        LanguageCode::Pl,
+        LanguageCode::Pt,
+        LanguageCode::Ro,
+        LanguageCode::Sk,
+        LanguageCode::Sl,
+        LanguageCode::Sv,
+    ];
+
+    /// The lowercase two letter code, e.g. `de`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LanguageCode::Bg => "bg",
+            LanguageCode::Cs => "cs",
+            LanguageCode::Da => "da",
+            LanguageCode::De => "de",
+            LanguageCode::El => "el",
+            LanguageCode::En => "en",
+            LanguageCode::Es => "es",
+            LanguageCode::Et => "et",
+            LanguageCode::Fi => "fi",
+            LanguageCode::Fr => "fr",
+            LanguageCode::Ga => "ga",
+            LanguageCode::Hr => "hr",
+            LanguageCode::Hu => "hu",
+            LanguageCode::It => "it",
+            LanguageCode::Lt => "lt",
+            LanguageCode::Lv => "lv",
+            LanguageCode::Mt => "mt",
+            LanguageCode::Nl => "nl",
+            LanguageCode::Pl => "pl",
+            LanguageCode::Pt => "pt",
+            LanguageCode::Ro => "ro",
+            LanguageCode::Sk => "sk",
+            LanguageCode::Sl => "sl",
+            LanguageCode::Sv => "sv",
+        }
+    }
+
+    /// The [`Locale`] with this language, `None` for the languages without translations.
+    pub fn locale(self) -> Option<Locale> {
+        match self {
+            LanguageCode::En => Some(Locale::En),
+            LanguageCode::De => Some(Locale::De),
+            LanguageCode::Fr => Some(Locale::Fr),
+            LanguageCode::Es => Some(Locale::Es),
+            LanguageCode::Ro => Some(Locale::Ro),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for LanguageCode {
+    type Err = anyhow::Error;
+    /// Accepts the two letter code in either case, e.g. `de` or `DE`.
+    fn from_str(code: &str) -> Result<Self> {
+        LanguageCode::ALL
+            .into_iter()
+            .find(|language| language.as_str().eq_ignore_ascii_case(code))
+            .ok_or_else(|| anyhow::anyhow!("Unknown ISO 639-1 language code: {:?}", code))
+    }
+}
+
+/// Name of a driver activity in the given locale, as shown on the VU display.
+pub fn activity_label(activity: &ActivityChangeInfoCardActivity, locale: Locale) -> &'static str {
+    let translations = match activity {
+        ActivityChangeInfoCardActivity::Driving => {
+            ["Driving", "Lenken", "Conduite", "Conducción", "Conducere"]
+        }
+        ActivityChangeInfoCardActivity::Work => ["Work", "Arbeit", "Travail", "Trabajo", "Muncă"],
+        ActivityChangeInfoCardActivity::Availability => [
+            "Availability",
+            "Bereitschaft",
+            "Disponibilité",
+            "Disponibilidad",
+            "Disponibilitate",
+        ],
+        ActivityChangeInfoCardActivity::BreakRest => [
+            "Break/rest",
+            "Pause/Ruhezeit",
+            "Pause/repos",
+            "Pausa/descanso",
+            "Pauză/odihnă",
+        ],
+    };
+    translations[locale_index(locale)]
+}
+
+/// Name of an event or fault type in the given locale, keyed by its serialized name (e.g.
+/// `PowerSupplyInterruption`) like [`crate::view::VuEventView::event_type`], so that it applies to
+/// all generations. `None` for names that are not an event or fault type.
+pub fn event_label(event_type: &str, locale: Locale) -> Option<&'static str> {
+    EVENT_TRANSLATIONS
+        .iter()
+        .find(|(name, _)| *name == event_type)
+        .map(|(_, translations)| translations[locale_index(locale)])
+}
+
+/// Index of `locale` in the English, German, French, Spanish and Romanian label arrays.
+fn locale_index(locale: Locale) -> usize {
+    match locale {
+        Locale::En => 0,
+        Locale::De => 1,
+        Locale::Fr => 2,
+        Locale::Es => 3,
+        Locale::Ro => 4,
+    }
+}
+
+/// Serialized (camelCase) names of every field holding a [`NationNumeric`].
+const NATION_FIELDS: [&str; 7] = [
+    "cardIssuingMemberState",
+    "dailyWorkPeriodCountry",
+    "vehicleRegistrationNation",
+    "drivingLicenceIssuingNation",
+    "countryLeft",
+    "countryEntered",
+    "calibrationCountry",
+];
+
+/// Name of a nation code in the given locale.
+/// Unknown codes resolve to the localized "Reserved for Future Use".
+pub fn nation_name(code: u8, locale: Locale) -> &'static str {
+    let english = NationNumeric::name(code);
+    let Some(translations) = NATION_TRANSLATIONS
+        .iter()
+        .find(|(name, _)| *name == english)
+        .map(|(_, translations)| translations)
+    else {
+        return english;
+    };
+    match locale {
+        Locale::En => english,
+        Locale::De => translations[0],
+        Locale::Fr => translations[1],
+        Locale::Es => translations[2],
+        Locale::Ro => translations[3],
+    }
+}
+
+/// Adds a `<field>Localized` sibling next to every nation field in a serialized card or VU,
+/// however deeply nested, holding the country name in the given locale.
+/// The original (English) value is left untouched.
+pub fn localize_nations(value: &mut Value, locale: Locale) {
+    match value {
+        Value::Object(map) => {
+            let localized: Vec<(String, &'static str)> = NATION_FIELDS
+                .iter()
+                .filter_map(|field| {
+                    let english = map.get(*field)?.as_str()?;
+                    let code = NationNumeric(english.to_string()).code()?;
+                    Some((format!("{}Localized", field), nation_name(code, locale)))
+                })
+                .collect();
+            for (key, name) in localized {
+                map.insert(key, Value::String(name.to_string()));
+            }
+            for child in map.values_mut() {
+                localize_nations(child, locale);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                localize_nations(item, locale);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// English name (as produced by [`NationNumeric::name`]) to German, French, Spanish and Romanian.
+const NATION_TRANSLATIONS: [(&str, [&str; 4]); 60] = [
+    (
+        "No information available",
+        [
+            "Keine Angaben verfügbar",
+            "Aucune information disponible",
+            "No hay información disponible",
+            "Nicio informație disponibilă",
+        ],
+    ),
+    ("Austria", ["Österreich", "Autriche", "Austria", "Austria"]),
+    ("Albania", ["Albanien", "Albanie", "Albania", "Albania"]),
+    ("Andorra", ["Andorra", "Andorre", "Andorra", "Andorra"]),
+    ("Armenia", ["Armenien", "Arménie", "Armenia", "Armenia"]),
+    (
+        "Azerbaijan",
+        ["Aserbaidschan", "Azerbaïdjan", "Azerbaiyán", "Azerbaidjan"],
+    ),
+    ("Belgium", ["Belgien", "Belgique", "Bélgica", "Belgia"]),
+    (
+        "Bulgaria",
+        ["Bulgarien", "Bulgarie", "Bulgaria", "Bulgaria"],
+    ),
+    (
+        "Bosnia Herzegovina",
+        [
+            "Bosnien und Herzegowina",
+            "Bosnie-Herzégovine",
+            "Bosnia y Herzegovina",
+            "Bosnia și Herțegovina",
+        ],
+    ),
+    (
+        "Belarus",
+        ["Belarus", "Biélorussie", "Bielorrusia", "Belarus"],
+    ),
+    ("Switzerland", ["Schweiz", "Suisse", "Suiza", "Elveția"]),
+    ("Cyprus", ["Zypern", "Chypre", "Chipre", "Cipru"]),
+    (
+        "Czech Republic",
+        [
+            "Tschechien",
+            "République tchèque",
+            "República Checa",
+            "Republica Cehă",
+        ],
+    ),
+    (
+        "Germany",
+        ["Deutschland", "Allemagne", "Alemania", "Germania"],
+    ),
+    (
+        "Denmark",
+        ["Dänemark", "Danemark", "Dinamarca", "Danemarca"],
+    ),
+    ("Spain", ["Spanien", "Espagne", "España", "Spania"]),
+    ("Estonia", ["Estland", "Estonie", "Estonia", "Estonia"]),
+    ("France", ["Frankreich", "France", "Francia", "Franța"]),
+    ("Finland", ["Finnland", "Finlande", "Finlandia", "Finlanda"]),
+    (
+        "Liechtenstein",
+        [
+            "Liechtenstein",
+            "Liechtenstein",
+            "Liechtenstein",
+            "Liechtenstein",
+        ],
+    ),
+    (
+        "Faroe Islands",
+        ["Färöer", "Îles Féroé", "Islas Feroe", "Insulele Feroe"],
+    ),
+    (
+        "United Kingdom",
+        [
+            "Vereinigtes Königreich",
+            "Royaume-Uni",
+            "Reino Unido",
+            "Regatul Unit",
+        ],
+    ),
+    ("Georgia", ["Georgien", "Géorgie", "Georgia", "Georgia"]),
+    ("Greece", ["Griechenland", "Grèce", "Grecia", "Grecia"]),
+    ("Hungary", ["Ungarn", "Hongrie", "Hungría", "Ungaria"]),
+    ("Croatia", ["Kroatien", "Croatie", "Croacia", "Croația"]),
+    ("Italy", ["Italien", "Italie", "Italia", "Italia"]),
+    ("Ireland", ["Irland", "Irlande", "Irlanda", "Irlanda"]),
+    ("Iceland", ["Island", "Islande", "Islandia", "Islanda"]),
+    (
+        "Kazakhstan",
+        ["Kasachstan", "Kazakhstan", "Kazajistán", "Kazahstan"],
+    ),
+    (
+        "Luxembourg",
+        ["Luxemburg", "Luxembourg", "Luxemburgo", "Luxemburg"],
+    ),
+    ("Lithuania", ["Litauen", "Lituanie", "Lituania", "Lituania"]),
+    ("Latvia", ["Lettland", "Lettonie", "Letonia", "Letonia"]),
+    ("Malta", ["Malta", "Malte", "Malta", "Malta"]),
+    ("Monaco", ["Monaco", "Monaco", "Mónaco", "Monaco"]),
+    (
+        "Moldova",
+        ["Moldau", "Moldavie", "Moldavia", "Republica Moldova"],
+    ),
+    (
+        "North Macedonia",
+        [
+            "Nordmazedonien",
+            "Macédoine du Nord",
+            "Macedonia del Norte",
+            "Macedonia de Nord",
+        ],
+    ),
+    ("Norway", ["Norwegen", "Norvège", "Noruega", "Norvegia"]),
+    (
+        "Netherlands",
+        ["Niederlande", "Pays-Bas", "Países Bajos", "Țările de Jos"],
+    ),
+    (
+        "Portugal",
+        ["Portugal", "Portugal", "Portugal", "Portugalia"],
+    ),
+    ("Poland", ["Polen", "Pologne", "Polonia", "Polonia"]),
+    ("Romania", ["Rumänien", "Roumanie", "Rumanía", "România"]),
+    (
+        "San Marino",
+        ["San Marino", "Saint-Marin", "San Marino", "San Marino"],
+    ),
+    ("Russia", ["Russland", "Russie", "Rusia", "Rusia"]),
+    ("Sweden", ["Schweden", "Suède", "Suecia", "Suedia"]),
+    (
+        "Slovakia",
+        ["Slowakei", "Slovaquie", "Eslovaquia", "Slovacia"],
+    ),
+    (
+        "Slovenia",
+        ["Slowenien", "Slovénie", "Eslovenia", "Slovenia"],
+    ),
+    (
+        "Turkmenistan",
+        [
+            "Turkmenistan",
+            "Turkménistan",
+            "Turkmenistán",
+            "Turkmenistan",
+        ],
+    ),
+    ("Türkiye", ["Türkei", "Turquie", "Turquía", "Turcia"]),
+    ("Ukraine", ["Ukraine", "Ukraine", "Ucrania", "Ucraina"]),
+    (
+        "Vatican City",
+        [
+            "Vatikanstadt",
+            "Cité du Vatican",
+            "Ciudad del Vaticano",
+            "Vatican",
+        ],
+    ),
+    (
+        "Montenegro",
+        ["Montenegro", "Monténégro", "Montenegro", "Muntenegru"],
+    ),
+    ("Serbia", ["Serbien", "Serbie", "Serbia", "Serbia"]),
+    (
+        "Uzbekistan",
+        ["Usbekistan", "Ouzbékistan", "Uzbekistán", "Uzbekistan"],
+    ),
+    (
+        "Tajikistan",
+        ["Tadschikistan", "Tadjikistan", "Tayikistán", "Tadjikistan"],
+    ),
+    (
+        "Kyrgyz Republic",
+        ["Kirgisistan", "Kirghizistan", "Kirguistán", "Kârgâzstan"],
+    ),
+    (
+        "European Community",
+        [
+            "Europäische Gemeinschaft",
+            "Communauté européenne",
+            "Comunidad Europea",
+            "Comunitatea Europeană",
+        ],
+    ),
+    (
+        "Rest of Europe",
+        [
+            "Übriges Europa",
+            "Reste de l'Europe",
+            "Resto de Europa",
+            "Restul Europei",
+        ],
+    ),
+    (
+        "Rest of the World",
+        [
+            "Übrige Welt",
+            "Reste du monde",
+            "Resto del mundo",
+            "Restul lumii",
+        ],
+    ),
+    (
+        "Reserved for Future Use",
+        [
+            "Für zukünftige Verwendung reserviert",
+            "Réservé pour usage futur",
+            "Reservado para uso futuro",
+            "Rezervat pentru utilizare viitoare",
+        ],
+    ),
+];
+
+/// Serialized name of every event and fault type of all generations to its English, German,
+/// French, Spanish and Romanian name.
+const EVENT_TRANSLATIONS: [(&str, [&str; 5]); 49] = [
+    ("NoFurtherDetails", ["No further details", "Keine weiteren Angaben", "Pas d'autres précisions", "Sin más detalles", "Fără alte detalii"]),
+    ("InsertionOfNonValidCard", ["Insertion of a non valid card", "Einstecken einer ungültigen Karte", "Insertion d'une carte non valable", "Inserción de una tarjeta no válida", "Introducerea unui card nevalabil"]),
+    ("CardConflict", ["Card conflict", "Kartenkonflikt", "Conflit de cartes", "Conflicto de tarjetas", "Conflict de carduri"]),
+    ("TimeOverlap", ["Time overlap", "Zeitüberschneidung", "Chevauchement temporel", "Solapamiento temporal", "Suprapunere de timp"]),
+    ("DrivingWithoutAppropriateCard", ["Driving without an appropriate card", "Fahren ohne gültige Karte", "Conduite sans carte appropriée", "Conducción sin la tarjeta adecuada", "Conducere fără cardul corespunzător"]),
+    ("CardInsertionWhileDriving", ["Card insertion while driving", "Einstecken der Karte während der Fahrt", "Insertion d'une carte en cours de conduite", "Inserción de la tarjeta durante la conducción", "Introducerea cardului în timpul conducerii"]),
+    ("LastCardSessionNotCorrectlyClosed", ["Last card session not correctly closed", "Letzter Kartenvorgang nicht korrekt abgeschlossen", "Dernière session de la carte mal clôturée", "Última sesión de la tarjeta cerrada incorrectamente", "Ultima sesiune a cardului nu a fost închisă corect"]),
+    ("OverSpeeding", ["Over speeding", "Geschwindigkeitsüberschreitung", "Excès de vitesse", "Exceso de velocidad", "Depășirea vitezei"]),
+    ("PowerSupplyInterruption", ["Power supply interruption", "Unterbrechung der Stromversorgung", "Interruption de l'alimentation électrique", "Interrupción del suministro eléctrico", "Întreruperea alimentării cu energie"]),
+    ("MotionDataError", ["Motion data error", "Fehler Bewegungsdaten", "Erreur des données de mouvement", "Error en los datos de movimiento", "Eroare a datelor de mișcare"]),
+    ("VehicleMotionConflict", ["Vehicle motion conflict", "Konflikt Fahrzeugbewegung", "Conflit concernant le mouvement du véhicule", "Conflicto de movimiento del vehículo", "Conflict privind mișcarea vehiculului"]),
+    ("TimeConflict", ["Time conflict", "Zeitkonflikt", "Conflit horaire", "Conflicto horario", "Conflict de timp"]),
+    ("CommunicationErrorWithRemoteCommunicationFacility", ["Communication error with the remote communication facility", "Kommunikationsfehler mit der Fernkommunikationseinrichtung", "Erreur de communication avec le dispositif de communication à distance", "Error de comunicación con el equipo de comunicación remota", "Eroare de comunicare cu dispozitivul de comunicare la distanță"]),
+    ("AbsenceOfPositionInfoFromGNSSReceiver", ["Absence of position information from the GNSS receiver", "Fehlende Positionsdaten vom GNSS-Empfänger", "Absence d'informations de position du récepteur GNSS", "Ausencia de información de posición del receptor GNSS", "Lipsa informațiilor de poziție de la receptorul GNSS"]),
+    ("CommunicationErrorWithExternalGNSSFacility", ["Communication error with the external GNSS facility", "Kommunikationsfehler mit der externen GNSS-Ausrüstung", "Erreur de communication avec le dispositif GNSS externe", "Error de comunicación con el equipo GNSS externo", "Eroare de comunicare cu dispozitivul GNSS extern"]),
+    ("GNSSAnomaly", ["GNSS anomaly", "GNSS-Anomalie", "Anomalie GNSS", "Anomalía GNSS", "Anomalie GNSS"]),
+    ("VUSecurityBreachAttemptNoFurtherDetails", ["VU security breach attempt, no further details", "Versuch einer Sicherheitsverletzung an der FE, keine weiteren Angaben", "Tentative d'atteinte à la sécurité de la VU, pas d'autres précisions", "Intento de violación de la seguridad de la VU, sin más detalles", "Tentativă de încălcare a securității VU, fără alte detalii"]),
+    ("MotionSensorAuthenticationFailure", ["Motion sensor authentication failure", "Authentisierungsfehler des Weg- und Geschwindigkeitsgebers", "Échec de l'authentification du capteur de mouvement", "Fallo de autenticación del sensor de movimiento", "Eșec la autentificarea senzorului de mișcare"]),
+    ("TachographCardAuthenticationFailure", ["Tachograph card authentication failure", "Authentisierungsfehler der Fahrtenschreiberkarte", "Échec de l'authentification de la carte tachygraphique", "Fallo de autenticación de la tarjeta de tacógrafo", "Eșec la autentificarea cardului de tahograf"]),
+    ("UnauthorizedChangeOfMotionSensor", ["Unauthorised change of motion sensor", "Unerlaubter Wechsel des Weg- und Geschwindigkeitsgebers", "Changement non autorisé du capteur de mouvement", "Cambio no autorizado del sensor de movimiento", "Schimbarea neautorizată a senzorului de mișcare"]),
+    ("CardDataInputIntegrityError", ["Card data input integrity error", "Integritätsfehler der Karteneingabedaten", "Erreur d'intégrité des données d'entrée de la carte", "Error de integridad en la entrada de datos de la tarjeta", "Eroare de integritate a datelor introduse de pe card"]),
+    ("StoredUserDataIntegrityError", ["Stored user data integrity error", "Integritätsfehler der gespeicherten Benutzerdaten", "Erreur d'intégrité des données utilisateur mémorisées", "Error de integridad de los datos de usuario almacenados", "Eroare de integritate a datelor de utilizator stocate"]),
+    ("InternalDataTransferError", ["Internal data transfer error", "Fehler bei der internen Datenübertragung", "Erreur de transfert interne des données", "Error en la transferencia interna de datos", "Eroare la transferul intern de date"]),
+    ("UnauthorizedCaseOpening", ["Unauthorised case opening", "Unerlaubtes Öffnen des Gehäuses", "Ouverture non autorisée du boîtier", "Apertura no autorizada de la carcasa", "Deschiderea neautorizată a carcasei"]),
+    ("HardwareSabotage", ["Hardware sabotage", "Sabotage der Hardware", "Sabotage du matériel", "Sabotaje del hardware", "Sabotarea echipamentului"]),
+    ("TamperDetectionOfGNSS", ["Tamper detection of GNSS", "Manipulationserkennung GNSS", "Détection de manipulation du GNSS", "Detección de manipulación del GNSS", "Detectarea manipulării GNSS"]),
+    ("ExternalGNSSFacilityAuthenticationFailure", ["External GNSS facility authentication failure", "Authentisierungsfehler der externen GNSS-Ausrüstung", "Échec de l'authentification du dispositif GNSS externe", "Fallo de autenticación del equipo GNSS externo", "Eșec la autentificarea dispozitivului GNSS extern"]),
+    ("ExternalGNSSFacilityCertificateExpired", ["External GNSS facility certificate expired", "Zertifikat der externen GNSS-Ausrüstung abgelaufen", "Certificat du dispositif GNSS externe expiré", "Certificado del equipo GNSS externo caducado", "Certificatul dispozitivului GNSS extern a expirat"]),
+    ("InconsistencyBetweenMotionDataAndStoredDriverActivityData", ["Inconsistency between motion data and stored driver activity data", "Widerspruch zwischen Bewegungsdaten und gespeicherten Fahrertätigkeiten", "Incohérence entre les données de mouvement et les activités du conducteur mémorisées", "Incoherencia entre los datos de movimiento y las actividades del conductor almacenadas", "Neconcordanță între datele de mișcare și activitățile conducătorului stocate"]),
+    ("SensorSecurityBreachAttemptNoFurtherDetails", ["Sensor security breach attempt, no further details", "Versuch einer Sicherheitsverletzung am Geber, keine weiteren Angaben", "Tentative d'atteinte à la sécurité du capteur, pas d'autres précisions", "Intento de violación de la seguridad del sensor, sin más detalles", "Tentativă de încălcare a securității senzorului, fără alte detalii"]),
+    ("SensorAuthenticationFailure", ["Sensor authentication failure", "Authentisierungsfehler des Gebers", "Échec de l'authentification du capteur", "Fallo de autenticación del sensor", "Eșec la autentificarea senzorului"]),
+    ("SensorStoredDataIntegrityError", ["Sensor stored data integrity error", "Integritätsfehler der im Geber gespeicherten Daten", "Erreur d'intégrité des données mémorisées du capteur", "Error de integridad de los datos almacenados del sensor", "Eroare de integritate a datelor stocate în senzor"]),
+    ("SensorInternalDataTransferError", ["Sensor internal data transfer error", "Fehler bei der internen Datenübertragung des Gebers", "Erreur de transfert interne des données du capteur", "Error en la transferencia interna de datos del sensor", "Eroare la transferul intern de date al senzorului"]),
+    ("SensorUnauthorizedCaseOpening", ["Sensor unauthorised case opening", "Unerlaubtes Öffnen des Gebergehäuses", "Ouverture non autorisée du boîtier du capteur", "Apertura no autorizada de la carcasa del sensor", "Deschiderea neautorizată a carcasei senzorului"]),
+    ("SensorHardwareSabotage", ["Sensor hardware sabotage", "Sabotage der Geberhardware", "Sabotage du matériel du capteur", "Sabotaje del hardware del sensor", "Sabotarea echipamentului senzorului"]),
+    ("ControlDeviceFaultNoFurtherDetails", ["Recording equipment fault, no further details", "Störung des Kontrollgeräts, keine weiteren Angaben", "Défaillance de l'appareil de contrôle, pas d'autres précisions", "Fallo del aparato de control, sin más detalles", "Defecțiune a aparatului de înregistrare, fără alte detalii"]),
+    ("VUInternalFault", ["VU internal fault", "Interne Störung der FE", "Défaillance interne de la VU", "Fallo interno de la VU", "Defecțiune internă a VU"]),
+    ("PrinterFault", ["Printer fault", "Störung des Druckers", "Défaillance de l'imprimante", "Fallo de la impresora", "Defecțiune a imprimantei"]),
+    ("DisplayFault", ["Display fault", "Störung der Anzeige", "Défaillance de l'affichage", "Fallo de la pantalla", "Defecțiune a afișajului"]),
+    ("DownloadingFault", ["Downloading fault", "Störung beim Herunterladen", "Défaillance du téléchargement", "Fallo de la descarga", "Defecțiune la descărcare"]),
+    ("SensorFault", ["Sensor fault", "Störung des Gebers", "Défaillance du capteur", "Fallo del sensor", "Defecțiune a senzorului"]),
+    ("InternalGNSSReceiver", ["Internal GNSS receiver fault", "Störung des internen GNSS-Empfängers", "Défaillance du récepteur GNSS interne", "Fallo del receptor GNSS interno", "Defecțiune a receptorului GNSS intern"]),
+    ("ExternalGNSSFacility", ["External GNSS facility fault", "Störung der externen GNSS-Ausrüstung", "Défaillance du dispositif GNSS externe", "Fallo del equipo GNSS externo", "Defecțiune a dispozitivului GNSS extern"]),
+    ("RemoteCommunicationFacility", ["Remote communication facility fault", "Störung der Fernkommunikationseinrichtung", "Défaillance du dispositif de communication à distance", "Fallo del equipo de comunicación remota", "Defecțiune a dispozitivului de comunicare la distanță"]),
+    ("ITSInterface", ["ITS interface fault", "Störung der ITS-Schnittstelle", "Défaillance de l'interface ITS", "Fallo de la interfaz ITS", "Defecțiune a interfeței ITS"]),
+    ("InternalSensorFault", ["Internal sensor fault", "Störung des internen Gebers", "Défaillance du capteur interne", "Fallo del sensor interno", "Defecțiune a senzorului intern"]),
+    ("CardFaultNoFurtherDetails", ["Card fault, no further details", "Kartenstörung, keine weiteren Angaben", "Défaillance de la carte, pas d'autres précisions", "Fallo de la tarjeta, sin más detalles", "Defecțiune a cardului, fără alte detalii"]),
+    ("RFU", ["Reserved for future use", "Für zukünftige Verwendung reserviert", "Réservé pour usage futur", "Reservado para uso futuro", "Rezervat pentru utilizare viitoare"]),
+    ("ManufacturerSpecific", ["Manufacturer specific", "Herstellerspezifisch", "Spécifique au constructeur", "Específico del fabricante", "Specific producătorului"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_localize_nations_adds_nested_siblings() {
+        let mut value = json!({
+            "cardIssuingMemberState": "Germany",
+            "placeRecords": [
+                { "dailyWorkPeriodCountry": "Romania" },
+                { "dailyWorkPeriodCountry": "Reserved for Future Use" }
+            ],
+            "holderName": "Germany"
+        });
+        localize_nations(&mut value, "fr-BE".parse().unwrap());
+
+        assert_eq!(value["cardIssuingMemberStateLocalized"], "Allemagne");
+        assert_eq!(value["cardIssuingMemberState"], "Germany");
+        assert_eq!(
+            value["placeRecords"][0]["dailyWorkPeriodCountryLocalized"],
+            "Roumanie"
+        );
+        assert_eq!(
+            value["placeRecords"][1]["dailyWorkPeriodCountryLocalized"],
+            "Réservé pour usage futur"
+        );
+        assert!(value.get("holderNameLocalized").is_none());
+    }
+
+    #[test]
+    fn test_language_code() {
+        let language = |code: &str| Language(crate::dt::IA5String(code.to_string()));
+        assert_eq!(language("DE").code(), Some(LanguageCode::De));
+        assert_eq!(Locale::from_language(&language("ro")), Some(Locale::Ro));
+        assert_eq!(language("hu").code().unwrap().as_str(), "hu");
+        assert_eq!(Locale::from_language(&language("hu")), None);
+        assert_eq!(language("").code(), None);
+        assert_eq!(language("xx").code(), None);
+    }
+
+    #[test]
+    fn test_every_event_type_is_translated() {
+        use crate::dt::{gen1, gen2, gen2v2};
+        use crate::sink::label;
+        use std::io::Cursor;
+
+        for code in 0..=u8::MAX {
+            let input = [code];
+            let labels = [
+                label(&gen1::EventFaultType::parse(&mut Cursor::new(&input[..])).unwrap()),
+                label(&gen2::EventFaultTypeGen2::parse(&mut Cursor::new(&input[..])).unwrap()),
+                label(&gen2v2::EventFaultTypeGen2V2::parse(&mut Cursor::new(&input[..])).unwrap()),
+            ];
+            for event_type in labels {
+                assert!(
+                    event_label(&event_type, Locale::De).is_some(),
+                    "{}",
+                    event_type
+                );
+            }
+        }
+        assert_eq!(
+            event_label("PowerSupplyInterruption", Locale::Fr),
+            Some("Interruption de l'alimentation électrique")
+        );
+        assert_eq!(
+            activity_label(&ActivityChangeInfoCardActivity::Driving, Locale::De),
+            "Lenken"
+        );
+        assert_eq!(event_label("Driving", Locale::En), None);
+    }
+
+    #[test]
+    fn test_every_nation_code_is_translated() {
+        for code in 0..=u8::MAX {
+            assert!(NATION_TRANSLATIONS
+                .iter()
+                .any(|(name, _)| *name == NationNumeric::name(code)));
+        }
+        assert!("xx".parse::<Locale>().is_err());
+    }
+}
@@ -0,0 +1,36 @@
+//! The supported API of the crate, kept stable across minor releases.
+//!
+//! Parser internals, above all the spec types in [`crate::dt`], may change in any release as the
+//! parsing is reworked. Code that only needs to parse files, serialize the output and run the
+//! analysis helpers should import from here:
+//!
+//! ```no_run
+//! use tachograph_parser::prelude::*;
+//!
+//! let data = parse_from_bytes(&std::fs::read("driver.ddd")?)?;
+//! if let TachoData::Card { card_data } = data {
+//!     println!("{}", to_json(&card_data, &JsonOptions::default())?);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+pub use crate::capabilities::{capabilities, Capabilities};
+pub use crate::card_parser::{peek_card_identification, CardData, CardParser};
+pub use crate::detector::{
+    detect_detailed_from_bytes, detect_from_bytes, detect_from_file, TachoFileDetection,
+    TachoFileType,
+};
+pub use crate::diagnostics::Diagnostics;
+pub use crate::extensions::{CardExtension, ExtensionRegistry};
+pub use crate::json::{to_json, write_json, JsonOptions, TimestampFormat};
+pub use crate::sensor_parser::{SensorData, SensorParser};
+pub use crate::source::{MemorySource, TachoRead};
+pub use crate::units::{Kilometers, Kmh, Minutes};
+pub use crate::vu_parser::{
+    peek_vu_identification, VuData, VuParser, VuParserBuilder, VuStreamParser,
+};
+pub use crate::{
+    parse_card_from_bytes, parse_card_from_bytes_to_json, parse_card_from_file,
+    parse_card_from_file_to_json, parse_from_bytes, parse_from_source, parse_sensor_from_bytes,
+    parse_sensor_from_file, parse_vu_from_bytes, parse_vu_from_bytes_to_json, parse_vu_from_file,
+    parse_vu_from_file_to_json, TachoData,
+};
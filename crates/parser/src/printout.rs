@@ -0,0 +1,440 @@
+//! Text printouts laid out like the ones printed by the VU (Annex IC, Appendix 4), so that parsed
+//! data can be compared line by line with the device's own printout.
+//!
+//! Printouts are 24 characters wide. The pictograms of Appendix 3 are replaced by the unicode
+//! characters below, and times are shown in the UTC offset of [`PrintoutOptions`] instead of UTC.
+use crate::analysis::ActivityPeriod;
+use crate::dt::{
+    ActivityChangeInfoCardActivity, ActivityChangeInfoSlot, CardNumber, CardSlotNumber, Coded,
+    HolderName, OdometerShort, TimeReal,
+};
+use crate::sink::{card_number_to_string, vu_identification_record, IdentificationRecord};
+use crate::units::Kilometers;
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
+use std::fmt::Write;
+
+pub const DRIVING: &str = "⊙";
+pub const WORK: &str = "⚒";
+pub const AVAILABILITY: &str = "⊠";
+pub const REST: &str = "⏾";
+pub const CARD: &str = "▭";
+pub const VEHICLE: &str = "⛟";
+pub const VEHICLE_UNIT: &str = "▣";
+pub const CARD_INSERTION: &str = "▶";
+pub const CARD_WITHDRAWAL: &str = "◀";
+pub const DAILY_SUMMARY: &str = "Σ";
+
+const WIDTH: usize = 24;
+
+#[derive(Debug, Clone)]
+pub struct PrintoutOptions {
+    /// Offset the day and all times are shown in, UTC by default.
+    pub utc_offset: FixedOffset,
+    /// Time of printing shown in the header, the current time when `None`.
+    pub printed_at: Option<DateTime<Utc>>,
+}
+impl Default for PrintoutOptions {
+    fn default() -> Self {
+        PrintoutOptions {
+            utc_offset: FixedOffset::east_opt(0).unwrap(),
+            printed_at: None,
+        }
+    }
+}
+
+/// A card insertion of a VU activities block.
+#[derive(Debug, Clone, PartialEq)]
+struct CardInsertion {
+    slot: ActivityChangeInfoSlot,
+    card_number: String,
+    holder_name: String,
+    insertion_time: DateTime<Utc>,
+    insertion_odometer: Kilometers,
+    withdrawal_time: Option<DateTime<Utc>>,
+    withdrawal_odometer: Kilometers,
+}
+impl CardInsertion {
+    fn new(
+        slot: &Coded<CardSlotNumber>,
+        card_number: &CardNumber,
+        holder_name: &HolderName,
+        insertion_time: &TimeReal,
+        insertion_odometer: &OdometerShort,
+        withdrawal_time: &Option<TimeReal>,
+        withdrawal_odometer: &OdometerShort,
+    ) -> Self {
+        CardInsertion {
+            slot: match slot {
+                Coded::Known(CardSlotNumber::CoDriverSlot) => ActivityChangeInfoSlot::CoDriver,
+                _ => ActivityChangeInfoSlot::Driver,
+            },
+            card_number: card_number_to_string(card_number).unwrap_or_default(),
+            holder_name: format!(
+                "{} {}",
+                holder_name.holder_first_names.name.0.trim(),
+                holder_name.holder_surname.name.0.trim()
+            ),
+            insertion_time: insertion_time.0,
+            insertion_odometer: insertion_odometer.0,
+            withdrawal_time: withdrawal_time.as_ref().map(|time| time.0),
+            withdrawal_odometer: withdrawal_odometer.0,
+        }
+    }
+}
+
+impl VuData {
+    /// The '24h driver activities from VU' printout of `day`, a day in the UTC offset of
+    /// `options`: vehicle identification, then for each slot the card insertions and activities
+    /// of the day, and the daily totals per slot.
+    pub fn daily_printout(&self, day: NaiveDate, options: &PrintoutOptions) -> String {
+        render_daily_printout(
+            &self.vehicle_identification(),
+            &self.all_card_insertions(),
+            &self.activity_timeline(),
+            day,
+            options,
+        )
+    }
+
+    fn vehicle_identification(&self) -> IdentificationRecord {
+        match self {
+            VuData::Gen1(blocks) => {
+                let overview = &blocks.vu_overview;
+                vu_identification_record(
+                    Some(&overview.vehicle_identification_number),
+                    Some(
+                        &overview
+                            .vehicle_registration_identification
+                            .vehicle_registration_number
+                            .vehicle_reg_number,
+                    ),
+                )
+            }
+            VuData::Gen2(blocks) => {
+                let overview = &blocks.vu_overview;
+                vu_identification_record(
+                    overview.vehicle_identification_number_record_array.first(),
+                    overview
+                        .vehicle_registration_number_record_array
+                        .first()
+                        .map(|vrn| &vrn.vehicle_reg_number),
+                )
+            }
+            VuData::Gen2V2(blocks) => {
+                let overview = &blocks.vu_overview;
+                vu_identification_record(
+                    overview.vehicle_identification_number_record_array.first(),
+                    overview
+                        .vehicle_registration_identification_record_array
+                        .first()
+                        .map(|vri| &vri.vehicle_registration_number.vehicle_reg_number),
+                )
+            }
+        }
+    }
+
+    /// Card insertions of every activities block, oldest first. Insertions spanning midnight are
+    /// stored with both days and listed once.
+    fn all_card_insertions(&self) -> Vec<CardInsertion> {
+        let mut insertions: Vec<CardInsertion> = match self {
+            VuData::Gen1(blocks) => blocks
+                .vu_activities
+                .iter()
+                .flat_map(|block| &block.vu_card_iw_data.vu_card_iw_records)
+                .map(|record| {
+                    CardInsertion::new(
+                        &record.card_slot_number,
+                        &record.full_card_number.card_number,
+                        &record.card_holder_name,
+                        &record.card_insertion_time,
+                        &record.vehicle_odometer_value_at_insertion,
+                        &record.card_withdrawal_time,
+                        &record.vehicle_odometer_value_at_withdrawal,
+                    )
+                })
+                .collect(),
+            VuData::Gen2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .flat_map(|block| &block.vu_card_iw_record_array)
+                .map(|record| {
+                    CardInsertion::new(
+                        &record.card_slot_number,
+                        &record
+                            .full_card_number_and_generation
+                            .full_card_number
+                            .card_number,
+                        &record.card_holder_name,
+                        &record.card_insertion_date,
+                        &record.vehicle_odometer_value_at_insertion,
+                        &record.card_withdrawl_time,
+                        &record.vehicle_odometer_value_at_withdrawal,
+                    )
+                })
+                .collect(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .flat_map(|block| &block.vu_card_iw_record_array)
+                .map(|record| {
+                    CardInsertion::new(
+                        &record.card_slot_number,
+                        &record
+                            .full_card_number_and_generation
+                            .full_card_number
+                            .card_number,
+                        &record.card_holder_name,
+                        &record.card_insertion_date,
+                        &record.vehicle_odometer_value_at_insertion,
+                        &record.card_withdrawl_time,
+                        &record.vehicle_odometer_value_at_withdrawal,
+                    )
+                })
+                .collect(),
+        };
+        insertions.sort_by_key(|insertion| insertion.insertion_time);
+        insertions.dedup();
+        insertions
+    }
+}
+
+fn activity_pictogram(activity: &ActivityChangeInfoCardActivity) -> &'static str {
+    match activity {
+        ActivityChangeInfoCardActivity::Driving => DRIVING,
+        ActivityChangeInfoCardActivity::Work => WORK,
+        ActivityChangeInfoCardActivity::Availability => AVAILABILITY,
+        ActivityChangeInfoCardActivity::BreakRest => REST,
+    }
+}
+
+/// Duration as `hhHmm`, the format of the printouts.
+fn hours_minutes(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{:02}h{:02}", minutes / 60, minutes % 60)
+}
+
+fn render_daily_printout(
+    vehicle: &IdentificationRecord,
+    insertions: &[CardInsertion],
+    timeline: &[ActivityPeriod],
+    day: NaiveDate,
+    options: &PrintoutOptions,
+) -> String {
+    let offset = options.utc_offset;
+    let local = |time: DateTime<Utc>| time.with_timezone(&offset);
+    let day_start = day
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(offset)
+        .unwrap()
+        .with_timezone(&Utc);
+    let day_end = day_start + Duration::days(1);
+    let separator = "-".repeat(WIDTH);
+    let printed_at = options.printed_at.unwrap_or_else(Utc::now);
+
+    let mut out = String::new();
+    // writing to a String cannot fail
+    let mut line = |text: &str| {
+        let _ = writeln!(out, "{}", text);
+    };
+    line(&separator);
+    line(&format!(
+        "{} {}",
+        local(printed_at).format("%d/%m/%Y %H:%M"),
+        offset
+    ));
+    line(&format!("24h{}▼ {}", VEHICLE_UNIT, day.format("%d/%m/%Y")));
+    line(&separator);
+    line(&format!(
+        "{} {}",
+        VEHICLE,
+        vehicle
+            .vehicle_identification_number
+            .as_deref()
+            .unwrap_or("")
+    ));
+    line(&format!(
+        "{} {}",
+        VEHICLE,
+        vehicle.vehicle_registration_number.as_deref().unwrap_or("")
+    ));
+
+    let mut summaries = Vec::new();
+    for (number, slot) in [
+        (1, ActivityChangeInfoSlot::Driver),
+        (2, ActivityChangeInfoSlot::CoDriver),
+    ] {
+        line(&separator);
+        line(&format!("{} {}", number, DRIVING));
+
+        // (time, text) entries of the slot, printed in chronological order
+        let mut entries: Vec<(DateTime<Utc>, String)> = Vec::new();
+        for insertion in insertions.iter().filter(|insertion| insertion.slot == slot) {
+            let withdrawal = insertion
+                .withdrawal_time
+                .unwrap_or(DateTime::<Utc>::MAX_UTC);
+            if insertion.insertion_time >= day_end || withdrawal < day_start {
+                continue;
+            }
+            if insertion.insertion_time >= day_start {
+                entries.push((
+                    insertion.insertion_time,
+                    format!(
+                        "{} {}\n{} {}\n{} {} {}",
+                        CARD,
+                        insertion.holder_name,
+                        CARD,
+                        insertion.card_number,
+                        CARD_INSERTION,
+                        local(insertion.insertion_time).format("%H:%M"),
+                        insertion.insertion_odometer
+                    ),
+                ));
+            }
+            if let Some(withdrawal) = insertion.withdrawal_time.filter(|time| *time < day_end) {
+                entries.push((
+                    withdrawal,
+                    format!(
+                        "{} {} {}",
+                        CARD_WITHDRAWAL,
+                        local(withdrawal).format("%H:%M"),
+                        insertion.withdrawal_odometer
+                    ),
+                ));
+            }
+        }
+
+        let mut totals = [Duration::zero(); 4];
+        for period in timeline.iter().filter(|period| period.slot == slot) {
+            let start = period.start.max(day_start);
+            let end = period.end.min(day_end);
+            if start >= end {
+                continue;
+            }
+            let index = match period.activity {
+                ActivityChangeInfoCardActivity::Driving => 0,
+                ActivityChangeInfoCardActivity::Work => 1,
+                ActivityChangeInfoCardActivity::Availability => 2,
+                ActivityChangeInfoCardActivity::BreakRest => 3,
+            };
+            totals[index] += end - start;
+            entries.push((
+                start,
+                format!(
+                    "{} {} {}",
+                    activity_pictogram(&period.activity),
+                    local(start).format("%H:%M"),
+                    hours_minutes(end - start)
+                ),
+            ));
+        }
+        // a withdrawal and the activity starting with it are printed withdrawal first
+        entries.sort_by_key(|(time, _)| *time);
+        for (_, text) in &entries {
+            line(text);
+        }
+        summaries.push((number, totals));
+    }
+
+    line(&separator);
+    line(&format!("{} {}", DAILY_SUMMARY, DRIVING));
+    for (number, totals) in summaries {
+        line(&format!(
+            "{} {} {} {} {}",
+            number,
+            DRIVING,
+            hours_minutes(totals[0]),
+            WORK,
+            hours_minutes(totals[1])
+        ));
+        line(&format!(
+            "  {} {} {} {}",
+            AVAILABILITY,
+            hours_minutes(totals[2]),
+            REST,
+            hours_minutes(totals[3])
+        ));
+    }
+    line(&separator);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::ActivityOrigin;
+
+    #[test]
+    fn test_render_daily_printout() {
+        let time = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        let period = |start: &str, end: &str, activity| ActivityPeriod {
+            start: time(start),
+            end: time(end),
+            slot: ActivityChangeInfoSlot::Driver,
+            activity,
+            origin: ActivityOrigin::Recorded,
+        };
+        let timeline = [
+            period(
+                "2024-02-29T20:00:00Z",
+                "2024-03-01T06:00:00Z",
+                ActivityChangeInfoCardActivity::BreakRest,
+            ),
+            period(
+                "2024-03-01T06:00:00Z",
+                "2024-03-01T08:30:00Z",
+                ActivityChangeInfoCardActivity::Driving,
+            ),
+            period(
+                "2024-03-01T08:30:00Z",
+                "2024-03-01T09:00:00Z",
+                ActivityChangeInfoCardActivity::Work,
+            ),
+        ];
+        let insertions = [CardInsertion {
+            slot: ActivityChangeInfoSlot::Driver,
+            card_number: "DF000012345678".to_string(),
+            holder_name: "Jan Jansen".to_string(),
+            insertion_time: time("2024-03-01T05:58:00Z"),
+            insertion_odometer: Kilometers(1000),
+            withdrawal_time: Some(time("2024-03-01T09:00:00Z")),
+            withdrawal_odometer: Kilometers(1210),
+        }];
+        let vehicle = IdentificationRecord {
+            vehicle_identification_number: Some("WDB9634031L123456".to_string()),
+            vehicle_registration_number: Some("B 123ABC".to_string()),
+            ..Default::default()
+        };
+        let options = PrintoutOptions {
+            utc_offset: FixedOffset::east_opt(3600).unwrap(),
+            printed_at: Some(time("2024-03-02T12:00:00Z")),
+        };
+        let printout = render_daily_printout(
+            &vehicle,
+            &insertions,
+            &timeline,
+            "2024-03-01".parse().unwrap(),
+            &options,
+        );
+        let lines: Vec<&str> = printout.lines().collect();
+        assert_eq!(lines[1], "02/03/2024 13:00 +01:00");
+        assert_eq!(lines[2], "24h▣▼ 01/03/2024");
+        assert_eq!(lines[4], "⛟ WDB9634031L123456");
+        // the local day starts at 23:00 UTC, in the middle of the rest
+        let slot_1 = &lines[lines.iter().position(|line| *line == "1 ⊙").unwrap() + 1..];
+        assert_eq!(
+            &slot_1[..7],
+            [
+                "⏾ 00:00 07h00",
+                "▭ Jan Jansen",
+                "▭ DF000012345678",
+                "▶ 06:58 1000 km",
+                "⊙ 07:00 02h30",
+                "⚒ 09:30 00h30",
+                "◀ 10:00 1210 km",
+            ]
+        );
+        assert!(printout.contains("1 ⊙ 02h30 ⚒ 00h30\n  ⊠ 00h00 ⏾ 07h00\n"));
+        assert!(lines.iter().all(|line| line.chars().count() <= WIDTH));
+    }
+}
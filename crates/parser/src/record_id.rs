@@ -0,0 +1,63 @@
+use crate::dt::{gen1, gen2, CardActivityDailyRecord};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Deterministic ID of a record, so incremental loaders can upsert records from overlapping
+/// downloads of the same card or VU without building their own keys.
+///
+/// `owner` is the card number or VIN the record belongs to, `kind` the type of record and `key`
+/// the fields identifying the record within the owner's data. The ID is the first 16 bytes of a
+/// SHA-256 over the length-prefixed parts, as lowercase hex.
+pub fn record_id(owner: &str, kind: &str, key: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in [owner, kind].iter().chain(key) {
+        hasher.update((part.len() as u32).to_be_bytes());
+        hasher.update(part.as_bytes());
+    }
+    crate::fingerprint::to_hex(&hasher.finalize()[..16])
+}
+
+/// Serialized form of a field used as part of a key.
+fn key_part<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+impl CardActivityDailyRecord {
+    /// See [`record_id`]; a card holds at most one daily record per day.
+    pub fn record_id(&self, owner: &str) -> String {
+        record_id(
+            owner,
+            "dailyRecord",
+            &[&self.activity_record_date.0.to_rfc3339()],
+        )
+    }
+}
+
+impl gen1::PlaceRecord {
+    /// See [`record_id`]; a begin and an end entry may share the same time.
+    pub fn record_id(&self, owner: &str) -> String {
+        record_id(
+            owner,
+            "place",
+            &[
+                &self.entry_time.0.to_rfc3339(),
+                &key_part(&self.entry_type_daily_work_period),
+            ],
+        )
+    }
+}
+
+impl gen2::PlaceRecordGen2 {
+    /// See [`record_id`]. Matches the ID of the Gen1 copy of the same entry when the entry
+    /// type is one both generations know.
+    pub fn record_id(&self, owner: &str) -> String {
+        record_id(
+            owner,
+            "place",
+            &[
+                &self.entry_time.0.to_rfc3339(),
+                &key_part(&self.entry_type_daily_work_period),
+            ],
+        )
+    }
+}
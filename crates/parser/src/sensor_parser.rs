@@ -0,0 +1,129 @@
+//! Motion sensor memory dumps, usually with an `.esm` extension.
+//!
+//! The regulation defines the data a motion sensor stores, its identification and the VUs it was
+//! paired with, but no download format for it. Workshop tools export the two structures back to
+//! back, as they are laid out in the spec, which is what is parsed here.
+use crate::binary::{self, BinaryFormat};
+use crate::dt::gen1::{SensorIdentification, SensorInstallation};
+use crate::json::{self, JsonOptions};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct SensorData {
+    pub sensor_identification: SensorIdentification,
+    /// The first and the current pairing of the sensor with a VU.
+    pub sensor_installation: SensorInstallation,
+    /// Vendor data some tools append after the pairing records, kept as is.
+    pub trailing_bytes: Vec<u8>,
+}
+
+pub struct SensorParser {
+    input: Vec<u8>,
+}
+impl SensorParser {
+    pub fn new_from_file(file_path: &str) -> Result<Self> {
+        let input = std::fs::read(file_path).context("Failed to read file")?;
+        Ok(SensorParser { input })
+    }
+    pub fn new_from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(SensorParser {
+            input: bytes.to_vec(),
+        })
+    }
+
+    pub fn parse(&self) -> Result<SensorData> {
+        let mut cursor = Cursor::new(self.input.as_slice());
+        let sensor_identification = SensorIdentification::parse(&mut cursor)
+            .context("Failed to parse sensor_identification")?;
+        let sensor_installation = SensorInstallation::parse(&mut cursor)
+            .context("Failed to parse sensor_installation")?;
+        let mut trailing_bytes = Vec::new();
+        cursor
+            .read_to_end(&mut trailing_bytes)
+            .context("Failed to read trailing bytes")?;
+
+        Ok(SensorData {
+            sensor_identification,
+            sensor_installation,
+            trailing_bytes,
+        })
+    }
+    pub fn parse_to_json(&self) -> Result<String> {
+        let sensor_data = self.parse().context("Failed to parse sensor data")?;
+        let json = serde_json::to_string(&sensor_data)
+            .context("Failed to convert serde value to JSON string")?;
+        Ok(json)
+    }
+    pub fn parse_to_json_pretty(&self) -> Result<String> {
+        let sensor_data = self.parse().context("Failed to parse sensor data")?;
+        let pretty_json = serde_json::to_string_pretty(&sensor_data)
+            .context("Failed to convert serde value to pretty JSON string")?;
+        Ok(pretty_json)
+    }
+    pub fn parse_to_json_with_options(&self, options: &JsonOptions) -> Result<String> {
+        let sensor_data = self.parse().context("Failed to parse sensor data")?;
+        json::to_json_with_source(&sensor_data, &self.input, options)
+    }
+    pub fn parse_to_binary_with_options(
+        &self,
+        format: BinaryFormat,
+        options: &JsonOptions,
+    ) -> Result<Vec<u8>> {
+        let sensor_data = self.parse().context("Failed to parse sensor data")?;
+        binary::to_binary_with_source(&sensor_data, &self.input, format, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extended_serial_number(serial_number: u32, manufacturer_code: u8) -> Vec<u8> {
+        let mut bytes = serial_number.to_be_bytes().to_vec();
+        bytes.extend([0x03, 0x19, 0x06, manufacturer_code]);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_sensor_data() {
+        let mut input = extended_serial_number(12345678, 0x10);
+        input.extend(b"e1-0001 SC-00001OS");
+        input.extend(0x5C3D_8A00u32.to_be_bytes());
+        input.extend(b"e1-0123 ");
+        input.extend(extended_serial_number(1111, 0x20));
+        input.extend(0x6000_0000u32.to_be_bytes());
+        input.extend(b"e1-0456 ");
+        input.extend(extended_serial_number(2222, 0x20));
+        input.extend([0xAA, 0xBB]);
+        assert_eq!(
+            input.len(),
+            SensorIdentification::SIZE + SensorInstallation::SIZE + 2
+        );
+
+        let sensor_data = SensorParser::new_from_bytes(&input)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let identification = &sensor_data.sensor_identification;
+        assert_eq!(identification.sensor_serial_number.serial_number, 12345678);
+        assert_eq!(identification.sensor_approval_number.0 .0, "e1-0001");
+        assert_eq!(identification.sensor_sc_identifier.0 .0, "SC-00001");
+        assert_eq!(identification.sensor_os_identifier.0 .0, "OS");
+        let installation = &sensor_data.sensor_installation;
+        assert_eq!(installation.first_vu_serial_number.serial_number, 1111);
+        assert_eq!(installation.current_vu_serial_number.serial_number, 2222);
+        assert_eq!(installation.current_vu_approval_number.0 .0, "e1-0456");
+        assert_eq!(sensor_data.trailing_bytes, vec![0xAA, 0xBB]);
+
+        let truncated = SensorParser::new_from_bytes(&input[..SensorIdentification::SIZE + 10])
+            .unwrap()
+            .parse();
+        assert!(truncated.is_err());
+    }
+}
@@ -0,0 +1,902 @@
+use crate::analysis;
+use crate::card_parser::CardData;
+use crate::detector::TachoFileType;
+use crate::dt::{
+    self, gen1, gen2, gen2v2, ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus,
+    ActivityChangeInfoSlot, ActivityChangeInfoStatus, CardActivityChangeInfo, CardNumber,
+};
+use crate::record_id::record_id;
+use crate::vu_parser::VuData;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Who or what the downloaded file belongs to: a card holder for card files, a vehicle for VU files.
+pub struct IdentificationRecord {
+    /// Only set when the record comes from a whole parsed file, not when it was peeked.
+    pub file_type: Option<TachoFileType>,
+    pub card_number: Option<String>,
+    pub holder_surname: Option<String>,
+    pub holder_first_names: Option<String>,
+    pub vehicle_identification_number: Option<String>,
+    pub vehicle_registration_number: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A single activity change, with the day-relative minutes already resolved to a timestamp.
+pub struct ActivityRecord {
+    /// Stable across downloads, see [`record_id`].
+    pub id: String,
+    pub time: DateTime<Utc>,
+    pub slot: ActivityChangeInfoSlot,
+    pub activity: ActivityChangeInfoCardActivity,
+    pub card_status: ActivityChangeInfoCardStatus,
+    pub driving_status: ActivityChangeInfoStatus,
+}
+impl ActivityRecord {
+    fn from_change_info(owner: &str, day: &DateTime<Utc>, change: &CardActivityChangeInfo) -> Self {
+        let time = *day + change.minutes.duration();
+        ActivityRecord {
+            id: record_id(
+                owner,
+                "activity",
+                &[&time.to_rfc3339(), &label(&change.slot)],
+            ),
+            time,
+            slot: change.slot.clone(),
+            activity: change.activity.clone(),
+            card_status: change.card_status.clone(),
+            driving_status: change.driving_or_following_activity_status.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum EventKind {
+    Event,
+    Fault,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An event or fault, regardless of generation or whether it was stored on a card or in a VU.
+pub struct EventRecord {
+    /// Stable across downloads, see [`record_id`].
+    pub id: String,
+    pub kind: EventKind,
+    pub event_type: String,
+    pub begin_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+impl EventRecord {
+    fn new(
+        owner: &str,
+        kind: EventKind,
+        event_type: String,
+        begin_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Self {
+        let (begin_time, end_time) = analysis::normalize_event_times(begin_time, end_time);
+        EventRecord {
+            id: record_id(
+                owner,
+                &label(&kind),
+                &[&event_type, &begin_time.to_rfc3339()],
+            ),
+            kind,
+            event_type,
+            begin_time,
+            end_time,
+        }
+    }
+}
+
+/// Receives the records of a parsed file one at a time, so integrators can stream them
+/// into their own storage format instead of walking `CardData`/`VuData` themselves.
+///
+/// The identification is written first. The VU parsers then write the records of every block
+/// while they parse, in file order, which puts activities before events in downloads that keep
+/// their blocks in TREP order, see [`crate::vu_parser::VuStreamParser::parse_to_sink`]. Card
+/// files are written once parsed, activities then events, as only the whole file tells which
+/// generation's copy of the data to write. `finish` is called once after the last record, and
+/// not when parsing fails.
+pub trait TachoSink {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()>;
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()>;
+    fn write_event(&mut self, record: &EventRecord) -> Result<()>;
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "record", rename_all = "camelCase")]
+enum TaggedRecord<'a> {
+    Identification(&'a IdentificationRecord),
+    Activity(&'a ActivityRecord),
+    Event(&'a EventRecord),
+}
+
+/// Writes one JSON object per line, tagged with a `record` field.
+pub struct NdjsonSink<W: Write> {
+    writer: W,
+}
+impl<W: Write> NdjsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        NdjsonSink { writer }
+    }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+    fn write_tagged(&mut self, record: &TaggedRecord) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record).context("Failed to serialize record")?;
+        self.writer
+            .write_all(b"\n")
+            .context("Failed to write NDJSON line")?;
+        Ok(())
+    }
+}
+impl<W: Write> TachoSink for NdjsonSink<W> {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Identification(record))
+    }
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Activity(record))
+    }
+    fn write_event(&mut self, record: &EventRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Event(record))
+    }
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to flush NDJSON sink")
+    }
+}
+
+/// Writes the same lines as [`NdjsonSink`], each preceded by its length in bytes (newline included)
+/// as a big-endian `u32`. Consumers that forward every record as one message, e.g. into a Kafka
+/// producer, can read whole records without scanning for newlines; dropping the prefixes yields NDJSON.
+pub struct LengthPrefixedNdjsonSink<W: Write> {
+    writer: W,
+    line: Vec<u8>,
+}
+impl<W: Write> LengthPrefixedNdjsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        LengthPrefixedNdjsonSink {
+            writer,
+            line: Vec::new(),
+        }
+    }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+    fn write_tagged(&mut self, record: &TaggedRecord) -> Result<()> {
+        self.line.clear();
+        serde_json::to_writer(&mut self.line, record).context("Failed to serialize record")?;
+        self.line.push(b'\n');
+        let length = u32::try_from(self.line.len()).context("Record too large to frame")?;
+        self.writer
+            .write_all(&length.to_be_bytes())
+            .and_then(|_| self.writer.write_all(&self.line))
+            .context("Failed to write framed record")?;
+        Ok(())
+    }
+}
+impl<W: Write> TachoSink for LengthPrefixedNdjsonSink<W> {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Identification(record))
+    }
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Activity(record))
+    }
+    fn write_event(&mut self, record: &EventRecord) -> Result<()> {
+        self.write_tagged(&TaggedRecord::Event(record))
+    }
+    fn finish(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .context("Failed to flush length-prefixed NDJSON sink")
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonDocument {
+    identification: Vec<IdentificationRecord>,
+    activities: Vec<ActivityRecord>,
+    events: Vec<EventRecord>,
+}
+
+/// Collects all records and writes a single JSON document on `finish`.
+pub struct JsonSink<W: Write> {
+    writer: W,
+    pretty: bool,
+    document: JsonDocument,
+}
+impl<W: Write> JsonSink<W> {
+    pub fn new(writer: W) -> Self {
+        JsonSink {
+            writer,
+            pretty: false,
+            document: JsonDocument::default(),
+        }
+    }
+    pub fn new_pretty(writer: W) -> Self {
+        JsonSink {
+            writer,
+            pretty: true,
+            document: JsonDocument::default(),
+        }
+    }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+impl<W: Write> TachoSink for JsonSink<W> {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()> {
+        self.document.identification.push(record.clone());
+        Ok(())
+    }
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()> {
+        self.document.activities.push(record.clone());
+        Ok(())
+    }
+    fn write_event(&mut self, record: &EventRecord) -> Result<()> {
+        self.document.events.push(record.clone());
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<()> {
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut self.writer, &self.document)
+        } else {
+            serde_json::to_writer(&mut self.writer, &self.document)
+        }
+        .context("Failed to serialize JSON document")?;
+        self.writer.flush().context("Failed to flush JSON sink")
+    }
+}
+
+/// Writes every record as a row of a single CSV table; columns that do not apply to a record are left empty.
+pub struct CsvSink<W: Write> {
+    writer: W,
+    header_written: bool,
+}
+impl<W: Write> CsvSink<W> {
+    const HEADER: [&'static str; 14] = [
+        "record",
+        "time",
+        "endTime",
+        "slot",
+        "activity",
+        "cardStatus",
+        "drivingStatus",
+        "eventType",
+        "cardNumber",
+        "holderSurname",
+        "holderFirstNames",
+        "vehicleIdentificationNumber",
+        "vehicleRegistrationNumber",
+        "id",
+    ];
+
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer,
+            header_written: false,
+        }
+    }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    fn write_row(&mut self, row: [String; 14]) -> Result<()> {
+        if !self.header_written {
+            writeln!(self.writer, "{}", Self::HEADER.join(","))
+                .context("Failed to write CSV header")?;
+            self.header_written = true;
+        }
+        let line = row
+            .iter()
+            .map(|field| Self::escape(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(self.writer, "{}", line).context("Failed to write CSV row")?;
+        Ok(())
+    }
+}
+impl<W: Write> TachoSink for CsvSink<W> {
+    fn write_identification(&mut self, record: &IdentificationRecord) -> Result<()> {
+        let opt = |value: &Option<String>| value.clone().unwrap_or_default();
+        self.write_row([
+            "identification".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            opt(&record.card_number),
+            opt(&record.holder_surname),
+            opt(&record.holder_first_names),
+            opt(&record.vehicle_identification_number),
+            opt(&record.vehicle_registration_number),
+            String::new(),
+        ])
+    }
+    fn write_activity(&mut self, record: &ActivityRecord) -> Result<()> {
+        self.write_row([
+            "activity".to_string(),
+            record.time.to_rfc3339(),
+            String::new(),
+            label(&record.slot),
+            label(&record.activity),
+            label(&record.card_status),
+            label(&record.driving_status),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            record.id.clone(),
+        ])
+    }
+    fn write_event(&mut self, record: &EventRecord) -> Result<()> {
+        self.write_row([
+            label(&record.kind).to_lowercase(),
+            record.begin_time.to_rfc3339(),
+            record
+                .end_time
+                .map(|end_time| end_time.to_rfc3339())
+                .unwrap_or_default(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            record.event_type.clone(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            record.id.clone(),
+        ])
+    }
+    fn finish(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to flush CSV sink")
+    }
+}
+
+/// Serialized name of an enum variant, falling back to its JSON representation. Variants that
+/// keep their raw byte, e.g. `RFU(u8)`, are labelled by their name only.
+pub(crate) fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(serde_json::Value::Object(map)) if map.len() == 1 => map
+            .into_iter()
+            .next()
+            .map(|(name, _)| name)
+            .unwrap_or_default(),
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+pub(crate) fn card_number_to_string(card_number: &CardNumber) -> Option<String> {
+    match card_number {
+        CardNumber::Driver {
+            driver_identification,
+            card_replacement_index,
+            card_renewal_index,
+        } => Some(format!(
+            "{}{}{}",
+            driver_identification.0, card_replacement_index.0 .0, card_renewal_index.0 .0
+        )),
+        CardNumber::Owner {
+            owner_identification,
+            card_consecutive_index,
+            card_replacement_index,
+            card_renewal_index,
+        } => Some(format!(
+            "{}{}{}{}",
+            owner_identification.0,
+            card_consecutive_index.0 .0,
+            card_replacement_index.0 .0,
+            card_renewal_index.0 .0
+        )),
+        CardNumber::Raw { .. } | CardNumber::None => None,
+    }
+}
+
+pub(crate) fn card_identification_record(
+    identification: &dt::Identification,
+) -> IdentificationRecord {
+    let holder = &identification
+        .driver_card_holder_identification
+        .card_holder_number;
+    IdentificationRecord {
+        card_number: card_number_to_string(&identification.card_identification.card_number),
+        holder_surname: Some(holder.holder_surname.name.0.clone()),
+        holder_first_names: Some(holder.holder_first_names.name.0.clone()),
+        ..Default::default()
+    }
+}
+
+/// Identification of a VU file, from the first VIN and registration number of its overview block.
+pub(crate) fn vu_identification_record(
+    vin: Option<&dt::VehicleIdentificationNumber>,
+    vehicle_reg_number: Option<&dt::IA5String>,
+) -> IdentificationRecord {
+    IdentificationRecord {
+        vehicle_identification_number: vin.map(|vin| vin.0 .0.clone()),
+        vehicle_registration_number: vehicle_reg_number.map(|number| number.0.clone()),
+        ..Default::default()
+    }
+}
+
+fn write_card_identification(
+    file_type: TachoFileType,
+    identification: &dt::Identification,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    sink.write_identification(&IdentificationRecord {
+        file_type: Some(file_type),
+        ..card_identification_record(identification)
+    })
+}
+
+/// Card number the IDs of a card's records are derived from.
+fn card_owner(identification: &dt::Identification) -> String {
+    card_number_to_string(&identification.card_identification.card_number).unwrap_or_default()
+}
+
+fn write_card_activities(
+    driver_activity_data: &dt::DriverActivityData,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for daily_record in &driver_activity_data
+        .card_driver_activity
+        .activity_daily_records
+    {
+        let day = &daily_record.activity_record_date.0;
+        for change in &daily_record.activity_change_info {
+            sink.write_activity(&ActivityRecord::from_change_info(owner, day, change))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_card_gen1_events(
+    blocks: &gen1::CardEventData,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in blocks.records.iter().flatten() {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Event,
+            label(&record.event_type),
+            record.event_begin_time.0,
+            record.event_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    Ok(())
+}
+
+fn write_card_gen1_faults(
+    blocks: &gen1::CardFaultData,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in blocks.records.iter().flatten() {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Fault,
+            label(&record.fault_type),
+            record.fault_begin_time.0,
+            record.fault_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    Ok(())
+}
+
+fn write_card_gen2_events(
+    blocks: &gen2::CardEventDataGen2,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in blocks.records() {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Event,
+            label(&record.event_type),
+            record.event_begin_time.0,
+            record.event_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    Ok(())
+}
+
+fn write_card_gen2_faults(
+    blocks: &gen2::CardFaultDataGen2,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in blocks.records.iter().flatten() {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Fault,
+            label(&record.fault_type),
+            record.fault_begin_time.0,
+            record.fault_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Feeds a parsed card file into a sink. Gen2 cards carry a copy of the Gen1 data,
+/// so only the most recent generation's blocks are written to avoid duplicate records.
+pub fn write_card_data(card_data: &CardData, sink: &mut impl TachoSink) -> Result<()> {
+    match card_data {
+        CardData::Gen1 { gen1_blocks, .. } => {
+            let owner = card_owner(&gen1_blocks.identification);
+            write_card_identification(card_data.file_type(), &gen1_blocks.identification, sink)?;
+            write_card_activities(&gen1_blocks.driver_activity_data, &owner, sink)?;
+            write_card_gen1_events(&gen1_blocks.events_data, &owner, sink)?;
+            write_card_gen1_faults(&gen1_blocks.faults_data, &owner, sink)?;
+        }
+        CardData::Gen2 { gen2_blocks, .. } | CardData::Gen2V2 { gen2_blocks, .. } => {
+            let owner = card_owner(&gen2_blocks.identification);
+            write_card_identification(card_data.file_type(), &gen2_blocks.identification, sink)?;
+            write_card_activities(&gen2_blocks.driver_activity_data, &owner, sink)?;
+            write_card_gen2_events(&gen2_blocks.events_data, &owner, sink)?;
+            write_card_gen2_faults(&gen2_blocks.faults_data, &owner, sink)?;
+        }
+    }
+    sink.finish()
+}
+
+fn write_vu_activities<'a>(
+    owner: &str,
+    day: Option<&DateTime<Utc>>,
+    changes: impl IntoIterator<Item = &'a CardActivityChangeInfo>,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    // Without the downloaded day the minutes cannot be anchored to a timestamp
+    let Some(day) = day else {
+        return Ok(());
+    };
+    for change in changes {
+        sink.write_activity(&ActivityRecord::from_change_info(owner, day, change))?;
+    }
+    Ok(())
+}
+
+/// Identification of a Gen1 VU file, from its overview block.
+pub(crate) fn vu_gen1_identification(overview: &gen1::VuOverviewBlock) -> IdentificationRecord {
+    IdentificationRecord {
+        file_type: Some(TachoFileType::VehicleUnitGen1),
+        ..vu_identification_record(
+            Some(&overview.vehicle_identification_number),
+            Some(
+                &overview
+                    .vehicle_registration_identification
+                    .vehicle_registration_number
+                    .vehicle_reg_number,
+            ),
+        )
+    }
+}
+
+/// Identification of a Gen2 VU file, from its overview block.
+pub(crate) fn vu_gen2_identification(overview: &gen2::VuOverviewBlockGen2) -> IdentificationRecord {
+    IdentificationRecord {
+        file_type: Some(TachoFileType::VehicleUnitGen2),
+        ..vu_identification_record(
+            overview.vehicle_identification_number_record_array.first(),
+            overview
+                .vehicle_registration_number_record_array
+                .first()
+                .map(|vrn| &vrn.vehicle_reg_number),
+        )
+    }
+}
+
+/// Identification of a Gen2V2 VU file, from its overview block.
+pub(crate) fn vu_gen2v2_identification(
+    overview: &gen2v2::VuOverviewBlockGen2V2,
+) -> IdentificationRecord {
+    IdentificationRecord {
+        file_type: Some(TachoFileType::VehicleUnitGen2V2),
+        ..vu_identification_record(
+            overview.vehicle_identification_number_record_array.first(),
+            overview
+                .vehicle_registration_identification_record_array
+                .first()
+                .map(|vri| &vri.vehicle_registration_number.vehicle_reg_number),
+        )
+    }
+}
+
+/// Vehicle the IDs of a VU file's records are derived from.
+pub(crate) fn vu_owner(identification: &IdentificationRecord) -> String {
+    identification
+        .vehicle_identification_number
+        .clone()
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_vu_gen1_activities(
+    activities: &gen1::VuActivitiesBlock,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    write_vu_activities(
+        owner,
+        Some(&activities.time_real.0),
+        &activities.vu_activity_daily_data.activity_change_infos,
+        sink,
+    )
+}
+
+pub(crate) fn write_vu_gen2_activities(
+    activities: &gen2::VuActivitiesBlockGen2,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    write_vu_activities(
+        owner,
+        activities
+            .date_of_day_downloaded_record_array
+            .first()
+            .map(|day| &day.0 .0),
+        &activities.vu_activity_daily_record_array,
+        sink,
+    )
+}
+
+pub(crate) fn write_vu_gen2v2_activities(
+    activities: &gen2v2::VuActivitiesBlockGen2V2,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    write_vu_activities(
+        owner,
+        activities
+            .date_of_day_downloaded_record_array
+            .first()
+            .map(|day| &day.0 .0),
+        &activities.vu_activity_daily_record_array,
+        sink,
+    )
+}
+
+pub(crate) fn write_vu_gen1_events(
+    events_and_faults: &gen1::VuEventsAndFaultsBlock,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in &events_and_faults.vu_event_data.vu_event_records {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Event,
+            label(&record.event_type),
+            record.event_begin_time.0,
+            record.event_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    for record in &events_and_faults.vu_fault_data.vu_fault_records {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Fault,
+            label(&record.fault_type),
+            record.fault_begin_time.0,
+            Some(record.fault_end_time.0),
+        ))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_vu_gen2_events(
+    events_and_faults: &gen2::VuEventsAndFaultsBlockGen2,
+    owner: &str,
+    sink: &mut impl TachoSink,
+) -> Result<()> {
+    for record in &events_and_faults.vu_event_record_array {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Event,
+            label(&record.event_type),
+            record.event_begin_time.0,
+            record.event_end_time.as_ref().map(|t| t.0),
+        ))?;
+    }
+    for record in &events_and_faults.vu_fault_record_array {
+        sink.write_event(&EventRecord::new(
+            owner,
+            EventKind::Fault,
+            label(&record.fault_type),
+            record.fault_begin_time.0,
+            Some(record.fault_end_time.0),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Feeds a parsed VU file into a sink. The parsers write the same records block by block while
+/// they parse, see [`crate::vu_parser::VuParser::parse_to_sink`].
+pub fn write_vu_data(vu_data: &VuData, sink: &mut impl TachoSink) -> Result<()> {
+    match vu_data {
+        VuData::Gen1(blocks) => {
+            let identification = vu_gen1_identification(&blocks.vu_overview);
+            let owner = vu_owner(&identification);
+            sink.write_identification(&identification)?;
+            for activities in &blocks.vu_activities {
+                write_vu_gen1_activities(activities, &owner, sink)?;
+            }
+            for events_and_faults in &blocks.vu_events_and_faults {
+                write_vu_gen1_events(events_and_faults, &owner, sink)?;
+            }
+        }
+        VuData::Gen2(blocks) => {
+            let identification = vu_gen2_identification(&blocks.vu_overview);
+            let owner = vu_owner(&identification);
+            sink.write_identification(&identification)?;
+            for activities in &blocks.vu_activities {
+                write_vu_gen2_activities(activities, &owner, sink)?;
+            }
+            for events_and_faults in &blocks.vu_events_and_faults {
+                write_vu_gen2_events(events_and_faults, &owner, sink)?;
+            }
+        }
+        VuData::Gen2V2(blocks) => {
+            let identification = vu_gen2v2_identification(&blocks.vu_overview);
+            let owner = vu_owner(&identification);
+            sink.write_identification(&identification)?;
+            for activities in &blocks.vu_activities {
+                write_vu_gen2v2_activities(activities, &owner, sink)?;
+            }
+            for events_and_faults in &blocks.vu_events_and_faults {
+                write_vu_gen2_events(events_and_faults, &owner, sink)?;
+            }
+        }
+    }
+    sink.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::Minutes;
+
+    fn sample_activity() -> ActivityRecord {
+        ActivityRecord {
+            id: "0123456789abcdef0123456789abcdef".to_string(),
+            time: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            slot: ActivityChangeInfoSlot::Driver,
+            activity: ActivityChangeInfoCardActivity::Driving,
+            card_status: ActivityChangeInfoCardStatus::Inserted,
+            driving_status: ActivityChangeInfoStatus::Single,
+        }
+    }
+
+    #[test]
+    fn test_ndjson_sink_writes_one_tagged_line_per_record() {
+        let mut sink = NdjsonSink::new(Vec::new());
+        sink.write_activity(&sample_activity()).unwrap();
+        sink.write_event(&EventRecord::new(
+            "1234567890ABCDEFG",
+            EventKind::Fault,
+            "PowerSupplyInterruption".to_string(),
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            None,
+        ))
+        .unwrap();
+        sink.finish().unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["record"], "activity");
+        assert_eq!(first["activity"], "Driving");
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["record"], "event");
+        assert_eq!(second["kind"], "Fault");
+    }
+
+    #[test]
+    fn test_length_prefixed_sink_frames_ndjson_lines() {
+        let mut sink = LengthPrefixedNdjsonSink::new(Vec::new());
+        sink.write_identification(&IdentificationRecord::default())
+            .unwrap();
+        sink.write_activity(&sample_activity()).unwrap();
+        sink.finish().unwrap();
+
+        let output = sink.into_inner();
+        let mut rest = output.as_slice();
+        let mut lines = Vec::new();
+        while !rest.is_empty() {
+            let (length, tail) = rest.split_at(4);
+            let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+            let (line, tail) = tail.split_at(length);
+            lines.push(std::str::from_utf8(line).unwrap());
+            rest = tail;
+        }
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.ends_with('\n')));
+
+        let mut ndjson = NdjsonSink::new(Vec::new());
+        ndjson
+            .write_identification(&IdentificationRecord::default())
+            .unwrap();
+        ndjson.write_activity(&sample_activity()).unwrap();
+        assert_eq!(
+            lines.concat(),
+            String::from_utf8(ndjson.into_inner()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_csv_sink_escapes_fields() {
+        let mut sink = CsvSink::new(Vec::new());
+        sink.write_identification(&IdentificationRecord {
+            holder_surname: Some("O\"Brien, Jr".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        sink.write_activity(&sample_activity()).unwrap();
+        sink.finish().unwrap();
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("record,time,endTime"));
+        assert!(lines[1].contains("\"O\"\"Brien, Jr\""));
+        assert!(lines[2].starts_with("activity,2023-11-14T22:13:20+00:00,,Driver,Driving"));
+        assert!(lines[2].ends_with(",0123456789abcdef0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_record_ids_are_stable_across_downloads() {
+        let day = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let change = CardActivityChangeInfo {
+            slot: ActivityChangeInfoSlot::Driver,
+            driving_or_following_activity_status: ActivityChangeInfoStatus::Single,
+            card_status: ActivityChangeInfoCardStatus::Inserted,
+            activity: ActivityChangeInfoCardActivity::Driving,
+            minutes: Minutes(60),
+        };
+        let first = ActivityRecord::from_change_info("1234567890ABCDEFG", &day, &change);
+        let again = ActivityRecord::from_change_info("1234567890ABCDEFG", &day, &change);
+        assert_eq!(first.id, again.id);
+        assert_eq!(first.id.len(), 32);
+
+        let co_driver = CardActivityChangeInfo {
+            slot: ActivityChangeInfoSlot::CoDriver,
+            ..change.clone()
+        };
+        let other_slot = ActivityRecord::from_change_info("1234567890ABCDEFG", &day, &co_driver);
+        let other_owner = ActivityRecord::from_change_info("ABCDEFG1234567890", &day, &change);
+        assert_ne!(first.id, other_slot.id);
+        assert_ne!(first.id, other_owner.id);
+    }
+}
@@ -0,0 +1,172 @@
+use std::borrow::Cow;
+use std::io;
+
+/// Random-access byte source a download can be parsed from, see [`crate::parse_from_source`].
+///
+/// Lets integrations parse data that is not a plain file or buffer, e.g. a blob that is decrypted
+/// on the fly, without writing the plaintext to disk first. Implemented for byte slices, `Vec<u8>`,
+/// files and, through [`MemorySource`], any other in-memory buffer such as a memory map.
+pub trait TachoRead {
+    /// Total size of the source in bytes.
+    fn size(&self) -> io::Result<u64>;
+    /// Fills `buf` with the bytes starting at `offset`, failing with
+    /// [`io::ErrorKind::UnexpectedEof`] if the source ends first.
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()>;
+    /// `size` bytes starting at `offset`, borrowed when the source is in memory.
+    fn take_exact(&self, offset: u64, size: usize) -> io::Result<Cow<'_, [u8]>> {
+        let mut buf = vec![0; size];
+        self.read_exact_at(offset, &mut buf)?;
+        Ok(Cow::Owned(buf))
+    }
+}
+
+fn out_of_bounds(offset: u64, size: usize, len: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!(
+            "Attempted to read {} bytes at offset {}, but the source has {} bytes",
+            size, offset, len
+        ),
+    )
+}
+
+impl TachoRead for [u8] {
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        buf.copy_from_slice(&self.take_exact(offset, buf.len())?);
+        Ok(())
+    }
+    fn take_exact(&self, offset: u64, size: usize) -> io::Result<Cow<'_, [u8]>> {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|start| self.get(start..start.checked_add(size)?))
+            .map(Cow::Borrowed)
+            .ok_or_else(|| out_of_bounds(offset, size, self.len()))
+    }
+}
+
+impl TachoRead for Vec<u8> {
+    fn size(&self) -> io::Result<u64> {
+        self.as_slice().size()
+    }
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        self.as_slice().read_exact_at(offset, buf)
+    }
+    fn take_exact(&self, offset: u64, size: usize) -> io::Result<Cow<'_, [u8]>> {
+        self.as_slice().take_exact(offset, size)
+    }
+}
+
+impl<T: TachoRead + ?Sized> TachoRead for &T {
+    fn size(&self) -> io::Result<u64> {
+        (**self).size()
+    }
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_exact_at(offset, buf)
+    }
+    fn take_exact(&self, offset: u64, size: usize) -> io::Result<Cow<'_, [u8]>> {
+        (**self).take_exact(offset, size)
+    }
+}
+
+impl TachoRead for std::fs::File {
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+    #[cfg(unix)]
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        std::os::unix::fs::FileExt::read_exact_at(self, buf, offset)
+    }
+    #[cfg(windows)]
+    fn read_exact_at(&self, mut offset: u64, mut buf: &mut [u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            match std::os::windows::fs::FileExt::seek_read(self, buf, offset) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => {
+                    buf = &mut buf[read..];
+                    offset += read as u64;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Adapts any in-memory buffer, e.g. a `memmap2::Mmap` or `bytes::Bytes`, into a [`TachoRead`]
+/// that is read without copying.
+pub struct MemorySource<T: AsRef<[u8]>>(pub T);
+impl<T: AsRef<[u8]>> TachoRead for MemorySource<T> {
+    fn size(&self) -> io::Result<u64> {
+        self.0.as_ref().size()
+    }
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        self.0.as_ref().read_exact_at(offset, buf)
+    }
+    fn take_exact(&self, offset: u64, size: usize) -> io::Result<Cow<'_, [u8]>> {
+        self.0.as_ref().take_exact(offset, size)
+    }
+}
+
+/// The whole content of a source. The parsers work on contiguous memory, so sources that are
+/// not already in memory are read once, in a single call.
+pub(crate) fn read_all<S: TachoRead + ?Sized>(source: &S) -> io::Result<Cow<'_, [u8]>> {
+    let size = usize::try_from(source.size()?)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Source too large"))?;
+    source.take_exact(0, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vu_parser::VuParser;
+
+    /// Stands in for an encrypted blob that is decrypted while it is read.
+    struct XorSource {
+        ciphertext: Vec<u8>,
+        key: u8,
+    }
+    impl TachoRead for XorSource {
+        fn size(&self) -> io::Result<u64> {
+            Ok(self.ciphertext.len() as u64)
+        }
+        fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+            self.ciphertext.read_exact_at(offset, buf)?;
+            buf.iter_mut().for_each(|byte| *byte ^= self.key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_from_custom_source() {
+        let plaintext = include_bytes!("../tests/fixtures/vu_gen1_unknown_block.ddd");
+        let source = XorSource {
+            ciphertext: plaintext.iter().map(|byte| byte ^ 0x5A).collect(),
+            key: 0x5A,
+        };
+        let expected = VuParser::new_from_bytes(plaintext)
+            .unwrap()
+            .parse_to_json()
+            .unwrap();
+        let actual = VuParser::new_from_source(&source)
+            .unwrap()
+            .parse_to_json()
+            .unwrap();
+        assert_eq!(actual, expected);
+
+        let slice: &[u8] = plaintext;
+        assert!(matches!(slice.take_exact(2, 4).unwrap(), Cow::Borrowed(_)));
+        let error = slice.take_exact(plaintext.len() as u64 - 1, 2).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(
+            MemorySource(plaintext.to_vec())
+                .take_exact(0, 2)
+                .unwrap()
+                .as_ref(),
+            &plaintext[..2]
+        );
+    }
+}
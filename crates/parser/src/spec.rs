@@ -0,0 +1,67 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Where a data type is defined in appendix 1 (data dictionary) of Regulation (EU) 2016/799.
+pub struct SpecRef {
+    /// Path of the Rust type, e.g. `dt::gen2::FullCardNumberGen2`.
+    pub rust_type: &'static str,
+    /// Name of the data type in the regulation, e.g. `FullCardNumber`.
+    pub data_type: &'static str,
+    /// Section of appendix 1, e.g. `2.73`.
+    pub section: &'static str,
+    /// Link to the section in the consolidated text on EUR-Lex.
+    pub url: &'static str,
+}
+
+/// Types of [`crate::dt`] that are defined by a section of appendix 1.
+pub trait SpecDocumented {
+    fn spec_ref() -> &'static SpecRef;
+}
+
+// Generated by build.rs from the appendix links in the doc comments of `src/dt`
+include!(concat!(env!("OUT_DIR"), "/spec_refs.rs"));
+
+/// Every data type with an appendix reference, in source order. Type aliases are listed here
+/// but don't implement [`SpecDocumented`], as their target type already does.
+pub fn spec_refs() -> &'static [SpecRef] {
+    SPEC_REFS
+}
+
+/// Finds the reference of a type by its Rust path, e.g. `dt::gen1::CardIccIdentification`,
+/// or by the name of the data type in the regulation, e.g. `CardIccIdentification`. Several
+/// types share a data type name across generations, the first one is returned.
+pub fn find_spec_ref(name: &str) -> Option<&'static SpecRef> {
+    SPEC_REFS
+        .iter()
+        .find(|spec_ref| spec_ref.rust_type == name)
+        .or_else(|| SPEC_REFS.iter().find(|spec_ref| spec_ref.data_type == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dt::gen2::{FullCardNumberGen2, VuCertificateGen2};
+    use crate::dt::TimeReal;
+
+    #[test]
+    fn test_spec_refs() {
+        let full_card_number = FullCardNumberGen2::spec_ref();
+        assert_eq!(full_card_number.rust_type, "dt::gen2::FullCardNumberGen2");
+        assert_eq!(full_card_number.data_type, "FullCardNumber");
+        assert_eq!(full_card_number.section, "2.73");
+        assert!(full_card_number.url.ends_with("#cons_toc_d1e21400"));
+        assert_eq!(TimeReal::spec_ref().section, "2.162");
+
+        // aliases are only in the registry
+        let vu_certificate = find_spec_ref("dt::gen2::VuCertificateGen2").unwrap();
+        assert_eq!(vu_certificate.data_type, "VuCertificate");
+        assert_eq!(VuCertificateGen2::spec_ref().data_type, "Certificate");
+
+        assert_eq!(find_spec_ref("TimeReal"), Some(TimeReal::spec_ref()));
+        assert_eq!(find_spec_ref("NoSuchType"), None);
+        assert!(spec_refs()
+            .iter()
+            .all(|spec_ref| spec_ref.url.starts_with("https://eur-lex.europa.eu/")));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::context::scoped;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::fmt::Write;
+use std::panic::Location;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+/// How many bytes of a byte string are shown in its value summary.
+const MAX_SUMMARIZED_BYTES: usize = 16;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A single primitive read performed by the parser.
+pub struct TraceEntry {
+    /// Offset from the start of the file, or `None` when the bytes were read from a buffer
+    /// assembled by the parser (e.g. the unwrapped cyclic activity data of a card).
+    pub offset: Option<usize>,
+    pub length: usize,
+    /// Primitive that was read: `u8`, `u16`, `u32` or `bytes`.
+    pub type_name: String,
+    pub value: String,
+    /// Source location of the read, which identifies the field being parsed.
+    pub location: String,
+}
+
+struct Recorder {
+    input_start: usize,
+    input_len: usize,
+    entries: Vec<TraceEntry>,
+}
+
+thread_local! {
+    /// Whether a [`capture`] runs on this thread, checked before the recorder is borrowed so that
+    /// reads outside of one only cost a flag check.
+    static TRACING: Cell<bool> = const { Cell::new(false) };
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Runs `parse` while recording every primitive read of `input` made on this thread.
+pub fn capture<T>(input: &[u8], parse: impl FnOnce() -> T) -> (T, Vec<TraceEntry>) {
+    let recorder = Recorder {
+        input_start: input.as_ptr() as usize,
+        input_len: input.len(),
+        entries: Vec::new(),
+    };
+    let ((output, recorder), _) =
+        scoped(&TRACING, true, || scoped(&RECORDER, Some(recorder), parse));
+    let entries = recorder.expect("trace recorder missing").entries;
+    (output, entries)
+}
+
+/// Called by [`crate::bytes::TracedRead`] after each successful read of `length` bytes at `position` of `buffer`.
+#[track_caller]
+pub(crate) fn record(
+    buffer: &[u8],
+    position: u64,
+    length: usize,
+    type_name: &str,
+    summarize: impl FnOnce() -> String,
+) {
+    if !TRACING.with(Cell::get) {
+        return;
+    }
+    let location = Location::caller();
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let Some(recorder) = recorder.as_mut() else {
+            return;
+        };
+        // Sub-cursors borrow slices of the input, so the file offset follows from the slice address
+        let address = buffer.as_ptr() as usize + position as usize;
+        let offset = address
+            .checked_sub(recorder.input_start)
+            .filter(|offset| offset + length <= recorder.input_len);
+        recorder.entries.push(TraceEntry {
+            offset,
+            length,
+            type_name: type_name.to_string(),
+            value: summarize(),
+            location: format!("{}:{}", location.file(), location.line()),
+        });
+    });
+}
+
+pub(crate) fn summarize_bytes(bytes: &[u8]) -> String {
+    let mut summary = hex(&bytes[..bytes.len().min(MAX_SUMMARIZED_BYTES)]);
+    if bytes.len() > MAX_SUMMARIZED_BYTES {
+        summary.push_str(" ..");
+    }
+    if !bytes.is_empty() && bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        let _ = write!(
+            summary,
+            " \"{}\"",
+            String::from_utf8_lossy(bytes).trim_end()
+        );
+    }
+    summary
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Renders the trace as an annotated hex dump, one line per read in file order.
+/// Bytes the parser never read are listed as gaps, reads outside the file are listed at the end.
+pub fn render_annotated_hex(input: &[u8], entries: &[TraceEntry]) -> String {
+    let mut in_file: Vec<&TraceEntry> = entries.iter().filter(|e| e.offset.is_some()).collect();
+    in_file.sort_by_key(|entry| entry.offset);
+
+    let mut output = String::new();
+    let mut cursor = 0;
+    for entry in in_file {
+        let offset = entry.offset.unwrap_or_default();
+        if offset > cursor {
+            let _ = writeln!(
+                output,
+                "{:08X}  {:<48}  not read ({} bytes)",
+                cursor,
+                summarize_bytes(&input[cursor..offset]),
+                offset - cursor
+            );
+        }
+        let _ = writeln!(
+            output,
+            "{:08X}  {:<48}  {:<5} {}  {}",
+            offset,
+            hex(&input[offset..(offset + entry.length).min(offset + MAX_SUMMARIZED_BYTES)]),
+            entry.type_name,
+            entry.value,
+            entry.location
+        );
+        cursor = cursor.max(offset + entry.length);
+    }
+    if cursor < input.len() {
+        let _ = writeln!(
+            output,
+            "{:08X}  {:<48}  not read ({} bytes)",
+            cursor,
+            summarize_bytes(&input[cursor..]),
+            input.len() - cursor
+        );
+    }
+    for entry in entries.iter().filter(|e| e.offset.is_none()) {
+        let _ = writeln!(
+            output,
+            "--------  {:<48}  {:<5} {}  {}",
+            "", entry.type_name, entry.value, entry.location
+        );
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytes::TracedRead;
+    use byteorder::BigEndian;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_capture_records_file_offsets_of_sub_cursor_reads() {
+        let input = [0x01, 0x00, 0x05, 0x41, 0x42, 0xFF];
+        let (sum, entries) = capture(&input, || {
+            let mut cursor = Cursor::new(&input[..]);
+            let a = cursor.read_u8().unwrap();
+            // a cursor over a sub-slice, as produced by `take_exact`
+            let mut inner = Cursor::new(&input[1..5]);
+            let b = inner.read_u16::<BigEndian>().unwrap();
+            let mut text = [0u8; 2];
+            inner.read_exact(&mut text).unwrap();
+            // a buffer that is not part of the input
+            let copy = input.to_vec();
+            let c = Cursor::new(&copy[..]).read_u8().unwrap();
+            a as u16 + b + c as u16
+        });
+        assert_eq!(sum, 7);
+
+        let offsets: Vec<_> = entries.iter().map(|e| e.offset).collect();
+        assert_eq!(offsets, vec![Some(0), Some(1), Some(3), None]);
+        assert_eq!(entries[1].value, "0x0005 (5)");
+        assert_eq!(entries[2].value, "41 42 \"AB\"");
+        assert!(entries[0].location.contains("trace.rs:"));
+
+        let rendered = render_annotated_hex(&input, &entries);
+        assert!(rendered.contains("not read (1 bytes)"));
+
+        // nothing is recorded outside of `capture`
+        let (_, entries) = capture(&input, || ());
+        Cursor::new(&input[..]).read_u8().unwrap();
+        assert!(entries.is_empty());
+        assert!(!TRACING.with(Cell::get));
+    }
+}
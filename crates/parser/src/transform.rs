@@ -0,0 +1,244 @@
+//! Transforms applied to parsed data before it is stored.
+
+use crate::dt::gen2::GeoCoordinatesGen2;
+use crate::dt::VuDetailedSpeedBlock;
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// What [`minimize`] keeps of the per second speed in the detailed speed blocks.
+pub enum SpeedRetention {
+    Keep,
+    /// Keeps the minutes of speed that begin at or after this time, e.g. the last 24 hours
+    /// an accident investigation may ask for.
+    Since(DateTime<Utc>),
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// What [`minimize`] keeps of the GNSS positions of the place, accumulated driving, border
+/// crossing and load/unload records.
+pub enum PositionRetention {
+    Keep,
+    /// Snaps positions to a grid of `step_degrees`, e.g. `0.1` for about 10 km.
+    Coarsen {
+        step_degrees: f64,
+    },
+    /// Replaces positions with [`GeoCoordinatesGen2::UNKNOWN_POSITION`].
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Which high resolution data [`minimize`] strips. The default keeps neither detailed speed nor
+/// positions.
+pub struct RetentionPolicy {
+    pub detailed_speed: SpeedRetention,
+    pub positions: PositionRetention,
+}
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            detailed_speed: SpeedRetention::Drop,
+            positions: PositionRetention::Remove,
+        }
+    }
+}
+
+/// Strips the per second speed and GNSS positions `policy` does not keep from `vu_data`, so that
+/// only what is needed is persisted.
+///
+/// Activities, card insertions, odometer values and events and faults, overspeeding included,
+/// are left as they are, as are the time, country and odometer of the records positions are
+/// removed from. Signatures are kept but no longer match the data. The segments are cleared,
+/// exporting them would copy the stripped data back from the original download.
+pub fn minimize(vu_data: &mut VuData, policy: RetentionPolicy) {
+    match vu_data {
+        VuData::Gen1(blocks) => {
+            for block in &mut blocks.vu_detailed_speed {
+                let data = &mut block.vu_detailed_speed_data;
+                retain_speed(&mut data.vu_detailed_speed_records, policy.detailed_speed);
+                data.no_of_speed_blocks = data.vu_detailed_speed_records.len() as u16;
+            }
+            blocks
+                .vu_detailed_speed
+                .retain(|block| block.vu_detailed_speed_data.no_of_speed_blocks != 0);
+            blocks.segments = None;
+        }
+        VuData::Gen2(blocks) => {
+            for block in &mut blocks.vu_detailed_speed {
+                retain_speed(
+                    &mut block.vu_detailed_speed_block_record_array,
+                    policy.detailed_speed,
+                );
+            }
+            blocks
+                .vu_detailed_speed
+                .retain(|block| !block.vu_detailed_speed_block_record_array.is_empty());
+            for block in &mut blocks.vu_activities {
+                for record in &mut block.vu_place_daily_work_period_record_array {
+                    let place = &mut record.place_record.entry_gnss_place_record;
+                    minimize_position(&mut place.geo_coordinates, policy.positions);
+                }
+                for record in &mut block.vu_gnss_ad_record_array {
+                    let place = &mut record.gnss_place_record;
+                    minimize_position(&mut place.geo_coordinates, policy.positions);
+                }
+            }
+            blocks.segments = None;
+        }
+        VuData::Gen2V2(blocks) => {
+            for block in &mut blocks.vu_activities {
+                let places = block
+                    .vu_place_daily_work_period_record_array
+                    .iter_mut()
+                    .map(|record| &mut record.place_record.entry_gnss_place_auth_record);
+                let accumulated_driving = block
+                    .vu_gnss_ad_record_array
+                    .iter_mut()
+                    .map(|record| &mut record.gnss_place_auth_record);
+                let border_crossings = block
+                    .vu_border_crossing_record_array
+                    .iter_mut()
+                    .map(|record| &mut record.gnss_place_auth_record);
+                let load_unload = block
+                    .vu_load_unload_record_array
+                    .iter_mut()
+                    .map(|record| &mut record.gnss_place_auth_record);
+                for place in places
+                    .chain(accumulated_driving)
+                    .chain(border_crossings)
+                    .chain(load_unload)
+                {
+                    minimize_position(&mut place.gnss_coordinates, policy.positions);
+                }
+            }
+            blocks.segments = None;
+        }
+    }
+}
+
+fn retain_speed(blocks: &mut Vec<VuDetailedSpeedBlock>, retention: SpeedRetention) {
+    match retention {
+        SpeedRetention::Keep => {}
+        SpeedRetention::Since(since) => {
+            blocks.retain(|block| block.speed_block_begin_date.0 >= since)
+        }
+        SpeedRetention::Drop => blocks.clear(),
+    }
+}
+
+fn minimize_position(coordinates: &mut GeoCoordinatesGen2, retention: PositionRetention) {
+    match retention {
+        PositionRetention::Keep => {}
+        PositionRetention::Coarsen { step_degrees } => {
+            let (Some(latitude), Some(longitude)) = (coordinates.latitude, coordinates.longitude)
+            else {
+                return;
+            };
+            let snap = |value: f64| (value / step_degrees).round() * step_degrees;
+            *coordinates = GeoCoordinatesGen2 {
+                precision_degrees: coordinates.precision_degrees.max(step_degrees / 2.0),
+                ..GeoCoordinatesGen2::from_raw(
+                    GeoCoordinatesGen2::encode_coordinate(snap(latitude)),
+                    GeoCoordinatesGen2::encode_coordinate(snap(longitude)),
+                )
+            };
+        }
+        PositionRetention::Remove => {
+            *coordinates = GeoCoordinatesGen2::from_raw(
+                GeoCoordinatesGen2::UNKNOWN_POSITION,
+                GeoCoordinatesGen2::UNKNOWN_POSITION,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dt::{gen1, Speed, TimeReal};
+    use crate::units::Kmh;
+    use crate::vu_parser::VuParser;
+
+    fn speed_block(begin: &str) -> VuDetailedSpeedBlock {
+        VuDetailedSpeedBlock {
+            speed_block_begin_date: TimeReal(begin.parse().unwrap()),
+            speeds_per_second: (0..60).map(|_| Speed(Kmh(80))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_minimize_detailed_speed() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let mut vu_data = VuParser::new_from_bytes(input)
+            .unwrap()
+            .keep_segments(true)
+            .parse()
+            .unwrap();
+        let VuData::Gen1(blocks) = &mut vu_data else {
+            unreachable!()
+        };
+        let records = vec![
+            speed_block("2024-03-01T10:00:00Z"),
+            speed_block("2024-03-02T10:00:00Z"),
+        ];
+        blocks.vu_detailed_speed.push(gen1::VuDetailedSpeedBlock {
+            vu_detailed_speed_data: gen1::VuDetailedSpeedData {
+                no_of_speed_blocks: records.len() as u16,
+                vu_detailed_speed_records: records,
+            },
+            signature: gen1::Signature(vec![0; 128]),
+        });
+
+        let mut kept = vu_data.clone();
+        let policy = RetentionPolicy {
+            detailed_speed: SpeedRetention::Since("2024-03-02T00:00:00Z".parse().unwrap()),
+            positions: PositionRetention::Keep,
+        };
+        minimize(&mut kept, policy);
+        let VuData::Gen1(blocks) = &kept else {
+            unreachable!()
+        };
+        let data = &blocks.vu_detailed_speed[0].vu_detailed_speed_data;
+        assert_eq!(data.no_of_speed_blocks, 1);
+        assert_eq!(
+            data.vu_detailed_speed_records[0].speed_block_begin_date,
+            TimeReal("2024-03-02T10:00:00Z".parse().unwrap())
+        );
+        assert!(blocks.segments.is_none());
+
+        minimize(&mut vu_data, RetentionPolicy::default());
+        let VuData::Gen1(blocks) = &vu_data else {
+            unreachable!()
+        };
+        assert!(blocks.vu_detailed_speed.is_empty());
+    }
+
+    #[test]
+    fn test_minimize_position() {
+        // 50°50.5' N, 4°21.3' E
+        let position = GeoCoordinatesGen2::from_raw(50505, 4213);
+
+        let mut coarse = position.clone();
+        minimize_position(
+            &mut coarse,
+            PositionRetention::Coarsen { step_degrees: 0.5 },
+        );
+        assert_eq!(coarse.latitude, Some(51.0));
+        assert_eq!(coarse.longitude, Some(4.5));
+        assert_eq!((coarse.raw_latitude, coarse.raw_longitude), (51000, 4300));
+        assert_eq!(coarse.precision_degrees, 0.25);
+
+        let mut removed = position.clone();
+        minimize_position(&mut removed, PositionRetention::Remove);
+        assert_eq!((removed.latitude, removed.longitude), (None, None));
+        assert_eq!(removed.raw_latitude, GeoCoordinatesGen2::UNKNOWN_POSITION);
+
+        let mut unknown = removed.clone();
+        minimize_position(
+            &mut unknown,
+            PositionRetention::Coarsen { step_degrees: 0.5 },
+        );
+        assert_eq!(unknown, removed);
+    }
+}
@@ -0,0 +1,86 @@
+//! Unit newtypes for the numbers of the data dictionary. They serialize as plain numbers, so the
+//! JSON output is unchanged, but keep kilometres, minutes and speeds from being mixed up.
+use chrono::{Duration, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A distance or odometer reading in km.
+pub struct Kilometers(pub u32);
+impl Kilometers {
+    pub fn meters(self) -> u64 {
+        self.0 as u64 * 1000
+    }
+
+    pub fn miles(self) -> f64 {
+        self.0 as f64 / 1.609344
+    }
+}
+impl From<Kilometers> for u32 {
+    fn from(km: Kilometers) -> Self {
+        km.0
+    }
+}
+impl fmt::Display for Kilometers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} km", self.0)
+    }
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A duration in minutes, or a time of day as minutes since 00:00.
+pub struct Minutes(pub u16);
+impl Minutes {
+    pub fn duration(self) -> Duration {
+        Duration::minutes(self.0 as i64)
+    }
+
+    /// The time of day, `None` past 23:59.
+    pub fn time_of_day(self) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(self.0 as u32 / 60, self.0 as u32 % 60, 0)
+    }
+}
+impl From<Minutes> for u16 {
+    fn from(minutes: Minutes) -> Self {
+        minutes.0
+    }
+}
+impl fmt::Display for Minutes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} min", self.0)
+    }
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A speed in km/h.
+pub struct Kmh(pub u8);
+impl Kmh {
+    pub fn meters_per_second(self) -> f64 {
+        self.0 as f64 / 3.6
+    }
+
+    pub fn mph(self) -> f64 {
+        self.0 as f64 / 1.609344
+    }
+}
+impl From<Kmh> for u8 {
+    fn from(speed: Kmh) -> Self {
+        speed.0
+    }
+}
+impl fmt::Display for Kmh {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} km/h", self.0)
+    }
+}
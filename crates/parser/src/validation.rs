@@ -0,0 +1,195 @@
+use crate::card_parser::{self, EfAppendix, EfParseStatus, EfTag};
+use crate::detector::{self, TachoFileType};
+use crate::vu_parser::VuParser;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum IssueSeverity {
+    /// The file cannot be parsed, or is missing data a valid download must contain.
+    Error,
+    /// The file can be parsed, but some of its data will be skipped.
+    Warning,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub struct ValidationIssue {
+    pub severity: IssueSeverity,
+    /// Offset from the start of the file of the block or EF the issue is about.
+    pub offset: Option<usize>,
+    pub message: String,
+}
+impl ValidationIssue {
+    pub fn error(offset: Option<usize>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            severity: IssueSeverity::Error,
+            offset,
+            message: message.into(),
+        }
+    }
+    pub fn warning(offset: Option<usize>, message: impl Into<String>) -> Self {
+        ValidationIssue {
+            severity: IssueSeverity::Warning,
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// Result of [`validate_bytes`].
+pub struct ValidationOutcome {
+    /// `None` when the file type could not be detected.
+    pub file_type: Option<TachoFileType>,
+    /// Whether the file is free of [`IssueSeverity::Error`] issues.
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// Checks the structure of a card or VU file without building its data model.
+/// Every EF or block is parsed on its own and dropped right away, so memory use is bounded by the
+/// largest EF or block rather than by the whole file. Signatures are checked for presence only.
+pub fn validate_bytes(bytes: &[u8]) -> ValidationOutcome {
+    let file_type = match detector::detect_from_bytes(bytes) {
+        Ok(file_type) => file_type,
+        Err(e) => {
+            return ValidationOutcome {
+                file_type: None,
+                valid: false,
+                issues: vec![ValidationIssue::error(Some(0), format!("{:#}", e))],
+            }
+        }
+    };
+    let issues = match file_type {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => VuParser::validate_blocks(bytes),
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => validate_card(bytes),
+    };
+    ValidationOutcome {
+        file_type: Some(file_type),
+        valid: issues
+            .iter()
+            .all(|issue| issue.severity != IssueSeverity::Error),
+        issues,
+    }
+}
+
+fn validate_card(bytes: &[u8]) -> Vec<ValidationIssue> {
+    let coverage = match card_parser::ef_coverage(bytes) {
+        Ok(coverage) => coverage,
+        Err(e) => return vec![ValidationIssue::error(None, format!("{:#}", e))],
+    };
+    let mut issues = Vec::new();
+    for ef in &coverage {
+        let offset = Some(ef.offset);
+        match &ef.status {
+            EfParseStatus::Parsed => {}
+            EfParseStatus::PartiallyParsed { unused_bytes } => {
+                issues.push(ValidationIssue::warning(
+                    offset,
+                    format!(
+                        "EF {:#06x} (file_id {}) has {} unused bytes",
+                        ef.sfid, ef.file_id, unused_bytes
+                    ),
+                ))
+            }
+            EfParseStatus::Failed { error } => issues.push(ValidationIssue::error(
+                offset,
+                format!("EF {:#06x} (file_id {}): {}", ef.sfid, ef.file_id, error),
+            )),
+            EfParseStatus::PreservedRaw => issues.push(ValidationIssue::warning(
+                offset,
+                format!(
+                    "Unknown EF {:#06x} (file_id {}) is kept as raw bytes",
+                    ef.sfid, ef.file_id
+                ),
+            )),
+            EfParseStatus::Skipped => issues.push(ValidationIssue::error(
+                offset,
+                format!(
+                    "EF {:#06x} (file_id {}) is not read by the parser",
+                    ef.sfid, ef.file_id
+                ),
+            )),
+        }
+    }
+
+    let present: HashSet<(u16, u8)> = coverage.iter().map(|ef| (ef.sfid, ef.file_id)).collect();
+    for ef in &coverage {
+        let Some(appendix) = EfAppendix::from_byte(ef.file_id) else {
+            continue;
+        };
+        if !appendix.is_signature()
+            && EfTag::from_fid(ef.sfid).is_signed()
+            && !present.contains(&(ef.sfid, appendix.signature().byte()))
+        {
+            issues.push(ValidationIssue::error(
+                Some(ef.offset),
+                format!(
+                    "EF {:#06x} (file_id {}) has no signature",
+                    ef.sfid, ef.file_id
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_card_reports_missing_signature() {
+        let mut input = Vec::new();
+        // CardIccIdentification
+        input.extend_from_slice(&[0x00, 0x02, 0x00, 0x00, 0x19]);
+        let mut card_icc_identification = [0u8; 25];
+        // manufacturer code of the serial number and the card personaliser
+        card_icc_identification[8] = 0x10;
+        card_icc_identification[17] = 0x10;
+        input.extend_from_slice(&card_icc_identification);
+        // CardChipIdentification
+        input.extend_from_slice(&[0x00, 0x05, 0x00, 0x00, 0x08]);
+        input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        // ApplicationIdentification of a driver card and its signature
+        input.extend_from_slice(&[0x05, 0x01, 0x00, 0x00, 0x0A]);
+        input.extend_from_slice(&[0x01, 0x00, 0x00, 6, 12, 0x2A, 0x30, 0x00, 0x54, 112]);
+        input.extend_from_slice(&[0x05, 0x01, 0x01, 0x00, 0x80]);
+        input.extend_from_slice(&[0u8; 128]);
+        // CardDownload without its signature
+        input.extend_from_slice(&[0x05, 0x0E, 0x00, 0x00, 0x04]);
+        input.extend_from_slice(&[0x5F, 0x5E, 0x10, 0x00]);
+
+        let outcome = validate_bytes(&input);
+        assert_eq!(outcome.file_type, Some(TachoFileType::DriverCardGen1));
+        assert!(!outcome.valid);
+        assert_eq!(outcome.issues.len(), 1, "{:?}", outcome.issues);
+        assert_eq!(outcome.issues[0].offset, Some(191));
+        assert!(outcome.issues[0].message.contains("no signature"));
+    }
+
+    #[test]
+    fn test_validate_vu_continues_after_a_bad_block() {
+        let fixture = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        assert!(validate_bytes(fixture).valid);
+
+        let mut input = fixture.to_vec();
+        // a truncated activities block
+        input.extend_from_slice(&[0x76, 0x02, 0x5F]);
+        let outcome = validate_bytes(&input);
+        assert!(!outcome.valid);
+        assert_eq!(outcome.issues.len(), 1, "{:?}", outcome.issues);
+        assert_eq!(outcome.issues[0].offset, Some(fixture.len()));
+    }
+}
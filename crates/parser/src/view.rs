@@ -0,0 +1,600 @@
+use crate::analysis::normalize_event_times;
+use crate::capabilities::Generation;
+use crate::dt::external::NationNumeric;
+use crate::dt::{
+    gen1, gen2, gen2v2, Address, CardActivityChangeInfo, CardNumber, EventFaultRecordPurpose, Name,
+    TyreSize, VehicleIdentificationNumber, VehicleRegistrationIdentification,
+};
+use crate::sink::{label, EventKind};
+use crate::units::{Kilometers, Kmh};
+use crate::vu_parser::VuData;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// A full card number recorded by a VU, regardless of generation.
+pub struct CardNumberView<'a> {
+    pub card_issuing_member_state: &'a NationNumeric,
+    pub card_number: &'a CardNumber,
+    /// Only recorded by Gen2 VUs, and only for some records.
+    pub card_generation: Option<&'a gen2::GenerationGen2>,
+}
+impl<'a> From<&'a gen1::FullCardNumber> for CardNumberView<'a> {
+    fn from(full_card_number: &'a gen1::FullCardNumber) -> Self {
+        CardNumberView {
+            card_issuing_member_state: &full_card_number.card_issuing_member_state,
+            card_number: &full_card_number.card_number,
+            card_generation: None,
+        }
+    }
+}
+impl<'a> From<&'a gen2::FullCardNumberGen2> for CardNumberView<'a> {
+    fn from(full_card_number: &'a gen2::FullCardNumberGen2) -> Self {
+        CardNumberView {
+            card_issuing_member_state: &full_card_number.card_issuing_member_state,
+            card_number: &full_card_number.card_number,
+            card_generation: None,
+        }
+    }
+}
+impl<'a> From<&'a gen2::FullCardNumberAndGenerationGen2> for CardNumberView<'a> {
+    fn from(card: &'a gen2::FullCardNumberAndGenerationGen2) -> Self {
+        CardNumberView {
+            card_generation: Some(&card.generation),
+            ..CardNumberView::from(&card.full_card_number)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// An event or fault recorded by a VU, regardless of generation, see [`VuData::events`].
+pub struct VuEventView<'a> {
+    pub kind: EventKind,
+    pub generation: Generation,
+    /// Serialized event or fault type, e.g. `PowerSupplyInterruption`.
+    pub event_type: String,
+    pub record_purpose: &'a EventFaultRecordPurpose,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub begin_time: DateTime<Utc>,
+    /// Always set for faults.
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub end_time: Option<DateTime<Utc>>,
+    pub card_driver_slot_begin: Option<CardNumberView<'a>>,
+    pub card_codriver_slot_begin: Option<CardNumberView<'a>>,
+    pub card_driver_slot_end: Option<CardNumberView<'a>>,
+    pub card_codriver_slot_end: Option<CardNumberView<'a>>,
+    /// Only recorded for events.
+    pub similar_events_number: Option<u8>,
+    /// Only recorded by Gen2 VUs.
+    pub manufacturer_specific_data: Option<&'a gen2::ManufacturerSpecificEventFaultDataGen2>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// A calibration recorded by a VU, regardless of generation, see [`VuData::calibrations`].
+pub struct CalibrationView<'a> {
+    pub generation: Generation,
+    /// Serialized calibration purpose, e.g. `PeriodicInspection`.
+    pub purpose: String,
+    pub workshop_name: &'a Name,
+    pub workshop_address: &'a Address,
+    pub workshop_card_number: CardNumberView<'a>,
+    pub workshop_card_expiry_date: Option<DateTime<Utc>>,
+    pub vehicle_identification_number: Option<&'a VehicleIdentificationNumber>,
+    pub vehicle_registration_identification: Option<&'a VehicleRegistrationIdentification>,
+    pub w_vehicle_characteristic_constant: u16,
+    pub k_constant_of_recording_equipment: u16,
+    pub l_tyre_circumference: u16,
+    pub tyre_size: &'a TyreSize,
+    /// In km/h.
+    pub authorised_speed: Kmh,
+    pub old_odometer_value: Kilometers,
+    pub new_odometer_value: Kilometers,
+    pub old_time_value: Option<DateTime<Utc>>,
+    pub new_time_value: Option<DateTime<Utc>>,
+    pub next_calibration_date: Option<DateTime<Utc>>,
+    /// Only recorded by Gen2 VUs.
+    pub seal_data_vu: Option<&'a gen2::SealDataVuGen2>,
+    /// Only recorded by Gen2v2 VUs.
+    pub by_default_load_type: Option<&'a gen2v2::LoadType>,
+    /// Only recorded by Gen2v2 VUs.
+    pub calibration_country: Option<&'a NationNumeric>,
+    /// Only recorded by Gen2v2 VUs.
+    pub calibration_country_timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(untagged)]
+/// A place record of a Gen2 VU activities block, in the format of its generation.
+pub enum VuPlaceView<'a> {
+    Gen2(&'a gen2::VuPlaceDailyWorkPeriodRecordGen2),
+    Gen2V2(&'a gen2v2::VuPlaceDailyWorkPeriodRecordGen2V2),
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(untagged)]
+/// A GNSS accumulated driving record of a Gen2 VU activities block, in the format of its
+/// generation.
+pub enum VuGnssAdView<'a> {
+    Gen2(&'a gen2::VuGNSSADRecordGen2),
+    Gen2V2(&'a gen2v2::VuGNSSADRecordGen2V2),
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// The records of a Gen2 VU activities block together with the day they were downloaded for,
+/// see [`VuData::activity_days`].
+pub struct VuActivityDayView<'a> {
+    pub generation: Generation,
+    /// The block's DateOfDayDownloaded.
+    pub date: DateTime<Utc>,
+    pub odometer_value_midnight: Option<Kilometers>,
+    pub card_iw_records: &'a [gen2::VuCardIwRecordGen2],
+    /// Slot status at 00:00 followed by the activity changes of the day.
+    pub activity_changes: &'a [CardActivityChangeInfo],
+    pub places: Vec<VuPlaceView<'a>>,
+    pub gnss_ad_records: Vec<VuGnssAdView<'a>>,
+    pub specific_conditions: &'a [gen2::SpecificConditionRecordGen2],
+    /// Only recorded by Gen2v2 VUs.
+    pub border_crossings: &'a [gen2v2::VuBorderCrossingRecord],
+    /// Only recorded by Gen2v2 VUs.
+    pub load_unload_operations: &'a [gen2v2::VuLoadUnloadRecord],
+}
+
+impl VuData {
+    /// Events and faults of every events and faults block, the events of each block first.
+    /// Overspeeding and time adjustment records are not included.
+    pub fn events(&self) -> Vec<VuEventView<'_>> {
+        match self {
+            VuData::Gen1(blocks) => {
+                let mut views = Vec::new();
+                for block in &blocks.vu_events_and_faults {
+                    for record in &block.vu_event_data.vu_event_records {
+                        views.push(VuEventView {
+                            kind: EventKind::Event,
+                            generation: Generation::Gen1,
+                            event_type: label(&record.event_type),
+                            record_purpose: &record.event_record_purpose,
+                            begin_time: record.event_begin_time.0,
+                            end_time: record.event_end_time.as_ref().map(|time| time.0),
+                            card_driver_slot_begin: record
+                                .card_number_driver_slot_begin
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_codriver_slot_begin: record
+                                .card_number_codriver_slot_begin
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_driver_slot_end: record
+                                .card_number_driver_slot_end
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_codriver_slot_end: record
+                                .card_number_codriver_slot_end
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            similar_events_number: Some(record.similar_events_number.0),
+                            manufacturer_specific_data: None,
+                        });
+                    }
+                    for record in &block.vu_fault_data.vu_fault_records {
+                        views.push(VuEventView {
+                            kind: EventKind::Fault,
+                            generation: Generation::Gen1,
+                            event_type: label(&record.fault_type),
+                            record_purpose: &record.fault_record_purpose,
+                            begin_time: record.fault_begin_time.0,
+                            end_time: Some(record.fault_end_time.0),
+                            card_driver_slot_begin: record
+                                .card_number_driver_slot_begin
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_codriver_slot_begin: record
+                                .card_number_codriver_slot_begin
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_driver_slot_end: record
+                                .card_number_driver_slot_end
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            card_codriver_slot_end: record
+                                .card_number_codriver_slot_end
+                                .as_ref()
+                                .map(CardNumberView::from),
+                            similar_events_number: None,
+                            manufacturer_specific_data: None,
+                        });
+                    }
+                }
+                views
+            }
+            VuData::Gen2(blocks) => gen2_events(&blocks.vu_events_and_faults, Generation::Gen2),
+            VuData::Gen2V2(blocks) => gen2_events(&blocks.vu_events_and_faults, Generation::Gen2V2),
+        }
+    }
+
+    /// Events and faults of [`VuData::events`] in chronological order, with their times made safe
+    /// for duration computations by [`normalize_event_times`].
+    pub fn events_sorted(&self) -> Vec<VuEventView<'_>> {
+        let mut events = self.events();
+        for event in &mut events {
+            (event.begin_time, event.end_time) =
+                normalize_event_times(event.begin_time, event.end_time);
+        }
+        events.sort_by_key(|event| event.begin_time);
+        events
+    }
+
+    /// Calibrations of every company locks block, oldest block first.
+    pub fn calibrations(&self) -> Vec<CalibrationView<'_>> {
+        match self {
+            VuData::Gen1(blocks) => blocks
+                .vu_company_locks
+                .iter()
+                .flat_map(|block| &block.vu_calibration_data.vu_calibration_records)
+                .map(|record| CalibrationView {
+                    generation: Generation::Gen1,
+                    purpose: label(&record.calibration_purpose),
+                    workshop_name: &record.workshop_name,
+                    workshop_address: &record.workshop_address,
+                    workshop_card_number: CardNumberView::from(&record.workshop_card_number),
+                    workshop_card_expiry_date: record
+                        .workshop_card_expiry_date
+                        .as_ref()
+                        .map(|date| date.0),
+                    vehicle_identification_number: record.vehicle_identification_number.as_ref(),
+                    vehicle_registration_identification: record
+                        .vehicle_registration_identification
+                        .as_ref(),
+                    w_vehicle_characteristic_constant: record.w_vehicle_characteristic_constant.0,
+                    k_constant_of_recording_equipment: record.k_constant_of_recording_equipment.0,
+                    l_tyre_circumference: record.l_tyre_circumference.0,
+                    tyre_size: &record.tyre_size,
+                    authorised_speed: record.authorised_speed.0,
+                    old_odometer_value: record.old_odometer_value.0,
+                    new_odometer_value: record.new_odometer_value.0,
+                    old_time_value: record.old_time_value.as_ref().map(|time| time.0),
+                    new_time_value: record.new_time_value.as_ref().map(|time| time.0),
+                    next_calibration_date: record.next_calibration_date.as_ref().map(|date| date.0),
+                    seal_data_vu: None,
+                    by_default_load_type: None,
+                    calibration_country: None,
+                    calibration_country_timestamp: None,
+                })
+                .collect(),
+            VuData::Gen2(blocks) => blocks
+                .vu_company_locks
+                .iter()
+                .flat_map(|block| &block.vu_calibration_record_array)
+                .map(|record| CalibrationView {
+                    generation: Generation::Gen2,
+                    purpose: label(&record.calibration_purpose),
+                    workshop_name: &record.workshop_name,
+                    workshop_address: &record.workshop_address,
+                    workshop_card_number: CardNumberView::from(&record.workshop_card_number),
+                    workshop_card_expiry_date: record
+                        .workshop_card_expiry_date
+                        .as_ref()
+                        .map(|date| date.0),
+                    vehicle_identification_number: record.vehicle_identification_number.as_ref(),
+                    vehicle_registration_identification: record
+                        .vehicle_registration_identification
+                        .as_ref(),
+                    w_vehicle_characteristic_constant: record.w_vehicle_characteristic_constant.0,
+                    k_constant_of_recording_equipment: record.k_constant_of_recording_equipment.0,
+                    l_tyre_circumference: record.l_tyre_circumference.0,
+                    tyre_size: &record.tyre_size,
+                    authorised_speed: record.authorised_speed.0,
+                    old_odometer_value: record.old_odometer_value.0,
+                    new_odometer_value: record.new_odometer_value.0,
+                    old_time_value: record.old_time_value.as_ref().map(|time| time.0),
+                    new_time_value: record.new_time_value.as_ref().map(|time| time.0),
+                    next_calibration_date: record.next_calibration_date.as_ref().map(|date| date.0),
+                    seal_data_vu: Some(&record.seal_data_vu),
+                    by_default_load_type: None,
+                    calibration_country: None,
+                    calibration_country_timestamp: None,
+                })
+                .collect(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_company_locks
+                .iter()
+                .flat_map(|block| &block.vu_calibration_record_array)
+                .map(|record| CalibrationView {
+                    generation: Generation::Gen2V2,
+                    purpose: label(&record.calibration_purpose),
+                    workshop_name: &record.workshop_name,
+                    workshop_address: &record.workshop_address,
+                    workshop_card_number: CardNumberView::from(&record.workshop_card_number),
+                    workshop_card_expiry_date: record
+                        .workshop_card_expiry_date
+                        .as_ref()
+                        .map(|date| date.0),
+                    vehicle_identification_number: record.vehicle_identification_number.as_ref(),
+                    vehicle_registration_identification: record
+                        .vehicle_registration_identification
+                        .as_ref(),
+                    w_vehicle_characteristic_constant: record.w_vehicle_characteristic_constant.0,
+                    k_constant_of_recording_equipment: record.k_constant_of_recording_equipment.0,
+                    l_tyre_circumference: record.l_tyre_circumference.0,
+                    tyre_size: &record.tyre_size,
+                    authorised_speed: record.authorised_speed.0,
+                    old_odometer_value: record.old_odometer_value.0,
+                    new_odometer_value: record.new_odometer_value.0,
+                    old_time_value: record.old_time_value.as_ref().map(|time| time.0),
+                    new_time_value: record.new_time_value.as_ref().map(|time| time.0),
+                    next_calibration_date: record.next_calibration_date.as_ref().map(|date| date.0),
+                    seal_data_vu: Some(&record.seal_data_vu),
+                    by_default_load_type: Some(&record.by_default_load_type),
+                    calibration_country: Some(&record.calibration_country),
+                    calibration_country_timestamp: Some(record.calibration_country_timestamp.0),
+                })
+                .collect(),
+        }
+    }
+
+    /// One entry per Gen2 activities block, in file order, with every record of the block tied to
+    /// the block's DateOfDayDownloaded. Blocks without a date are left out. Gen1 activities blocks
+    /// already carry their day in [`gen1::VuActivitiesBlock::time_real`], so this is empty for
+    /// Gen1 files.
+    pub fn activity_days(&self) -> Vec<VuActivityDayView<'_>> {
+        match self {
+            VuData::Gen1(_) => Vec::new(),
+            VuData::Gen2(blocks) => gen2_activity_days(&blocks.vu_activities),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_activities
+                .iter()
+                .filter_map(|block| {
+                    Some(VuActivityDayView {
+                        generation: Generation::Gen2V2,
+                        date: block.date_of_day_downloaded_record_array.first()?.0 .0,
+                        odometer_value_midnight: block
+                            .odometer_value_midnight_record_array
+                            .first()
+                            .map(|odometer| odometer.0),
+                        card_iw_records: &block.vu_card_iw_record_array,
+                        activity_changes: &block.vu_activity_daily_record_array,
+                        places: block
+                            .vu_place_daily_work_period_record_array
+                            .iter()
+                            .map(VuPlaceView::Gen2V2)
+                            .collect(),
+                        gnss_ad_records: block
+                            .vu_gnss_ad_record_array
+                            .iter()
+                            .map(VuGnssAdView::Gen2V2)
+                            .collect(),
+                        specific_conditions: &block.vu_specific_condition_record_array,
+                        border_crossings: &block.vu_border_crossing_record_array,
+                        load_unload_operations: &block.vu_load_unload_record_array,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+fn gen2_activity_days(blocks: &[gen2::VuActivitiesBlockGen2]) -> Vec<VuActivityDayView<'_>> {
+    blocks
+        .iter()
+        .filter_map(|block| {
+            Some(VuActivityDayView {
+                generation: Generation::Gen2,
+                date: block.date_of_day_downloaded_record_array.first()?.0 .0,
+                odometer_value_midnight: block
+                    .odometer_value_midnight_record_array
+                    .first()
+                    .map(|odometer| odometer.0),
+                card_iw_records: &block.vu_card_iw_record_array,
+                activity_changes: &block.vu_activity_daily_record_array,
+                places: block
+                    .vu_place_daily_work_period_record_array
+                    .iter()
+                    .map(VuPlaceView::Gen2)
+                    .collect(),
+                gnss_ad_records: block
+                    .vu_gnss_ad_record_array
+                    .iter()
+                    .map(VuGnssAdView::Gen2)
+                    .collect(),
+                specific_conditions: &block.vu_specific_condition_record_array,
+                border_crossings: &[],
+                load_unload_operations: &[],
+            })
+        })
+        .collect()
+}
+
+/// Gen2v2 VUs store events and faults in the Gen2 block format.
+fn gen2_events(
+    blocks: &[gen2::VuEventsAndFaultsBlockGen2],
+    generation: Generation,
+) -> Vec<VuEventView<'_>> {
+    let mut views = Vec::new();
+    for block in blocks {
+        for record in &block.vu_event_record_array {
+            views.push(VuEventView {
+                kind: EventKind::Event,
+                generation,
+                event_type: label(&record.event_type),
+                record_purpose: &record.event_record_purpose,
+                begin_time: record.event_begin_time.0,
+                end_time: record.event_end_time.as_ref().map(|time| time.0),
+                card_driver_slot_begin: record
+                    .card_number_and_gen_driver_slot_begin
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_codriver_slot_begin: record
+                    .card_number_and_gen_codriver_slot_begin
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_driver_slot_end: record
+                    .card_number_and_gen_driver_slot_end
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_codriver_slot_end: record
+                    .card_number_and_gen_codriver_slot_end
+                    .as_ref()
+                    .map(CardNumberView::from),
+                similar_events_number: Some(record.similar_events_number.0),
+                manufacturer_specific_data: record.manufacturer_specific_event_fault_data.as_ref(),
+            });
+        }
+        for record in &block.vu_fault_record_array {
+            views.push(VuEventView {
+                kind: EventKind::Fault,
+                generation,
+                event_type: label(&record.fault_type),
+                record_purpose: &record.fault_record_purpose,
+                begin_time: record.fault_begin_time.0,
+                end_time: Some(record.fault_end_time.0),
+                card_driver_slot_begin: record
+                    .card_number_and_gen_driver_slot_begin
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_codriver_slot_begin: record
+                    .card_number_and_gen_codriver_slot_begin
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_driver_slot_end: record
+                    .card_number_and_gen_driver_slot_end
+                    .as_ref()
+                    .map(CardNumberView::from),
+                card_codriver_slot_end: record
+                    .card_number_and_gen_codriver_slot_end
+                    .as_ref()
+                    .map(CardNumberView::from),
+                similar_events_number: None,
+                manufacturer_specific_data: record.manufacturer_specific_event_fault_data.as_ref(),
+            });
+        }
+    }
+    views
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vu_parser::VuParser;
+
+    #[test]
+    fn test_events_view() {
+        let mut input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd").to_vec();
+        input.extend_from_slice(&[0x76, 0x03]);
+        // no faults, a single power supply interruption with a driver card in the driver slot
+        input.extend_from_slice(&[0x00, 0x01, 0x08, 0x00]);
+        input.extend_from_slice(&0x65E1_8000u32.to_be_bytes());
+        input.extend_from_slice(&0x65E1_8E10u32.to_be_bytes());
+        let mut driver_card = vec![0x01, 0x0D];
+        driver_card.extend_from_slice(b"DF00000123456700");
+        for _ in 0..2 {
+            input.extend_from_slice(&driver_card);
+            input.extend_from_slice(&[0xFF; 18]);
+        }
+        input.push(3);
+        // no overspeeding control data, overspeeding events or time adjustments
+        input.extend_from_slice(&[0x00; 11]);
+        input.extend_from_slice(&[0x00; 128]);
+
+        let vu_data = VuParser::new_from_bytes(&input).unwrap().parse().unwrap();
+        let events = vu_data.events();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(
+            (&event.kind, event.generation, event.event_type.as_str()),
+            (
+                &EventKind::Event,
+                Generation::Gen1,
+                "PowerSupplyInterruption"
+            )
+        );
+        assert_eq!(
+            event.end_time.unwrap() - event.begin_time,
+            chrono::Duration::hours(1)
+        );
+        let card = event.card_driver_slot_begin.unwrap();
+        assert_eq!(card.card_issuing_member_state.0, "Germany");
+        assert!(card.card_generation.is_none());
+        assert!(event.card_codriver_slot_begin.is_none());
+        assert_eq!(event.similar_events_number, Some(3));
+        assert!(event.manufacturer_specific_data.is_none());
+        assert!(vu_data.calibrations().is_empty());
+        assert!(vu_data.activity_days().is_empty());
+    }
+
+    #[test]
+    fn test_events_sorted() {
+        let mut input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd").to_vec();
+        input.extend_from_slice(&[0x76, 0x03]);
+        // no faults, two power supply interruptions without cards, the first one recorded ending
+        // before its begin
+        input.extend_from_slice(&[0x00, 0x02]);
+        for (begin, end) in [(0x65E1_8E10u32, 0x65E1_8000u32), (0x65E1_71F0, 0x65E1_8000)] {
+            input.extend_from_slice(&[0x08, 0x00]);
+            input.extend_from_slice(&begin.to_be_bytes());
+            input.extend_from_slice(&end.to_be_bytes());
+            input.extend_from_slice(&[0xFF; 18 * 4]);
+            input.push(1);
+        }
+        // no overspeeding control data, overspeeding events or time adjustments
+        input.extend_from_slice(&[0x00; 11]);
+        input.extend_from_slice(&[0x00; 128]);
+
+        let vu_data = VuParser::new_from_bytes(&input).unwrap().parse().unwrap();
+        let events = vu_data.events_sorted();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].begin_time < events[1].begin_time);
+        assert_eq!(
+            events[0].end_time.unwrap() - events[0].begin_time,
+            chrono::Duration::hours(1)
+        );
+        assert_eq!(events[1].end_time, Some(events[1].begin_time));
+    }
+
+    #[test]
+    fn test_gen2_activity_days() {
+        let record_array = |record_type: u8, record_size: u16, records: &[&[u8]]| {
+            let mut bytes = vec![record_type];
+            bytes.extend_from_slice(&record_size.to_be_bytes());
+            bytes.extend_from_slice(&(records.len() as u16).to_be_bytes());
+            for record in records {
+                bytes.extend_from_slice(record);
+            }
+            bytes
+        };
+        let block = |date: u32, activity_changes: &[&[u8]]| {
+            let mut input = record_array(0x06, 4, &[&date.to_be_bytes()]);
+            input.extend(record_array(0x0F, 3, &[&[0x00, 0x10, 0x00]]));
+            input.extend(record_array(0x0D, 1, &[]));
+            input.extend(record_array(0x01, 2, activity_changes));
+            input.extend(record_array(0x1C, 1, &[]));
+            input.extend(record_array(0x16, 1, &[]));
+            input.extend(record_array(0x09, 1, &[]));
+            input.extend(record_array(0x08, 1, &[]));
+            gen2::VuActivitiesBlockGen2::parse(&mut std::io::Cursor::new(&input[..])).unwrap()
+        };
+        let blocks = vec![
+            block(0x65E1_1A80, &[&[0x00, 0x00], &[0x18, 0x3C]]),
+            block(0x65E2_6C00, &[&[0x00, 0x00]]),
+        ];
+
+        let days = gen2_activity_days(&blocks);
+        assert_eq!(days.len(), 2);
+        assert_eq!(
+            days[0].date,
+            blocks[0].date_of_day_downloaded_record_array[0].0 .0
+        );
+        assert_eq!(days[0].activity_changes.len(), 2);
+        assert_eq!(days[1].activity_changes.len(), 1);
+        assert_eq!(days[0].odometer_value_midnight, Some(Kilometers(0x1000)));
+        assert!(days.iter().all(|day| day.places.is_empty()
+            && day.border_crossings.is_empty()
+            && day.generation == Generation::Gen2));
+    }
+}
@@ -1,12 +1,26 @@
-use crate::dt::{gen1, gen2, gen2v2};
+use crate::binary::{self, BinaryFormat};
+use crate::bytes::TracedRead;
+use crate::capabilities::Generation;
+use crate::context::{with_parse_context, ParseSettings};
+use crate::detector::TachoFileType;
+use crate::diagnostics::{Diagnostics, TruncatedBlock, UnknownBlock};
+use crate::dt::{
+    gen1, gen2, gen2v2, ActivityMinutesPolicy, IA5String, StringPolicy,
+    VehicleIdentificationNumber, VuDownloadablePeriod,
+};
+use crate::json::{self, JsonOptions, SourceDigest};
+use crate::sink::{self, TachoSink};
+use crate::source::{self, TachoRead};
+use crate::trace::{self, TraceEntry};
+use crate::validation::{IssueSeverity, ValidationIssue};
 use anyhow::{Context, Result};
-use byteorder::ReadBytesExt;
 use serde::{Deserialize, Serialize};
-use std::io::{BufRead, Cursor};
+use std::io::{BufRead, Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuGen1Blocks {
@@ -15,9 +29,12 @@ pub struct VuGen1Blocks {
     pub vu_events_and_faults: Vec<gen1::VuEventsAndFaultsBlock>,
     pub vu_detailed_speed: Vec<gen1::VuDetailedSpeedBlock>,
     pub vu_company_locks: Vec<gen1::VuCompanyLocksBlock>,
+    /// Where each block was read from, only kept with [`VuParser::keep_segments`].
+    pub segments: Option<Vec<VuSegment>>,
+    pub diagnostics: Diagnostics,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuGen2Blocks {
@@ -26,9 +43,12 @@ pub struct VuGen2Blocks {
     pub vu_events_and_faults: Vec<gen2::VuEventsAndFaultsBlockGen2>,
     pub vu_detailed_speed: Vec<gen2::VuDetailedSpeedBlockGen2>,
     pub vu_company_locks: Vec<gen2::VuCompanyLocksGen2>,
+    /// Where each block was read from, only kept with [`VuParser::keep_segments`].
+    pub segments: Option<Vec<VuSegment>>,
+    pub diagnostics: Diagnostics,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "ts", derive(TS))]
 pub struct VuGen2V2Blocks {
@@ -36,9 +56,41 @@ pub struct VuGen2V2Blocks {
     pub vu_activities: Vec<gen2v2::VuActivitiesBlockGen2V2>,
     pub vu_events_and_faults: Vec<gen2::VuEventsAndFaultsBlockGen2>,
     pub vu_company_locks: Vec<gen2v2::VuCompanyLocksGen2V2>,
+    /// Where each block was read from, only kept with [`VuParser::keep_segments`].
+    pub segments: Option<Vec<VuSegment>>,
+    pub diagnostics: Diagnostics,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// The bytes of a recognized block in the original download, SID and TREP included.
+pub struct VuSegment {
+    pub trep: u8,
+    /// Index of the parsed block among the blocks of its TREP, e.g. `vu_activities[index]`.
+    pub index: usize,
+    pub offset: usize,
+    pub length: usize,
+}
+impl VuSegment {
+    fn new(trep: u8, range: Range<usize>, previous: &[VuSegment]) -> Self {
+        VuSegment {
+            trep,
+            index: previous
+                .iter()
+                .filter(|segment| segment.trep == trep)
+                .count(),
+            offset: range.start,
+            length: range.len(),
+        }
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "generation")]
 #[cfg_attr(feature = "ts", derive(TS))]
@@ -47,35 +99,803 @@ pub enum VuData {
     Gen2(VuGen2Blocks),
     Gen2V2(VuGen2V2Blocks),
 }
+impl VuData {
+    pub fn file_type(&self) -> TachoFileType {
+        match self {
+            VuData::Gen1(_) => TachoFileType::VehicleUnitGen1,
+            VuData::Gen2(_) => TachoFileType::VehicleUnitGen2,
+            VuData::Gen2V2(_) => TachoFileType::VehicleUnitGen2V2,
+        }
+    }
+
+    pub fn diagnostics(&self) -> &Diagnostics {
+        match self {
+            VuData::Gen1(blocks) => &blocks.diagnostics,
+            VuData::Gen2(blocks) => &blocks.diagnostics,
+            VuData::Gen2V2(blocks) => &blocks.diagnostics,
+        }
+    }
+
+    fn diagnostics_mut(&mut self) -> &mut Diagnostics {
+        match self {
+            VuData::Gen1(blocks) => &mut blocks.diagnostics,
+            VuData::Gen2(blocks) => &mut blocks.diagnostics,
+            VuData::Gen2V2(blocks) => &mut blocks.diagnostics,
+        }
+    }
+
+    pub fn segments(&self) -> Option<&[VuSegment]> {
+        match self {
+            VuData::Gen1(blocks) => blocks.segments.as_deref(),
+            VuData::Gen2(blocks) => blocks.segments.as_deref(),
+            VuData::Gen2V2(blocks) => blocks.segments.as_deref(),
+        }
+    }
+
+    /// Builds a new download file from the blocks of `input`, the file this data was parsed
+    /// from, for which `keep` returns true, e.g. only the overview and the activities of some days.
+    /// The overview is always kept since a download must start with it, the other blocks stay in
+    /// file order. Each block carries its own signature, so the blocks kept remain verifiable.
+    /// Fails if the data was parsed without [`VuParser::keep_segments`].
+    pub fn export_segments(
+        &self,
+        input: &[u8],
+        keep: impl Fn(&VuSegment) -> bool,
+    ) -> Result<Vec<u8>> {
+        let segments = self
+            .segments()
+            .context("Segments were not kept, see VuParser::keep_segments")?;
+        let overview_trep = match self {
+            VuData::Gen1(_) => VuParser::GEN1_TREPS[0],
+            VuData::Gen2(_) => VuParser::GEN2_TREPS[0],
+            VuData::Gen2V2(_) => VuParser::GEN2V2_TREPS[0],
+        };
+        let mut output = Vec::new();
+        for segment in segments {
+            if segment.trep == overview_trep || keep(segment) {
+                let bytes = input
+                    .get(segment.range())
+                    .context("Input is shorter than the parsed download")?;
+                output.extend_from_slice(bytes);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Bytes of the file that are not part of any block the parser recognized, such as unknown
+    /// blocks or trailing data appended by the download tool.
+    pub fn bytes_unaccounted(&self) -> usize {
+        let diagnostics = self.diagnostics();
+        diagnostics.total_bytes - diagnostics.recognized_bytes
+    }
+
+    /// Combines the parts of a download that was split across several files, as some download
+    /// devices write one file per TREP. All parts must come from the same generation of VU, for
+    /// the same vehicle and downloadable period. The overview every part repeats is kept from the
+    /// first part, and a block found in several parts is kept once, the others stay in the order
+    /// of the parts. Segments are dropped since they point into different files; the offsets of
+    /// the unknown blocks in the diagnostics stay relative to their own part.
+    pub fn merge(parts: Vec<VuData>) -> Result<VuData> {
+        let mut parts = parts.into_iter();
+        let mut merged = parts.next().context("No parts to merge")?;
+        for (index, part) in parts.enumerate() {
+            let index = index + 1;
+            if std::mem::discriminant(&merged) != std::mem::discriminant(&part) {
+                anyhow::bail!(
+                    "Part {} was downloaded from a VU of another generation",
+                    index
+                );
+            }
+            if merged.vehicle_identification_number() != part.vehicle_identification_number() {
+                anyhow::bail!("Part {} was downloaded from another vehicle", index);
+            }
+            if merged.downloadable_period() != part.downloadable_period() {
+                anyhow::bail!("Part {} covers another downloadable period", index);
+            }
+            match (&mut merged, part) {
+                (VuData::Gen1(merged), VuData::Gen1(part)) => {
+                    merge_blocks(&mut merged.vu_activities, part.vu_activities);
+                    merge_blocks(&mut merged.vu_events_and_faults, part.vu_events_and_faults);
+                    merge_blocks(&mut merged.vu_detailed_speed, part.vu_detailed_speed);
+                    merge_blocks(&mut merged.vu_company_locks, part.vu_company_locks);
+                    merge_diagnostics(&mut merged.diagnostics, part.diagnostics);
+                }
+                (VuData::Gen2(merged), VuData::Gen2(part)) => {
+                    merge_blocks(&mut merged.vu_activities, part.vu_activities);
+                    merge_blocks(&mut merged.vu_events_and_faults, part.vu_events_and_faults);
+                    merge_blocks(&mut merged.vu_detailed_speed, part.vu_detailed_speed);
+                    merge_blocks(&mut merged.vu_company_locks, part.vu_company_locks);
+                    merge_diagnostics(&mut merged.diagnostics, part.diagnostics);
+                }
+                (VuData::Gen2V2(merged), VuData::Gen2V2(part)) => {
+                    merge_blocks(&mut merged.vu_activities, part.vu_activities);
+                    merge_blocks(&mut merged.vu_events_and_faults, part.vu_events_and_faults);
+                    merge_blocks(&mut merged.vu_company_locks, part.vu_company_locks);
+                    merge_diagnostics(&mut merged.diagnostics, part.diagnostics);
+                }
+                _ => unreachable!("generations were compared above"),
+            }
+        }
+        match &mut merged {
+            VuData::Gen1(blocks) => blocks.segments = None,
+            VuData::Gen2(blocks) => blocks.segments = None,
+            VuData::Gen2V2(blocks) => blocks.segments = None,
+        }
+        Ok(merged)
+    }
+
+    pub(crate) fn vehicle_identification_number(&self) -> Option<&VehicleIdentificationNumber> {
+        match self {
+            VuData::Gen1(blocks) => Some(&blocks.vu_overview.vehicle_identification_number),
+            VuData::Gen2(blocks) => blocks
+                .vu_overview
+                .vehicle_identification_number_record_array
+                .first(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_overview
+                .vehicle_identification_number_record_array
+                .first(),
+        }
+    }
+
+    pub(crate) fn vehicle_registration_number(&self) -> Option<&IA5String> {
+        match self {
+            VuData::Gen1(blocks) => Some(
+                &blocks
+                    .vu_overview
+                    .vehicle_registration_identification
+                    .vehicle_registration_number
+                    .vehicle_reg_number,
+            ),
+            VuData::Gen2(blocks) => blocks
+                .vu_overview
+                .vehicle_registration_number_record_array
+                .first()
+                .map(|vrn| &vrn.vehicle_reg_number),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_overview
+                .vehicle_registration_identification_record_array
+                .first()
+                .map(|vri| &vri.vehicle_registration_number.vehicle_reg_number),
+        }
+    }
+
+    fn downloadable_period(&self) -> Option<&VuDownloadablePeriod> {
+        match self {
+            VuData::Gen1(blocks) => Some(&blocks.vu_overview.vu_downloadable_period),
+            VuData::Gen2(blocks) => blocks
+                .vu_overview
+                .vu_downloadable_period_record_array
+                .first(),
+            VuData::Gen2V2(blocks) => blocks
+                .vu_overview
+                .vu_downloadable_period_record_array
+                .first(),
+        }
+    }
+}
+
+/// Appends the blocks of another part of a download, skipping those already present.
+fn merge_blocks<T: PartialEq>(merged: &mut Vec<T>, blocks: Vec<T>) {
+    for block in blocks {
+        if !merged.contains(&block) {
+            merged.push(block);
+        }
+    }
+}
+
+fn merge_diagnostics(merged: &mut Diagnostics, diagnostics: Diagnostics) {
+    merged.unknown_blocks.extend(diagnostics.unknown_blocks);
+    merged.total_bytes += diagnostics.total_bytes;
+    merged.recognized_bytes += diagnostics.recognized_bytes;
+    merged.response_headers |= diagnostics.response_headers;
+    merged.byte_stuffing |= diagnostics.byte_stuffing;
+    merged.signatures_skipped |= diagnostics.signatures_skipped;
+    merged.invalid_fields.extend(diagnostics.invalid_fields);
+    if merged.truncated_block.is_none() {
+        merged.truncated_block = diagnostics.truncated_block;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// Structural validation run by [`VuParser::parse`] before building the data model.
+pub enum ValidationLevel {
+    /// No validation beyond what parsing itself checks.
+    #[default]
+    Off,
+    /// Logs the issues found by [`crate::validation::validate_bytes`] and parses anyway.
+    Warn,
+    /// Fails the parse when validation finds any error.
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// Which recognized blocks end up in [`VuData`]. Filtered blocks are still parsed to find their
+/// end, but are dropped right away. The overview block is always kept.
+pub enum TrepFilter {
+    #[default]
+    All,
+    /// Keeps only the blocks with these TREPs.
+    Only(Vec<u8>),
+    /// Keeps every block but the ones with these TREPs.
+    Except(Vec<u8>),
+}
+impl TrepFilter {
+    pub fn keeps(&self, trep: u8) -> bool {
+        match self {
+            TrepFilter::All => true,
+            TrepFilter::Only(treps) => treps.contains(&trep),
+            TrepFilter::Except(treps) => !treps.contains(&trep),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Configures a [`VuParser`]. The defaults match [`VuParser::new_from_bytes`].
+pub struct VuParserBuilder {
+    generation: Option<Generation>,
+    trep_filter: TrepFilter,
+    max_input_bytes: Option<usize>,
+    max_blocks: Option<usize>,
+    validation: ValidationLevel,
+    string_policy: StringPolicy,
+    activity_minutes_policy: ActivityMinutesPolicy,
+    lenient: bool,
+    keep_segments: bool,
+    skip_signatures: bool,
+}
+impl VuParserBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the file as this generation instead of detecting it from the first block, e.g.
+    /// for files prefixed with a header by the download tool. Bytes before the first block of
+    /// the generation are skipped as an unknown block.
+    pub fn generation(mut self, generation: Generation) -> Self {
+        self.generation = Some(generation);
+        self
+    }
+
+    /// Keeps only the blocks with these TREPs, see [`TrepFilter`].
+    pub fn only_treps(mut self, treps: &[u8]) -> Self {
+        self.trep_filter = TrepFilter::Only(treps.to_vec());
+        self
+    }
+
+    /// Drops the blocks with these TREPs, see [`TrepFilter`].
+    pub fn skip_treps(mut self, treps: &[u8]) -> Self {
+        self.trep_filter = TrepFilter::Except(treps.to_vec());
+        self
+    }
+
+    /// Refuses inputs larger than `max_input_bytes`. Files are checked before being read.
+    pub fn max_input_bytes(mut self, max_input_bytes: usize) -> Self {
+        self.max_input_bytes = Some(max_input_bytes);
+        self
+    }
+
+    /// Fails the parse once more than `max_blocks` blocks, known or unknown, were read.
+    pub fn max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = Some(max_blocks);
+        self
+    }
+
+    pub fn validation(mut self, validation: ValidationLevel) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    pub fn string_policy(mut self, string_policy: StringPolicy) -> Self {
+        self.string_policy = string_policy;
+        self
+    }
+
+    /// Keeps, clamps or drops activity changes past the end of their day, see
+    /// [`ActivityMinutesPolicy`].
+    pub fn activity_minutes_policy(mut self, policy: ActivityMinutesPolicy) -> Self {
+        self.activity_minutes_policy = policy;
+        self
+    }
+
+    /// See [`VuParser::lenient`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// See [`VuParser::keep_segments`].
+    pub fn keep_segments(mut self, keep_segments: bool) -> Self {
+        self.keep_segments = keep_segments;
+        self
+    }
+
+    /// Seeks past signatures and certificates instead of copying them, leaving them empty in the
+    /// parsed data and setting [`Diagnostics::signatures_skipped`]. Saves a good part of the bytes
+    /// copied for pipelines that do not verify signatures.
+    pub fn skip_signatures(mut self, skip_signatures: bool) -> Self {
+        self.skip_signatures = skip_signatures;
+        self
+    }
+
+    fn parse_settings(&self) -> ParseSettings {
+        ParseSettings {
+            string_policy: self.string_policy,
+            skip_signatures: self.skip_signatures,
+            activity_minutes_policy: self.activity_minutes_policy,
+        }
+    }
+
+    fn check_input_size(&self, size: usize) -> Result<()> {
+        match self.max_input_bytes {
+            Some(max_input_bytes) if size > max_input_bytes => Err(anyhow::anyhow!(
+                "Input of {} bytes exceeds the limit of {} bytes",
+                size,
+                max_input_bytes
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails once more blocks than allowed by [`VuParserBuilder::max_blocks`] were read.
+    fn check_block_limit(&self, blocks: usize) -> Result<()> {
+        match self.max_blocks {
+            Some(max_blocks) if blocks > max_blocks => {
+                Err(anyhow::anyhow!("File has more than {} blocks", max_blocks))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn build(&self, input: Vec<u8>) -> VuParser {
+        match VuParser::unstuff(&input) {
+            Some(unstuffed) => VuParser {
+                input: unstuffed,
+                stuffed_source: Some(SourceDigest::new(&input)),
+                config: self.clone(),
+            },
+            None => VuParser {
+                input,
+                stuffed_source: None,
+                config: self.clone(),
+            },
+        }
+    }
+
+    pub fn from_file(&self, file_path: &str) -> Result<VuParser> {
+        let metadata = std::fs::metadata(file_path).context("Failed to read file")?;
+        self.check_input_size(metadata.len() as usize)?;
+        let input = std::fs::read(file_path).context("Failed to read file")?;
+        Ok(self.build(input))
+    }
+
+    pub fn from_bytes(&self, bytes: &[u8]) -> Result<VuParser> {
+        self.check_input_size(bytes.len())?;
+        Ok(self.build(bytes.to_vec()))
+    }
+
+    /// Reads the whole download from `source`, see [`VuParser::new_from_source`].
+    pub fn from_source<S: TachoRead + ?Sized>(&self, source: &S) -> Result<VuParser> {
+        let input = source::read_all(source).context("Failed to read source")?;
+        self.check_input_size(input.len())?;
+        Ok(self.build(input.into_owned()))
+    }
+
+    /// Streams the download from `reader`, starting at its current position, see
+    /// [`VuParser::new_from_reader`]. Validation needs the whole file and is not supported.
+    pub fn from_reader<R: Read + Seek>(&self, mut reader: R) -> Result<VuStreamParser<R>> {
+        if self.validation != ValidationLevel::Off {
+            anyhow::bail!("Validation needs the whole file, parse from bytes to validate");
+        }
+        let start = reader.stream_position().context("Failed to seek reader")?;
+        let end = reader
+            .seek(SeekFrom::End(0))
+            .context("Failed to seek reader")?;
+        reader
+            .seek(SeekFrom::Start(start))
+            .context("Failed to seek reader")?;
+        self.check_input_size(end.saturating_sub(start) as usize)?;
+        Ok(VuStreamParser {
+            reader,
+            config: self.clone(),
+        })
+    }
+}
 
 pub struct VuParser {
     input: Vec<u8>,
+    /// Digest of the input as it was read, when it was byte stuffed and `input` holds the
+    /// unstuffed bytes. The provenance is of the file as it was read.
+    stuffed_source: Option<SourceDigest>,
+    config: VuParserBuilder,
 }
 impl VuParser {
+    pub fn builder() -> VuParserBuilder {
+        VuParserBuilder::new()
+    }
     pub fn new_from_file(file_path: &str) -> Result<Self> {
-        let input = std::fs::read(file_path).context("Failed to read file")?;
-        Ok(VuParser { input })
+        VuParserBuilder::new().from_file(file_path)
     }
     pub fn new_from_bytes(bytes: &[u8]) -> Result<Self> {
-        Ok(VuParser {
-            input: bytes.to_vec(),
+        VuParserBuilder::new().from_bytes(bytes)
+    }
+    /// Reads the whole download from `source`, e.g. a blob that is decrypted while it is read.
+    pub fn new_from_source<S: TachoRead + ?Sized>(source: &S) -> Result<Self> {
+        VuParserBuilder::new().from_source(source)
+    }
+    /// Streams the download from `reader` instead of reading it into memory first, for archives
+    /// too large to buffer, e.g. objects in remote storage behind a seekable adapter.
+    pub fn new_from_reader<R: Read + Seek>(reader: R) -> Result<VuStreamParser<R>> {
+        VuParserBuilder::new().from_reader(reader)
+    }
+
+    pub(crate) const GEN1_TREPS: [u8; 5] = tachograph_detector::GEN1_TREPS;
+    pub(crate) const GEN2_TREPS: [u8; 5] = tachograph_detector::GEN2_TREPS;
+    pub(crate) const GEN2V2_TREPS: [u8; 4] = tachograph_detector::GEN2V2_TREPS;
+
+    /// Records an unknown block in the diagnostics and moves the cursor to the next known block marker
+    /// (SID 0x76 followed by one of `known_treps`), or to the end of the input if there is none.
+    /// Must be called right after the SID and TREP bytes were read.
+    fn skip_unknown_block(
+        cursor: &mut Cursor<&[u8]>,
+        sid: u8,
+        trep: u8,
+        known_treps: &[u8],
+        diagnostics: &mut Diagnostics,
+    ) {
+        let data = *cursor.get_ref();
+        let offset = cursor.position() as usize - 2;
+        let length = Self::record_unknown_block(
+            sid,
+            trep,
+            offset,
+            &data[offset..],
+            known_treps,
+            diagnostics,
+        );
+        cursor.set_position((offset + length) as u64);
+    }
+
+    /// Records the unknown block at the start of `data`, found at `offset` of the file, in the
+    /// diagnostics. It ends at the next known block marker in `data` or with `data`, and its length
+    /// is returned.
+    fn record_unknown_block(
+        sid: u8,
+        trep: u8,
+        offset: usize,
+        data: &[u8],
+        known_treps: &[u8],
+        diagnostics: &mut Diagnostics,
+    ) -> usize {
+        let next_block =
+            Self::find_block_marker(&data[2..], known_treps).map(|position| position + 2);
+        let length = next_block.unwrap_or(data.len());
+
+        log::warn!(
+            "Unknown block type: sid: {:#04x}, trep: {:#04x} at offset {}, skipping {} bytes",
+            sid,
+            trep,
+            offset,
+            length
+        );
+        diagnostics.unknown_blocks.push(UnknownBlock {
+            sid,
+            trep,
+            offset,
+            skipped_bytes: length,
+            resynchronized: next_block.is_some(),
+        });
+        length
+    }
+
+    /// Length of the download response message header at the start of `data`, see
+    /// [`tachograph_detector::response_header_length`].
+    pub(crate) fn response_header_length(data: &[u8]) -> usize {
+        tachograph_detector::response_header_length(data)
+    }
+
+    /// Moves the cursor past the response message header in front of the next block, if any.
+    fn skip_response_header(cursor: &mut Cursor<&[u8]>, diagnostics: &mut Diagnostics) {
+        let position = cursor.position() as usize;
+        let length = Self::record_response_header(&cursor.get_ref()[position..], diagnostics);
+        cursor.set_position((position + length) as u64);
+    }
+
+    /// Length of the response message header at the start of `data`, recorded in the diagnostics
+    /// when there is one.
+    fn record_response_header(data: &[u8], diagnostics: &mut Diagnostics) -> usize {
+        let length = Self::response_header_length(data);
+        if length > 0 {
+            diagnostics.recognized_bytes += length;
+            diagnostics.response_headers = true;
+        }
+        length
+    }
+
+    /// Removes the byte stuffing of files captured directly from the download interface, where
+    /// every 0x10 of the data is sent twice. `None` when `input` does not look stuffed: it has no
+    /// 0x10, a 0x10 that is not doubled, or its blocks do not leave fewer bytes unaccounted for
+    /// once unstuffed.
+    ///
+    /// Offsets in the diagnostics and segments of the parsed data refer to the unstuffed bytes.
+    ///
+    /// The input is only copied once every 0x10 turned out doubled and its blocks do not all parse
+    /// as they are.
+    pub(crate) fn unstuff(input: &[u8]) -> Option<Vec<u8>> {
+        let mut unstuffing = Unstuffing::default();
+        if !unstuffing.scan_slice(input) || unstuffing.in_pair || unstuffing.pairs == 0 {
+            return None;
+        }
+        let unaccounted = |input: &[u8]| match Self::block_ranges(input) {
+            Ok(ranges) => input.len() - ranges.iter().map(|range| range.len()).sum::<usize>(),
+            Err(_) => input.len(),
+        };
+        let stuffed = unaccounted(input);
+        if stuffed == 0 {
+            return None;
+        }
+        let mut unstuffed = input.to_vec();
+        let length = Unstuffing::default().unstuff(&mut unstuffed)?;
+        unstuffed.truncate(length);
+        (unaccounted(&unstuffed) < stuffed).then_some(unstuffed)
+    }
+
+    /// TREPs of the generation the file's first block belongs to.
+    fn known_treps(input: &[u8]) -> Option<&'static [u8]> {
+        match &input[Self::response_header_length(input)..] {
+            [0x76, 0x01..=0x05, ..] => Some(&Self::GEN1_TREPS),
+            [0x76, 0x21..=0x25, ..] => Some(&Self::GEN2_TREPS),
+            [0x76, 0x31..=0x35, ..] => Some(&Self::GEN2V2_TREPS),
+            _ => None,
+        }
+    }
+
+    /// Byte ranges of the recognized blocks, SID and TREP included, in file order.
+    /// Unknown blocks are skipped the same way `parse` skips them.
+    pub(crate) fn block_ranges(input: &[u8]) -> Result<Vec<Range<usize>>> {
+        let known_treps =
+            Self::known_treps(input).context("File does not start with a VuOverview block")?;
+        let mut ranges = Vec::new();
+        let mut diagnostics = Diagnostics::default();
+        let mut cursor = Cursor::new(input);
+        while (cursor.position() as usize) < input.len() {
+            Self::skip_response_header(&mut cursor, &mut diagnostics);
+            let start = cursor.position() as usize;
+            let sid = cursor.read_u8().context("Failed to read sid")?;
+            let trep = cursor.read_u8().context("Failed to read trep")?;
+            if sid == 0x76 && known_treps.contains(&trep) {
+                Self::drop_block(&mut cursor, trep)?;
+                ranges.push(start..cursor.position() as usize);
+            } else {
+                Self::skip_unknown_block(&mut cursor, sid, trep, known_treps, &mut diagnostics);
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Checks every block of [`block_coverage`] and that the overview block is present.
+    pub(crate) fn validate_blocks(input: &[u8]) -> Vec<ValidationIssue> {
+        let coverage = match block_coverage(input) {
+            Ok(coverage) => coverage,
+            Err(e) => return vec![ValidationIssue::error(Some(0), format!("{:#}", e))],
+        };
+        let overview_trep = input[Self::response_header_length(input) + 1];
+        let mut issues = Vec::new();
+        for block in &coverage {
+            if let BlockParseStatus::Failed { error } = &block.status {
+                let message = match block.trep {
+                    Some(trep) => {
+                        format!("Failed to parse block with TREP {:#04x}: {}", trep, error)
+                    }
+                    None => error.clone(),
+                };
+                issues.push(ValidationIssue::error(Some(block.offset), message));
+            }
+        }
+        for block in &coverage {
+            if let (BlockParseStatus::Unknown, Some(trep)) = (&block.status, block.trep) {
+                issues.push(ValidationIssue::warning(
+                    Some(block.offset),
+                    format!(
+                        "Unknown block with SID {:#04x} and TREP {:#04x}, {} bytes skipped",
+                        block.sid, trep, block.length
+                    ),
+                ));
+            }
+        }
+        let has_overview = coverage.iter().any(|block| {
+            block.trep == Some(overview_trep) && block.status == BlockParseStatus::Parsed
+        });
+        if !has_overview {
+            issues.push(ValidationIssue::error(
+                None,
+                "No valid VuOverview block found",
+            ));
+        }
+        issues
+    }
+
+    /// Parses every block on its own and reports which ones the parser handles, see
+    /// [`BlockCoverage`].
+    pub fn block_coverage(&self) -> Result<Vec<BlockCoverage>> {
+        block_coverage(&self.input)
+    }
+
+    /// In lenient mode, a final block the file ends in the middle of, as happens with aborted
+    /// downloads, is dropped and reported in [`Diagnostics::truncated_block`] instead of failing
+    /// the parse.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.config.lenient = lenient;
+        self
+    }
+
+    /// Keeps the byte range of every recognized block in [`VuData::segments`], so that a subset of
+    /// the download can be re-exported with [`VuData::export_segments`].
+    pub fn keep_segments(mut self, keep_segments: bool) -> Self {
+        self.config.keep_segments = keep_segments;
+        self
+    }
+
+    /// Runs `parse_block` on the block whose SID and TREP were just read. In lenient mode a block
+    /// that runs out of data with no known block marker after it is taken as truncated.
+    fn parse_block<'a>(
+        &self,
+        cursor: &mut Cursor<&'a [u8]>,
+        known_treps: &[u8],
+        diagnostics: &mut Diagnostics,
+        parse_block: impl FnOnce(&mut Cursor<&'a [u8]>, &mut Diagnostics) -> Result<()>,
+    ) -> Result<()> {
+        if !self.config.lenient {
+            return parse_block(cursor, diagnostics);
+        }
+        let block_start = cursor.position() as usize - 2;
+        let Err(err) = parse_block(&mut *cursor, &mut *diagnostics) else {
+            return Ok(());
+        };
+        let block = &cursor.get_ref()[block_start..];
+        if !Self::is_truncated(&err, block, known_treps) {
+            return Err(err);
+        }
+        log::warn!("Final block is truncated: {:#}", err);
+        let missing_bytes = Self::missing_bytes(block, known_treps);
+        Self::truncate(cursor, block_start, missing_bytes, diagnostics);
+        Ok(())
+    }
+
+    /// Whether the block at the start of `block` that failed with `err` is the final block of a
+    /// download that ends in its middle: it ran out of data with no known block marker after it.
+    fn is_truncated(err: &anyhow::Error, block: &[u8], known_treps: &[u8]) -> bool {
+        Self::ran_out_of_data(err) && !Self::has_block_marker(&block[2..], known_treps)
+    }
+
+    /// Bytes missing from the truncated block at the start of `block`, see
+    /// [`TruncatedBlock::missing_bytes`].
+    fn missing_bytes(block: &[u8], known_treps: &[u8]) -> Option<usize> {
+        (known_treps != Self::GEN1_TREPS).then(|| gen2::record_arrays_missing_bytes(&block[2..]))
+    }
+
+    fn ran_out_of_data(err: &anyhow::Error) -> bool {
+        err.chain().any(|cause| {
+            cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof)
         })
     }
 
+    /// Position of the first known block marker in `data`, SID 0x76 followed by one of `known_treps`.
+    fn find_block_marker(data: &[u8], known_treps: &[u8]) -> Option<usize> {
+        data.windows(2)
+            .position(|marker| marker[0] == 0x76 && known_treps.contains(&marker[1]))
+    }
+
+    fn has_block_marker(data: &[u8], known_treps: &[u8]) -> bool {
+        Self::find_block_marker(data, known_treps).is_some()
+    }
+
+    /// Records the block at `block_start` as truncated and moves the cursor to the end of the input.
+    fn truncate(
+        cursor: &mut Cursor<&[u8]>,
+        block_start: usize,
+        missing_bytes: Option<usize>,
+        diagnostics: &mut Diagnostics,
+    ) {
+        let input = *cursor.get_ref();
+        diagnostics.truncated_block = Some(TruncatedBlock {
+            offset: block_start,
+            header: input[block_start..input.len().min(block_start + 2)].to_vec(),
+            available_bytes: input.len() - block_start,
+            missing_bytes,
+        });
+        cursor.set_position(input.len() as u64);
+    }
+
+    /// Fails in [`ValidationLevel::Strict`] when the blocks have structural errors.
+    fn validate(&self) -> Result<()> {
+        if self.config.validation == ValidationLevel::Off {
+            return Ok(());
+        }
+        let issues = Self::validate_blocks(&self.input);
+        for issue in &issues {
+            log::warn!(
+                "Validation {:?} at offset {:?}: {}",
+                issue.severity,
+                issue.offset,
+                issue.message
+            );
+        }
+        let errors: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.severity == IssueSeverity::Error)
+            .map(|issue| issue.message.as_str())
+            .collect();
+        if self.config.validation == ValidationLevel::Strict && !errors.is_empty() {
+            anyhow::bail!("Validation failed: {}", errors.join("; "));
+        }
+        Ok(())
+    }
+
+    /// Parses the recognized block for `trep` and drops it, e.g. when the [`TrepFilter`] does not
+    /// keep it.
+    fn drop_block(cursor: &mut Cursor<&[u8]>, trep: u8) -> Result<()> {
+        VuBlock::parse(cursor, trep)
+            .context("Block without parser")?
+            .with_context(|| format!("Failed to parse block with TREP {:#04x}", trep))
+            .map(drop)
+    }
+
     pub fn parse(&self) -> Result<VuData> {
-        let mut cursor = Cursor::new(&self.input[..]);
+        self.validate()?;
+        let settings = self.config.parse_settings();
+        let (vu_data, invalid_fields) = with_parse_context(settings, &self.input, || {
+            let mut blocks = Vec::new();
+            let (segments, diagnostics) = self.read_blocks(&mut |block| {
+                blocks.push(block);
+                Ok(())
+            })?;
+            VuBlock::collect(
+                blocks,
+                self.config.keep_segments.then_some(segments),
+                diagnostics,
+            )
+        });
+        let mut vu_data = vu_data?;
+        vu_data.diagnostics_mut().invalid_fields = invalid_fields;
+        vu_data.diagnostics_mut().byte_stuffing = self.stuffed_source.is_some();
+        vu_data.diagnostics_mut().signatures_skipped = self.config.skip_signatures;
+        Ok(vu_data)
+    }
 
+    /// Parses the blocks of the file's generation, handing each one kept by the [`TrepFilter`] to
+    /// `on_block` as soon as it is parsed.
+    fn read_blocks(
+        &self,
+        on_block: &mut impl FnMut(VuBlock) -> Result<()>,
+    ) -> Result<(Vec<VuSegment>, Diagnostics)> {
+        let mut cursor = Cursor::new(&self.input[..]);
+        let generation = match self.config.generation {
+            Some(generation) => generation,
+            None => Self::detect_generation(&mut cursor)?,
+        };
+        self.parse_blocks(&mut cursor, Self::generation_treps(generation), on_block)
+    }
+    fn detect_generation(cursor: &mut Cursor<&[u8]>) -> Result<Generation> {
         // Read the first byte to determine the generation
-        let trep = cursor.fill_buf().context("Failed to fill buffer")?[0];
-        let sid = cursor.fill_buf().context("Failed to fill buffer")?[1];
+        let buffer = cursor.fill_buf().context("Failed to fill buffer")?;
+        let buffer = &buffer[Self::response_header_length(buffer)..];
+        let [trep, sid, ..] = *buffer else {
+            anyhow::bail!(
+                "File of {} bytes is too short for a block header",
+                buffer.len()
+            );
+        };
 
         match (trep, sid) {
             // Gen1 (checks for first block being VuOverviewBlock)
-            (0x76, 0x01..=0x05) => self.parse_gen1(&mut cursor),
+            (0x76, 0x01..=0x05) => Ok(Generation::Gen1),
             // Gen2 (checks for first block being VuOverviewBlock)
-            (0x76, 0x21..=0x25) => self.parse_gen2(&mut cursor),
+            (0x76, 0x21..=0x25) => Ok(Generation::Gen2),
             // Gen2V2 (checks for first block being VuOverviewBlock)
-            (0x76, 0x31..=0x35) => self.parse_gen2v2(&mut cursor),
+            (0x76, 0x31..=0x35) => Ok(Generation::Gen2V2),
             _ => Err(anyhow::anyhow!(
                 "Unknown file format: trep {:02x} sid {:02x}",
                 trep,
@@ -83,13 +903,34 @@ impl VuParser {
             )),
         }
     }
-    fn parse_gen1(&self, cursor: &mut Cursor<&[u8]>) -> Result<VuData> {
-        let mut vu_overview: Option<gen1::VuOverviewBlock> = None;
-        let mut vu_activities: Vec<gen1::VuActivitiesBlock> = Vec::new();
-        let mut vu_events_and_faults: Vec<gen1::VuEventsAndFaultsBlock> = Vec::new();
-        let mut vu_detailed_speed: Vec<gen1::VuDetailedSpeedBlock> = Vec::new();
-        let mut vu_company_locks: Vec<gen1::VuCompanyLocksBlock> = Vec::new();
+    /// TREPs of the blocks of `generation`.
+    fn generation_treps(generation: Generation) -> &'static [u8] {
+        match generation {
+            Generation::Gen1 => &Self::GEN1_TREPS,
+            Generation::Gen2 => &Self::GEN2_TREPS,
+            Generation::Gen2V2 => &Self::GEN2V2_TREPS,
+        }
+    }
+
+    /// Parses the blocks with `known_treps`, the TREPs of one generation, and skips the others as
+    /// unknown blocks.
+    fn parse_blocks(
+        &self,
+        cursor: &mut Cursor<&[u8]>,
+        known_treps: &[u8],
+        on_block: &mut impl FnMut(VuBlock) -> Result<()>,
+    ) -> Result<(Vec<VuSegment>, Diagnostics)> {
+        let mut segments: Vec<VuSegment> = Vec::new();
+        let mut diagnostics = Diagnostics::default();
         while !cursor.fill_buf()?.is_empty() {
+            self.config
+                .check_block_limit(segments.len() + diagnostics.unknown_blocks.len() + 1)?;
+            Self::skip_response_header(cursor, &mut diagnostics);
+            let block_start = cursor.position() as usize;
+            if self.config.lenient && self.input.len() - block_start < 2 {
+                Self::truncate(cursor, block_start, None, &mut diagnostics);
+                break;
+            }
             let sid = cursor.read_u8().context("Failed to read sid")?;
             let trep = cursor.read_u8().context("Failed to read trep")?;
             log::debug!(
@@ -97,172 +938,845 @@ impl VuParser {
                 sid,
                 trep
             );
-            match (sid, trep) {
-                (0x76, 0x01) => {
-                    vu_overview = Some(
-                        gen1::VuOverviewBlock::parse(cursor)
-                            .context("Failed to parse VuOverviewBlock")?,
-                    );
-                }
-                (0x76, 0x02) => {
-                    vu_activities.push(
-                        gen1::VuActivitiesBlock::parse(cursor)
-                            .context("Failed to parse VuActivitiesBlock")?,
-                    );
-                }
-                (0x76, 0x03) => {
-                    vu_events_and_faults.push(
-                        gen1::VuEventsAndFaultsBlock::parse(cursor)
-                            .context("Failed to parse VuEventsAndFaultsBlock")?,
-                    );
-                }
-                (0x76, 0x04) => {
-                    vu_detailed_speed.push(
-                        gen1::VuDetailedSpeedBlock::parse(cursor)
-                            .context("Failed to parse VuDetailedSpeedData")?,
-                    );
-                }
-                (0x76, 0x05) => {
-                    vu_company_locks.push(
-                        gen1::VuCompanyLocksBlock::parse(cursor)
-                            .context("Failed to parse VuCompanyLocksBlock")?,
-                    );
-                }
-                _ => {
-                    log::warn!("Unknown block type: sid: {:#04x}, trep: {:#04x}", sid, trep);
-                    break;
-                }
+            let known = sid == 0x76 && known_treps.contains(&trep);
+            let mut block = None;
+            self.parse_block(
+                cursor,
+                known_treps,
+                &mut diagnostics,
+                |cursor, diagnostics| {
+                    if known {
+                        block = read_block(&self.config, trep, cursor)?;
+                    } else {
+                        Self::skip_unknown_block(cursor, sid, trep, known_treps, diagnostics);
+                    }
+                    Ok(())
+                },
+            )?;
+            if let Some(block) = block {
+                on_block(block)?;
+            }
+            if known && diagnostics.truncated_block.is_none() {
+                diagnostics.recognized_bytes += cursor.position() as usize - block_start;
+                let range = block_start..cursor.position() as usize;
+                segments.push(VuSegment::new(trep, range, &segments));
             }
         }
+        diagnostics.total_bytes = self.input.len();
+        Ok((segments, diagnostics))
+    }
 
-        // Implement Gen1 parsing logic here
-        Ok(VuData::Gen1(VuGen1Blocks {
-            vu_overview: vu_overview
-                .context("unable to find VuOverviewBlock after parsing file")?,
-            vu_activities,
-            vu_events_and_faults,
-            vu_detailed_speed,
-            vu_company_locks,
-        }))
+    /// Parses the VU file and writes its identification, activity and event records into `sink`
+    /// block by block, as each block is parsed. Records of the blocks before a failing block are
+    /// written by the time the parse fails.
+    pub fn parse_to_sink(&self, sink: &mut impl TachoSink) -> Result<()> {
+        self.validate()?;
+        let mut writer = RecordWriter::new(sink);
+        let (parsed, _) = with_parse_context(self.config.parse_settings(), &self.input, || {
+            self.read_blocks(&mut |block| writer.write(block))
+        });
+        parsed.context("Failed to parse vehicle data")?;
+        writer.finish()
     }
 
-    fn parse_gen2(&self, cursor: &mut Cursor<&[u8]>) -> Result<VuData> {
-        let mut vu_overview: Option<gen2::VuOverviewBlockGen2> = None;
-        let mut vu_activities: Vec<gen2::VuActivitiesBlockGen2> = Vec::new();
-        let mut vu_events_and_faults: Vec<gen2::VuEventsAndFaultsBlockGen2> = Vec::new();
-        let mut vu_detailed_speed: Vec<gen2::VuDetailedSpeedBlockGen2> = Vec::new();
-        let mut vu_company_locks: Vec<gen2::VuCompanyLocksGen2> = Vec::new();
+    pub fn parse_to_json(&self) -> Result<String> {
+        let vu_data = self.parse().context("Failed to parse vehicle data")?;
+        let pretty_json = serde_json::to_string_pretty(&vu_data)
+            .context("Failed to convert serde value to pretty JSON string")?;
+        Ok(pretty_json)
+    }
+    pub fn parse_to_json_pretty(&self) -> Result<String> {
+        let vu_data = self.parse().context("Failed to parse vehicle data")?;
+        let pretty_json = serde_json::to_string_pretty(&vu_data)
+            .context("Failed to convert serde value to pretty JSON string")?;
+        Ok(pretty_json)
+    }
+    /// Parses the file while recording every primitive read, see [`trace::render_annotated_hex`].
+    /// The trace is returned even when parsing fails, up to the failing read.
+    pub fn parse_with_trace(&self) -> (Result<VuData>, Vec<TraceEntry>) {
+        trace::capture(&self.input, || self.parse())
+    }
+    pub fn parse_to_json_with_options(&self, options: &JsonOptions) -> Result<String> {
+        let vu_data = self.parse().context("Failed to parse vehicle data")?;
+        // the provenance is of the file as it was read, stuffing included
+        match &self.stuffed_source {
+            Some(source) => json::to_json_with_digest(&vu_data, source, options),
+            None => json::to_json_with_source(&vu_data, &self.input, options),
+        }
+    }
+    /// Same as [`Self::parse_to_json_with_options`], in CBOR or MessagePack.
+    pub fn parse_to_binary_with_options(
+        &self,
+        format: BinaryFormat,
+        options: &JsonOptions,
+    ) -> Result<Vec<u8>> {
+        let vu_data = self.parse().context("Failed to parse vehicle data")?;
+        match &self.stuffed_source {
+            Some(source) => binary::to_binary_with_digest(&vu_data, source, format, options),
+            None => binary::to_binary_with_source(&vu_data, &self.input, format, options),
+        }
+    }
+}
 
-        while !cursor.fill_buf()?.is_empty() {
-            let sid = cursor.read_u8().context("Failed to read sid")?;
-            let trep = cursor.read_u8().context("Failed to read trep")?;
-            log::debug!(
-                "Parsing vu data with sid: {:#04x}, trep: {:#04x}",
+/// Parses a VU download from a reader one block at a time, see [`VuParser::new_from_reader`].
+///
+/// VU blocks do not start with their length, so the parser reads ahead until the block it is on
+/// parses, then drops its bytes. Only the current block is held in memory next to the parsed data.
+/// A block that runs out of data is read further only while no known block that parses starts
+/// after it, so a malformed block does not pull the rest of the download into memory.
+///
+/// The download is read once before parsing, up to its first 0x10 that is not doubled, to tell
+/// whether it was captured with its byte stuffing, see [`Diagnostics::byte_stuffing`]. A stuffed
+/// download is read twice more to check that unstuffing it accounts for more of its bytes.
+pub struct VuStreamParser<R> {
+    reader: R,
+    config: VuParserBuilder,
+}
+impl<R: Read + Seek> VuStreamParser<R> {
+    /// See [`VuParser::lenient`].
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.config.lenient = lenient;
+        self
+    }
+
+    /// See [`VuParser::keep_segments`].
+    pub fn keep_segments(mut self, keep_segments: bool) -> Self {
+        self.config.keep_segments = keep_segments;
+        self
+    }
+
+    /// Parses the VU file and writes its identification, activity and event records into `sink`
+    /// as each block is parsed, see [`VuParser::parse_to_sink`]. The parsed blocks are dropped
+    /// once written, so only the block being read is held in memory.
+    pub fn parse_to_sink(self, sink: &mut impl TachoSink) -> Result<()> {
+        let mut writer = RecordWriter::new(sink);
+        self.read_blocks(&mut |block| writer.write(block))
+            .context("Failed to parse vehicle data")?;
+        writer.finish()
+    }
+
+    pub fn parse(self) -> Result<VuData> {
+        let keep_segments = self.config.keep_segments;
+        let mut blocks = Vec::new();
+        let (segments, diagnostics) = self.read_blocks(&mut |block| {
+            blocks.push(block);
+            Ok(())
+        })?;
+        VuBlock::collect(blocks, keep_segments.then_some(segments), diagnostics)
+    }
+
+    /// Parses the blocks of the download, unstuffed if it is byte stuffed, handing each one kept
+    /// by the [`TrepFilter`] to `on_block` as soon as it is parsed.
+    fn read_blocks(
+        self,
+        on_block: &mut impl FnMut(VuBlock) -> Result<()>,
+    ) -> Result<(Vec<VuSegment>, Diagnostics)> {
+        let VuStreamParser { mut reader, config } = self;
+        let start = reader.stream_position().context("Failed to seek reader")?;
+        let rewind = |reader: &mut R| {
+            reader
+                .seek(SeekFrom::Start(start))
+                .context("Failed to seek reader")
+        };
+        let stuffing = Unstuffing::scan(&mut reader)?;
+        rewind(&mut reader)?;
+        let Some(stuffing) = stuffing else {
+            return parse_stream(reader, &config, on_block);
+        };
+        // the same check as VuParser::unstuff, on the blocks the download holds either way
+        let probe = config.clone().only_treps(&[]).lenient(false);
+        let unaccounted = |parsed: Result<(Vec<VuSegment>, Diagnostics)>, total_bytes: usize| {
+            parsed.map_or(total_bytes, |(_, diagnostics)| {
+                diagnostics.total_bytes - diagnostics.recognized_bytes
+            })
+        };
+        let stuffed = unaccounted(
+            parse_stream(&mut reader, &probe, &mut |_| Ok(())),
+            stuffing.read,
+        );
+        rewind(&mut reader)?;
+        let unstuffed = unaccounted(
+            parse_stream(Unstuffed::new(&mut reader), &probe, &mut |_| Ok(())),
+            stuffing.read - stuffing.pairs,
+        );
+        rewind(&mut reader)?;
+        if unstuffed >= stuffed {
+            return parse_stream(reader, &config, on_block);
+        }
+        let (segments, mut diagnostics) = parse_stream(Unstuffed::new(reader), &config, on_block)?;
+        diagnostics.byte_stuffing = true;
+        Ok((segments, diagnostics))
+    }
+}
+
+/// Parses the blocks read from `reader` one at a time, see [`VuStreamParser`].
+fn parse_stream(
+    reader: impl Read,
+    config: &VuParserBuilder,
+    on_block: &mut impl FnMut(VuBlock) -> Result<()>,
+) -> Result<(Vec<VuSegment>, Diagnostics)> {
+    let mut window = BlockWindow {
+        reader,
+        buffer: Vec::new(),
+        offset: 0,
+        eof: false,
+    };
+    window.fill(6)?;
+    let known_treps = match config.generation {
+        Some(generation) => VuParser::generation_treps(generation),
+        None => VuParser::known_treps(&window.buffer)
+            .context("File does not start with a VuOverview block")?,
+    };
+    let mut segments: Vec<VuSegment> = Vec::new();
+    let mut diagnostics = Diagnostics::default();
+
+    loop {
+        window.fill(6)?;
+        if window.buffer.is_empty() {
+            break;
+        }
+        config.check_block_limit(segments.len() + diagnostics.unknown_blocks.len() + 1)?;
+        let header_length = VuParser::record_response_header(&window.buffer, &mut diagnostics);
+        window.consume(header_length);
+        window.fill(2)?;
+        let block_start = window.offset;
+        if window.buffer.len() < 2 {
+            if !config.lenient {
+                anyhow::bail!("Failed to read trep");
+            }
+            diagnostics.truncated_block = Some(window.truncated_block(None));
+            break;
+        }
+        let (sid, trep) = (window.buffer[0], window.buffer[1]);
+        log::debug!(
+            "Parsing vu data with sid: {:#04x}, trep: {:#04x}",
+            sid,
+            trep
+        );
+
+        if sid != 0x76 || !known_treps.contains(&trep) {
+            while VuParser::find_block_marker(&window.buffer[2..], known_treps).is_none()
+                && window.grow()?
+            {}
+            let length = VuParser::record_unknown_block(
                 sid,
-                trep
+                trep,
+                block_start,
+                &window.buffer,
+                known_treps,
+                &mut diagnostics,
             );
-            match (sid, trep) {
-                (0x76, 0x21) => {
-                    vu_overview = Some(
-                        gen2::VuOverviewBlockGen2::parse(cursor)
-                            .context("Failed to parse VuOverviewGen2")?,
-                    )
+            window.consume(length);
+            continue;
+        }
+
+        let parsed = loop {
+            let mut cursor = Cursor::new(&window.buffer[..]);
+            cursor.set_position(2);
+            let (result, invalid_fields) =
+                with_parse_context(config.parse_settings(), &window.buffer, || {
+                    read_block(config, trep, &mut cursor)
+                });
+            let err = match result {
+                Ok(block) => {
+                    let shifted = invalid_fields.into_iter().map(|mut field| {
+                        field.offset = field.offset.map(|offset| offset + window.offset);
+                        field
+                    });
+                    diagnostics.invalid_fields.extend(shifted);
+                    break Some((block, cursor.position() as usize));
                 }
-                (0x76, 0x22) => vu_activities.push(
-                    gen2::VuActivitiesBlockGen2::parse(cursor)
-                        .context("Failed to parse VuActivitiesGen2")?,
-                ),
-                (0x76, 0x23) => vu_events_and_faults.push(
-                    gen2::VuEventsAndFaultsBlockGen2::parse(cursor)
-                        .context("Failed to parse VuEventsAndFaultsGen2")?,
-                ),
-                (0x76, 0x24) => vu_detailed_speed.push(
-                    gen2::VuDetailedSpeedBlockGen2::parse(cursor)
-                        .context("Failed to parse VuDetailedSpeed")?,
-                ),
-                (0x76, 0x25) => vu_company_locks.push(
-                    gen2::VuCompanyLocksGen2::parse(cursor)
-                        .context("Failed to parse VuCompanyLocksGen2")?,
-                ),
-                _ => {
-                    log::warn!("Unknown block type: sid: {:#04x}, trep: {:#04x}", sid, trep);
-                    break;
+                Err(err) => err,
+            };
+            if VuParser::ran_out_of_data(&err)
+                && !window.has_block_after(config, known_treps)
+                && window.grow()?
+            {
+                continue;
+            }
+            if !config.lenient || !VuParser::is_truncated(&err, &window.buffer, known_treps) {
+                return Err(err);
+            }
+            log::warn!("Final block is truncated: {:#}", err);
+            let missing_bytes = VuParser::missing_bytes(&window.buffer, known_treps);
+            diagnostics.truncated_block = Some(window.truncated_block(missing_bytes));
+            break None;
+        };
+        let Some((block, length)) = parsed else {
+            break;
+        };
+        if let Some(block) = block {
+            on_block(block)?;
+        }
+        diagnostics.recognized_bytes += length;
+        segments.push(VuSegment::new(
+            trep,
+            block_start..block_start + length,
+            &segments,
+        ));
+        window.consume(length);
+    }
+    diagnostics.total_bytes = window.offset + window.buffer.len();
+    diagnostics.signatures_skipped = config.skip_signatures;
+    Ok((segments, diagnostics))
+}
+
+/// Bytes read ahead by a [`VuStreamParser`] that no block was parsed from yet.
+struct BlockWindow<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Offset of `buffer[0]` from the start of the download.
+    offset: usize,
+    eof: bool,
+}
+impl<R: Read> BlockWindow<R> {
+    const MIN_READ: usize = if cfg!(test) { 16 } else { 64 * 1024 };
+
+    /// Reads at least as many bytes as are already buffered, so that a block is parsed a
+    /// logarithmic number of times. Returns false once the reader has no more bytes.
+    fn grow(&mut self) -> Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let wanted = self.buffer.len().max(Self::MIN_READ);
+        let read = self
+            .reader
+            .by_ref()
+            .take(wanted as u64)
+            .read_to_end(&mut self.buffer)
+            .context("Failed to read from reader")?;
+        self.eof = read < wanted;
+        Ok(read > 0)
+    }
+
+    /// Reads until at least `length` bytes are buffered or the reader ends.
+    fn fill(&mut self, length: usize) -> Result<()> {
+        while self.buffer.len() < length && self.grow()? {}
+        Ok(())
+    }
+
+    fn consume(&mut self, length: usize) {
+        self.buffer.drain(..length);
+        self.offset += length;
+    }
+
+    /// Whether a known block starts after the SID and TREP of the buffered block and parses from
+    /// the buffered bytes. Blocks do not overlap, so a block that ran out of data with such a
+    /// block after it is malformed rather than longer than the buffer.
+    fn has_block_after(&self, config: &VuParserBuilder, known_treps: &[u8]) -> bool {
+        let mut start = 2;
+        while let Some(position) = VuParser::find_block_marker(&self.buffer[start..], known_treps) {
+            let marker = start + position;
+            let mut cursor = Cursor::new(&self.buffer[marker + 2..]);
+            let (parsed, _) = with_parse_context(config.parse_settings(), &self.buffer, || {
+                VuBlock::parse(&mut cursor, self.buffer[marker + 1])
+            });
+            if matches!(parsed, Some(Ok(_))) {
+                return true;
+            }
+            start = marker + 1;
+        }
+        false
+    }
+
+    /// The rest of the download, taken as a block the download ends in the middle of.
+    fn truncated_block(&self, missing_bytes: Option<usize>) -> TruncatedBlock {
+        TruncatedBlock {
+            offset: self.offset,
+            header: self.buffer[..self.buffer.len().min(2)].to_vec(),
+            available_bytes: self.buffer.len(),
+            missing_bytes,
+        }
+    }
+}
+
+/// Removes the byte stuffing of a download read in chunks, see [`VuParser::unstuff`].
+#[derive(Default)]
+struct Unstuffing {
+    /// The last byte was a 0x10 whose repetition was not read yet.
+    in_pair: bool,
+    /// Doubled 0x10s found so far.
+    pairs: usize,
+    /// Stuffed bytes read so far.
+    read: usize,
+}
+impl Unstuffing {
+    /// Unstuffs `chunk` in place and returns its unstuffed length, `None` at a 0x10 that is not
+    /// doubled.
+    fn unstuff(&mut self, chunk: &mut [u8]) -> Option<usize> {
+        let mut length = 0;
+        for index in 0..chunk.len() {
+            let byte = chunk[index];
+            if self.in_pair {
+                if byte != 0x10 {
+                    return None;
                 }
+                self.in_pair = false;
+                self.pairs += 1;
+                continue;
             }
+            self.in_pair = byte == 0x10;
+            chunk[length] = byte;
+            length += 1;
         }
+        self.read += chunk.len();
+        Some(length)
+    }
 
-        Ok(VuData::Gen2(VuGen2Blocks {
-            vu_overview: vu_overview
-                .context("unable to find VuOverviewBlock after parsing file")?,
-            vu_activities,
-            vu_events_and_faults,
-            vu_detailed_speed,
-            vu_company_locks,
-        }))
+    /// Counts the doubled 0x10s of `chunk` without unstuffing it, `false` at a 0x10 that is not
+    /// doubled.
+    fn scan_slice(&mut self, chunk: &[u8]) -> bool {
+        for &byte in chunk {
+            if self.in_pair {
+                if byte != 0x10 {
+                    return false;
+                }
+                self.in_pair = false;
+                self.pairs += 1;
+            } else {
+                self.in_pair = byte == 0x10;
+            }
+        }
+        self.read += chunk.len();
+        true
     }
 
-    fn parse_gen2v2(&self, cursor: &mut Cursor<&[u8]>) -> Result<VuData> {
-        let mut vu_overview: Option<gen2v2::VuOverviewBlockGen2V2> = None;
-        let mut vu_activities: Vec<gen2v2::VuActivitiesBlockGen2V2> = Vec::new();
-        let mut vu_events_and_faults: Vec<gen2::VuEventsAndFaultsBlockGen2> = Vec::new();
-        let mut vu_company_locks: Vec<gen2v2::VuCompanyLocksGen2V2> = Vec::new();
+    /// Reads `reader` to its end, or to its first 0x10 that is not doubled, and returns the
+    /// stuffing found when every 0x10 is doubled and there is at least one.
+    fn scan(reader: impl Read) -> Result<Option<Self>> {
+        let mut unstuffed = Unstuffed::new(reader);
+        match std::io::copy(&mut unstuffed, &mut std::io::sink()) {
+            Ok(_) => Ok(Some(unstuffed.unstuffing).filter(|stuffing| stuffing.pairs > 0)),
+            Err(_) if unstuffed.not_stuffed => Ok(None),
+            Err(e) => Err(e).context("Failed to read from reader"),
+        }
+    }
+}
 
-        while !cursor.fill_buf()?.is_empty() {
-            let sid = cursor.read_u8().context("Failed to read sid")?;
-            let trep = cursor.read_u8().context("Failed to read trep")?;
-            log::debug!(
-                "Parsing vu data with sid: {:#04x}, trep: {:#04x}",
-                sid,
-                trep
-            );
-            match (sid, trep) {
-                (0x76, 0x31) => {
-                    vu_overview = Some(
-                        gen2v2::VuOverviewBlockGen2V2::parse(cursor)
-                            .context("Failed to parse VuOverviewGen2V2")?,
-                    )
+/// Reads a byte stuffed download with its stuffing removed.
+struct Unstuffed<R> {
+    reader: R,
+    unstuffing: Unstuffing,
+    /// A 0x10 that is not doubled was read, the download is not stuffed.
+    not_stuffed: bool,
+}
+impl<R> Unstuffed<R> {
+    fn new(reader: R) -> Self {
+        Unstuffed {
+            reader,
+            unstuffing: Unstuffing::default(),
+            not_stuffed: false,
+        }
+    }
+}
+impl<R: Read> Read for Unstuffed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let read = self.reader.read(buf)?;
+            let length = match read {
+                0 if !self.unstuffing.in_pair => return Ok(0),
+                0 => None,
+                _ => self.unstuffing.unstuff(&mut buf[..read]),
+            };
+            match length {
+                Some(0) => continue,
+                Some(length) => return Ok(length),
+                None => {
+                    self.not_stuffed = true;
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "0x10 that is not doubled in a byte stuffed download",
+                    ));
                 }
-                (0x76, 0x32) => vu_activities.push(
-                    gen2v2::VuActivitiesBlockGen2V2::parse(cursor)
-                        .context("Failed to parse VuActivitiesGen2V2")?,
-                ),
-                (0x76, 0x33) => vu_events_and_faults.push(
-                    gen2::VuEventsAndFaultsBlockGen2::parse(cursor)
-                        .context("Failed to parse VuEventsAndFaultsGen2")?,
-                ),
-                (0x76, 0x35) => vu_company_locks.push(
-                    gen2v2::VuCompanyLocksGen2V2::parse(cursor)
-                        .context("Failed to parse VuCompanyLocksGen2V2")?,
-                ),
-                _ => {
-                    log::warn!("Unknown block type: sid: {:02x}, trep: {:02x}", sid, trep);
-                    break;
+            }
+        }
+    }
+}
+
+/// Parses the known block for `trep` from a cursor right after its SID and TREP, `None` when the
+/// [`TrepFilter`] drops it. The overview block is always kept.
+fn read_block(
+    config: &VuParserBuilder,
+    trep: u8,
+    cursor: &mut Cursor<&[u8]>,
+) -> Result<Option<VuBlock>> {
+    let is_overview = matches!(trep, 0x01 | 0x21 | 0x31);
+    if !is_overview && !config.trep_filter.keeps(trep) {
+        VuParser::drop_block(cursor, trep)?;
+        return Ok(None);
+    }
+    VuBlock::parse(cursor, trep)
+        .context("Block without parser")?
+        .map(Some)
+}
+
+/// A block of any generation, as parsed by [`VuBlock::parse`] before it is added to the
+/// [`VuData`] of its generation.
+enum VuBlock {
+    Gen1Overview(gen1::VuOverviewBlock),
+    Gen1Activities(gen1::VuActivitiesBlock),
+    Gen1EventsAndFaults(gen1::VuEventsAndFaultsBlock),
+    Gen1DetailedSpeed(gen1::VuDetailedSpeedBlock),
+    Gen1CompanyLocks(gen1::VuCompanyLocksBlock),
+    Gen2Overview(gen2::VuOverviewBlockGen2),
+    Gen2Activities(gen2::VuActivitiesBlockGen2),
+    /// TREP 0x23 of Gen2 and 0x33 of Gen2V2, which share their layout.
+    Gen2EventsAndFaults(gen2::VuEventsAndFaultsBlockGen2),
+    Gen2DetailedSpeed(gen2::VuDetailedSpeedBlockGen2),
+    Gen2CompanyLocks(gen2::VuCompanyLocksGen2),
+    Gen2V2Overview(gen2v2::VuOverviewBlockGen2V2),
+    Gen2V2Activities(gen2v2::VuActivitiesBlockGen2V2),
+    Gen2V2CompanyLocks(gen2v2::VuCompanyLocksGen2V2),
+}
+impl VuBlock {
+    /// Parses the block for `trep` from a cursor right after its SID and TREP, `None` for a TREP
+    /// the parser does not handle. Every path that reads VU blocks goes through here.
+    fn parse(cursor: &mut Cursor<&[u8]>, trep: u8) -> Option<Result<Self>> {
+        let block = match trep {
+            0x01 => gen1::VuOverviewBlock::parse(cursor)
+                .context("Failed to parse VuOverviewBlock")
+                .map(Self::Gen1Overview),
+            0x02 => gen1::VuActivitiesBlock::parse(cursor)
+                .context("Failed to parse VuActivitiesBlock")
+                .map(Self::Gen1Activities),
+            0x03 => gen1::VuEventsAndFaultsBlock::parse(cursor)
+                .context("Failed to parse VuEventsAndFaultsBlock")
+                .map(Self::Gen1EventsAndFaults),
+            0x04 => gen1::VuDetailedSpeedBlock::parse(cursor)
+                .context("Failed to parse VuDetailedSpeedData")
+                .map(Self::Gen1DetailedSpeed),
+            0x05 => gen1::VuCompanyLocksBlock::parse(cursor)
+                .context("Failed to parse VuCompanyLocksBlock")
+                .map(Self::Gen1CompanyLocks),
+            0x21 => gen2::VuOverviewBlockGen2::parse(cursor)
+                .context("Failed to parse VuOverviewGen2")
+                .map(Self::Gen2Overview),
+            0x22 => gen2::VuActivitiesBlockGen2::parse(cursor)
+                .context("Failed to parse VuActivitiesGen2")
+                .map(Self::Gen2Activities),
+            0x23 | 0x33 => gen2::VuEventsAndFaultsBlockGen2::parse(cursor)
+                .context("Failed to parse VuEventsAndFaultsGen2")
+                .map(Self::Gen2EventsAndFaults),
+            0x24 => gen2::VuDetailedSpeedBlockGen2::parse(cursor)
+                .context("Failed to parse VuDetailedSpeed")
+                .map(Self::Gen2DetailedSpeed),
+            0x25 => gen2::VuCompanyLocksGen2::parse(cursor)
+                .context("Failed to parse VuCompanyLocksGen2")
+                .map(Self::Gen2CompanyLocks),
+            0x31 => gen2v2::VuOverviewBlockGen2V2::parse(cursor)
+                .context("Failed to parse VuOverviewGen2V2")
+                .map(Self::Gen2V2Overview),
+            0x32 => gen2v2::VuActivitiesBlockGen2V2::parse(cursor)
+                .context("Failed to parse VuActivitiesGen2V2")
+                .map(Self::Gen2V2Activities),
+            0x35 => gen2v2::VuCompanyLocksGen2V2::parse(cursor)
+                .context("Failed to parse VuCompanyLocksGen2V2")
+                .map(Self::Gen2V2CompanyLocks),
+            _ => return None,
+        };
+        Some(block)
+    }
+
+    /// Writes the activity and event records of the block into `sink`, with IDs derived from
+    /// `owner`, see [`sink::vu_owner`]. Overview blocks write nothing, see [`RecordWriter`].
+    fn write_records(&self, owner: &str, sink: &mut impl TachoSink) -> Result<()> {
+        match self {
+            VuBlock::Gen1Activities(block) => sink::write_vu_gen1_activities(block, owner, sink),
+            VuBlock::Gen1EventsAndFaults(block) => sink::write_vu_gen1_events(block, owner, sink),
+            VuBlock::Gen2Activities(block) => sink::write_vu_gen2_activities(block, owner, sink),
+            VuBlock::Gen2EventsAndFaults(block) => sink::write_vu_gen2_events(block, owner, sink),
+            VuBlock::Gen2V2Activities(block) => {
+                sink::write_vu_gen2v2_activities(block, owner, sink)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds the data of the generation of the overview block from `blocks`, which keep their
+    /// file order. A later overview block replaces an earlier one.
+    fn collect(
+        blocks: Vec<VuBlock>,
+        segments: Option<Vec<VuSegment>>,
+        diagnostics: Diagnostics,
+    ) -> Result<VuData> {
+        let mut vu_data = None;
+        let mut others = Vec::new();
+        for block in blocks {
+            match block {
+                VuBlock::Gen1Overview(vu_overview) => {
+                    vu_data = Some(VuData::Gen1(VuGen1Blocks {
+                        vu_overview,
+                        vu_activities: Vec::new(),
+                        vu_events_and_faults: Vec::new(),
+                        vu_detailed_speed: Vec::new(),
+                        vu_company_locks: Vec::new(),
+                        segments: None,
+                        diagnostics: Diagnostics::default(),
+                    }))
+                }
+                VuBlock::Gen2Overview(vu_overview) => {
+                    vu_data = Some(VuData::Gen2(VuGen2Blocks {
+                        vu_overview,
+                        vu_activities: Vec::new(),
+                        vu_events_and_faults: Vec::new(),
+                        vu_detailed_speed: Vec::new(),
+                        vu_company_locks: Vec::new(),
+                        segments: None,
+                        diagnostics: Diagnostics::default(),
+                    }))
+                }
+                VuBlock::Gen2V2Overview(vu_overview) => {
+                    vu_data = Some(VuData::Gen2V2(VuGen2V2Blocks {
+                        vu_overview,
+                        vu_activities: Vec::new(),
+                        vu_events_and_faults: Vec::new(),
+                        vu_company_locks: Vec::new(),
+                        segments: None,
+                        diagnostics: Diagnostics::default(),
+                    }))
                 }
+                block => others.push(block),
             }
         }
-        Ok(VuData::Gen2V2(VuGen2V2Blocks {
-            vu_overview: vu_overview
-                .context("unable to find VuOverviewBlock after parsing file")?,
-            vu_activities,
-            vu_events_and_faults,
-            vu_company_locks,
-        }))
+        let mut vu_data = vu_data.context("unable to find VuOverviewBlock after parsing file")?;
+        for block in others {
+            match (&mut vu_data, block) {
+                (VuData::Gen1(blocks), VuBlock::Gen1Activities(block)) => {
+                    blocks.vu_activities.push(block)
+                }
+                (VuData::Gen1(blocks), VuBlock::Gen1EventsAndFaults(block)) => {
+                    blocks.vu_events_and_faults.push(block)
+                }
+                (VuData::Gen1(blocks), VuBlock::Gen1DetailedSpeed(block)) => {
+                    blocks.vu_detailed_speed.push(block)
+                }
+                (VuData::Gen1(blocks), VuBlock::Gen1CompanyLocks(block)) => {
+                    blocks.vu_company_locks.push(block)
+                }
+                (VuData::Gen2(blocks), VuBlock::Gen2Activities(block)) => {
+                    blocks.vu_activities.push(block)
+                }
+                (VuData::Gen2(blocks), VuBlock::Gen2EventsAndFaults(block)) => {
+                    blocks.vu_events_and_faults.push(block)
+                }
+                (VuData::Gen2(blocks), VuBlock::Gen2DetailedSpeed(block)) => {
+                    blocks.vu_detailed_speed.push(block)
+                }
+                (VuData::Gen2(blocks), VuBlock::Gen2CompanyLocks(block)) => {
+                    blocks.vu_company_locks.push(block)
+                }
+                (VuData::Gen2V2(blocks), VuBlock::Gen2V2Activities(block)) => {
+                    blocks.vu_activities.push(block)
+                }
+                (VuData::Gen2V2(blocks), VuBlock::Gen2EventsAndFaults(block)) => {
+                    blocks.vu_events_and_faults.push(block)
+                }
+                (VuData::Gen2V2(blocks), VuBlock::Gen2V2CompanyLocks(block)) => {
+                    blocks.vu_company_locks.push(block)
+                }
+                _ => anyhow::bail!("Block is not of the generation of the VuOverview block"),
+            }
+        }
+        match &mut vu_data {
+            VuData::Gen1(blocks) => (blocks.segments, blocks.diagnostics) = (segments, diagnostics),
+            VuData::Gen2(blocks) => (blocks.segments, blocks.diagnostics) = (segments, diagnostics),
+            VuData::Gen2V2(blocks) => {
+                (blocks.segments, blocks.diagnostics) = (segments, diagnostics)
+            }
+        }
+        Ok(vu_data)
     }
+}
 
-    pub fn parse_to_json(&self) -> Result<String> {
-        let vu_data = self.parse().context("Failed to parse vehicle data")?;
-        let pretty_json = serde_json::to_string_pretty(&vu_data)
-            .context("Failed to convert serde value to pretty JSON string")?;
-        Ok(pretty_json)
+/// Writes the records of VU blocks into a [`TachoSink`] as they are parsed. Blocks parsed before
+/// the overview block are held back until it comes, as the IDs of their records derive from the
+/// vehicle it identifies.
+struct RecordWriter<'a, S> {
+    sink: &'a mut S,
+    /// Vehicle of the overview block, once it was written.
+    owner: Option<String>,
+    pending: Vec<VuBlock>,
+}
+impl<'a, S: TachoSink> RecordWriter<'a, S> {
+    fn new(sink: &'a mut S) -> Self {
+        RecordWriter {
+            sink,
+            owner: None,
+            pending: Vec::new(),
+        }
     }
-    pub fn parse_to_json_pretty(&self) -> Result<String> {
-        let vu_data = self.parse().context("Failed to parse vehicle data")?;
-        let pretty_json = serde_json::to_string_pretty(&vu_data)
-            .context("Failed to convert serde value to pretty JSON string")?;
-        Ok(pretty_json)
+
+    /// Writes the records of `block`. Only the first overview block is written.
+    fn write(&mut self, block: VuBlock) -> Result<()> {
+        if let Some(owner) = &self.owner {
+            return block.write_records(owner, self.sink);
+        }
+        let identification = match &block {
+            VuBlock::Gen1Overview(overview) => sink::vu_gen1_identification(overview),
+            VuBlock::Gen2Overview(overview) => sink::vu_gen2_identification(overview),
+            VuBlock::Gen2V2Overview(overview) => sink::vu_gen2v2_identification(overview),
+            _ => {
+                self.pending.push(block);
+                return Ok(());
+            }
+        };
+        let owner = sink::vu_owner(&identification);
+        self.sink.write_identification(&identification)?;
+        for block in std::mem::take(&mut self.pending) {
+            block.write_records(&owner, self.sink)?;
+        }
+        self.owner = Some(owner);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.owner.is_none() {
+            anyhow::bail!("unable to find VuOverviewBlock after parsing file");
+        }
+        self.sink.finish()
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "status")]
+#[cfg_attr(feature = "ts", derive(TS))]
+pub enum BlockParseStatus {
+    /// The block was parsed.
+    Parsed,
+    /// The parser returned an error for this block. The walk continues at the next known block
+    /// marker, as the length of the block is unknown.
+    Failed { error: String },
+    /// The SID or TREP is not handled by the parser, the block was skipped up to the next known
+    /// block marker.
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(TS))]
+/// One block found in a VU file, see [`VuParser::block_coverage`].
+pub struct BlockCoverage {
+    /// Offset of the SID from the start of the file.
+    pub offset: usize,
+    pub sid: u8,
+    /// `None` when the file ends right after the SID.
+    pub trep: Option<u8>,
+    /// Bytes up to the next block, SID and TREP included.
+    pub length: usize,
+    pub status: BlockParseStatus,
+}
+
+/// Walks the blocks of a VU file the same way `parse` does, but drops every block right after
+/// parsing it. Unlike `parse`, a failing block does not stop the walk: it continues at the next
+/// known block marker.
+pub(crate) fn block_coverage(input: &[u8]) -> Result<Vec<BlockCoverage>> {
+    let known_treps =
+        VuParser::known_treps(input).context("File does not start with a VuOverview block")?;
+    let mut coverage = Vec::new();
+    let mut diagnostics = Diagnostics::default();
+    let mut cursor = Cursor::new(input);
+    while (cursor.position() as usize) < input.len() {
+        VuParser::skip_response_header(&mut cursor, &mut diagnostics);
+        let offset = cursor.position() as usize;
+        let (Ok(sid), Ok(trep)) = (cursor.read_u8(), cursor.read_u8()) else {
+            coverage.push(BlockCoverage {
+                offset,
+                sid: input[offset],
+                trep: None,
+                length: input.len() - offset,
+                status: BlockParseStatus::Failed {
+                    error: "File ends in the middle of a block header".to_string(),
+                },
+            });
+            break;
+        };
+        let parsed = if sid == 0x76 && known_treps.contains(&trep) {
+            VuBlock::parse(&mut cursor, trep)
+        } else {
+            None
+        };
+        let status = match parsed {
+            Some(Ok(_)) => BlockParseStatus::Parsed,
+            Some(Err(e)) => {
+                let next_block = input[offset + 2..]
+                    .windows(2)
+                    .position(|marker| marker[0] == 0x76 && known_treps.contains(&marker[1]))
+                    .map_or(input.len(), |position| offset + 2 + position);
+                cursor.set_position(next_block as u64);
+                BlockParseStatus::Failed {
+                    error: format!("{:#}", e),
+                }
+            }
+            None => {
+                VuParser::skip_unknown_block(&mut cursor, sid, trep, known_treps, &mut diagnostics);
+                BlockParseStatus::Unknown
+            }
+        };
+        coverage.push(BlockCoverage {
+            offset,
+            sid,
+            trep: Some(trep),
+            length: cursor.position() as usize - offset,
+            status,
+        });
+    }
+    Ok(coverage)
+}
+
+/// Reads only the overview block at the start of a VU file, which holds the vehicle
+/// identification, without looking at the blocks after it.
+pub fn peek_vu_identification(bytes: &[u8]) -> Result<sink::IdentificationRecord> {
+    let mut cursor = Cursor::new(&bytes[VuParser::response_header_length(bytes)..]);
+    let sid = cursor.read_u8().context("Failed to read sid")?;
+    let trep = cursor.read_u8().context("Failed to read trep")?;
+    let identification = match (sid, trep) {
+        (0x76, 0x01) => {
+            let overview = gen1::VuOverviewBlock::parse(&mut cursor)
+                .context("Failed to parse VuOverviewBlock")?;
+            sink::vu_identification_record(
+                Some(&overview.vehicle_identification_number),
+                Some(
+                    &overview
+                        .vehicle_registration_identification
+                        .vehicle_registration_number
+                        .vehicle_reg_number,
+                ),
+            )
+        }
+        (0x76, 0x21) => {
+            let overview = gen2::VuOverviewBlockGen2::parse(&mut cursor)
+                .context("Failed to parse VuOverviewBlockGen2")?;
+            sink::vu_identification_record(
+                overview.vehicle_identification_number_record_array.first(),
+                overview
+                    .vehicle_registration_number_record_array
+                    .first()
+                    .map(|vrn| &vrn.vehicle_reg_number),
+            )
+        }
+        (0x76, 0x31) => {
+            let overview = gen2v2::VuOverviewBlockGen2V2::parse(&mut cursor)
+                .context("Failed to parse VuOverviewBlockGen2V2")?;
+            sink::vu_identification_record(
+                overview.vehicle_identification_number_record_array.first(),
+                overview
+                    .vehicle_registration_identification_record_array
+                    .first()
+                    .map(|vri| &vri.vehicle_registration_number.vehicle_reg_number),
+            )
+        }
+        _ => anyhow::bail!(
+            "File does not start with a VuOverview block: sid {:#04x} trep {:#04x}",
+            sid,
+            trep
+        ),
+    };
+    Ok(identification)
+}
+
 #[cfg(test)]
 mod tests {
     use rayon::prelude::*;
@@ -272,6 +1786,660 @@ mod tests {
     use std::fs;
     use std::path::Path;
 
+    #[test]
+    fn test_skip_unknown_block_resyncs_at_next_known_trep() {
+        let data: &[u8] = &[0x76, 0x99, 0xAA, 0x76, 0x0F, 0x76, 0x02, 0x00];
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(2);
+        let mut diagnostics = Diagnostics::default();
+
+        VuParser::skip_unknown_block(
+            &mut cursor,
+            0x76,
+            0x99,
+            &VuParser::GEN1_TREPS,
+            &mut diagnostics,
+        );
+
+        assert_eq!(cursor.position(), 5);
+        let unknown = &diagnostics.unknown_blocks[0];
+        assert_eq!((unknown.offset, unknown.skipped_bytes), (0, 5));
+        assert!(unknown.resynchronized);
+    }
+
+    #[test]
+    fn test_small_record_array_matches_record_array() {
+        // record type VehicleIdentificationNumber, record size 17, 1 record
+        let mut input = vec![0x0A, 0x00, 0x11, 0x00, 0x01];
+        input.extend(b"WDB9634031L123456");
+
+        let vins = gen2::RecordArray::parse_small(
+            &mut Cursor::new(input.as_slice()),
+            crate::dt::VehicleIdentificationNumber::parse,
+        )
+        .unwrap();
+        let expected = gen2::RecordArray::parse(
+            &mut Cursor::new(input.as_slice()),
+            crate::dt::VehicleIdentificationNumber::parse,
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(vins.len(), 1);
+        assert_eq!(
+            serde_json::to_string(&vins).unwrap(),
+            serde_json::to_string(&expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bytes_unaccounted() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_unknown_block.ddd");
+        let vu_data = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+        let diagnostics = vu_data.diagnostics();
+        assert_eq!(diagnostics.total_bytes, input.len());
+        assert_eq!(vu_data.bytes_unaccounted(), 18);
+        assert_eq!(
+            vu_data.bytes_unaccounted(),
+            diagnostics.unknown_blocks[0].skipped_bytes
+        );
+    }
+
+    #[test]
+    fn test_lenient_parse_drops_truncated_final_block() {
+        let overview: &[u8] = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let mut input = overview.to_vec();
+        input.extend_from_slice(&overview[..100]);
+
+        assert!(VuParser::new_from_bytes(&input).unwrap().parse().is_err());
+        let vu_data = VuParser::new_from_bytes(&input)
+            .unwrap()
+            .lenient(true)
+            .parse()
+            .unwrap();
+        let truncated = vu_data.diagnostics().truncated_block.clone().unwrap();
+        assert_eq!(truncated.offset, overview.len());
+        assert_eq!(truncated.header, vec![0x76, 0x01]);
+        assert_eq!(truncated.available_bytes, 100);
+        assert_eq!(truncated.missing_bytes, None);
+        assert_eq!(vu_data.bytes_unaccounted(), 100);
+
+        // a lone trailing byte
+        input.truncate(overview.len() + 1);
+        let vu_data = VuParser::new_from_bytes(&input)
+            .unwrap()
+            .lenient(true)
+            .parse()
+            .unwrap();
+        let truncated = vu_data.diagnostics().truncated_block.clone().unwrap();
+        assert_eq!(
+            (truncated.header, truncated.available_bytes),
+            (vec![0x76], 1)
+        );
+    }
+
+    #[test]
+    fn test_lenient_parse_of_every_truncation() {
+        for fixture in [
+            &include_bytes!("../tests/fixtures/vu_gen1_overview.ddd")[..],
+            &include_bytes!("../tests/fixtures/vu_gen1_locks_and_controls.ddd")[..],
+        ] {
+            // a complete download followed by one aborted at every byte
+            for length in 1..fixture.len() {
+                let mut input = fixture.to_vec();
+                input.extend_from_slice(&fixture[..length]);
+                let vu_data = VuParser::new_from_bytes(&input)
+                    .unwrap()
+                    .lenient(true)
+                    .parse()
+                    .unwrap();
+                assert!(vu_data.diagnostics().truncated_block.is_some());
+            }
+            // the only download aborted at every byte fails or parses, without panicking
+            for length in 0..fixture.len() {
+                let _ = VuParser::new_from_bytes(&fixture[..length])
+                    .and_then(|parser| parser.lenient(true).parse());
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_from_reader() {
+        for input in [
+            &include_bytes!("../tests/fixtures/vu_gen1_overview.ddd")[..],
+            &include_bytes!("../tests/fixtures/vu_gen1_unknown_block.ddd")[..],
+            &include_bytes!("../tests/fixtures/vu_gen1_locks_and_controls.ddd")[..],
+        ] {
+            let expected = VuParser::new_from_bytes(input)
+                .unwrap()
+                .keep_segments(true)
+                .parse()
+                .unwrap();
+            let vu_data = VuParser::new_from_reader(Cursor::new(input))
+                .unwrap()
+                .keep_segments(true)
+                .parse()
+                .unwrap();
+            assert_eq!(vu_data, expected);
+        }
+
+        // starts at the reader's position, e.g. after a header of the download tool
+        let overview: &[u8] = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let mut input = vec![0xAB; 4];
+        input.extend_from_slice(overview);
+        let mut reader = Cursor::new(input.as_slice());
+        reader.set_position(4);
+        let vu_data = VuParser::new_from_reader(reader).unwrap().parse().unwrap();
+        assert_eq!(vu_data.diagnostics().total_bytes, overview.len());
+
+        let mut input = overview.to_vec();
+        input.extend_from_slice(&overview[..100]);
+        assert!(VuParser::new_from_reader(Cursor::new(input.as_slice()))
+            .unwrap()
+            .parse()
+            .is_err());
+        let vu_data = VuParser::new_from_reader(Cursor::new(input.as_slice()))
+            .unwrap()
+            .lenient(true)
+            .parse()
+            .unwrap();
+        let expected = VuParser::new_from_bytes(&input)
+            .unwrap()
+            .lenient(true)
+            .parse()
+            .unwrap();
+        assert_eq!(vu_data, expected);
+
+        assert!(VuParser::builder()
+            .max_input_bytes(100)
+            .from_reader(Cursor::new(overview))
+            .is_err());
+        assert!(VuParser::builder()
+            .validation(ValidationLevel::Strict)
+            .from_reader(Cursor::new(overview))
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_with_response_headers() {
+        for input in [
+            &include_bytes!("../tests/fixtures/vu_gen1_overview.ddd")[..],
+            &include_bytes!("../tests/fixtures/vu_gen1_locks_and_controls.ddd")[..],
+        ] {
+            let mut wrapped = Vec::new();
+            for range in VuParser::block_ranges(input).unwrap() {
+                let length = (range.len() as u8).wrapping_sub(2);
+                wrapped.extend_from_slice(&[0x80, 0xF0, 0xEE, length]);
+                wrapped.extend_from_slice(&input[range]);
+            }
+            assert_eq!(
+                crate::detector::detect_from_bytes(&wrapped).unwrap(),
+                TachoFileType::VehicleUnitGen1
+            );
+
+            let expected = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+            for mut vu_data in [
+                VuParser::new_from_bytes(&wrapped).unwrap().parse().unwrap(),
+                VuParser::new_from_reader(Cursor::new(wrapped.as_slice()))
+                    .unwrap()
+                    .parse()
+                    .unwrap(),
+            ] {
+                let VuData::Gen1(blocks) = &mut vu_data else {
+                    unreachable!()
+                };
+                assert!(blocks.diagnostics.response_headers);
+                assert_eq!(blocks.diagnostics.total_bytes, wrapped.len());
+                assert_eq!(blocks.diagnostics.recognized_bytes, wrapped.len());
+                blocks.diagnostics = expected.diagnostics().clone();
+                assert_eq!(vu_data, expected);
+            }
+            assert!(block_coverage(&wrapped)
+                .unwrap()
+                .iter()
+                .all(|block| block.status == BlockParseStatus::Parsed));
+        }
+    }
+
+    #[test]
+    fn test_parse_byte_stuffed() {
+        let mut input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd").to_vec();
+        // the fixture has no 0x10, put some into the signature at the end of the overview
+        let length = input.len();
+        input[length - 1] = 0x10;
+        input[length - 3] = 0x10;
+        input[length - 4] = 0x10;
+        let stuffed: Vec<u8> = input
+            .iter()
+            .flat_map(|&byte| match byte {
+                0x10 => vec![0x10, 0x10],
+                byte => vec![byte],
+            })
+            .collect();
+        assert_eq!(stuffed.len(), input.len() + 3);
+
+        let expected = VuParser::new_from_bytes(&input).unwrap().parse().unwrap();
+        assert!(!expected.diagnostics().byte_stuffing);
+        for mut vu_data in [
+            VuParser::new_from_bytes(&stuffed).unwrap().parse().unwrap(),
+            VuParser::new_from_reader(Cursor::new(stuffed.as_slice()))
+                .unwrap()
+                .parse()
+                .unwrap(),
+        ] {
+            let VuData::Gen1(blocks) = &mut vu_data else {
+                unreachable!()
+            };
+            assert!(blocks.diagnostics.byte_stuffing);
+            blocks.diagnostics.byte_stuffing = false;
+            assert_eq!(vu_data, expected);
+        }
+        let vu_data = VuParser::new_from_reader(Cursor::new(input.as_slice()))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(vu_data, expected);
+
+        // the provenance is of the file as it was read
+        let options = JsonOptions {
+            provenance: true,
+            ..JsonOptions::default()
+        };
+        let json = VuParser::new_from_bytes(&stuffed)
+            .unwrap()
+            .parse_to_json_with_options(&options)
+            .unwrap();
+        let provenance: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            provenance["provenance"]["sourceSha256"],
+            json::Provenance::new(&stuffed).source_sha256
+        );
+        assert_eq!(provenance["provenance"]["sourceBytes"], stuffed.len());
+
+        // a single 0x10 cannot come from stuffing, the file is parsed as it is
+        assert!(VuParser::unstuff(&input).is_none());
+    }
+
+    #[test]
+    fn test_stream_stops_reading_at_malformed_block() {
+        /// Counts the bytes read since the last seek.
+        struct CountingReader<'a> {
+            inner: Cursor<&'a [u8]>,
+            read: &'a Cell<usize>,
+        }
+        impl Read for CountingReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let read = self.inner.read(buf)?;
+                self.read.set(self.read.get() + read);
+                Ok(read)
+            }
+        }
+        impl Seek for CountingReader<'_> {
+            fn seek(&mut self, position: SeekFrom) -> std::io::Result<u64> {
+                self.read.set(0);
+                self.inner.seek(position)
+            }
+        }
+        use std::cell::Cell;
+
+        let overview = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        // a detailed speed block claiming 0xFFFF minutes of speed data, followed by an events and
+        // faults block with only its over speeding control data, and bytes that all parse as speed
+        // data up to the end of the file
+        let mut input = overview.to_vec();
+        input.extend_from_slice(&[0x76, 0x04, 0xFF, 0xFF]);
+        input.extend_from_slice(&[0x76, 0x03, 0x00, 0x00]);
+        input.extend_from_slice(&0x65E1_8000u32.to_be_bytes());
+        input.extend_from_slice(&0x65E1_8E10u32.to_be_bytes());
+        input.extend_from_slice(&[0x00; 3]);
+        input.extend_from_slice(&[0x01; 128 + 20_000]);
+        for lenient in [false, true] {
+            let read = Cell::new(0);
+            let reader = CountingReader {
+                inner: Cursor::new(&input),
+                read: &read,
+            };
+            let parsed = VuParser::new_from_reader(reader)
+                .unwrap()
+                .lenient(lenient)
+                .parse();
+            assert!(parsed.is_err());
+            assert!(read.get() < 4096, "read {} bytes", read.get());
+        }
+    }
+
+    #[test]
+    fn test_export_segments() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_unknown_block.ddd");
+        let vu_data = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+        assert!(vu_data.segments().is_none());
+        assert!(vu_data.export_segments(input, |_| true).is_err());
+
+        let vu_data = VuParser::new_from_bytes(input)
+            .unwrap()
+            .keep_segments(true)
+            .parse()
+            .unwrap();
+        let segments = vu_data.segments().unwrap();
+        let ranges: Vec<Range<usize>> = segments.iter().map(VuSegment::range).collect();
+        assert_eq!(ranges, VuParser::block_ranges(input).unwrap());
+        assert_eq!((segments[0].trep, segments[0].index), (0x01, 0));
+
+        // the overview is kept even when filtered out, the unknown block is dropped
+        let exported = vu_data.export_segments(input, |_| false).unwrap();
+        assert_eq!(exported.len(), input.len() - vu_data.bytes_unaccounted());
+        let reparsed = VuParser::new_from_bytes(&exported)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(reparsed.bytes_unaccounted(), 0);
+        assert_eq!(
+            serde_json::to_value(&reparsed).unwrap()["vuOverview"],
+            serde_json::to_value(&vu_data).unwrap()["vuOverview"]
+        );
+    }
+
+    #[test]
+    fn test_skip_signatures() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let parse = |skip_signatures| {
+            VuParser::builder()
+                .skip_signatures(skip_signatures)
+                .from_bytes(input)
+                .unwrap()
+                .parse()
+                .unwrap()
+        };
+        let (VuData::Gen1(read), VuData::Gen1(skipped)) = (parse(false), parse(true)) else {
+            unreachable!();
+        };
+        assert!(skipped.vu_overview.signature.0.is_empty());
+        assert!(skipped.vu_overview.vu_certificate.0.is_empty());
+        assert!(skipped.diagnostics.signatures_skipped);
+        assert!(!read.vu_overview.signature.0.is_empty());
+        assert!(!read.diagnostics.signatures_skipped);
+        assert_eq!(
+            skipped.vu_overview.vehicle_identification_number,
+            read.vu_overview.vehicle_identification_number
+        );
+
+        let streamed = VuParser::builder()
+            .skip_signatures(true)
+            .from_reader(Cursor::new(&input[..]))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(streamed.diagnostics().signatures_skipped);
+    }
+
+    #[test]
+    fn test_builder() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let vu_data = VuParser::builder()
+            .skip_treps(&[0x01])
+            .from_bytes(input)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(matches!(vu_data, VuData::Gen1(_)));
+
+        let builder = VuParser::builder().max_input_bytes(input.len() - 1);
+        assert!(builder.from_bytes(input).is_err());
+        let parser = VuParser::builder().max_blocks(0).from_bytes(input).unwrap();
+        assert!(parser.parse().is_err());
+        let parser = VuParser::builder()
+            .generation(Generation::Gen2)
+            .from_bytes(input)
+            .unwrap();
+        assert!(parser.parse().is_err());
+
+        let raw = VuParser::builder()
+            .string_policy(StringPolicy::Raw)
+            .from_bytes(input)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let (VuData::Gen1(clean), VuData::Gen1(raw)) = (&vu_data, &raw) else {
+            unreachable!();
+        };
+        let vrn = |blocks: &VuGen1Blocks| {
+            blocks
+                .vu_overview
+                .vehicle_registration_identification
+                .vehicle_registration_number
+                .vehicle_reg_number
+                .0
+                .clone()
+        };
+        assert_ne!(vrn(raw), vrn(clean));
+        assert_eq!(vrn(raw).trim(), vrn(clean));
+
+        // only strict validation fails on the truncated final block
+        let mut truncated = input.to_vec();
+        truncated.extend_from_slice(&input[..100]);
+        let parse = |validation| {
+            VuParser::builder()
+                .validation(validation)
+                .lenient(true)
+                .from_bytes(&truncated)
+                .unwrap()
+                .parse()
+        };
+        assert!(parse(ValidationLevel::Warn).is_ok());
+        assert!(parse(ValidationLevel::Strict).is_err());
+    }
+
+    #[test]
+    fn test_parsed_data_compares_equal() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let vu_data = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+        assert_eq!(
+            vu_data,
+            VuParser::new_from_bytes(input).unwrap().parse().unwrap()
+        );
+        let VuData::Gen1(mut blocks) = vu_data.clone() else {
+            unreachable!();
+        };
+        blocks
+            .vu_overview
+            .vu_downloadable_period
+            .max_downloadable_time
+            .0 += chrono::Duration::days(1);
+        assert_ne!(VuData::Gen1(blocks), vu_data);
+    }
+
+    #[test]
+    fn test_peek_vu_identification() {
+        let input = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        let VuData::Gen1(blocks) = VuParser::new_from_bytes(input).unwrap().parse().unwrap() else {
+            unreachable!();
+        };
+        let record = peek_vu_identification(input).unwrap();
+        assert_eq!(
+            record.vehicle_identification_number.as_ref(),
+            Some(&blocks.vu_overview.vehicle_identification_number.0 .0)
+        );
+        assert!(record.vehicle_registration_number.is_some());
+        assert_eq!(record.card_number, None);
+
+        // only the overview block is read, a broken block after it does not matter
+        let mut with_garbage = input.to_vec();
+        with_garbage.extend_from_slice(&[0x76, 0x02, 0xFF]);
+        assert!(peek_vu_identification(&with_garbage).is_ok());
+        assert!(peek_vu_identification(&input[2..]).is_err());
+    }
+
+    #[test]
+    fn test_gen2v2_overview_registration_identification() {
+        let record_array = |record_type: u8, record_size: u16, records: &[&[u8]]| {
+            let mut bytes = vec![record_type];
+            bytes.extend_from_slice(&record_size.to_be_bytes());
+            bytes.extend_from_slice(&(records.len() as u16).to_be_bytes());
+            for record in records {
+                bytes.extend_from_slice(record);
+            }
+            bytes
+        };
+        // nation, code page and the 13 characters of the registration number
+        let mut registration = vec![0x0D, 0x01];
+        registration.extend_from_slice(b"B AB 1234    ");
+        let mut downloadable_period = 0x65E1_1A80u32.to_be_bytes().to_vec();
+        downloadable_period.extend_from_slice(&0x65E2_6C00u32.to_be_bytes());
+
+        let mut input = vec![0x76, 0x31];
+        input.extend(record_array(0x15, 1, &[]));
+        input.extend(record_array(0x16, 1, &[]));
+        input.extend(record_array(0x0A, 17, &[b"WDB9634031L123456"]));
+        input.extend(record_array(0x24, 15, &[&registration]));
+        input.extend(record_array(0x0C, 4, &[&0x65E2_6C00u32.to_be_bytes()]));
+        input.extend(record_array(0x11, 8, &[&downloadable_period]));
+        input.extend(record_array(0x02, 1, &[&[0x00]]));
+        input.extend(record_array(0x14, 1, &[]));
+        input.extend(record_array(0x10, 1, &[]));
+        input.extend(record_array(0x07, 1, &[]));
+        input.extend(record_array(0x08, 64, &[&[0xAB; 64]]));
+
+        let VuData::Gen2V2(blocks) = VuParser::new_from_bytes(&input).unwrap().parse().unwrap()
+        else {
+            unreachable!();
+        };
+        let identification = &blocks
+            .vu_overview
+            .vehicle_registration_identification_record_array[0];
+        assert_eq!(identification.vehicle_registration_nation.0, "Germany");
+        assert_eq!(
+            identification
+                .vehicle_registration_number
+                .vehicle_reg_number
+                .0,
+            "B AB 1234"
+        );
+        let record = peek_vu_identification(&input).unwrap();
+        assert_eq!(
+            record.vehicle_identification_number.as_deref(),
+            Some("WDB9634031L123456")
+        );
+        assert_eq!(
+            record.vehicle_registration_number.as_deref(),
+            Some("B AB 1234")
+        );
+    }
+
+    #[test]
+    fn test_merge_parts() {
+        let overview = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        // an events and faults block holding a single power supply interruption
+        let mut with_events = overview.to_vec();
+        with_events.extend_from_slice(&[0x76, 0x03, 0x00, 0x01, 0x08, 0x00]);
+        with_events.extend_from_slice(&0x65E1_8000u32.to_be_bytes());
+        with_events.extend_from_slice(&0x65E1_8E10u32.to_be_bytes());
+        with_events.extend_from_slice(&[0xFF; 4 * 18]);
+        with_events.push(1);
+        with_events.extend_from_slice(&[0x00; 11 + 128]);
+        let parse = |input: &[u8]| {
+            VuParser::new_from_bytes(input)
+                .unwrap()
+                .keep_segments(true)
+                .parse()
+                .unwrap()
+        };
+
+        let merged = VuData::merge(vec![
+            parse(overview),
+            parse(&with_events),
+            parse(&with_events),
+        ])
+        .unwrap();
+        let VuData::Gen1(blocks) = &merged else {
+            unreachable!();
+        };
+        assert_eq!(blocks.vu_events_and_faults.len(), 1);
+        assert_eq!(blocks.segments, None);
+        assert_eq!(
+            merged.diagnostics().total_bytes,
+            overview.len() + 2 * with_events.len()
+        );
+
+        let VuData::Gen1(mut other_vehicle) = parse(overview) else {
+            unreachable!();
+        };
+        other_vehicle.vu_overview.vehicle_identification_number.0 .0 = "OTHER".to_string();
+        let error = VuData::merge(vec![parse(overview), VuData::Gen1(other_vehicle)]).unwrap_err();
+        assert!(error.to_string().contains("another vehicle"));
+        assert!(VuData::merge(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_parse_to_sink_writes_blocks_as_parsed() {
+        use crate::sink::NdjsonSink;
+
+        let overview = include_bytes!("../tests/fixtures/vu_gen1_overview.ddd");
+        // a single power supply interruption, as in test_merge_parts
+        let mut events = vec![0x76, 0x03, 0x00, 0x01, 0x08, 0x00];
+        events.extend_from_slice(&0x65E1_8000u32.to_be_bytes());
+        events.extend_from_slice(&0x65E1_8E10u32.to_be_bytes());
+        events.extend_from_slice(&[0xFF; 4 * 18]);
+        events.push(1);
+        events.extend_from_slice(&[0x00; 11 + 128]);
+        let expected = |input: &[u8]| {
+            let vu_data = VuParser::new_from_bytes(input).unwrap().parse().unwrap();
+            let mut sink = NdjsonSink::new(Vec::new());
+            sink::write_vu_data(&vu_data, &mut sink).unwrap();
+            sink.into_inner()
+        };
+        let from_bytes = |input: &[u8]| {
+            let mut sink = NdjsonSink::new(Vec::new());
+            let parsed = VuParser::new_from_bytes(input)
+                .unwrap()
+                .parse_to_sink(&mut sink);
+            (parsed, sink.into_inner())
+        };
+        let from_reader = |input: &[u8]| {
+            let mut sink = NdjsonSink::new(Vec::new());
+            let parsed = VuParser::new_from_reader(Cursor::new(input))
+                .unwrap()
+                .parse_to_sink(&mut sink);
+            (parsed, sink.into_inner())
+        };
+
+        // the events block is held back until the overview block identifies the vehicle
+        let overview_first = [&overview[..], &events].concat();
+        let events_first = [&events[..], &overview[..]].concat();
+        for input in [overview_first.clone(), events_first] {
+            for parse in [from_bytes, from_reader] {
+                let (parsed, written) = parse(&input);
+                parsed.unwrap();
+                assert_eq!(written, expected(&overview_first));
+            }
+        }
+
+        // records of the blocks before a block cut short are written by the time the parse fails
+        let mut truncated = overview_first.clone();
+        truncated.extend_from_slice(&[0x76, 0x03, 0x00, 0x01]);
+        for parse in [from_bytes, from_reader] {
+            let (parsed, written) = parse(&truncated);
+            assert!(parsed.is_err());
+            assert_eq!(written, expected(&overview_first));
+        }
+
+        let mut sink = NdjsonSink::new(Vec::new());
+        let error = VuParser::new_from_bytes(&events)
+            .unwrap()
+            .parse_to_sink(&mut sink)
+            .unwrap_err();
+        assert!(error.to_string().contains("VuOverviewBlock"));
+        assert!(sink.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_record_arrays_missing_bytes() {
+        // 3 records of 2 bytes, then an array of 2 records of 4 bytes cut after 3 bytes
+        let mut data = vec![0x01, 0x00, 0x02, 0x00, 0x03, 1, 2, 3, 4, 5, 6];
+        data.extend_from_slice(&[0x02, 0x00, 0x04, 0x00, 0x02, 1, 2, 3]);
+        assert_eq!(gen2::record_arrays_missing_bytes(&data), 5);
+        // cut inside the header of the second array
+        assert_eq!(gen2::record_arrays_missing_bytes(&data[..13]), 3);
+    }
+
     #[test]
     fn test_process_vu_file() {
         let data_dir = Path::new("../../data/ddd");
@@ -0,0 +1,120 @@
+//! Property tests for the bit-level codecs: decoding a value and encoding it again must give back
+//! the original bytes, so that encoders never drift from the parser.
+use proptest::prelude::*;
+use std::io::Cursor;
+use tachograph_parser::dt::gen2::GeoCoordinatesGen2;
+use tachograph_parser::dt::{
+    ActivityChangeInfoCardActivity, ActivityChangeInfoCardStatus, ActivityChangeInfoSlot,
+    ActivityChangeInfoStatus, BCDString, CardActivityChangeInfo, CardSlotStatus, CardSlotsStatus,
+    Coded,
+};
+use tachograph_parser::units::Minutes;
+
+fn card_slot_status() -> impl Strategy<Value = CardSlotStatus> {
+    prop_oneof![
+        Just(CardSlotStatus::NoCardInserted),
+        Just(CardSlotStatus::DriverCardInserted),
+        Just(CardSlotStatus::WorkshopCardInserted),
+        Just(CardSlotStatus::ControlCardInserted),
+        Just(CardSlotStatus::CompanyCardInserted),
+    ]
+}
+
+/// Change infos whose driving status matches their card status, the only ones `encode` keeps.
+fn card_activity_change_info() -> impl Strategy<Value = CardActivityChangeInfo> {
+    let slot = prop_oneof![
+        Just(ActivityChangeInfoSlot::Driver),
+        Just(ActivityChangeInfoSlot::CoDriver),
+    ];
+    let status = prop_oneof![
+        Just((
+            ActivityChangeInfoCardStatus::Inserted,
+            ActivityChangeInfoStatus::Single
+        )),
+        Just((
+            ActivityChangeInfoCardStatus::Inserted,
+            ActivityChangeInfoStatus::Crew
+        )),
+        Just((
+            ActivityChangeInfoCardStatus::NotInserted,
+            ActivityChangeInfoStatus::Unknown
+        )),
+        Just((
+            ActivityChangeInfoCardStatus::NotInserted,
+            ActivityChangeInfoStatus::Known
+        )),
+    ];
+    let activity = prop_oneof![
+        Just(ActivityChangeInfoCardActivity::BreakRest),
+        Just(ActivityChangeInfoCardActivity::Availability),
+        Just(ActivityChangeInfoCardActivity::Work),
+        Just(ActivityChangeInfoCardActivity::Driving),
+    ];
+    (slot, status, activity, 0..0x0800u16).prop_map(
+        |(slot, (card_status, driving_or_following_activity_status), activity, minutes)| {
+            CardActivityChangeInfo {
+                slot,
+                driving_or_following_activity_status,
+                card_status,
+                activity,
+                minutes: Minutes(minutes),
+            }
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn activity_change_info_word_round_trips(word: u16) {
+        let change_info =
+            CardActivityChangeInfo::parse(&mut Cursor::new(&word.to_be_bytes()[..])).unwrap();
+        prop_assert_eq!(change_info.encode(), word);
+    }
+
+    #[test]
+    fn activity_change_info_round_trips(change_info in card_activity_change_info()) {
+        let word = change_info.encode().to_be_bytes();
+        let decoded = CardActivityChangeInfo::parse(&mut Cursor::new(&word[..])).unwrap();
+        prop_assert_eq!(decoded, change_info);
+    }
+
+    #[test]
+    fn card_slots_status_round_trips(codriver in card_slot_status(), driver in card_slot_status()) {
+        let status = CardSlotsStatus {
+            codriver: Coded::Known(codriver),
+            driver: Coded::Known(driver),
+        };
+        let byte = [status.encode()];
+        let decoded = CardSlotsStatus::parse(&mut Cursor::new(&byte[..])).unwrap();
+        prop_assert_eq!(decoded, status);
+    }
+
+    #[test]
+    fn card_slots_status_byte_round_trips(byte: u8) {
+        // unknown slot codes are kept as read, so every byte encodes back to itself
+        let status = CardSlotsStatus::parse(&mut Cursor::new(&[byte][..])).unwrap();
+        prop_assert_eq!(status.encode(), byte);
+    }
+
+    #[test]
+    fn geo_coordinates_bytes_round_trip(bytes: [u8; 6]) {
+        let coordinates = GeoCoordinatesGen2::parse(&mut Cursor::new(&bytes[..])).unwrap();
+        prop_assert_eq!(coordinates.encode(), bytes);
+    }
+
+    #[test]
+    fn geo_coordinates_degrees_round_trip(latitude in -90.0..=90.0f64, longitude in -180.0..=180.0f64) {
+        let raw_latitude = GeoCoordinatesGen2::encode_coordinate(latitude);
+        let raw_longitude = GeoCoordinatesGen2::encode_coordinate(longitude);
+        let coordinates = GeoCoordinatesGen2::from_raw(raw_latitude, raw_longitude);
+        let precision = coordinates.precision_degrees + f64::EPSILON * 180.0;
+        prop_assert!((coordinates.latitude.unwrap() - latitude).abs() <= precision);
+        prop_assert!((coordinates.longitude.unwrap() - longitude).abs() <= precision);
+    }
+
+    #[test]
+    fn bcd_string_round_trips(bytes in prop::collection::vec(any::<u8>(), 0..8)) {
+        let bcd = BCDString::parse_dyn_size(&mut Cursor::new(&bytes[..]), bytes.len()).unwrap();
+        prop_assert_eq!(bcd.encode().unwrap(), bytes);
+    }
+}
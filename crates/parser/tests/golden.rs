@@ -0,0 +1,146 @@
+//! Golden-file regression tests.
+//!
+//! Every `.ddd` file in the fixtures directory is parsed and its JSON output compared against the
+//! `.json` snapshot next to it. Fixtures must be synthetic or anonymized.
+//!
+//! - `GOLDEN_FIXTURES_DIR` points the harness at another directory (defaults to `tests/fixtures`).
+//! - `UPDATE_GOLDEN=1` rewrites the snapshots instead of comparing against them.
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tachograph_parser::card_parser::CardParser;
+use tachograph_parser::detector::{self, TachoFileType};
+use tachograph_parser::vu_parser::VuParser;
+
+/// How many differing paths are printed per fixture before the rest are summarized.
+const MAX_REPORTED_DIFFERENCES: usize = 20;
+
+fn fixtures_dir() -> PathBuf {
+    std::env::var_os("GOLDEN_FIXTURES_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures"))
+}
+
+fn update_mode() -> bool {
+    std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value != "0")
+}
+
+fn parse_to_json(bytes: &[u8]) -> Result<String> {
+    match detector::detect_from_bytes(bytes).context("Failed to detect file type")? {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => {
+            VuParser::new_from_bytes(bytes)?.parse_to_json_pretty()
+        }
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => {
+            CardParser::new_from_bytes(bytes)?.parse_to_json_pretty()
+        }
+    }
+}
+
+/// Collects the JSON pointers at which `actual` differs from `expected`.
+fn diff_values(path: &str, expected: &Value, actual: &Value, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let child = format!("{}/{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_values(&child, expected_value, actual_value, differences)
+                    }
+                    None => differences.push(format!("{}: missing", child)),
+                }
+            }
+            for key in actual.keys().filter(|key| !expected.contains_key(*key)) {
+                differences.push(format!("{}/{}: unexpected", path, key));
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected_item, actual_item)) in expected.iter().zip(actual).enumerate() {
+                diff_values(
+                    &format!("{}/{}", path, index),
+                    expected_item,
+                    actual_item,
+                    differences,
+                );
+            }
+        }
+        _ if expected != actual => {
+            differences.push(format!("{}: expected {}, got {}", path, expected, actual));
+        }
+        _ => {}
+    }
+}
+
+fn check_fixture(fixture: &Path, update: bool) -> Result<Vec<String>> {
+    let bytes = fs::read(fixture).context("Failed to read fixture")?;
+    let actual_json = parse_to_json(&bytes).context("Failed to parse fixture")?;
+    let snapshot = fixture.with_extension("json");
+
+    if update {
+        fs::write(&snapshot, format!("{}\n", actual_json)).context("Failed to write snapshot")?;
+        return Ok(Vec::new());
+    }
+
+    let expected_json = fs::read_to_string(&snapshot).with_context(|| {
+        format!(
+            "Failed to read snapshot {}, run with UPDATE_GOLDEN=1 to create it",
+            snapshot.display()
+        )
+    })?;
+    let expected: Value =
+        serde_json::from_str(&expected_json).context("Failed to parse snapshot")?;
+    let actual: Value = serde_json::from_str(&actual_json).context("Failed to parse output")?;
+
+    let mut differences = Vec::new();
+    diff_values("", &expected, &actual, &mut differences);
+    Ok(differences)
+}
+
+#[test]
+fn test_golden_files() {
+    let dir = fixtures_dir();
+    let update = update_mode();
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("Failed to read fixtures dir {}: {}", dir.display(), e))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ddd"))
+        })
+        .collect();
+    fixtures.sort();
+    assert!(
+        !fixtures.is_empty(),
+        "No fixtures found in {}",
+        dir.display()
+    );
+
+    let mut failures = Vec::new();
+    for fixture in &fixtures {
+        let name = fixture.file_name().unwrap().to_string_lossy();
+        match check_fixture(fixture, update) {
+            Ok(differences) if differences.is_empty() => {}
+            Ok(differences) => {
+                let mut report = format!("{}: {} differences", name, differences.len());
+                for difference in differences.iter().take(MAX_REPORTED_DIFFERENCES) {
+                    report.push_str(&format!("\n    {}", difference));
+                }
+                failures.push(report);
+            }
+            Err(e) => failures.push(format!("{}: {:#}", name, e)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} golden files failed:\n{}",
+        failures.len(),
+        fixtures.len(),
+        failures.join("\n")
+    );
+}
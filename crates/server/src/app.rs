@@ -0,0 +1,287 @@
+use crate::metrics::{Endpoint, Metrics};
+use anyhow::Result;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tachograph_parser::{
+    card_parser::CardParser,
+    detector::{self, TachoFileDetection, TachoFileType},
+    json::{JsonOptions, TimestampFormat},
+    locale::Locale,
+    validation::{self, ValidationOutcome},
+    vu_parser::VuParser,
+};
+use tokio::sync::Semaphore;
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+    /// Largest accepted upload in bytes, larger ones are answered with 413.
+    pub max_body_size: usize,
+    /// Uploads processed at the same time, further ones are answered with 503.
+    pub max_concurrent: usize,
+    /// Processing time after which a request is answered with 504.
+    pub timeout: Duration,
+}
+
+struct AppState {
+    limits: Limits,
+    permits: Arc<Semaphore>,
+    metrics: Metrics,
+}
+
+pub fn router(limits: Limits) -> Router {
+    let state = Arc::new(AppState {
+        permits: Arc::new(Semaphore::new(limits.max_concurrent)),
+        metrics: Metrics::default(),
+        limits: limits.clone(),
+    });
+    Router::new()
+        .route("/parse", post(parse))
+        .route("/detect", post(detect))
+        .route("/validate", post(validate))
+        .route("/metrics", get(metrics))
+        .route("/health", get(|| async { "ok" }))
+        .layer(DefaultBodyLimit::max(limits.max_body_size))
+        .with_state(state)
+}
+
+/// Answered as `{"error": "<message>"}`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+    fn unprocessable(err: anyhow::Error) -> Self {
+        ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{:#}", err))
+    }
+}
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({ "error": self.message });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// Runs `work` on the blocking thread pool within the concurrency and time limits,
+/// and records the request in the metrics.
+async fn run_limited<T, F>(
+    state: &AppState,
+    endpoint: Endpoint,
+    body: Bytes,
+    work: F,
+) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&[u8]) -> Result<T, ApiError> + Send + 'static,
+{
+    let started = Instant::now();
+    let size = body.len();
+    let result = async {
+        let permit = state.permits.clone().try_acquire_owned().map_err(|_| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many uploads in progress, retry later",
+            )
+        })?;
+        // The permit moves into the task, so a timed out parse keeps counting against the limit
+        // until it actually finishes
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            work(&body)
+        });
+        match tokio::time::timeout(state.limits.timeout, task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Parser panicked",
+            )),
+            Err(_) => Err(ApiError::new(
+                StatusCode::GATEWAY_TIMEOUT,
+                "Processing timed out",
+            )),
+        }
+    }
+    .await;
+    let status = match &result {
+        Ok(_) => StatusCode::OK,
+        Err(err) => err.status,
+    };
+    state
+        .metrics
+        .record(endpoint, status, size, started.elapsed());
+    result
+}
+
+#[derive(Debug, Deserialize)]
+struct ParseParams {
+    pretty: Option<bool>,
+    /// See [`Locale`], e.g. `de`.
+    locale: Option<String>,
+    /// See [`TimestampFormat`], e.g. `unix-seconds`.
+    timestamps: Option<String>,
+    /// See [`JsonOptions::provenance`].
+    provenance: Option<bool>,
+    /// See [`JsonOptions::canonical_order`].
+    canonical_order: Option<bool>,
+}
+impl ParseParams {
+    fn json_options(&self) -> Result<JsonOptions> {
+        Ok(JsonOptions {
+            pretty: self.pretty.unwrap_or(false),
+            nation_locale: self
+                .locale
+                .as_deref()
+                .map(str::parse::<Locale>)
+                .transpose()?,
+            timestamp_format: self
+                .timestamps
+                .as_deref()
+                .map(str::parse::<TimestampFormat>)
+                .transpose()?
+                .unwrap_or_default(),
+            provenance: self.provenance.unwrap_or(false),
+            canonical_order: self.canonical_order.unwrap_or(false),
+        })
+    }
+}
+
+fn parse_to_json(bytes: &[u8], options: &JsonOptions) -> Result<String> {
+    match detector::detect_from_bytes(bytes)? {
+        TachoFileType::VehicleUnitGen1
+        | TachoFileType::VehicleUnitGen2
+        | TachoFileType::VehicleUnitGen2V2 => {
+            VuParser::new_from_bytes(bytes)?.parse_to_json_with_options(options)
+        }
+        TachoFileType::DriverCardGen1
+        | TachoFileType::DriverCardGen2
+        | TachoFileType::DriverCardGen2V2 => {
+            CardParser::new_from_bytes(bytes)?.parse_to_json_with_options(options)
+        }
+    }
+}
+
+/// `POST /parse`: the uploaded card or VU file as JSON.
+async fn parse(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ParseParams>,
+    body: Bytes,
+) -> Result<Response, ApiError> {
+    let json = run_limited(&state, Endpoint::Parse, body, move |bytes| {
+        let options = params
+            .json_options()
+            .map_err(|err| ApiError::new(StatusCode::BAD_REQUEST, format!("{:#}", err)))?;
+        parse_to_json(bytes, &options).map_err(ApiError::unprocessable)
+    })
+    .await?;
+    Ok(([(header::CONTENT_TYPE, "application/json")], json).into_response())
+}
+
+/// `POST /detect`: the [`TachoFileDetection`].
+async fn detect(
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> Result<Json<TachoFileDetection>, ApiError> {
+    let detection = run_limited(&state, Endpoint::Detect, body, |bytes| {
+        detector::detect_detailed_from_bytes(bytes).map_err(ApiError::unprocessable)
+    })
+    .await?;
+    Ok(Json(detection))
+}
+
+/// `POST /validate`: the [`ValidationOutcome`], also for files that are not valid.
+async fn validate(
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> Result<Json<ValidationOutcome>, ApiError> {
+    let outcome = run_limited(&state, Endpoint::Validate, body, |bytes| {
+        Ok(validation::validate_bytes(bytes))
+    })
+    .await?;
+    Ok(Json(outcome))
+}
+
+async fn metrics(State(state): State<Arc<AppState>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const VU_FILE: &[u8] = include_bytes!("../../parser/tests/fixtures/vu_gen1_unknown_block.ddd");
+
+    fn test_router(max_body_size: usize) -> Router {
+        router(Limits {
+            max_body_size,
+            max_concurrent: 2,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    async fn post(router: &Router, uri: &str, body: &[u8]) -> (StatusCode, String) {
+        let request = Request::post(uri).body(Body::from(body.to_vec())).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let router = test_router(1024 * 1024);
+
+        let (status, body) = post(&router, "/detect", VU_FILE).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"{"fileType":"vehicle-unit-gen1","cardKind":null}"#);
+
+        let (status, body) = post(&router, "/parse?timestamps=unix-seconds", VU_FILE).await;
+        assert_eq!(status, StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(parsed.is_object());
+
+        let (status, body) = post(&router, "/parse", b"not a tachograph file").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(body.contains("Unsupported tacho file type"));
+
+        let (status, _) = post(&router, "/parse?locale=xx", VU_FILE).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = post(&router, "/validate", VU_FILE).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#""fileType":"vehicle-unit-gen1""#));
+
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let metrics = String::from_utf8(body.to_vec()).unwrap();
+        assert!(metrics.contains(r#"tachod_requests_total{endpoint="parse",status="2xx"} 1"#));
+        assert!(metrics.contains(r#"tachod_requests_total{endpoint="parse",status="4xx"} 2"#));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_uploads_over_the_limit() {
+        let router = test_router(16);
+        let (status, _) = post(&router, "/parse", VU_FILE).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
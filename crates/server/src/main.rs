@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use app::Limits;
+use clap::{value_parser, Arg, Command};
+use flexi_logger::Logger;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+mod app;
+mod metrics;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let matches = Command::new("Tachograph Parser Server")
+        .name(env!("CARGO_PKG_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about(env!("CARGO_PKG_DESCRIPTION"))
+        .arg(
+            Arg::new("listen")
+                .long("listen")
+                .value_parser(value_parser!(SocketAddr))
+                .default_value("127.0.0.1:8080")
+                .help("Address to listen on"),
+        )
+        .arg(
+            Arg::new("max-body-size")
+                .long("max-body-size")
+                .value_parser(value_parser!(usize))
+                .default_value("16777216")
+                .help("Largest accepted upload in bytes"),
+        )
+        .arg(
+            Arg::new("max-concurrent")
+                .long("max-concurrent")
+                .value_parser(value_parser!(usize))
+                .help("Uploads processed at the same time, defaults to the number of CPUs"),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_parser(value_parser!(u64))
+                .default_value("30")
+                .help("Processing time limit per upload in seconds"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(clap::ArgAction::Count)
+                .help("Enable verbose logging"),
+        )
+        .get_matches();
+
+    if matches.get_count("verbose") > 0 {
+        std::env::set_var("RUST_LOG", "trace");
+        Logger::try_with_env()?
+            .start()
+            .context("Failed to start logger")?;
+    }
+
+    let limits = Limits {
+        max_body_size: *matches.get_one::<usize>("max-body-size").unwrap(),
+        max_concurrent: matches
+            .get_one::<usize>("max-concurrent")
+            .copied()
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(4, |parallelism| parallelism.get())
+            }),
+        timeout: Duration::from_secs(*matches.get_one::<u64>("timeout").unwrap()),
+    };
+    let address = matches.get_one::<SocketAddr>("listen").unwrap();
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Failed to listen on {}", address))?;
+    println!("Listening on {}", address);
+
+    axum::serve(listener, app::router(limits))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .context("Server failed")
+}
@@ -0,0 +1,111 @@
+use axum::http::StatusCode;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Endpoint {
+    Parse,
+    Detect,
+    Validate,
+}
+impl Endpoint {
+    const ALL: [Endpoint; 3] = [Endpoint::Parse, Endpoint::Detect, Endpoint::Validate];
+
+    fn name(self) -> &'static str {
+        match self {
+            Endpoint::Parse => "parse",
+            Endpoint::Detect => "detect",
+            Endpoint::Validate => "validate",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointMetrics {
+    ok: AtomicU64,
+    client_errors: AtomicU64,
+    server_errors: AtomicU64,
+    bytes: AtomicU64,
+    duration_micros: AtomicU64,
+}
+
+/// Request counters, rendered in the Prometheus text format on `GET /metrics`.
+/// Uploads rejected for exceeding the body limit never reach a handler and are not counted.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    endpoints: [EndpointMetrics; 3],
+}
+impl Metrics {
+    pub fn record(&self, endpoint: Endpoint, status: StatusCode, bytes: usize, duration: Duration) {
+        let metrics = &self.endpoints[endpoint as usize];
+        let counter = if status.is_server_error() {
+            &metrics.server_errors
+        } else if status.is_client_error() {
+            &metrics.client_errors
+        } else {
+            &metrics.ok
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        metrics.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        metrics
+            .duration_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+
+        output.push_str("# HELP tachod_requests_total Requests by endpoint and status class.\n");
+        output.push_str("# TYPE tachod_requests_total counter\n");
+        for endpoint in Endpoint::ALL {
+            let metrics = &self.endpoints[endpoint as usize];
+            for (status, counter) in [
+                ("2xx", &metrics.ok),
+                ("4xx", &metrics.client_errors),
+                ("5xx", &metrics.server_errors),
+            ] {
+                let _ = writeln!(
+                    output,
+                    "tachod_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                    endpoint.name(),
+                    status,
+                    load(counter)
+                );
+            }
+        }
+
+        output.push_str("# HELP tachod_request_bytes_total Uploaded bytes by endpoint.\n");
+        output.push_str("# TYPE tachod_request_bytes_total counter\n");
+        for endpoint in Endpoint::ALL {
+            let _ = writeln!(
+                output,
+                "tachod_request_bytes_total{{endpoint=\"{}\"}} {}",
+                endpoint.name(),
+                load(&self.endpoints[endpoint as usize].bytes)
+            );
+        }
+
+        output.push_str("# HELP tachod_request_duration_seconds Processing time by endpoint.\n");
+        output.push_str("# TYPE tachod_request_duration_seconds summary\n");
+        for endpoint in Endpoint::ALL {
+            let metrics = &self.endpoints[endpoint as usize];
+            let count =
+                load(&metrics.ok) + load(&metrics.client_errors) + load(&metrics.server_errors);
+            let _ = writeln!(
+                output,
+                "tachod_request_duration_seconds_sum{{endpoint=\"{}\"}} {}",
+                endpoint.name(),
+                load(&metrics.duration_micros) as f64 / 1_000_000.0
+            );
+            let _ = writeln!(
+                output,
+                "tachod_request_duration_seconds_count{{endpoint=\"{}\"}} {}",
+                endpoint.name(),
+                count
+            );
+        }
+        output
+    }
+}
@@ -1,6 +1,11 @@
 import { it } from "node:test";
 import { strict as assert } from "node:assert";
-import { parseVu, parseCard, detectTachoFileType } from "../index.js";
+import {
+	parseVu,
+	parseCard,
+	detectTachoFileType,
+	parseVuFile,
+} from "../index.js";
 import { Buffer } from "node:buffer";
 import { readFileSync } from "node:fs";
 
@@ -38,3 +43,14 @@ it("should detect a tacho file type in under 100ms", async () => {
 	);
 	assert(duration < 100_000_000);
 });
+it("should parse a tacho file with parseVuFile in under 100ms", async () => {
+	const start = performance.now();
+	const parsed = parseVuFile("../../data/vu_gen2.ddd", true);
+	const end = performance.now();
+	const duration = end - start;
+	console.log(
+		`Parsing with parseVuFile took ${duration.toFixed(2)} milliseconds`,
+	);
+
+	assert(duration < 100_000_000);
+});
@@ -1,9 +1,24 @@
 import { describe, it } from "node:test";
-import { parseVu, parseCard, detectTachoFileType } from "../index.js";
+import {
+	parseVu,
+	parseCard,
+	parseSensor,
+	detectTachoFileType,
+	parseVuFile,
+	parseCardFile,
+	TachoStream,
+	activitiesByDay,
+	eventsSorted,
+	driverEvents,
+} from "../index.js";
 import type { Buffer } from "node:buffer";
 import type { VuData } from "../bindings/VuData.js";
 import type { CardData } from "../bindings/CardData.js";
-import type { TachoFileType } from "../bindings/TachoFileType.js";
+import type { SensorData } from "../bindings/SensorData.js";
+import type { TachoFileDetection } from "../bindings/TachoFileDetection.js";
+import type { ActivityDay } from "../bindings/ActivityDay.js";
+import type { VuEventView } from "../bindings/VuEventView.js";
+import type { DriverEvent } from "../bindings/DriverEvent.js";
 
 it("should have correct types for parseVu", () => {
 	type ParseVuType = (bytes: Buffer) => VuData;
@@ -15,7 +30,41 @@ it("should have correct types for parseCard", () => {
 	const _parseCard: ParseCardType = parseCard;
 });
 
+it("should have correct types for parseSensor", () => {
+	type ParseSensorType = (bytes: Buffer) => SensorData;
+	const _parseSensor: ParseSensorType = parseSensor;
+});
+
 it("should have correct types for detectTachoFileType", () => {
-	type DetectTachoFileTypeType = (bytes: Buffer) => TachoFileType;
+	type DetectTachoFileTypeType = (bytes: Buffer) => TachoFileDetection;
 	const _detectTachoFileType: DetectTachoFileTypeType = detectTachoFileType;
 });
+
+it("should have correct types for parseVuFile and parseCardFile", () => {
+	type ParseVuFileType = (path: string) => VuData;
+	type ParseCardFileType = (path: string) => CardData;
+	const _parseVuFile: ParseVuFileType = parseVuFile;
+	const _parseCardFile: ParseCardFileType = parseCardFile;
+});
+
+it("should have correct types for TachoStream", () => {
+	type FinishVuType = () => VuData;
+	type FinishCardType = () => CardData;
+	const stream = new TachoStream();
+	const _push: (chunk: Buffer) => void = stream.push.bind(stream);
+	const _finishVu: FinishVuType = stream.finishVu.bind(stream);
+	const _finishCard: FinishCardType = stream.finishCard.bind(stream);
+});
+
+it("should have correct types for the analysis helpers", () => {
+	type ActivitiesByDayType = (cardData: CardData) => ActivityDay[];
+	type EventsSortedType = (vuData: VuData) => VuEventView[];
+	type DriverEventsType = (
+		cardNumber: string,
+		cards: CardData[],
+		vus: VuData[],
+	) => DriverEvent[];
+	const _activitiesByDay: ActivitiesByDayType = activitiesByDay;
+	const _eventsSorted: EventsSortedType = eventsSorted;
+	const _driverEvents: DriverEventsType = driverEvents;
+});
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActivityPeriod } from "./ActivityPeriod";
+
+/**
+ * The periods of one UTC day, see [`activities_by_day`].
+ */
+export type ActivityDay = { date: string, periods: Array<ActivityPeriod>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether an activity was recorded by the VU or entered manually by the driver.
+ */
+export type ActivityOrigin = "Recorded" | "ManualEntry";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActivityChangeInfoCardActivity } from "./ActivityChangeInfoCardActivity";
+import type { ActivityChangeInfoSlot } from "./ActivityChangeInfoSlot";
+import type { ActivityOrigin } from "./ActivityOrigin";
+
+/**
+ * A continuous stretch of a single activity.
+ */
+export type ActivityPeriod = { start: string, end: string, slot: ActivityChangeInfoSlot, activity: ActivityChangeInfoCardActivity, origin: ActivityOrigin, };
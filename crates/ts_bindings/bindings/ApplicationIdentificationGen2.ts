@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CompanyCardApplicationIdentificationGen2 } from "./CompanyCardApplicationIdentificationGen2";
+import type { ControlCardApplicationIdentificationGen2 } from "./ControlCardApplicationIdentificationGen2";
+import type { DriverCardApplicationIdentificationGen2 } from "./DriverCardApplicationIdentificationGen2";
+import type { WorkshopCardApplicationIdentificationGen2 } from "./WorkshopCardApplicationIdentificationGen2";
+
+export type ApplicationIdentificationGen2 = { "type": "DriverCard" } & DriverCardApplicationIdentificationGen2 | { "type": "WorkshopCard" } & WorkshopCardApplicationIdentificationGen2 | { "type": "ControlCard" } & ControlCardApplicationIdentificationGen2 | { "type": "CompanyCard" } & CompanyCardApplicationIdentificationGen2;
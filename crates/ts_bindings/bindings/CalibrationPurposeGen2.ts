@@ -3,4 +3,4 @@
 /**
  * [CalibrationPurpose: appendix 2.8.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e16597)
  */
-export type CalibrationPurposeGen2 = "Reserved" | "Activation" | "FirstInstallation" | "Installation" | "PeriodicInspection" | "EntryOfVRNByCompany" | "TimeAdjustmentWithoutCalibration" | "RFU" | "ManufacturerSpecific";
+export type CalibrationPurposeGen2 = "Reserved" | "Activation" | "FirstInstallation" | "Installation" | "PeriodicInspection" | "EntryOfVRNByCompany" | "TimeAdjustmentWithoutCalibration" | { "RFU": number } | "ManufacturerSpecific";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InvalidField } from "./InvalidField";
+import type { TruncatedBlock } from "./TruncatedBlock";
+
+/**
+ * Non-fatal findings collected while parsing a card file, see [`Diagnostics`] for VU files.
+ */
+export type CardDiagnostics = { 
+/**
+ * EF the file ends in the middle of, only set in lenient mode, see
+ * [`crate::card_parser::CardParser::lenient`].
+ */
+truncatedEf: TruncatedBlock | null, 
+/**
+ * Optional fields of all generations whose bytes could not be decoded, see
+ * [`Diagnostics::invalid_fields`].
+ */
+invalidFields: Array<InvalidField>, };
@@ -6,4 +6,8 @@ import type { VehicleRegistrationIdentification } from "./VehicleRegistrationIde
 /**
  * [CardEventRecord: appendix 2.20.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17247)
  */
-export type CardEventRecordGen2 = { eventType: EventFaultTypeGen2, eventBeginTime: TimeReal, eventEndTime: TimeReal, eventVehicleRegistration: VehicleRegistrationIdentification, };
+export type CardEventRecordGen2 = { eventType: EventFaultTypeGen2, eventBeginTime: TimeReal, 
+/**
+ * `None` for a event that had not ended yet, stored with an invalid end time.
+ */
+eventEndTime: TimeReal | null, eventVehicleRegistration: VehicleRegistrationIdentification, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * A manufacturer-specific EF decoded by a parser of the [`ExtensionRegistry`]. The EF itself
+ * is still in `unknown_efs`.
+ */
+export type CardExtension = { manufacturerCode: number, sfid: number, 
+/**
+ * Appendix byte of the EF, see [`RawCardEf::file_id`].
+ */
+fileId: number, 
+/**
+ * What the parser returned, `None` when it failed.
+ */
+value: JsonValue | null, 
+/**
+ * Error of the parser, which does not fail the parse of the card.
+ */
+error: string | null, };
@@ -6,4 +6,8 @@ import type { VehicleRegistrationIdentification } from "./VehicleRegistrationIde
 /**
  * [CardFaultData: appendix 2.21.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17292)
  */
-export type CardFaultRecord = { faultType: EventFaultType, faultBeginTime: TimeReal, faultEndTime: TimeReal, faultVehicleRegistration: VehicleRegistrationIdentification, };
+export type CardFaultRecord = { faultType: EventFaultType, faultBeginTime: TimeReal, 
+/**
+ * `None` for a fault that had not ended yet, stored with an invalid end time.
+ */
+faultEndTime: TimeReal | null, faultVehicleRegistration: VehicleRegistrationIdentification, };
@@ -13,9 +13,15 @@ import type { Certificate } from "./Certificate";
 import type { CurrentUsage } from "./CurrentUsage";
 import type { DriverActivityData } from "./DriverActivityData";
 import type { Identification } from "./Identification";
+import type { RawCardEf } from "./RawCardEf";
 import type { SensorInstallation } from "./SensorInstallation";
 import type { Signature } from "./Signature";
 import type { SpecificConditions } from "./SpecificConditions";
 import type { WorkshopCardCalibrationData } from "./WorkshopCardCalibrationData";
 
-export type CardGen1Blocks = { cardIccIdentification: CardIccIdentification, cardChipIdentification: CardChipIdentification, applicationIdentification: ApplicationIdentification, applicationIdentificationSignature: Signature, cardCertificate: Certificate, memberStateCertificate: Certificate, identification: Identification, identificationSignature: Signature, cardDownload: CardDownload | null, cardDownloadSignature: Signature | null, calibration: WorkshopCardCalibrationData | null, calibrationSignature: Signature | null, sensorInstallationData: SensorInstallation | null, sensorInstallationDataSignature: Signature | null, driverLicenceInfo: CardDrivingLicenceInformation | null, driverLicenceInfoSignature: Signature | null, eventsData: CardEventData, eventsDataSignature: Signature, faultsData: CardFaultData, faultsDataSignature: Signature, driverActivityData: DriverActivityData, driverActivityDataSignature: Signature, vehiclesUsed: CardVehiclesUsed, vehiclesUsedSignature: Signature, places: CardPlaceDailyWorkPeriod, placesSignature: Signature, currentUsage: CurrentUsage | null, currentUsageSignature: Signature | null, controlActivityData: CardControlActivityDataRecord, controlActivityDataSignature: Signature, specificConditions: SpecificConditions, specificConditionsSignature: Signature, };
+export type CardGen1Blocks = { cardIccIdentification: CardIccIdentification, cardChipIdentification: CardChipIdentification, applicationIdentification: ApplicationIdentification, applicationIdentificationSignature: Signature, cardCertificate: Certificate, memberStateCertificate: Certificate, identification: Identification, identificationSignature: Signature, cardDownload: CardDownload | null, cardDownloadSignature: Signature | null, calibration: WorkshopCardCalibrationData | null, calibrationSignature: Signature | null, sensorInstallationData: SensorInstallation | null, sensorInstallationDataSignature: Signature | null, driverLicenceInfo: CardDrivingLicenceInformation | null, driverLicenceInfoSignature: Signature | null, eventsData: CardEventData, eventsDataSignature: Signature, faultsData: CardFaultData, faultsDataSignature: Signature, driverActivityData: DriverActivityData, driverActivityDataSignature: Signature, vehiclesUsed: CardVehiclesUsed, vehiclesUsedSignature: Signature, places: CardPlaceDailyWorkPeriod, placesSignature: Signature, currentUsage: CurrentUsage | null, currentUsageSignature: Signature | null, controlActivityData: CardControlActivityDataRecord, controlActivityDataSignature: Signature, specificConditions: SpecificConditions, specificConditionsSignature: Signature, 
+/**
+ * EFs of the Tachograph DF the parser does not know about, kept as raw bytes.
+ * On a card without Gen2 data, this also holds the unknown EFs with a Gen2 file_id.
+ */
+unknownEfs: Array<RawCardEf>, };
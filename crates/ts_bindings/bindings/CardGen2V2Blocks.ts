@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CardBorderCrossings } from "./CardBorderCrossings";
+import type { CardIccIdentificationGen2V2 } from "./CardIccIdentificationGen2V2";
 import type { CardLoadTypeEntries } from "./CardLoadTypeEntries";
 import type { CardLoadUnloadOperations } from "./CardLoadUnloadOperations";
 import type { CardPlacesAuthDailyWorkPeriod } from "./CardPlacesAuthDailyWorkPeriod";
@@ -8,4 +9,9 @@ import type { GNSSAuthAccumulatedDriving } from "./GNSSAuthAccumulatedDriving";
 import type { SignatureGen2 } from "./SignatureGen2";
 import type { VuConfigurations } from "./VuConfigurations";
 
-export type CardGen2V2Blocks = { applicationIdentification: DriverCardApplicationIdentificationGen2V2, applicationIdentificationSignature: SignatureGen2, placesAuthentication: CardPlacesAuthDailyWorkPeriod, placesAuthenticationSignature: SignatureGen2, gnssPlacesAuthentication: GNSSAuthAccumulatedDriving, gnssPlacesAuthenticationSignature: SignatureGen2, borderCrossings: CardBorderCrossings, borderCrossingsSignature: SignatureGen2, loadUnloadOperations: CardLoadUnloadOperations, loadUnloadOperationsSignature: SignatureGen2, loadTypeEntries: CardLoadTypeEntries, loadTypeEntriesSignature: SignatureGen2, vuConfigurations: VuConfigurations | null, vuConfigurationsSignature: SignatureGen2 | null, };
+export type CardGen2V2Blocks = { 
+/**
+ * The Tachograph_G2 CardIccIdentification decoded with the version 2 layout, which
+ * keeps bytes the Gen2 layout does not know about.
+ */
+cardIccIdentification: CardIccIdentificationGen2V2 | null, applicationIdentification: DriverCardApplicationIdentificationGen2V2, applicationIdentificationSignature: SignatureGen2, placesAuthentication: CardPlacesAuthDailyWorkPeriod, placesAuthenticationSignature: SignatureGen2, gnssPlacesAuthentication: GNSSAuthAccumulatedDriving, gnssPlacesAuthenticationSignature: SignatureGen2, borderCrossings: CardBorderCrossings, borderCrossingsSignature: SignatureGen2, loadUnloadOperations: CardLoadUnloadOperations, loadUnloadOperationsSignature: SignatureGen2, loadTypeEntries: CardLoadTypeEntries, loadTypeEntriesSignature: SignatureGen2, vuConfigurations: VuConfigurations | null, vuConfigurationsSignature: SignatureGen2 | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardApprovalNumber } from "./CardApprovalNumber";
+import type { EmbedderIcAssemblerId } from "./EmbedderIcAssemblerId";
+import type { ExtendedSerialNumberGen2V2 } from "./ExtendedSerialNumberGen2V2";
+import type { ManufacturerCode } from "./ManufacturerCode";
+
+/**
+ * [CardIccIdentification: appendix 2.23.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17407)
+ */
+export type CardIccIdentificationGen2V2 = { clockStop: number, cardExtendedSerialNumber: ExtendedSerialNumberGen2V2, cardApprovalNumber: CardApprovalNumber, cardPersonaliserId: ManufacturerCode, embedderIcAssemblerId: EmbedderIcAssemblerId, icIdentifier: Array<number>, 
+/**
+ * Bytes following the Gen2 layout, which version 2 cards may append.
+ */
+additionalData: Array<number>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Kind of card a card file was downloaded from, read from the card type in
+ * EF_Application_Identification.
+ */
+export type CardKind = "Driver" | "Workshop" | "Control" | "Company";
@@ -7,4 +7,4 @@ import type { IA5String } from "./IA5String";
 /**
  * [CardNumber: appendix 2.26.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e17629)
  */
-export type CardNumber = { "type": "driver", driverIdentification: IA5String, cardReplacementIndex: CardReplacementIndex, cardRenewalIndex: CardRenewalIndex, } | { "type": "owner", ownerIdentification: IA5String, cardConsecutiveIndex: CardConsecutiveIndex, cardReplacementIndex: CardReplacementIndex, cardRenewalIndex: CardRenewalIndex, } | { "type": "none" };
+export type CardNumber = { "type": "driver", driverIdentification: IA5String, cardReplacementIndex: CardReplacementIndex, cardRenewalIndex: CardRenewalIndex, } | { "type": "owner", ownerIdentification: IA5String, cardConsecutiveIndex: CardConsecutiveIndex, cardReplacementIndex: CardReplacementIndex, cardRenewalIndex: CardRenewalIndex, } | { "type": "raw", raw: Array<number>, } | { "type": "none" };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardNumber } from "./CardNumber";
+import type { GenerationGen2 } from "./GenerationGen2";
+import type { NationNumeric } from "./NationNumeric";
+
+/**
+ * A full card number recorded by a VU, regardless of generation.
+ */
+export type CardNumberView = { cardIssuingMemberState: NationNumeric, cardNumber: CardNumber, 
+/**
+ * Only recorded by Gen2 VUs, and only for some records.
+ */
+cardGeneration: GenerationGen2 | null, };
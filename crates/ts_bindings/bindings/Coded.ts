@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A byte-mapped value which keeps the raw byte when it is newer than the implemented spec
+ * revision, so that such values do not fail parsing. Serializes as `T` when known and as the
+ * raw byte otherwise.
+ */
+export type Coded<T> = T | number;
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardStructureVersion } from "./CardStructureVersion";
+import type { EquipmentTypeGen2 } from "./EquipmentTypeGen2";
+
+/**
+ * [ControlCardApplicationIdentification: appendix 2.50.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e18950)
+ */
+export type ControlCardApplicationIdentificationGen2 = { typeOfTachographCardId: EquipmentTypeGen2, cardStructureVersion: CardStructureVersion, noOfControlActivityRecords: number, };
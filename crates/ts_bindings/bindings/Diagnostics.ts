@@ -0,0 +1,46 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InvalidField } from "./InvalidField";
+import type { TruncatedBlock } from "./TruncatedBlock";
+import type { UnknownBlock } from "./UnknownBlock";
+
+/**
+ * Non-fatal findings collected while parsing a file.
+ * Anything recorded here was tolerated by the parser, but the output may be incomplete.
+ */
+export type Diagnostics = { 
+/**
+ * VU blocks whose SID/TREP the parser does not know, and which were skipped.
+ */
+unknownBlocks: Array<UnknownBlock>, 
+/**
+ * Size of the parsed file.
+ */
+totalBytes: number, 
+/**
+ * Bytes consumed by recognized blocks, including their SID/TREP header.
+ */
+recognizedBytes: number, 
+/**
+ * Final block cut short by an aborted download, only set in lenient mode.
+ */
+truncatedBlock: TruncatedBlock | null, 
+/**
+ * Blocks were preceded by the header of their download response message, kept by some
+ * download tools. The headers were skipped and count as recognized bytes.
+ */
+responseHeaders: boolean, 
+/**
+ * The file was captured from the download interface with its byte stuffing, every 0x10
+ * doubled. It was unstuffed before parsing, sizes and offsets are of the unstuffed bytes.
+ */
+byteStuffing: boolean, 
+/**
+ * Optional fields whose bytes were present but could not be decoded, and which are `None`
+ * in the parsed data like the fields left empty.
+ */
+invalidFields: Array<InvalidField>, 
+/**
+ * Signatures and certificates were moved past instead of read, see
+ * [`crate::vu_parser::VuParserBuilder::skip_signatures`]. Their bytes are left empty.
+ */
+signaturesSkipped: boolean, };
@@ -1,7 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Kilometers } from "./Kilometers";
 
 /**
  * [Distance: appendix 2.60.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e19665)
  * Value in km in the operational range 0 to 9 999 km.
  */
-export type Distance = number;
+export type Distance = Kilometers;
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventKind } from "./EventKind";
+import type { RecordOrigin } from "./RecordOrigin";
+
+/**
+ * An incident of a driver, recorded on their card, by the VUs they drove, or both, see
+ * [`driver_events`].
+ */
+export type DriverEvent = { kind: EventKind, 
+/**
+ * Serialized event or fault type, e.g. `PowerSupplyInterruption`.
+ */
+eventType: string, beginTime: string, endTime: string | null, 
+/**
+ * Whether the driver's card recorded the incident.
+ */
+onCard: boolean, 
+/**
+ * VINs of the VU downloads that recorded the incident.
+ */
+vehicleIdentificationNumbers: Array<string>, 
+/**
+ * Kinds and generations of the downloads that recorded the incident, each listed once.
+ */
+origins: Array<RecordOrigin>, };
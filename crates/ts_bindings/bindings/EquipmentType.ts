@@ -3,4 +3,4 @@
 /**
  * [EquipmentType: appendix 2.67.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20100)
  */
-export type EquipmentType = "Reserved" | "DriverCard" | "WorkshopCard" | "ControlCard" | "CompanyCard" | "ManufacturingCard" | "VehicleUnit" | "MotionSensor" | "RFU";
+export type EquipmentType = "Reserved" | "DriverCard" | "WorkshopCard" | "ControlCard" | "CompanyCard" | "ManufacturingCard" | "VehicleUnit" | "MotionSensor" | { "RFU": number };
@@ -3,4 +3,4 @@
 /**
  * [EventFaultType: appendix 2.70.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20338)
  */
-export type EventFaultTypeGen2 = "NoFurtherDetails" | "InsertionOfNonValidCard" | "CardConflict" | "TimeOverlap" | "DrivingWithoutAppropriateCard" | "CardInsertionWhileDriving" | "LastCardSessionNotCorrectlyClosed" | "OverSpeeding" | "PowerSupplyInterruption" | "MotionDataError" | "VehicleMotionConflict" | "TimeConflict" | "CommunicationErrorWithRemoteCommunicationFacility" | "AbsenceOfPositionInfoFromGNSSReceiver" | "CommunicationErrorWithExternalGNSSFacility" | "VUSecurityBreachAttemptNoFurtherDetails" | "MotionSensorAuthenticationFailure" | "TachographCardAuthenticationFailure" | "UnauthorizedChangeOfMotionSensor" | "CardDataInputIntegrityError" | "StoredUserDataIntegrityError" | "InternalDataTransferError" | "UnauthorizedCaseOpening" | "HardwareSabotage" | "TamperDetectionOfGNSS" | "ExternalGNSSFacilityAuthenticationFailure" | "ExternalGNSSFacilityCertificateExpired" | "SensorSecurityBreachAttemptNoFurtherDetails" | "SensorAuthenticationFailure" | "SensorStoredDataIntegrityError" | "SensorInternalDataTransferError" | "SensorUnauthorizedCaseOpening" | "SensorHardwareSabotage" | "ControlDeviceFaultNoFurtherDetails" | "VUInternalFault" | "PrinterFault" | "DisplayFault" | "DownloadingFault" | "SensorFault" | "InternalGNSSReceiver" | "ExternalGNSSFacility" | "RemoteCommunicationFacility" | "ITSInterface" | "CardFaultNoFurtherDetails" | "RFU" | "ManufacturerSpecific";
+export type EventFaultTypeGen2 = "NoFurtherDetails" | "InsertionOfNonValidCard" | "CardConflict" | "TimeOverlap" | "DrivingWithoutAppropriateCard" | "CardInsertionWhileDriving" | "LastCardSessionNotCorrectlyClosed" | "OverSpeeding" | "PowerSupplyInterruption" | "MotionDataError" | "VehicleMotionConflict" | "TimeConflict" | "CommunicationErrorWithRemoteCommunicationFacility" | "AbsenceOfPositionInfoFromGNSSReceiver" | "CommunicationErrorWithExternalGNSSFacility" | "VUSecurityBreachAttemptNoFurtherDetails" | "MotionSensorAuthenticationFailure" | "TachographCardAuthenticationFailure" | "UnauthorizedChangeOfMotionSensor" | "CardDataInputIntegrityError" | "StoredUserDataIntegrityError" | "InternalDataTransferError" | "UnauthorizedCaseOpening" | "HardwareSabotage" | "TamperDetectionOfGNSS" | "ExternalGNSSFacilityAuthenticationFailure" | "ExternalGNSSFacilityCertificateExpired" | "SensorSecurityBreachAttemptNoFurtherDetails" | "SensorAuthenticationFailure" | "SensorStoredDataIntegrityError" | "SensorInternalDataTransferError" | "SensorUnauthorizedCaseOpening" | "SensorHardwareSabotage" | "ControlDeviceFaultNoFurtherDetails" | "VUInternalFault" | "PrinterFault" | "DisplayFault" | "DownloadingFault" | "SensorFault" | "InternalGNSSReceiver" | "ExternalGNSSFacility" | "RemoteCommunicationFacility" | "ITSInterface" | "CardFaultNoFurtherDetails" | { "RFU": number } | "ManufacturerSpecific";
@@ -3,4 +3,4 @@
 /**
  * [EventFaultType: appendix 2.70.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20338)
  */
-export type EventFaultTypeGen2V2 = "NoFurtherDetails" | "InsertionOfNonValidCard" | "CardConflict" | "TimeOverlap" | "DrivingWithoutAppropriateCard" | "CardInsertionWhileDriving" | "LastCardSessionNotCorrectlyClosed" | "OverSpeeding" | "PowerSupplyInterruption" | "MotionDataError" | "VehicleMotionConflict" | "TimeConflict" | "CommunicationErrorWithRemoteCommunicationFacility" | "AbsenceOfPositionInfoFromGNSSReceiver" | "CommunicationErrorWithExternalGNSSFacility" | "GNSSAnomaly" | "VUSecurityBreachAttemptNoFurtherDetails" | "MotionSensorAuthenticationFailure" | "TachographCardAuthenticationFailure" | "UnauthorizedChangeOfMotionSensor" | "CardDataInputIntegrityError" | "StoredUserDataIntegrityError" | "InternalDataTransferError" | "UnauthorizedCaseOpening" | "HardwareSabotage" | "TamperDetectionOfGNSS" | "ExternalGNSSFacilityAuthenticationFailure" | "ExternalGNSSFacilityCertificateExpired" | "InconsistencyBetweenMotionDataAndStoredDriverActivityData" | "SensorSecurityBreachAttemptNoFurtherDetails" | "SensorAuthenticationFailure" | "SensorStoredDataIntegrityError" | "SensorInternalDataTransferError" | "SensorUnauthorizedCaseOpening" | "SensorHardwareSabotage" | "ControlDeviceFaultNoFurtherDetails" | "VUInternalFault" | "PrinterFault" | "DisplayFault" | "DownloadingFault" | "SensorFault" | "InternalGNSSReceiver" | "ExternalGNSSFacility" | "RemoteCommunicationFacility" | "ITSInterface" | "InternalSensorFault" | "CardFaultNoFurtherDetails" | "RFU" | "ManufacturerSpecific";
+export type EventFaultTypeGen2V2 = "NoFurtherDetails" | "InsertionOfNonValidCard" | "CardConflict" | "TimeOverlap" | "DrivingWithoutAppropriateCard" | "CardInsertionWhileDriving" | "LastCardSessionNotCorrectlyClosed" | "OverSpeeding" | "PowerSupplyInterruption" | "MotionDataError" | "VehicleMotionConflict" | "TimeConflict" | "CommunicationErrorWithRemoteCommunicationFacility" | "AbsenceOfPositionInfoFromGNSSReceiver" | "CommunicationErrorWithExternalGNSSFacility" | "GNSSAnomaly" | "VUSecurityBreachAttemptNoFurtherDetails" | "MotionSensorAuthenticationFailure" | "TachographCardAuthenticationFailure" | "UnauthorizedChangeOfMotionSensor" | "CardDataInputIntegrityError" | "StoredUserDataIntegrityError" | "InternalDataTransferError" | "UnauthorizedCaseOpening" | "HardwareSabotage" | "TamperDetectionOfGNSS" | "ExternalGNSSFacilityAuthenticationFailure" | "ExternalGNSSFacilityCertificateExpired" | "InconsistencyBetweenMotionDataAndStoredDriverActivityData" | "SensorSecurityBreachAttemptNoFurtherDetails" | "SensorAuthenticationFailure" | "SensorStoredDataIntegrityError" | "SensorInternalDataTransferError" | "SensorUnauthorizedCaseOpening" | "SensorHardwareSabotage" | "ControlDeviceFaultNoFurtherDetails" | "VUInternalFault" | "PrinterFault" | "DisplayFault" | "DownloadingFault" | "SensorFault" | "InternalGNSSReceiver" | "ExternalGNSSFacility" | "RemoteCommunicationFacility" | "ITSInterface" | "InternalSensorFault" | "CardFaultNoFurtherDetails" | { "RFU": number } | "ManufacturerSpecific";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Position of a sub-array in [`CardEventDataGen2`]. The card keeps a fixed sub-array per event
+ * type, ordered by ascending event type, with the security breach attempts grouped in the last.
+ */
+export type EventFaultTypeGroup = number;
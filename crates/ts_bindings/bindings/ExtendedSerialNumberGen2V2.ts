@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EquipmentTypeGen2 } from "./EquipmentTypeGen2";
+import type { ManufacturerCode } from "./ManufacturerCode";
+import type { MonthYear } from "./MonthYear";
+
+/**
+ * [ExtendedSerialNumber: appendix 2.72.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e20372)
+ */
+export type ExtendedSerialNumberGen2V2 = { serialNumber: number, monthYear: MonthYear, equipmentType: EquipmentTypeGen2, manufacturerCode: ManufacturerCode, };
@@ -2,5 +2,18 @@
 
 /**
  * [GeoCoordinates: appendix 2.76.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21534)
+ * Both values are stored as ±DDDMM.M multiplied by 10, so they are precise to a tenth of an arc minute.
  */
-export type GeoCoordinatesGen2 = { latitude: number, longitude: number, };
+export type GeoCoordinatesGen2 = { 
+/**
+ * Decimal degrees, `None` when the position is unknown or the raw value is invalid.
+ */
+latitude: number | null, 
+/**
+ * Decimal degrees, `None` when the position is unknown or the raw value is invalid.
+ */
+longitude: number | null, 
+/**
+ * Decoded degrees are exact to ± this value.
+ */
+precisionDegrees: number, rawLatitude: number, rawLongitude: number, };
@@ -2,5 +2,19 @@
 
 /**
  * [GNSSAccuracy: appendix 2.77.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e21573)
+ * The stored value is the HDOP of the fix multiplied by 10, see appendix 12.
  */
-export type GnssAccuracyGen2 = number;
+export type GnssAccuracyGen2 = { 
+/**
+ * Raw value as stored, in the range 0 to 100.
+ */
+value: number, 
+/**
+ * Horizontal dilution of precision, `value / 10`.
+ */
+hdop: number, 
+/**
+ * Rough horizontal error in meters, the HDOP multiplied by a nominal range error of
+ * [`GnssAccuracyGen2::NOMINAL_RANGE_ERROR_METERS`].
+ */
+estimatedErrorMeters: number, };
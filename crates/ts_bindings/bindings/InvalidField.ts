@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An optional field that holds data the parser could not decode, e.g. a `TimeReal` past 2038.
+ * Fields left empty, all `0x00` or all `0xFF`, are absent rather than invalid and are not
+ * reported.
+ */
+export type InvalidField = { 
+/**
+ * Name of the field, e.g. `event_end_time`.
+ */
+field: string, 
+/**
+ * Offset from the start of the file, or `None` when the bytes were read from a buffer
+ * assembled by the parser (e.g. the unwrapped cyclic activity data of a card).
+ */
+offset: number | null, raw: Array<number>, error: string, };
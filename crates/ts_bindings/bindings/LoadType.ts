@@ -3,4 +3,4 @@
 /**
  * [LoadType: appendix 2.90a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22110)
  */
-export type LoadType = "UndefinedLoadType" | "Goods" | "Passengers" | "RFU";
+export type LoadType = "UndefinedLoadType" | "Goods" | "Passengers" | { "RFU": number };
@@ -3,4 +3,4 @@
 /**
  * [ManufacturerCode: appendix 2.94.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22253)
  */
-export type ManufacturerCode = string;
+export type ManufacturerCode = { code: number, name: string, };
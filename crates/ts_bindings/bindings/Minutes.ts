@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A duration in minutes, or a time of day as minutes since 00:00.
+ */
+export type Minutes = number;
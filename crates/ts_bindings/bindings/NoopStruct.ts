@@ -1,6 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActivityDay } from "./ActivityDay";
 import type { CardData } from "./CardData";
+import type { DriverEvent } from "./DriverEvent";
+import type { SensorData } from "./SensorData";
+import type { TachoFileDetection } from "./TachoFileDetection";
 import type { TachoFileType } from "./TachoFileType";
 import type { VuData } from "./VuData";
+import type { VuEventView } from "./VuEventView";
 
-export type NoopStruct = { card_data: CardData, vu_data: VuData, tacho_file_type: TachoFileType, };
+export type NoopStruct = { card_data: CardData, vu_data: VuData, sensor_data: SensorData, tacho_file_type: TachoFileType, tacho_file_detection: TachoFileDetection, activity_day: ActivityDay, vu_event_view: VuEventView, driver_event: DriverEvent, };
@@ -1,7 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Kilometers } from "./Kilometers";
 
 /**
  * [OdometerShort: appendix 2.113.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22854)
  * Value in km in the operating range 0 to 9 999 999 km.
  */
-export type OdometerShort = number;
+export type OdometerShort = Kilometers;
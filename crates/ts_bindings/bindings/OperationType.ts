@@ -3,4 +3,4 @@
 /**
  * [OperationType: appendix 2.114a.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e22905)
  */
-export type OperationType = "RFU" | "LoadOperation" | "UnloadOperation" | "SimultaneousLoadUnloadOperation";
+export type OperationType = { "RFU": number } | "LoadOperation" | "UnloadOperation" | "SimultaneousLoadUnloadOperation";
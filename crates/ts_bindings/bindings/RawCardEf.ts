@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An EF present in the file that the parser has no structure for, such as the proprietary EFs
+ * some manufacturers add after the standard set.
+ * It deserializes back from the JSON output unchanged, and [`RawCardEf::to_bytes`] restores it
+ * as it was in the downloaded file, so re-export tools can keep vendor data.
+ */
+export type RawCardEf = { 
+/**
+ * File identifier of the EF.
+ */
+sfid: number, 
+/**
+ * Appendix byte following the file identifier: 0 for Gen1 data, 1 for its signature,
+ * 2 for Gen2 data and 3 for its signature.
+ */
+fileId: number, data: Array<number>, };
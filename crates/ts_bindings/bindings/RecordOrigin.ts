@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataSource } from "./DataSource";
+import type { Generation } from "./Generation";
+
+/**
+ * The download a record of a merge was read from, to tell apart what each generation of cards
+ * and VUs recorded in mixed fleets.
+ */
+export type RecordOrigin = { source: DataSource, generation: Generation, };
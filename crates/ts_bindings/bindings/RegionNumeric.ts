@@ -1,6 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Coded } from "./Coded";
 
 /**
  * [RegionNumeric: appendix 2.122.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e23612)
+ * Regions newer than this list keep their raw byte, see [`Coded`].
  */
-export type RegionNumeric = string;
+export type RegionNumeric = Coded<string>;
@@ -2,5 +2,6 @@
 
 /**
  * https://dtc.jrc.ec.europa.eu/dtc_seal_manufacturer_code.php.html
+ * Holds the name of a registered manufacturer, or the two letter code as read for the others.
  */
 export type SealManufacturerCode = string;
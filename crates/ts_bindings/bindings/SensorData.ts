@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SensorIdentification } from "./SensorIdentification";
+import type { SensorInstallation } from "./SensorInstallation";
+
+export type SensorData = { sensorIdentification: SensorIdentification, 
+/**
+ * The first and the current pairing of the sensor with a VU.
+ */
+sensorInstallation: SensorInstallation, 
+/**
+ * Vendor data some tools append after the pairing records, kept as is.
+ */
+trailingBytes: Array<number>, };
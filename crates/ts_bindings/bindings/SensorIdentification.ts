@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExtendedSerialNumber } from "./ExtendedSerialNumber";
+import type { SensorApprovalNumber } from "./SensorApprovalNumber";
+import type { SensorOSIdentifier } from "./SensorOSIdentifier";
+import type { SensorSCIdentifier } from "./SensorSCIdentifier";
+
+/**
+ * [SensorIdentification: appendix 2.140.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+ */
+export type SensorIdentification = { sensorSerialNumber: ExtendedSerialNumber, sensorApprovalNumber: SensorApprovalNumber, 
+/**
+ * Identifier of the security component of the sensor.
+ */
+sensorScIdentifier: SensorSCIdentifier, 
+/**
+ * Identifier of the operating system of the sensor.
+ */
+sensorOsIdentifier: SensorOSIdentifier, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IA5String } from "./IA5String";
+
+/**
+ * [SensorOSIdentifier: appendix 2.143.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+ */
+export type SensorOSIdentifier = IA5String;
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IA5String } from "./IA5String";
+
+/**
+ * [SensorSCIdentifier: appendix 2.147.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+ */
+export type SensorSCIdentifier = IA5String;
@@ -2,5 +2,7 @@
 
 /**
  * [SpecificConditionType: appendix 2.154.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24685)
+ * Gen1 only records the begin of a ferry or train crossing, there is no end marker. Other
+ * values, such as the Gen2 crossing end (0x04), are kept as [`Coded::Unknown`].
  */
-export type SpecificConditionType = "RFU" | "OutOfScopeBegin" | "OutOfScopeEnd" | "FerryTrainCrossingBegin";
+export type SpecificConditionType = "OutOfScopeBegin" | "OutOfScopeEnd" | "FerryTrainCrossingBegin";
@@ -3,4 +3,4 @@
 /**
  * [SpecificConditionType: appendix 2.154.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e24685)
  */
-export type SpecificConditionTypeGen2 = "RFU" | "OutOfScopeBegin" | "OutOfScopeEnd" | "FerryTrainCrossingBegin" | "FerryTrainCrossingEnd";
+export type SpecificConditionTypeGen2 = { "RFU": number } | "OutOfScopeBegin" | "OutOfScopeEnd" | "FerryTrainCrossingBegin" | "FerryTrainCrossingEnd";
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardKind } from "./CardKind";
+import type { TachoFileType } from "./TachoFileType";
+
+export type TachoFileDetection = { fileType: TachoFileType, 
+/**
+ * `None` for VU files, and for card files whose card type could not be read.
+ */
+cardKind: CardKind | null, };
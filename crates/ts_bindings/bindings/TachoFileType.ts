@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type TachoFileType = "VehicleUnitGen1" | "VehicleUnitGen2" | "VehicleUnitGen2V2" | "DriverCardGen1" | "DriverCardGen2" | "DriverCardGen2V2";
+/**
+ * Kind of downloaded file. Displayed, parsed and serialized as the same kebab-case name,
+ * e.g. `vehicle-unit-gen2-v2`, so every output refers to a file type the same way.
+ */
+export type TachoFileType = "vehicle-unit-gen1" | "vehicle-unit-gen2" | "vehicle-unit-gen2-v2" | "driver-card-gen1" | "driver-card-gen2" | "driver-card-gen2-v2";
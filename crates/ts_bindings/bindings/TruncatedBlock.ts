@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A VU block or card EF the file ends in the middle of, typically because the download was
+ * aborted. Lenient parsing drops it and keeps everything before it.
+ */
+export type TruncatedBlock = { 
+/**
+ * Offset of the block from the start of the file.
+ */
+offset: number, 
+/**
+ * Header bytes of the block: SID and TREP of a VU block, or file identifier, appendix byte
+ * and length of a card EF. Shorter when the file ends inside the header.
+ */
+header: Array<number>, 
+/**
+ * Bytes of the block present in the file, header included.
+ */
+availableBytes: number, 
+/**
+ * Bytes missing from the block. Exact for card EFs, whose header holds their length. VU
+ * blocks do not record their length, so for Gen2 blocks this is a lower bound read from the
+ * record array headers, and for Gen1 blocks it is unknown.
+ */
+missingBytes: number | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type UnknownBlock = { sid: number, trep: number, 
+/**
+ * Offset of the SID byte from the start of the file.
+ */
+offset: number, 
+/**
+ * Bytes skipped until the next known block marker, or until the end of the file.
+ */
+skippedBytes: number, 
+/**
+ * Whether another known block was found after this one.
+ */
+resynchronized: boolean, };
@@ -1,7 +0,0 @@
-// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
-import type { IA5String } from "./IA5String";
-
-/**
- * [VehicleRegistrationNumber: appendix 2.168.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25188)
- */
-export type VehicleRegistrationNumberGen2V2 = { codePage: number, vehicleRegNumber: IA5String, };
@@ -1,6 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Address } from "./Address";
 import type { CalibrationPurpose } from "./CalibrationPurpose";
+import type { Coded } from "./Coded";
 import type { FullCardNumber } from "./FullCardNumber";
 import type { KConstantOfRecordingEquipment } from "./KConstantOfRecordingEquipment";
 import type { LTyreCircumference } from "./LTyreCircumference";
@@ -16,4 +17,4 @@ import type { WVehicleCharacteristicConstant } from "./WVehicleCharacteristicCon
 /**
  * [VuCalibrationRecord: appendix 2.174.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25500)
  */
-export type VuCalibrationRecord = { calibrationPurpose: CalibrationPurpose, workshopName: Name, workshopAddress: Address, workshopCardNumber: FullCardNumber, workshopCardExpiryDate: TimeReal | null, vehicleIdentificationNumber: VehicleIdentificationNumber | null, vehicleRegistrationIdentification: VehicleRegistrationIdentification | null, wVehicleCharacteristicConstant: WVehicleCharacteristicConstant, kConstantOfRecordingEquipment: KConstantOfRecordingEquipment, lTyreCircumference: LTyreCircumference, tyreSize: TyreSize, authorisedSpeed: Speed, oldOdometerValue: OdometerShort, newOdometerValue: OdometerShort, oldTimeValue: TimeReal | null, newTimeValue: TimeReal | null, nextCalibrationDate: TimeReal | null, };
+export type VuCalibrationRecord = { calibrationPurpose: Coded<CalibrationPurpose>, workshopName: Name, workshopAddress: Address, workshopCardNumber: FullCardNumber, workshopCardExpiryDate: TimeReal | null, vehicleIdentificationNumber: VehicleIdentificationNumber | null, vehicleRegistrationIdentification: VehicleRegistrationIdentification | null, wVehicleCharacteristicConstant: WVehicleCharacteristicConstant, kConstantOfRecordingEquipment: KConstantOfRecordingEquipment, lTyreCircumference: LTyreCircumference, tyreSize: TyreSize, authorisedSpeed: Speed, oldOdometerValue: OdometerShort, newOdometerValue: OdometerShort, oldTimeValue: TimeReal | null, newTimeValue: TimeReal | null, nextCalibrationDate: TimeReal | null, };
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CardSlotNumber } from "./CardSlotNumber";
+import type { Coded } from "./Coded";
 import type { FullCardNumber } from "./FullCardNumber";
 import type { HolderName } from "./HolderName";
 import type { ManualInputFlag } from "./ManualInputFlag";
@@ -10,4 +11,4 @@ import type { TimeReal } from "./TimeReal";
 /**
  * [VuCardIwRecord: appendix 2.177.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e25809)
  */
-export type VuCardIwRecord = { cardHolderName: HolderName, fullCardNumber: FullCardNumber, cardExpiryDate: TimeReal, cardInsertionTime: TimeReal, vehicleOdometerValueAtInsertion: OdometerShort, cardSlotNumber: CardSlotNumber, cardWithdrawalTime: TimeReal | null, vehicleOdometerValueAtWithdrawal: OdometerShort, previousVehicleInfo: PreviousVehicleInfo | null, manualEntryFlag: ManualInputFlag | null, };
+export type VuCardIwRecord = { cardHolderName: HolderName, fullCardNumber: FullCardNumber, cardExpiryDate: TimeReal, cardInsertionTime: TimeReal, vehicleOdometerValueAtInsertion: OdometerShort, cardSlotNumber: Coded<CardSlotNumber>, cardWithdrawalTime: TimeReal | null, vehicleOdometerValueAtWithdrawal: OdometerShort, previousVehicleInfo: PreviousVehicleInfo | null, manualEntryFlag: Coded<ManualInputFlag> | null, };
@@ -6,4 +6,8 @@ import type { TimeReal } from "./TimeReal";
 /**
  * [VuControlActivityRecord: appendix 2.187.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821#cons_toc_d1e26392)
  */
-export type VuControlActivityRecord = { controlType: ControlType, controlTime: TimeReal, controlCardNumber: FullCardNumber, downloadPeriodBeginTime: TimeReal, downloadPeriodEndTime: TimeReal, };
+export type VuControlActivityRecord = { controlType: ControlType, controlTime: TimeReal, controlCardNumber: FullCardNumber, 
+/**
+ * Only set for VU downloads, zero otherwise.
+ */
+downloadPeriodBeginTime: TimeReal | null, downloadPeriodEndTime: TimeReal | null, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardNumberView } from "./CardNumberView";
+import type { EventFaultRecordPurpose } from "./EventFaultRecordPurpose";
+import type { EventKind } from "./EventKind";
+import type { Generation } from "./Generation";
+import type { ManufacturerSpecificEventFaultDataGen2 } from "./ManufacturerSpecificEventFaultDataGen2";
+
+/**
+ * An event or fault recorded by a VU, regardless of generation, see [`VuData::events`].
+ */
+export type VuEventView = { kind: EventKind, generation: Generation, 
+/**
+ * Serialized event or fault type, e.g. `PowerSupplyInterruption`.
+ */
+eventType: string, recordPurpose: EventFaultRecordPurpose, beginTime: string, 
+/**
+ * Always set for faults.
+ */
+endTime: string | null, cardDriverSlotBegin: CardNumberView | null, cardCodriverSlotBegin: CardNumberView | null, cardDriverSlotEnd: CardNumberView | null, cardCodriverSlotEnd: CardNumberView | null, 
+/**
+ * Only recorded for events.
+ */
+similarEventsNumber: number | null, 
+/**
+ * Only recorded by Gen2 VUs.
+ */
+manufacturerSpecificData: ManufacturerSpecificEventFaultDataGen2 | null, };
@@ -1,8 +1,14 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Diagnostics } from "./Diagnostics";
 import type { VuActivitiesBlock } from "./VuActivitiesBlock";
 import type { VuCompanyLocksBlock } from "./VuCompanyLocksBlock";
-import type { VuDetailedSpeedBlock } from "./VuDetailedSpeedBlock";
+import type { VuDetailedSpeedBlockGen1 } from "./VuDetailedSpeedBlockGen1";
 import type { VuEventsAndFaultsBlock } from "./VuEventsAndFaultsBlock";
 import type { VuOverviewBlock } from "./VuOverviewBlock";
+import type { VuSegment } from "./VuSegment";
 
-export type VuGen1Blocks = { vuOverview: VuOverviewBlock, vuActivities: Array<VuActivitiesBlock>, vuEventsAndFaults: Array<VuEventsAndFaultsBlock>, vuDetailedSpeed: Array<VuDetailedSpeedBlock>, vuCompanyLocks: Array<VuCompanyLocksBlock>, };
+export type VuGen1Blocks = { vuOverview: VuOverviewBlock, vuActivities: Array<VuActivitiesBlock>, vuEventsAndFaults: Array<VuEventsAndFaultsBlock>, vuDetailedSpeed: Array<VuDetailedSpeedBlockGen1>, vuCompanyLocks: Array<VuCompanyLocksBlock>, 
+/**
+ * Where each block was read from, only kept with [`VuParser::keep_segments`].
+ */
+segments: Array<VuSegment> | null, diagnostics: Diagnostics, };
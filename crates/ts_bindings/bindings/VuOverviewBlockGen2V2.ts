@@ -4,7 +4,7 @@ import type { CertificateGen2 } from "./CertificateGen2";
 import type { SignatureGen2 } from "./SignatureGen2";
 import type { TimeReal } from "./TimeReal";
 import type { VehicleIdentificationNumber } from "./VehicleIdentificationNumber";
-import type { VehicleRegistrationNumberGen2V2 } from "./VehicleRegistrationNumberGen2V2";
+import type { VehicleRegistrationIdentification } from "./VehicleRegistrationIdentification";
 import type { VuCompanyLocksRecordGen2 } from "./VuCompanyLocksRecordGen2";
 import type { VuControlActivityRecordGen2 } from "./VuControlActivityRecordGen2";
 import type { VuDownloadActivityDataGen2 } from "./VuDownloadActivityDataGen2";
@@ -27,9 +27,9 @@ vuCertificateRecordArray: Array<CertificateGen2>,
  */
 vehicleIdentificationNumberRecordArray: Array<VehicleIdentificationNumber>, 
 /**
- * Vehicle registration number
+ * Vehicle registration nation and number, replacing the Gen2 registration number array
  */
-vehicleRegistrationNumberRecordArray: Array<VehicleRegistrationNumberGen2V2>, 
+vehicleRegistrationIdentificationRecordArray: Array<VehicleRegistrationIdentification>, 
 /**
  * VU current date and time
  */
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The bytes of a recognized block in the original download, SID and TREP included.
+ */
+export type VuSegment = { trep: number, 
+/**
+ * Index of the parsed block among the blocks of its TREP, e.g. `vu_activities[index]`.
+ */
+index: number, offset: number, length: number, };
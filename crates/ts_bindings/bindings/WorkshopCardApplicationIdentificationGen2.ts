@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardStructureVersion } from "./CardStructureVersion";
+import type { EquipmentTypeGen2 } from "./EquipmentTypeGen2";
+
+/**
+ * [WorkshopCardApplicationIdentification: appendix 2.234.](https://eur-lex.europa.eu/legal-content/EN/TXT/PDF/?uri=CELEX:02016R0799-20230821)
+ */
+export type WorkshopCardApplicationIdentificationGen2 = { typeOfTachographCardId: EquipmentTypeGen2, cardStructureVersion: CardStructureVersion, noOfEventsPerType: number, noOfFaultsPerType: number, activityStructureLength: number, noOfCardVehicleRecords: number, noOfCardPlaceRecords: number, noOfCalibrationRecords: number, noOfGnssAdRecords: number, noOfSpecificConditionRecords: number, noOfCardVehicleUnitRecords: number, };
@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CalibrationPurpose } from "./CalibrationPurpose";
+import type { Coded } from "./Coded";
 import type { ExtendedSerialNumber } from "./ExtendedSerialNumber";
 import type { KConstantOfRecordingEquipment } from "./KConstantOfRecordingEquipment";
 import type { LTyreCircumference } from "./LTyreCircumference";
@@ -12,4 +13,4 @@ import type { VehicleRegistrationIdentification } from "./VehicleRegistrationIde
 import type { VuPartNumber } from "./VuPartNumber";
 import type { WVehicleCharacteristicConstant } from "./WVehicleCharacteristicConstant";
 
-export type WorkshopCardCalibrationRecord = { calibrationPurpose: CalibrationPurpose, vehicleIdentificationNumber: VehicleIdentificationNumber, vehicleRegistration: VehicleRegistrationIdentification, wVehicleCharacteristicConstant: WVehicleCharacteristicConstant, kConstantOfRecordingEquipment: KConstantOfRecordingEquipment, lTyreCircumference: LTyreCircumference, tyreSize: TyreSize, authorisedSpeed: Speed, oldOdometerValue: OdometerShort, newOdometerValue: OdometerShort, oldTimeValue: TimeReal, newTimeValue: TimeReal, nextCalibrationDate: TimeReal, vuPartNumber: VuPartNumber, vuSerialNumber: ExtendedSerialNumber, sensorSerialNumber: ExtendedSerialNumber, };
+export type WorkshopCardCalibrationRecord = { calibrationPurpose: Coded<CalibrationPurpose>, vehicleIdentificationNumber: VehicleIdentificationNumber, vehicleRegistration: VehicleRegistrationIdentification, wVehicleCharacteristicConstant: WVehicleCharacteristicConstant, kConstantOfRecordingEquipment: KConstantOfRecordingEquipment, lTyreCircumference: LTyreCircumference, tyreSize: TyreSize, authorisedSpeed: Speed, oldOdometerValue: OdometerShort, newOdometerValue: OdometerShort, oldTimeValue: TimeReal, newTimeValue: TimeReal, nextCalibrationDate: TimeReal, vuPartNumber: VuPartNumber, vuSerialNumber: ExtendedSerialNumber, sensorSerialNumber: ExtendedSerialNumber, };
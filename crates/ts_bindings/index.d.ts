@@ -1,6 +1,10 @@
 import type { VuData } from "./bindings/VuData";
 import type { CardData } from "./bindings/CardData";
-import type { TachoFileType } from "./bindings/TachoFileType";
+import type { SensorData } from "./bindings/SensorData";
+import type { TachoFileDetection } from "./bindings/TachoFileDetection";
+import type { ActivityDay } from "./bindings/ActivityDay";
+import type { VuEventView } from "./bindings/VuEventView";
+import type { DriverEvent } from "./bindings/DriverEvent";
 /* tslint:disable */
 /* eslint-disable */
 
@@ -8,4 +12,37 @@ import type { TachoFileType } from "./bindings/TachoFileType";
 
 export declare function parseVu(bytes: Buffer): VuData
 export declare function parseCard(bytes: Buffer): CardData
-export declare function detectTachoFileType(bytes: Buffer): TachoFileType
+export declare function parseSensor(bytes: Buffer): SensorData
+export declare function detectTachoFileType(bytes: Buffer): TachoFileDetection
+/** Reads the file on the Rust side, so that its bytes never cross into JS. */
+export declare function parseVuFile(path: string): VuData
+/** Reads the file on the Rust side, like `parseVuFile`. */
+export declare function parseCardFile(path: string): CardData
+/**
+ * Collects a download pushed in chunks, e.g. from a `Readable`, so that the whole file never
+ * has to be assembled into one Buffer on the JS side. The chunks are copied into one buffer on
+ * the Rust side and only parsed once finished, so the stream holds the whole download in memory.
+ */
+export declare class TachoStream {
+  constructor()
+  push(chunk: Buffer): void
+  /** Parses the pushed chunks as a VU download and empties the stream. */
+  finishVu(): VuData
+  /** Parses the pushed chunks as a card download and empties the stream. */
+  finishCard(): CardData
+}
+/**
+ * Activity periods of the card per UTC day, oldest first, with periods crossing midnight split
+ * between both days.
+ */
+export declare function activitiesByDay(cardData: CardData): ActivityDay[]
+/**
+ * Events and faults of the VU in chronological order, with end times before their begin moved
+ * to the begin.
+ */
+export declare function eventsSorted(vuData: VuData): VuEventView[]
+/**
+ * Events and faults of the driver holding `cardNumber`, from their card downloads and the VU
+ * downloads that name the card, in one chronological list without duplicates.
+ */
+export declare function driverEvents(cardNumber: string, cards: CardData[], vus: VuData[]): DriverEvent[]
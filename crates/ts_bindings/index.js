@@ -310,8 +310,18 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { parseVu, parseCard, detectTachoFileType } = nativeBinding
+const { parseVu, parseCard, parseSensor, detectTachoFileType, parseVuFile, parseCardFile, TachoStream, activitiesByDay, eventsSorted, driverEvents } = nativeBinding
 
 module.exports.parseVu = (...input) => JSON.parse(parseVu(...input))
 module.exports.parseCard = (...input) => JSON.parse(parseCard(...input))
-module.exports.detectTachoFileType = detectTachoFileType
+module.exports.parseSensor = (...input) => JSON.parse(parseSensor(...input))
+module.exports.detectTachoFileType = (...input) => JSON.parse(detectTachoFileType(...input))
+module.exports.parseVuFile = (...input) => JSON.parse(parseVuFile(...input))
+module.exports.parseCardFile = (...input) => JSON.parse(parseCardFile(...input))
+const { finishVu, finishCard } = TachoStream.prototype
+TachoStream.prototype.finishVu = function () { return JSON.parse(finishVu.call(this)) }
+TachoStream.prototype.finishCard = function () { return JSON.parse(finishCard.call(this)) }
+module.exports.TachoStream = TachoStream
+module.exports.activitiesByDay = (cardData) => JSON.parse(activitiesByDay(JSON.stringify(cardData)))
+module.exports.eventsSorted = (vuData) => JSON.parse(eventsSorted(JSON.stringify(vuData)))
+module.exports.driverEvents = (cardNumber, cards, vus) => JSON.parse(driverEvents(cardNumber, JSON.stringify(cards), JSON.stringify(vus)))
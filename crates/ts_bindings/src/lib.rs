@@ -1,7 +1,10 @@
 #![allow(warnings)]
 use napi::bindgen_prelude::Buffer;
+use tachograph_parser::analysis::{self, ActivityDay, DriverEvent};
 use tachograph_parser::card_parser::CardData;
-use tachograph_parser::detector::TachoFileType;
+use tachograph_parser::detector::{TachoFileDetection, TachoFileType};
+use tachograph_parser::sensor_parser::{SensorData, SensorParser};
+use tachograph_parser::view::VuEventView;
 use tachograph_parser::vu_parser::VuData;
 use ts_rs::TS;
 
@@ -13,7 +16,12 @@ extern crate napi_derive;
 struct NoopStruct {
     card_data: CardData,
     vu_data: VuData,
+    sensor_data: SensorData,
     tacho_file_type: TachoFileType,
+    tacho_file_detection: TachoFileDetection,
+    activity_day: ActivityDay,
+    vu_event_view: VuEventView<'static>,
+    driver_event: DriverEvent,
 }
 
 #[napi(ts_return_type = "VuData")]
@@ -28,9 +36,104 @@ pub fn parse_card(bytes: Buffer) -> Result<String, napi::Error> {
         .map_err(|e| napi::Error::from_reason(e.to_string()))
 }
 
-#[napi(ts_return_type = "TachoFileType")]
+#[napi(ts_return_type = "SensorData")]
+pub fn parse_sensor(bytes: Buffer) -> Result<String, napi::Error> {
+    SensorParser::new_from_bytes(&bytes)
+        .and_then(|parser| parser.parse_to_json())
+        .map_err(|e| napi::Error::from_reason(e.to_string()))
+}
+
+#[napi(ts_return_type = "TachoFileDetection")]
 pub fn detect_tacho_file_type(bytes: Buffer) -> Result<String, napi::Error> {
-    let value = tachograph_parser::detector::detect_from_bytes(&bytes)
+    let value = tachograph_parser::detector::detect_detailed_from_bytes(&bytes)
         .map_err(|e| napi::Error::from_reason(e.to_string()))?;
-    Ok(value.to_string())
+    serde_json::to_string(&value).map_err(|e| napi::Error::from_reason(e.to_string()))
+}
+
+fn to_napi_error(e: impl std::fmt::Display) -> napi::Error {
+    napi::Error::from_reason(e.to_string())
+}
+
+/// Reads the file on the Rust side, so that its bytes never cross into JS.
+#[napi(ts_return_type = "VuData")]
+pub fn parse_vu_file(path: String) -> Result<String, napi::Error> {
+    tachograph_parser::parse_vu_from_file_to_json(&path).map_err(to_napi_error)
+}
+
+/// Reads the file on the Rust side, like `parseVuFile`.
+#[napi(ts_return_type = "CardData")]
+pub fn parse_card_file(path: String) -> Result<String, napi::Error> {
+    tachograph_parser::parse_card_from_file_to_json(&path).map_err(to_napi_error)
+}
+
+/// Collects a download pushed in chunks, e.g. from a `Readable`, so that the whole file never
+/// has to be assembled into one Buffer on the JS side. The chunks are copied into one buffer on
+/// the Rust side and only parsed once finished, so the stream holds the whole download in memory.
+#[napi]
+pub struct TachoStream {
+    bytes: Vec<u8>,
+}
+
+#[napi]
+impl TachoStream {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        TachoStream { bytes: Vec::new() }
+    }
+
+    #[napi]
+    pub fn push(&mut self, chunk: Buffer) {
+        self.bytes.extend_from_slice(&chunk);
+    }
+
+    /// Parses the pushed chunks as a VU download and empties the stream.
+    #[napi(ts_return_type = "VuData")]
+    pub fn finish_vu(&mut self) -> Result<String, napi::Error> {
+        let bytes = std::mem::take(&mut self.bytes);
+        tachograph_parser::parse_vu_from_bytes_to_json(&bytes).map_err(to_napi_error)
+    }
+
+    /// Parses the pushed chunks as a card download and empties the stream.
+    #[napi(ts_return_type = "CardData")]
+    pub fn finish_card(&mut self) -> Result<String, napi::Error> {
+        let bytes = std::mem::take(&mut self.bytes);
+        tachograph_parser::parse_card_from_bytes_to_json(&bytes).map_err(to_napi_error)
+    }
+}
+
+// The helpers below take the objects returned by the parse functions, serialized back to JSON by
+// the JS wrappers, so that JS consumers get the semantics of the Rust analysis.
+
+/// Activity periods of the card per UTC day, oldest first, with periods crossing midnight split
+/// between both days.
+#[napi(ts_args_type = "cardData: CardData", ts_return_type = "ActivityDay[]")]
+pub fn activities_by_day(card_data: String) -> Result<String, napi::Error> {
+    let card_data: CardData = serde_json::from_str(&card_data).map_err(to_napi_error)?;
+    let days = analysis::activities_by_day(&card_data.activity_timeline());
+    serde_json::to_string(&days).map_err(to_napi_error)
+}
+
+/// Events and faults of the VU in chronological order, with end times before their begin moved
+/// to the begin.
+#[napi(ts_args_type = "vuData: VuData", ts_return_type = "VuEventView[]")]
+pub fn events_sorted(vu_data: String) -> Result<String, napi::Error> {
+    let vu_data: VuData = serde_json::from_str(&vu_data).map_err(to_napi_error)?;
+    serde_json::to_string(&vu_data.events_sorted()).map_err(to_napi_error)
+}
+
+/// Events and faults of the driver holding `cardNumber`, from their card downloads and the VU
+/// downloads that name the card, in one chronological list without duplicates.
+#[napi(
+    ts_args_type = "cardNumber: string, cards: CardData[], vus: VuData[]",
+    ts_return_type = "DriverEvent[]"
+)]
+pub fn driver_events(
+    card_number: String,
+    cards: String,
+    vus: String,
+) -> Result<String, napi::Error> {
+    let cards: Vec<CardData> = serde_json::from_str(&cards).map_err(to_napi_error)?;
+    let vus: Vec<VuData> = serde_json::from_str(&vus).map_err(to_napi_error)?;
+    let events = analysis::driver_events(&card_number, None, &cards, &vus);
+    serde_json::to_string(&events).map_err(to_napi_error)
 }
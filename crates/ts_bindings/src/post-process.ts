@@ -9,7 +9,11 @@ const content = fs.readFileSync(indexDtsPath, "utf8");
 // Define the import statements to be added
 const importStatements = `import type { VuData } from "./bindings/VuData";
 import type { CardData } from "./bindings/CardData";
-import type { TachoFileType } from "./bindings/TachoFileType";
+import type { SensorData } from "./bindings/SensorData";
+import type { TachoFileDetection } from "./bindings/TachoFileDetection";
+import type { ActivityDay } from "./bindings/ActivityDay";
+import type { VuEventView } from "./bindings/VuEventView";
+import type { DriverEvent } from "./bindings/DriverEvent";
 `;
 
 // Combine the import statements with the existing content
@@ -28,10 +32,48 @@ jsContent = jsContent.replace(
 	"module.exports.parseCard = parseCard",
 	"module.exports.parseCard = (...input) => JSON.parse(parseCard(...input))",
 );
+jsContent = jsContent.replace(
+	"module.exports.parseSensor = parseSensor",
+	"module.exports.parseSensor = (...input) => JSON.parse(parseSensor(...input))",
+);
 jsContent = jsContent.replace(
 	"module.exports.parseVu = parseVu",
 	"module.exports.parseVu = (...input) => JSON.parse(parseVu(...input))",
 );
+jsContent = jsContent.replace(
+	"module.exports.detectTachoFileType = detectTachoFileType",
+	"module.exports.detectTachoFileType = (...input) => JSON.parse(detectTachoFileType(...input))",
+);
+
+jsContent = jsContent.replace(
+	"module.exports.parseVuFile = parseVuFile",
+	"module.exports.parseVuFile = (...input) => JSON.parse(parseVuFile(...input))",
+);
+jsContent = jsContent.replace(
+	"module.exports.parseCardFile = parseCardFile",
+	"module.exports.parseCardFile = (...input) => JSON.parse(parseCardFile(...input))",
+);
+jsContent = jsContent.replace(
+	"module.exports.TachoStream = TachoStream",
+	`const { finishVu, finishCard } = TachoStream.prototype
+TachoStream.prototype.finishVu = function () { return JSON.parse(finishVu.call(this)) }
+TachoStream.prototype.finishCard = function () { return JSON.parse(finishCard.call(this)) }
+module.exports.TachoStream = TachoStream`,
+);
+
+// The analysis helpers take the parsed objects, which are handed to Rust as JSON
+jsContent = jsContent.replace(
+	"module.exports.activitiesByDay = activitiesByDay",
+	"module.exports.activitiesByDay = (cardData) => JSON.parse(activitiesByDay(JSON.stringify(cardData)))",
+);
+jsContent = jsContent.replace(
+	"module.exports.eventsSorted = eventsSorted",
+	"module.exports.eventsSorted = (vuData) => JSON.parse(eventsSorted(JSON.stringify(vuData)))",
+);
+jsContent = jsContent.replace(
+	"module.exports.driverEvents = driverEvents",
+	"module.exports.driverEvents = (cardNumber, cards, vus) => JSON.parse(driverEvents(cardNumber, JSON.stringify(cards), JSON.stringify(vus)))",
+);
 
 // Write the updated content back to index.js
 fs.writeFileSync(indexJsPath, jsContent);